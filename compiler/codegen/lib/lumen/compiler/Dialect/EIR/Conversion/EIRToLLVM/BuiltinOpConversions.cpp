@@ -192,7 +192,7 @@ struct TraceConstructOpConversion : public EIROpConversion<TraceConstructOp> {
 
     auto termTy = ctx.getUsizeType();
     StringRef symbolName("__lumen_builtin_trace_construct");
-    auto callee = ctx.getOrInsertFunction(symbolName, termTy, {});
+    auto callee = ctx.getOrInsertFunction(symbolName, termTy, {termTy});
 
     auto calleeSymbol =
         FlatSymbolRefAttr::get(symbolName, callee->getContext());
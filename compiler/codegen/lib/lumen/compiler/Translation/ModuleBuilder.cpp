@@ -608,12 +608,19 @@ void ModuleBuilder::build_trace_capture_op(Location loc, Block *dest,
   builder.create<BranchOp>(loc, dest, extendedArgs);
 }
 
-extern "C" MLIRValueRef MLIRBuildTraceConstructOp(MLIRModuleBuilderRef,
+extern "C" MLIRValueRef MLIRBuildTraceConstructOp(MLIRModuleBuilderRef b,
                                                   MLIRLocationRef locref,
-                                                  MLIRValueRef) {
-  // TODO: For now we do nothing, but we'll want code that fetches
-  // a term value representing the stack trace
-  return nullptr;
+                                                  MLIRValueRef c) {
+  ModuleBuilder *builder = unwrap(b);
+  Location loc = unwrap(locref);
+  Value capture = unwrap(c);
+  return wrap(builder->build_trace_construct_op(loc, capture));
+}
+
+Value ModuleBuilder::build_trace_construct_op(Location loc, Value capture) {
+  auto termType = TermType::get(builder.getContext());
+  auto constructOp = builder.create<TraceConstructOp>(loc, termType, capture);
+  return constructOp.getResult();
 }
 
 //===----------------------------------------------------------------------===//
@@ -961,6 +968,17 @@ static Optional<Value> buildIntrinsicCmpNeqStrictOp(OpBuilder &builder,
   return op.getResult();
 }
 
+// Export closures are created by the runtime, which knows how to call them
+static Optional<Value> buildIntrinsicMakeFunOp(OpBuilder &builder,
+                                               Location loc,
+                                               ArrayRef<Value> args) {
+  assert(args.size() == 3 && "expected make_fun/3 to receive three operands");
+  auto termTy = builder.getType<TermType>();
+  auto callee = builder.getSymbolRefAttr("erlang:make_fun/3");
+  auto op = builder.create<CallOp>(loc, callee, ArrayRef<Type>{termTy}, args);
+  return op.getResult(0);
+}
+
 using BuildIntrinsicFnT = Optional<Value> (*)(OpBuilder &, Location loc,
                                               ArrayRef<Value>);
 
@@ -972,6 +990,7 @@ static Optional<BuildIntrinsicFnT> getIntrinsicBuilder(StringRef target) {
                    .Case("erlang:throw/1", buildIntrinsicThrowOp)
                    .Case("erlang:raise/3", buildIntrinsicRaiseOp)
                    .Case("erlang:print/1", buildIntrinsicPrintOp)
+                   .Case("erlang:make_fun/3", buildIntrinsicMakeFunOp)
                    .Case("erlang:+/2", buildIntrinsicAddOp)
                    .Case("erlang:-/2", buildIntrinsicSubOp)
                    .Case("erlang:*/2", buildIntrinsicMulOp)
@@ -103,6 +103,7 @@ class ModuleBuilder {
 
   void build_trace_capture_op(Location loc, Block *dest,
                               ArrayRef<MLIRValueRef> destArgs = {});
+  Value build_trace_construct_op(Location loc, Value capture);
 
   //===----------------------------------------------------------------------===//
   // Constants
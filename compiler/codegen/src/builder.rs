@@ -8,7 +8,7 @@ pub(super) mod traits;
 pub(super) mod value;
 
 use std::cell::RefCell;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ffi::{CStr, CString};
 use std::sync::Arc;
 
@@ -26,6 +26,7 @@ use liblumen_session::Options;
 use liblumen_llvm::target::{TargetMachine, TargetMachineRef};
 use liblumen_mlir::{Context, Dialect, Module};
 
+use crate::meta::FunctionLocation;
 use crate::Result;
 
 pub(crate) use self::ffi::ModuleBuilderRef;
@@ -36,6 +37,7 @@ pub struct GeneratedModule {
     pub module: Module,
     pub atoms: HashSet<Symbol>,
     pub symbols: HashSet<FunctionSymbol>,
+    pub locations: HashMap<FunctionSymbol, FunctionLocation>,
 }
 
 /// Constructs an MLIR module from an EIR module, using the provided context and options
@@ -56,14 +58,18 @@ pub fn build(
 /// from an EIR module.
 ///
 /// It maintains a module-local atom table, and a table of
-/// function symbols created during the build. These are later
-/// combined with the same tables of other modules to form a
-/// global set of atoms and symbols.
+/// function symbols created during the build, along with where
+/// each function is defined. These are later combined with the
+/// same tables of other modules to form a global set of atoms
+/// and symbols.
 pub struct ModuleBuilder<'m> {
     builder: ModuleBuilderRef,
     module: &'m ir::Module,
     atoms: RefCell<HashSet<Symbol>>,
     symbols: RefCell<HashSet<FunctionSymbol>>,
+    locations: RefCell<HashMap<FunctionSymbol, FunctionLocation>>,
+    // True if the module calls `erlang:load_nif/2`, so NIFs may replace its functions
+    loads_nifs: bool,
     filemap: Arc<FileMap>,
     source_filename: CString,
 }
@@ -111,6 +117,8 @@ impl<'m> ModuleBuilder<'m> {
             module,
             atoms: RefCell::new(atoms),
             symbols: RefCell::new(HashSet::new()),
+            locations: RefCell::new(HashMap::new()),
+            loads_nifs: false,
             filemap,
             source_filename,
         }
@@ -144,6 +152,11 @@ impl<'m> ModuleBuilder<'m> {
 
         debug!("building mlir module for {}", self.module.name());
 
+        self.loads_nifs = self
+            .module
+            .function_iter()
+            .any(|f| function::calls_load_nif(f.function()));
+
         for f in self.module.function_iter() {
             let ident = f.function().ident();
             // Don't generate module_info/0 and module_info/1 for now
@@ -167,9 +180,16 @@ impl<'m> ModuleBuilder<'m> {
             module: Module::new(result, Dialect::EIR),
             atoms: self.atoms.into_inner(),
             symbols: self.symbols.into_inner(),
+            locations: self.locations.into_inner(),
         })
     }
 
+    /// Returns true if NIFs may replace the functions of this module
+    #[inline]
+    pub fn loads_nifs(&self) -> bool {
+        self.loads_nifs
+    }
+
     /// Returns the set of atoms found in this module
     pub fn atoms(&self) -> core::cell::Ref<HashSet<Symbol>> {
         self.atoms.borrow()
@@ -189,4 +209,9 @@ impl<'m> ModuleBuilder<'m> {
     pub fn symbols_mut(&self) -> core::cell::RefMut<HashSet<FunctionSymbol>> {
         self.symbols.borrow_mut()
     }
+
+    /// Returns the locations of the functions found in this module, mutably
+    pub fn locations_mut(&self) -> core::cell::RefMut<HashMap<FunctionSymbol, FunctionLocation>> {
+        self.locations.borrow_mut()
+    }
 }
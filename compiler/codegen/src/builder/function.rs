@@ -1,6 +1,7 @@
 mod function;
 pub use self::function::*;
 
+use std::ffi::CString;
 use std::mem;
 use std::ptr;
 use std::sync::Arc;
@@ -23,8 +24,9 @@ use crate::Result;
 
 use super::block::{Block, BlockData};
 use super::ffi::*;
-use super::ops::builders::{ClosureBuilder, ConstantBuilder};
+use super::ops::builders::{ClosureBuilder, ConstantBuilder, APPLY, IS_NIF};
 use super::ops::*;
+use super::traits::AsValueRef;
 use super::value::{Value, ValueData, ValueDef};
 use super::ModuleBuilder;
 
@@ -153,9 +155,16 @@ impl<'a, 'm, 'f> FunctionBuilder<'a, 'm, 'f> {
         let mut func = Function::with_name_signature(eir.span(), name, signature);
         let (mlir, entry_ref) = func.build(self.builder)?;
 
+        // A NIF loaded by its module replaces the function, so check for one before the body
+        let body_ref = if self.builder.loads_nifs() && data.entry == eir.block_entry() {
+            self.build_nif_dispatch(eir, func.name(), mlir, entry_ref, &entry_params, options)?
+        } else {
+            entry_ref
+        };
+
         // Mirror the entry block for our init block
         let init_block =
-            func.new_block_with_params(Some(data.entry), entry_ref, entry_params.as_slice());
+            func.new_block_with_params(Some(data.entry), body_ref, entry_params.as_slice());
         // Initialize ret/esc continuations
         func.set_return_continuation(ret, init_block);
         func.set_escape_continuation(esc, init_block);
@@ -173,6 +182,131 @@ impl<'a, 'm, 'f> FunctionBuilder<'a, 'm, 'f> {
             pos: Position::at(init_block),
         })
     }
+
+    /// Builds the start of a function of a module that calls `erlang:load_nif/2`, which calls the
+    /// NIF of the same name through `erlang:apply/3` if a loaded NIF library defines one, as
+    /// loading a NIF library replaces the code of those functions in ERTS.
+    ///
+    /// Returns the block the body of the function is built in, which takes the same arguments as
+    /// the entry block.
+    fn build_nif_dispatch(
+        &self,
+        eir: &ir::Function,
+        name: &FunctionIdent,
+        function: FunctionOpRef,
+        entry_ref: BlockRef,
+        entry_params: &[(Param, Option<ir::Value>)],
+        options: &Options,
+    ) -> Result<BlockRef> {
+        debug!("{}: building NIF dispatch", name);
+
+        let builder = self.builder.as_ref();
+        let sl = self
+            .builder
+            .location(eir.span().start())
+            .expect("expected source location for function");
+        let params = entry_params
+            .iter()
+            .map(|(p, _)| p.clone())
+            .collect::<Vec<_>>();
+        let params_ptr = match params.len() {
+            0 => ptr::null(),
+            _ => params.as_ptr(),
+        };
+        let is_nif_param = [Param {
+            ty: Type::Term,
+            span: Span::default(),
+            is_implicit: false,
+        }];
+
+        let (check_ref, nif_ref, body_ref) = unsafe {
+            (
+                MLIRAppendBasicBlock(builder, function, is_nif_param.as_ptr(), 1),
+                MLIRAppendBasicBlock(builder, function, ptr::null(), 0),
+                MLIRAppendBasicBlock(builder, function, params_ptr, params.len() as libc::c_uint),
+            )
+        };
+        assert!(!check_ref.is_null() && !nif_ref.is_null() && !body_ref.is_null());
+
+        // Appending a block positions the builder at its end, so return to the entry block
+        unsafe { MLIRBlockPositionAtEnd(builder, entry_ref) };
+        let loc = unsafe { MLIRCreateLocation(builder, sl) };
+        let args = (0..params.len())
+            .map(|i| get_block_argument(entry_ref, i))
+            .collect::<Vec<_>>();
+        let module_ref = name.module.name.as_value_ref(loc, builder, options)?;
+        let function_ref = name.name.name.as_value_ref(loc, builder, options)?;
+        let arity_ref = (name.arity as i64).as_value_ref(loc, builder, options)?;
+
+        let is_nif = CString::new(IS_NIF).unwrap();
+        let is_nif_args = [module_ref, function_ref, arity_ref];
+        unsafe {
+            MLIRBuildStaticCall(
+                builder,
+                loc,
+                is_nif.as_ptr(),
+                is_nif_args.as_ptr(),
+                is_nif_args.len() as libc::c_uint,
+                /* is_tail */ false,
+                check_ref,
+                ptr::null(),
+                0,
+                Default::default(),
+                ptr::null(),
+                0,
+            );
+
+            MLIRBlockPositionAtEnd(builder, check_ref);
+            MLIRBuildIf(
+                builder,
+                loc,
+                get_block_argument(check_ref, 0),
+                nif_ref,
+                ptr::null(),
+                0,
+                body_ref,
+                args.as_ptr(),
+                args.len() as libc::c_uint,
+                Default::default(),
+                ptr::null(),
+                0,
+            );
+
+            MLIRBlockPositionAtEnd(builder, nif_ref);
+        }
+
+        let nil_ref = unsafe { MLIRBuildConstantNil(builder, loc) };
+        if nil_ref.is_null() {
+            return Err(anyhow!("failed to construct constant nil"));
+        }
+        let argument_list = args
+            .iter()
+            .rev()
+            .fold(nil_ref, |tail_ref, head_ref| unsafe {
+                MLIRCons(builder, loc, *head_ref, tail_ref)
+            });
+
+        let apply = CString::new(APPLY).unwrap();
+        let apply_args = [module_ref, function_ref, argument_list];
+        unsafe {
+            MLIRBuildStaticCall(
+                builder,
+                loc,
+                apply.as_ptr(),
+                apply_args.as_ptr(),
+                apply_args.len() as libc::c_uint,
+                /* is_tail */ true,
+                Default::default(),
+                ptr::null(),
+                0,
+                Default::default(),
+                ptr::null(),
+                0,
+            );
+        }
+
+        Ok(body_ref)
+    }
 }
 
 /// This builder type is essentially a sub-type of FunctionBuilder, and handles
@@ -1395,6 +1529,58 @@ impl Position {
     }
 }
 
+/// Returns true if `f` calls `erlang:load_nif/2`, which allows NIFs to replace the functions of
+/// its module
+pub(super) fn calls_load_nif(f: &ir::Function) -> bool {
+    let analysis = libeir_lowerutils::analyze(f);
+
+    analysis.functions.iter().any(|(_, data)| {
+        data.scope
+            .iter()
+            .copied()
+            .any(|block| is_load_nif_call(f, block))
+    })
+}
+
+fn is_load_nif_call(f: &ir::Function, block: ir::Block) -> bool {
+    match f.block_kind(block) {
+        Some(ir::OpKind::Call(ir::CallKind::Function)) => (),
+        _ => return false,
+    }
+
+    let callee = f.block_reads(block)[0];
+    let primop = match f.value_primop(callee) {
+        Some(primop) => primop,
+        None => return false,
+    };
+    if *f.primop_kind(primop) != ir::PrimOpKind::CaptureFunction {
+        return false;
+    }
+
+    let const_kind = |value: ir::Value| f.value_const(value).map(|c| f.const_kind(c));
+    match f.primop_reads(primop) {
+        [module, function, arity, ..] => {
+            match (
+                const_kind(*module),
+                const_kind(*function),
+                const_kind(*arity),
+            ) {
+                (
+                    Some(ConstKind::Atomic(AtomicTerm::Atom(AtomTerm(module)))),
+                    Some(ConstKind::Atomic(AtomicTerm::Atom(AtomTerm(function)))),
+                    Some(ConstKind::Atomic(AtomicTerm::Int(ir::IntTerm(arity)))),
+                ) => {
+                    module.as_str().get() == "erlang"
+                        && function.as_str().get() == "load_nif"
+                        && *arity == 2
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 /// Shared helper to map an EIR value to its constant kind
 pub(super) fn value_to_const_kind<'f>(
     function: &'f ir::Function,
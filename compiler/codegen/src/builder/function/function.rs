@@ -22,6 +22,7 @@ use crate::builder::block::*;
 use crate::builder::ffi::{self, Span, Type};
 use crate::builder::value::*;
 use crate::builder::ModuleBuilder;
+use crate::meta::FunctionLocation;
 use crate::Result;
 
 pub struct Function {
@@ -117,12 +118,10 @@ impl Function {
         let c_name = CString::new(self.name.to_string()).unwrap();
         // TODO: support multi-return
         let result_type = returns.get(0).unwrap_or(&Type::None);
-        let loc = unsafe {
-            let sl = builder
-                .location(self.span.start())
-                .expect("expected source location for function");
-            ffi::MLIRCreateLocation(builder.as_ref(), sl)
-        };
+        let sl = builder
+            .location(self.span.start())
+            .expect("expected source location for function");
+        let loc = unsafe { ffi::MLIRCreateLocation(builder.as_ref(), sl) };
 
         let ffi::FunctionDeclResult {
             function,
@@ -144,13 +143,21 @@ impl Function {
             ));
         }
 
-        // Register function symbol globally
-        builder.symbols_mut().insert(FunctionSymbol {
+        // Register function symbol globally, along with its location for stacktraces
+        let symbol = FunctionSymbol {
             module: self.name.module.name.as_usize(),
             function: self.name.name.name.as_usize(),
             arity: self.name.arity as u8,
             ptr: ptr::null(),
-        });
+        };
+        builder.symbols_mut().insert(symbol);
+        builder.locations_mut().insert(
+            symbol,
+            FunctionLocation {
+                file: builder.filename().to_string_lossy().into_owned(),
+                line: sl.line,
+            },
+        );
 
         Ok((function, entry_block))
     }
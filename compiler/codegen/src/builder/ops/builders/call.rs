@@ -1,4 +1,6 @@
-use std::ffi::CString;
+use std::ffi::{CStr, CString};
+
+use crate::builder::traits::*;
 
 use super::*;
 
@@ -71,26 +73,91 @@ impl CallBuilder {
                 builder.debug(&format!("static call target is {}", ident));
 
                 let name = CString::new(ident.to_string()).unwrap();
-                unsafe {
-                    MLIRBuildStaticCall(
-                        builder.as_ref(),
-                        op.loc,
-                        name.as_ptr(),
-                        args.as_ptr(),
-                        args.len() as libc::c_uint,
-                        op.is_tail,
-                        ok_block,
-                        ok_args.as_ptr(),
-                        ok_args.len() as libc::c_uint,
-                        err_block,
-                        err_args.as_ptr(),
-                        err_args.len() as libc::c_uint,
-                    );
-                }
+                Self::build_static_call(
+                    builder, &op, &name, &args, ok_block, &ok_args, err_block, &err_args,
+                );
+
+                Ok(None)
+            }
+            Callee::LocalDynamic {
+                module, function, ..
+            } => {
+                builder.debug(&format!("locally dynamic call target is {}", &op.callee));
+
+                let module_ref =
+                    module
+                        .name
+                        .as_value_ref(op.loc, builder.as_ref(), builder.options())?;
+                let function_ref = builder.value_ref(function);
+                let argument_list = build_list(builder, op.loc, &args)?;
+
+                let name = CString::new(APPLY).unwrap();
+                let apply_args = [module_ref, function_ref, argument_list];
+                Self::build_static_call(
+                    builder,
+                    &op,
+                    &name,
+                    &apply_args,
+                    ok_block,
+                    &ok_args,
+                    err_block,
+                    &err_args,
+                );
+
+                Ok(None)
+            }
+            Callee::GlobalDynamic {
+                module, function, ..
+            } => {
+                builder.debug(&format!("globally dynamic call target is {}", &op.callee));
+
+                let module_ref = builder.value_ref(module);
+                let function_ref = builder.value_ref(function);
+                let argument_list = build_list(builder, op.loc, &args)?;
+
+                let name = CString::new(APPLY).unwrap();
+                let apply_args = [module_ref, function_ref, argument_list];
+                Self::build_static_call(
+                    builder,
+                    &op,
+                    &name,
+                    &apply_args,
+                    ok_block,
+                    &ok_args,
+                    err_block,
+                    &err_args,
+                );
 
                 Ok(None)
             }
-            callee => todo!("unimplemented call type {:?}", callee),
+        }
+    }
+
+    fn build_static_call<'f, 'o>(
+        builder: &mut ScopedFunctionBuilder<'f, 'o>,
+        op: &Call,
+        name: &CStr,
+        args: &[ValueRef],
+        ok_block: BlockRef,
+        ok_args: &[ValueRef],
+        err_block: BlockRef,
+        err_args: &[ValueRef],
+    ) {
+        unsafe {
+            MLIRBuildStaticCall(
+                builder.as_ref(),
+                op.loc,
+                name.as_ptr(),
+                args.as_ptr(),
+                args.len() as libc::c_uint,
+                op.is_tail,
+                ok_block,
+                ok_args.as_ptr(),
+                ok_args.len() as libc::c_uint,
+                err_block,
+                err_args.as_ptr(),
+                err_args.len() as libc::c_uint,
+            );
         }
     }
 }
@@ -98,15 +165,94 @@ impl CallBuilder {
 pub struct CalleeBuilder;
 
 impl CalleeBuilder {
+    /// Builds a function captured with `fun M:F/A` as an export closure, which calls the function
+    /// through the dispatch table when it is applied, so that the module and function may be
+    /// values only known at runtime.
     pub fn build<'f, 'o>(
-        _builder: &mut ScopedFunctionBuilder<'f, 'o>,
+        builder: &mut ScopedFunctionBuilder<'f, 'o>,
         ir_value: Option<ir::Value>,
         op: FunctionRef,
     ) -> Result<Option<Value>> {
-        todo!(
-            "build function reference constant for {:?} = {:?}",
-            ir_value,
-            &op.callee
-        );
+        builder.debug(&format!("function reference is {}", &op.callee));
+
+        let loc = op.loc;
+        let (module_ref, function_ref, arity) = match op.callee {
+            Callee::Static(ident) => (
+                ident
+                    .module
+                    .name
+                    .as_value_ref(loc, builder.as_ref(), builder.options())?,
+                ident
+                    .name
+                    .name
+                    .as_value_ref(loc, builder.as_ref(), builder.options())?,
+                ident.arity,
+            ),
+            Callee::LocalDynamic {
+                module,
+                function,
+                arity,
+            } => (
+                module
+                    .name
+                    .as_value_ref(loc, builder.as_ref(), builder.options())?,
+                builder.value_ref(function),
+                arity,
+            ),
+            Callee::GlobalDynamic {
+                module,
+                function,
+                arity,
+            } => (
+                builder.value_ref(module),
+                builder.value_ref(function),
+                arity,
+            ),
+            Callee::ClosureDynamic(_) => unreachable!("a closure is not a function reference"),
+        };
+        let arity_ref = (arity as i64).as_value_ref(loc, builder.as_ref(), builder.options())?;
+
+        let name = CString::new(MAKE_FUN).unwrap();
+        let argv = [module_ref, function_ref, arity_ref];
+        let fun_ref = unsafe {
+            MLIRBuildIntrinsic(
+                builder.as_ref(),
+                loc,
+                name.as_ptr(),
+                argv.as_ptr(),
+                argv.len() as libc::c_uint,
+            )
+        };
+        assert!(!fun_ref.is_null());
+
+        let fun = builder.new_value(ir_value, fun_ref, ValueDef::Result(0));
+        Ok(Some(fun))
     }
 }
+
+/// Calls whose module or function is only known at runtime are made through this function, which
+/// looks the callee up in the dispatch table, and raises `undef` if it isn't there
+pub const APPLY: &str = "erlang:apply/3";
+/// Returns whether a loaded NIF library defines `Module:Function/Arity`
+pub const IS_NIF: &str = "__lumen_builtin_is_nif";
+/// Creates the export closure of a function captured with `fun M:F/A`
+const MAKE_FUN: &str = "erlang:make_fun/3";
+
+fn build_list<'f, 'o>(
+    builder: &mut ScopedFunctionBuilder<'f, 'o>,
+    loc: LocationRef,
+    elements: &[ValueRef],
+) -> Result<ValueRef> {
+    let nil_ref = unsafe { MLIRBuildConstantNil(builder.as_ref(), loc) };
+    if nil_ref.is_null() {
+        return Err(anyhow!("failed to construct constant nil"));
+    }
+
+    let list_ref = elements
+        .iter()
+        .rev()
+        .fold(nil_ref, |tail_ref, head_ref| unsafe {
+            MLIRCons(builder.as_ref(), loc, *head_ref, tail_ref)
+        });
+    Ok(list_ref)
+}
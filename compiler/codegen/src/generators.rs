@@ -1,18 +1,24 @@
 mod atom_table;
+mod location_table;
+mod nif_table;
 mod symbol_table;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
 use std::path::Path;
+use std::sync::Arc;
 
 use libeir_intern::Symbol;
 
 use liblumen_core::symbols::FunctionSymbol;
 use liblumen_llvm::target::TargetMachine;
-use liblumen_llvm::Context;
+use liblumen_llvm::{Context, Module};
 
-use crate::meta::CodegenResults;
+use crate::meta::{CodegenResults, CompiledModule, FunctionLocation};
 use crate::Result;
 
+/// Generates the tables the runtime is initialized from, writing an object file for each to
+/// `output_dir` for the linker
 pub fn run(
     result: &mut CodegenResults,
     context: &Context,
@@ -20,12 +26,46 @@ pub fn run(
     output_dir: &Path,
     atoms: HashSet<Symbol>,
     symbols: HashSet<FunctionSymbol>,
+    locations: HashMap<FunctionSymbol, FunctionLocation>,
 ) -> Result<()> {
-    let atom_table = atom_table::generate(context, target_machine, atoms, output_dir)?;
-    result.modules.push(atom_table);
+    let modules = build(context, target_machine, atoms, symbols, locations)?;
+    for (name, module) in modules {
+        // Open object file for writing
+        let path = output_dir.join(&format!("{}.o", name));
+        let mut file = File::create(path.as_path())?;
+        // Emit object file
+        module.emit_obj(&mut file)?;
 
-    let symbol_table = symbol_table::generate(context, target_machine, symbols, output_dir)?;
-    result.modules.push(symbol_table);
+        result.modules.push(Arc::new(CompiledModule::new(
+            name.to_string(),
+            Some(path),
+            None,
+        )));
+    }
 
     Ok(())
 }
+
+/// Generates the tables the runtime is initialized from as in-memory modules, named after the
+/// object files `run` would write for them
+///
+/// This is used directly when the modules are loaded into a JIT instead of being linked.
+pub fn build(
+    context: &Context,
+    target_machine: &TargetMachine,
+    atoms: HashSet<Symbol>,
+    symbols: HashSet<FunctionSymbol>,
+    locations: HashMap<FunctionSymbol, FunctionLocation>,
+) -> Result<Vec<(&'static str, Module)>> {
+    let atom_table = atom_table::generate(context, target_machine, atoms)?;
+    let nif_table = nif_table::generate(context, target_machine, &symbols)?;
+    let location_table = location_table::generate(context, target_machine, locations)?;
+    let symbol_table = symbol_table::generate(context, target_machine, symbols)?;
+
+    Ok(vec![
+        (atom_table::NAME, atom_table),
+        (nif_table::NAME, nif_table),
+        (location_table::NAME, location_table),
+        (symbol_table::NAME, symbol_table),
+    ])
+}
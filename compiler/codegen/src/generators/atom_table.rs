@@ -1,8 +1,5 @@
 use std::collections::HashSet;
 use std::ffi::CString;
-use std::fs::File;
-use std::path::Path;
-use std::sync::Arc;
 
 use libeir_intern::Symbol;
 
@@ -11,9 +8,11 @@ use liblumen_llvm::builder::ModuleBuilder;
 use liblumen_llvm::enums::Linkage;
 use liblumen_llvm::target::TargetMachine;
 
-use crate::meta::CompiledModule;
 use crate::Result;
 
+/// The name of the generated module, and of its object file
+pub const NAME: &'static str = "liblumen_crt_atoms";
+
 /// Generates an LLVM module containing the raw atom table data for the current build
 ///
 /// Process is as follows:
@@ -28,10 +27,7 @@ pub fn generate(
     context: &llvm::Context,
     target_machine: &TargetMachine,
     mut atoms: HashSet<Symbol>,
-    output_dir: &Path,
-) -> Result<Arc<CompiledModule>> {
-    const NAME: &'static str = "liblumen_crt_atoms";
-
+) -> Result<llvm::Module> {
     let builder = ModuleBuilder::new(NAME, context, target_machine)?;
 
     // Ensure true/false are always present
@@ -109,17 +105,5 @@ pub fn generate(
     );
     builder.set_alignment(table_size_global, 8);
 
-    // Finalize module
-    let module = builder.finish();
-    // Open object file for writing
-    let path = output_dir.join(&format!("{}.o", NAME));
-    let mut file = File::create(path.as_path())?;
-    // Emit object file
-    module.emit_obj(&mut file)?;
-
-    Ok(Arc::new(CompiledModule::new(
-        NAME.to_string(),
-        Some(path),
-        None,
-    )))
+    Ok(builder.finish())
 }
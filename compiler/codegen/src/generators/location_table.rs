@@ -0,0 +1,114 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::CString;
+
+use liblumen_core::symbols::FunctionSymbol;
+use liblumen_llvm as llvm;
+use liblumen_llvm::builder::ModuleBuilder;
+use liblumen_llvm::enums::Linkage;
+use liblumen_llvm::target::TargetMachine;
+
+use crate::meta::FunctionLocation;
+use crate::Result;
+
+/// The name of the generated module, and of its object file
+pub const NAME: &'static str = "liblumen_crt_locations";
+
+/// Generates an LLVM module containing the source locations of the functions in the current build
+///
+/// The runtime uses this to add the file and line to each frame of a stacktrace, after it has
+/// found the function of the frame in the symbol table.
+///
+/// Process is as follows:
+/// - Generate a constant for each source file name
+/// - Generate a constant array containing `FunctionLocation` structs for all functions:
+///   - Has type `{ i64, i64, i8, i8*, i32 }`
+///   - The first three fields are the module, function and arity, as in `FunctionSymbol`
+///   - The fourth field is the pointer to the file name constant
+///   - The last field is the line the function is defined on
+/// - Generate the __LUMEN_LOCATION_TABLE global as a pointer to the first element of the array
+/// - Generate the __LUMEN_LOCATION_TABLE_SIZE global with the number of elements in the array
+pub fn generate(
+    context: &llvm::Context,
+    target_machine: &TargetMachine,
+    locations: HashMap<FunctionSymbol, FunctionLocation>,
+) -> Result<llvm::Module> {
+    let builder = ModuleBuilder::new(NAME, context, target_machine)?;
+
+    // Sorted, so that the table is the same from build to build
+    let mut locations = locations.into_iter().collect::<Vec<_>>();
+    locations.sort_by_key(|(symbol, _)| (symbol.module, symbol.function, symbol.arity));
+
+    // Generate a constant for each file, as every function in a module shares one
+    let i8_type = builder.get_i8_type();
+    let mut files = BTreeMap::new();
+    for (_, location) in locations.iter() {
+        if files.contains_key(location.file.as_str()) {
+            continue;
+        }
+        let s = CString::new(location.file.as_str()).unwrap();
+        let size = s.as_bytes().len();
+        let string_type = builder.get_array_type(size + 1, i8_type);
+        let init = builder.build_constant_cstring(s, /* null_terminate= */ true);
+        let constant = builder.build_constant(
+            string_type,
+            &format!("__file{}.value", files.len()),
+            Some(init),
+        );
+        builder.set_linkage(constant, Linkage::Private);
+        builder.set_alignment(constant, 8);
+        files.insert(location.file.as_str(), constant);
+    }
+
+    // Generate constants array entries
+    let usize_type = builder.get_usize_type();
+    let i8ptr_type = builder.get_pointer_type(i8_type);
+    let i32_type = builder.get_i32_type();
+    let entry_type = builder.get_struct_type(
+        Some("FunctionLocation"),
+        &[usize_type, usize_type, i8_type, i8ptr_type, i32_type],
+    );
+
+    let mut entries = Vec::with_capacity(locations.len());
+    for (symbol, location) in locations.iter() {
+        let module = builder.build_constant_uint(usize_type, symbol.module);
+        let function = builder.build_constant_uint(usize_type, symbol.function);
+        let arity = builder.build_constant_uint(i8_type, symbol.arity as usize);
+        let file = builder.build_const_inbounds_gep(files[location.file.as_str()], &[0, 0]);
+        let line = builder.build_constant_uint(i32_type, location.line as usize);
+        entries.push(
+            builder.build_constant_struct(entry_type, &[module, function, arity, file, line]),
+        );
+    }
+
+    // Generate constants array
+    let entries_const_init = builder.build_constant_array(entry_type, entries.as_slice());
+    let entries_const_ty = builder.type_of(entries_const_init);
+    let entries_const = builder.build_constant(
+        entries_const_ty,
+        "__LUMEN_LOCATION_TABLE_ENTRIES",
+        Some(entries_const_init),
+    );
+    builder.set_linkage(entries_const, Linkage::Private);
+    builder.set_alignment(entries_const, 8);
+
+    // Generate location table global itself
+    let entry_ptr_type = builder.get_pointer_type(entry_type);
+    let table_global_init = builder.build_const_inbounds_gep(entries_const, &[0, 0]);
+    let table_global = builder.build_global(
+        entry_ptr_type,
+        "__LUMEN_LOCATION_TABLE",
+        Some(table_global_init),
+    );
+    builder.set_alignment(table_global, 8);
+
+    // Generate location table size global
+    let table_size_global_init = builder.build_constant_uint(usize_type, entries.len());
+    let table_size_global = builder.build_global(
+        usize_type,
+        "__LUMEN_LOCATION_TABLE_SIZE",
+        Some(table_size_global_init),
+    );
+    builder.set_alignment(table_size_global, 8);
+
+    Ok(builder.finish())
+}
@@ -0,0 +1,80 @@
+use std::collections::{BTreeSet, HashSet};
+use std::ffi::CString;
+use std::mem;
+
+use libeir_intern::Symbol;
+
+use liblumen_core::symbols::FunctionSymbol;
+use liblumen_llvm as llvm;
+use liblumen_llvm::builder::ModuleBuilder;
+use liblumen_llvm::enums::Linkage;
+use liblumen_llvm::target::TargetMachine;
+
+use crate::Result;
+
+/// The name of the generated module, and of its object file
+pub const NAME: &'static str = "liblumen_crt_nifs";
+
+/// Generates an LLVM module containing the NIF table for the current build
+///
+/// The table has an entry for each compiled module, which is an extern weak reference to the
+/// `<module>_nif_init` function that `ERL_NIF_INIT` defines in a NIF library. At link time this
+/// resolves to the function if a linked library defines it, and to null otherwise, so when we
+/// boot the runtime we can load every NIF library that was linked without knowing them up front.
+pub fn generate(
+    context: &llvm::Context,
+    target_machine: &TargetMachine,
+    symbols: &HashSet<FunctionSymbol>,
+) -> Result<llvm::Module> {
+    // Sorted, so that the table is the same from build to build
+    let modules: BTreeSet<String> = symbols
+        .iter()
+        .map(|symbol| unsafe { mem::transmute::<u32, Symbol>(symbol.module as u32) }.to_string())
+        .collect();
+
+    let builder = ModuleBuilder::new(NAME, context, target_machine)?;
+
+    let usize_type = builder.get_usize_type();
+    let i8_type = builder.get_i8_type();
+    let init_type = builder.get_function_type(builder.get_pointer_type(i8_type), &[], false);
+    let init_ptr_type = builder.get_pointer_type(init_type);
+
+    // Build values for array
+    let mut inits = Vec::with_capacity(modules.len());
+    for module in modules.iter() {
+        let name = CString::new(format!("{}_nif_init", module)).unwrap();
+        let decl = builder.build_function(&name, init_type);
+        builder.set_linkage(decl, Linkage::ExternWeak);
+        inits.push(decl);
+    }
+
+    // Generate global array of all init functions
+    let inits_const_init = builder.build_constant_array(init_ptr_type, inits.as_slice());
+    let inits_const_ty = builder.type_of(inits_const_init);
+    let inits_const = builder.build_constant(
+        inits_const_ty,
+        "__LUMEN_NIF_TABLE_ENTRIES",
+        Some(inits_const_init),
+    );
+    builder.set_linkage(inits_const, Linkage::Private);
+    builder.set_alignment(inits_const, 8);
+
+    let table_global_init = builder.build_const_inbounds_gep(inits_const, &[0, 0]);
+    let table_global = builder.build_global(
+        builder.get_pointer_type(init_ptr_type),
+        "__LUMEN_NIF_TABLE",
+        Some(table_global_init),
+    );
+    builder.set_alignment(table_global, 8);
+
+    // Generate array length global
+    let table_size_global_init = builder.build_constant_uint(usize_type, inits.len());
+    let table_size_global = builder.build_global(
+        usize_type,
+        "__LUMEN_NIF_TABLE_SIZE",
+        Some(table_size_global_init),
+    );
+    builder.set_alignment(table_size_global, 8);
+
+    Ok(builder.finish())
+}
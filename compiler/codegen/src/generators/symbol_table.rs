@@ -1,9 +1,6 @@
 use std::collections::HashSet;
 use std::ffi::CString;
-use std::fs::File;
 use std::mem;
-use std::path::Path;
-use std::sync::Arc;
 
 use libeir_intern::{Ident, Symbol};
 use libeir_ir::FunctionIdent;
@@ -14,9 +11,11 @@ use liblumen_llvm::builder::ModuleBuilder;
 use liblumen_llvm::enums::{Linkage, ThreadLocalMode};
 use liblumen_llvm::target::TargetMachine;
 
-use crate::meta::CompiledModule;
 use crate::Result;
 
+/// The name of the generated module, and of its object file
+pub const NAME: &'static str = "liblumen_crt_dispatch";
+
 /// Generates an LLVM module containing the raw symbol table data for the current build
 ///
 /// This is similar to the atom table generation, but simpler, in that we just generate
@@ -28,10 +27,7 @@ pub fn generate(
     context: &llvm::Context,
     target_machine: &TargetMachine,
     symbols: HashSet<FunctionSymbol>,
-    output_dir: &Path,
-) -> Result<Arc<CompiledModule>> {
-    const NAME: &'static str = "liblumen_crt_dispatch";
-
+) -> Result<llvm::Module> {
     let builder = ModuleBuilder::new(NAME, context, target_machine)?;
 
     fn declare_extern_symbol<'ctx>(
@@ -142,17 +138,5 @@ pub fn generate(
     builder.set_is_tail(lang_start_call, true);
     builder.build_return(lang_start_call);
 
-    // Finalize module
-    let module = builder.finish();
-    // Open object file for writing
-    let path = output_dir.join(&format!("{}.o", NAME));
-    let mut file = File::create(path.as_path())?;
-    // Emit object file
-    module.emit_obj(&mut file)?;
-
-    Ok(Arc::new(CompiledModule::new(
-        NAME.to_string(),
-        Some(path),
-        None,
-    )))
+    Ok(builder.finish())
 }
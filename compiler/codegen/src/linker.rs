@@ -19,7 +19,7 @@ use liblumen_target::{LinkerFlavor, LldFlavor};
 
 use self::command::Command;
 
-pub use self::link::link_binary;
+pub use self::link::{jit_archives, link_binary};
 
 #[derive(PartialEq, Clone, Debug)]
 pub enum LibSource {
@@ -44,6 +44,27 @@ impl LibSource {
     }
 }
 
+/// The functions of the embedding API in the runtime, declared in `lumen.h`, which are the only
+/// symbols exported from a library
+const EMBEDDING_API: &[&str] = &[
+    "lumen_start",
+    "lumen_stop",
+    "lumen_call",
+    "lumen_env_new",
+    "lumen_env_free",
+    "lumen_make_atom",
+    "lumen_make_int",
+    "lumen_make_binary",
+    "lumen_make_tuple",
+    "lumen_make_list",
+    "lumen_get_int",
+    "lumen_get_atom",
+    "lumen_get_binary",
+    "lumen_get_tuple",
+    "lumen_get_list_cell",
+    "lumen_is_empty_list",
+];
+
 /// For all the linkers we support, and information they might
 /// need out of the shared crate context before we get rid of it.
 #[derive(Debug)]
@@ -53,9 +74,18 @@ pub struct LinkerInfo {
 
 impl LinkerInfo {
     pub fn new() -> LinkerInfo {
-        Self {
-            exports: Default::default(),
+        let mut exports = FxHashMap::default();
+        exports.insert(ProjectType::Executable, Vec::new());
+        for project_type in &[
+            ProjectType::Dylib,
+            ProjectType::Staticlib,
+            ProjectType::Cdylib,
+        ] {
+            let symbols = EMBEDDING_API.iter().map(|s| s.to_string()).collect();
+            exports.insert(*project_type, symbols);
         }
+
+        Self { exports }
     }
 
     pub fn to_linker<'a>(
@@ -26,7 +26,10 @@ use crate::linker::rpath::{self, RPathConfig};
 use crate::linker::Linker;
 use crate::meta::{CodegenResults, LibSource};
 
-use super::archive::{ArchiveBuilder, LlvmArchiveBuilder};
+use super::archive::{find_library, ArchiveBuilder, LlvmArchiveBuilder};
+
+/// The declarations of the embedding API in the runtime, for C programs using a library
+const EMBEDDING_API_HEADER: &str = include_str!("../../../../runtimes/minimal/include/lumen.h");
 
 enum RlibFlavor {
     Normal,
@@ -64,16 +67,23 @@ pub fn link_binary(
         .as_ref()
         .map(|of| of.clone())
         .unwrap_or_else(|| {
-            let name = PathBuf::from(options.project_name.as_str());
-            let ext = match project_type {
-                ProjectType::Executable if options.target.options.is_like_windows => "exe",
-                ProjectType::Executable => "out",
-                ProjectType::Staticlib => "a",
-                _ => "o",
-            };
-            let mut p = output_dir.as_path().join(name);
-            p.set_extension(ext);
-            p
+            let name = options.project_name.as_str();
+            let t = &options.target.options;
+            match project_type {
+                ProjectType::Executable => {
+                    let ext = if t.is_like_windows { "exe" } else { "out" };
+                    let mut p = output_dir.as_path().join(name);
+                    p.set_extension(ext);
+                    p
+                }
+                ProjectType::Staticlib => output_dir.join(format!(
+                    "{}{}{}",
+                    t.staticlib_prefix, name, t.staticlib_suffix
+                )),
+                ProjectType::Dylib | ProjectType::Cdylib => {
+                    output_dir.join(format!("{}{}{}", t.dll_prefix, name, t.dll_suffix))
+                }
+            }
         });
 
     match project_type {
@@ -100,6 +110,13 @@ pub fn link_binary(
         }
     }
 
+    // Libraries are called from C through the embedding API of the runtime
+    if let ProjectType::Staticlib | ProjectType::Cdylib = project_type {
+        let header = output_dir.join("lumen.h");
+        fs::write(&header, EMBEDDING_API_HEADER)
+            .map_err(|err| anyhow!("couldn't write {}: {}", header.display(), err))?;
+    }
+
     // Remove the temporary object file and metadata if we aren't saving temps
     for obj in codegen_results.modules.iter().filter_map(|m| m.object()) {
         if let Err(e) = remove(obj) {
@@ -145,6 +162,23 @@ fn link_staticlib(
         }
     }
 
+    // Bundle the runtime, so that the library is all a C program needs to link
+    let rlib_dir = options.target_filesearch(PathKind::All).get_lib_path();
+    for lib in runtime_libraries(options) {
+        if lib.ends_with(".rlib") {
+            let name = lib.trim_start_matches("lib").trim_end_matches(".rlib");
+            ab.add_rlib(
+                &rlib_dir.join(lib),
+                name,
+                /* lto= */ false,
+                /* skip_objects= */ false,
+            )
+            .map_err(|err| anyhow!("could not add {} to the archive: {}", lib, err))?;
+        } else {
+            ab.add_native_library(lib);
+        }
+    }
+
     ab.update_symbols();
     ab.build();
 
@@ -888,7 +922,7 @@ fn link_args(
     // If we're building something like a dynamic library then some platforms
     // need to make sure that all symbols are exported correctly from the
     // dynamic library.
-    //cmd.export_symbols(tmpdir, project_type);
+    cmd.export_symbols(tmpdir, project_type);
 
     // When linking a dynamic library, we put the metadata into a section of the
     // executable. This metadata is in a separate object file from the main
@@ -1086,14 +1120,8 @@ pub fn add_local_native_libraries(
     let search_path = archive_search_paths(options);
 
     // Add runtime libs we depend on
-    let no_std = options.codegen_opts.no_std.unwrap_or(false);
-    let libstd_libs = match options.target.arch.as_str() {
-        "x86_64" if !no_std => vec!["libpanic_unwind.rlib", "lumen_rt_minimal"],
-        "wasm32" if !no_std => vec!["libpanic_abort.rlib", "lumen_web"],
-        _ => vec!["libpanic_unwind.rlib"],
-    };
     let rlib_dir = filesearch.get_lib_path();
-    for lib in libstd_libs {
+    for lib in runtime_libraries(options) {
         if lib.ends_with(".rlib") {
             link_rlib(cmd, options, tmpdir, &rlib_dir.join(lib));
         } else {
@@ -1240,6 +1268,40 @@ fn create_rlib<'a>(
     ab
 }
 
+/// The runtime libraries linked into every executable or library for the target, which are
+/// either rlibs in the sysroot, or native static libraries
+fn runtime_libraries(options: &Options) -> Vec<&'static str> {
+    let no_std = options.codegen_opts.no_std.unwrap_or(false);
+    match options.target.arch.as_str() {
+        "x86_64" if !no_std => vec!["libpanic_unwind.rlib", "lumen_rt_minimal"],
+        "wasm32" if !no_std => vec!["libpanic_abort.rlib", "lumen_web"],
+        _ => vec!["libpanic_unwind.rlib"],
+    }
+}
+
+/// The archives a JIT loads in place of linking an executable: the runtime libraries for the
+/// target, followed by the native libraries given with `-l static=NAME`
+pub fn jit_archives(options: &Options) -> anyhow::Result<Vec<PathBuf>> {
+    let rlib_dir = options.target_filesearch(PathKind::All).get_lib_path();
+    let search_paths = archive_search_paths(options);
+
+    let mut archives = Vec::new();
+    for lib in runtime_libraries(options) {
+        if lib.ends_with(".rlib") {
+            archives.push(rlib_dir.join(lib));
+        } else {
+            archives.push(find_library(lib, &search_paths, options)?);
+        }
+    }
+    for (name, _, kind) in options.link_libraries.iter() {
+        if let Some(NativeLibraryKind::NativeStatic) = kind {
+            archives.push(find_library(name, &search_paths, options)?);
+        }
+    }
+
+    Ok(archives)
+}
+
 fn link_rlib(cmd: &mut dyn Linker, options: &Options, tmpdir: &Path, rlib_path: &Path) {
     use super::archive::builder::{METADATA_FILENAME, RLIB_BYTECODE_EXTENSION};
 
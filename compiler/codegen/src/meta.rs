@@ -40,6 +40,14 @@ impl CompiledModule {
     }
 }
 
+/// Where a function is defined in the source, so that the runtime can give the location of each
+/// frame of a stacktrace
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FunctionLocation {
+    pub file: String,
+    pub line: u32,
+}
+
 #[derive(Debug)]
 pub struct CodegenResults {
     pub project_name: String,
@@ -31,6 +31,7 @@ pub fn parser<'a, 'b>() -> App<'a, 'b> {
         )
         .subcommand(print_command())
         .subcommand(compile_command())
+        .subcommand(run_command())
 }
 
 pub fn print_print_help() {
@@ -73,6 +74,18 @@ fn print_command<'a, 'b>() -> App<'a, 'b> {
         .subcommand(
             App::new("passes").about("Prints the LLVM passes registered with the pass manager"),
         )
+        .subcommand(
+            App::new("beam-asm")
+                .about("Prints the BEAM assembly in a .beam file, in the same format as `erlc -S`")
+                .arg(
+                    Arg::with_name("input")
+                        .index(1)
+                        .help("Path to the .beam file to disassemble")
+                        .required(true)
+                        .takes_value(true)
+                        .value_name("PATH"),
+                ),
+        )
 }
 
 fn compile_command<'a, 'b>() -> App<'a, 'b> {
@@ -110,6 +123,20 @@ fn compile_command<'a, 'b>() -> App<'a, 'b> {
                 .takes_value(true)
                 .value_name("NAME"),
         )
+        .arg(
+            Arg::with_name("project-type")
+                .help(
+                    "The type of artifact to build.\n\
+                     A staticlib or cdylib is a library which C programs can call \
+                     through the API declared in the `lumen.h` written next to it",
+                )
+                .next_line_help(true)
+                .long("crate-type")
+                .alias("project-type")
+                .takes_value(true)
+                .possible_values(&["bin", "lib", "dylib", "staticlib", "cdylib"])
+                .value_name("TYPE"),
+        )
         .arg(
             Arg::with_name("output")
                 .help("Write output to FILE")
@@ -244,6 +271,134 @@ fn compile_command<'a, 'b>() -> App<'a, 'b> {
         )
 }
 
+fn run_command<'a, 'b>() -> App<'a, 'b> {
+    App::new("run")
+        .about("Compiles Erlang sources in memory and runs them, without linking an executable")
+        .setting(AppSettings::DeriveDisplayOrder)
+        .arg(
+            Arg::with_name("input")
+                .index(1)
+                .help(
+                    "Path to the source file or directory to run.\n\
+                     You may also use `-` as a file name to read a file from stdin.\n\
+                     If not provided, the compiler will use the current directory as input.",
+                )
+                .next_line_help(true)
+                .takes_value(true)
+                .value_name("PATH"),
+        )
+        .arg(
+            Arg::with_name("args")
+                .last(true)
+                .help("Arguments passed to the program, as they would be to the executable")
+                .next_line_help(true)
+                .multiple(true)
+                .value_name("ARGS"),
+        )
+        .arg(
+            Arg::with_name("name")
+                .help("Specify the name of the project being run")
+                .short("n")
+                .long("name")
+                .takes_value(true)
+                .value_name("NAME"),
+        )
+        .arg(
+            Arg::with_name("debug")
+                .help("Generate source level debug information (same as -C debuginfo=2)")
+                .short("g")
+                .long("debug"),
+        )
+        .arg(
+            Arg::with_name("optimize")
+                .help("Apply optimizations (equivalent to -C opt-level=2)")
+                .short("O")
+                .long("optimize"),
+        )
+        .arg(
+            Arg::with_name("color")
+                .help("Configure coloring of output")
+                .next_line_help(true)
+                .long("color")
+                .possible_values(&["never", "always", "auto"])
+                .default_value("auto"),
+        )
+        .arg(
+            Arg::with_name("define")
+                .help("Define a macro, e.g. -D TEST or -D FOO=BAR")
+                .short("D")
+                .long("define")
+                .takes_value(true)
+                .value_name("NAME[=VALUE]")
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("warnings-as-errors")
+                .help("Causes the compiler to treat all warnings as errors")
+                .long("warnings-as-errors"),
+        )
+        .arg(
+            Arg::with_name("no-warn")
+                .help("Disable warnings")
+                .long("no-warn")
+                .conflicts_with("warnings-as-errors"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .help("Set verbosity level")
+                .short("v")
+                .multiple(true),
+        )
+        .arg(
+            Arg::with_name("link-library")
+                .help(
+                    "Load the specified native library NAME into the program.\n\
+                     The optional KIND can be one of: static, or dylib (default)",
+                )
+                .next_line_help(true)
+                .short("l")
+                .takes_value(true)
+                .value_name("[KIND=]NAME")
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("search-path")
+                .help(
+                    "Add a directory to the library search path.\n\
+                     The optional KIND can be one of: dependency, \
+                     native, framework, or all (default)",
+                )
+                .next_line_help(true)
+                .short("L")
+                .takes_value(true)
+                .value_name("[KIND=]PATH")
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("append-path")
+                .help("Appends a path to the Erlang code path")
+                .long("append-path")
+                .short("p")
+                .value_name("PATH")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("prepend-path")
+                .help("Prepends a path to the Erlang code path")
+                .long("prepend-path")
+                .short("P")
+                .value_name("PATH")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+}
+
 fn target_arg<'a, 'b>() -> Arg<'a, 'b> {
     Arg::with_name("target")
         .short("t")
@@ -39,8 +39,18 @@ pub fn handle_command<'a>(
     // Initialize codegen backend
     codegen::init(&options)?;
 
+    // Open the incremental cache before anything is parsed, so that the symbols used by cached
+    // modules are interned with the same ids they were compiled with
+    let incremental_cache = IncrementalCache::new(&options);
+    if let Some(ref cache) = incremental_cache {
+        cache.restore_symbols();
+    }
+
     // Build query database
     let mut db = CompilerDatabase::new(codemap, diagnostics);
+    if let Some(cache) = incremental_cache {
+        db.set_incremental_cache(cache);
+    }
 
     // The core of the query system is the initial set of options provided to the compiler
     //
@@ -108,6 +118,13 @@ pub fn handle_command<'a>(
     let target_machine = db.get_target_machine(thread_id);
     let atoms = db.take_atoms();
     let symbols = db.take_symbols();
+    let locations = db.take_locations();
+    if let Some(cache) = db.incremental_cache() {
+        if let Err(err) = cache.save_symbols(&atoms, &symbols) {
+            db.diagnostics()
+                .warn(format!("unable to save the incremental cache: {:#}", err));
+        }
+    }
     let output_dir = db.output_dir();
     codegen::generators::run(
         &mut codegen_results,
@@ -116,6 +133,7 @@ pub fn handle_command<'a>(
         output_dir.as_path(),
         atoms,
         symbols,
+        locations,
     )?;
 
     // Link all compiled objects
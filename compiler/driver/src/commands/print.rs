@@ -1,10 +1,12 @@
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use anyhow::anyhow;
 use clap::ArgMatches;
 
 use libeir_diagnostics::Emitter;
 
+use liblumen_beam as beam;
 use liblumen_codegen as codegen;
 use liblumen_llvm as llvm;
 use liblumen_session::{CodegenOptions, DebuggingOptions, Options};
@@ -61,6 +63,12 @@ pub fn handle_command<'a>(
         ("passes", _subcommand_matches) => {
             llvm::passes::print();
         }
+        ("beam-asm", subcommand_matches) => {
+            let input = subcommand_matches.unwrap().value_of("input").unwrap();
+            let module = beam::asm::Module::from_beam_file(cwd.join(input))
+                .map_err(|err| anyhow!("unable to disassemble {}: {}", input, err))?;
+            println!("{}", module);
+        }
         (subcommand, _) => unimplemented!("print subcommand '{}' is not implemented", subcommand),
     }
 
@@ -0,0 +1,165 @@
+use std::ffi::CString;
+use std::mem;
+use std::os::raw::{c_char, c_int};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::thread;
+use std::time::Instant;
+
+use anyhow::anyhow;
+
+use clap::ArgMatches;
+
+use log::debug;
+
+use libeir_diagnostics::{CodeMap, Emitter};
+
+use liblumen_codegen as codegen;
+use liblumen_codegen::linker;
+use liblumen_llvm as llvm;
+use liblumen_llvm::jit::JitStack;
+use liblumen_session::{CodegenOptions, DebuggingOptions, Options};
+use liblumen_util::fs::NativeLibraryKind;
+use liblumen_util::time::HumanDuration;
+
+use crate::commands::*;
+use crate::compiler::{prelude::*, *};
+
+/// The signature of `main` in the runtime, which starts it the same way as an executable
+type Main = extern "C" fn(c_int, *const *const c_char) -> c_int;
+
+pub fn handle_command<'a>(
+    c_opts: CodegenOptions,
+    z_opts: DebuggingOptions,
+    matches: &ArgMatches<'a>,
+    cwd: PathBuf,
+    emitter: Option<Arc<dyn Emitter>>,
+) -> anyhow::Result<()> {
+    // Extract options from provided arguments
+    let options = Options::new(c_opts, z_opts, cwd, &matches)?;
+    // Construct empty code map for use in compilation
+    let codemap = Arc::new(RwLock::new(CodeMap::new()));
+    // Set up diagnostics
+    let diagnostics = create_diagnostics_handler(&options, codemap.clone(), emitter);
+
+    // Initialize codegen backend
+    codegen::init(&options)?;
+
+    // Build query database
+    //
+    // Nothing is written to disk, so the incremental cache is not used
+    let mut db = CompilerDatabase::new(codemap, diagnostics);
+    db.set_options(Arc::new(options));
+
+    let inputs = db.inputs().unwrap_or_else(abort_on_err);
+
+    let num_inputs = inputs.len();
+    if num_inputs < 1 {
+        db.diagnostics()
+            .fatal_str("No input sources found!")
+            .raise();
+    }
+
+    // Every module is loaded into the same JIT, so they are all built on this thread, in the
+    // same LLVM context
+    let start = Instant::now();
+    let thread_id = thread::current().id();
+    let mut modules = Vec::with_capacity(num_inputs);
+    for input in inputs.iter().copied() {
+        use liblumen_incremental::InternerDatabase;
+
+        debug!("building llvm module for {:?}", input);
+        match db.get_llvm_module(thread_id, input) {
+            Ok(module) => modules.push(module),
+            Err(_) => {
+                let input_info = db.lookup_intern_input(input);
+                db.diagnostics().failed("Failed", input_info.source_name());
+            }
+        }
+    }
+
+    // Do not proceed to running if there were compilation errors
+    let diagnostics = db.diagnostics();
+    diagnostics.abort_if_errors();
+
+    // Generate the tables of atoms, functions, locations and NIFs in memory, in place of the
+    // objects an executable is linked with
+    let context = db.llvm_context(thread_id);
+    let target_machine = db.get_target_machine(thread_id);
+    let tables = codegen::generators::build(
+        &context,
+        &target_machine,
+        db.take_atoms(),
+        db.take_symbols(),
+        db.take_locations(),
+    )?;
+
+    // The JIT takes ownership of its target machine, so it is given one of its own
+    let options = db.options();
+    let mut jit = JitStack::new(llvm::target::create_target_machine(
+        &options,
+        &diagnostics,
+        false,
+    ))?;
+    for module in modules.iter() {
+        jit.add_module(llvm::Module::clone(module))?;
+    }
+    for (_, table) in tables {
+        jit.add_module(table)?;
+    }
+
+    // Link with the runtime, and the native libraries, the same way an executable would be
+    for archive in linker::jit_archives(&options)? {
+        jit.add_archive(&archive)?;
+    }
+    for (name, _, kind) in options.link_libraries.iter() {
+        match kind {
+            Some(NativeLibraryKind::NativeStatic) => (),
+            None | Some(NativeLibraryKind::NativeUnknown) => {
+                let target_options = &options.target.options;
+                jit.load_library(&format!(
+                    "{}{}{}",
+                    target_options.dll_prefix, name, target_options.dll_suffix
+                ))?;
+            }
+            Some(kind) => {
+                return Err(anyhow!(
+                    "unable to load {}: {:?} libraries cannot be run",
+                    name,
+                    kind
+                ))
+            }
+        }
+    }
+
+    let main = match jit.get_symbol_address("main")? {
+        Some(address) => unsafe { mem::transmute::<usize, Main>(address as usize) },
+        None => return Err(anyhow!("unable to find the entry point of the runtime")),
+    };
+
+    let duration = HumanDuration::since(start);
+    diagnostics.success(
+        "Finished",
+        &format!("compiled {} in {:#}", options.project_name, duration),
+    );
+
+    // The program sees the project name as its own, followed by the arguments after `--`
+    let mut args = vec![CString::new(options.project_name.as_str())?];
+    if let Some(values) = matches.values_of("args") {
+        for value in values {
+            args.push(CString::new(value)?);
+        }
+    }
+    let argv = args
+        .iter()
+        .map(|arg| arg.as_ptr())
+        .chain(std::iter::once(std::ptr::null()))
+        .collect::<Vec<_>>();
+
+    let status = main(args.len() as c_int, argv.as_ptr());
+    if status != 0 {
+        std::process::exit(status);
+    }
+
+    Ok(())
+}
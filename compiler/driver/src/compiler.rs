@@ -1,8 +1,9 @@
+mod cache;
 mod intern;
 mod queries;
 mod query_groups;
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex, RwLock};
 
@@ -14,12 +15,15 @@ use salsa::Snapshot;
 use libeir_diagnostics::{CodeMap, Diagnostic};
 use libeir_intern::Symbol;
 
+use liblumen_codegen::meta::FunctionLocation;
 use liblumen_core::symbols::FunctionSymbol;
 use liblumen_incremental::{InternedInput, InternerStorage};
 pub use liblumen_incremental::{ParserDatabase, ParserDatabaseBase};
 use liblumen_incremental::{ParserStorage, QueryResult};
 use liblumen_session::{DiagnosticsHandler, Emit, Options, OutputType};
 
+pub use self::cache::IncrementalCache;
+
 pub(crate) mod prelude {
     pub use super::query_groups::*;
 }
@@ -33,6 +37,8 @@ pub struct CompilerDatabase {
     codemap: Arc<RwLock<CodeMap>>,
     atoms: Arc<Mutex<HashSet<Symbol>>>,
     symbols: Arc<Mutex<HashSet<FunctionSymbol>>>,
+    locations: Arc<Mutex<HashMap<FunctionSymbol, FunctionLocation>>>,
+    incremental_cache: Option<Arc<IncrementalCache>>,
 }
 impl CompilerDatabase {
     pub fn new(codemap: Arc<RwLock<CodeMap>>, diagnostics: DiagnosticsHandler) -> Self {
@@ -45,8 +51,15 @@ impl CompilerDatabase {
             codemap,
             atoms: Arc::new(Mutex::new(atoms)),
             symbols: Arc::new(Mutex::new(HashSet::default())),
+            locations: Arc::new(Mutex::new(HashMap::default())),
+            incremental_cache: None,
         }
     }
+
+    /// Reuses unchanged modules from `cache` instead of compiling them
+    pub fn set_incremental_cache(&mut self, cache: IncrementalCache) {
+        self.incremental_cache = Some(Arc::new(cache));
+    }
 }
 impl salsa::Database for CompilerDatabase {
     fn salsa_runtime(&self) -> &salsa::Runtime<Self> {
@@ -65,6 +78,8 @@ impl salsa::ParallelDatabase for CompilerDatabase {
             codemap: self.codemap.clone(),
             atoms: self.atoms.clone(),
             symbols: self.symbols.clone(),
+            locations: self.locations.clone(),
+            incremental_cache: self.incremental_cache.clone(),
         })
     }
 }
@@ -182,4 +197,27 @@ impl CodegenDatabaseBase for CompilerDatabase {
             locked.insert(*i);
         }
     }
+
+    fn take_locations(&mut self) -> HashMap<FunctionSymbol, FunctionLocation> {
+        let locations = Arc::get_mut(&mut self.locations)
+            .unwrap()
+            .get_mut()
+            .unwrap();
+        let empty = HashMap::default();
+        core::mem::replace(locations, empty)
+    }
+
+    fn add_locations<'a, I>(&self, locations: I)
+    where
+        I: Iterator<Item = (&'a FunctionSymbol, &'a FunctionLocation)>,
+    {
+        let mut locked = self.locations.lock().unwrap();
+        for (symbol, location) in locations {
+            locked.insert(*symbol, location.clone());
+        }
+    }
+
+    fn incremental_cache(&self) -> Option<&IncrementalCache> {
+        self.incremental_cache.as_deref()
+    }
 }
@@ -0,0 +1,434 @@
+//! The incremental compilation cache, kept in the output directory between runs.
+//!
+//! Every module compiled to an object file is stored in the cache under a key derived from its
+//! source, the headers it could include, the options that affect code generation and the compiler
+//! itself, together with a manifest of the atoms and function symbols it contributed, and where
+//! those functions are defined. When a later run computes the same key for a module, its object
+//! file is reused instead of compiling it again.
+//!
+//! Object files refer to atoms by the id they were interned with, which depends on the order in
+//! which every symbol was interned during that run. To keep those ids valid, the ids of all atoms
+//! and function names used by the cached modules are saved at the end of a run, and interned again
+//! in the same order at the start of the next one, before anything is parsed. A cached module is
+//! only reused if each of its atoms and function names ended up with the id it was compiled with.
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File};
+use std::hash::{Hash, Hasher};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Context};
+
+use log::debug;
+
+use libeir_intern::Symbol;
+
+use liblumen_beam::serialization::etf;
+use liblumen_beam::serialization::etf::pattern::{any, Any, VarList, U32, U64, U8};
+use liblumen_codegen::meta::FunctionLocation;
+use liblumen_core::symbols::FunctionSymbol;
+use liblumen_incremental::InternedInput;
+use liblumen_session::{Input, Options, OutputType};
+
+/// The name of the file holding the ids of the symbols used by cached modules
+const SYMBOLS_FILE: &'static str = "symbols";
+
+pub struct IncrementalCache {
+    dir: PathBuf,
+    fingerprint: u64,
+    generated: Mutex<HashMap<InternedInput, Generated>>,
+}
+
+/// The atoms and function symbols generated for a module during this run
+struct Generated {
+    atoms: HashSet<Symbol>,
+    symbols: HashSet<FunctionSymbol>,
+    locations: HashMap<FunctionSymbol, FunctionLocation>,
+}
+
+/// A module from the cache that can be reused
+pub struct CachedModule {
+    pub object: PathBuf,
+    pub atoms: Vec<Symbol>,
+    pub symbols: Vec<FunctionSymbol>,
+    pub locations: HashMap<FunctionSymbol, FunctionLocation>,
+}
+
+impl IncrementalCache {
+    /// Opens the cache in the output directory.
+    ///
+    /// Returns `None` if it was disabled with `-C no_incremental`, or if outputs other than object
+    /// files were requested, as only object files are cached.
+    pub fn new(options: &Options) -> Option<Self> {
+        if options.codegen_opts.no_incremental {
+            return None;
+        }
+        let only_objects = options
+            .output_types
+            .keys()
+            .all(|output_type| match output_type {
+                OutputType::Object | OutputType::Exe => true,
+                _ => false,
+            });
+        if !only_objects {
+            debug!("not using the incremental cache, as outputs other than objects are emitted");
+            return None;
+        }
+
+        Some(Self {
+            dir: options.output_dir().join("incremental"),
+            fingerprint: fingerprint(options),
+            generated: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Interns the symbols used by cached modules with the ids they had in the previous run.
+    ///
+    /// This must be called before anything else is interned concurrently, i.e. before any of the
+    /// inputs are parsed.
+    pub fn restore_symbols(&self) {
+        let path = self.dir.join(SYMBOLS_FILE);
+        let names = match read_term(&path).and_then(|term| {
+            let names = term.as_match(names_pattern()).ok()?;
+            decode_names(names)
+        }) {
+            Some(names) => names,
+            None => return,
+        };
+
+        // Symbols are interned with consecutive ids, so interning unused names fills in the gaps
+        // between the ids that need to be restored
+        let mut fillers = 0;
+        let mut fill = || {
+            fillers += 1;
+            Symbol::intern(&format!("\0incremental-filler-{}", fillers)).as_usize()
+        };
+
+        let mut next = fill() + 1;
+        for (id, name) in names {
+            while next < id {
+                next = fill() + 1;
+            }
+            let symbol = Symbol::intern(&name);
+            if symbol.as_usize() == next {
+                next += 1;
+            }
+        }
+        debug!("restored symbols from {}", path.display());
+    }
+
+    /// Saves the ids of the atoms and function names used by this run, so that the next run can
+    /// restore them.
+    pub fn save_symbols(
+        &self,
+        atoms: &HashSet<Symbol>,
+        symbols: &HashSet<FunctionSymbol>,
+    ) -> anyhow::Result<()> {
+        let mut ids = atoms.iter().map(|atom| atom.as_usize()).collect::<Vec<_>>();
+        for symbol in symbols.iter() {
+            ids.push(symbol.module);
+            ids.push(symbol.function);
+        }
+        ids.sort();
+        ids.dedup();
+
+        fs::create_dir_all(&self.dir)?;
+        write_term(&self.dir.join(SYMBOLS_FILE), &encode_names(&ids))
+    }
+
+    /// The key of `input` in the cache, if it can be cached.
+    pub fn key(&self, input: &Input) -> Option<u64> {
+        let path = match input {
+            Input::File(ref path) => path,
+            Input::Str { .. } => return None,
+        };
+        let source = fs::read(path).ok()?;
+
+        let mut hasher = DefaultHasher::new();
+        self.fingerprint.hash(&mut hasher);
+        path.hash(&mut hasher);
+        source.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
+    /// Records the atoms and function symbols generated for `input`, so that they can be stored in
+    /// the cache once its object file is emitted.
+    pub fn record(
+        &self,
+        input: InternedInput,
+        atoms: &HashSet<Symbol>,
+        symbols: &HashSet<FunctionSymbol>,
+        locations: &HashMap<FunctionSymbol, FunctionLocation>,
+    ) {
+        self.generated.lock().unwrap().insert(
+            input,
+            Generated {
+                atoms: atoms.clone(),
+                symbols: symbols.clone(),
+                locations: locations.clone(),
+            },
+        );
+    }
+
+    /// Loads the module `stem` if it is cached under `key` and all of its symbols were restored.
+    pub fn load(&self, stem: &str, key: u64) -> Option<CachedModule> {
+        let (object, manifest) = self.entry_paths(stem, key);
+        let term = read_term(&manifest)?;
+        let (names, atoms, symbols) = term
+            .as_match((
+                names_pattern(),
+                VarList(U64),
+                VarList((U64, U64, U8, any::<etf::Binary>(), U32)),
+            ))
+            .ok()?;
+
+        for (id, name) in decode_names(names)? {
+            let symbol = Symbol::intern(&name);
+            if symbol.as_usize() != id {
+                debug!(
+                    "not reusing {}, as {} has id {} instead of {}",
+                    stem,
+                    name,
+                    symbol.as_usize(),
+                    id
+                );
+                return None;
+            }
+        }
+        if !object.is_file() {
+            return None;
+        }
+
+        let atoms = atoms
+            .into_iter()
+            .map(|id| symbol_from_id(id as usize))
+            .collect();
+        let mut locations = HashMap::with_capacity(symbols.len());
+        let symbols = symbols
+            .into_iter()
+            .map(|(module, function, arity, file, line)| {
+                let symbol = FunctionSymbol {
+                    module: module as usize,
+                    function: function as usize,
+                    arity,
+                    ptr: std::ptr::null(),
+                };
+                if line > 0 {
+                    let file = String::from_utf8_lossy(&file.bytes).into_owned();
+                    locations.insert(symbol, FunctionLocation { file, line });
+                }
+                symbol
+            })
+            .collect();
+        Some(CachedModule {
+            object,
+            atoms,
+            symbols,
+            locations,
+        })
+    }
+
+    /// Stores the object file of `input` in the cache under `key`, replacing any older entry for
+    /// the module `stem`.
+    ///
+    /// Inputs for which no atoms and symbols were recorded, like MLIR sources, are not stored.
+    pub fn store(
+        &self,
+        input: InternedInput,
+        stem: &str,
+        key: u64,
+        object: &Path,
+    ) -> anyhow::Result<()> {
+        let generated = match self.generated.lock().unwrap().remove(&input) {
+            Some(generated) => generated,
+            None => return Ok(()),
+        };
+
+        fs::create_dir_all(&self.dir)?;
+        self.remove_entries(stem)?;
+
+        let mut ids = generated
+            .atoms
+            .iter()
+            .map(|atom| atom.as_usize())
+            .collect::<Vec<_>>();
+        ids.sort();
+        let mut names = ids.clone();
+        for symbol in generated.symbols.iter() {
+            names.push(symbol.module);
+            names.push(symbol.function);
+        }
+        names.sort();
+        names.dedup();
+
+        let manifest = etf::Term::from(etf::Tuple::from(vec![
+            encode_names(&names),
+            list(ids.into_iter().map(integer).collect()),
+            encode_symbols(&generated.symbols, &generated.locations),
+        ]));
+
+        let (cached_object, cached_manifest) = self.entry_paths(stem, key);
+        fs::copy(object, &cached_object)
+            .with_context(|| format!("unable to copy {}", object.display()))?;
+        write_term(&cached_manifest, &manifest)
+    }
+
+    fn entry_paths(&self, stem: &str, key: u64) -> (PathBuf, PathBuf) {
+        // Module names can contain dots, so the extension can't be set with `with_extension`
+        let entry = format!("{}-{:016x}", stem, key);
+        (
+            self.dir.join(format!("{}.o", entry)),
+            self.dir.join(format!("{}.manifest", entry)),
+        )
+    }
+
+    /// Removes the entries of older versions of the module `stem`
+    fn remove_entries(&self, stem: &str) -> anyhow::Result<()> {
+        let prefix = format!("{}-", stem);
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            let is_entry = path
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(&prefix))
+                .map(|key| key.len() == 16 && key.chars().all(|c| c.is_ascii_hexdigit()))
+                .unwrap_or(false);
+            if is_entry {
+                fs::remove_file(&path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Hashes everything besides the source of a module that can change its object file.
+fn fingerprint(options: &Options) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    crate::LUMEN_RELEASE.hash(&mut hasher);
+    crate::LUMEN_COMMIT_HASH.hash(&mut hasher);
+    options.target.triple().hash(&mut hasher);
+    format!("{:?}", options.project_type).hash(&mut hasher);
+    format!("{:?}", options.opt_level).hash(&mut hasher);
+    format!("{:?}", options.debug_info).hash(&mut hasher);
+    options.debug_assertions.hash(&mut hasher);
+    format!("{:?}", options.codegen_opts).hash(&mut hasher);
+    format!("{:?}", options.debugging_opts).hash(&mut hasher);
+
+    let mut defines = options.defines.iter().collect::<Vec<_>>();
+    defines.sort();
+    defines.hash(&mut hasher);
+
+    // Any change to a header invalidates every module, as it isn't known which modules include it
+    let output_dir = options.output_dir();
+    let dirs = std::iter::once(&options.current_dir)
+        .chain(options.include_path.iter())
+        .chain(options.code_path.iter());
+    for dir in dirs {
+        let walker = walkdir::WalkDir::new(dir)
+            .sort_by(|a, b| a.file_name().cmp(b.file_name()))
+            .into_iter()
+            .filter_entry(|entry| {
+                let name = entry.file_name().to_string_lossy();
+                entry.depth() == 0
+                    || !(name.starts_with('.') || name == "_build" || entry.path() == output_dir)
+            });
+        for entry in walker.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.extension().map(|ext| ext == "hrl").unwrap_or(false) {
+                path.hash(&mut hasher);
+                fs::read(path).unwrap_or_default().hash(&mut hasher);
+            }
+        }
+    }
+
+    hasher.finish()
+}
+
+fn symbol_from_id(id: usize) -> Symbol {
+    unsafe { std::mem::transmute::<u32, Symbol>(id as u32) }
+}
+
+fn integer(i: usize) -> etf::Term {
+    etf::Term::from(etf::BigInteger::from(i))
+}
+
+fn list(elements: Vec<etf::Term>) -> etf::Term {
+    etf::Term::from(etf::List::from(elements))
+}
+
+/// Encodes the ids with their names as `[{Id, Name :: binary()}]`
+fn encode_names(ids: &[usize]) -> etf::Term {
+    list(
+        ids.iter()
+            .map(|&id| {
+                let name = symbol_from_id(id).as_str().get().as_bytes().to_vec();
+                etf::Term::from(etf::Tuple::from(vec![
+                    integer(id),
+                    etf::Term::from(etf::Binary::from(name)),
+                ]))
+            })
+            .collect(),
+    )
+}
+
+/// Matches the ids and names encoded by `encode_names`
+fn names_pattern() -> VarList<(U64, Any<etf::Binary>)> {
+    VarList((U64, any()))
+}
+
+/// Decodes the names matched by `names_pattern`, in the order of the ids
+fn decode_names(pairs: Vec<(u64, &etf::Binary)>) -> Option<Vec<(usize, String)>> {
+    let mut names = Vec::with_capacity(pairs.len());
+    for (id, name) in pairs {
+        let name = String::from_utf8(name.bytes.clone()).ok()?;
+        names.push((id as usize, name));
+    }
+    names.sort();
+    Some(names)
+}
+
+/// Encodes the function symbols as `[{ModuleId, FunctionId, Arity, File :: binary(), Line}]`,
+/// where `Line` is 0 if the location of the function is unknown
+fn encode_symbols(
+    symbols: &HashSet<FunctionSymbol>,
+    locations: &HashMap<FunctionSymbol, FunctionLocation>,
+) -> etf::Term {
+    list(
+        symbols
+            .iter()
+            .map(|symbol| {
+                let (file, line) = match locations.get(symbol) {
+                    Some(location) => (location.file.as_bytes().to_vec(), location.line),
+                    None => (Vec::new(), 0),
+                };
+                etf::Term::from(etf::Tuple::from(vec![
+                    integer(symbol.module),
+                    integer(symbol.function),
+                    integer(symbol.arity as usize),
+                    etf::Term::from(etf::Binary::from(file)),
+                    integer(line as usize),
+                ]))
+            })
+            .collect(),
+    )
+}
+
+fn read_term(path: &Path) -> Option<etf::Term> {
+    let file = File::open(path).ok()?;
+    match etf::Term::decode(BufReader::new(file)) {
+        Ok(term) => Some(term),
+        Err(err) => {
+            debug!("ignoring {}: {}", path.display(), err);
+            None
+        }
+    }
+}
+
+fn write_term(path: &Path, term: &etf::Term) -> anyhow::Result<()> {
+    let mut bytes = Vec::new();
+    term.encode(&mut bytes)
+        .map_err(|err| anyhow!("unable to encode {}: {}", path.display(), err))?;
+    fs::write(path, bytes).with_context(|| format!("unable to write {}", path.display()))
+}
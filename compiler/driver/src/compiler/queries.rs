@@ -1,3 +1,5 @@
+use std::fs::File;
+use std::io;
 use std::ops::Deref;
 use std::sync::Arc;
 use std::thread::{self, ThreadId};
@@ -11,7 +13,7 @@ use liblumen_codegen::{self as codegen, GeneratedModule};
 use liblumen_incremental::{InternedInput, QueryResult};
 use liblumen_llvm as llvm;
 use liblumen_mlir as mlir;
-use liblumen_session::{Input, InputType, OutputType};
+use liblumen_session::{Input, InputType, Options, OutputType};
 
 use crate::compiler::query_groups::*;
 
@@ -96,9 +98,14 @@ where
             module: mlir_module,
             atoms,
             symbols,
+            locations,
         }) => {
             db.add_atoms(atoms.iter());
             db.add_symbols(symbols.iter());
+            db.add_locations(locations.iter());
+            if let Some(cache) = db.incremental_cache() {
+                cache.record(input, &atoms, &symbols, &locations);
+            }
             db.maybe_emit_file_with_opts(&options, input, &mlir_module)?;
             Ok(Arc::new(mlir_module))
         }
@@ -119,7 +126,7 @@ where
     C: CodegenDatabase,
 {
     match db.input_type(input) {
-        InputType::Erlang | InputType::AbstractErlang | InputType::EIR => {
+        InputType::Erlang | InputType::AbstractErlang | InputType::BEAM | InputType::EIR => {
             debug!("input {:?} is erlang", input);
             Ok(db.generate_mlir(thread_id, input)?)
         }
@@ -241,6 +248,17 @@ where
     let source_name = input_info.source_name();
     let diagnostics = db.diagnostics();
 
+    // Reuse the object file from the incremental cache if nothing it depends on has changed
+    let cache_key = db
+        .incremental_cache()
+        .and_then(|cache| cache.key(&input_info));
+    if let Some(key) = cache_key {
+        if let Some(compiled) = reuse_cached(db, &options, input, key)? {
+            diagnostics.success("Fresh", &source_name);
+            return Ok(compiled);
+        }
+    }
+
     diagnostics.success("Compiling", &source_name);
     debug!(
         "compiling {:?} ({:?}) on thread {:?}",
@@ -276,17 +294,62 @@ where
         .maybe_emit(&input_info, OutputType::LLVMBitcode)
         .map(|filename| db.output_dir().join(filename));
 
-    let compiled = Arc::new(CompiledModule::new(
-        input_info.file_stem().to_string_lossy().into_owned(),
-        obj_path,
-        bc_path,
-    ));
+    let name = input_info.file_stem().to_string_lossy().into_owned();
+    if let (Some(cache), Some(key), Some(obj_path)) =
+        (db.incremental_cache(), cache_key, obj_path.as_ref())
+    {
+        if let Err(err) = cache.store(input, &name, key, obj_path) {
+            diagnostics.warn(format!(
+                "unable to add {} to the incremental cache: {:#}",
+                source_name, err
+            ));
+        }
+    }
+
+    let compiled = Arc::new(CompiledModule::new(name, obj_path, bc_path));
 
     debug!("compilation finished for {:?}", input);
     diagnostics.success("Compiled", &source_name);
     Ok(compiled)
 }
 
+/// Emits the object file of `input` from the incremental cache, if it is cached under `key`
+fn reuse_cached<C>(
+    db: &C,
+    options: &Options,
+    input: InternedInput,
+    key: u64,
+) -> QueryResult<Option<Arc<CompiledModule>>>
+where
+    C: CodegenDatabase,
+{
+    let input_info = db.lookup_intern_input(input);
+    let name = input_info.file_stem().to_string_lossy().into_owned();
+    let cached = match db
+        .incremental_cache()
+        .and_then(|cache| cache.load(&name, key))
+    {
+        Some(cached) => cached,
+        None => return Ok(None),
+    };
+
+    debug!(
+        "reusing {} from the incremental cache",
+        cached.object.display()
+    );
+    let obj_path =
+        db.maybe_emit_file_with_callback_and_opts(options, input, OutputType::Object, |outfile| {
+            let mut object = File::open(&cached.object)?;
+            io::copy(&mut object, outfile)?;
+            Ok(())
+        })?;
+    db.add_atoms(cached.atoms.iter());
+    db.add_symbols(cached.symbols.iter());
+    db.add_locations(cached.locations.iter());
+
+    Ok(Some(Arc::new(CompiledModule::new(name, obj_path, None))))
+}
+
 fn get_input_source_name<C>(db: &C, input: InternedInput) -> Option<String>
 where
     C: CodegenDatabase,
@@ -1,8 +1,8 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::thread::ThreadId;
 
-use liblumen_codegen::meta::CompiledModule;
+use liblumen_codegen::meta::{CompiledModule, FunctionLocation};
 use liblumen_core::symbols::FunctionSymbol;
 use liblumen_incremental::ParserDatabase;
 use liblumen_incremental::{InternedInput, QueryResult};
@@ -11,6 +11,7 @@ use liblumen_mlir as mlir;
 
 use crate::compiler::intern::InternedString;
 use crate::compiler::queries;
+use crate::compiler::IncrementalCache;
 
 #[salsa::query_group(CodegenStorage)]
 pub trait CodegenDatabase: CodegenDatabaseBase {
@@ -77,4 +78,9 @@ pub trait CodegenDatabaseBase: ParserDatabase + StringInternerDatabase {
     fn add_symbols<'a, I>(&self, symbols: I)
     where
         I: Iterator<Item = &'a FunctionSymbol>;
+    fn take_locations(&mut self) -> HashMap<FunctionSymbol, FunctionLocation>;
+    fn add_locations<'a, I>(&self, locations: I)
+    where
+        I: Iterator<Item = (&'a FunctionSymbol, &'a FunctionLocation)>;
+    fn incremental_cache(&self) -> Option<&IncrementalCache>;
 }
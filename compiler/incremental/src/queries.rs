@@ -111,7 +111,7 @@ where
 
     let frontend: AnyFrontend = match db.input_type(input) {
         InputType::Erlang => ErlangFrontend::new(db.parse_config()).into(),
-        InputType::AbstractErlang => AbstrErlangFrontend::new().into(),
+        InputType::AbstractErlang | InputType::BEAM => AbstrErlangFrontend::new().into(),
         InputType::EIR => EirFrontend::new().into(),
         _ => unreachable!(),
     };
@@ -119,6 +119,10 @@ where
     let codemap = db.codemap().clone();
 
     let (result, diags) = match db.lookup_intern_input(input) {
+        Input::File(ref path) if db.input_type(input) == InputType::BEAM => {
+            let listing = beam_listing(db, path)?;
+            frontend.parse_string_dyn(codemap, &listing)
+        }
         Input::File(ref path) => frontend.parse_file_dyn(codemap, path),
         Input::Str { ref input, .. } => frontend.parse_string_dyn(codemap, input),
     };
@@ -137,6 +141,33 @@ where
     }
 }
 
+/// The abstract code from a BEAM file compiled with `debug_info`, written as the text the
+/// abstract Erlang frontend parses.
+fn beam_listing<P>(db: &P, path: &Path) -> QueryResult<String>
+where
+    P: ParserDatabase,
+{
+    use anyhow::anyhow;
+    use liblumen_beam::syntax::ast::error::FromBeamError;
+    use liblumen_beam::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    match AbstractCode::from_beam_file(path).and_then(|code| code.to_listing()) {
+        Ok(listing) => Ok(listing),
+        Err(FromBeamError::NoDebugInfo) => {
+            db.diagnostics().error(anyhow!(
+                "{} was compiled without debug_info, recompile it with `erlc +debug_info`",
+                path.display()
+            ));
+            Err(())
+        }
+        Err(err) => {
+            db.diagnostics()
+                .error(anyhow!("unable to read {}: {}", path.display(), err));
+            Err(())
+        }
+    }
+}
+
 pub(crate) fn input_eir<P>(db: &P, input: InternedInput) -> QueryResult<IRModule>
 where
     P: ParserDatabase,
@@ -1,5 +1,6 @@
 use std::fmt;
 
+use crate::sys::debuginfo::LLVMDWARFEmissionKind;
 use crate::sys::{LLVMLinkage, LLVMThreadLocalMode};
 
 #[derive(Copy, Clone)]
@@ -58,6 +59,7 @@ pub enum Linkage {
     Internal,
     External,
     Weak,
+    ExternWeak,
 }
 impl Default for Linkage {
     fn default() -> Self {
@@ -71,6 +73,7 @@ impl Into<LLVMLinkage> for Linkage {
             Self::Internal => LLVMLinkage::LLVMInternalLinkage,
             Self::External => LLVMLinkage::LLVMExternalLinkage,
             Self::Weak => LLVMLinkage::LLVMWeakAnyLinkage,
+            Self::ExternWeak => LLVMLinkage::LLVMExternalWeakLinkage,
         }
     }
 }
@@ -111,3 +114,12 @@ pub fn to_llvm_opt_settings(cfg: liblumen_session::OptLevel) -> (CodeGenOptLevel
         OptLevel::SizeMin => (CodeGenOptLevel::Default, CodeGenOptSize::Aggressive),
     }
 }
+
+pub fn to_llvm_debug_emission_kind(cfg: liblumen_session::DebugInfo) -> LLVMDWARFEmissionKind {
+    use liblumen_session::DebugInfo;
+    match cfg {
+        DebugInfo::None => LLVMDWARFEmissionKind::LLVMDWARFEmissionKindNone,
+        DebugInfo::Limited => LLVMDWARFEmissionKind::LLVMDWARFEmissionKindLineTablesOnly,
+        DebugInfo::Full => LLVMDWARFEmissionKind::LLVMDWARFEmissionKindFull,
+    }
+}
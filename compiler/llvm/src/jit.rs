@@ -0,0 +1,175 @@
+//! A JIT built on LLVM's ORC APIs, which compiles modules and links them with object files in
+//! the memory of the current process, so that compiled code can be called without writing or
+//! linking an executable.
+use std::ffi::{CStr, CString};
+use std::path::Path;
+use std::ptr;
+
+use anyhow::anyhow;
+
+use crate::archives::ArchiveRO;
+use crate::module::Module;
+use crate::sys::core::LLVMCreateMemoryBufferWithMemoryRangeCopy;
+use crate::sys::error::{LLVMDisposeErrorMessage, LLVMErrorRef, LLVMGetErrorMessage};
+use crate::sys::orc::*;
+use crate::sys::support::{LLVMLoadLibraryPermanently, LLVMSearchForAddressOfSymbol};
+use crate::target::TargetMachine;
+use crate::Result;
+
+/// An ORC JIT stack
+///
+/// Symbols are resolved against the modules and objects added to the stack first, then against
+/// the current process and the libraries loaded with `load_library`.
+pub struct JitStack {
+    stack: LLVMOrcJITStackRef,
+}
+impl JitStack {
+    /// Creates a new JIT stack, which takes ownership of `target_machine`
+    pub fn new(target_machine: TargetMachine) -> Result<Self> {
+        crate::require_inited();
+
+        // Loading the program itself makes the symbols of the current process searchable
+        if unsafe { LLVMLoadLibraryPermanently(ptr::null()) } != 0 {
+            return Err(anyhow!(
+                "unable to search the symbols of the current process"
+            ));
+        }
+
+        let stack = unsafe { LLVMOrcCreateInstance(target_machine.as_ref()) };
+        if stack.is_null() {
+            return Err(anyhow!("unable to create JIT"));
+        }
+
+        Ok(Self { stack })
+    }
+
+    /// Compiles `module`, and adds the result to the JIT
+    ///
+    /// The JIT takes ownership of the module, so it must not be used afterwards.
+    pub fn add_module(&mut self, module: Module) -> Result<()> {
+        let mut handle = 0;
+        let err = unsafe {
+            LLVMOrcAddEagerlyCompiledIR(
+                self.stack,
+                &mut handle,
+                module.as_ref(),
+                Some(resolve_symbol),
+                ptr::null_mut(),
+            )
+        };
+
+        check(err)
+    }
+
+    /// Adds each object file in the archive at `path` to the JIT
+    ///
+    /// Like a static link, an object is only linked if a symbol it defines is used.
+    pub fn add_archive(&mut self, path: &Path) -> Result<()> {
+        let archive = ArchiveRO::open(path)
+            .map_err(|err| anyhow!("unable to open {}: {}", path.display(), err))?;
+
+        for child in archive.iter() {
+            let child =
+                child.map_err(|err| anyhow!("unable to read {}: {}", path.display(), err))?;
+            // Rust libraries also hold their metadata and bitcode, which are not objects
+            let name = match child.name() {
+                Some(name) if name.ends_with(".o") => name,
+                _ => continue,
+            };
+            self.add_object(name, child.data())?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds the object file `data` to the JIT, which copies it
+    pub fn add_object(&mut self, name: &str, data: &[u8]) -> Result<()> {
+        let name = CString::new(name).unwrap();
+        let buffer = unsafe {
+            LLVMCreateMemoryBufferWithMemoryRangeCopy(
+                data.as_ptr() as *const libc::c_char,
+                data.len(),
+                name.as_ptr(),
+            )
+        };
+
+        let mut handle = 0;
+        let err = unsafe {
+            LLVMOrcAddObjectFile(
+                self.stack,
+                &mut handle,
+                buffer,
+                Some(resolve_symbol),
+                ptr::null_mut(),
+            )
+        };
+
+        check(err)
+    }
+
+    /// Loads the shared library `filename`, so that its symbols can be linked to
+    pub fn load_library(&mut self, filename: &str) -> Result<()> {
+        let filename = CString::new(filename).unwrap();
+
+        if unsafe { LLVMLoadLibraryPermanently(filename.as_ptr()) } != 0 {
+            Err(anyhow!("unable to load {}", filename.to_string_lossy()))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns the address of the symbol `name`, linking the code it depends on if it has not
+    /// been already, or `None` if no module or object added to the JIT defines it
+    pub fn get_symbol_address(&self, name: &str) -> Result<Option<u64>> {
+        let name = CString::new(name).unwrap();
+
+        let mut address = 0;
+        check(unsafe { LLVMOrcGetSymbolAddress(self.stack, &mut address, name.as_ptr()) })?;
+
+        if address == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(address))
+        }
+    }
+}
+impl Drop for JitStack {
+    fn drop(&mut self) {
+        unsafe {
+            LLVMOrcDisposeInstance(self.stack);
+        }
+    }
+}
+
+/// Resolves the symbols not defined in the JIT, which the stack looks up itself, against the
+/// current process and the libraries loaded into it
+extern "C" fn resolve_symbol(name: *const libc::c_char, _ctx: *mut libc::c_void) -> u64 {
+    let name = unsafe { CStr::from_ptr(name) };
+    // The names are mangled, so on Darwin they have an extra leading underscore
+    let name = if cfg!(target_os = "macos") {
+        let bytes = name.to_bytes_with_nul();
+        if bytes.starts_with(b"_") {
+            CStr::from_bytes_with_nul(&bytes[1..]).unwrap()
+        } else {
+            name
+        }
+    } else {
+        name
+    };
+
+    unsafe { LLVMSearchForAddressOfSymbol(name.as_ptr()) as u64 }
+}
+
+fn check(err: LLVMErrorRef) -> Result<()> {
+    if err.is_null() {
+        return Ok(());
+    }
+
+    unsafe {
+        let message = LLVMGetErrorMessage(err);
+        let result = Err(anyhow!("{}", CStr::from_ptr(message).to_string_lossy()));
+        LLVMDisposeErrorMessage(message);
+
+        result
+    }
+}
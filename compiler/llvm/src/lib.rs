@@ -7,6 +7,7 @@ pub mod config;
 pub mod context;
 pub mod diagnostics;
 pub mod enums;
+pub mod jit;
 pub mod module;
 pub mod passes;
 pub mod sys;
@@ -94,7 +94,7 @@ pub enum LLVMDWARFSourceLanguage {
 
 /// The amount of debug information to emit.
 #[repr(C)]
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum LLVMDWARFEmissionKind {
     LLVMDWARFEmissionKindNone = 0,
     LLVMDWARFEmissionKindFull,
@@ -2,6 +2,8 @@
 #include "lumen/llvm/Target.h"
 
 #include "mlir/Target/LLVMIR.h"
+#include "mlir/Target/LLVMIR/ModuleTranslation.h"
+#include "mlir/Dialect/LLVMIR/LLVMDialect.h"
 #include "mlir/Conversion/StandardToLLVM/ConvertStandardToLLVMPass.h"
 #include "mlir/ExecutionEngine/ExecutionEngine.h"
 #include "mlir/ExecutionEngine/OptUtils.h"
@@ -12,19 +14,28 @@
 #include "mlir/Transforms/Passes.h"
 
 #include "llvm-c/Core.h"
+#include "llvm-c/DebugInfo.h"
 #include "llvm-c/TargetMachine.h"
 #include "llvm/ADT/StringRef.h"
+#include "llvm/BinaryFormat/Dwarf.h"
 #include "llvm/Support/CBindingWrapping.h"
+#include "llvm/Support/Path.h"
+#include "llvm/IR/DIBuilder.h"
+#include "llvm/IR/DebugInfoMetadata.h"
+#include "llvm/IR/IRBuilder.h"
 #include "llvm/IR/Module.h"
 #include "llvm/Target/TargetMachine.h"
 
+using ::mlir::Location;
 using ::mlir::MLIRContext;
 using ::mlir::ModuleOp;
 using ::mlir::OpPassManager;
 using ::mlir::OwningModuleRef;
+using ::mlir::Operation;
 using ::mlir::PassManager;
 using ::llvm::StringRef;
 using ::llvm::TargetMachine;
+using DebugEmissionKind = ::llvm::DICompileUnit::DebugEmissionKind;
 
 using namespace lumen;
 
@@ -49,9 +60,143 @@ extern "C" MLIRModuleRef MLIRLowerModule(MLIRContextRef context,
 }
 
 
+//===----------------------------------------------------------------------===//
+// Debug Info
+//===----------------------------------------------------------------------===//
+
+namespace {
+
+/// Translates the LLVM dialect to LLVM IR with DWARF debug info.
+///
+/// Each function gets a subprogram named after its symbol, i.e.
+/// `module:function/arity`, and each instruction the line of the operation it
+/// was translated from, which the codegen builder took from the Erlang source.
+template <DebugEmissionKind EmissionKind>
+class DebugInfoTranslation : public mlir::LLVM::ModuleTranslation {
+ public:
+  DebugInfoTranslation(Operation *module,
+                       std::unique_ptr<llvm::Module> llvmModule)
+      : ModuleTranslation(module, std::move(llvmModule)) {}
+
+  // The translated module outlives the translation, so the debug info can be
+  // completed here, once every function has been translated
+  ~DebugInfoTranslation() override {
+    if (diBuilder) diBuilder->finalize();
+  }
+
+ protected:
+  mlir::LogicalResult convertOperation(Operation &op,
+                                       llvm::IRBuilder<> &builder) override {
+    llvm::Function *fn = builder.GetInsertBlock()->getParent();
+    llvm::DISubprogram *subprogram = getOrCreateSubprogram(op, fn);
+    unsigned line = 0, column = 0;
+    getLineAndColumn(op.getLoc(), line, column);
+    builder.SetCurrentDebugLocation(
+        llvm::DILocation::get(fn->getContext(), line, column, subprogram));
+
+    auto result = ModuleTranslation::convertOperation(op, builder);
+
+    // Instructions not translated from an operation, such as the phi nodes
+    // of block arguments, may be in another function, so have no location
+    builder.SetCurrentDebugLocation(llvm::DebugLoc());
+    return result;
+  }
+
+ private:
+  llvm::DISubprogram *getOrCreateSubprogram(Operation &op, llvm::Function *fn) {
+    if (auto *subprogram = fn->getSubprogram()) return subprogram;
+
+    llvm::Module *llvmModule = fn->getParent();
+    if (!diBuilder) {
+      auto moduleOp = op.getParentOfType<mlir::ModuleOp>();
+      diBuilder = std::make_unique<llvm::DIBuilder>(*llvmModule);
+      // DWARF has no language code for Erlang, and C is understood by every
+      // debugger and profiler
+      compileUnit = diBuilder->createCompileUnit(
+          llvm::dwarf::DW_LANG_C, getFile(moduleOp.getLoc()), "lumen",
+          /*isOptimized=*/false, /*flags=*/"", /*runtimeVersion=*/0,
+          /*splitName=*/"", EmissionKind);
+      if (!llvmModule->getModuleFlag("Debug Info Version"))
+        llvmModule->addModuleFlag(llvm::Module::Warning, "Debug Info Version",
+                                  llvm::DEBUG_METADATA_VERSION);
+      if (!llvmModule->getModuleFlag("Dwarf Version"))
+        llvmModule->addModuleFlag(llvm::Module::Warning, "Dwarf Version", 4);
+    }
+
+    Location loc = op.getParentOfType<mlir::LLVM::LLVMFuncOp>().getLoc();
+    unsigned line = 0, column = 0;
+    getLineAndColumn(loc, line, column);
+    llvm::DIFile *file = getFile(loc);
+    auto *type = diBuilder->createSubroutineType(
+        diBuilder->getOrCreateTypeArray(llvm::None));
+    auto *subprogram = diBuilder->createFunction(
+        file, fn->getName(), /*linkageName=*/fn->getName(), file, line, type,
+        /*scopeLine=*/line, llvm::DINode::FlagZero,
+        llvm::DISubprogram::SPFlagDefinition);
+    fn->setSubprogram(subprogram);
+    return subprogram;
+  }
+
+  llvm::DIFile *getFile(Location loc) {
+    auto fileLoc = getFileLineColLoc(loc);
+    if (!fileLoc) {
+      if (compileUnit) return compileUnit->getFile();
+      return diBuilder->createFile("<unknown>", "");
+    }
+
+    StringRef path = fileLoc.getFilename();
+    return diBuilder->createFile(llvm::sys::path::filename(path),
+                                 llvm::sys::path::parent_path(path));
+  }
+
+  static void getLineAndColumn(Location loc, unsigned &line,
+                               unsigned &column) {
+    if (auto fileLoc = getFileLineColLoc(loc)) {
+      line = fileLoc.getLine();
+      column = fileLoc.getColumn();
+    }
+  }
+
+  // Values may have several source locations, in which case the first is used
+  static mlir::FileLineColLoc getFileLineColLoc(Location loc) {
+    if (auto fileLoc = loc.dyn_cast<mlir::FileLineColLoc>()) return fileLoc;
+    if (auto fusedLoc = loc.dyn_cast<mlir::FusedLoc>()) {
+      for (Location child : fusedLoc.getLocations())
+        if (auto fileLoc = getFileLineColLoc(child)) return fileLoc;
+    }
+    if (auto nameLoc = loc.dyn_cast<mlir::NameLoc>())
+      return getFileLineColLoc(nameLoc.getChildLoc());
+    if (auto callSiteLoc = loc.dyn_cast<mlir::CallSiteLoc>())
+      return getFileLineColLoc(callSiteLoc.getCallee());
+    return {};
+  }
+
+  std::unique_ptr<llvm::DIBuilder> diBuilder;
+  llvm::DICompileUnit *compileUnit = nullptr;
+};
+
+std::unique_ptr<llvm::Module> translateModule(ModuleOp mod,
+                                              LLVMDWARFEmissionKind debugInfo) {
+  using mlir::LLVM::ModuleTranslation;
+
+  switch (debugInfo) {
+    case LLVMDWARFEmissionKindFull:
+      return ModuleTranslation::translateModule<
+          DebugInfoTranslation<DebugEmissionKind::FullDebug>>(mod);
+    case LLVMDWARFEmissionKindLineTablesOnly:
+      return ModuleTranslation::translateModule<
+          DebugInfoTranslation<DebugEmissionKind::LineTablesOnly>>(mod);
+    default:
+      return mlir::translateModuleToLLVMIR(mod);
+  }
+}
+
+}  // namespace
+
 extern "C" LLVMModuleRef MLIRLowerToLLVMIR(MLIRModuleRef m,
                                            const char *sourceName, OptLevel opt,
                                            SizeLevel size,
+                                           LLVMDWARFEmissionKind debugInfo,
                                            LLVMTargetMachineRef tm) {
   ModuleOp *mod = unwrap(m);
   TargetMachine *targetMachine = unwrap(tm);
@@ -67,7 +212,7 @@ extern "C" LLVMModuleRef MLIRLowerToLLVMIR(MLIRModuleRef m,
   auto modName = mod->getName();
 
   OwningModuleRef ownedMod(*mod);
-  auto llvmModPtr = mlir::translateModuleToLLVMIR(*ownedMod);
+  auto llvmModPtr = translateModule(*ownedMod, debugInfo);
   if (!llvmModPtr) {
     llvm::errs() << "Failed to emit LLVM IR!\n";
     return nullptr;
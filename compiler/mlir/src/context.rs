@@ -8,6 +8,7 @@ use anyhow::anyhow;
 
 use liblumen_llvm as llvm;
 use liblumen_llvm::enums::{CodeGenOptLevel, CodeGenOptSize};
+use liblumen_llvm::sys::debuginfo::LLVMDWARFEmissionKind;
 use liblumen_llvm::target::{TargetMachine, TargetMachineRef};
 use liblumen_llvm::utils::{MemoryBuffer, MemoryBufferRef};
 use liblumen_session::{Options, OutputType};
@@ -33,6 +34,7 @@ pub struct Context {
     target_machine: TargetMachineRef,
     opt: CodeGenOptLevel,
     size: CodeGenOptSize,
+    debug_info: LLVMDWARFEmissionKind,
 }
 unsafe impl Send for Context {}
 unsafe impl Sync for Context {}
@@ -40,6 +42,7 @@ impl Context {
     pub fn new(thread_id: ThreadId, options: &Options, target_machine: &TargetMachine) -> Self {
         let target_machine = target_machine.as_ref();
         let (opt, size) = llvm::enums::to_llvm_opt_settings(options.opt_level);
+        let debug_info = llvm::enums::to_llvm_debug_emission_kind(options.debug_info);
         let context = unsafe { MLIRCreateContext() };
         let enable_timing = options.debugging_opts.time_passes;
         let enable_statistics = options.debugging_opts.perf_stats;
@@ -59,6 +62,7 @@ impl Context {
             target_machine,
             opt,
             size,
+            debug_info,
         }
     }
 
@@ -70,6 +74,10 @@ impl Context {
         self.size
     }
 
+    pub fn debug_info(&self) -> LLVMDWARFEmissionKind {
+        self.debug_info
+    }
+
     pub fn pass_manager_ref(&self) -> PassManagerRef {
         self.pass_manager
     }
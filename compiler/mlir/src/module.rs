@@ -9,6 +9,7 @@ use anyhow::anyhow;
 
 use liblumen_llvm as llvm;
 use liblumen_llvm::enums::{CodeGenOptLevel, CodeGenOptSize};
+use liblumen_llvm::sys::debuginfo::LLVMDWARFEmissionKind;
 use liblumen_llvm::target::TargetMachineRef;
 use liblumen_llvm::utils::{LLVMString, MemoryBufferRef};
 use liblumen_session::{Emit, OutputType};
@@ -68,12 +69,31 @@ impl Module {
     ) -> anyhow::Result<llvm::module::Module> {
         let opt = context.opt_level();
         let size = context.opt_size();
+        let debug_info = context.debug_info();
         let target_machine = context.target_machine_ref();
         let result = if let Some(sn) = source_name {
             let f = CString::new(sn)?;
-            unsafe { MLIRLowerToLLVMIR(self.as_ref(), f.as_ptr(), opt, size, target_machine) }
+            unsafe {
+                MLIRLowerToLLVMIR(
+                    self.as_ref(),
+                    f.as_ptr(),
+                    opt,
+                    size,
+                    debug_info,
+                    target_machine,
+                )
+            }
         } else {
-            unsafe { MLIRLowerToLLVMIR(self.as_ref(), ptr::null(), opt, size, target_machine) }
+            unsafe {
+                MLIRLowerToLLVMIR(
+                    self.as_ref(),
+                    ptr::null(),
+                    opt,
+                    size,
+                    debug_info,
+                    target_machine,
+                )
+            }
         };
         if result.is_null() {
             Err(anyhow!("lowering to llvm failed"))
@@ -148,6 +168,7 @@ extern "C" {
         source_name: *const libc::c_char,
         opt: CodeGenOptLevel,
         size: CodeGenOptSize,
+        debug_info: LLVMDWARFEmissionKind,
         target_machine: TargetMachineRef,
     ) -> *mut llvm::module::ModuleImpl;
 
@@ -9,6 +9,7 @@ use libeir_diagnostics::FileName;
 pub enum InputType {
     Erlang,
     AbstractErlang,
+    BEAM,
     EIR,
     MLIR,
     Unknown(Option<String>),
@@ -17,10 +18,15 @@ impl InputType {
     const TYPES: &'static [InputType] = &[
         InputType::Erlang,
         InputType::AbstractErlang,
+        InputType::BEAM,
         InputType::EIR,
         InputType::MLIR,
     ];
 
+    /// Whether `path` is a source file to compile when it is found in a source directory.
+    ///
+    /// BEAM files are only compiled when given as inputs themselves, as directories such as
+    /// `ebin` or `_build` hold the BEAM files compiled from the sources next to them.
     pub fn is_valid(path: &Path) -> bool {
         if !path.exists() || !path.is_file() {
             return false;
@@ -48,6 +54,7 @@ impl fmt::Display for InputType {
         match self {
             Self::Erlang => f.write_str("erl"),
             Self::AbstractErlang => f.write_str("abstr"),
+            Self::BEAM => f.write_str("beam"),
             Self::EIR => f.write_str("eir"),
             Self::MLIR => f.write_str("mlir"),
             Self::Unknown(None) => f.write_str("unknown (no extension)"),
@@ -82,6 +89,7 @@ impl Input {
             Input::File(ref file) => match file.extension().and_then(|ext| ext.to_str()) {
                 Some("erl") => InputType::Erlang,
                 Some("abstr") => InputType::AbstractErlang,
+                Some("beam") => InputType::BEAM,
                 Some("eir") => InputType::EIR,
                 Some("mlir") => InputType::MLIR,
                 Some(t) => InputType::Unknown(Some(t.to_string())),
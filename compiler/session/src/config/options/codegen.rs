@@ -126,4 +126,7 @@ pub struct CodegenOptions {
     #[option]
     /// When set, does not implicitly link the Lumen runtime
     pub no_std: Option<bool>,
+    #[option]
+    /// Compile every module, instead of reusing unchanged ones from the incremental cache
+    pub no_incremental: bool,
 }
@@ -61,6 +61,12 @@ pub fn call_run_erlang(
                         "WAITING Run queues len = {:?}",
                         Scheduler::current().run_queues_len()
                     ));
+                } else if Scheduler::current().skip_to_next_timeout() {
+                    // The virtual clock was moved to the timer being waited on
+                    continue;
+                } else if !Scheduler::current().hierarchy.read().is_empty() {
+                    // Waiting for a timer, such as the one of a `receive ... after`
+                    continue;
                 } else {
                     panic!(
                         "{:?} did not run.  Deadlock likely in {:#?}",
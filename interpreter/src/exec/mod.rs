@@ -23,6 +23,7 @@ use crate::module::{ErlangFunction, NativeFunctionKind, ResolvedFunction};
 use crate::vm::VMState;
 
 mod r#match;
+mod receive;
 
 macro_rules! trace {
     ($($t:tt)*) => (lumen_rt_full::system::io::puts(&format_args!($($t)*).to_string()))
@@ -502,6 +503,43 @@ impl CallExecutor {
         }
     }
 
+    /// Raises `exception` from `block` to the throw continuation of `fun`, as if a call in
+    /// `block` raised it.  If that continuation is no longer live, `proc` exits with `exception`
+    /// instead.
+    fn raise(
+        &mut self,
+        proc: &Arc<Process>,
+        fun: &ErlangFunction,
+        block: Block,
+        exception: RuntimeException,
+    ) -> Result<OpResult, SystemException> {
+        let throw_cont_value = fun.fun.block_args(fun.fun.block_entry())[1];
+
+        match self.binds.get(&throw_cont_value).copied() {
+            Some(throw_cont) => {
+                let kind = match exception {
+                    RuntimeException::Throw(_) => atom!("throw"),
+                    RuntimeException::Exit(_) => atom!("EXIT"),
+                    RuntimeException::Error(_) => atom!("error"),
+                };
+
+                self.next_args.extend_from_slice(&[
+                    kind,
+                    exception.reason().unwrap(),
+                    atom!("trace"),
+                ]);
+
+                Ok(OpResult::Term(throw_cont))
+            }
+            None => {
+                let curr_cont = self.make_closure(proc, fun, block)?;
+                proc.exception(exception);
+
+                Ok(OpResult::TermYield(curr_cont))
+            }
+        }
+    }
+
     fn val_call(
         &mut self,
         proc: &Arc<Process>,
@@ -638,8 +676,14 @@ impl CallExecutor {
                 assert!(reads.len() == 2);
 
                 let timeout = self.make_term(proc, fun, reads[1])?;
-                // Only infinity supported
-                assert!(timeout == Atom::str_to_term("infinity"));
+
+                match receive::start(proc, timeout) {
+                    Ok(()) => (),
+                    Err(Exception::Runtime(exception)) => {
+                        return self.raise(proc, fun, block, exception)
+                    }
+                    Err(Exception::System(exception)) => return Err(exception),
+                }
 
                 proc.mailbox.lock().borrow_mut().recv_start();
 
@@ -653,20 +697,38 @@ impl CallExecutor {
 
                 let mailbox_lock = proc.mailbox.lock();
                 let mut mailbox = mailbox_lock.borrow_mut();
-                if let Some(msg_term) = mailbox.recv_peek() {
-                    mailbox.recv_increment();
+                loop {
+                    match mailbox.recv_peek() {
+                        // The message of the timer is never matched
+                        Some(msg_term) if receive::is_timeout_message(proc, msg_term) => {
+                            mailbox.recv_increment();
+                        }
+                        Some(msg_term) => {
+                            mailbox.recv_increment();
 
-                    std::mem::drop(mailbox);
-                    std::mem::drop(mailbox_lock);
+                            std::mem::drop(mailbox);
+                            std::mem::drop(mailbox_lock);
 
-                    self.next_args.push(msg_term);
-                    self.val_call(proc, fun, reads[1])
-                } else {
-                    // If there are no messages, schedule a call
-                    // to the current block for later.
-                    self.next_args.push(Term::NIL);
-                    proc.wait();
-                    Ok(OpResult::TermYield(curr_cont))
+                            self.next_args.push(msg_term);
+                            break self.val_call(proc, fun, reads[1]);
+                        }
+                        None if receive::is_timed_out(proc) => {
+                            receive::finish(proc, &mut mailbox);
+                            mailbox.recv_timeout();
+
+                            std::mem::drop(mailbox);
+                            std::mem::drop(mailbox_lock);
+
+                            break self.val_call(proc, fun, reads[0]);
+                        }
+                        None => {
+                            // If there are no messages, schedule a call
+                            // to the current block for later.
+                            self.next_args.push(Term::NIL);
+                            proc.wait();
+                            break Ok(OpResult::TermYield(curr_cont));
+                        }
+                    }
                 }
             }
             OpKind::Intrinsic(name) if *name == Symbol::intern("receive_done") => {
@@ -686,6 +748,7 @@ impl CallExecutor {
                 }
 
                 mailbox.recv_finish(proc);
+                receive::finish(proc, &mut mailbox);
 
                 self.val_call(proc, fun, reads[0])
             }
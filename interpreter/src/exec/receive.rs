@@ -0,0 +1,180 @@
+//! The timeout of a `receive ... after`, which lasts from `receive_start` until the receive
+//! matches a message in `receive_done` or times out in `receive_wait`.
+//!
+//! A process is in at most one receive at a time, so the timer is kept on the process instead of
+//! being passed through the blocks of the receive.
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::{self, Exception};
+use liblumen_alloc::erts::process::{Mailbox, Process};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::timeout::Timeout;
+use liblumen_alloc::{atom, error};
+
+use lumen_rt_core::time::{monotonic, Milliseconds};
+
+use lumen_rt_full::scheduler::{Scheduled, Scheduler};
+use lumen_rt_full::timer::{self, Destination};
+
+/// Starts the timeout of the receive `process` is entering.
+///
+/// The timer sends `{timeout, TimerReference, receive}` to `process`, so that it stops waiting
+/// when the timeout passes.  `receive_wait` skips that message, and `finish` removes it.
+///
+/// Like ERTS, a `timeout` that isn't `infinity` or a non-negative integer is
+/// `error:timeout_value`.
+pub fn start(process: &Arc<Process>, timeout: Term) -> exception::Result<()> {
+    let option_timeout = match timeout.decode() {
+        Ok(TypedTerm::Atom(atom)) if atom == "infinity" => Some(Timeout::Infinity),
+        Ok(TypedTerm::SmallInteger(small)) => Timeout::from_millis(small).ok(),
+        _ => None,
+    };
+
+    let timeout = match option_timeout {
+        Some(timeout) => timeout,
+        None => {
+            return Err(Exception::Runtime(error!(
+                atom!("timeout_value"),
+                anyhow!(
+                    "timeout ({}) is not infinity or a non-negative integer",
+                    timeout
+                )
+                .into()
+            )))
+        }
+    };
+
+    let option_receive_timer = match timeout {
+        Timeout::Infinity => None,
+        Timeout::Immediate => Some(ReceiveTimer::Immediate),
+        Timeout::Duration(duration) => {
+            let monotonic_time_milliseconds =
+                monotonic::time_in_milliseconds() + duration.as_millis() as Milliseconds;
+            let timer_reference = timer::start(
+                monotonic_time_milliseconds,
+                Destination::Process(Arc::downgrade(process)),
+                timer::Timeout::TimeoutTuple,
+                atom!("receive"),
+                process,
+            )?;
+            let timer_reference: Boxed<Reference> = timer_reference.try_into().unwrap();
+
+            Some(ReceiveTimer::Started {
+                scheduler: timer_reference.scheduler().unwrap(),
+                reference_number: timer_reference.number(),
+            })
+        }
+    };
+
+    *process.receive_timer.lock() = match option_receive_timer {
+        Some(receive_timer) => Some(Resource::new(Box::new(receive_timer))?),
+        None => None,
+    };
+
+    Ok(())
+}
+
+/// Whether the receive `process` is in has timed out.
+///
+/// `after 0` times out as soon as no message in the mailbox matches.
+pub fn is_timed_out(process: &Process) -> bool {
+    with_receive_timer(process, |receive_timer| match receive_timer {
+        ReceiveTimer::Immediate => true,
+        ReceiveTimer::Started {
+            scheduler,
+            reference_number,
+        } => scheduler.hierarchy.read().read(*reference_number).is_none(),
+    })
+    .unwrap_or(false)
+}
+
+/// Whether `message` was sent by the timer of the receive `process` is in, and so must not be
+/// matched against the patterns of the receive.
+pub fn is_timeout_message(process: &Process, message: Term) -> bool {
+    with_receive_timer(process, |receive_timer| {
+        receive_timer.is_timeout_message(message)
+    })
+    .unwrap_or(false)
+}
+
+/// Cancels the timer of the receive `process` is leaving, removing its message from `mailbox` if
+/// it was already sent.
+pub fn finish(process: &Process, mailbox: &mut Mailbox) {
+    let option_resource = process.receive_timer.lock().take();
+
+    if let Some(receive_timer) = option_resource
+        .as_ref()
+        .and_then(|resource| resource.downcast_ref::<ReceiveTimer>())
+    {
+        if let ReceiveTimer::Started {
+            scheduler,
+            reference_number,
+        } = receive_timer
+        {
+            let cancelled = scheduler
+                .hierarchy
+                .write()
+                .cancel(*reference_number)
+                .is_some();
+
+            if !cancelled {
+                mailbox.flush(
+                    |message| receive_timer.is_timeout_message(*message.data()),
+                    process,
+                );
+            }
+        }
+    }
+}
+
+// Private
+
+fn with_receive_timer<F, R>(process: &Process, f: F) -> Option<R>
+where
+    F: FnOnce(&ReceiveTimer) -> R,
+{
+    process
+        .receive_timer
+        .lock()
+        .as_ref()
+        .and_then(|resource| resource.downcast_ref::<ReceiveTimer>())
+        .map(f)
+}
+
+enum ReceiveTimer {
+    /// `after 0` needs no timer
+    Immediate,
+    Started {
+        scheduler: Arc<Scheduler>,
+        reference_number: ReferenceNumber,
+    },
+}
+
+impl ReceiveTimer {
+    fn is_timeout_message(&self, message: Term) -> bool {
+        match self {
+            ReceiveTimer::Immediate => false,
+            ReceiveTimer::Started {
+                scheduler,
+                reference_number,
+            } => match message.decode() {
+                Ok(TypedTerm::Tuple(tuple)) => {
+                    tuple.len() == 3
+                        && tuple[0] == atom!("timeout")
+                        && match tuple[1].decode() {
+                            Ok(TypedTerm::Reference(reference)) => {
+                                reference.scheduler_id() == scheduler.id
+                                    && reference.number() == *reference_number
+                            }
+                            _ => false,
+                        }
+                }
+                _ => false,
+            },
+        }
+    }
+}
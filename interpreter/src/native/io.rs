@@ -0,0 +1,29 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use liblumen_otp::{io, io_lib};
+
+use crate::module::NativeModule;
+
+pub fn make_io() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("io").unwrap());
+
+    native.add_simple(Atom::try_from_str("format").unwrap(), 1, |_proc, args| {
+        io::format_1::native(args[0])
+    });
+
+    native.add_simple(Atom::try_from_str("format").unwrap(), 2, |_proc, args| {
+        io::format_2::native(args[0], args[1])
+    });
+
+    native
+}
+
+pub fn make_io_lib() -> NativeModule {
+    let mut native = NativeModule::new(Atom::try_from_str("io_lib").unwrap());
+
+    native.add_simple(Atom::try_from_str("format").unwrap(), 2, |proc, args| {
+        io_lib::format_2::native(proc, args[0], args[1])
+    });
+
+    native
+}
@@ -1,6 +1,9 @@
 mod erlang;
 pub use erlang::make_erlang;
 
+mod io;
+pub use io::{make_io, make_io_lib};
+
 mod lists;
 pub use lists::make_lists;
 
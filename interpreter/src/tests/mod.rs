@@ -12,6 +12,8 @@ use libeir_util_parse::{ArcCodemap, Errors};
 
 use liblumen_alloc::erts::term::prelude::*;
 
+use lumen_rt_core::time::monotonic;
+
 use lumen_rt_full::scheduler::Scheduler;
 
 fn parse<T>(input: &str, config: ParseConfig) -> (T, ArcCodemap)
@@ -222,6 +224,110 @@ run() ->
     assert!(res.result == Ok(Atom::str_to_term("d")));
 }
 
+#[test]
+fn receive_after() {
+    &*VM;
+    // The timeout is taken as soon as the process waits, instead of after 10ms of real time
+    monotonic::start_virtual_clock();
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    liblumen_otp::erlang::apply_3::export();
+
+    let module = Atom::try_from_str("receive_after").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(receive_after).
+
+run() ->
+    receive
+        never -> never
+    after 10 ->
+        timeout
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(Atom::str_to_term("timeout")));
+}
+
+#[test]
+fn receive_after_0() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    liblumen_otp::erlang::apply_3::export();
+
+    let module = Atom::try_from_str("receive_after_0").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(receive_after_0).
+
+run() ->
+    self() ! a,
+    got_a = receive a -> got_a after 0 -> timeout end,
+    receive a -> got_a after 0 -> timeout end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result == Ok(Atom::str_to_term("timeout")));
+}
+
+#[test]
+fn receive_after_invalid_timeout_is_timeout_value_error() {
+    &*VM;
+
+    let arc_scheduler = Scheduler::current();
+    let init_arc_process = arc_scheduler.spawn_init(0).unwrap();
+
+    liblumen_otp::erlang::apply_3::export();
+
+    let module = Atom::try_from_str("receive_after_invalid_timeout").unwrap();
+    let function = Atom::try_from_str("run").unwrap();
+
+    let eir_mod = compile(
+        "
+-module(receive_after_invalid_timeout).
+
+run() ->
+    caught = try wait(-1) of _ -> returned catch error:timeout_value -> caught end,
+    wait(not_a_timeout).
+
+wait(Timeout) ->
+    receive
+        never -> never
+    after Timeout ->
+        timeout
+    end.
+",
+    );
+
+    VM.modules.write().unwrap().register_erlang_module(eir_mod);
+
+    let res = crate::call_result::call_run_erlang(init_arc_process.clone(), module, function, &[]);
+
+    assert!(res.result.is_err());
+    if let Err((typ, reason, _trace)) = res.result {
+        assert!(typ == Atom::str_to_term("error"));
+        assert!(reason == Atom::str_to_term("timeout_value"));
+    }
+}
+
 #[test]
 #[ignore]
 fn ping_pong_count() {
@@ -1,6 +1,7 @@
 pub mod apply;
 pub mod exception;
 pub mod fragment;
+pub mod literal;
 pub mod message;
 mod module_function_arity;
 pub mod node;
@@ -1,13 +1,16 @@
 use core::ffi::c_void;
 use core::mem;
 use core::slice;
+use core::str;
+use std::ffi::CStr;
+use std::os::raw::c_char;
 
 use hashbrown::HashMap;
 
 use once_cell::sync::OnceCell;
 
 use liblumen_arena::DroplessArena;
-use liblumen_core::symbols::FunctionSymbol;
+use liblumen_core::symbols::{FunctionLocation, FunctionSymbol};
 #[cfg(all(unix, target_arch = "x86_64"))]
 use liblumen_core::sys::dynamic_call;
 use liblumen_core::sys::dynamic_call::DynamicCallee;
@@ -57,6 +60,22 @@ pub fn find_symbol(mfa: &ModuleFunctionArity) -> Option<DynamicCallee> {
     }
 }
 
+/// Returns the function whose code starts at `function`, if it is in the symbol table.
+///
+/// Returns `None` if the symbol table has not been initialized.
+pub fn find_ident(function: *const c_void) -> Option<&'static ModuleFunctionArity> {
+    SYMBOLS
+        .get()
+        .and_then(|symbols| symbols.get_ident(function))
+}
+
+/// Returns the source file and line where the function is defined, if the compiler recorded them.
+pub fn find_location(mfa: &ModuleFunctionArity) -> Option<(&'static str, u32)> {
+    LOCATIONS
+        .get()
+        .and_then(|locations| locations.get(mfa).copied())
+}
+
 pub fn dump_symbols() {
     let symbols = unsafe { SYMBOLS.get_unchecked() };
     symbols.dump();
@@ -65,6 +84,9 @@ pub fn dump_symbols() {
 /// The symbol table used by the runtime system
 static SYMBOLS: OnceCell<SymbolTable> = OnceCell::new();
 
+/// The source locations of the functions in the symbol table
+static LOCATIONS: OnceCell<HashMap<ModuleFunctionArity, (&'static str, u32)>> = OnceCell::new();
+
 /// Performs one-time initialization of the atom table at program start, using the
 /// array of constant atom values present in the compiled program.
 ///
@@ -97,6 +119,54 @@ pub unsafe extern "C" fn InitializeLumenDispatchTable(
     }
 }
 
+/// Performs one-time initialization of the location table at program start, using the
+/// array of function locations present in the compiled program.
+///
+/// It is expected that this will be called by code generated by the compiler, during the
+/// earliest phase of startup, after the atom table has been initialized.
+#[no_mangle]
+pub unsafe extern "C" fn InitializeLumenLocationTable(
+    table: *const FunctionLocation,
+    len: usize,
+) -> bool {
+    if len == 0 {
+        return true;
+    }
+    if table.is_null() {
+        return false;
+    }
+    let raw_table = slice::from_raw_parts::<'static>(table, len);
+    let mut locations = HashMap::with_capacity(len);
+
+    for FunctionLocation {
+        module,
+        function,
+        arity,
+        file,
+        line,
+    } in raw_table.iter()
+    {
+        let mfa = ModuleFunctionArity {
+            module: Atom::from_id(*module),
+            function: Atom::from_id(*function),
+            arity: *arity,
+        };
+        // This is safe because the file names are static, null-terminated strings
+        let file = CStr::from_ptr(*file as *const c_char).to_bytes();
+        let file = match str::from_utf8(file) {
+            Ok(file) => file,
+            Err(_) => return false,
+        };
+        locations.insert(mfa, (file, *line));
+    }
+
+    if let Err(_) = LOCATIONS.set(locations) {
+        panic!("tried to initialize location table more than once!");
+    } else {
+        true
+    }
+}
+
 struct SymbolTable {
     functions: HashMap<&'static ModuleFunctionArity, *const c_void>,
     idents: HashMap<*const c_void, &'static ModuleFunctionArity>,
@@ -153,7 +223,6 @@ impl SymbolTable {
         Ok(table)
     }
 
-    #[allow(unused)]
     fn get_ident(&self, function: *const c_void) -> Option<&'static ModuleFunctionArity> {
         self.idents.get(&function).copied()
     }
@@ -9,7 +9,7 @@ use liblumen_core::alloc::Layout;
 
 use crate::erts;
 use crate::erts::exception::AllocResult;
-use crate::erts::process::alloc::{Heap, HeapAlloc};
+use crate::erts::process::alloc::{Heap, HeapAlloc, HeapIter};
 use crate::erts::term::prelude::*;
 use crate::std_alloc;
 
@@ -87,20 +87,46 @@ impl HeapFragment {
 
         Self::new(layout)
     }
+
+    /// Releases every reference-counted term in the fragment, such as each `ProcBin`, however
+    /// deeply it is nested, and then frees the fragment.
+    ///
+    /// Dropping a fragment only releases the term at the start of it.
+    ///
+    /// # Safety
+    ///
+    /// Nothing may refer to a term in the fragment after it is freed.
+    pub unsafe fn release_and_free(fragment: NonNull<Self>) {
+        for term in (*fragment.as_ptr()).iter_mut() {
+            let ptr = term as *mut Term;
+
+            if term.is_procbin() {
+                ptr::drop_in_place(ptr as *mut ProcBin);
+            } else if term.is_resource_reference() {
+                ptr::drop_in_place(ptr as *mut Resource);
+            }
+        }
+
+        // Not dropped, as that would release the term at the start of the fragment again
+        (*fragment.as_ptr()).dealloc();
+    }
+
+    /// Deallocates the memory backing this fragment, including the fragment itself
+    unsafe fn dealloc(&mut self) {
+        assert!(!self.link.is_linked());
+        let (layout, _offset) = Layout::new::<Self>().extend(self.raw.layout()).unwrap();
+        let ptr = NonNull::new_unchecked(self as *const _ as *mut u8);
+        std_alloc::dealloc(ptr, layout);
+    }
 }
 impl Drop for HeapFragment {
     fn drop(&mut self) {
-        assert!(!self.link.is_linked());
         // Check if the contained value needs to have its destructor run
         let ptr = self.raw.base as *mut Term;
         let term = unsafe { *ptr };
         term.release();
         // Actually deallocate the memory backing this fragment
-        let (layout, _offset) = Layout::new::<Self>().extend(self.raw.layout()).unwrap();
-        unsafe {
-            let ptr = NonNull::new_unchecked(self as *const _ as *mut u8);
-            std_alloc::dealloc(ptr, layout);
-        }
+        unsafe { self.dealloc() }
     }
 }
 impl Heap for HeapFragment {
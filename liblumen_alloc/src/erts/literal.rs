@@ -0,0 +1,154 @@
+//! Areas of memory holding terms that are shared by every process, such as persistent terms.
+//!
+//! Boxes pointing into these areas can be encoded as literals, but list pointers and boxed
+//! `ProcBin`s have no literal encoding, so garbage collection checks whether they point into a
+//! registered area before moving what they point to, as moving it would write a move marker into
+//! memory that other processes are still reading.
+//!
+//! Garbage collection checks every such pointer, so `contains` takes no lock: the areas are kept
+//! sorted in a snapshot that is replaced, never changed, when an area is registered or
+//! unregistered, and a replaced snapshot is only freed once no `contains` can still be reading
+//! it.
+use core::ptr;
+use core::sync::atomic::{AtomicPtr, AtomicUsize, Ordering};
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::Mutex;
+
+use crate::erts::process::alloc::{Heap, HeapIter};
+use crate::erts::term::prelude::*;
+
+/// The `(start, end)` of every area, sorted by `start`
+type Areas = Vec<(usize, usize)>;
+
+static AREAS: AtomicPtr<Areas> = AtomicPtr::new(ptr::null_mut());
+// The number of `contains` reading a snapshot of `AREAS`, so that replaced snapshots are only
+// freed when there are none
+static READERS: AtomicUsize = AtomicUsize::new(0);
+
+// The bounds of all areas, so that pointers outside of every area are rejected straight away
+static LOW: AtomicUsize = AtomicUsize::new(usize::max_value());
+static HIGH: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    // Held while the areas are replaced, along with the replaced snapshots that may still be read
+    static ref REPLACED: Mutex<Vec<Box<Areas>>> = Default::default();
+}
+
+/// Re-encodes every box in the used part of `heap` as a literal, however deeply it is nested,
+/// then registers it as a literal area, so that none of its terms are ever moved or copied by the
+/// processes reading them.
+///
+/// # Safety
+///
+/// The heap must not be freed, moved or allocated in again until it is `unregister`ed, as any
+/// number of processes can share the terms in it.
+pub unsafe fn register<H: Heap>(heap: &mut H) {
+    for term in heap.iter_mut() {
+        if term.is_boxed() {
+            *term = term.into_literal();
+        }
+    }
+
+    let start = heap.heap_start() as usize;
+    let end = heap.heap_top() as usize;
+
+    if start == end {
+        return;
+    }
+
+    replace(|areas| {
+        let index = areas
+            .binary_search_by_key(&start, |&(area_start, _)| area_start)
+            .unwrap_or_else(|index| index);
+        areas.insert(index, (start, end));
+    });
+}
+
+/// Removes `heap` from the literal areas, so that it can be freed.
+///
+/// # Safety
+///
+/// No process, message or table may still refer to a term in the heap, as garbage collection
+/// would move them out of it once it is no longer an area.
+pub unsafe fn unregister<H: Heap>(heap: &H) {
+    let start = heap.heap_start() as usize;
+
+    replace(|areas| {
+        if let Ok(index) = areas.binary_search_by_key(&start, |&(area_start, _)| area_start) {
+            areas.remove(index);
+        }
+    });
+}
+
+/// Returns `true` if `ptr` points into a literal area.
+#[inline]
+pub fn contains<T: ?Sized>(ptr: *const T) -> bool {
+    let address = ptr as *const () as usize;
+
+    if address < LOW.load(Ordering::Relaxed) || HIGH.load(Ordering::Relaxed) <= address {
+        return false;
+    }
+
+    READERS.fetch_add(1, Ordering::SeqCst);
+
+    let areas_ptr = AREAS.load(Ordering::SeqCst);
+    let contained = !areas_ptr.is_null() && {
+        let areas = unsafe { &*areas_ptr };
+
+        // The last area starting at or before `address` is the only one that can contain it
+        match areas.binary_search_by_key(&address, |&(start, _)| start) {
+            Ok(_) => true,
+            Err(0) => false,
+            Err(index) => address < areas[index - 1].1,
+        }
+    };
+
+    READERS.fetch_sub(1, Ordering::SeqCst);
+
+    contained
+}
+
+// Private
+
+/// Publishes a copy of the areas changed by `change`, then frees the replaced snapshots if no
+/// `contains` is reading any of them.
+fn replace<F: FnOnce(&mut Areas)>(change: F) {
+    let mut replaced = REPLACED.lock();
+
+    let current_ptr = AREAS.load(Ordering::SeqCst);
+    let mut areas = if current_ptr.is_null() {
+        Areas::new()
+    } else {
+        unsafe { (*current_ptr).clone() }
+    };
+    change(&mut areas);
+
+    let (low, high) = match (areas.first(), areas.last()) {
+        (Some(&(low, _)), Some(&(_, high))) => (low, high),
+        _ => (usize::max_value(), 0),
+    };
+    // Widen the bounds before publishing, so that no pointer into a new area is rejected before
+    // it is looked up, and only then set them to those of the areas.
+    LOW.fetch_min(low, Ordering::SeqCst);
+    HIGH.fetch_max(high, Ordering::SeqCst);
+
+    let replaced_ptr = AREAS.swap(Box::into_raw(Box::new(areas)), Ordering::SeqCst);
+
+    LOW.store(low, Ordering::SeqCst);
+    HIGH.store(high, Ordering::SeqCst);
+
+    if !replaced_ptr.is_null() {
+        replaced.push(unsafe { Box::from_raw(replaced_ptr) });
+    }
+
+    // A `contains` that loaded a replaced snapshot has been counted in `READERS` since before the
+    // swap above, so once there are none, none of them can still be read.
+    if READERS.load(Ordering::SeqCst) == 0 {
+        replaced.clear();
+    }
+}
@@ -33,6 +33,10 @@ impl Node {
     pub fn name(&self) -> Atom {
         self.name.lock().get()
     }
+
+    pub fn set_name(&self, name: Atom) {
+        self.name.lock().set(name)
+    }
 }
 
 impl Eq for Node {}
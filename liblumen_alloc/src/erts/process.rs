@@ -5,6 +5,8 @@ mod flags;
 pub mod gc;
 mod heap;
 mod mailbox;
+mod max_heap_size;
+mod message_queue_data;
 mod monitor;
 pub mod priority;
 
@@ -48,6 +50,8 @@ use self::gc::{GcError, RootSet};
 pub use self::flags::*;
 pub use self::heap::ProcessHeap;
 pub use self::mailbox::*;
+pub use self::max_heap_size::MaxHeapSize;
+pub use self::message_queue_data::MessageQueueData;
 pub use self::monitor::Monitor;
 pub use self::priority::Priority;
 
@@ -89,19 +93,26 @@ pub struct Process {
     /// ID of the scheduler that is running the process
     scheduler_id: Mutex<Option<scheduler::ID>>,
     /// The priority of the process in `scheduler`.
-    pub priority: Priority,
+    priority: Mutex<Priority>,
     /// Process flags, e.g. `Process.flag/1`
     flags: AtomicProcessFlags,
     /// Minimum size of the heap that this process will start with
-    min_heap_size: usize,
+    min_heap_size: AtomicUsize,
     /// The maximum size of the heap allowed for this process
-    max_heap_size: usize,
+    max_heap_size: Mutex<MaxHeapSize>,
     /// Minimum virtual heap size for this process
-    min_vheap_size: usize,
+    min_vheap_size: AtomicUsize,
     /// The percentage of used to unused space at which a collection is triggered
     gc_threshold: f64,
     /// The maximum number of minor collections before a full sweep occurs
-    max_gen_gcs: usize,
+    max_gen_gcs: AtomicUsize,
+    /// Where messages are stored, set with `process_flag(message_queue_data, _)`
+    message_queue_data: Mutex<MessageQueueData>,
+    /// The module called when an undefined function is called, set with
+    /// `process_flag(error_handler, _)`
+    error_handler: Mutex<Atom>,
+    /// The number of calls to save, set with `process_flag(save_calls, _)`
+    save_calls: AtomicUsize,
     /// off-heap allocations
     off_heap: SpinLock<LinkedList<HeapFragmentAdapter>>,
     off_heap_size: AtomicUsize,
@@ -120,6 +131,12 @@ pub struct Process {
     code_stack: Mutex<code::stack::Stack>,
     pub status: RwLock<Status>,
     pub registered_name: RwLock<Option<Atom>>,
+    /// The stacktrace of the last exception raised by compiled code, for `erlang:get_stacktrace/0`,
+    /// kept as the handle captured by the runtime, so that it isn't on the heap
+    pub stacktrace: Mutex<Option<Resource>>,
+    /// The timer of the `receive ... after` the interpreter is running in the process, kept as a
+    /// handle so that it is freed with the process if it exits in the middle of the receive
+    pub receive_timer: Mutex<Option<Resource>>,
     /// Pids of processes that are linked to this process and need to be exited when this process
     /// exits
     pub linked_pid_set: DashSet<Pid>,
@@ -159,11 +176,14 @@ impl Process {
 
         Self {
             flags: AtomicProcessFlags::new(ProcessFlags::Default),
-            min_heap_size: heap_size,
-            max_heap_size: 0,
-            min_vheap_size: 0,
+            min_heap_size: AtomicUsize::new(heap_size),
+            max_heap_size: Default::default(),
+            min_vheap_size: AtomicUsize::new(0),
             gc_threshold: 0.75,
-            max_gen_gcs: 65535,
+            max_gen_gcs: AtomicUsize::new(65535),
+            message_queue_data: Default::default(),
+            error_handler: Mutex::new(Atom::from_str("error_handler")),
+            save_calls: AtomicUsize::new(0),
             off_heap,
             off_heap_size: AtomicUsize::new(0),
             dictionary: Default::default(),
@@ -175,13 +195,15 @@ impl Process {
             registers: Default::default(),
             code_stack: Default::default(),
             scheduler_id: Mutex::new(None),
-            priority,
+            priority: Mutex::new(priority),
             parent_pid,
             group_leader_pid: Mutex::new(group_leader_pid),
             initial_module_function_arity,
             run_reductions: Default::default(),
             total_reductions: Default::default(),
             registered_name: Default::default(),
+            stacktrace: Default::default(),
+            receive_timer: Default::default(),
             linked_pid_set: Default::default(),
             monitor_by_reference: Default::default(),
             monitored_pid_by_reference: Default::default(),
@@ -216,6 +238,18 @@ impl Process {
         *self.scheduler_id.lock() = Some(scheduler_id);
     }
 
+    pub fn priority(&self) -> Priority {
+        *self.priority.lock()
+    }
+
+    /// Sets the priority, returning the old priority.
+    ///
+    /// The process is enqueued in the run queue for the new priority the next time its scheduler
+    /// enqueues it.
+    pub fn set_priority(&self, priority: Priority) -> Priority {
+        mem::replace(&mut *self.priority.lock(), priority)
+    }
+
     // Flags
 
     pub fn are_flags_set(&self, flags: ProcessFlags) -> bool {
@@ -250,6 +284,98 @@ impl Process {
         self.are_flags_set(ProcessFlags::TrapExit)
     }
 
+    pub fn sensitive(&self, value: bool) -> bool {
+        let flag = ProcessFlags::Sensitive;
+
+        let old_flags = if value {
+            self.set_flags(flag)
+        } else {
+            self.clear_flags(flag)
+        };
+
+        old_flags.are_set(flag)
+    }
+
+    pub fn is_sensitive(&self) -> bool {
+        self.are_flags_set(ProcessFlags::Sensitive)
+    }
+
+    pub fn error_handler(&self) -> Atom {
+        *self.error_handler.lock()
+    }
+
+    /// Sets the module called when an undefined function is called, returning the old module.
+    pub fn set_error_handler(&self, error_handler: Atom) -> Atom {
+        mem::replace(&mut *self.error_handler.lock(), error_handler)
+    }
+
+    pub fn message_queue_data(&self) -> MessageQueueData {
+        *self.message_queue_data.lock()
+    }
+
+    /// Sets where messages are stored, returning the old setting.
+    pub fn set_message_queue_data(&self, message_queue_data: MessageQueueData) -> MessageQueueData {
+        mem::replace(&mut *self.message_queue_data.lock(), message_queue_data)
+    }
+
+    pub fn save_calls(&self) -> usize {
+        self.save_calls.load(Ordering::SeqCst)
+    }
+
+    /// Sets the number of calls to save, returning the old number.
+    pub fn set_save_calls(&self, save_calls: usize) -> usize {
+        self.save_calls.swap(save_calls, Ordering::SeqCst)
+    }
+
+    // Sizes
+
+    /// The minimum size of the heap in words.
+    pub fn min_heap_size(&self) -> usize {
+        self.min_heap_size.load(Ordering::SeqCst)
+    }
+
+    /// Sets the minimum size of the heap in words that the heap will not shrink below on the next
+    /// garbage collection, returning the old size.
+    pub fn set_min_heap_size(&self, min_heap_size: usize) -> usize {
+        self.min_heap_size.swap(min_heap_size, Ordering::SeqCst)
+    }
+
+    /// The limit on the size of the heap enforced during garbage collection.
+    pub fn max_heap_size(&self) -> MaxHeapSize {
+        *self.max_heap_size.lock()
+    }
+
+    /// Sets the limit on the size of the heap, returning the old limit.
+    pub fn set_max_heap_size(&self, max_heap_size: MaxHeapSize) -> MaxHeapSize {
+        mem::replace(&mut *self.max_heap_size.lock(), max_heap_size)
+    }
+
+    /// The minimum size of the virtual binary heap in words.
+    pub fn min_vheap_size(&self) -> usize {
+        self.min_vheap_size.load(Ordering::SeqCst)
+    }
+
+    /// Sets the minimum size of the virtual binary heap in words, returning the old size.
+    pub fn set_min_vheap_size(&self, min_vheap_size: usize) -> usize {
+        self.min_vheap_size.swap(min_vheap_size, Ordering::SeqCst)
+    }
+
+    /// The maximum number of minor collections before a full sweep, which is `fullsweep_after` in
+    /// `process_info/2`.
+    pub fn max_gen_gcs(&self) -> usize {
+        self.max_gen_gcs.load(Ordering::SeqCst)
+    }
+
+    /// Sets the maximum number of minor collections before a full sweep, returning the old number.
+    pub fn set_max_gen_gcs(&self, max_gen_gcs: usize) -> usize {
+        self.max_gen_gcs.swap(max_gen_gcs, Ordering::SeqCst)
+    }
+
+    /// The size of the young heap in words, not including any heap fragments.
+    pub fn heap_size(&self) -> usize {
+        self.heap.lock().heap_size()
+    }
+
     // Alloc
 
     /// Acquires exclusive access to the process heap, blocking the current thread until it is able
@@ -496,8 +622,16 @@ impl Process {
     }
 
     /// Returns `true` if the process should stop waiting and be rescheduled as runnable.
+    ///
+    /// With `message_queue_data` set to `off_heap`, messages are always stored in heap fragments,
+    /// so senders never contend for the heap of the process.
     pub fn send_from_other(&self, data: Term) -> AllocResult<bool> {
-        match self.heap.try_lock() {
+        let mut option_heap = match self.message_queue_data() {
+            MessageQueueData::OnHeap => self.heap.try_lock(),
+            MessageQueueData::OffHeap => None,
+        };
+
+        match option_heap {
             Some(ref mut destination_heap) => match data.clone_to_heap(destination_heap) {
                 Ok(destination_data) => {
                     self.send_message(Message::Process(message::Process {
@@ -517,17 +651,21 @@ impl Process {
             }
         }
 
-        // status.write() scope
-        {
-            let mut writable_status = self.status.write();
+        Ok(self.stop_waiting())
+    }
 
-            if *writable_status == Status::Waiting {
-                *writable_status = Status::Runnable;
+    /// Makes the process runnable if it is waiting for a message.
+    ///
+    /// Returns `true` if the process was waiting and should be rescheduled as runnable.
+    pub fn stop_waiting(&self) -> bool {
+        let mut writable_status = self.status.write();
 
-                Ok(true)
-            } else {
-                Ok(false)
-            }
+        if *writable_status == Status::Waiting {
+            *writable_status = Status::Runnable;
+
+            true
+        } else {
+            false
         }
     }
 
@@ -805,6 +943,15 @@ impl Process {
         }
     }
 
+    /// Returns all key/value pairs from the process dictionary without allocating, so that they
+    /// can be copied to the heap of another process
+    pub fn dictionary_entry_vec(&self) -> Vec<(Term, Term)> {
+        self.dictionary
+            .iter()
+            .map(|entry| (*entry.key(), *entry.value()))
+            .collect()
+    }
+
     /// Returns list of all keys from the process dictionary.
     pub fn get_keys(&self) -> AllocResult<Term> {
         let mut heap = self.heap.lock();
@@ -907,8 +1054,9 @@ impl Process {
         heap.should_collect(self.gc_threshold)
     }
 
+    /// The size of the heap fragments in words.
     #[inline(always)]
-    fn off_heap_size(&self) -> usize {
+    pub fn off_heap_size(&self) -> usize {
         self.off_heap_size.load(Ordering::Acquire)
     }
 
@@ -952,9 +1100,31 @@ impl Process {
     /// `GcError` documentation.
     ///
     /// `need` is specified in words.
+    ///
+    /// A minor collection that finds that a full sweep is required or that the max heap size may
+    /// be exceeded is retried as a full sweep, so `GcError::MaxHeapSizeExceeded` is only returned
+    /// when even a full sweep would exceed the max heap size and the process is to be killed.
     #[inline]
     pub fn garbage_collect(&self, need: usize, roots: &mut [Term]) -> Result<usize, GcError> {
         let mut heap = self.heap.lock();
+        let full_sweep = self.needs_fullsweep();
+
+        match self.garbage_collect_heap(&mut heap, need, roots) {
+            Err(GcError::FullsweepRequired) | Err(GcError::MaxHeapSizeExceeded) if !full_sweep => {
+                self.set_flags(ProcessFlags::NeedFullSweep);
+
+                self.garbage_collect_heap(&mut heap, need, roots)
+            }
+            result => result,
+        }
+    }
+
+    fn garbage_collect_heap(
+        &self,
+        heap: &mut ProcessHeap,
+        need: usize,
+        roots: &mut [Term],
+    ) -> Result<usize, GcError> {
         // The roots passed in here are pointers to the native stack/registers, all other roots
         // we are able to pick up from the current process context
         let mut rootset = RootSet::new(roots);
@@ -2,6 +2,7 @@ use core::alloc::Layout;
 use core::ptr::NonNull;
 
 use crate::erts::exception::AllocResult;
+use crate::erts::literal;
 use crate::erts::process::alloc::*;
 use crate::erts::term::prelude::*;
 
@@ -369,8 +370,13 @@ where
             return 0;
         }
 
-        // Check if this is a move marker
         let box_ptr: *mut Term = (*pos).dyn_cast();
+        // Skip the boxes in literal areas that can't be encoded as literals, such as `ProcBin`s
+        if literal::contains(box_ptr) {
+            return 0;
+        }
+
+        // Check if this is a move marker
         let unboxed = &*box_ptr;
         if unboxed.is_boxed() {
             // Overwrite the move marker with the forwarding address
@@ -412,13 +418,14 @@ where
     }
 
     if term.is_non_empty_list() {
-        // Skip pointers to literals
-        if term.is_literal() {
+        let ptr: Boxed<Cons> = (*pos).dyn_cast();
+        // Skip pointers to literals, which lists have no encoding for, so they are only known by
+        // the area they point into
+        if literal::contains(ptr.as_ptr()) {
             return 0;
         }
 
         // Check if this is a move marker
-        let ptr: Boxed<Cons> = (*pos).dyn_cast();
         let cons = ptr.as_ref();
         if cons.is_move_marker() {
             // Overwrite the move marker with the forwarding address
@@ -78,7 +78,7 @@ impl ProcessHeap {
 
         // Initialize the collector
         // Determine if the current collection requires a full sweep or not
-        if process.needs_fullsweep() || self.gen_gc_count >= process.max_gen_gcs {
+        if process.needs_fullsweep() || self.gen_gc_count >= process.max_gen_gcs() {
             self.collect_full(process, needed, roots)
         } else {
             self.collect_minor(process, needed, roots)
@@ -117,8 +117,10 @@ impl ProcessHeap {
             };
 
         // Verify that our projected heap size is not going to blow the max heap size, if set
-        // NOTE: When this happens, we will be left with no choice but to kill the process
-        if process.max_heap_size > 0 && process.max_heap_size < new_heap_size {
+        // NOTE: When this happens, we will be left with no choice but to kill the process.  When
+        // the process is not to be killed, the heap is allowed to grow past the max heap size.
+        let max_heap_size = process.max_heap_size();
+        if max_heap_size.is_enabled() && max_heap_size.kill && max_heap_size.size < new_heap_size {
             return Err(GcError::MaxHeapSizeExceeded);
         }
 
@@ -198,13 +200,13 @@ impl ProcessHeap {
 
         // Check if the needed space consumes less than 25% of the new heap,
         // and if so, shrink the new heap immediately to free the unused space
-        if total_size > needed_after * 4 && process.min_heap_size < total_size {
+        if total_size > needed_after * 4 && process.min_heap_size() < total_size {
             // Shrink to double our estimated need
             let mut estimate = needed_after * 2;
             // If our estimated need is too low, round up to the min heap size;
             // otherwise, calculate the next heap size bucket our need falls in
-            if estimate < process.min_heap_size {
-                estimate = process.min_heap_size;
+            if estimate < process.min_heap_size() {
+                estimate = process.min_heap_size();
             } else {
                 estimate = alloc::next_heap_size(estimate);
             }
@@ -248,7 +250,8 @@ impl ProcessHeap {
         // the max heap size, if one was configured.
         //
         // If a max heap size is set, make sure we're not going to exceed it
-        if process.max_heap_size > 0 {
+        let max_heap_size = process.max_heap_size();
+        if max_heap_size.is_enabled() {
             // First, check if we have exceeded the max heap size
             let mut heap_size = size_before;
             // In this estimate, our stack size includes unused area between stack and heap
@@ -269,7 +272,7 @@ impl ProcessHeap {
             heap_size += alloc::next_heap_size(baseline_size);
 
             // When this error type is returned, a full sweep will be triggered
-            if heap_size > process.max_heap_size {
+            if heap_size > max_heap_size.size {
                 return Err(GcError::MaxHeapSizeExceeded);
             }
         }
@@ -356,8 +359,8 @@ impl ProcessHeap {
 
             // If the new estimate is less than the min heap size, then round up;
             // otherwise, round the estimate up to the nearest heap size bucket
-            if estimate < process.min_heap_size {
-                estimate = process.min_heap_size;
+            if estimate < process.min_heap_size() {
+                estimate = process.min_heap_size();
             } else {
                 estimate = alloc::next_heap_size(estimate);
             }
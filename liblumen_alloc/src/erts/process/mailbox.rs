@@ -58,6 +58,10 @@ impl Mailbox {
         self.remove(self.cursor - 1, proc);
         self.cursor = 0;
     }
+    /// Ends a receive that timed out, leaving all messages in the mailbox
+    pub fn recv_timeout(&mut self) {
+        self.cursor = 0;
+    }
     // End receive implementation for the eir interpreter
 
     pub fn flush<F>(&mut self, predicate: F, process: &Process) -> bool
@@ -0,0 +1,83 @@
+use core::convert::{TryFrom, TryInto};
+
+use anyhow::*;
+
+use crate::erts::term::prelude::*;
+
+/// The limit on the size of a process's heap, as set by the `max_heap_size` spawn option or
+/// process flag.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct MaxHeapSize {
+    /// The maximum size of the heap in words.  `0` disables the limit.
+    pub size: usize,
+    /// Whether the process is killed when its heap would grow past `size`.
+    pub kill: bool,
+    /// Whether an error report is logged when the process's heap would grow past `size`.
+    pub error_logger: bool,
+}
+
+impl MaxHeapSize {
+    pub fn is_enabled(&self) -> bool {
+        0 < self.size
+    }
+}
+
+impl Default for MaxHeapSize {
+    fn default() -> Self {
+        Self {
+            size: 0,
+            kill: true,
+            error_logger: true,
+        }
+    }
+}
+
+impl TryFrom<Term> for MaxHeapSize {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        match term.decode().unwrap() {
+            TypedTerm::Map(map) => {
+                let mut max_heap_size: Self = Default::default();
+
+                for (key, value) in map.iter() {
+                    let key_atom: Atom = (*key)
+                        .try_into()
+                        .context("max_heap_size key is not an atom")?;
+
+                    match key_atom.name() {
+                        "size" => {
+                            max_heap_size.size =
+                                (*value).try_into().context("max_heap_size size")?;
+                        }
+                        "kill" => {
+                            max_heap_size.kill =
+                                (*value).try_into().context("max_heap_size kill")?;
+                        }
+                        "error_logger" => {
+                            max_heap_size.error_logger =
+                                (*value).try_into().context("max_heap_size error_logger")?;
+                        }
+                        name => {
+                            return Err(TryAtomFromTermError(name)).context(
+                                "supported max_heap_size keys are size, kill, and error_logger",
+                            )
+                        }
+                    }
+                }
+
+                Ok(max_heap_size)
+            }
+            _ => {
+                let size = term
+                    .try_into()
+                    .context("max_heap_size is not a non-negative integer or a map")?;
+
+                Ok(Self {
+                    size,
+                    ..Default::default()
+                })
+            }
+        }
+    }
+}
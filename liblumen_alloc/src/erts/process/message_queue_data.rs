@@ -1,10 +1,10 @@
-use std::convert::{TryFrom, TryInto};
+use core::convert::{TryFrom, TryInto};
 
 use anyhow::Context;
 
-use liblumen_alloc::erts::term::prelude::*;
+use crate::erts::term::prelude::*;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum MessageQueueData {
     OnHeap,
     OffHeap,
@@ -16,6 +16,17 @@ impl Default for MessageQueueData {
     }
 }
 
+impl From<MessageQueueData> for Atom {
+    fn from(message_queue_data: MessageQueueData) -> Self {
+        let name = match message_queue_data {
+            MessageQueueData::OnHeap => "on_heap",
+            MessageQueueData::OffHeap => "off_heap",
+        };
+
+        Atom::from_str(name)
+    }
+}
+
 impl TryFrom<Term> for MessageQueueData {
     type Error = anyhow::Error;
 
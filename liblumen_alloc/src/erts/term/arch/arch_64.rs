@@ -80,6 +80,24 @@ impl RawTerm {
         Self(Encoding::encode_literal(value))
     }
 
+    /// Re-encodes a boxed term as a pointer to a literal, which garbage collection never moves
+    /// and cloning only copies the pointer to.
+    ///
+    /// Immediates, lists and `ProcBin`s are returned as they are, as they can't be literal
+    /// pointers, or in the case of `ProcBin`, would be decoded as a `BinaryLiteral`.
+    ///
+    /// # Safety
+    ///
+    /// The boxed value must never be freed or mutated, as any number of processes can share it.
+    #[inline]
+    pub unsafe fn into_literal(self) -> Self {
+        if self.is_boxed() && !self.is_literal() && !self.is_boxed_procbin() {
+            Self::encode_literal(self.decode_box())
+        } else {
+            self
+        }
+    }
+
     #[cfg_attr(target_arch = "x86_64", allow(unused))]
     #[inline]
     pub(crate) fn encode_header(value: u64, tag: u64) -> Self {
@@ -155,13 +155,36 @@ pub struct ExternalReference {
     reference: Reference,
 }
 impl_static_header!(ExternalReference, Term::HEADER_EXTERN_REF);
+impl ExternalReference {
+    pub fn new(arc_node: Arc<Node>, scheduler_id: scheduler::ID, number: ReferenceNumber) -> Self {
+        Self {
+            header: Default::default(),
+            arc_node,
+            reference: Reference::new(scheduler_id, number),
+        }
+    }
+
+    pub fn arc_node(&self) -> Arc<Node> {
+        self.arc_node.clone()
+    }
+
+    pub fn reference(&self) -> &Reference {
+        &self.reference
+    }
+}
 impl CloneToProcess for ExternalReference {
     #[inline]
-    fn clone_to_heap<A>(&self, _heap: &mut A) -> AllocResult<Term>
+    fn clone_to_heap<A>(&self, heap: &mut A) -> AllocResult<Term>
     where
         A: ?Sized + TermAlloc,
     {
-        unimplemented!()
+        unsafe {
+            let layout = Layout::new::<Self>();
+            let ptr = heap.alloc_layout(layout)?.as_ptr() as *mut Self;
+            ptr.write(self.clone());
+
+            Ok(ptr.into())
+        }
     }
 
     fn size_in_words(&self) -> usize {
@@ -289,7 +289,7 @@ impl ResourceInner {
                 .as_ptr();
 
             ptr.write(Self {
-                reference_count: Default::default(),
+                reference_count: AtomicUsize::new(1),
                 resource,
             });
 
@@ -0,0 +1,688 @@
+//! Disassembles the `Code` chunk of a BEAM file into generic BEAM instructions.
+//!
+//! The operands are decoded from the compact term encoding and resolved against the other chunks,
+//! so atoms, literals, imports, funs and strings appear as values instead of indices into tables.
+//! The [`Display`](std::fmt::Display) implementation of [`Module`](Module) prints the instructions
+//! in the same format as `erlc -S`, except that `line` instructions show the index into the `Line`
+//! chunk instead of the location.
+//!
+//! # Examples
+//!
+//!     use liblumen_beam::beam::asm::Module;
+//!
+//!     let module = Module::from_beam_file("tests/testdata/reader/test.beam").unwrap();
+//!     assert_eq!("test", module.name);
+//!     println!("{}", module);
+//!
+//! ## References
+//!
+//! * [BEAM Wisdoms - BEAM File Format](http://beam-wisdoms.clau.se/en/latest/indepth-beam-file.html)
+//! * [`genop.tab`](https://github.com/erlang/otp/blob/master/lib/compiler/src/genop.tab) in the
+//!   Erlang/OTP compiler
+mod compact;
+pub mod opcode;
+
+#[cfg(test)]
+mod test;
+
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use failure::Fail;
+use num::bigint::BigInt;
+
+use crate::beam::chunk::StandardChunk;
+use crate::beam::reader::{ReadError, StandardBeamFile};
+use crate::serialization::etf;
+
+use self::compact::Decoder;
+
+/// A module disassembled from the `Code` chunk of a BEAM file.
+#[derive(Debug, PartialEq)]
+pub struct Module {
+    pub name: String,
+    /// The instruction set version from the `Code` chunk header.
+    pub version: u32,
+    pub exports: Vec<Export>,
+    pub label_count: u32,
+    pub functions: Vec<Function>,
+}
+impl Module {
+    pub fn from_beam_file<P: AsRef<Path>>(path: P) -> Result<Self, DisassembleError> {
+        let beam = StandardBeamFile::from_file(path)?;
+        Self::from_beam(&beam)
+    }
+
+    pub fn from_beam(beam: &StandardBeamFile) -> Result<Self, DisassembleError> {
+        let tables = Tables::from_beam(beam)?;
+        let code = match beam.get_chunk(b"Code") {
+            Some(StandardChunk::Code(code)) => code,
+            _ => return Err(DisassembleError::MissingChunk("Code")),
+        };
+        let exports = match beam.get_chunk(b"ExpT") {
+            Some(StandardChunk::ExpT(exp_t)) => exp_t
+                .exports
+                .iter()
+                .map(|export| {
+                    Ok(Export {
+                        name: tables.atom(export.function as u64)?.to_string(),
+                        arity: export.arity,
+                        label: export.label,
+                    })
+                })
+                .collect::<Result<Vec<_>, DisassembleError>>()?,
+            _ => Vec::new(),
+        };
+
+        // `info_size` counts the header fields after itself, which newer compilers may extend
+        let extra_header_size = (code.info_size as usize).saturating_sub(16);
+        let bytecode = code.bytecode.get(extra_header_size..).unwrap_or(&[]);
+        let instructions = decode_instructions(bytecode, &tables)?;
+
+        Ok(Module {
+            name: tables.atom(1)?.to_string(),
+            version: code.version,
+            exports,
+            label_count: code.label_count,
+            functions: group_functions(instructions)?,
+        })
+    }
+}
+impl Display for Module {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{module, ")?;
+        write_atom(f, &self.name)?;
+        writeln!(f, "}}.  %% version = {}", self.version)?;
+        writeln!(f)?;
+
+        let mut exports: Vec<&Export> = self.exports.iter().collect();
+        exports.sort_by(|a, b| (&a.name, a.arity).cmp(&(&b.name, b.arity)));
+        write!(f, "{{exports, [")?;
+        for (i, export) in exports.iter().enumerate() {
+            if i != 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{{")?;
+            write_atom(f, &export.name)?;
+            write!(f, ",{}}}", export.arity)?;
+        }
+        writeln!(f, "]}}.")?;
+        writeln!(f)?;
+
+        writeln!(f, "{{labels, {}}}.", self.label_count)?;
+
+        for function in &self.functions {
+            writeln!(f)?;
+            writeln!(f)?;
+            function.fmt(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Export {
+    pub name: String,
+    pub arity: u32,
+    pub label: u32,
+}
+
+/// The instructions from the `label` before a `func_info` up to the next function.
+#[derive(Debug, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub arity: u32,
+    /// The label after `func_info` that calls jump to.
+    pub entry: u64,
+    pub instructions: Vec<Instruction>,
+}
+impl Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{{function, ")?;
+        write_atom(f, &self.name)?;
+        write!(f, ", {}, {}}}.", self.arity, self.entry)?;
+
+        for instruction in &self.instructions {
+            writeln!(f)?;
+            if instruction.opcode == opcode::LABEL {
+                write!(f, "  {}", instruction)?;
+            } else {
+                write!(f, "    {}", instruction)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Instruction {
+    pub opcode: u8,
+    pub name: &'static str,
+    pub operands: Vec<Operand>,
+}
+impl Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.operands.is_empty() {
+            return write!(f, "{}.", self.name);
+        }
+
+        write!(f, "{{{}", self.name)?;
+        for operand in &self.operands {
+            write!(f, ",{}", operand)?;
+        }
+        write!(f, "}}.")
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Operand {
+    /// An untagged unsigned integer, such as an arity, a size or a count of live registers.
+    Unsigned(u64),
+    Integer(BigInt),
+    Atom(String),
+    Nil,
+    X(u64),
+    Y(u64),
+    Label(u64),
+    Character(u64),
+    List(Vec<Operand>),
+    FloatRegister(u64),
+    AllocationList(Vec<Allocation>),
+    Literal(etf::Term),
+    /// A register annotated with an index into the `Type` chunk (OTP 25 and later).
+    TypedRegister {
+        register: Box<Operand>,
+        type_index: u64,
+    },
+    /// An entry in the `ImpT` chunk, used by external calls and BIFs.
+    ExtFunc {
+        module: String,
+        function: String,
+        arity: u32,
+    },
+    /// An entry in the `FunT` chunk, used by `make_fun2` and `make_fun3`.
+    Fun {
+        function: String,
+        arity: u32,
+        label: u32,
+        index: u32,
+        old_uniq: u32,
+        num_free: u32,
+    },
+    /// A substring of the `StrT` chunk, used by `bs_put_string` and `bs_match_string`.
+    String(Vec<u8>),
+}
+impl Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Operand::Unsigned(u) => write!(f, "{}", u),
+            Operand::Integer(ref i) => write!(f, "{{integer,{}}}", i),
+            Operand::Atom(ref name) => {
+                write!(f, "{{atom,")?;
+                write_atom(f, name)?;
+                write!(f, "}}")
+            }
+            Operand::Nil => write!(f, "nil"),
+            Operand::X(x) => write!(f, "{{x,{}}}", x),
+            Operand::Y(y) => write!(f, "{{y,{}}}", y),
+            Operand::Label(label) => write!(f, "{{f,{}}}", label),
+            Operand::Character(c) => write!(f, "{{char,{}}}", c),
+            Operand::List(ref elements) => {
+                write!(f, "{{list,[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]}}")
+            }
+            Operand::FloatRegister(fr) => write!(f, "{{fr,{}}}", fr),
+            Operand::AllocationList(ref allocations) => {
+                write!(f, "{{alloc,[")?;
+                for (i, allocation) in allocations.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    match *allocation {
+                        Allocation::Words(n) => write!(f, "{{words,{}}}", n)?,
+                        Allocation::Floats(n) => write!(f, "{{floats,{}}}", n)?,
+                        Allocation::Funs(n) => write!(f, "{{funs,{}}}", n)?,
+                    }
+                }
+                write!(f, "]}}")
+            }
+            Operand::Literal(ref term) => {
+                write!(f, "{{literal,")?;
+                write_term(f, term)?;
+                write!(f, "}}")
+            }
+            Operand::TypedRegister {
+                ref register,
+                type_index,
+            } => write!(f, "{{tr,{},{}}}", register, type_index),
+            Operand::ExtFunc {
+                ref module,
+                ref function,
+                arity,
+            } => {
+                write!(f, "{{extfunc,")?;
+                write_atom(f, module)?;
+                write!(f, ",")?;
+                write_atom(f, function)?;
+                write!(f, ",{}}}", arity)
+            }
+            Operand::Fun {
+                label,
+                index,
+                old_uniq,
+                num_free,
+                ..
+            } => write!(f, "{{f,{}}},{},{},{}", label, index, old_uniq, num_free),
+            Operand::String(ref bytes) => {
+                write!(f, "{{string,")?;
+                write_string(f, bytes)?;
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// An entry in the allocation list of `allocate_heap` and `test_heap`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Allocation {
+    Words(u64),
+    Floats(u64),
+    Funs(u64),
+}
+
+#[derive(Fail, Debug)]
+pub enum DisassembleError {
+    #[fail(display = "invalid beam file: {}", _0)]
+    BeamFile(#[fail(cause)] ReadError),
+
+    #[fail(display = "unable to decode literal: {}", _0)]
+    TermDecode(#[fail(cause)] etf::DecodeError),
+
+    #[fail(display = "missing {} chunk", _0)]
+    MissingChunk(&'static str),
+
+    #[fail(display = "unexpected end of code")]
+    UnexpectedEndOfCode,
+
+    #[fail(display = "unknown opcode {}", _0)]
+    UnknownOpcode(u8),
+
+    #[fail(display = "invalid compact term starting with {:#04x}", _0)]
+    InvalidCompactTerm(u8),
+
+    #[fail(display = "{} index {} is out of range", table, index)]
+    IndexOutOfRange { table: &'static str, index: u64 },
+
+    #[fail(display = "malformed {} instruction", _0)]
+    MalformedInstruction(&'static str),
+}
+impl From<ReadError> for DisassembleError {
+    fn from(x: ReadError) -> Self {
+        DisassembleError::BeamFile(x)
+    }
+}
+impl From<etf::DecodeError> for DisassembleError {
+    fn from(x: etf::DecodeError) -> Self {
+        DisassembleError::TermDecode(x)
+    }
+}
+
+/// The chunks that operands refer to by index.
+#[derive(Default)]
+struct Tables {
+    atoms: Vec<String>,
+    imports: Vec<Operand>,
+    literals: Vec<etf::Term>,
+    funs: Vec<Operand>,
+    strings: Vec<u8>,
+}
+impl Tables {
+    fn from_beam(beam: &StandardBeamFile) -> Result<Self, DisassembleError> {
+        let mut tables = Tables::default();
+
+        match beam.atoms() {
+            Some(StandardChunk::Atom(atom)) => {
+                tables.atoms = atom.atoms.iter().map(|atom| atom.name.clone()).collect()
+            }
+            _ => return Err(DisassembleError::MissingChunk("Atom")),
+        }
+
+        if let Some(StandardChunk::ImpT(imp_t)) = beam.get_chunk(b"ImpT") {
+            for import in &imp_t.imports {
+                let ext_func = Operand::ExtFunc {
+                    module: tables.atom(import.module as u64)?.to_string(),
+                    function: tables.atom(import.function as u64)?.to_string(),
+                    arity: import.arity,
+                };
+                tables.imports.push(ext_func);
+            }
+        }
+
+        if let Some(StandardChunk::LitT(lit_t)) = beam.get_chunk(b"LitT") {
+            for literal in &lit_t.literals {
+                tables
+                    .literals
+                    .push(etf::Term::decode(std::io::Cursor::new(literal))?);
+            }
+        }
+
+        if let Some(StandardChunk::FunT(fun_t)) = beam.get_chunk(b"FunT") {
+            for function in &fun_t.functions {
+                let fun = Operand::Fun {
+                    function: tables.atom(function.function as u64)?.to_string(),
+                    arity: function.arity,
+                    label: function.label,
+                    index: function.index,
+                    old_uniq: function.old_uniq,
+                    num_free: function.num_free,
+                };
+                tables.funs.push(fun);
+            }
+        }
+
+        if let Some(StandardChunk::StrT(str_t)) = beam.get_chunk(b"StrT") {
+            tables.strings = str_t.strings.clone();
+        }
+
+        Ok(tables)
+    }
+
+    /// Atoms are numbered from 1, as 0 is used for `[]` in operands.
+    fn atom(&self, index: u64) -> Result<&str, DisassembleError> {
+        index
+            .checked_sub(1)
+            .and_then(|i| self.atoms.get(i as usize))
+            .map(|name| name.as_str())
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "atom",
+                index,
+            })
+    }
+
+    fn literal(&self, index: u64) -> Result<&etf::Term, DisassembleError> {
+        self.literals
+            .get(index as usize)
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "literal",
+                index,
+            })
+    }
+
+    fn string(&self, offset: u64, len: u64) -> Result<&[u8], DisassembleError> {
+        self.strings
+            .get(offset as usize..(offset + len) as usize)
+            .ok_or(DisassembleError::IndexOutOfRange {
+                table: "string",
+                index: offset,
+            })
+    }
+}
+
+fn decode_instructions(
+    bytecode: &[u8],
+    tables: &Tables,
+) -> Result<Vec<Instruction>, DisassembleError> {
+    let mut decoder = Decoder::new(bytecode, tables);
+    let mut instructions = Vec::new();
+
+    while !decoder.is_empty() {
+        let opcode = decoder.read_u8()?;
+        if opcode == opcode::INT_CODE_END {
+            break;
+        }
+
+        let (name, arity) =
+            opcode::name_and_arity(opcode).ok_or(DisassembleError::UnknownOpcode(opcode))?;
+        let mut operands = Vec::with_capacity(arity);
+        for _ in 0..arity {
+            operands.push(decoder.read_operand()?);
+        }
+
+        let mut instruction = Instruction {
+            opcode,
+            name,
+            operands,
+        };
+        resolve(&mut instruction, tables)?;
+        instructions.push(instruction);
+    }
+
+    Ok(instructions)
+}
+
+/// Replaces the untagged indices that some instructions use for the import, fun and string tables
+/// with the entries they refer to.
+fn resolve(instruction: &mut Instruction, tables: &Tables) -> Result<(), DisassembleError> {
+    let name = instruction.name;
+    let operands = &mut instruction.operands;
+    let unsigned = |operand: &Operand| match *operand {
+        Operand::Unsigned(u) => Ok(u),
+        _ => Err(DisassembleError::MalformedInstruction(name)),
+    };
+    let table_entry = |table: &'static str, entries: &[Operand], index: u64| {
+        entries
+            .get(index as usize)
+            .cloned()
+            .ok_or(DisassembleError::IndexOutOfRange { table, index })
+    };
+
+    let import_position = match name {
+        "bif0" => Some(0),
+        "call_ext" | "call_ext_last" | "call_ext_only" | "bif1" | "bif2" => Some(1),
+        "gc_bif1" | "gc_bif2" | "gc_bif3" => Some(2),
+        _ => None,
+    };
+    if let Some(position) = import_position {
+        let index = unsigned(&operands[position])?;
+        operands[position] = table_entry("import", &tables.imports, index)?;
+    }
+
+    match name {
+        "make_fun2" | "make_fun3" => {
+            let index = unsigned(&operands[0])?;
+            operands[0] = table_entry("fun", &tables.funs, index)?;
+        }
+        "bs_put_string" => {
+            let len = unsigned(&operands[0])?;
+            let offset = unsigned(&operands[1])?;
+            operands[1] = Operand::String(tables.string(offset, len)?.to_vec());
+        }
+        "bs_match_string" => {
+            let bits = unsigned(&operands[2])?;
+            let offset = unsigned(&operands[3])?;
+            let len = (bits + 7) / 8;
+            operands[3] = Operand::String(tables.string(offset, len)?.to_vec());
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Splits the instructions into functions, each starting with the `label` (and `line`) before its
+/// `func_info`.
+fn group_functions(instructions: Vec<Instruction>) -> Result<Vec<Function>, DisassembleError> {
+    let mut starts = Vec::new();
+    for (i, instruction) in instructions.iter().enumerate() {
+        if instruction.opcode == opcode::FUNC_INFO {
+            let start = instructions[..i]
+                .iter()
+                .rposition(|instruction| instruction.opcode == opcode::LABEL)
+                .ok_or(DisassembleError::MalformedInstruction("func_info"))?;
+            starts.push(start);
+        }
+    }
+
+    let mut functions = Vec::with_capacity(starts.len());
+    let mut remaining = instructions;
+    for start in starts.into_iter().rev() {
+        let instructions = remaining.split_off(start);
+        functions.push(function_from_instructions(instructions)?);
+    }
+    functions.reverse();
+
+    Ok(functions)
+}
+
+fn function_from_instructions(
+    instructions: Vec<Instruction>,
+) -> Result<Function, DisassembleError> {
+    let malformed = || DisassembleError::MalformedInstruction("func_info");
+    let func_info_position = instructions
+        .iter()
+        .position(|instruction| instruction.opcode == opcode::FUNC_INFO)
+        .ok_or_else(malformed)?;
+
+    let (name, arity) = match instructions[func_info_position].operands.as_slice() {
+        [Operand::Atom(_), Operand::Atom(name), Operand::Unsigned(arity)] => {
+            (name.clone(), *arity as u32)
+        }
+        _ => return Err(malformed()),
+    };
+    let entry = match instructions.get(func_info_position + 1) {
+        Some(Instruction {
+            opcode: opcode::LABEL,
+            operands,
+            ..
+        }) => match operands.as_slice() {
+            [Operand::Unsigned(label)] => *label,
+            _ => return Err(malformed()),
+        },
+        _ => return Err(malformed()),
+    };
+
+    Ok(Function {
+        name,
+        arity,
+        entry,
+        instructions,
+    })
+}
+
+/// Writes an atom the way `io:format("~p")` does, only quoting it when it has to be.
+fn write_atom(f: &mut fmt::Formatter, name: &str) -> fmt::Result {
+    const RESERVED: &[&str] = &[
+        "after", "and", "andalso", "band", "begin", "bnot", "bor", "bsl", "bsr", "bxor", "case",
+        "catch", "cond", "div", "end", "fun", "if", "let", "not", "of", "or", "orelse", "receive",
+        "rem", "try", "when", "xor",
+    ];
+
+    let mut chars = name.chars();
+    let is_bare = match chars.next() {
+        Some(first) => {
+            first.is_ascii_lowercase()
+                && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@')
+        }
+        None => false,
+    };
+
+    if is_bare && !RESERVED.contains(&name) {
+        write!(f, "{}", name)
+    } else {
+        write!(f, "{}", etf::Atom::from(name))
+    }
+}
+
+/// Writes a literal the way `io:format("~p")` does, though without line breaks.
+fn write_term(f: &mut fmt::Formatter, term: &etf::Term) -> fmt::Result {
+    fn write_elements(f: &mut fmt::Formatter, elements: &[etf::Term]) -> fmt::Result {
+        for (i, element) in elements.iter().enumerate() {
+            if i != 0 {
+                write!(f, ",")?;
+            }
+            write_term(f, element)?;
+        }
+        Ok(())
+    }
+
+    match *term {
+        etf::Term::Atom(ref atom) => write_atom(f, &atom.name),
+        // `Display` drops the fraction of integral floats
+        etf::Term::Float(ref float) => {
+            let debug = format!("{:?}", float.value);
+            if debug.contains('.') {
+                write!(f, "{}", debug)
+            } else {
+                match debug.find('e') {
+                    Some(e) => write!(f, "{}.0{}", &debug[..e], &debug[e..]),
+                    None => write!(f, "{}.0", debug),
+                }
+            }
+        }
+        etf::Term::List(ref list) => {
+            let string: Option<Vec<u8>> = list
+                .elements
+                .iter()
+                .map(|element| match *element {
+                    etf::Term::FixInteger(etf::FixInteger { value })
+                        if (0..0x7f).contains(&value) && is_printable(value as u8) =>
+                    {
+                        Some(value as u8)
+                    }
+                    _ => None,
+                })
+                .collect();
+
+            match string {
+                Some(ref bytes) if !bytes.is_empty() => write_string(f, bytes),
+                _ => {
+                    write!(f, "[")?;
+                    write_elements(f, &list.elements)?;
+                    write!(f, "]")
+                }
+            }
+        }
+        etf::Term::ImproperList(ref list) => {
+            write!(f, "[")?;
+            write_elements(f, &list.elements)?;
+            write!(f, "|")?;
+            write_term(f, &list.last)?;
+            write!(f, "]")
+        }
+        etf::Term::Tuple(ref tuple) => {
+            write!(f, "{{")?;
+            write_elements(f, &tuple.elements)?;
+            write!(f, "}}")
+        }
+        etf::Term::Map(ref map) => {
+            write!(f, "#{{")?;
+            for (i, (key, value)) in map.entries.iter().enumerate() {
+                if i != 0 {
+                    write!(f, ",")?;
+                }
+                write_term(f, key)?;
+                write!(f, " => ")?;
+                write_term(f, value)?;
+            }
+            write!(f, "}}")
+        }
+        ref other => write!(f, "{}", other),
+    }
+}
+
+fn is_printable(byte: u8) -> bool {
+    (0x20..0x7f).contains(&byte) || byte == b'\n' || byte == b'\t'
+}
+
+fn write_string(f: &mut fmt::Formatter, bytes: &[u8]) -> fmt::Result {
+    write!(f, "\"")?;
+    for &byte in bytes {
+        match byte {
+            b'"' => write!(f, "\\\"")?,
+            b'\\' => write!(f, "\\\\")?,
+            b'\n' => write!(f, "\\n")?,
+            b'\t' => write!(f, "\\t")?,
+            _ if is_printable(byte) => write!(f, "{}", byte as char)?,
+            _ => write!(f, "\\{:03o}", byte)?,
+        }
+    }
+    write!(f, "\"")
+}
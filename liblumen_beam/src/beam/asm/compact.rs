@@ -0,0 +1,169 @@
+//! The compact term encoding used for the operands of instructions in the `Code` chunk.
+//!
+//! The low 3 bits of the first byte are the tag.  For every tag except the extended one, the value
+//! follows in the remaining bits of the first byte, the next byte or a run of big-endian bytes,
+//! depending on how large it is.
+//!
+//! ## References
+//!
+//! * [BEAM Wisdoms - Compact Term Encoding](http://beam-wisdoms.clau.se/en/latest/indepth-beam-file.html#beam-compact-term-encoding)
+//! * `beam_asm:encode/2` in the Erlang/OTP compiler
+use num::bigint::BigInt;
+use num::traits::ToPrimitive;
+
+use super::{Allocation, DisassembleError, Operand, Tables};
+
+const TAG_LITERAL: u8 = 0;
+const TAG_INTEGER: u8 = 1;
+const TAG_ATOM: u8 = 2;
+const TAG_X_REGISTER: u8 = 3;
+const TAG_Y_REGISTER: u8 = 4;
+const TAG_LABEL: u8 = 5;
+const TAG_CHARACTER: u8 = 6;
+const TAG_EXTENDED: u8 = 7;
+
+const EXTENDED_LIST: u8 = 1;
+const EXTENDED_FLOAT_REGISTER: u8 = 2;
+const EXTENDED_ALLOCATION_LIST: u8 = 3;
+const EXTENDED_LITERAL: u8 = 4;
+const EXTENDED_TYPED_REGISTER: u8 = 5;
+
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+    position: usize,
+    tables: &'a Tables,
+}
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8], tables: &'a Tables) -> Self {
+        Decoder {
+            bytes,
+            position: 0,
+            tables,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, DisassembleError> {
+        let byte = *self
+            .bytes
+            .get(self.position)
+            .ok_or(DisassembleError::UnexpectedEndOfCode)?;
+        self.position += 1;
+        Ok(byte)
+    }
+
+    pub fn read_operand(&mut self) -> Result<Operand, DisassembleError> {
+        let byte = self.read_u8()?;
+        let tag = byte & 0b111;
+        let operand = match tag {
+            TAG_LITERAL => Operand::Unsigned(self.read_unsigned(byte)?),
+            TAG_INTEGER => Operand::Integer(self.read_value(byte)?),
+            TAG_ATOM => match self.read_unsigned(byte)? {
+                0 => Operand::Nil,
+                index => Operand::Atom(self.tables.atom(index)?.to_string()),
+            },
+            TAG_X_REGISTER => Operand::X(self.read_unsigned(byte)?),
+            TAG_Y_REGISTER => Operand::Y(self.read_unsigned(byte)?),
+            TAG_LABEL => Operand::Label(self.read_unsigned(byte)?),
+            TAG_CHARACTER => Operand::Character(self.read_unsigned(byte)?),
+            TAG_EXTENDED => self.read_extended(byte)?,
+            _ => unreachable!(),
+        };
+        Ok(operand)
+    }
+
+    fn read_extended(&mut self, byte: u8) -> Result<Operand, DisassembleError> {
+        if byte & 0b1000 != 0 {
+            return Err(DisassembleError::InvalidCompactTerm(byte));
+        }
+
+        match byte >> 4 {
+            EXTENDED_LIST => {
+                // The length is only trusted as far as there are elements to read, so nothing is
+                // reserved up front
+                let len = self.read_operand_unsigned()?;
+                let mut elements = Vec::new();
+                for _ in 0..len {
+                    elements.push(self.read_operand()?);
+                }
+                Ok(Operand::List(elements))
+            }
+            EXTENDED_FLOAT_REGISTER => Ok(Operand::FloatRegister(self.read_operand_unsigned()?)),
+            EXTENDED_ALLOCATION_LIST => {
+                let len = self.read_operand_unsigned()?;
+                let mut allocations = Vec::new();
+                for _ in 0..len {
+                    let kind = self.read_operand_unsigned()?;
+                    let count = self.read_operand_unsigned()?;
+                    let allocation = match kind {
+                        0 => Allocation::Words(count),
+                        1 => Allocation::Floats(count),
+                        2 => Allocation::Funs(count),
+                        _ => return Err(DisassembleError::InvalidCompactTerm(byte)),
+                    };
+                    allocations.push(allocation);
+                }
+                Ok(Operand::AllocationList(allocations))
+            }
+            EXTENDED_LITERAL => {
+                let index = self.read_operand_unsigned()?;
+                Ok(Operand::Literal(self.tables.literal(index)?.clone()))
+            }
+            EXTENDED_TYPED_REGISTER => {
+                let register = self.read_operand()?;
+                let type_index = self.read_operand_unsigned()?;
+                Ok(Operand::TypedRegister {
+                    register: Box::new(register),
+                    type_index,
+                })
+            }
+            _ => Err(DisassembleError::InvalidCompactTerm(byte)),
+        }
+    }
+
+    /// The unsigned value of a whole operand, as used for sizes inside extended operands.
+    fn read_operand_unsigned(&mut self) -> Result<u64, DisassembleError> {
+        let byte = self.read_u8()?;
+        match byte & 0b111 {
+            TAG_LITERAL => self.read_unsigned(byte),
+            _ => Err(DisassembleError::InvalidCompactTerm(byte)),
+        }
+    }
+
+    fn read_unsigned(&mut self, byte: u8) -> Result<u64, DisassembleError> {
+        self.read_value(byte)?
+            .to_u64()
+            .ok_or(DisassembleError::InvalidCompactTerm(byte))
+    }
+
+    /// The value following the tag in `byte`.  Values that don't fit in 11 bits are stored in
+    /// two's complement, so only integers can be negative.
+    fn read_value(&mut self, byte: u8) -> Result<BigInt, DisassembleError> {
+        if byte & 0b1000 == 0 {
+            // 4 bits in the high nibble
+            Ok(BigInt::from(byte >> 4))
+        } else if byte & 0b1_0000 == 0 {
+            // 3 bits in the high bits followed by another byte
+            let low = self.read_u8()?;
+            Ok(BigInt::from(
+                (((byte & 0b1110_0000) as u16) << 3) | low as u16,
+            ))
+        } else {
+            let len = match byte >> 5 {
+                // The number of bytes, less 9, is encoded as a literal
+                0b111 => (self.read_operand_unsigned()? as usize).saturating_add(9),
+                len => len as usize + 2,
+            };
+            let bytes = self
+                .position
+                .checked_add(len)
+                .and_then(|end| self.bytes.get(self.position..end))
+                .ok_or(DisassembleError::UnexpectedEndOfCode)?;
+            self.position += len;
+            Ok(BigInt::from_signed_bytes_be(bytes))
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use num::bigint::BigInt;
+
+use crate::beam::asm::compact::Decoder;
+use crate::beam::asm::*;
+
+#[test]
+fn functions() {
+    let module = Module::from_beam_file("tests/testdata/reader/test.beam").unwrap();
+
+    assert_eq!("test", module.name);
+    assert_eq!(9, module.label_count);
+    assert_eq!(
+        vec![
+            ("hello", 1, 2),
+            ("module_info", 0, 4),
+            ("module_info", 1, 6),
+            ("-hello/1-fun-0-", 1, 8)
+        ],
+        module
+            .functions
+            .iter()
+            .map(|function| (function.name.as_str(), function.arity, function.entry))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn resolves_operands() {
+    let module = Module::from_beam_file("tests/testdata/reader/test.beam").unwrap();
+
+    let hello = &module.functions[0];
+    assert_eq!(
+        Instruction {
+            opcode: opcode::FUNC_INFO,
+            name: "func_info",
+            operands: vec![
+                Operand::Atom("test".to_string()),
+                Operand::Atom("hello".to_string()),
+                Operand::Unsigned(1),
+            ],
+        },
+        hello.instructions[2]
+    );
+    match hello.instructions[5].operands[0] {
+        Operand::Fun {
+            ref function,
+            arity,
+            label,
+            ..
+        } => {
+            assert_eq!("-hello/1-fun-0-", function);
+            assert_eq!(1, arity);
+            assert_eq!(8, label);
+        }
+        ref other => panic!("{:?} is not a fun", other),
+    }
+
+    let fun = &module.functions[3];
+    let operands: Vec<&Operand> = fun
+        .instructions
+        .iter()
+        .flat_map(|instruction| instruction.operands.iter())
+        .collect();
+    assert!(operands.contains(&&Operand::ExtFunc {
+        module: "io".to_string(),
+        function: "format".to_string(),
+        arity: 2,
+    }));
+    assert!(
+        operands.contains(&&Operand::Literal(etf::Term::from(etf::List::from(
+            "Hello ~p!"
+                .bytes()
+                .map(|b| etf::Term::from(etf::FixInteger::from(b)))
+                .collect::<Vec<_>>()
+        ))))
+    );
+}
+
+#[test]
+fn display() {
+    let module = Module::from_beam_file("tests/testdata/reader/test.beam").unwrap();
+    let asm = module.to_string();
+
+    assert!(asm.starts_with("{module, test}.  %% version = 0\n"));
+    assert!(asm.contains("\n{exports, [{hello,1},{module_info,0},{module_info,1}]}.\n"));
+    assert!(asm.contains(
+        "\n{function, module_info, 0, 4}.\n  {label,3}.\n    {line,0}.\n    \
+         {func_info,{atom,test},{atom,module_info},0}.\n  {label,4}.\n"
+    ));
+    assert!(asm.contains("\n{function, '-hello/1-fun-0-', 1, 8}.\n"));
+    assert!(asm.contains("\n    {move,{literal,\"Hello ~p!\"},{x,0}}.\n"));
+    assert!(asm.contains("\n    {call_ext_only,2,{extfunc,io,format,2}}."));
+}
+
+#[test]
+fn every_function_of_a_larger_module() {
+    let module = Module::from_beam_file("tests/testdata/ast/test.beam").unwrap();
+
+    assert_eq!("test", module.name);
+    for export in &module.exports {
+        assert!(module
+            .functions
+            .iter()
+            .any(|function| function.name == export.name
+                && function.arity == export.arity
+                && function.entry == export.label as u64));
+    }
+}
+
+#[test]
+fn compact_terms() {
+    let tables = Tables {
+        atoms: vec!["module".to_string(), "ok".to_string()],
+        ..Default::default()
+    };
+    let decode = |bytes: &[u8]| {
+        let mut decoder = Decoder::new(bytes, &tables);
+        let operand = decoder.read_operand().unwrap();
+        assert!(decoder.is_empty());
+        operand
+    };
+
+    // 4-bit values
+    assert_eq!(Operand::Unsigned(3), decode(&[0x30]));
+    assert_eq!(Operand::X(1), decode(&[0x13]));
+    assert_eq!(Operand::Nil, decode(&[0x02]));
+    assert_eq!(Operand::Atom("ok".to_string()), decode(&[0x22]));
+    // 11-bit values
+    assert_eq!(Operand::Label(1000), decode(&[0x6d, 0xe8]));
+    // 2 to 8 byte values are two's complement
+    assert_eq!(
+        Operand::Integer(BigInt::from(-1)),
+        decode(&[0x19, 0xff, 0xff])
+    );
+    assert_eq!(
+        Operand::Integer(BigInt::from(0x1_0000)),
+        decode(&[0x39, 0x01, 0x00, 0x00])
+    );
+    // larger values have their byte count, less 9, as a nested literal
+    let mut large = vec![0xf9, 0x00, 0x01];
+    large.extend_from_slice(&[0; 8]);
+    assert_eq!(Operand::Integer(BigInt::from(1) << 64), decode(&large));
+    // extended terms
+    assert_eq!(
+        Operand::List(vec![Operand::Integer(BigInt::from(1)), Operand::Label(2)]),
+        decode(&[0x17, 0x20, 0x11, 0x25])
+    );
+    assert_eq!(Operand::FloatRegister(2), decode(&[0x27, 0x20]));
+    assert_eq!(
+        Operand::AllocationList(vec![Allocation::Words(3), Allocation::Floats(1)]),
+        decode(&[0x37, 0x20, 0x00, 0x30, 0x10, 0x10])
+    );
+}
+
+#[test]
+fn compact_terms_with_lengths_past_the_end_are_rejected() {
+    let tables = Tables::default();
+    let decode = |bytes: &[u8]| Decoder::new(bytes, &tables).read_operand();
+
+    // A list or allocation list of 2^63 - 1 elements with none following
+    let huge_len = [0xd8, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+    for &extended in &[0x17, 0x37] {
+        let mut bytes = vec![extended];
+        bytes.extend_from_slice(&huge_len);
+        assert!(decode(&bytes).is_err());
+    }
+
+    // An integer of 2^64 - 1 bytes, less 9
+    let mut bytes = vec![0xf9, 0xf8, 0x00, 0x00];
+    bytes.extend_from_slice(&[0xff; 8]);
+    assert!(decode(&bytes).is_err());
+}
@@ -35,9 +35,13 @@ impl<C: Chunk> BeamFile<C> {
         BeamFile { chunks, order }
     }
     /// Adds a chunk to the BEAM file
+    ///
+    /// A chunk with the same id as one already in the file replaces it, keeping its position.
     pub fn push_chunk(&mut self, chunk: C) {
-        self.order.push(*chunk.id());
-        self.chunks.insert(*chunk.id(), chunk);
+        let id = *chunk.id();
+        if self.chunks.insert(id, chunk).is_none() {
+            self.order.push(id);
+        }
     }
     /// Returns all chunks in the order they were encountered in the origin BEAM file
     ///
@@ -19,7 +19,11 @@ use std::str;
 use byteorder::BigEndian;
 use byteorder::ReadBytesExt;
 use byteorder::WriteBytesExt;
-use libflate::zlib;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use crate::serialization::etf;
 
 use super::parts;
 use super::Result;
@@ -95,6 +99,28 @@ pub trait Chunk {
     fn encode_data<W: Write>(&self, writer: W) -> Result<()>;
 }
 
+/// Implements `from_term` and `to_term` for a chunk that holds a single term in the External Term
+/// Format, compressing it like the Erlang compiler does for that chunk.
+macro_rules! impl_term_chunk {
+    ($chunk:ident, compressed: $compressed:expr) => {
+        impl $chunk {
+            pub fn from_term(term: &etf::Term) -> std::result::Result<Self, etf::EncodeError> {
+                let mut buf = Vec::new();
+                if $compressed {
+                    term.encode_compressed(&mut buf)?;
+                } else {
+                    term.encode(&mut buf)?;
+                }
+                Ok($chunk { term: buf })
+            }
+
+            pub fn to_term(&self) -> etf::DecodeResult {
+                etf::Term::decode(Cursor::new(&self.term))
+            }
+        }
+    };
+}
+
 /// A raw representation of a chunk.
 ///
 /// This implementation does not interpret the data of a chunk
@@ -179,10 +205,13 @@ impl Chunk for AtomChunk {
             let mut buf = vec![0; len];
             reader.read_exact(&mut buf)?;
 
-            let name = str::from_utf8(&buf).map(|s| s.to_string())?;
-            atoms.push(parts::Atom {
-                name: name.to_string(),
-            });
+            let name = if unicode {
+                str::from_utf8(&buf).map(|s| s.to_string())?
+            } else {
+                // Latin-1 is the first 256 code points of Unicode
+                buf.iter().map(|&b| b as char).collect()
+            };
+            atoms.push(parts::Atom { name });
         }
         Ok(AtomChunk {
             is_unicode: unicode,
@@ -192,9 +221,32 @@ impl Chunk for AtomChunk {
     fn encode_data<W: Write>(&self, mut writer: W) -> Result<()> {
         writer.write_u32::<BigEndian>(self.atoms.len() as u32)?;
         for atom in &self.atoms {
-            assert!(atom.name.len() < 0x100);
-            writer.write_u8(atom.name.len() as u8)?;
-            writer.write_all(atom.name.as_bytes())?;
+            let bytes = if self.is_unicode {
+                atom.name.as_bytes().to_vec()
+            } else {
+                atom.name
+                    .chars()
+                    .map(|c| {
+                        if (c as u32) < 0x100 {
+                            Some(c as u8)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Option<Vec<u8>>>()
+                    .ok_or_else(|| {
+                        invalid_input(format!("atom {:?} is not Latin-1, use AtU8", atom.name))
+                    })?
+            };
+            if bytes.len() > 0xFF {
+                return Err(invalid_input(format!(
+                    "atom {:?} is longer than 255 bytes",
+                    atom.name
+                ))
+                .into());
+            }
+            writer.write_u8(bytes.len() as u8)?;
+            writer.write_all(&bytes)?;
         }
         Ok(())
     }
@@ -454,7 +506,7 @@ impl Chunk for LitTChunk {
     {
         auxiliary::check_chunk_id(id, b"LitT")?;
         let _uncompressed_size = reader.read_u32::<BigEndian>()?;
-        let mut decoder = zlib::Decoder::new(reader)?;
+        let mut decoder = ZlibDecoder::new(reader);
 
         let count = decoder.read_u32::<BigEndian>()? as usize;
         let mut literals = Vec::with_capacity(count);
@@ -474,16 +526,37 @@ impl Chunk for LitTChunk {
             .fold(4, |acc, l| acc + 4 + l.len() as u32);
         writer.write_u32::<BigEndian>(uncompressed_size)?;
 
-        let mut encoder = zlib::Encoder::new(writer)?;
+        // The default level is the same as the Erlang compiler uses, so the output is the same
+        let mut encoder = ZlibEncoder::new(writer, Compression::default());
         encoder.write_u32::<BigEndian>(self.literals.len() as u32)?;
         for literal in &self.literals {
             encoder.write_u32::<BigEndian>(literal.len() as u32)?;
             encoder.write_all(literal)?;
         }
-        encoder.finish().into_result()?;
+        encoder.finish()?;
         Ok(())
     }
 }
+impl LitTChunk {
+    /// Encodes each of `terms` as a literal.
+    pub fn from_terms(terms: &[etf::Term]) -> std::result::Result<Self, etf::EncodeError> {
+        let mut literals = Vec::with_capacity(terms.len());
+        for term in terms {
+            let mut literal = Vec::new();
+            term.encode(&mut literal)?;
+            literals.push(literal);
+        }
+        Ok(LitTChunk { literals })
+    }
+
+    /// Decodes the literals, in the order the `Code` chunk refers to them.
+    pub fn to_terms(&self) -> std::result::Result<Vec<etf::Term>, etf::DecodeError> {
+        self.literals
+            .iter()
+            .map(|literal| etf::Term::decode(Cursor::new(literal)))
+            .collect()
+    }
+}
 
 /// A table of the FA pairs and their corresponding label in [CodeChunk](CodeChunk)
 /// 1. Index of function atom in [AtomChunk](AtomChunk)
@@ -631,6 +704,41 @@ impl Chunk for AttrChunk {
         Ok(())
     }
 }
+impl_term_chunk!(AttrChunk, compressed: false);
+impl AttrChunk {
+    /// The `{Name, Values}` pairs of the attributes, in order.
+    pub fn attributes(&self) -> std::result::Result<Vec<(etf::Atom, etf::Term)>, etf::DecodeError> {
+        use crate::serialization::etf::pattern::{Any, VarList};
+
+        let term = self.to_term()?;
+        let pairs = term
+            .as_match(VarList((Any::<etf::Atom>::new(), Any::<etf::Term>::new())))
+            .map_err(|_| etf::DecodeError::UnexpectedType {
+                value: term.clone(),
+                expected: "[{atom(), term()}]".to_string(),
+            })?;
+        Ok(pairs
+            .into_iter()
+            .map(|(name, values)| (name.clone(), values.clone()))
+            .collect())
+    }
+
+    /// Encodes the `{Name, Values}` pairs of the attributes.
+    pub fn from_attributes(
+        attributes: &[(etf::Atom, etf::Term)],
+    ) -> std::result::Result<Self, etf::EncodeError> {
+        let pairs = attributes
+            .iter()
+            .map(|(name, values)| {
+                etf::Term::from(etf::Tuple::from(vec![
+                    etf::Term::from(name.clone()),
+                    values.clone(),
+                ]))
+            })
+            .collect::<Vec<_>>();
+        Self::from_term(&etf::Term::from(etf::List::from(pairs)))
+    }
+}
 
 /// The `"CInf"` chunk is the Compilation Information for the Erlang or Erlang Core compiler. Even
 /// Elixir modules have it because Elixir code passes through this part of the Erlang Core compiler.
@@ -662,6 +770,7 @@ impl Chunk for CInfChunk {
         Ok(())
     }
 }
+impl_term_chunk!(CInfChunk, compressed: false);
 
 /// A representation of the `"Abst"` chunk.
 ///
@@ -697,6 +806,7 @@ impl Chunk for AbstChunk {
         Ok(())
     }
 }
+impl_term_chunk!(AbstChunk, compressed: true);
 
 /// A representation of the `"Dbgi"` chunk.
 #[derive(Debug, PartialEq, Eq)]
@@ -741,6 +851,7 @@ impl Chunk for DbgiChunk {
         Ok(())
     }
 }
+impl_term_chunk!(DbgiChunk, compressed: true);
 
 /// A representation of the `"Docs"` chunk.
 #[derive(Debug, PartialEq, Eq)]
@@ -790,6 +901,11 @@ impl Chunk for DocsChunk {
         Ok(())
     }
 }
+impl_term_chunk!(DocsChunk, compressed: true);
+
+fn invalid_input(message: String) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, message)
+}
 
 /// A representation of commonly used chunk.
 ///
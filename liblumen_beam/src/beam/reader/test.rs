@@ -1,16 +1,13 @@
-use std::fs::File;
-use std::io::Read;
-use std::io::Write;
+use std::io::Cursor;
 use std::path::PathBuf;
 
 use crate::beam::reader::chunk;
 use crate::beam::reader::chunk::Chunk;
 use crate::beam::reader::chunk::StandardChunk;
 use crate::beam::reader::parts;
-use crate::beam::reader::BeamFile;
 use crate::beam::reader::RawBeamFile;
-use crate::beam::reader::Result;
 use crate::beam::reader::StandardBeamFile;
+use crate::serialization::etf;
 
 #[test]
 fn raw_chunks() {
@@ -181,51 +178,144 @@ fn standard_chunks() {
     assert_eq!(307, find_chunk!(beam, Abst).term.len());
 }
 
-enum EncodeTestChunk {
-    Idempotent(chunk::StandardChunk),
-    Other(chunk::RawChunk),
-}
-impl chunk::Chunk for EncodeTestChunk {
-    fn id(&self) -> &chunk::Id {
-        use self::EncodeTestChunk::*;
-        match *self {
-            Idempotent(ref c) => c.id(),
-            Other(ref c) => c.id(),
-        }
-    }
-    fn decode_data<R: Read>(id: &chunk::Id, reader: R) -> Result<Self>
-    where
-        Self: Sized,
-    {
-        use self::EncodeTestChunk::*;
-        match id {
-            b"LitT" => Ok(Other(chunk::RawChunk::decode_data(id, reader)?)),
-            _ => Ok(Idempotent(chunk::StandardChunk::decode_data(id, reader)?)),
-        }
-    }
-    fn encode_data<W: Write>(&self, writer: W) -> Result<()> {
-        use self::EncodeTestChunk::*;
-        match *self {
-            Idempotent(ref c) => c.encode_data(writer),
-            Other(ref c) => c.encode_data(writer),
-        }
+#[test]
+fn encode_chunks() {
+    for path in &[
+        "tests/testdata/reader/test.beam",
+        "tests/testdata/reader/Elixir.Unicode.beam",
+        "tests/testdata/ast/test.beam",
+        "tests/testdata/simple.beam",
+    ] {
+        let original = std::fs::read(path).unwrap();
+
+        let beam = StandardBeamFile::from_reader(Cursor::new(&original)).unwrap();
+        let mut encoded = Vec::new();
+        beam.to_writer(&mut encoded).unwrap();
+
+        assert!(
+            original == encoded,
+            "{} was not encoded byte for byte",
+            path
+        );
     }
 }
 
 #[test]
-fn encode_chunks() {
-    let mut original = Vec::new();
-    std::io::copy(
-        &mut File::open(test_file("test.beam")).unwrap(),
-        &mut original,
-    )
-    .unwrap();
-
-    let beam = BeamFile::<EncodeTestChunk>::from_reader(std::io::Cursor::new(&original)).unwrap();
+fn encode_literals() {
+    let beam = StandardBeamFile::from_file(test_file("test.beam")).unwrap();
+    let literals = match beam.get_chunk(b"LitT") {
+        Some(StandardChunk::LitT(literals)) => literals,
+        _ => panic!("no LitT chunk"),
+    };
+
+    let terms = literals.to_terms().unwrap();
+    assert_eq!(literals, &chunk::LitTChunk::from_terms(&terms).unwrap());
+}
+
+#[test]
+fn encode_attributes() {
+    let mut beam = StandardBeamFile::from_file(test_file("test.beam")).unwrap();
+    let attributes = match beam.get_chunk(b"Attr") {
+        Some(StandardChunk::Attr(chunk)) => chunk.attributes().unwrap(),
+        _ => panic!("no Attr chunk"),
+    };
+    assert_eq!(
+        vec!["vsn"],
+        attributes
+            .iter()
+            .map(|(name, _)| name.name.as_str())
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        find_attr(&beam),
+        &chunk::AttrChunk::from_attributes(&attributes).unwrap()
+    );
+
+    let mut attributes = attributes;
+    attributes[0].1 = etf::Term::from(etf::List::from(vec![etf::Term::from(
+        etf::FixInteger::from(1),
+    )]));
+    attributes.push((
+        etf::Atom::from("author"),
+        etf::Term::from(etf::List::from(vec![etf::Term::from(etf::Atom::from(
+            "lumen",
+        ))])),
+    ));
+    beam.push_chunk(StandardChunk::Attr(
+        chunk::AttrChunk::from_attributes(&attributes).unwrap(),
+    ));
+
     let mut encoded = Vec::new();
     beam.to_writer(&mut encoded).unwrap();
+    let beam = StandardBeamFile::from_reader(Cursor::new(&encoded)).unwrap();
+    assert_eq!(
+        vec![
+            "Atom", "Code", "StrT", "ImpT", "ExpT", "FunT", "LitT", "LocT", "Attr", "CInf", "Abst",
+            "Line",
+        ],
+        collect_id(&beam.chunks())
+    );
+    assert_eq!(attributes, find_attr(&beam).attributes().unwrap());
+}
+
+#[test]
+fn encode_docs() {
+    let beam = StandardBeamFile::from_file(test_file("Elixir.Unicode.beam")).unwrap();
+    let docs = match beam.get_chunk(b"Docs") {
+        Some(StandardChunk::Docs(docs)) => docs,
+        _ => panic!("no Docs chunk"),
+    };
+
+    let term = docs.to_term().unwrap();
+    assert_eq!(docs, &chunk::DocsChunk::from_term(&term).unwrap());
+}
+
+#[test]
+fn encode_atoms() {
+    let beam = StandardBeamFile::from_file(test_file("Elixir.Unicode.beam")).unwrap();
+    let atoms = match beam.atoms() {
+        Some(StandardChunk::Atom(atoms)) => atoms,
+        _ => panic!("no atom chunk"),
+    };
+    assert!(atoms.is_unicode);
+
+    let mut encoded = Vec::new();
+    atoms.encode(&mut encoded).unwrap();
+    assert_eq!(
+        atoms,
+        &chunk::AtomChunk::decode(Cursor::new(&encoded)).unwrap()
+    );
 
-    assert_eq!(original, encoded);
+    let latin1 = chunk::AtomChunk {
+        is_unicode: false,
+        atoms: vec![parts::Atom {
+            name: "caf\u{e9}".to_string(),
+        }],
+    };
+    let mut encoded = Vec::new();
+    latin1.encode_data(&mut encoded).unwrap();
+    assert_eq!(b"\0\0\0\x01\x04caf\xe9", &encoded[..]);
+    let mut encoded = Vec::new();
+    latin1.encode(&mut encoded).unwrap();
+    assert_eq!(
+        latin1,
+        chunk::AtomChunk::decode(Cursor::new(&encoded)).unwrap()
+    );
+
+    let not_latin1 = chunk::AtomChunk {
+        is_unicode: false,
+        atoms: vec![parts::Atom {
+            name: "\u{1f600}".to_string(),
+        }],
+    };
+    assert!(not_latin1.encode(Vec::new()).is_err());
+}
+
+fn find_attr(beam: &StandardBeamFile) -> &chunk::AttrChunk {
+    match beam.get_chunk(b"Attr") {
+        Some(StandardChunk::Attr(chunk)) => chunk,
+        _ => panic!("no Attr chunk"),
+    }
 }
 
 fn test_file(name: &str) -> PathBuf {
@@ -68,6 +68,11 @@ impl Term {
         codec::Encoder::new(writer).encode(self)
     }
 
+    /// Encodes the term compressed with zlib, like `term_to_binary(Term, [compressed])`.
+    pub fn encode_compressed<W: std::io::Write>(&self, writer: W) -> EncodeResult {
+        codec::Encoder::new(writer).encode_compressed(self)
+    }
+
     pub fn as_match<'a, P>(&'a self, pattern: P) -> pattern::Result<P::Output>
     where
         P: pattern::Pattern<'a>,
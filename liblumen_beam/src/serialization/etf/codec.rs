@@ -6,7 +6,9 @@ use byteorder::BigEndian;
 use byteorder::ReadBytesExt;
 use byteorder::WriteBytesExt;
 
-use libflate::zlib;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
 
 use num::bigint::BigInt;
 
@@ -161,7 +163,7 @@ impl<R: std::io::Read> Decoder<R> {
     }
     fn decode_compressed_term(&mut self) -> DecodeResult {
         let _uncompressed_size = self.reader.read_u32::<BigEndian>()? as usize;
-        let zlib_decoder = zlib::Decoder::new(&mut self.reader)?;
+        let zlib_decoder = ZlibDecoder::new(&mut self.reader);
         let mut decoder = Decoder::new(zlib_decoder);
         decoder.decode_term()
     }
@@ -422,6 +424,28 @@ impl<W: std::io::Write> Encoder<W> {
         self.writer.write_u8(VERSION)?;
         self.encode_term(term)
     }
+    pub fn encode_compressed(mut self, term: &Term) -> EncodeResult {
+        let mut uncompressed = Vec::new();
+        Encoder::new(&mut uncompressed).encode_term(term)?;
+
+        // The default level is the same as `term_to_binary/2` uses, so the output is the same
+        let mut zlib_encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        zlib_encoder.write_all(&uncompressed)?;
+        let compressed = zlib_encoder.finish()?;
+
+        self.writer.write_u8(VERSION)?;
+        // Like `term_to_binary/2`, fall back to the uncompressed term if compressing doesn't
+        // make it smaller
+        if compressed.len() + 5 < uncompressed.len() {
+            self.writer.write_u8(COMPRESSED_TERM)?;
+            self.writer
+                .write_u32::<BigEndian>(uncompressed.len() as u32)?;
+            self.writer.write_all(&compressed)?;
+        } else {
+            self.writer.write_all(&uncompressed)?;
+        }
+        Ok(())
+    }
     fn encode_term(&mut self, term: &Term) -> EncodeResult {
         match *term {
             Term::Atom(ref x) => self.encode_atom(x),
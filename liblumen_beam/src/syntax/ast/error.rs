@@ -41,6 +41,15 @@ pub enum FromBeamError {
     #[fail(display = "debug info is required but not present")]
     NoDebugInfo,
 
+    #[fail(
+        display = "debug info from the {} backend is not supported, only erl_abstract_code",
+        _0
+    )]
+    UnsupportedDebugInfoBackend(String),
+
+    #[fail(display = "term cannot be written as Erlang source: {}", _0)]
+    UnwritableTerm(etf::Term),
+
     #[fail(display = "missing module attribute")]
     NoModuleAttribute,
 
@@ -9,8 +9,6 @@ use crate::serialization::etf::pattern::{Or, Union2, Union3};
 use crate::serialization::etf::pattern::{Pattern, Unmatch};
 use crate::serialization::etf::pattern::{Uint, F64, I32, U32, U64};
 
-use crate::beam::chunk::Chunk;
-
 use crate::syntax::ast::ast::clause;
 use crate::syntax::ast::ast::common;
 use crate::syntax::ast::ast::expr;
@@ -42,20 +40,138 @@ pub struct AbstractCode {
 impl AbstractCode {
     pub fn from_beam_file<P: AsRef<Path>>(path: P) -> FromBeamResult<Self> {
         let beam = crate::beam::reader::RawBeamFile::from_file(path)?;
+        Self::from_beam(&beam)
+    }
+    /// Reads the abstract code from the `Dbgi` chunk written by OTP 20 and later, falling back
+    /// to the `Abst` chunk written by older compilers.
+    pub fn from_beam(beam: &crate::beam::reader::RawBeamFile) -> FromBeamResult<Self> {
+        if let Some(chunk) = beam.get_chunk(b"Dbgi") {
+            let debug_info = etf::Term::decode(std::io::Cursor::new(&chunk.data))?;
+            return Self::from_debug_info(&debug_info);
+        }
+
         let chunk = beam
-            .chunks()
-            .into_iter()
-            .find(|c| c.id() == b"Abst")
+            .get_chunk(b"Abst")
+            .filter(|chunk| !chunk.data.is_empty())
             .ok_or(FromBeamError::NoDebugInfo)?;
         let code = etf::Term::decode(std::io::Cursor::new(&chunk.data))?;
         Ok(AbstractCode { code })
     }
+    /// `{debug_info_v1, Backend, Data}` from a `Dbgi` chunk.  Only the `erl_abstract_code`
+    /// backend stores the abstract code itself, as `{Forms | none, Options}`; other backends,
+    /// such as Elixir's, need their compiler to produce it.
+    fn from_debug_info(debug_info: &etf::Term) -> FromBeamResult<Self> {
+        let (_, backend, data) = debug_info.as_match(("debug_info_v1", atom(), any()))?;
+        if backend != "erl_abstract_code" {
+            return Err(FromBeamError::UnsupportedDebugInfoBackend(backend));
+        }
+
+        let (forms, _) = data.as_match((any(), any()))?;
+        if forms.as_match("none").is_ok() {
+            return Err(FromBeamError::NoDebugInfo);
+        }
+
+        let code = etf::Term::from(etf::Tuple::from(vec![
+            etf::Term::from(etf::Atom::from("raw_abstract_v1")),
+            forms.clone(),
+        ]));
+        Ok(AbstractCode { code })
+    }
     pub fn to_forms(&self) -> FromBeamResult<Vec<form::Form>> {
         let (_, forms) = self
             .code
             .as_match(("raw_abstract_v1", VarList(to!(form::Form))))?;
         Ok(forms)
     }
+    /// Writes the forms as Erlang terms, each terminated by a `.`, which is the textual abstract
+    /// format that `file:consult/1` and the abstract Erlang frontend read.
+    pub fn to_listing(&self) -> FromBeamResult<String> {
+        let (_, forms) = self.code.as_match(("raw_abstract_v1", VarList(any())))?;
+        let mut listing = String::new();
+        for form in forms {
+            write_listing_term(&mut listing, form)?;
+            listing.push_str(".\n");
+        }
+        Ok(listing)
+    }
+}
+
+fn write_listing_term(listing: &mut String, term: &etf::Term) -> FromBeamResult<()> {
+    use std::fmt::Write;
+
+    fn write_elements(listing: &mut String, elements: &[etf::Term]) -> FromBeamResult<()> {
+        for (i, element) in elements.iter().enumerate() {
+            if i != 0 {
+                listing.push(',');
+            }
+            write_listing_term(listing, element)?;
+        }
+        Ok(())
+    }
+
+    match *term {
+        etf::Term::Atom(ref x) => write!(listing, "{}", x).unwrap(),
+        etf::Term::FixInteger(ref x) => write!(listing, "{}", x).unwrap(),
+        etf::Term::BigInteger(ref x) => write!(listing, "{}", x).unwrap(),
+        // `Display` drops the fraction of integral floats, which would read back as integers
+        etf::Term::Float(ref x) => {
+            let float = format!("{:?}", x.value);
+            if float.contains('.') {
+                listing.push_str(&float);
+            } else {
+                match float.find('e') {
+                    Some(e) => {
+                        listing.push_str(&float[..e]);
+                        listing.push_str(".0");
+                        listing.push_str(&float[e..]);
+                    }
+                    None => {
+                        listing.push_str(&float);
+                        listing.push_str(".0");
+                    }
+                }
+            }
+        }
+        etf::Term::Binary(ref x) => write!(listing, "{}", x).unwrap(),
+        etf::Term::BitBinary(ref x) => write!(listing, "{}", x).unwrap(),
+        etf::Term::List(ref x) => {
+            listing.push('[');
+            write_elements(listing, &x.elements)?;
+            listing.push(']');
+        }
+        etf::Term::ImproperList(ref x) => {
+            listing.push('[');
+            write_elements(listing, &x.elements)?;
+            listing.push('|');
+            write_listing_term(listing, &x.last)?;
+            listing.push(']');
+        }
+        etf::Term::Tuple(ref x) => {
+            listing.push('{');
+            write_elements(listing, &x.elements)?;
+            listing.push('}');
+        }
+        etf::Term::Map(ref x) => {
+            listing.push_str("#{");
+            for (i, (key, value)) in x.entries.iter().enumerate() {
+                if i != 0 {
+                    listing.push(',');
+                }
+                write_listing_term(listing, key)?;
+                listing.push_str("=>");
+                write_listing_term(listing, value)?;
+            }
+            listing.push('}');
+        }
+        etf::Term::Pid(_)
+        | etf::Term::Port(_)
+        | etf::Term::Reference(_)
+        | etf::Term::ExternalFun(_)
+        | etf::Term::InternalFun(_) => {
+            return Err(FromBeamError::UnwritableTerm(term.clone()));
+        }
+    }
+    Ok(())
 }
 
 trait FromTerm<'a> {
@@ -9,3 +9,101 @@ fn it_works() {
         })
         .unwrap();
 }
+
+#[test]
+fn debug_info_chunk() {
+    use crate::beam::reader::chunk::{Chunk, RawChunk};
+    use crate::beam::reader::RawBeamFile;
+    use crate::serialization::etf;
+    use crate::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    let abst_beam = RawBeamFile::from_file("tests/testdata/ast/test.beam").unwrap();
+    let abst_code = AbstractCode::from_beam(&abst_beam).unwrap();
+    let forms = match abst_code.code {
+        etf::Term::Tuple(ref tuple) => tuple.elements[1].clone(),
+        ref other => panic!("{} is not {{raw_abstract_v1, Forms}}", other),
+    };
+
+    // OTP 20 and later write the forms in `Dbgi` instead of `Abst`
+    let debug_info = etf::Term::from(etf::Tuple::from(vec![
+        etf::Term::from(etf::Atom::from("debug_info_v1")),
+        etf::Term::from(etf::Atom::from("erl_abstract_code")),
+        etf::Term::from(etf::Tuple::from(vec![
+            forms,
+            etf::Term::from(etf::List::nil()),
+        ])),
+    ]));
+    let mut data = Vec::new();
+    debug_info.encode(&mut data).unwrap();
+
+    let mut dbgi_beam = RawBeamFile::new();
+    for chunk in abst_beam.chunks() {
+        if chunk.id() != b"Abst" {
+            dbgi_beam.push_chunk(RawChunk {
+                id: *chunk.id(),
+                data: chunk.data.clone(),
+            });
+        }
+    }
+    dbgi_beam.push_chunk(RawChunk { id: *b"Dbgi", data });
+
+    let dbgi_code = AbstractCode::from_beam(&dbgi_beam).unwrap();
+    assert_eq!(abst_code.code, dbgi_code.code);
+    assert!(dbgi_code.to_forms().is_ok());
+}
+
+#[test]
+fn without_debug_info() {
+    match AST::from_beam_file("tests/testdata/simple.beam") {
+        Err(FromBeamError::NoDebugInfo) => (),
+        other => panic!("expected NoDebugInfo, got {:?}", other),
+    }
+}
+
+#[test]
+fn with_elixir_debug_info() {
+    match AST::from_beam_file("tests/testdata/reader/Elixir.Unicode.beam") {
+        Err(FromBeamError::UnsupportedDebugInfoBackend(ref backend)) => {
+            assert_eq!(backend, "elixir_erl")
+        }
+        other => panic!("expected UnsupportedDebugInfoBackend, got {:?}", other),
+    }
+}
+
+#[test]
+fn listing() {
+    use crate::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    let code = AbstractCode::from_beam_file("tests/testdata/ast/test.beam").unwrap();
+    let listing = code.to_listing().unwrap();
+
+    assert!(listing.starts_with("{'attribute',1,'file',"));
+    assert!(listing.contains("\n{'attribute',1,'module','test'}.\n"));
+    assert!(listing.contains("{'attribute',9,'export',[{'literals',0}]}.\n"));
+    assert!(listing.ends_with("}.\n"));
+}
+
+#[test]
+fn listing_floats() {
+    use crate::serialization::etf;
+    use crate::syntax::ast::format::raw_abstract_v1::AbstractCode;
+
+    let float = |value: f64| {
+        etf::Term::from(etf::Tuple::from(vec![
+            etf::Term::from(etf::Atom::from("float")),
+            etf::Term::from(etf::FixInteger::from(1)),
+            etf::Term::from(etf::Float::from(value)),
+        ]))
+    };
+    let code = AbstractCode {
+        code: etf::Term::from(etf::Tuple::from(vec![
+            etf::Term::from(etf::Atom::from("raw_abstract_v1")),
+            etf::Term::from(etf::List::from(vec![float(1.0), float(1e100), float(0.5)])),
+        ])),
+    };
+
+    assert_eq!(
+        code.to_listing().unwrap(),
+        "{'float',1,1.0}.\n{'float',1,1.0e100}.\n{'float',1,0.5}.\n"
+    );
+}
@@ -83,3 +83,22 @@ impl FunctionSymbol {
 // It is safe to do so, since the data is static and lives for the life of the program
 unsafe impl Sync for FunctionSymbol {}
 unsafe impl Send for FunctionSymbol {}
+
+/// This struct represents the serialized form of a location table entry
+///
+/// Like `FunctionSymbol`, this is laid out in memory to be identical to
+/// `ModuleFunctionArity`, followed by where the function is defined.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct FunctionLocation {
+    /// Module name atom
+    pub module: usize,
+    /// Function name atom
+    pub function: usize,
+    /// The arity of the function
+    pub arity: u8,
+    /// A null-terminated string containing the path of the source file
+    pub file: *const u8,
+    /// The line the function is defined on
+    pub line: u32,
+}
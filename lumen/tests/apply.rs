@@ -0,0 +1,105 @@
+mod apply {
+    use std::process::{Command, Stdio};
+    use std::sync::Once;
+
+    #[test]
+    fn remote_call_with_variable_module_calls_function() {
+        ensure_compiled();
+
+        let apply_output = Command::new("./apply")
+            .arg("remote")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&apply_output.stdout), "4\n");
+    }
+
+    #[test]
+    fn apply_3_with_variable_module_calls_function() {
+        ensure_compiled();
+
+        let apply_output = Command::new("./apply")
+            .arg("apply")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&apply_output.stdout), "6\n");
+    }
+
+    #[test]
+    fn remote_call_to_undefined_function_raises_undef() {
+        ensure_compiled();
+
+        let apply_output = Command::new("./apply")
+            .arg("undef")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&apply_output.stdout), "undef\n");
+    }
+
+    #[test]
+    fn fun_with_variable_module_calls_function() {
+        ensure_compiled();
+
+        let apply_output = Command::new("./apply")
+            .arg("fun")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert_eq!(String::from_utf8_lossy(&apply_output.stdout), "10\n");
+    }
+
+    static COMPILED: Once = Once::new();
+
+    fn ensure_compiled() {
+        COMPILED.call_once(|| {
+            compile();
+        })
+    }
+
+    fn compile() {
+        std::fs::create_dir_all("_build").unwrap();
+
+        let mut command = Command::new("../bin/lumen");
+
+        command
+            .arg("compile")
+            .arg("--output-dir")
+            .arg("_build")
+            .arg("-o")
+            .arg("apply")
+            .arg("-lc");
+
+        add_link_args(&mut command);
+
+        let compile_output = command
+            .arg("tests/apply/init.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lm");
+    }
+}
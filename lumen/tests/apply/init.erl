@@ -0,0 +1,29 @@
+-module(init).
+-export([start/0, double/1]).
+-import(erlang, [print/1]).
+-spec start() -> ok | error.
+start() ->
+  Module = module(),
+  case init:get_plain_arguments() of
+    [_, <<"remote">> | _] ->
+      print(Module:double(2));
+    [_, <<"apply">> | _] ->
+      print(apply(Module, double, [3]));
+    [_, <<"undef">> | _] ->
+      try Module:undefined(4) of
+        _ -> print(defined)
+      catch
+        error:undef -> print(undef)
+      end;
+    [_, <<"fun">> | _] ->
+      Fun = fun Module:double/1,
+      print(Fun(5));
+    _ ->
+      print(nothing)
+  end.
+-spec double(integer()) -> integer().
+double(N) ->
+  N * 2.
+-spec module() -> module().
+module() ->
+  init.
@@ -0,0 +1,82 @@
+mod debuginfo {
+    use std::fs;
+    use std::path::PathBuf;
+    use std::process::{Command, Stdio};
+
+    #[test]
+    fn with_full_debuginfo_emits_compile_unit_subprograms_and_locations() {
+        let llvm_ir = compile("2");
+
+        assert!(llvm_ir.contains("DICompileUnit"), "llvm_ir = {}", llvm_ir);
+        assert!(
+            llvm_ir.contains("!DISubprogram(name: \"init:double/1\""),
+            "llvm_ir = {}",
+            llvm_ir
+        );
+        assert!(
+            llvm_ir.contains("!DILocation(line:"),
+            "llvm_ir = {}",
+            llvm_ir
+        );
+    }
+
+    #[test]
+    fn without_debuginfo_emits_no_debug_metadata() {
+        let llvm_ir = compile("0");
+
+        assert!(!llvm_ir.contains("DICompileUnit"), "llvm_ir = {}", llvm_ir);
+        assert!(!llvm_ir.contains("DISubprogram"), "llvm_ir = {}", llvm_ir);
+        assert!(!llvm_ir.contains("DILocation"), "llvm_ir = {}", llvm_ir);
+    }
+
+    /// Compiles `tests/debuginfo/init.erl` with `-C debuginfo=<level>`, returning the LLVM IR
+    /// emitted for it
+    fn compile(level: &str) -> String {
+        // Each level has its own directory, as the tests run in parallel
+        let output_dir = PathBuf::from("_build").join(format!("debuginfo_{}", level));
+        let _ = fs::remove_dir_all(&output_dir);
+        fs::create_dir_all(&output_dir).unwrap();
+
+        let mut command = Command::new("../bin/lumen");
+
+        command
+            .arg("compile")
+            .arg("--output-dir")
+            .arg(&output_dir)
+            .arg("-o")
+            .arg(output_dir.join("init"))
+            .arg("--emit=llvm-ir,obj,link")
+            .arg("-C")
+            .arg(format!("debuginfo={}", level))
+            .arg("-lc");
+
+        add_link_args(&mut command);
+
+        let compile_output = command
+            .arg("tests/debuginfo/init.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+
+        fs::read_to_string(output_dir.join("init.ll")).unwrap()
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lm");
+    }
+}
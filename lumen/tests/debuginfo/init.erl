@@ -0,0 +1,13 @@
+-module(init).
+
+-export([start/0]).
+
+-import(erlang, [print/1]).
+
+-spec start() -> ok | error.
+start() ->
+  print(double(21)).
+
+-spec double(integer()) -> integer().
+double(N) ->
+  N * 2.
@@ -0,0 +1,129 @@
+mod embed {
+    use std::process::{Command, Stdio};
+    use std::sync::Once;
+
+    #[test]
+    fn start_and_stop_print_nothing() {
+        let embed_output = run("start");
+
+        assert_eq!(String::from_utf8_lossy(&embed_output.stdout), "started\n");
+        assert_eq!(String::from_utf8_lossy(&embed_output.stderr), "");
+    }
+
+    #[test]
+    fn call_returns_result() {
+        let embed_output = run("call");
+
+        assert_eq!(String::from_utf8_lossy(&embed_output.stdout), "3\n");
+    }
+
+    #[test]
+    fn made_terms_round_trip_through_call() {
+        let embed_output = run("round_trip");
+
+        assert_eq!(
+            String::from_utf8_lossy(&embed_output.stdout),
+            "ok bin -1 9223372036854775807\n"
+        );
+    }
+
+    #[test]
+    fn call_to_undefined_function_returns_undef() {
+        let embed_output = run("undef");
+
+        // `LUMEN_UNDEF` for the wrong arity and for an unknown function
+        assert_eq!(String::from_utf8_lossy(&embed_output.stdout), "6 6\n");
+    }
+
+    #[test]
+    fn none_elements_and_arguments_are_rejected() {
+        let embed_output = run("none");
+
+        // `LUMEN_NONE` tuple and list, and `LUMEN_BADARG` call
+        assert_eq!(String::from_utf8_lossy(&embed_output.stdout), "1 1 1\n");
+    }
+
+    fn run(test: &str) -> std::process::Output {
+        ensure_compiled();
+
+        let embed_output = Command::new("./embed")
+            .arg(test)
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            embed_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&embed_output.stdout),
+            String::from_utf8_lossy(&embed_output.stderr)
+        );
+
+        embed_output
+    }
+
+    static COMPILED: Once = Once::new();
+
+    fn ensure_compiled() {
+        COMPILED.call_once(|| {
+            compile();
+        })
+    }
+
+    fn compile() {
+        std::fs::create_dir_all("_build/embed").unwrap();
+
+        let compile_output = Command::new("../bin/lumen")
+            .arg("compile")
+            .arg("--crate-type")
+            .arg("staticlib")
+            .arg("--output-dir")
+            .arg("_build/embed")
+            .arg("-o")
+            .arg("_build/embed/libembed.a")
+            .arg("tests/embed/embed.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            compile_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&compile_output.stdout),
+            String::from_utf8_lossy(&compile_output.stderr)
+        );
+
+        // The library is linked into a C program through the `lumen.h` written next to it
+        let mut command = Command::new("cc");
+
+        command
+            .arg("-I_build/embed")
+            .arg("-o")
+            .arg("embed")
+            .arg("tests/embed/main.c")
+            .arg("_build/embed/libembed.a");
+
+        add_link_args(&mut command);
+
+        let cc_output = command.stdin(Stdio::null()).output().unwrap();
+
+        assert!(
+            cc_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&cc_output.stdout),
+            String::from_utf8_lossy(&cc_output.stderr)
+        );
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn add_link_args(_command: &mut Command) {}
+
+    #[cfg(target_os = "linux")]
+    fn add_link_args(command: &mut Command) {
+        command
+            .arg("-lunwind")
+            .arg("-lpthread")
+            .arg("-ldl")
+            .arg("-lm");
+    }
+}
@@ -0,0 +1,8 @@
+-module(embed).
+-export([add/2, echo/1]).
+-spec add(integer(), integer()) -> integer().
+add(Left, Right) ->
+  Left + Right.
+-spec echo(term()) -> term().
+echo(Term) ->
+  Term.
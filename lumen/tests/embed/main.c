@@ -0,0 +1,105 @@
+#include <stdio.h>
+#include <string.h>
+
+#include "lumen.h"
+
+static int start(lumen_env_t *env) {
+    (void)env;
+    printf("started\n");
+    return 0;
+}
+
+static int call(lumen_env_t *env) {
+    lumen_term_t args[] = {lumen_make_int(env, 1), lumen_make_int(env, 2)};
+    lumen_term_t result;
+    int64_t sum;
+
+    lumen_status_t status = lumen_call(env, "embed", "add", args, 2, &result);
+    if (status != LUMEN_OK || !lumen_get_int(result, &sum)) {
+        return 1;
+    }
+    printf("%lld\n", (long long)sum);
+    return 0;
+}
+
+static int round_trip(lumen_env_t *env) {
+    lumen_term_t list_elements[] = {lumen_make_int(env, -1),
+                                    lumen_make_int(env, INT64_MAX)};
+    lumen_term_t tuple_elements[] = {
+        lumen_make_atom(env, "ok"),
+        lumen_make_binary(env, (const uint8_t *)"bin", 3),
+        lumen_make_list(env, list_elements, 2),
+    };
+    lumen_term_t args[] = {lumen_make_tuple(env, tuple_elements, 3)};
+    lumen_term_t result;
+    const lumen_term_t *elements;
+    size_t arity;
+    const char *name;
+    size_t name_len;
+    const uint8_t *data;
+    size_t data_len;
+    lumen_term_t head, tail;
+    int64_t first, second;
+
+    if (lumen_call(env, "embed", "echo", args, 1, &result) != LUMEN_OK ||
+        !lumen_get_tuple(result, &elements, &arity) || arity != 3 ||
+        !lumen_get_atom(elements[0], &name, &name_len) ||
+        !lumen_get_binary(elements[1], &data, &data_len) ||
+        !lumen_get_list_cell(elements[2], &head, &tail) ||
+        !lumen_get_int(head, &first) ||
+        !lumen_get_list_cell(tail, &head, &tail) ||
+        !lumen_get_int(head, &second) || !lumen_is_empty_list(tail)) {
+        return 1;
+    }
+    printf("%.*s %.*s %lld %lld\n", (int)name_len, name, (int)data_len,
+           (const char *)data, (long long)first, (long long)second);
+    return 0;
+}
+
+static int undef(lumen_env_t *env) {
+    lumen_term_t args[] = {lumen_make_int(env, 1)};
+    lumen_term_t result;
+
+    printf("%d %d\n", lumen_call(env, "embed", "add", args, 1, &result),
+           lumen_call(env, "embed", "undefined_function", args, 1, &result));
+    return 0;
+}
+
+static int none(lumen_env_t *env) {
+    lumen_term_t elements[] = {lumen_make_int(env, 1), LUMEN_NONE};
+    lumen_term_t result;
+
+    printf("%d %d %d\n", lumen_make_tuple(env, elements, 2) == LUMEN_NONE,
+           lumen_make_list(env, elements, 2) == LUMEN_NONE,
+           lumen_call(env, "embed", "add", elements, 2, &result));
+    return 0;
+}
+
+int main(int argc, char **argv) {
+    static const struct {
+        const char *name;
+        int (*run)(lumen_env_t *env);
+    } tests[] = {{"start", start},
+                 {"call", call},
+                 {"round_trip", round_trip},
+                 {"undef", undef},
+                 {"none", none}};
+    int failed = 1;
+
+    if (argc != 2 || lumen_start(0, NULL) != LUMEN_OK) {
+        return 1;
+    }
+
+    lumen_env_t *env = lumen_env_new();
+    for (size_t i = 0; i < sizeof(tests) / sizeof(tests[0]); i++) {
+        if (strcmp(argv[1], tests[i].name) == 0) {
+            failed = tests[i].run(env);
+        }
+    }
+    lumen_env_free(env);
+
+    if (lumen_stop() != LUMEN_OK) {
+        return 1;
+    }
+    return failed;
+}
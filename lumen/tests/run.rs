@@ -0,0 +1,30 @@
+mod run {
+    use std::process::{Command, Stdio};
+
+    /// The exceptions are raised in compiled code, in a function it calls, and in the runtime,
+    /// so they unwind through the JIT compiled frames and the runtime linked with them
+    #[test]
+    fn catches_exceptions_raised_through_jit_compiled_code() {
+        let run_output = Command::new("../bin/lumen")
+            .arg("run")
+            .arg("tests/run/init.erl")
+            .stdin(Stdio::null())
+            .output()
+            .unwrap();
+
+        assert!(
+            run_output.status.success(),
+            "stdout = {}\nstderr = {}",
+            String::from_utf8_lossy(&run_output.stdout),
+            String::from_utf8_lossy(&run_output.stderr)
+        );
+
+        // The program's output follows whatever the compiler reports before running it
+        let stdout = String::from_utf8_lossy(&run_output.stdout);
+        assert!(
+            stdout.ends_with("thrown\nbadmatch\nundef\n"),
+            "stdout = {}",
+            stdout
+        );
+    }
+}
@@ -0,0 +1,28 @@
+-module(init).
+-export([start/0]).
+-import(erlang, [print/1]).
+-spec start() -> ok | error.
+start() ->
+  try throw(thrown) of
+    _ -> print(returned)
+  catch
+    throw:Thrown -> print(Thrown)
+  end,
+  try match_ok(error) of
+    _ -> print(returned)
+  catch
+    error:{badmatch, error} -> print(badmatch)
+  end,
+  Module = module(),
+  try Module:undefined() of
+    _ -> print(returned)
+  catch
+    error:undef -> print(undef)
+  end.
+-spec match_ok(term()) -> term().
+match_ok(Term) ->
+  {ok, Value} = Term,
+  Value.
+-spec module() -> module().
+module() ->
+  init.
@@ -0,0 +1,8 @@
+pub mod get_env_2;
+pub mod get_env_3;
+
+use liblumen_alloc::erts::term::prelude::Atom;
+
+fn module() -> Atom {
+    Atom::try_from_str("application").unwrap()
+}
@@ -0,0 +1,26 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+#[native_implemented_function(get_env/2)]
+pub fn native(process: &Process, application: Term, key: Term) -> exception::Result<Term> {
+    let application_atom = term_try_into_atom!(application)?;
+    let key_atom = term_try_into_atom!(key)?;
+
+    match lumen_rt_full::application::get_env(process, application_atom, key_atom) {
+        Some(value) => process
+            .tuple_from_slice(&[atom!("ok"), value])
+            .map_err(From::from),
+        None => Ok(atom!("undefined")),
+    }
+}
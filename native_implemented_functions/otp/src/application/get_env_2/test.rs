@@ -0,0 +1,98 @@
+use std::convert::TryInto;
+
+use proptest::strategy::Just;
+use proptest::{prop_assert, prop_assert_eq};
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::application::get_env_2::native;
+use crate::test::strategy;
+
+#[test]
+fn without_atom_application_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_atom(arc_process.clone()),
+                strategy::term::atom(),
+            )
+        },
+        |(arc_process, application, key)| {
+            prop_assert_is_not_atom!(native(&arc_process, application, key), application);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_atom_application_without_atom_key_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::atom(),
+                strategy::term::is_not_atom(arc_process.clone()),
+            )
+        },
+        |(arc_process, application, key)| {
+            prop_assert_is_not_atom!(native(&arc_process, application, key), key);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn without_key_set_returns_undefined() {
+    crate::test::with_process(|process| {
+        assert_eq!(
+            native(
+                process,
+                Atom::str_to_term("get_env_2_without_key_set"),
+                Atom::str_to_term("key")
+            ),
+            Ok(atom!("undefined"))
+        );
+    });
+}
+
+#[test]
+fn with_key_set_returns_ok_tuple_with_copy_of_value() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term(arc_process.clone()),
+            )
+        },
+        |(arc_process, value)| {
+            let application = Atom::from_str("get_env_2_with_key_set");
+            let key = Atom::from_str("key");
+
+            lumen_rt_full::application::set_env(application, key, value).unwrap();
+
+            let result = native(
+                &arc_process,
+                application.encode().unwrap(),
+                key.encode().unwrap(),
+            );
+
+            prop_assert!(result.is_ok());
+
+            let tuple_result: Result<Boxed<Tuple>, _> = result.unwrap().try_into();
+
+            prop_assert!(tuple_result.is_ok());
+
+            let tuple = tuple_result.unwrap();
+
+            prop_assert_eq!(tuple.len(), 2);
+            prop_assert_eq!(tuple[0], atom!("ok"));
+            prop_assert_eq!(tuple[1], value);
+
+            Ok(())
+        },
+    );
+}
@@ -0,0 +1,82 @@
+use proptest::prop_assert_eq;
+use proptest::strategy::Just;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::application::get_env_3::native;
+use crate::test::strategy;
+
+#[test]
+fn without_atom_application_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_atom(arc_process.clone()),
+                strategy::term::atom(),
+                strategy::term(arc_process.clone()),
+            )
+        },
+        |(arc_process, application, key, default)| {
+            prop_assert_is_not_atom!(native(&arc_process, application, key, default), application);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn without_key_set_returns_default() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term(arc_process.clone()),
+            )
+        },
+        |(arc_process, default)| {
+            prop_assert_eq!(
+                native(
+                    &arc_process,
+                    Atom::str_to_term("get_env_3_without_key_set"),
+                    Atom::str_to_term("key"),
+                    default
+                ),
+                Ok(default)
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_key_set_returns_copy_of_value() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term(arc_process.clone()),
+                strategy::term(arc_process.clone()),
+            )
+        },
+        |(arc_process, value, default)| {
+            let application = Atom::from_str("get_env_3_with_key_set");
+            let key = Atom::from_str("key");
+
+            lumen_rt_full::application::set_env(application, key, value).unwrap();
+
+            prop_assert_eq!(
+                native(
+                    &arc_process,
+                    application.encode().unwrap(),
+                    key.encode().unwrap(),
+                    default
+                ),
+                Ok(value)
+            );
+
+            Ok(())
+        },
+    );
+}
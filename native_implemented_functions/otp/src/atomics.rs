@@ -0,0 +1,136 @@
+pub mod add_3;
+pub mod compare_exchange_4;
+pub mod get_2;
+pub mod new_2;
+pub mod put_3;
+
+use std::convert::TryInto;
+use std::sync::atomic::AtomicU64;
+
+use anyhow::*;
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+
+use liblumen_alloc::erts::exception::{AllocResult, InternalResult};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use lumen_rt_core::context::term_is_not_type;
+
+/// The array behind an `atomics_ref()`.
+///
+/// The array is only reachable through a `Resource`, which every copy of the reference shares, so
+/// processes on any scheduler update the same integers without locking.
+pub struct Atomics {
+    signed: bool,
+    array: Box<[AtomicU64]>,
+}
+
+impl Atomics {
+    pub fn new(arity: usize, signed: bool) -> Self {
+        Self {
+            signed,
+            array: (0..arity).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+
+    pub fn is_signed(&self) -> bool {
+        self.signed
+    }
+
+    pub fn len(&self) -> usize {
+        self.array.len()
+    }
+
+    /// The atomic at the one-based `index`.
+    pub(crate) fn atomic(&self, index: Term) -> InternalResult<&AtomicU64> {
+        let index_usize: usize = index
+            .try_into()
+            .with_context(|| term_is_not_type("index", index, "a positive integer"))?;
+
+        if 1 <= index_usize && index_usize <= self.len() {
+            Ok(&self.array[index_usize - 1])
+        } else {
+            Err(anyhow!("index ({}) is not between 1 and {}", index, self.len()).into())
+        }
+    }
+
+    /// The integer stored as `bits`.
+    pub(crate) fn bits_to_term(&self, process: &Process, bits: u64) -> AllocResult<Term> {
+        if self.signed {
+            process.integer(bits as i64)
+        } else {
+            process.integer(bits)
+        }
+    }
+
+    /// The bits to store for the integer `value`, which must fit in the array.
+    pub(crate) fn term_try_into_bits(&self, name: &str, value: Term) -> InternalResult<u64> {
+        let (option_bits, r#type) = if self.signed {
+            (
+                term_to_big_int(value).and_then(|big_int| big_int.to_i64().map(|i| i as u64)),
+                "a signed 64-bit integer",
+            )
+        } else {
+            (
+                term_to_big_int(value).and_then(|big_int| big_int.to_u64()),
+                "an unsigned 64-bit integer",
+            )
+        };
+
+        option_bits.ok_or_else(|| anyhow!(term_is_not_type(name, value, r#type)).into())
+    }
+}
+
+/// The resource behind `atomics_ref` if it refers to `Atomics`.
+///
+/// The `Resource` has to be kept while the `Atomics` are used, as it is what keeps them alive.
+fn term_try_into_atomics(atomics_ref: Term) -> InternalResult<Resource> {
+    term_try_into_resource::<Atomics>("atomics_ref", atomics_ref, "an atomics_ref()")
+}
+
+/// The resource behind `term` if it refers to a `T`.
+pub(crate) fn term_try_into_resource<T: 'static>(
+    name: &str,
+    term: Term,
+    r#type: &str,
+) -> InternalResult<Resource> {
+    let boxed: Boxed<Resource> = term
+        .try_into()
+        .with_context(|| term_is_not_type(name, term, r#type))?;
+    let resource: Resource = boxed.into();
+
+    if resource.is::<T>() {
+        Ok(resource)
+    } else {
+        Err(anyhow!(term_is_not_type(name, term, r#type)).into())
+    }
+}
+
+/// The bits to add for `increment`, which wraps around like two's complement addition, so it
+/// can be any integer that fits in either a signed or an unsigned 64-bit integer.
+pub(crate) fn term_try_into_increment_bits(increment: Term) -> InternalResult<u64> {
+    term_to_big_int(increment)
+        .and_then(|big_int| {
+            big_int
+                .to_i64()
+                .map(|i| i as u64)
+                .or_else(|| big_int.to_u64())
+        })
+        .ok_or_else(|| {
+            anyhow!(term_is_not_type(
+                "increment",
+                increment,
+                "an integer that fits in 64 bits"
+            ))
+            .into()
+        })
+}
+
+fn module() -> Atom {
+    Atom::try_from_str("atomics").unwrap()
+}
+
+fn term_to_big_int(term: Term) -> Option<BigInt> {
+    term.try_into().ok()
+}
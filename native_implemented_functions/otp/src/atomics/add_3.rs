@@ -0,0 +1,24 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::atomic::Ordering;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{term_try_into_atomics, term_try_into_increment_bits, Atomics};
+
+/// Wraps around on overflow, in both signed and unsigned arrays.
+#[native_implemented_function(add/3)]
+pub fn native(atomics_ref: Term, index: Term, increment: Term) -> exception::Result<Term> {
+    let resource = term_try_into_atomics(atomics_ref)?;
+    let atomics: &Atomics = resource.downcast_ref().unwrap();
+    let atomic = atomics.atomic(index)?;
+    let bits = term_try_into_increment_bits(increment)?;
+    atomic.fetch_add(bits, Ordering::SeqCst);
+
+    Ok(atom!("ok"))
+}
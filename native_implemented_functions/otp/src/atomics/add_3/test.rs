@@ -0,0 +1,65 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+use crate::atomics::add_3::native;
+use crate::atomics::{get_2, new_2, put_3};
+use crate::test::{process, with_process};
+
+#[test]
+fn is_seen_by_other_processes_sharing_the_reference() {
+    with_process(|process| {
+        let atomics_ref = new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+        let other_process = process::default();
+        let other_atomics_ref = atomics_ref.clone_to_process(&other_process);
+        let index = process.integer(1).unwrap();
+
+        assert_eq!(
+            native(other_atomics_ref, index, process.integer(5).unwrap()),
+            Ok(atom!("ok"))
+        );
+        assert_eq!(
+            get_2::native(process, atomics_ref, index),
+            Ok(process.integer(5).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_overflow_wraps_around() {
+    with_process(|process| {
+        let atomics_ref = new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+        let index = process.integer(1).unwrap();
+        put_3::native(
+            atomics_ref,
+            index,
+            process.integer(i64::max_value()).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            native(atomics_ref, index, process.integer(1).unwrap()),
+            Ok(atom!("ok"))
+        );
+        assert_eq!(
+            get_2::native(process, atomics_ref, index),
+            Ok(process.integer(i64::min_value()).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_increment_that_does_not_fit_in_64_bits_errors_badarg() {
+    with_process(|process| {
+        let atomics_ref = new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+        let increment = process.integer(u128::from(u64::max_value()) + 1).unwrap();
+
+        assert_badarg!(
+            native(atomics_ref, process.integer(1).unwrap(), increment),
+            format!(
+                "increment ({}) is not an integer that fits in 64 bits",
+                increment
+            )
+        );
+    });
+}
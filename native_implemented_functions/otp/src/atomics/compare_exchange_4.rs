@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::atomic::Ordering;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{term_try_into_atomics, Atomics};
+
+/// Returns `ok` if `desired` was stored, otherwise the value that did not match `expected`.
+#[native_implemented_function(compare_exchange/4)]
+pub fn native(
+    process: &Process,
+    atomics_ref: Term,
+    index: Term,
+    expected: Term,
+    desired: Term,
+) -> exception::Result<Term> {
+    let resource = term_try_into_atomics(atomics_ref)?;
+    let atomics: &Atomics = resource.downcast_ref().unwrap();
+    let atomic = atomics.atomic(index)?;
+    let expected_bits = atomics.term_try_into_bits("expected", expected)?;
+    let desired_bits = atomics.term_try_into_bits("desired", desired)?;
+
+    match atomic.compare_exchange(
+        expected_bits,
+        desired_bits,
+        Ordering::SeqCst,
+        Ordering::SeqCst,
+    ) {
+        Ok(_) => Ok(atom!("ok")),
+        Err(actual_bits) => atomics
+            .bits_to_term(process, actual_bits)
+            .map_err(From::from),
+    }
+}
@@ -0,0 +1,48 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::atomics::compare_exchange_4::native;
+use crate::atomics::{get_2, new_2};
+use crate::test::with_process;
+
+#[test]
+fn with_expected_value_stores_desired_value() {
+    with_process(|process| {
+        let atomics_ref = new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+        let index = process.integer(1).unwrap();
+        let desired = process.integer(7).unwrap();
+
+        assert_eq!(
+            native(
+                process,
+                atomics_ref,
+                index,
+                process.integer(0).unwrap(),
+                desired
+            ),
+            Ok(atom!("ok"))
+        );
+        assert_eq!(get_2::native(process, atomics_ref, index), Ok(desired));
+    });
+}
+
+#[test]
+fn without_expected_value_returns_actual_value() {
+    with_process(|process| {
+        let atomics_ref = new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+        let index = process.integer(1).unwrap();
+        let zero = process.integer(0).unwrap();
+
+        assert_eq!(
+            native(
+                process,
+                atomics_ref,
+                index,
+                process.integer(1).unwrap(),
+                process.integer(2).unwrap()
+            ),
+            Ok(zero)
+        );
+        assert_eq!(get_2::native(process, atomics_ref, index), Ok(zero));
+    });
+}
@@ -0,0 +1,21 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::atomic::Ordering;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{term_try_into_atomics, Atomics};
+
+#[native_implemented_function(get/2)]
+pub fn native(process: &Process, atomics_ref: Term, index: Term) -> exception::Result<Term> {
+    let resource = term_try_into_atomics(atomics_ref)?;
+    let atomics: &Atomics = resource.downcast_ref().unwrap();
+    let bits = atomics.atomic(index)?.load(Ordering::SeqCst);
+
+    atomics.bits_to_term(process, bits).map_err(From::from)
+}
@@ -0,0 +1,35 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::atomics::get_2::native;
+use crate::atomics::new_2;
+use crate::counters;
+use crate::test::with_process;
+
+#[test]
+fn with_index_out_of_range_errors_badarg() {
+    with_process(|process| {
+        let atomics_ref = new_2::native(process, process.integer(2).unwrap(), Term::NIL).unwrap();
+
+        assert_badarg!(
+            native(process, atomics_ref, process.integer(0).unwrap()),
+            "index (0) is not between 1 and 2"
+        );
+        assert_badarg!(
+            native(process, atomics_ref, process.integer(3).unwrap()),
+            "index (3) is not between 1 and 2"
+        );
+    });
+}
+
+#[test]
+fn with_counters_ref_errors_badarg() {
+    with_process(|process| {
+        let counters_ref =
+            counters::new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+
+        assert_badarg!(
+            native(process, counters_ref, process.integer(1).unwrap()),
+            "is not an atomics_ref()"
+        );
+    });
+}
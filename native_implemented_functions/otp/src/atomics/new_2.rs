@@ -0,0 +1,78 @@
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::{self, InternalResult};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::context::{term_is_not_type, term_try_into_bool};
+use lumen_rt_core::proplist::TryPropListFromTermError;
+
+use super::Atomics;
+
+/// `Opts` can only be `[{signed, boolean()}]`, where signed is the default.
+#[native_implemented_function(new/2)]
+pub fn native(process: &Process, arity: Term, opts: Term) -> exception::Result<Term> {
+    let arity_usize: usize = arity
+        .try_into()
+        .with_context(|| term_is_not_type("arity", arity, "a positive integer"))?;
+
+    if arity_usize == 0 {
+        return Err(anyhow!(term_is_not_type("arity", arity, "a positive integer")).into());
+    }
+
+    let signed = term_try_into_signed(opts)?;
+
+    process
+        .resource(Box::new(Atomics::new(arity_usize, signed)))
+        .map_err(From::from)
+}
+
+// Private
+
+const SUPPORTED_OPTIONS_CONTEXT: &str = "the only supported option is {signed, boolean()}";
+
+fn term_try_into_signed(opts: Term) -> InternalResult<bool> {
+    let mut signed = true;
+    let mut opts_term = opts;
+
+    loop {
+        match opts_term.decode()? {
+            TypedTerm::Nil => return Ok(signed),
+            TypedTerm::List(cons) => {
+                signed = put_option_term(cons.head).context(SUPPORTED_OPTIONS_CONTEXT)?;
+                opts_term = cons.tail;
+            }
+            _ => {
+                return Err(ImproperListError)
+                    .context(SUPPORTED_OPTIONS_CONTEXT)
+                    .map_err(From::from)
+            }
+        }
+    }
+}
+
+fn put_option_term(option: Term) -> anyhow::Result<bool> {
+    let tuple: Boxed<Tuple> = option
+        .try_into()
+        .map_err(|_| TryPropListFromTermError::PropertyType)?;
+
+    if tuple.len() != 2 {
+        return Err(TryPropListFromTermError::TupleNotPair.into());
+    }
+
+    let atom: Atom = tuple[0]
+        .try_into()
+        .map_err(|_| TryPropListFromTermError::KeywordKeyType)?;
+
+    match atom.name() {
+        "signed" => term_try_into_bool("signed value", tuple[1]),
+        name => Err(TryPropListFromTermError::KeywordKeyName(name).into()),
+    }
+}
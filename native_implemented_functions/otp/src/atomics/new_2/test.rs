@@ -0,0 +1,49 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::atomics::get_2;
+use crate::atomics::new_2::native;
+use crate::test::with_process;
+
+#[test]
+fn without_positive_arity_errors_badarg() {
+    with_process(|process| {
+        let arity = process.integer(0).unwrap();
+
+        assert_badarg!(
+            native(process, arity, Term::NIL),
+            "arity (0) is not a positive integer"
+        );
+    });
+}
+
+#[test]
+fn with_unsupported_option_errors_badarg() {
+    with_process(|process| {
+        let option = process
+            .tuple_from_slice(&[atom!("unsigned"), true.into()])
+            .unwrap();
+        let opts = process.list_from_slice(&[option]).unwrap();
+
+        assert_badarg!(
+            native(process, process.integer(1).unwrap(), opts),
+            "the only supported option is {signed, boolean()}"
+        );
+    });
+}
+
+#[test]
+fn with_positive_arity_returns_reference_to_zeroed_atomics() {
+    with_process(|process| {
+        let atomics_ref = native(process, process.integer(2).unwrap(), Term::NIL).unwrap();
+
+        assert!(atomics_ref.is_boxed_resource_reference());
+
+        for index in 1..=2 {
+            assert_eq!(
+                get_2::native(process, atomics_ref, process.integer(index).unwrap()),
+                Ok(process.integer(0).unwrap())
+            );
+        }
+    });
+}
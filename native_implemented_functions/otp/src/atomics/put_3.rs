@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::atomic::Ordering;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{term_try_into_atomics, Atomics};
+
+#[native_implemented_function(put/3)]
+pub fn native(atomics_ref: Term, index: Term, value: Term) -> exception::Result<Term> {
+    let resource = term_try_into_atomics(atomics_ref)?;
+    let atomics: &Atomics = resource.downcast_ref().unwrap();
+    let atomic = atomics.atomic(index)?;
+    let bits = atomics.term_try_into_bits("value", value)?;
+    atomic.store(bits, Ordering::SeqCst);
+
+    Ok(atom!("ok"))
+}
@@ -0,0 +1,40 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::atomics::put_3::native;
+use crate::atomics::{get_2, new_2};
+use crate::test::with_process;
+
+#[test]
+fn with_signed_stores_negative_value() {
+    with_process(|process| {
+        let atomics_ref = new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+        let index = process.integer(1).unwrap();
+        let value = process.integer(i64::min_value()).unwrap();
+
+        assert_eq!(native(atomics_ref, index, value), Ok(atom!("ok")));
+        assert_eq!(get_2::native(process, atomics_ref, index), Ok(value));
+    });
+}
+
+#[test]
+fn with_unsigned_and_negative_value_errors_badarg() {
+    with_process(|process| {
+        let option = process
+            .tuple_from_slice(&[atom!("signed"), false.into()])
+            .unwrap();
+        let opts = process.list_from_slice(&[option]).unwrap();
+        let atomics_ref = new_2::native(process, process.integer(1).unwrap(), opts).unwrap();
+        let index = process.integer(1).unwrap();
+
+        assert_badarg!(
+            native(atomics_ref, index, process.integer(-1).unwrap()),
+            "value (-1) is not an unsigned 64-bit integer"
+        );
+
+        let max = process.integer(u64::max_value()).unwrap();
+
+        assert_eq!(native(atomics_ref, index, max), Ok(atom!("ok")));
+        assert_eq!(get_2::native(process, atomics_ref, index), Ok(max));
+    });
+}
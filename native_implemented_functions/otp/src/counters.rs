@@ -0,0 +1,36 @@
+pub mod add_3;
+pub mod get_2;
+pub mod new_2;
+pub mod put_3;
+
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::atomics::{term_try_into_resource, Atomics};
+
+/// The array behind a `counters_ref()`, which are always signed and wrap around on overflow.
+///
+/// It is a separate type from `Atomics`, so that a `counters_ref()` can't be passed to `atomics`
+/// or the other way around.
+pub struct Counters(Atomics);
+
+impl Counters {
+    pub fn new(size: usize) -> Self {
+        Self(Atomics::new(size, true))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+fn module() -> Atom {
+    Atom::try_from_str("counters").unwrap()
+}
+
+/// The resource behind `counters_ref` if it refers to `Counters`.
+///
+/// The `Resource` has to be kept while the `Counters` are used, as it is what keeps them alive.
+fn term_try_into_counters(counters_ref: Term) -> InternalResult<Resource> {
+    term_try_into_resource::<Counters>("counters_ref", counters_ref, "a counters_ref()")
+}
@@ -0,0 +1,26 @@
+#[cfg(test)]
+mod test;
+
+use std::sync::atomic::Ordering;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use crate::atomics::term_try_into_increment_bits;
+
+use super::{term_try_into_counters, Counters};
+
+/// Wraps around on overflow.
+#[native_implemented_function(add/3)]
+pub fn native(counters_ref: Term, index: Term, increment: Term) -> exception::Result<Term> {
+    let resource = term_try_into_counters(counters_ref)?;
+    let Counters(atomics): &Counters = resource.downcast_ref().unwrap();
+    let atomic = atomics.atomic(index)?;
+    let bits = term_try_into_increment_bits(increment)?;
+    atomic.fetch_add(bits, Ordering::SeqCst);
+
+    Ok(atom!("ok"))
+}
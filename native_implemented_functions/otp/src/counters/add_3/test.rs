@@ -0,0 +1,62 @@
+use std::thread;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+use crate::counters::add_3::native;
+use crate::counters::{get_2, new_2, put_3};
+use crate::test::{process, with_process};
+
+#[test]
+fn with_negative_increment_decrements() {
+    with_process(|process| {
+        let counters_ref = new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+        let index = process.integer(1).unwrap();
+        put_3::native(counters_ref, index, process.integer(10).unwrap()).unwrap();
+
+        assert_eq!(
+            native(counters_ref, index, process.integer(-11).unwrap()),
+            Ok(atom!("ok"))
+        );
+        assert_eq!(
+            get_2::native(process, counters_ref, index),
+            Ok(process.integer(-1).unwrap())
+        );
+    });
+}
+
+#[test]
+fn from_processes_on_different_threads_are_all_counted() {
+    const THREADS: usize = 4;
+    const ADDS: usize = 1_000;
+
+    with_process(|process| {
+        let counters_ref = new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+
+        let handles: Vec<_> = (0..THREADS)
+            .map(|_| {
+                let thread_process = process::default();
+                let thread_counters_ref = counters_ref.clone_to_process(&thread_process);
+
+                thread::spawn(move || {
+                    let index = thread_process.integer(1).unwrap();
+                    let increment = thread_process.integer(1).unwrap();
+
+                    for _ in 0..ADDS {
+                        native(thread_counters_ref, index, increment).unwrap();
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(
+            get_2::native(process, counters_ref, process.integer(1).unwrap()),
+            Ok(process.integer(THREADS * ADDS).unwrap())
+        );
+    });
+}
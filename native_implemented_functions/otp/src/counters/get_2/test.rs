@@ -0,0 +1,18 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::atomics;
+use crate::counters::get_2::native;
+use crate::test::with_process;
+
+#[test]
+fn with_atomics_ref_errors_badarg() {
+    with_process(|process| {
+        let atomics_ref =
+            atomics::new_2::native(process, process.integer(1).unwrap(), Term::NIL).unwrap();
+
+        assert_badarg!(
+            native(process, atomics_ref, process.integer(1).unwrap()),
+            "is not a counters_ref()"
+        );
+    });
+}
@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::{self, InternalResult};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::context::term_is_not_type;
+use lumen_rt_core::proplist::TryPropListFromTermError;
+
+use super::Counters;
+
+/// `Opts` can contain `atomics` and `write_concurrency`, but both are accepted without effect, as
+/// every counter is already a single atomic that every scheduler shares.
+#[native_implemented_function(new/2)]
+pub fn native(process: &Process, size: Term, opts: Term) -> exception::Result<Term> {
+    let size_usize: usize = size
+        .try_into()
+        .with_context(|| term_is_not_type("size", size, "a positive integer"))?;
+
+    if size_usize == 0 {
+        return Err(anyhow!(term_is_not_type("size", size, "a positive integer")).into());
+    }
+
+    check_opts(opts)?;
+
+    process
+        .resource(Box::new(Counters::new(size_usize)))
+        .map_err(From::from)
+}
+
+// Private
+
+const SUPPORTED_OPTIONS_CONTEXT: &str = "supported options are atomics and write_concurrency";
+
+fn check_opts(opts: Term) -> InternalResult<()> {
+    let mut opts_term = opts;
+
+    loop {
+        match opts_term.decode()? {
+            TypedTerm::Nil => return Ok(()),
+            TypedTerm::List(cons) => {
+                check_option_term(cons.head).context(SUPPORTED_OPTIONS_CONTEXT)?;
+                opts_term = cons.tail;
+            }
+            _ => {
+                return Err(ImproperListError)
+                    .context(SUPPORTED_OPTIONS_CONTEXT)
+                    .map_err(From::from)
+            }
+        }
+    }
+}
+
+fn check_option_term(option: Term) -> anyhow::Result<()> {
+    let atom: Atom = option
+        .try_into()
+        .map_err(|_| TryPropListFromTermError::PropertyType)?;
+
+    match atom.name() {
+        "atomics" | "write_concurrency" => Ok(()),
+        name => Err(TryPropListFromTermError::AtomName(name).into()),
+    }
+}
@@ -0,0 +1,30 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::counters::new_2::native;
+use crate::test::with_process;
+
+#[test]
+fn with_atomics_and_write_concurrency_returns_reference() {
+    with_process(|process| {
+        let opts = process
+            .list_from_slice(&[atom!("atomics"), atom!("write_concurrency")])
+            .unwrap();
+
+        let counters_ref = native(process, process.integer(1).unwrap(), opts).unwrap();
+
+        assert!(counters_ref.is_boxed_resource_reference());
+    });
+}
+
+#[test]
+fn with_unsupported_option_errors_badarg() {
+    with_process(|process| {
+        let opts = process.list_from_slice(&[atom!("signed")]).unwrap();
+
+        assert_badarg!(
+            native(process, process.integer(1).unwrap(), opts),
+            "supported options are atomics and write_concurrency"
+        );
+    });
+}
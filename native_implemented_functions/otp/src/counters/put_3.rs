@@ -0,0 +1,20 @@
+use std::sync::atomic::Ordering;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{term_try_into_counters, Counters};
+
+#[native_implemented_function(put/3)]
+pub fn native(counters_ref: Term, index: Term, value: Term) -> exception::Result<Term> {
+    let resource = term_try_into_counters(counters_ref)?;
+    let Counters(atomics): &Counters = resource.downcast_ref().unwrap();
+    let atomic = atomics.atomic(index)?;
+    let bits = atomics.term_try_into_bits("value", value)?;
+    atomic.store(bits, Ordering::SeqCst);
+
+    Ok(atom!("ok"))
+}
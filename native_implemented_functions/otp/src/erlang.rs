@@ -131,9 +131,14 @@ pub mod not_1;
 pub mod now_0;
 pub mod number_or_badarith_1;
 mod number_to_integer;
+pub mod open_port_2;
 pub mod or_2;
 pub mod orelse_2;
+pub mod port_close_1;
+pub mod port_command_2;
+pub mod port_info_1;
 pub mod process_flag_2;
+pub mod process_info_1;
 pub mod process_info_2;
 pub mod put_2;
 pub mod raise_3;
@@ -171,6 +176,7 @@ pub mod system_time_0;
 pub mod system_time_1;
 mod term_to_binary;
 pub mod term_to_binary_1;
+pub mod term_to_binary_2;
 pub mod throw_1;
 pub mod time_0;
 pub mod time_offset_0;
@@ -289,6 +295,13 @@ fn is_record(term: Term, record_tag: Term, size: Option<Term>) -> exception::Res
     }
 }
 
+/// Ports on other nodes can't be commanded, closed or inspected from this node.
+fn term_try_into_local_port(name: &str, term: Term) -> InternalResult<Port> {
+    term.try_into()
+        .with_context(|| term_is_not_type(name, term, "a local port"))
+        .map_err(From::from)
+}
+
 fn size_try_to_usize(size: Term) -> exception::Result<usize> {
     size.try_into()
         .with_context(|| format!("size ({}) must be a positive integer", size))
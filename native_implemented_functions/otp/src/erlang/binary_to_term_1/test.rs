@@ -2,10 +2,10 @@ use proptest::prop_assert_eq;
 use proptest::strategy::Just;
 
 use liblumen_alloc::erts::process::Process;
-use liblumen_alloc::erts::term::prelude::{Atom, Term};
+use liblumen_alloc::erts::term::prelude::*;
 
 use crate::erlang::binary_to_term_1::native;
-use crate::test::strategy;
+use crate::test::{strategy, with_process};
 
 #[test]
 fn without_binary_errors_badarg() {
@@ -169,6 +169,126 @@ fn with_binary_encoding_small_atom_utf8_returns_atom() {
     );
 }
 
+#[test]
+fn with_binary_encoding_float_returns_float() {
+    with_binary_returns_term(
+        // FLOAT_EXT for 1.5, which is only produced by `:erlang.term_to_binary(1.5, minor_version: 0)`
+        vec![
+            131, 99, 49, 46, 53, 48, 48, 48, 48, 48, 48, 48, 48, 48, 48, 48, 48, 48, 48, 48, 48,
+            48, 48, 48, 101, 43, 48, 48, 0, 0, 0, 0, 0,
+        ],
+        |process| process.float(1.5).unwrap(),
+    );
+}
+
+#[test]
+fn with_binary_encoding_compressed_list_returns_list() {
+    with_binary_returns_term(
+        // :erlang.term_to_binary(List.duplicate(:hello, 20), [:compressed])
+        vec![
+            131, 80, 0, 0, 0, 166, 120, 156, 203, 97, 96, 96, 16, 73, 97, 96, 205, 72, 205, 201,
+            201, 31, 172, 116, 22, 0, 8, 136, 50, 175,
+        ],
+        |process| {
+            process
+                .list_from_slice(&[Atom::str_to_term("hello"); 20])
+                .unwrap()
+        },
+    );
+}
+
+#[test]
+fn with_binary_encoding_compressed_term_with_wrong_uncompressed_size_errors_badarg() {
+    with_process(|process| {
+        // COMPRESSED declaring 1 byte around the 2 bytes of SMALL_INTEGER_EXT for 1
+        let binary = process
+            .binary_from_bytes(&[131, 80, 0, 0, 0, 1, 120, 156, 75, 100, 4, 0, 0, 197, 0, 99])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, binary),
+            "uncompressed size (2) does not match declared size (1)"
+        );
+    });
+}
+
+#[test]
+fn with_binary_encoding_compressed_term_in_list_errors_badarg() {
+    with_process(|process| {
+        // LIST_EXT containing COMPRESSED SMALL_INTEGER_EXT for 1
+        let binary = process
+            .binary_from_bytes(&[
+                131, 108, 0, 0, 0, 1, 80, 0, 0, 0, 2, 120, 156, 75, 100, 4, 0, 0, 197, 0, 99, 106,
+            ])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, binary),
+            "a compressed term (COMPRESSED) can only be at the top level"
+        );
+    });
+}
+
+#[test]
+fn with_binary_encoding_port_returns_port() {
+    with_binary_returns_term(
+        // PORT_EXT for #Port<0.5> on nonode@nohost
+        vec![
+            131, 102, 100, 0, 13, 110, 111, 110, 111, 100, 101, 64, 110, 111, 104, 111, 115, 116,
+            0, 0, 0, 5, 0,
+        ],
+        |_| unsafe { Port::from_raw(5) }.encode().unwrap(),
+    );
+}
+
+#[test]
+fn with_binary_encoding_new_port_returns_port() {
+    with_binary_returns_term(
+        // NEW_PORT_EXT for #Port<0.5> on nonode@nohost
+        vec![
+            131, 89, 100, 0, 13, 110, 111, 110, 111, 100, 101, 64, 110, 111, 104, 111, 115, 116, 0,
+            0, 0, 5, 0, 0, 0, 0,
+        ],
+        |_| unsafe { Port::from_raw(5) }.encode().unwrap(),
+    );
+}
+
+#[test]
+fn with_binary_encoding_reference_returns_reference() {
+    with_binary_returns_term(
+        // REFERENCE_EXT for #Ref<0.0.7> on nonode@nohost
+        vec![
+            131, 101, 100, 0, 13, 110, 111, 110, 111, 100, 101, 64, 110, 111, 104, 111, 115, 116,
+            0, 0, 0, 7, 0,
+        ],
+        |process| process.reference_from_scheduler(0_u32.into(), 7).unwrap(),
+    );
+}
+
+#[test]
+fn with_binary_encoding_new_reference_returns_reference() {
+    with_binary_returns_term(
+        // NEW_REFERENCE_EXT for #Ref<0.1.0.7> on nonode@nohost
+        vec![
+            131, 114, 0, 3, 100, 0, 13, 110, 111, 110, 111, 100, 101, 64, 110, 111, 104, 111, 115,
+            116, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 7,
+        ],
+        |process| process.reference_from_scheduler(1_u32.into(), 7).unwrap(),
+    );
+}
+
+#[test]
+fn with_binary_encoding_atom_cache_reference_errors_badarg() {
+    with_process(|process| {
+        let binary = process.binary_from_bytes(&[131, 82, 0]).unwrap();
+
+        assert_badarg!(
+            native(process, binary),
+            "atom cache reference (0) is only valid in a distribution message"
+        );
+    });
+}
+
 fn with_binary_returns_term<T>(byte_vec: Vec<u8>, term: T)
 where
     T: Fn(&Process) -> Term,
@@ -16,8 +16,7 @@ use liblumen_alloc::erts::term::prelude::*;
 
 use native_implemented_function::native_implemented_function;
 
-use lumen_rt_full::binary::to_term::Options;
-use lumen_rt_full::distribution::external_term_format::{term, version};
+use lumen_rt_full::binary::to_term::{bytes_to_term, Options};
 
 macro_rules! maybe_aligned_maybe_binary_try_into_term {
     ($process:expr, $options:expr, $binary:expr, $ident:expr) => {
@@ -69,12 +68,9 @@ fn versioned_tagged_bytes_try_into_term(
     options: &Options,
     bytes: &[u8],
 ) -> exception::Result<Term> {
-    let after_version_bytes = version::check(bytes)?;
-    let (term, after_term_bytes) =
-        term::decode_tagged(process, options.existing, after_version_bytes)?;
+    let (term, used_byte_len) = bytes_to_term(process, options, bytes)?;
 
     if options.used {
-        let used_byte_len = bytes.len() - after_term_bytes.len();
         let used = process.integer(used_byte_len)?;
 
         process
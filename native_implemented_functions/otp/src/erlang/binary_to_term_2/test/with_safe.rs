@@ -34,6 +34,79 @@ fn with_binary_encoding_small_atom_utf8_that_does_not_exist_errors_badarg() {
     ]);
 }
 
+#[test]
+fn with_binary_encoding_export_that_does_not_exist_errors_badarg() {
+    // :erlang.term_to_binary(&:erlang.ok/0)
+    let byte_vec = vec![
+        131, 113, 100, 0, 6, 101, 114, 108, 97, 110, 103, 100, 0, 2, 111, 107, 97, 0,
+    ];
+
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::binary::containing_bytes(byte_vec.clone(), arc_process.clone()),
+            )
+        },
+        |(arc_process, binary)| {
+            prop_assert_badarg!(
+                native(&arc_process, binary, options(&arc_process)),
+                "tried to convert to an export (erlang:ok/0) that doesn't exist"
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_binary_encoding_pid_with_atom_cache_reference_node_errors_badarg() {
+    // PID_EXT with an ATOM_CACHE_REF for its node
+    let byte_vec = vec![131, 103, 82, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0];
+
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::binary::containing_bytes(byte_vec.clone(), arc_process.clone()),
+            )
+        },
+        |(arc_process, binary)| {
+            prop_assert_badarg!(
+                native(&arc_process, binary, options(&arc_process)),
+                "atom cache reference (0) is only valid in a distribution message"
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_binary_encoding_compressed_term_declaring_oversized_uncompressed_size_errors_badarg() {
+    // COMPRESSED declaring 4 GiB around the 2 bytes of SMALL_INTEGER_EXT for 1
+    let byte_vec = vec![
+        131, 80, 255, 255, 255, 255, 120, 156, 75, 100, 4, 0, 0, 197, 0, 99,
+    ];
+
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::binary::containing_bytes(byte_vec.clone(), arc_process.clone()),
+            )
+        },
+        |(arc_process, binary)| {
+            prop_assert_badarg!(
+                native(&arc_process, binary, options(&arc_process)),
+                "uncompressed size (2) does not match declared size (4294967295)"
+            );
+
+            Ok(())
+        },
+    );
+}
+
 fn options(process: &Process) -> Term {
     process.cons(Atom::str_to_term("safe"), Term::NIL).unwrap()
 }
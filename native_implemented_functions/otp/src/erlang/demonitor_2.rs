@@ -15,6 +15,7 @@ use liblumen_alloc::erts::term::prelude::*;
 
 use lumen_rt_core::process::monitor::is_down;
 use lumen_rt_core::registry::pid_to_process;
+use lumen_rt_full::distribution;
 
 use native_implemented_function::native_implemented_function;
 
@@ -35,7 +36,7 @@ pub(in crate::erlang) fn demonitor(
     reference: &Reference,
     Options { flush, info }: Options,
 ) -> exception::Result<Term> {
-    match monitoring_process.demonitor(reference) {
+    let demonitored = match monitoring_process.demonitor(reference) {
         Some(monitored_pid) => {
             match pid_to_process(&monitored_pid) {
                 Some(monitored_arc_proces) => match monitored_arc_proces.demonitored(reference) {
@@ -45,25 +46,27 @@ pub(in crate::erlang) fn demonitor(
                 None => (),
             }
 
-            if flush {
-                let flushed = self::flush(monitoring_process, reference);
-
-                if info && flushed {
-                    Ok(false.into())
-                } else {
-                    Ok(true.into())
-                }
-            } else {
-                Ok(true.into())
-            }
+            true
         }
-        None => {
-            if info {
+        None => distribution::demonitor(monitoring_process, reference),
+    };
+
+    if demonitored {
+        if flush {
+            let flushed = self::flush(monitoring_process, reference);
+
+            if info && flushed {
                 Ok(false.into())
             } else {
                 Ok(true.into())
             }
+        } else {
+            Ok(true.into())
         }
+    } else if info {
+        Ok(false.into())
+    } else {
+        Ok(true.into())
     }
 }
 
@@ -6,6 +6,7 @@ mod with_float_left;
 mod with_heap_binary_left;
 mod with_list_left;
 mod with_local_pid_left;
+mod with_local_port_left;
 mod with_local_reference_left;
 mod with_map_left;
 mod with_small_integer_left;
@@ -13,10 +14,12 @@ mod with_subbinary_left;
 mod with_tuple_left;
 
 use proptest::prop_assert_eq;
+use proptest::strategy::Just;
 use proptest::test_runner::{Config, TestRunner};
 
 use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
 
 use crate::erlang::is_less_than_2::native;
 use crate::test::{external_arc_node, strategy};
@@ -17,6 +17,16 @@ fn with_number_atom_reference_function_or_port_returns_false() {
     );
 }
 
+#[test]
+fn with_external_reference_right_returns_false() {
+    is_less_than(
+        |_, process| {
+            ExternalReference::new(external_arc_node(), 0.into(), 1).clone_to_process(process)
+        },
+        false,
+    );
+}
+
 #[test]
 fn with_lesser_local_pid_right_returns_false() {
     is_less_than(|_, _| Pid::make_term(0, 0).unwrap(), false);
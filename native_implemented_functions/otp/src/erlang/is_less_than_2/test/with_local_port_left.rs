@@ -0,0 +1,103 @@
+use super::*;
+
+#[test]
+fn with_number_atom_reference_or_function_returns_false() {
+    run!(
+        |arc_process| {
+            (
+                Just(Port::next_term()),
+                strategy::term::number_atom_reference_function_or_port(arc_process),
+            )
+        },
+        |(left, right)| {
+            prop_assert_eq!(native(left, right), false.into());
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_external_reference_right_returns_false() {
+    is_less_than(
+        |_| Port::next_term(),
+        |_, process| {
+            ExternalReference::new(external_arc_node(), 0.into(), 1).clone_to_process(process)
+        },
+        false,
+    );
+}
+
+#[test]
+fn with_external_port_left_and_external_reference_right_returns_false() {
+    is_less_than(
+        |process| ExternalPort::new(external_arc_node(), 1).clone_to_process(process),
+        |_, process| {
+            ExternalReference::new(external_arc_node(), 0.into(), 1).clone_to_process(process)
+        },
+        false,
+    );
+}
+
+#[test]
+fn with_external_reference_left_returns_true() {
+    is_less_than(
+        |process| {
+            ExternalReference::new(external_arc_node(), 0.into(), 1).clone_to_process(process)
+        },
+        |_, _| Port::next_term(),
+        true,
+    );
+}
+
+#[test]
+fn with_lesser_local_port_right_returns_false() {
+    let lesser = Port::next_term();
+
+    is_less_than(|_| Port::next_term(), |_, _| lesser, false);
+}
+
+#[test]
+fn with_same_local_port_right_returns_false() {
+    is_less_than(|_| Port::next_term(), |left, _| left, false);
+}
+
+#[test]
+fn with_greater_local_port_right_returns_true() {
+    is_less_than(|_| Port::next_term(), |_, _| Port::next_term(), true);
+}
+
+#[test]
+fn with_external_port_right_returns_true() {
+    is_less_than(
+        |_| Port::next_term(),
+        |_, process| ExternalPort::new(external_arc_node(), 1).clone_to_process(process),
+        true,
+    );
+}
+
+#[test]
+fn with_local_pid_right_returns_true() {
+    is_less_than(
+        |_| Port::next_term(),
+        |_, _| Pid::make_term(0, 1).unwrap(),
+        true,
+    );
+}
+
+#[test]
+fn with_tuple_map_list_or_bitstring_returns_true() {
+    run!(
+        |arc_process| {
+            (
+                Just(Port::next_term()),
+                strategy::term::tuple_map_list_or_bitstring(arc_process),
+            )
+        },
+        |(left, right)| {
+            prop_assert_eq!(native(left, right), true.into());
+
+            Ok(())
+        },
+    );
+}
@@ -15,6 +15,7 @@ use liblumen_alloc::erts::term::prelude::*;
 use native_implemented_function::native_implemented_function;
 
 use lumen_rt_core::registry::pid_to_process;
+use lumen_rt_full::{distribution, port};
 
 #[native_implemented_function(link/1)]
 fn native(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
@@ -37,8 +38,22 @@ fn native(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
                 }
             }
         }
-        TypedTerm::Port(_) => unimplemented!(),
-        TypedTerm::ExternalPid(_) => unimplemented!(),
+        TypedTerm::Port(port) => {
+            if port::link(process, port) {
+                Ok(true.into())
+            } else {
+                Err(error!(
+                    Atom::str_to_term("noproc"),
+                    anyhow!("port ({}) is not open", port).into()
+                )
+                .into())
+            }
+        }
+        TypedTerm::ExternalPid(external_pid) => {
+            distribution::link(process, &external_pid);
+
+            Ok(true.into())
+        }
         TypedTerm::ExternalPort(_) => unimplemented!(),
         _ => Err(TypeError)
             .context(format!(
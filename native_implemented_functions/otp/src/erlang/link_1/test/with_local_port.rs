@@ -0,0 +1,43 @@
+use super::*;
+
+use liblumen_alloc::error;
+use liblumen_alloc::erts::term::prelude::Atom;
+
+use lumen_rt_full::port::{self, Name};
+
+use crate::test;
+
+#[test]
+fn with_closed_port_errors_noproc() {
+    with_process(|process| {
+        let owner = test::process::child(process);
+        let port = port::open(&owner, cat(), Default::default()).unwrap();
+        assert!(port::close(port));
+
+        assert_eq!(
+            native(process, port.encode().unwrap()),
+            Err(error!(Atom::str_to_term("noproc"), anyhow!("Test").into()).into())
+        );
+    });
+}
+
+#[test]
+fn with_open_port_links_to_port() {
+    with_process(|process| {
+        let owner = test::process::child(process);
+        let port = port::open(&owner, cat(), Default::default()).unwrap();
+
+        assert_eq!(native(process, port.encode().unwrap()), Ok(true.into()));
+
+        let links = port::info(port).unwrap().links;
+
+        assert!(links.contains(&owner.pid()));
+        assert!(links.contains(&process.pid()));
+
+        assert!(port::close(port));
+    });
+}
+
+fn cat() -> Name {
+    Name::Spawn("cat".to_string())
+}
@@ -19,6 +19,7 @@ use native_implemented_function::native_implemented_function;
 use crate::erlang::node_0;
 use lumen_rt_core::context::*;
 use lumen_rt_core::registry;
+use lumen_rt_full::distribution;
 use lumen_rt_full::process::{self, SchedulerDependentAlloc};
 
 const TYPE_CONTEXT: &str = "supported types are :port, :process, or :time_offset";
@@ -46,7 +47,9 @@ fn monitor_process_identifier(
     match process_identifier.decode()? {
         TypedTerm::Atom(atom) => monitor_process_registered_name(process, process_identifier, atom),
         TypedTerm::Pid(pid) => monitor_process_pid(process, process_identifier, pid),
-        TypedTerm::ExternalPid(_) => unimplemented!(),
+        TypedTerm::ExternalPid(external_pid) => {
+            distribution::monitor_pid(process, &external_pid).map_err(From::from)
+        }
         TypedTerm::Tuple(tuple) => monitor_process_tuple(process, process_identifier, &tuple),
         _ => Err(TypeError)
             .context(PROCESS_IDENTIFIER_CONTEXT)
@@ -125,13 +128,9 @@ fn monitor_process_tuple(
         if node == node_0::native() {
             monitor_process_registered_name(process, registered_name, registered_name_atom)
         } else {
-            let _: Atom = term_try_into_atom!(node)?;
+            let node_atom: Atom = term_try_into_atom!(node)?;
 
-            unimplemented!(
-                "node ({:?}) is not the local node ({:?})",
-                node,
-                node_0::native()
-            );
+            distribution::monitor_name(process, registered_name_atom, node_atom).map_err(From::from)
         }
     } else {
         Err(anyhow!(PROCESS_IDENTIFIER_CONTEXT).into())
@@ -6,14 +6,30 @@ use lumen_rt_core::time::monotonic;
 #[test]
 fn increases_after_2_native_time_units() {
     with_process(|process| {
-        let start_time_in_milliseconds = monotonic::freeze_time_in_milliseconds();
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
 
         let first = native(process).unwrap();
 
-        monotonic::freeze_at_time_in_milliseconds(start_time_in_milliseconds + 2);
+        monotonic::set_virtual_time_in_milliseconds(start_time_in_milliseconds + 2);
 
         let second = native(process).unwrap();
 
         assert!(first < second);
     });
 }
+
+#[test]
+fn does_not_decrease_when_virtual_clock_is_set_back() {
+    with_process(|process| {
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
+        monotonic::set_virtual_time_in_milliseconds(start_time_in_milliseconds + 2);
+
+        let first = native(process).unwrap();
+
+        monotonic::set_virtual_time_in_milliseconds(start_time_in_milliseconds);
+
+        let second = native(process).unwrap();
+
+        assert_eq!(first, second);
+    });
+}
@@ -11,11 +11,11 @@ fn with_invalid_unit_errors_badarg() {
 fn with_second_increases_after_2_seconds() {
     with_process(|process| {
         let unit = Atom::str_to_term("second");
-        let start_time_in_milliseconds = monotonic::freeze_time_in_milliseconds();
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
 
         let first = native(process, unit).unwrap();
 
-        monotonic::freeze_at_time_in_milliseconds(
+        monotonic::set_virtual_time_in_milliseconds(
             start_time_in_milliseconds + Duration::from_secs(2).as_millis() as Milliseconds,
         );
 
@@ -29,11 +29,11 @@ fn with_second_increases_after_2_seconds() {
 fn with_millisecond_increases_after_2_milliseconds() {
     with_process(|process| {
         let unit = Atom::str_to_term("millisecond");
-        let start_time_in_milliseconds = monotonic::freeze_time_in_milliseconds();
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
 
         let first = native(process, unit).unwrap();
 
-        monotonic::freeze_at_time_in_milliseconds(start_time_in_milliseconds + 2);
+        monotonic::set_virtual_time_in_milliseconds(start_time_in_milliseconds + 2);
 
         let second = native(process, unit).unwrap();
 
@@ -45,11 +45,11 @@ fn with_millisecond_increases_after_2_milliseconds() {
 fn with_microsecond_increases_after_2_milliseconds() {
     with_process(|process| {
         let unit = Atom::str_to_term("microsecond");
-        let start_time_in_milliseconds = monotonic::freeze_time_in_milliseconds();
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
 
         let first = native(process, unit).unwrap();
 
-        monotonic::freeze_at_time_in_milliseconds(start_time_in_milliseconds + 2);
+        monotonic::set_virtual_time_in_milliseconds(start_time_in_milliseconds + 2);
 
         let second = native(process, unit).unwrap();
 
@@ -61,11 +61,11 @@ fn with_microsecond_increases_after_2_milliseconds() {
 fn with_nanosecond_increases_after_2_milliseconds() {
     with_process(|process| {
         let unit = Atom::str_to_term("nanosecond");
-        let start_time_in_milliseconds = monotonic::freeze_time_in_milliseconds();
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
 
         let first = native(process, unit).unwrap();
 
-        monotonic::freeze_at_time_in_milliseconds(start_time_in_milliseconds + 2);
+        monotonic::set_virtual_time_in_milliseconds(start_time_in_milliseconds + 2);
 
         let second = native(process, unit).unwrap();
 
@@ -77,11 +77,11 @@ fn with_nanosecond_increases_after_2_milliseconds() {
 fn with_native_increases_after_2_native_time_units() {
     with_process(|process| {
         let unit = Atom::str_to_term("native");
-        let start_time_in_milliseconds = monotonic::freeze_time_in_milliseconds();
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
 
         let first = native(process, unit).unwrap();
 
-        monotonic::freeze_at_time_in_milliseconds(start_time_in_milliseconds + 2);
+        monotonic::set_virtual_time_in_milliseconds(start_time_in_milliseconds + 2);
 
         let second = native(process, unit).unwrap();
 
@@ -93,11 +93,11 @@ fn with_native_increases_after_2_native_time_units() {
 fn with_perf_counter_increases_after_2_perf_counter_ticks() {
     with_process(|process| {
         let unit = Atom::str_to_term("perf_counter");
-        let start_time_in_milliseconds = monotonic::freeze_time_in_milliseconds();
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
 
         let first = native(process, unit).unwrap();
 
-        monotonic::freeze_at_time_in_milliseconds(start_time_in_milliseconds + 2);
+        monotonic::set_virtual_time_in_milliseconds(start_time_in_milliseconds + 2);
 
         let second = native(process, unit).unwrap();
 
@@ -27,8 +27,8 @@ fn with_positive_increases_after_2_time_units() {
 
         let first = native(process, unit).unwrap();
 
-        let start_time_in_milliseconds = monotonic::freeze_time_in_milliseconds();
-        monotonic::freeze_at_time_in_milliseconds(
+        let start_time_in_milliseconds = monotonic::start_virtual_clock();
+        monotonic::set_virtual_time_in_milliseconds(
             start_time_in_milliseconds + Duration::from_secs(2).as_millis() as Milliseconds,
         );
 
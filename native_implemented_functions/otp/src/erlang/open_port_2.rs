@@ -0,0 +1,58 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::io;
+
+use anyhow::*;
+
+use liblumen_alloc::error;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_full::port::{self, Name, Options};
+
+#[native_implemented_function(open_port/2)]
+pub fn native(process: &Process, port_name: Term, port_settings: Term) -> exception::Result<Term> {
+    let name: Name = port_name.try_into()?;
+    let options: Options = port_settings.try_into()?;
+
+    if let Name::Spawn(_) = name {
+        if !options.args.is_empty() {
+            return Err(anyhow!(
+                "port_settings ({}) can only have args with spawn_executable",
+                port_settings
+            )
+            .into());
+        }
+    }
+
+    match port::open(process, name, options) {
+        Ok(port) => Ok(port.encode()?),
+        Err(io_error) => {
+            let reason = match io_error.kind() {
+                io::ErrorKind::NotFound => "enoent",
+                io::ErrorKind::PermissionDenied => "eacces",
+                _ => "einval",
+            };
+
+            Err(error!(
+                Atom::str_to_term(reason),
+                anyhow!(
+                    "port_name ({}) could not be spawned: {}",
+                    port_name,
+                    io_error
+                )
+                .into()
+            )
+            .into())
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::open_port_2::native;
+use crate::erlang::{port_close_1, port_command_2};
+use crate::test::{wait_for_message, with_process};
+
+#[test]
+fn without_tuple_port_name_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            native(
+                process,
+                process.charlist_from_str("cat").unwrap(),
+                Term::NIL
+            ),
+            "is not a tuple"
+        );
+    });
+}
+
+#[test]
+fn without_supported_port_name_tag_errors_badarg() {
+    with_process(|process| {
+        let port_name = process
+            .tuple_from_slice(&[Atom::str_to_term("fd"), process.integer(0).unwrap()])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, port_name, Term::NIL),
+            "tag is not supported"
+        );
+    });
+}
+
+#[test]
+fn without_supported_option_errors_badarg() {
+    with_process(|process| {
+        let port_settings = process
+            .list_from_slice(&[Atom::str_to_term("nouse_stdio")])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, spawn(process, "cat"), port_settings),
+            "atom name is not a supported property"
+        );
+    });
+}
+
+#[test]
+fn with_args_for_spawn_errors_badarg() {
+    with_process(|process| {
+        let args = process
+            .list_from_slice(&[process.charlist_from_str("-u").unwrap()])
+            .unwrap();
+        let port_settings = process
+            .list_from_slice(&[process
+                .tuple_from_slice(&[Atom::str_to_term("args"), args])
+                .unwrap()])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, spawn(process, "cat"), port_settings),
+            "can only have args with spawn_executable"
+        );
+    });
+}
+
+#[test]
+fn with_spawn_executable_that_does_not_exist_errors_enoent() {
+    with_process(|process| {
+        let port_name = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("spawn_executable"),
+                process.charlist_from_str("/lumen/does/not/exist").unwrap(),
+            ])
+            .unwrap();
+
+        assert_error!(
+            native(process, port_name, Term::NIL),
+            Atom::str_to_term("enoent")
+        );
+    });
+}
+
+#[test]
+fn with_spawn_executable_and_args_sends_output_to_owner() {
+    with_process(|process| {
+        let port_name = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("spawn_executable"),
+                process.charlist_from_str("/bin/echo").unwrap(),
+            ])
+            .unwrap();
+        let args = process
+            .list_from_slice(&[process.charlist_from_str("hello").unwrap()])
+            .unwrap();
+        let port_settings = process
+            .list_from_slice(&[
+                process
+                    .tuple_from_slice(&[Atom::str_to_term("args"), args])
+                    .unwrap(),
+                Atom::str_to_term("binary"),
+            ])
+            .unwrap();
+
+        let port = native(process, port_name, port_settings).unwrap();
+
+        assert!(port.is_port());
+
+        let data = process
+            .tuple_from_slice(&[
+                atom!("data"),
+                process.binary_from_bytes(b"hello\n").unwrap(),
+            ])
+            .unwrap();
+        let message = process.tuple_from_slice(&[port, data]).unwrap();
+
+        assert!(wait_for_message(process, message));
+    });
+}
+
+#[test]
+fn with_spawn_and_packet_sends_each_packet_to_owner() {
+    with_process(|process| {
+        let port_settings = process
+            .list_from_slice(&[
+                process
+                    .tuple_from_slice(&[Atom::str_to_term("packet"), process.integer(4).unwrap()])
+                    .unwrap(),
+                Atom::str_to_term("use_stdio"),
+            ])
+            .unwrap();
+
+        let port = native(process, spawn(process, "cat"), port_settings).unwrap();
+
+        for data in &["first", "second"] {
+            assert_eq!(
+                port_command_2::native(port, process.charlist_from_str(data).unwrap()),
+                Ok(true.into())
+            );
+        }
+
+        for data in &["first", "second"] {
+            let tagged_data = process
+                .tuple_from_slice(&[atom!("data"), process.charlist_from_str(data).unwrap()])
+                .unwrap();
+            let message = process.tuple_from_slice(&[port, tagged_data]).unwrap();
+
+            assert!(wait_for_message(process, message));
+        }
+
+        assert_eq!(port_close_1::native(port), Ok(true.into()));
+    });
+}
+
+fn spawn(process: &Process, command: &str) -> Term {
+    process
+        .tuple_from_slice(&[
+            Atom::str_to_term("spawn"),
+            process.charlist_from_str(command).unwrap(),
+        ])
+        .unwrap()
+}
@@ -0,0 +1,29 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_full::port;
+
+use crate::erlang::term_try_into_local_port;
+
+/// Closes `port` without the `{Port, closed}` reply of `Port ! {self(), close}`.
+#[native_implemented_function(port_close/1)]
+pub fn native(port: Term) -> exception::Result<Term> {
+    let port_port = term_try_into_local_port("port", port)?;
+
+    if port::close(port_port) {
+        Ok(true.into())
+    } else {
+        Err(anyhow!("port ({}) is not open", port).into())
+    }
+}
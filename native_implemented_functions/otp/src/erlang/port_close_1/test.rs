@@ -0,0 +1,53 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::port_close_1::native;
+use crate::erlang::{open_port_2, port_info_1};
+use crate::test::{wait_for_message, with_process};
+
+#[test]
+fn without_port_errors_badarg() {
+    let port = Atom::str_to_term("port");
+
+    assert_badarg!(native(port), "port (port) is not a local port");
+}
+
+#[test]
+fn with_open_port_closes_it_once() {
+    with_process(|process| {
+        let port_name = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("spawn"),
+                process.charlist_from_str("cat").unwrap(),
+            ])
+            .unwrap();
+        let port = open_port_2::native(process, port_name, Term::NIL).unwrap();
+
+        assert_eq!(native(port), Ok(true.into()));
+        assert_eq!(port_info_1::native(process, port), Ok(atom!("undefined")));
+        assert_badarg!(native(port), format!("port ({}) is not open", port));
+    });
+}
+
+#[test]
+fn with_owner_trapping_exits_sends_exit_normal_to_owner() {
+    with_process(|process| {
+        process.trap_exit(true);
+
+        let port_name = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("spawn"),
+                process.charlist_from_str("cat").unwrap(),
+            ])
+            .unwrap();
+        let port = open_port_2::native(process, port_name, Term::NIL).unwrap();
+
+        assert_eq!(native(port), Ok(true.into()));
+
+        let message = process
+            .tuple_from_slice(&[atom!("EXIT"), port, atom!("normal")])
+            .unwrap();
+
+        assert!(wait_for_message(process, message));
+    });
+}
@@ -0,0 +1,48 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::context::{r#type, term_is_not_type};
+use lumen_rt_full::port;
+
+use crate::erlang::iolist_or_binary;
+use crate::erlang::term_try_into_local_port;
+
+/// Sends `data` to the program of `port`.  Any process can command a port, not only its owner.
+#[native_implemented_function(port_command/2)]
+pub fn native(port: Term, data: Term) -> exception::Result<Term> {
+    let port_port = term_try_into_local_port("port", port)?;
+
+    let bytes = match data.decode()? {
+        TypedTerm::Nil
+        | TypedTerm::List(_)
+        | TypedTerm::BinaryLiteral(_)
+        | TypedTerm::HeapBinary(_)
+        | TypedTerm::MatchContext(_)
+        | TypedTerm::ProcBin(_)
+        | TypedTerm::SubBinary(_) => iolist_or_binary::to_bytes("data", data)?,
+        _ => {
+            return Err(TypeError)
+                .context(term_is_not_type(
+                    "data",
+                    data,
+                    &format!("an iolist ({}) or binary", r#type::IOLIST),
+                ))
+                .map_err(From::from)
+        }
+    };
+
+    port::command(port_port, &bytes)?;
+
+    Ok(true.into())
+}
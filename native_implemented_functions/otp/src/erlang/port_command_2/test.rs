@@ -0,0 +1,85 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::port_command_2::native;
+use crate::erlang::{open_port_2, port_close_1};
+use crate::test::{wait_for_message, with_process};
+
+#[test]
+fn without_local_port_errors_badarg() {
+    with_process(|process| {
+        let port = process.pid_term();
+
+        assert_badarg!(
+            native(port, Term::NIL),
+            format!("port ({}) is not a local port", port)
+        );
+    });
+}
+
+#[test]
+fn without_open_port_errors_badarg() {
+    let port = Port::next_term();
+
+    assert_badarg!(
+        native(port, Term::NIL),
+        format!("port ({}) is not open", port)
+    );
+}
+
+#[test]
+fn without_iolist_or_binary_data_errors_badarg() {
+    with_process(|process| {
+        let port = open_cat(process);
+        let data = process.integer(1).unwrap();
+
+        assert_badarg!(
+            native(port, data),
+            format!("data ({}) is not an iolist", data)
+        );
+
+        assert_eq!(port_close_1::native(port), Ok(true.into()));
+    });
+}
+
+#[test]
+fn with_iolist_writes_flattened_bytes() {
+    with_process(|process| {
+        let port = open_cat(process);
+        let data = process
+            .list_from_slice(&[
+                process.binary_from_bytes(b"he").unwrap(),
+                process
+                    .list_from_slice(&[process.integer(b'l').unwrap()])
+                    .unwrap(),
+                process.charlist_from_str("lo").unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(native(port, data), Ok(true.into()));
+
+        let tagged_data = process
+            .tuple_from_slice(&[atom!("data"), process.binary_from_bytes(b"hello").unwrap()])
+            .unwrap();
+        let message = process.tuple_from_slice(&[port, tagged_data]).unwrap();
+
+        assert!(wait_for_message(process, message));
+
+        assert_eq!(port_close_1::native(port), Ok(true.into()));
+    });
+}
+
+fn open_cat(process: &Process) -> Term {
+    let port_name = process
+        .tuple_from_slice(&[
+            Atom::str_to_term("spawn"),
+            process.charlist_from_str("cat").unwrap(),
+        ])
+        .unwrap();
+    let port_settings = process
+        .list_from_slice(&[Atom::str_to_term("binary")])
+        .unwrap();
+
+    open_port_2::native(process, port_name, port_settings).unwrap()
+}
@@ -0,0 +1,55 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_full::port::{self, Info};
+
+use crate::erlang::term_try_into_local_port;
+
+/// `[{name, Name}, {links, Links}, {id, Id}, {connected, Pid}, {input, Bytes}, {output, Bytes},
+/// {os_pid, OsPid}]`, or `undefined` if `port` is not open.
+#[native_implemented_function(port_info/1)]
+pub fn native(process: &Process, port: Term) -> exception::Result<Term> {
+    let port_port = term_try_into_local_port("port", port)?;
+
+    match port::info(port_port) {
+        Some(info) => info_to_list(process, info),
+        None => Ok(atom!("undefined")),
+    }
+}
+
+fn info_to_list(process: &Process, info: Info) -> exception::Result<Term> {
+    let mut link_vec = Vec::with_capacity(info.links.len());
+
+    for pid in &info.links {
+        link_vec.push(pid.encode()?);
+    }
+
+    let items = [
+        (atom!("name"), process.charlist_from_str(&info.name)?),
+        (atom!("links"), process.list_from_slice(&link_vec)?),
+        (atom!("id"), process.integer(info.id)?),
+        (atom!("connected"), info.connected.encode()?),
+        (atom!("input"), process.integer(info.input)?),
+        (atom!("output"), process.integer(info.output)?),
+        (atom!("os_pid"), process.integer(info.os_pid as u64)?),
+    ];
+
+    let mut item_vec = Vec::with_capacity(items.len());
+
+    for (key, value) in &items {
+        item_vec.push(process.tuple_from_slice(&[*key, *value])?);
+    }
+
+    process.list_from_slice(&item_vec).map_err(From::from)
+}
@@ -0,0 +1,58 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::port_info_1::native;
+use crate::erlang::{open_port_2, port_close_1};
+use crate::test::with_process;
+
+#[test]
+fn without_local_port_errors_badarg() {
+    with_process(|process| {
+        let port = process.integer(0).unwrap();
+
+        assert_badarg!(native(process, port), "port (0) is not a local port");
+    });
+}
+
+#[test]
+fn without_open_port_returns_undefined() {
+    with_process(|process| {
+        assert_eq!(native(process, Port::next_term()), Ok(atom!("undefined")));
+    });
+}
+
+#[test]
+fn with_open_port_returns_items() {
+    with_process(|process| {
+        let command = process.charlist_from_str("cat").unwrap();
+        let port_name = process
+            .tuple_from_slice(&[Atom::str_to_term("spawn"), command])
+            .unwrap();
+        let port = open_port_2::native(process, port_name, Term::NIL).unwrap();
+
+        let info = native(process, port).unwrap();
+        let info_cons: Boxed<Cons> = info.try_into().unwrap();
+        let items: Vec<Term> = info_cons
+            .into_iter()
+            .map(|result| result.unwrap())
+            .collect();
+        let pid = process.pid_term();
+        let pid_list = process.list_from_slice(&[pid]).unwrap();
+
+        assert_eq!(items.len(), 7);
+        assert_eq!(items[0], item(process, "name", command));
+        assert_eq!(items[1], item(process, "links", pid_list));
+        assert_eq!(items[3], item(process, "connected", pid));
+
+        assert_eq!(port_close_1::native(port), Ok(true.into()));
+    });
+}
+
+fn item(process: &Process, key: &str, value: Term) -> Term {
+    process
+        .tuple_from_slice(&[Atom::str_to_term(key), value])
+        .unwrap()
+}
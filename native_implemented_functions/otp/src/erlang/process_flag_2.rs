@@ -5,29 +5,115 @@
 #[cfg(all(not(target_arch = "wasm32"), test))]
 mod test;
 
+use std::convert::TryInto;
+
 use anyhow::*;
 
-use liblumen_alloc::erts::exception;
-use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::exception::{self, InternalResult};
+use liblumen_alloc::erts::process::{MaxHeapSize, MessageQueueData, Priority, Process};
 use liblumen_alloc::erts::term::prelude::*;
 
 use native_implemented_function::native_implemented_function;
 
 use lumen_rt_core::context::*;
 
+/// [BEAM](http://erlang.org/doc/man/erlang.html#process_flag-2) limits `save_calls` to `0..10000`.
+const MAX_SAVE_CALLS: usize = 10_000;
+
+/// Sets `flag` of `process` to `value`, returning the old value.
+///
+/// * `max_heap_size` is only enforced by runtimes that collect garbage when a process runs out of
+///   heap, like `lumen_rt_full`.
+/// * `message_queue_data` set to `off_heap` stores messages from other processes in heap
+///   fragments.
+/// * `save_calls` is only accepted as `0`, as saving calls is not supported.
+/// * `sensitive` hides the backtrace, dictionary and messages of the process from
+///   `process_info/2`.
 #[native_implemented_function(process_flag/2)]
 pub fn native(process: &Process, flag: Term, value: Term) -> exception::Result<Term> {
     let flag_atom = term_try_into_atom!(flag)?;
 
     match flag_atom.name() {
-        "error_handler" => unimplemented!(),
-        "max_heap_size" => unimplemented!(),
-        "message_queue_data" => unimplemented!(),
-        "min_bin_vheap_size" => unimplemented!(),
-        "min_heap_size" => unimplemented!(),
-        "priority" => unimplemented!(),
-        "save_calls" => unimplemented!(),
-        "sensitive" => unimplemented!(),
+        "error_handler" => {
+            let module = term_try_into_atom("error_handler value", value)?;
+
+            process.set_error_handler(module).encode().map_err(From::from)
+        }
+        "max_heap_size" => {
+            let max_heap_size: MaxHeapSize = value.try_into().context("max_heap_size value")?;
+            let min_heap_size = process.min_heap_size();
+
+            if max_heap_size.is_enabled() && max_heap_size.size < min_heap_size {
+                return Err(anyhow!(
+                    "max_heap_size size ({}) is less than min_heap_size ({})",
+                    max_heap_size.size,
+                    min_heap_size
+                )
+                .into());
+            }
+
+            let old_max_heap_size = process.set_max_heap_size(max_heap_size);
+
+            max_heap_size_to_term(process, old_max_heap_size).map_err(From::from)
+        }
+        "message_queue_data" => {
+            let message_queue_data: MessageQueueData = value.try_into()?;
+            let old_message_queue_data = process.set_message_queue_data(message_queue_data);
+
+            Atom::from(old_message_queue_data)
+                .encode()
+                .map_err(From::from)
+        }
+        "min_bin_vheap_size" => {
+            let min_bin_vheap_size = term_try_into_words("min_bin_vheap_size value", value)?;
+
+            process
+                .integer(process.set_min_vheap_size(min_bin_vheap_size))
+                .map_err(From::from)
+        }
+        "min_heap_size" => {
+            let min_heap_size = term_try_into_words("min_heap_size value", value)?;
+
+            process
+                .integer(process.set_min_heap_size(min_heap_size))
+                .map_err(From::from)
+        }
+        "priority" => {
+            let priority: Priority = value.try_into()?;
+
+            Atom::from(process.set_priority(priority))
+                .encode()
+                .map_err(From::from)
+        }
+        "save_calls" => {
+            let save_calls = term_try_into_words("save_calls value", value)?;
+
+            if MAX_SAVE_CALLS < save_calls {
+                return Err(anyhow!(
+                    "save_calls value ({}) is greater than {}",
+                    save_calls,
+                    MAX_SAVE_CALLS
+                )
+                .into());
+            }
+            // Calls are made directly by compiled code, so there is nowhere to save them
+            if 0 < save_calls {
+                return Err(anyhow!(
+                    "save_calls value ({}) is not 0, as saving calls is not supported",
+                    save_calls
+                )
+                .into());
+            }
+
+            process
+                .integer(process.set_save_calls(save_calls))
+                .map_err(From::from)
+        }
+        "sensitive" => {
+            let value_bool: bool = term_try_into_bool("sensitive value", value)?;
+
+            Ok(process.sensitive(value_bool).into())
+        }
         "trap_exit" => {
             let value_bool: bool = term_try_into_bool("trap_exit value", value)?;
 
@@ -36,3 +122,27 @@ pub fn native(process: &Process, flag: Term, value: Term) -> exception::Result<T
         name => Err(TryAtomFromTermError(name)).context("supported flags are error_handler, max_heap_size, message_queue_data, min_bin_vheap_size, min_heap_size, priority, save_calls, sensitive, and trap_exit").map_err(From::from),
     }
 }
+
+// Private
+
+/// `max_heap_size` is returned as a map even when it was set with only a size, like BEAM.
+fn max_heap_size_to_term(process: &Process, max_heap_size: MaxHeapSize) -> InternalResult<Term> {
+    let size = process.integer(max_heap_size.size)?;
+
+    process
+        .map_from_slice(&[
+            (
+                Atom::str_to_term("error_logger"),
+                max_heap_size.error_logger.into(),
+            ),
+            (Atom::str_to_term("kill"), max_heap_size.kill.into()),
+            (Atom::str_to_term("size"), size),
+        ])
+        .map_err(From::from)
+}
+
+fn term_try_into_words(name: &str, value: Term) -> anyhow::Result<usize> {
+    value
+        .try_into()
+        .with_context(|| term_is_not_non_negative_integer(name, value))
+}
@@ -0,0 +1,85 @@
+use super::*;
+
+use liblumen_alloc::erts::process::{MaxHeapSize, Process};
+
+#[test]
+fn with_size_returns_old_max_heap_size_map() {
+    with_process(|process| {
+        let size = process.integer(process.min_heap_size()).unwrap();
+
+        assert_eq!(
+            native(process, flag(), size),
+            Ok(max_heap_size_map(process, 0, true, true))
+        );
+        assert_eq!(
+            process.max_heap_size(),
+            MaxHeapSize {
+                size: process.min_heap_size(),
+                kill: true,
+                error_logger: true
+            }
+        );
+    });
+}
+
+#[test]
+fn with_map_returns_old_max_heap_size_map() {
+    with_process(|process| {
+        let size = process.min_heap_size() + 1;
+        let value = max_heap_size_map(process, size, false, false);
+
+        assert_eq!(
+            native(process, flag(), value),
+            Ok(max_heap_size_map(process, 0, true, true))
+        );
+        assert_eq!(
+            native(process, flag(), process.integer(0).unwrap()),
+            Ok(value)
+        );
+    });
+}
+
+#[test]
+fn with_size_less_than_min_heap_size_errors_badarg() {
+    with_process(|process| {
+        let min_heap_size = process.min_heap_size();
+        let size = process.integer(min_heap_size - 1).unwrap();
+
+        assert_badarg!(
+            native(process, flag(), size),
+            format!(
+                "max_heap_size size ({}) is less than min_heap_size ({})",
+                min_heap_size - 1,
+                min_heap_size
+            )
+        );
+    });
+}
+
+#[test]
+fn with_unsupported_map_key_errors_badarg() {
+    with_process(|process| {
+        let value = process
+            .map_from_slice(&[(Atom::str_to_term("limit"), process.integer(1).unwrap())])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, flag(), value),
+            "supported max_heap_size keys are size, kill, and error_logger"
+        );
+    });
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("max_heap_size")
+}
+
+fn max_heap_size_map(process: &Process, size: usize, kill: bool, error_logger: bool) -> Term {
+    process
+        .map_from_slice(&[
+            (Atom::str_to_term("error_logger"), error_logger.into()),
+            (Atom::str_to_term("kill"), kill.into()),
+            (Atom::str_to_term("size"), process.integer(size).unwrap()),
+        ])
+        .unwrap()
+}
@@ -0,0 +1,47 @@
+use super::*;
+
+use crate::test::{has_heap_message, with_process_arc};
+
+#[test]
+fn without_message_queue_data_value_errors_badarg() {
+    with_process(|process| {
+        let value = Atom::str_to_term("in_heap");
+
+        assert_badarg!(
+            native(process, flag(), value),
+            "supported message_queue_data are off_heap or on_heap"
+        );
+    });
+}
+
+#[test]
+fn with_message_queue_data_value_returns_old_value() {
+    with_process(|process| {
+        let off_heap = Atom::str_to_term("off_heap");
+
+        assert_eq!(
+            native(process, flag(), off_heap),
+            Ok(Atom::str_to_term("on_heap"))
+        );
+        assert_eq!(
+            native(process, flag(), Atom::str_to_term("on_heap")),
+            Ok(off_heap)
+        );
+    });
+}
+
+#[test]
+fn with_off_heap_value_stores_messages_from_other_processes_in_heap_fragments() {
+    with_process_arc(|arc_process| {
+        native(&arc_process, flag(), Atom::str_to_term("off_heap")).unwrap();
+
+        let message = Atom::str_to_term("message");
+        arc_process.send_from_other(message).unwrap();
+
+        assert!(has_heap_message(&arc_process, message));
+    });
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("message_queue_data")
+}
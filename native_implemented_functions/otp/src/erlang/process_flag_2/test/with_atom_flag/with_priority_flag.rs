@@ -0,0 +1,35 @@
+use super::*;
+
+use liblumen_alloc::erts::process::Priority;
+
+#[test]
+fn without_priority_value_errors_badarg() {
+    with_process(|process| {
+        let value = Atom::str_to_term("urgent");
+
+        assert_badarg!(
+            native(process, flag(), value),
+            "supported priorities are low, normal, high, or max"
+        );
+    });
+}
+
+#[test]
+fn with_priority_value_returns_old_priority() {
+    with_process(|process| {
+        let high = Atom::str_to_term("high");
+
+        assert_eq!(
+            native(process, flag(), high),
+            Ok(Atom::str_to_term("normal"))
+        );
+        assert_eq!(process.priority(), Priority::High);
+
+        assert_eq!(native(process, flag(), Atom::str_to_term("low")), Ok(high));
+        assert_eq!(process.priority(), Priority::Low);
+    });
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("priority")
+}
@@ -0,0 +1,58 @@
+use super::*;
+
+#[test]
+fn without_non_negative_integer_value_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_non_negative_integer(arc_process.clone()),
+            )
+        },
+        |(arc_process, value)| {
+            prop_assert_badarg!(
+                native(&arc_process, flag(), value),
+                format!("save_calls value ({}) is not a non-negative integer", value)
+            );
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_value_greater_than_10000_errors_badarg() {
+    with_process(|process| {
+        let value = process.integer(10_001).unwrap();
+
+        assert_badarg!(
+            native(process, flag(), value),
+            "save_calls value (10001) is greater than 10000"
+        );
+    });
+}
+
+#[test]
+fn with_positive_value_errors_badarg() {
+    with_process(|process| {
+        let value = process.integer(10).unwrap();
+
+        assert_badarg!(
+            native(process, flag(), value),
+            "save_calls value (10) is not 0, as saving calls is not supported"
+        );
+    });
+}
+
+#[test]
+fn with_zero_value_returns_old_value() {
+    with_process(|process| {
+        let value = process.integer(0).unwrap();
+
+        assert_eq!(native(process, flag(), value), Ok(value));
+    });
+}
+
+fn flag() -> Term {
+    Atom::str_to_term("save_calls")
+}
@@ -0,0 +1,70 @@
+// wasm32 proptest cannot be compiled at the same time as non-wasm32 proptest, so disable tests that
+// use proptest completely for wasm32
+//
+// See https://github.com/rust-lang/cargo/issues/4866
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::{self, InternalResult};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::registry::pid_to_process;
+
+use crate::erlang::process_info_2::item_value_tuple;
+
+/// The items returned by `process_info/1`, after `registered_name` when the process is registered.
+const ITEMS: &[&str] = &[
+    "current_function",
+    "initial_call",
+    "status",
+    "message_queue_len",
+    "links",
+    "dictionary",
+    "trap_exit",
+    "error_handler",
+    "priority",
+    "group_leader",
+    "total_heap_size",
+    "heap_size",
+    "stack_size",
+    "reductions",
+    "garbage_collection",
+    "suspending",
+];
+
+#[native_implemented_function(process_info/1)]
+pub fn native(process: &Process, pid: Term) -> exception::Result<Term> {
+    let pid_pid = term_try_into_local_pid!(pid)?;
+
+    if process.pid() == pid_pid {
+        process_info(process, process)
+    } else {
+        match pid_to_process(&pid_pid) {
+            Some(pid_arc_process) => process_info(process, &pid_arc_process),
+            None => Ok(atom!("undefined")),
+        }
+    }
+    .map_err(From::from)
+}
+
+// Private
+
+fn process_info(process: &Process, pid_process: &Process) -> InternalResult<Term> {
+    let mut tuple_vec = Vec::with_capacity(ITEMS.len() + 1);
+
+    if pid_process.registered_name.read().is_some() {
+        let registered_name = Atom::try_from_str("registered_name").unwrap();
+        tuple_vec.push(item_value_tuple(process, pid_process, registered_name)?);
+    }
+
+    for item in ITEMS {
+        let item_atom = Atom::try_from_str(item).unwrap();
+        tuple_vec.push(item_value_tuple(process, pid_process, item_atom)?);
+    }
+
+    process.list_from_slice(&tuple_vec).map_err(From::from)
+}
@@ -0,0 +1,74 @@
+use std::convert::TryInto;
+
+use proptest::strategy::Just;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use lumen_rt_core::registry;
+
+use crate::erlang::process_info_1::native;
+use crate::test;
+use crate::test::{registered_name, strategy, with_process_arc};
+
+#[test]
+fn without_local_pid_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_local_pid(arc_process.clone()),
+            )
+        },
+        |(arc_process, pid)| {
+            prop_assert_is_not_local_pid!(native(&arc_process, pid), pid);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn with_dead_pid_returns_undefined() {
+    with_process_arc(|arc_process| {
+        let pid = Pid::next_term();
+
+        assert_eq!(
+            native(&arc_process, pid),
+            Ok(Atom::str_to_term("undefined"))
+        );
+    });
+}
+
+#[test]
+fn without_registered_name_starts_with_current_function() {
+    with_process_arc(|arc_process| {
+        let info = native(&arc_process, arc_process.pid_term()).unwrap();
+
+        assert_eq!(first_item(info), Atom::str_to_term("current_function"));
+    });
+}
+
+#[test]
+fn with_registered_name_starts_with_registered_name() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+        let name = registered_name();
+        let name_atom: Atom = name.try_into().unwrap();
+
+        assert!(registry::put_atom_to_process(
+            name_atom,
+            child_arc_process.clone()
+        ));
+
+        let info = native(&parent_arc_process, child_arc_process.pid_term()).unwrap();
+
+        assert_eq!(first_item(info), Atom::str_to_term("registered_name"));
+    });
+}
+
+fn first_item(info: Term) -> Term {
+    let cons: Boxed<Cons> = info.try_into().unwrap();
+    let tuple: Boxed<Tuple> = cons.head.try_into().unwrap();
+
+    tuple[0]
+}
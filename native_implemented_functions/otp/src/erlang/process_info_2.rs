@@ -5,73 +5,321 @@
 #[cfg(all(not(target_arch = "wasm32"), test))]
 mod test;
 
+use std::mem;
+use std::sync::atomic::Ordering;
+
 use anyhow::*;
 
 use liblumen_alloc::atom;
 use liblumen_alloc::erts::exception::{self, InternalResult};
-use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::process::{Monitor, Process, Status};
 use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, ModuleFunctionArity};
 
 use native_implemented_function::native_implemented_function;
 
 use lumen_rt_core::registry::pid_to_process;
 
+use crate::erlang::node_0;
+
 #[native_implemented_function(process_info/2)]
 pub fn native(process: &Process, pid: Term, item: Term) -> exception::Result<Term> {
     let pid_pid = term_try_into_local_pid!(pid)?;
-    let item_atom: Atom = term_try_into_atom!(item)?;
+
+    // Check the items before the process, so that bad items are reported even for dead processes
+    let item_or_items = match item.decode().unwrap() {
+        TypedTerm::Nil => ItemOrItems::Items(Vec::new()),
+        TypedTerm::List(cons) => {
+            let mut item_vec = Vec::new();
+
+            for result in cons.into_iter() {
+                match result {
+                    Ok(element) => item_vec.push(term_try_into_item(element)?),
+                    Err(_) => {
+                        return Err(ImproperListError)
+                            .context(format!("item ({}) is not a proper list", item))
+                            .map_err(From::from)
+                    }
+                }
+            }
+
+            ItemOrItems::Items(item_vec)
+        }
+        _ => ItemOrItems::Item(term_try_into_item(item)?),
+    };
 
     if process.pid() == pid_pid {
-        process_info(process, item_atom)
+        process_info(process, process, item_or_items)
     } else {
         match pid_to_process(&pid_pid) {
-            Some(pid_arc_process) => process_info(&pid_arc_process, item_atom),
+            Some(pid_arc_process) => process_info(process, &pid_arc_process, item_or_items),
             None => Ok(atom!("undefined")),
         }
     }
     .map_err(From::from)
 }
 
+/// The `{Item, Value}` for `item` of `pid_process`, allocated on `process`.
+pub(in crate::erlang) fn item_value_tuple(
+    process: &Process,
+    pid_process: &Process,
+    item: Atom,
+) -> InternalResult<Term> {
+    let value = match item.name() {
+        // Like BEAM, the stack, dictionary and messages of a sensitive process are not shown
+        "backtrace" if pid_process.is_sensitive() => process.binary_from_str("")?,
+        "backtrace" => process.binary_from_str(&pid_process.stacktrace().to_string())?,
+        "current_function" => match pid_process.current_module_function_arity() {
+            Some(module_function_arity) => {
+                module_function_arity_tuple(process, &module_function_arity)?
+            }
+            None => atom!("undefined"),
+        },
+        "current_location" => match pid_process.current_module_function_arity() {
+            Some(module_function_arity) => location_tuple(process, &module_function_arity)?,
+            None => atom!("undefined"),
+        },
+        "current_stacktrace" => {
+            let mut location_vec = Vec::new();
+
+            for module_function_arity in pid_process.stacktrace().iter() {
+                location_vec.push(location_tuple(process, module_function_arity)?);
+            }
+
+            process.list_from_slice(&location_vec)?
+        }
+        "dictionary" if pid_process.is_sensitive() => Term::NIL,
+        "dictionary" => {
+            let mut entry_vec = Vec::new();
+
+            // Built on `process`, as `pid_process` may be running and allocating on its own heap
+            for (key, value) in pid_process.dictionary_entry_vec() {
+                let (key, value) = if process.pid() == pid_process.pid() {
+                    (key, value)
+                } else {
+                    (
+                        key.clone_to_process(process),
+                        value.clone_to_process(process),
+                    )
+                };
+
+                entry_vec.push(process.tuple_from_slice(&[key, value])?);
+            }
+
+            process.list_from_slice(&entry_vec)?
+        }
+        "error_handler" => pid_process.error_handler().encode()?,
+        "garbage_collection" => {
+            let min_bin_vheap_size = process.integer(pid_process.min_vheap_size())?;
+            let min_heap_size = process.integer(pid_process.min_heap_size())?;
+            let fullsweep_after = process.integer(pid_process.max_gen_gcs())?;
+
+            keyword_list(
+                process,
+                &[
+                    ("min_bin_vheap_size", min_bin_vheap_size),
+                    ("min_heap_size", min_heap_size),
+                    ("fullsweep_after", fullsweep_after),
+                ],
+            )?
+        }
+        "garbage_collection_info" => {
+            let heap_block_size = process.integer(pid_process.heap_size())?;
+            let mbuf_size = process.integer(pid_process.off_heap_size())?;
+            let stack_size = process.integer(pid_process.stack_used())?;
+
+            keyword_list(
+                process,
+                &[
+                    ("heap_block_size", heap_block_size),
+                    ("mbuf_size", mbuf_size),
+                    ("stack_size", stack_size),
+                ],
+            )?
+        }
+        "group_leader" => pid_process.get_group_leader_pid_term(),
+        "heap_size" => process.integer(pid_process.heap_size())?,
+        "initial_call" => {
+            module_function_arity_tuple(process, &pid_process.initial_module_function_arity)?
+        }
+        "links" => {
+            let pid_vec: Vec<Term> = pid_process
+                .linked_pid_set
+                .iter()
+                .map(|entry| entry.key().encode().unwrap())
+                .collect();
+
+            process.list_from_slice(&pid_vec)?
+        }
+        "last_calls" => false.into(),
+        "memory" => {
+            let words =
+                pid_process.heap_size() + pid_process.off_heap_size() + pid_process.stack_used();
+
+            process.integer(mem::size_of::<Process>() + words * mem::size_of::<Term>())?
+        }
+        "message_queue_len" => {
+            let len = pid_process.mailbox.lock().borrow().len();
+
+            process.integer(len)?
+        }
+        "messages" if pid_process.is_sensitive() => Term::NIL,
+        "messages" => {
+            let mailbox_guard = pid_process.mailbox.lock();
+            let mailbox = mailbox_guard.borrow();
+            let mut message_vec = Vec::with_capacity(mailbox.len());
+
+            for message in mailbox.iter() {
+                let data = *message.data();
+
+                message_vec.push(if process.pid() == pid_process.pid() {
+                    data
+                } else {
+                    data.clone_to_process(process)
+                });
+            }
+
+            process.list_from_slice(&message_vec)?
+        }
+        "min_heap_size" => process.integer(pid_process.min_heap_size())?,
+        "min_bin_vheap_size" => process.integer(pid_process.min_vheap_size())?,
+        "monitored_by" => {
+            let pid_vec: Vec<Term> = pid_process
+                .monitor_by_reference
+                .iter()
+                .map(|entry| entry.value().monitoring_pid().encode().unwrap())
+                .collect();
+
+            process.list_from_slice(&pid_vec)?
+        }
+        "monitors" => {
+            let mut monitor_vec = Vec::new();
+
+            for entry in pid_process.monitored_pid_by_reference.iter() {
+                let identifier = match monitored_name(entry.key(), entry.value()) {
+                    Some(monitored_name) => {
+                        process.tuple_from_slice(&[monitored_name.encode()?, node_0::native()])?
+                    }
+                    None => entry.value().encode()?,
+                };
+                monitor_vec.push(process.tuple_from_slice(&[atom!("process"), identifier])?);
+            }
+
+            process.list_from_slice(&monitor_vec)?
+        }
+        "message_queue_data" => Atom::from(pid_process.message_queue_data()).encode()?,
+        "priority" => Atom::from(pid_process.priority()).encode()?,
+        "reductions" => process.integer(pid_process.total_reductions.load(Ordering::SeqCst))?,
+        "registered_name" => match *pid_process.registered_name.read() {
+            Some(registered_name) => registered_name.encode()?,
+            None => Term::NIL,
+        },
+        "sequential_trace_token" => Term::NIL,
+        "stack_size" => process.integer(pid_process.stack_used())?,
+        "status" => match *pid_process.status.read() {
+            Status::Runnable => atom!("runnable"),
+            Status::Running => atom!("running"),
+            Status::Waiting => atom!("waiting"),
+            Status::Exiting(_) => atom!("exiting"),
+        },
+        "suspending" => Term::NIL,
+        "total_heap_size" => {
+            process.integer(pid_process.heap_size() + pid_process.off_heap_size())?
+        }
+        "trace" => process.integer(0)?,
+        "trap_exit" => pid_process.traps_exit().into(),
+        _ => unreachable!("{} should have been rejected by term_try_into_item", item),
+    };
+
+    process
+        .tuple_from_slice(&[item.encode()?, value])
+        .map_err(From::from)
+}
+
 // Private
 
-fn process_info(process: &Process, item: Atom) -> InternalResult<Term> {
-    match item.name() {
-        "backtrace" => unimplemented!(),
-        "binary" => unimplemented!(),
-        "catchlevel" => unimplemented!(),
-        "current_function" => unimplemented!(),
-        "current_location" => unimplemented!(),
-        "current_stacktrace" => unimplemented!(),
-        "dictionary" => unimplemented!(),
-        "error_handler" => unimplemented!(),
-        "garbage_collection" => unimplemented!(),
-        "garbage_collection_info" => unimplemented!(),
-        "group_leader" => unimplemented!(),
-        "heap_size" => unimplemented!(),
-        "initial_call" => unimplemented!(),
-        "links" => unimplemented!(),
-        "last_calls" => unimplemented!(),
-        "memory" => unimplemented!(),
-        "message_queue_len" => unimplemented!(),
-        "messages" => unimplemented!(),
-        "min_heap_size" => unimplemented!(),
-        "min_bin_vheap_size" => unimplemented!(),
-        "monitored_by" => unimplemented!(),
-        "monitors" => unimplemented!(),
-        "message_queue_data" => unimplemented!(),
-        "priority" => unimplemented!(),
-        "reductions" => unimplemented!(),
-        "registered_name" => registered_name(process),
-        "sequential_trace_token" => unimplemented!(),
-        "stack_size" => unimplemented!(),
-        "status" => unimplemented!(),
-        "suspending" => unimplemented!(),
-        "total_heap_size" => unimplemented!(),
-        "trace" => unimplemented!(),
-        "trap_exit" => unimplemented!(),
+enum ItemOrItems {
+    Item(Atom),
+    Items(Vec<Atom>),
+}
+
+fn process_info(
+    process: &Process,
+    pid_process: &Process,
+    item_or_items: ItemOrItems,
+) -> InternalResult<Term> {
+    match item_or_items {
+        // Only the single item form returns a bare `[]` when `pid_process` is not registered
+        ItemOrItems::Item(item)
+            if item.name() == "registered_name" && pid_process.registered_name.read().is_none() =>
+        {
+            Ok(Term::NIL)
+        }
+        ItemOrItems::Item(item) => item_value_tuple(process, pid_process, item),
+        ItemOrItems::Items(items) => {
+            let mut tuple_vec = Vec::with_capacity(items.len());
+
+            for item in items {
+                tuple_vec.push(item_value_tuple(process, pid_process, item)?);
+            }
+
+            process.list_from_slice(&tuple_vec).map_err(From::from)
+        }
+    }
+}
+
+/// The name `monitored_pid` was monitored by, if the monitor under `reference` was created with
+/// one, which only the monitored process records.
+fn monitored_name(reference: &Reference, monitored_pid: &Pid) -> Option<Atom> {
+    pid_to_process(monitored_pid).and_then(|monitored_arc_process| {
+        monitored_arc_process
+            .monitor_by_reference
+            .get(reference)
+            .and_then(|monitor| match *monitor {
+                Monitor::Name { monitored_name, .. } => Some(monitored_name),
+                Monitor::Pid { .. } => None,
+            })
+    })
+}
+
+fn term_try_into_item(item: Term) -> InternalResult<Atom> {
+    let item_atom: Atom = term_try_into_atom!(item)?;
+
+    match item_atom.name() {
+        "backtrace"
+        | "current_function"
+        | "current_location"
+        | "current_stacktrace"
+        | "dictionary"
+        | "error_handler"
+        | "garbage_collection"
+        | "garbage_collection_info"
+        | "group_leader"
+        | "heap_size"
+        | "initial_call"
+        | "links"
+        | "last_calls"
+        | "memory"
+        | "message_queue_len"
+        | "messages"
+        | "min_heap_size"
+        | "min_bin_vheap_size"
+        | "monitored_by"
+        | "monitors"
+        | "message_queue_data"
+        | "priority"
+        | "reductions"
+        | "registered_name"
+        | "sequential_trace_token"
+        | "stack_size"
+        | "status"
+        | "suspending"
+        | "total_heap_size"
+        | "trace"
+        | "trap_exit" => Ok(item_atom),
         name => Err(TryAtomFromTermError(name))
             .context(
-                "supported items are backtrace, binary, catchlevel, current_function, \
+                "supported items are backtrace, current_function, \
                  current_location, current_stacktrace, dictionary, error_handler, \
                  garbage_collection, garbage_collection_info, group_leader, heap_size, \
                  initial_call, links, last_calls, memory, message_queue_len, messages, \
@@ -84,16 +332,41 @@ fn process_info(process: &Process, item: Atom) -> InternalResult<Term> {
     }
 }
 
-fn registered_name(process: &Process) -> InternalResult<Term> {
-    match *process.registered_name.read() {
-        Some(registered_name) => {
-            let tag = atom!("registered_name");
-            let value = registered_name.encode()?;
+fn keyword_list(process: &Process, keywords: &[(&str, Term)]) -> InternalResult<Term> {
+    let mut tuple_vec = Vec::with_capacity(keywords.len());
 
-            process
-                .tuple_from_slice(&[tag, value])
-                .map_err(|error| error.into())
-        }
-        None => Ok(Term::NIL),
+    for (key, value) in keywords {
+        let key_term = Atom::str_to_term(key);
+        tuple_vec.push(process.tuple_from_slice(&[key_term, *value])?);
     }
+
+    process.list_from_slice(&tuple_vec).map_err(From::from)
+}
+
+/// `{Module, Function, Arity, Location}` with an empty `Location`, as file and line are not
+/// tracked.
+fn location_tuple(
+    process: &Process,
+    module_function_arity: &ModuleFunctionArity,
+) -> InternalResult<Term> {
+    let module = module_function_arity.module.encode()?;
+    let function = module_function_arity.function.encode()?;
+    let arity = process.integer(module_function_arity.arity)?;
+
+    process
+        .tuple_from_slice(&[module, function, arity, Term::NIL])
+        .map_err(From::from)
+}
+
+fn module_function_arity_tuple(
+    process: &Process,
+    module_function_arity: &ModuleFunctionArity,
+) -> InternalResult<Term> {
+    let module = module_function_arity.module.encode()?;
+    let function = module_function_arity.function.encode()?;
+    let arity = process.integer(module_function_arity.arity)?;
+
+    process
+        .tuple_from_slice(&[module, function, arity])
+        .map_err(From::from)
 }
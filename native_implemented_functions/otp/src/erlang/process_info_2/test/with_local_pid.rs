@@ -1,3 +1,9 @@
+mod with_dictionary;
+mod with_item_list;
+mod with_links;
+mod with_message_queue_len;
+mod with_messages;
+mod with_monitors;
 mod with_registered_name;
 
 use super::*;
@@ -10,7 +16,7 @@ fn without_supported_item_errors_badarg() {
                 let pid = arc_process.pid_term();
                 prop_assert_badarg!(
                     native(&arc_process, pid, item),
-                    "supported items are backtrace, binary, catchlevel, current_function, \
+                    "supported items are backtrace, current_function, \
                      current_location, current_stacktrace, dictionary, error_handler, \
                      garbage_collection, garbage_collection_info, group_leader, heap_size, \
                      initial_call, links, last_calls, memory, message_queue_len, messages, \
@@ -29,7 +35,37 @@ fn without_supported_item_errors_badarg() {
 fn unsupported_item_atom() -> BoxedStrategy<Term> {
     strategy::atom()
         .prop_filter("Item cannot be supported", |atom| match atom.name() {
-            "registered_name" => false,
+            "backtrace"
+            | "current_function"
+            | "current_location"
+            | "current_stacktrace"
+            | "dictionary"
+            | "error_handler"
+            | "garbage_collection"
+            | "garbage_collection_info"
+            | "group_leader"
+            | "heap_size"
+            | "initial_call"
+            | "links"
+            | "last_calls"
+            | "memory"
+            | "message_queue_len"
+            | "messages"
+            | "min_heap_size"
+            | "min_bin_vheap_size"
+            | "monitored_by"
+            | "monitors"
+            | "message_queue_data"
+            | "priority"
+            | "reductions"
+            | "registered_name"
+            | "sequential_trace_token"
+            | "stack_size"
+            | "status"
+            | "suspending"
+            | "total_heap_size"
+            | "trace"
+            | "trap_exit" => false,
             _ => true,
         })
         .prop_map(|atom| atom.encode().unwrap())
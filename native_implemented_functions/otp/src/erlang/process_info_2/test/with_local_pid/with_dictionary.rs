@@ -0,0 +1,65 @@
+use super::*;
+
+#[test]
+fn with_self_returns_entries() {
+    with_process_arc(|arc_process| {
+        let key = Atom::str_to_term("key");
+        let value = Atom::str_to_term("value");
+        arc_process.put(key, value).unwrap();
+
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    arc_process
+                        .list_from_slice(&[arc_process.tuple_from_slice(&[key, value]).unwrap()])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_other_returns_copy_of_entries() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+        let key = Atom::str_to_term("key");
+        let value = child_arc_process.list_from_slice(&[key]).unwrap();
+        child_arc_process.put(key, value).unwrap();
+
+        assert_eq!(
+            native(&parent_arc_process, child_arc_process.pid_term(), item()),
+            Ok(parent_arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    parent_arc_process
+                        .list_from_slice(&[parent_arc_process
+                            .tuple_from_slice(&[key, value])
+                            .unwrap()])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_sensitive_returns_empty_list() {
+    with_process_arc(|arc_process| {
+        let key = Atom::str_to_term("key");
+        let value = Atom::str_to_term("value");
+        arc_process.put(key, value).unwrap();
+        arc_process.sensitive(true);
+
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process.tuple_from_slice(&[item(), Term::NIL]).unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("dictionary")
+}
@@ -0,0 +1,58 @@
+use super::*;
+
+#[test]
+fn with_empty_list_returns_empty_list() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), Term::NIL),
+            Ok(Term::NIL)
+        );
+    });
+}
+
+#[test]
+fn with_items_returns_list_of_item_value_tuples_in_order() {
+    with_process_arc(|arc_process| {
+        let trap_exit = Atom::str_to_term("trap_exit");
+        let priority = Atom::str_to_term("priority");
+        let items = arc_process.list_from_slice(&[trap_exit, priority]).unwrap();
+
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), items),
+            Ok(arc_process
+                .list_from_slice(&[
+                    arc_process
+                        .tuple_from_slice(&[trap_exit, false.into()])
+                        .unwrap(),
+                    arc_process
+                        .tuple_from_slice(&[priority, Atom::str_to_term("normal")])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_unsupported_item_in_list_errors_badarg() {
+    with_process_arc(|arc_process| {
+        let items = arc_process
+            .list_from_slice(&[
+                Atom::str_to_term("trap_exit"),
+                Atom::str_to_term("unsupported"),
+            ])
+            .unwrap();
+
+        assert_badarg!(
+            native(&arc_process, arc_process.pid_term(), items),
+            "supported items are backtrace, current_function, \
+             current_location, current_stacktrace, dictionary, error_handler, \
+             garbage_collection, garbage_collection_info, group_leader, heap_size, \
+             initial_call, links, last_calls, memory, message_queue_len, messages, \
+             min_heap_size, min_bin_vheap_size, monitored_by, monitors, \
+             message_queue_data, priority, reductions, registered_name, \
+             sequential_trace_token, stack_size, status, suspending, \
+             total_heap_size, trace, trap_exit"
+        );
+    });
+}
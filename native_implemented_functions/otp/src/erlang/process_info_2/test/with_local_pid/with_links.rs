@@ -0,0 +1,35 @@
+use super::*;
+
+#[test]
+fn without_links_returns_empty_list() {
+    with_process_arc(|arc_process| {
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process.tuple_from_slice(&[item(), Term::NIL]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_link_returns_linked_pid() {
+    with_process_arc(|parent_arc_process| {
+        let child_arc_process = test::process::child(&parent_arc_process);
+        parent_arc_process.link(&child_arc_process);
+
+        assert_eq!(
+            native(&parent_arc_process, child_arc_process.pid_term(), item()),
+            Ok(parent_arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    parent_arc_process
+                        .list_from_slice(&[parent_arc_process.pid_term()])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("links")
+}
@@ -0,0 +1,33 @@
+use super::*;
+
+#[test]
+fn with_self_returns_messages() {
+    with_process_arc(|arc_process| {
+        let message = Atom::str_to_term("message");
+        arc_process.send_from_self(message);
+
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process
+                .tuple_from_slice(&[item(), arc_process.list_from_slice(&[message]).unwrap()])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_sensitive_returns_empty_list() {
+    with_process_arc(|arc_process| {
+        arc_process.send_from_self(Atom::str_to_term("message"));
+        arc_process.sensitive(true);
+
+        assert_eq!(
+            native(&arc_process, arc_process.pid_term(), item()),
+            Ok(arc_process.tuple_from_slice(&[item(), Term::NIL]).unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("messages")
+}
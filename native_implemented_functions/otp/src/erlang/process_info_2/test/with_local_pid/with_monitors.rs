@@ -0,0 +1,84 @@
+use super::*;
+
+use crate::erlang::{monitor_2, node_0};
+
+#[test]
+fn with_pid_monitor_returns_monitored_pid() {
+    with_process_arc(|monitoring_arc_process| {
+        let monitored_arc_process = test::process::child(&monitoring_arc_process);
+
+        assert!(monitor_2::native(
+            &monitoring_arc_process,
+            Atom::str_to_term("process"),
+            monitored_arc_process.pid_term()
+        )
+        .is_ok());
+
+        assert_eq!(
+            native(
+                &monitoring_arc_process,
+                monitoring_arc_process.pid_term(),
+                item()
+            ),
+            Ok(monitoring_arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    monitoring_arc_process
+                        .list_from_slice(&[monitoring_arc_process
+                            .tuple_from_slice(&[
+                                Atom::str_to_term("process"),
+                                monitored_arc_process.pid_term()
+                            ])
+                            .unwrap()])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_registered_name_monitor_returns_monitored_name_and_node() {
+    with_process_arc(|monitoring_arc_process| {
+        let monitored_arc_process = test::process::child(&monitoring_arc_process);
+        let registered_name = registered_name();
+        let registered_name_atom: Atom = registered_name.try_into().unwrap();
+
+        assert!(registry::put_atom_to_process(
+            registered_name_atom,
+            monitored_arc_process.clone()
+        ));
+        assert!(monitor_2::native(
+            &monitoring_arc_process,
+            Atom::str_to_term("process"),
+            registered_name
+        )
+        .is_ok());
+
+        let identifier = monitoring_arc_process
+            .tuple_from_slice(&[registered_name, node_0::native()])
+            .unwrap();
+
+        assert_eq!(
+            native(
+                &monitoring_arc_process,
+                monitoring_arc_process.pid_term(),
+                item()
+            ),
+            Ok(monitoring_arc_process
+                .tuple_from_slice(&[
+                    item(),
+                    monitoring_arc_process
+                        .list_from_slice(&[monitoring_arc_process
+                            .tuple_from_slice(&[Atom::str_to_term("process"), identifier])
+                            .unwrap()])
+                        .unwrap()
+                ])
+                .unwrap())
+        );
+    });
+}
+
+fn item() -> Term {
+    Atom::str_to_term("monitors")
+}
@@ -14,6 +14,26 @@ fn without_registered_returns_empty_list() {
     });
 }
 
+#[test]
+fn without_registered_in_item_list_returns_tuple_with_empty_list() {
+    with_process_arc(|unregistered_process_arc| {
+        let items = unregistered_process_arc.list_from_slice(&[item()]).unwrap();
+
+        assert_eq!(
+            native(
+                &unregistered_process_arc,
+                unregistered_process_arc.pid_term(),
+                items
+            ),
+            Ok(unregistered_process_arc
+                .list_from_slice(&[unregistered_process_arc
+                    .tuple_from_slice(&[item(), Term::NIL])
+                    .unwrap()])
+                .unwrap())
+        );
+    });
+}
+
 #[test]
 fn with_registered_returns_empty_list() {
     with_process_arc(|registered_process_arc| {
@@ -6,6 +6,10 @@ use proptest::{prop_assert, prop_assert_eq};
 
 use liblumen_alloc::erts::term::prelude::*;
 
+use lumen_rt_core::time::monotonic;
+
+use lumen_rt_full::timer;
+
 use crate::erlang;
 use crate::erlang::send_after_3::native;
 use crate::test;
@@ -78,6 +78,43 @@ fn with_same_process_sends_message_when_timer_expires() {
     );
 }
 
+#[test]
+fn with_same_process_sends_message_when_skipping_to_timer() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                milliseconds(),
+                strategy::term(arc_process),
+            )
+        },
+        |(arc_process, milliseconds, message)| {
+            let time = arc_process.integer(milliseconds).unwrap();
+            let destination = arc_process.pid_term();
+
+            let start_time_in_milliseconds = freeze_timeout();
+
+            let result = native(arc_process.clone(), time, destination, message);
+
+            prop_assert!(
+                result.is_ok(),
+                "Timer reference not returned.  Got {:?}",
+                result
+            );
+            prop_assert!(!has_message(&arc_process, message));
+
+            prop_assert!(timer::skip_to_next_timeout());
+            prop_assert_eq!(
+                monotonic::time_in_milliseconds(),
+                start_time_in_milliseconds + milliseconds + 1
+            );
+            prop_assert!(has_message(&arc_process, message));
+
+            Ok(())
+        },
+    );
+}
+
 #[test]
 fn without_process_sends_nothing_when_timer_expires() {
     run!(
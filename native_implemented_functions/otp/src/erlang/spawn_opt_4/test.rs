@@ -46,7 +46,8 @@ fn without_proper_list_options_errors_badarg() {
 
 const SUPPORTED_OPTIONS: &str = "supported options are :link, :monitor, \
                                  {:fullsweep_after, generational_collections :: pos_integer()}, \
-                                 {:max_heap_size, words :: pos_integer()}, \
+                                 {:max_heap_size, words :: non_neg_integer() | \
+                                 %{size: words, kill: boolean(), error_logger: boolean()}}, \
                                  {:message_queue_data, :off_heap | :on_heap}, \
                                  {:min_bin_vheap_size, words :: pos_integer()}, \
                                  {:min_heap_size, words :: pos_integer()}, and \
@@ -37,7 +37,7 @@ fn unregistered_sends_nothing_when_timer_expires() {
 
             prop_assert!(!has_message(&arc_process, timeout_message));
 
-            monotonic::freeze_at_time_in_milliseconds(
+            monotonic::set_virtual_time_in_milliseconds(
                 start_time_in_milliseconds + milliseconds + 1,
             );
 
@@ -9,8 +9,8 @@ use compression::*;
 use minor_version::*;
 
 pub struct Options {
-    compression: Compression,
-    minor_version: MinorVersion,
+    pub compression: Compression,
+    pub minor_version: MinorVersion,
 }
 
 impl Default for Options {
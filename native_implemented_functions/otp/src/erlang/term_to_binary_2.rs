@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::Term;
+
+use native_implemented_function::native_implemented_function;
+
+use crate::erlang::term_to_binary::options::Options;
+use crate::erlang::term_to_binary::term_to_binary;
+
+#[native_implemented_function(term_to_binary/2)]
+pub fn native(process: &Process, term: Term, options: Term) -> exception::Result<Term> {
+    let options: Options = options.try_into().map_err(|_| {
+        anyhow!(
+            "options ({}) is not a proper list of compressed, {{compressed, 0..9}}, or {{minor_version, 0..2}}",
+            options
+        )
+    })?;
+
+    term_to_binary(process, term, options)
+}
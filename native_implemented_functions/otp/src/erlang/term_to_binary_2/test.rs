@@ -0,0 +1,103 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::erlang::term_to_binary_2::native;
+use crate::erlang::{binary_to_term_1, term_to_binary_1};
+use crate::test::with_process;
+
+#[test]
+fn without_list_options_errors_badarg() {
+    with_process(|process| {
+        let options = Atom::str_to_term("compressed");
+
+        assert_badarg!(
+            native(process, Term::NIL, options),
+            format!("options ({}) is not a proper list", options)
+        );
+    });
+}
+
+#[test]
+fn with_compressed_level_out_of_range_errors_badarg() {
+    with_process(|process| {
+        let options = process
+            .list_from_slice(&[process
+                .tuple_from_slice(&[
+                    Atom::str_to_term("compressed"),
+                    process.integer(10).unwrap(),
+                ])
+                .unwrap()])
+            .unwrap();
+
+        assert_badarg!(native(process, Term::NIL, options), "{compressed, 0..9}");
+    });
+}
+
+#[test]
+fn with_compressed_and_compressible_term_returns_compressed_ext() {
+    with_process(|process| {
+        let term = compressible_term(process);
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("compressed")])
+            .unwrap();
+
+        let binary = native(process, term, options).unwrap();
+        let bytes: Vec<u8> = binary.try_into().unwrap();
+
+        assert_eq!(&bytes[0..2], &[VERSION_NUMBER, COMPRESSED]);
+
+        let uncompressed_binary = term_to_binary_1::native(process, term).unwrap();
+        let uncompressed_bytes: Vec<u8> = uncompressed_binary.try_into().unwrap();
+
+        assert!(bytes.len() < uncompressed_bytes.len());
+        // uncompressed size excludes the version number
+        assert_eq!(
+            &bytes[2..6],
+            &((uncompressed_bytes.len() - 1) as u32).to_be_bytes()
+        );
+        assert_eq!(binary_to_term_1::native(process, binary), Ok(term));
+    });
+}
+
+#[test]
+fn with_compressed_level_zero_returns_uncompressed() {
+    with_process(|process| {
+        let term = compressible_term(process);
+        let options = process
+            .list_from_slice(&[process
+                .tuple_from_slice(&[Atom::str_to_term("compressed"), process.integer(0).unwrap()])
+                .unwrap()])
+            .unwrap();
+
+        assert_eq!(
+            native(process, term, options),
+            term_to_binary_1::native(process, term)
+        );
+    });
+}
+
+#[test]
+fn with_compressed_and_incompressible_term_returns_uncompressed() {
+    with_process(|process| {
+        let term = Atom::str_to_term("a");
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("compressed")])
+            .unwrap();
+
+        assert_eq!(
+            native(process, term, options),
+            term_to_binary_1::native(process, term)
+        );
+    });
+}
+
+const VERSION_NUMBER: u8 = 131;
+const COMPRESSED: u8 = 80;
+
+fn compressible_term(process: &Process) -> Term {
+    process
+        .list_from_slice(&[Atom::str_to_term("compressible"); 64])
+        .unwrap()
+}
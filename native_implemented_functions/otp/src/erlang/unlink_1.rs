@@ -14,6 +14,7 @@ use liblumen_alloc::erts::term::prelude::*;
 use native_implemented_function::native_implemented_function;
 
 use lumen_rt_core::registry::pid_to_process;
+use lumen_rt_full::{distribution, port};
 
 #[native_implemented_function(unlink/1)]
 fn native(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
@@ -32,8 +33,16 @@ fn native(process: &Process, pid_or_port: Term) -> exception::Result<Term> {
                 Ok(true.into())
             }
         }
-        TypedTerm::Port(_) => unimplemented!(),
-        TypedTerm::ExternalPid(_) => unimplemented!(),
+        TypedTerm::Port(port) => {
+            port::unlink(process, port);
+
+            Ok(true.into())
+        }
+        TypedTerm::ExternalPid(external_pid) => {
+            distribution::unlink(process, &external_pid);
+
+            Ok(true.into())
+        }
         TypedTerm::ExternalPort(_) => unimplemented!(),
         _ => Err(TypeError)
             .context(format!(
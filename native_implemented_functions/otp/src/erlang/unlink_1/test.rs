@@ -1,4 +1,5 @@
 mod with_local_pid;
+mod with_local_port;
 
 use proptest::strategy::{Just, Strategy};
 
@@ -0,0 +1,37 @@
+use super::*;
+
+use liblumen_alloc::erts::term::prelude::Encode;
+
+use lumen_rt_full::port::{self, Name};
+
+use crate::test;
+
+#[test]
+fn with_closed_port_returns_true() {
+    with_process(|process| {
+        let owner = test::process::child(process);
+        let port = port::open(&owner, cat(), Default::default()).unwrap();
+        assert!(port::close(port));
+
+        assert_eq!(native(process, port.encode().unwrap()), Ok(true.into()));
+    });
+}
+
+#[test]
+fn with_linked_port_unlinks_from_port() {
+    with_process(|process| {
+        let owner = test::process::child(process);
+        let port = port::open(&owner, cat(), Default::default()).unwrap();
+        assert!(port::link(process, port));
+
+        assert_eq!(native(process, port.encode().unwrap()), Ok(true.into()));
+
+        assert_eq!(port::info(port).unwrap().links, vec![owner.pid()]);
+
+        assert!(port::close(port));
+    });
+}
+
+fn cat() -> Name {
+    Name::Spawn("cat".to_string())
+}
@@ -0,0 +1,101 @@
+pub mod delete_1;
+pub mod delete_2;
+pub mod foldl_3;
+pub mod info_2;
+pub mod insert_2;
+pub mod lookup_2;
+pub mod match_2;
+mod match_spec;
+pub mod new_2;
+pub mod select_2;
+pub mod tab2list_1;
+pub mod update_counter_3;
+
+use std::convert::{Infallible, TryInto};
+use std::sync::Arc;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+use lumen_rt_core::context::term_is_not_type;
+use lumen_rt_core::ets::{self, Table};
+
+fn module() -> Atom {
+    Atom::try_from_str("ets").unwrap()
+}
+
+/// Copies all objects of `table` to `process` as a list, in table order for `ordered_set`.
+fn objects_to_list(process: &Process, table: &Table) -> InternalResult<Term> {
+    let object_vec = table.try_fold(Vec::with_capacity(table.len()), |mut acc, object| {
+        acc.push(object.clone_to_process(process));
+
+        Ok::<_, Infallible>(acc)
+    });
+
+    process
+        .list_from_slice(&object_vec.unwrap())
+        .map_err(From::from)
+}
+
+/// The table referred to by `tab` if `process` is allowed to read it.
+fn readable_table(process: &Process, tab: Term) -> InternalResult<Arc<Table>> {
+    let table = table(tab)?;
+
+    if table.is_readable_by(process.pid()) {
+        Ok(table)
+    } else {
+        Err(anyhow!("table ({}) is private to another process", tab).into())
+    }
+}
+
+/// The table referred to by `tab`, which is either the reference returned by `ets:new/2` or
+/// the name of a `named_table`.
+fn table(tab: Term) -> InternalResult<Arc<Table>> {
+    let option_table = match tab.decode().unwrap() {
+        TypedTerm::Atom(name) => ets::get_by_name(&name),
+        TypedTerm::Reference(reference) => ets::get_by_reference(reference.as_ref()),
+        _ => {
+            return Err(TypeError)
+                .with_context(|| term_is_not_type("table", tab, "an atom or reference"))
+                .map_err(From::from)
+        }
+    };
+
+    option_table.ok_or_else(|| anyhow!("table ({}) does not exist", tab).into())
+}
+
+/// Checks that `object` is a tuple large enough to have a key at `table`'s `keypos`.
+fn term_try_into_object(table: &Table, object: Term) -> InternalResult<Boxed<Tuple>> {
+    let tuple: Boxed<Tuple> = object.try_into().with_context(|| {
+        format!(
+            "object ({}) is not a tuple with at least {} element(s)",
+            object, table.keypos
+        )
+    })?;
+
+    if table.keypos <= tuple.len() {
+        Ok(tuple)
+    } else {
+        Err(anyhow!(
+            "object ({}) is not a tuple with at least {} element(s)",
+            object,
+            table.keypos
+        )
+        .into())
+    }
+}
+
+/// The table referred to by `tab` if `process` is allowed to write to it.
+fn writable_table(process: &Process, tab: Term) -> InternalResult<Arc<Table>> {
+    let table = table(tab)?;
+
+    if table.is_writable_by(process.pid()) {
+        Ok(table)
+    } else {
+        Err(anyhow!("table ({}) is not writable by another process", tab).into())
+    }
+}
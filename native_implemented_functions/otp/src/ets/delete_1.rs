@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::ets;
+
+use super::writable_table;
+
+#[native_implemented_function(delete/1)]
+pub fn native(process: &Process, tab: Term) -> exception::Result<Term> {
+    let table = writable_table(process, tab)?;
+    ets::delete(&table);
+
+    Ok(true.into())
+}
@@ -0,0 +1,36 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::delete_1::native;
+use crate::ets::{info_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn with_table_deletes_table() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+
+        assert_eq!(native(process, tab), Ok(true.into()));
+        assert_eq!(
+            info_2::native(process, tab, Atom::str_to_term("size")),
+            Ok(Atom::str_to_term("undefined"))
+        );
+        assert_badarg!(
+            native(process, tab),
+            format!("table ({}) does not exist", tab)
+        );
+    });
+}
+
+#[test]
+fn with_named_table_frees_name() {
+    with_process(|process| {
+        let name = registered_name();
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("named_table")])
+            .unwrap();
+
+        assert_eq!(new_2::native(process, name, options), Ok(name));
+        assert_eq!(native(process, name), Ok(true.into()));
+        assert_eq!(new_2::native(process, name, options), Ok(name));
+    });
+}
@@ -0,0 +1,18 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::writable_table;
+
+#[native_implemented_function(delete/2)]
+pub fn native(process: &Process, tab: Term, key: Term) -> exception::Result<Term> {
+    let table = writable_table(process, tab)?;
+    table.delete(key);
+
+    Ok(true.into())
+}
@@ -0,0 +1,49 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::delete_2::native;
+use crate::ets::{insert_2, lookup_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn with_key_deletes_all_objects_with_key() {
+    with_process(|process| {
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("bag")])
+            .unwrap();
+        let tab = new_2::native(process, registered_name(), options).unwrap();
+        let key = Atom::str_to_term("key");
+        let other_key = Atom::str_to_term("other_key");
+        let other_object = process.tuple_from_slice(&[other_key]).unwrap();
+        let objects = process
+            .list_from_slice(&[
+                process
+                    .tuple_from_slice(&[key, process.integer(1).unwrap()])
+                    .unwrap(),
+                process
+                    .tuple_from_slice(&[key, process.integer(2).unwrap()])
+                    .unwrap(),
+                other_object,
+            ])
+            .unwrap();
+
+        assert_eq!(insert_2::native(process, tab, objects), Ok(true.into()));
+        assert_eq!(native(process, tab, key), Ok(true.into()));
+        assert_eq!(lookup_2::native(process, tab, key), Ok(Term::NIL));
+        assert_eq!(
+            lookup_2::native(process, tab, other_key),
+            Ok(process.list_from_slice(&[other_object]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn without_key_returns_true() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+
+        assert_eq!(
+            native(process, tab, Atom::str_to_term("key")),
+            Ok(true.into())
+        );
+    });
+}
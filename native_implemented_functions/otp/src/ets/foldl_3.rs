@@ -0,0 +1,106 @@
+mod label_1;
+
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::{self, Alloc};
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::{code, Process};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{Arity, ModuleFunctionArity};
+
+use super::{objects_to_list, readable_table};
+
+const ARITY: Arity = 3;
+
+pub fn export() {
+    lumen_rt_full::code::export::insert(super::module(), function(), ARITY, code);
+}
+
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    function: Term,
+    acc0: Term,
+    tab: Term,
+) -> Result<(), Alloc> {
+    process.stack_push(tab)?;
+    process.stack_push(acc0)?;
+    process.stack_push(function)?;
+    process.place_frame(frame(), placement);
+
+    Ok(())
+}
+
+// Private
+
+/// ```elixir
+/// def foldl(function, acc0, tab) do
+///   objects = :ets.tab2list(tab)
+///   :lists.foldl(function, acc0, objects)
+/// end
+/// ```
+///
+/// The objects are copied before `function` is called for any of them, so `function` can
+/// modify the table.
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let function = arc_process.stack_peek(1).unwrap();
+    let acc0 = arc_process.stack_peek(2).unwrap();
+    let tab = arc_process.stack_peek(3).unwrap();
+
+    const STACK_USED: usize = 3;
+
+    match objects(arc_process, function, tab) {
+        Ok(objects) => {
+            arc_process.stack_popn(STACK_USED);
+            label_1::place_frame_with_arguments(
+                arc_process,
+                Placement::Replace,
+                function,
+                objects,
+            )?;
+            // `label_1` expects the accumulator as if it was returned from calling `function`
+            arc_process.stack_push(acc0)?;
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => code::result_from_exception(arc_process, STACK_USED, exception),
+    }
+}
+
+fn frame() -> Frame {
+    Frame::new(module_function_arity(), code)
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("foldl").unwrap()
+}
+
+fn module_function_arity() -> Arc<ModuleFunctionArity> {
+    Arc::new(ModuleFunctionArity {
+        module: super::module(),
+        function: function(),
+        arity: ARITY,
+    })
+}
+
+fn objects(process: &Process, function: Term, tab: Term) -> exception::Result<Term> {
+    let function_boxed_closure: Boxed<Closure> = function
+        .try_into()
+        .with_context(|| format!("function ({}) is not a function", function))?;
+
+    if function_boxed_closure.arity() != 2 {
+        return Err(anyhow!("function ({}) does not have an arity of 2", function).into());
+    }
+
+    let table = readable_table(process, tab)?;
+
+    objects_to_list(process, &table).map_err(From::from)
+}
@@ -0,0 +1,80 @@
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use liblumen_alloc::erts::exception::Alloc;
+use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
+use liblumen_alloc::erts::process::{code, Process};
+use liblumen_alloc::erts::term::prelude::*;
+
+/// ```elixir
+/// # label 1
+/// # pushed to stack: (function, objects)
+/// # returned from call: acc
+/// # full stack: (acc, function, objects)
+/// # returns: acc
+/// case objects do
+///   [object | objects] ->
+///     acc = function.(object, acc)
+///     # label 1
+///   [] ->
+///     acc
+/// end
+/// ```
+pub fn place_frame_with_arguments(
+    process: &Process,
+    placement: Placement,
+    function: Term,
+    objects: Term,
+) -> Result<(), Alloc> {
+    assert!(function.is_boxed_function());
+    assert!(objects.is_list());
+    process.stack_push(objects)?;
+    process.stack_push(function)?;
+    process.place_frame(frame(process), placement);
+
+    Ok(())
+}
+
+// Private
+
+fn code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.reduce();
+
+    let acc = arc_process.stack_peek(1).unwrap();
+    let function = arc_process.stack_peek(2).unwrap();
+    let objects = arc_process.stack_peek(3).unwrap();
+
+    match objects.decode().unwrap() {
+        TypedTerm::List(objects_cons) => {
+            arc_process.stack_popn(3);
+
+            place_frame_with_arguments(
+                arc_process,
+                Placement::Replace,
+                function,
+                objects_cons.tail,
+            )?;
+
+            let function_boxed_closure: Boxed<Closure> = function.try_into().unwrap();
+            function_boxed_closure.place_frame_with_arguments(
+                arc_process,
+                Placement::Push,
+                vec![objects_cons.head, acc],
+            )?;
+
+            Process::call_code(arc_process)
+        }
+        TypedTerm::Nil => {
+            arc_process.return_from_call(3, acc)?;
+
+            Process::call_code(arc_process)
+        }
+        _ => unreachable!("objects ({}) is not a list", objects),
+    }
+}
+
+fn frame(process: &Process) -> Frame {
+    let module_function_arity = process.current_module_function_arity().unwrap();
+
+    Frame::new(module_function_arity, code)
+}
@@ -0,0 +1,104 @@
+use std::mem;
+use std::sync::Arc;
+
+use liblumen_alloc::borrow::clone_to_process::CloneToProcess;
+use liblumen_alloc::erts::process::code::stack::frame::Placement;
+use liblumen_alloc::erts::process::code::Code;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use lumen_rt_full::future::Ready;
+
+use crate::ets::foldl_3::place_frame_with_arguments;
+use crate::ets::{insert_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn without_function_errors_badarg() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+        let function = Atom::str_to_term("function");
+
+        let Ready {
+            arc_process: child_arc_process,
+            result,
+            ..
+        } = run_until_ready(function, Term::NIL, tab);
+
+        assert_badarg!(result, format!("function ({}) is not a function", function));
+
+        mem::drop(child_arc_process);
+    });
+}
+
+#[test]
+fn with_function_folds_over_objects() {
+    with_process(|process| {
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("ordered_set")])
+            .unwrap();
+        let tab = new_2::native(process, registered_name(), options).unwrap();
+        let first = process
+            .tuple_from_slice(&[process.integer(1).unwrap()])
+            .unwrap();
+        let second = process
+            .tuple_from_slice(&[process.integer(2).unwrap()])
+            .unwrap();
+        let objects = process.list_from_slice(&[first, second]).unwrap();
+
+        assert_eq!(insert_2::native(process, tab, objects), Ok(true.into()));
+
+        // fn object, acc -> [object | acc] end
+        let code: Code = |arc_process: &Arc<Process>| {
+            let object = arc_process.stack_peek(1).unwrap();
+            let acc = arc_process.stack_peek(2).unwrap();
+            let return_term = arc_process.cons(object, acc)?;
+            arc_process.return_from_call(2, return_term)?;
+
+            Process::call_code(arc_process)
+        };
+        let function = process
+            .export_closure(
+                Atom::try_from_str("module").unwrap(),
+                Atom::try_from_str("function").unwrap(),
+                2,
+                Some(code),
+            )
+            .unwrap();
+
+        let Ready {
+            arc_process: child_arc_process,
+            result,
+            ..
+        } = run_until_ready(function, Term::NIL, tab);
+
+        assert_eq!(
+            result,
+            Ok(process.list_from_slice(&[second, first]).unwrap())
+        );
+
+        mem::drop(child_arc_process);
+    });
+}
+
+fn run_until_ready(function: Term, acc0: Term, tab: Term) -> Ready {
+    lumen_rt_full::future::run_until_ready(
+        Default::default(),
+        |child_process| {
+            let child_function = function.clone_to_process(child_process);
+            let child_acc0 = acc0.clone_to_process(child_process);
+            let child_tab = tab.clone_to_process(child_process);
+
+            place_frame_with_arguments(
+                child_process,
+                Placement::Push,
+                child_function,
+                child_acc0,
+                child_tab,
+            )
+            .map_err(|e| e.into())
+        },
+        5_000,
+    )
+    .unwrap()
+}
@@ -0,0 +1,52 @@
+#[cfg(test)]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::ets::Table;
+
+/// Unlike the other `ets` functions, returns `undefined` instead of raising `badarg` when `tab`
+/// does not exist.
+#[native_implemented_function(info/2)]
+pub fn native(process: &Process, tab: Term, item: Term) -> exception::Result<Term> {
+    let item_atom = term_try_into_atom!(item)?;
+
+    match super::table(tab) {
+        Ok(table) => item_value(process, &table, tab, item_atom),
+        Err(_) if tab.is_atom() || tab.is_local_reference() => Ok(atom!("undefined")),
+        Err(error) => Err(error.into()),
+    }
+}
+
+// Private
+
+fn item_value(process: &Process, table: &Table, tab: Term, item: Atom) -> exception::Result<Term> {
+    let value = match item.name() {
+        "id" => tab,
+        "keypos" => process.integer(table.keypos)?,
+        "memory" => process.integer(table.memory())?,
+        "name" => table.name.encode()?,
+        "named_table" => table.named.into(),
+        "owner" => table.owner.encode()?,
+        "protection" => Atom::from(table.access).encode()?,
+        "size" => process.integer(table.len())?,
+        "type" => Atom::from(table.r#type).encode()?,
+        name => {
+            return Err(TryAtomFromTermError(name))
+                .context(
+                    "supported items are id, keypos, memory, name, named_table, owner, \
+                     protection, size, and type",
+                )
+                .map_err(From::from)
+        }
+    };
+
+    Ok(value)
+}
@@ -0,0 +1,59 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::info_2::native;
+use crate::ets::{insert_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn without_table_returns_undefined() {
+    with_process(|process| {
+        assert_eq!(
+            native(process, registered_name(), Atom::str_to_term("size")),
+            Ok(Atom::str_to_term("undefined"))
+        );
+    });
+}
+
+#[test]
+fn with_unsupported_item_errors_badarg() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+
+        assert_badarg!(
+            native(process, tab, Atom::str_to_term("unsupported")),
+            "supported items are id, keypos, memory, name, named_table, owner"
+        );
+    });
+}
+
+#[test]
+fn with_table_returns_options_and_size() {
+    with_process(|process| {
+        let name = registered_name();
+        let options = process
+            .list_from_slice(&[
+                Atom::str_to_term("bag"),
+                Atom::str_to_term("public"),
+                process
+                    .tuple_from_slice(&[Atom::str_to_term("keypos"), process.integer(2).unwrap()])
+                    .unwrap(),
+            ])
+            .unwrap();
+        let tab = new_2::native(process, name, options).unwrap();
+        let object = process
+            .tuple_from_slice(&[Atom::str_to_term("value"), Atom::str_to_term("key")])
+            .unwrap();
+
+        assert_eq!(insert_2::native(process, tab, object), Ok(true.into()));
+
+        let info = |item| native(process, tab, Atom::str_to_term(item));
+
+        assert_eq!(info("name"), Ok(name));
+        assert_eq!(info("named_table"), Ok(false.into()));
+        assert_eq!(info("type"), Ok(Atom::str_to_term("bag")));
+        assert_eq!(info("protection"), Ok(Atom::str_to_term("public")));
+        assert_eq!(info("keypos"), Ok(process.integer(2).unwrap()));
+        assert_eq!(info("owner"), Ok(process.pid_term()));
+        assert_eq!(info("size"), Ok(process.integer(1).unwrap()));
+    });
+}
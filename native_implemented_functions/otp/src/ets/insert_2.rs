@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{term_try_into_object, writable_table};
+
+#[native_implemented_function(insert/2)]
+pub fn native(process: &Process, tab: Term, object_or_objects: Term) -> exception::Result<Term> {
+    let table = writable_table(process, tab)?;
+
+    let object_vec = match object_or_objects.decode().unwrap() {
+        TypedTerm::Nil => Vec::new(),
+        TypedTerm::List(cons) => {
+            let mut object_vec = Vec::new();
+
+            for result in cons.into_iter() {
+                match result {
+                    Ok(object) => object_vec.push(term_try_into_object(&table, object)?),
+                    Err(_) => {
+                        return Err(ImproperListError)
+                            .context(format!(
+                                "objects ({}) is not a proper list",
+                                object_or_objects
+                            ))
+                            .map_err(From::from)
+                    }
+                }
+            }
+
+            object_vec
+        }
+        _ => vec![term_try_into_object(&table, object_or_objects)?],
+    };
+
+    table.insert(&object_vec)?;
+
+    Ok(true.into())
+}
@@ -0,0 +1,103 @@
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::insert_2::native;
+use crate::ets::{lookup_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn without_tuple_errors_badarg() {
+    with_process(|process| {
+        let tab = table(process, "set");
+        let object = process.integer(1).unwrap();
+
+        assert_badarg!(
+            native(process, tab, object),
+            format!(
+                "object ({}) is not a tuple with at least 1 element(s)",
+                object
+            )
+        );
+    });
+}
+
+#[test]
+fn with_set_replaces_object_with_same_key() {
+    with_process(|process| {
+        let tab = table(process, "set");
+        let key = Atom::str_to_term("key");
+        let first = process
+            .tuple_from_slice(&[key, process.integer(1).unwrap()])
+            .unwrap();
+        let second = process
+            .tuple_from_slice(&[key, process.integer(2).unwrap()])
+            .unwrap();
+
+        assert_eq!(native(process, tab, first), Ok(true.into()));
+        assert_eq!(native(process, tab, second), Ok(true.into()));
+        assert_eq!(
+            lookup_2::native(process, tab, key),
+            Ok(process.list_from_slice(&[second]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_bag_keeps_distinct_objects_with_same_key() {
+    with_process(|process| {
+        let tab = table(process, "bag");
+        let key = Atom::str_to_term("key");
+        let first = process
+            .tuple_from_slice(&[key, process.integer(1).unwrap()])
+            .unwrap();
+        let second = process
+            .tuple_from_slice(&[key, process.integer(2).unwrap()])
+            .unwrap();
+        let objects = process.list_from_slice(&[first, second, first]).unwrap();
+
+        assert_eq!(native(process, tab, objects), Ok(true.into()));
+        assert_eq!(
+            lookup_2::native(process, tab, key),
+            Ok(process.list_from_slice(&[first, second]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_duplicate_bag_keeps_identical_objects() {
+    with_process(|process| {
+        let tab = table(process, "duplicate_bag");
+        let key = Atom::str_to_term("key");
+        let object = process.tuple_from_slice(&[key]).unwrap();
+        let objects = process.list_from_slice(&[object, object]).unwrap();
+
+        assert_eq!(native(process, tab, objects), Ok(true.into()));
+        assert_eq!(lookup_2::native(process, tab, key), Ok(objects));
+    });
+}
+
+#[test]
+fn with_protected_table_from_another_process_errors_badarg() {
+    with_process(|owner_process| {
+        let tab = table(owner_process, "protected");
+
+        with_process(|process| {
+            let object = process
+                .tuple_from_slice(&[Atom::str_to_term("key")])
+                .unwrap();
+
+            assert_badarg!(
+                native(process, tab, object),
+                format!("table ({}) is not writable by another process", tab)
+            );
+        });
+    });
+}
+
+fn table(process: &Process, option: &str) -> Term {
+    let options = process
+        .list_from_slice(&[Atom::str_to_term(option)])
+        .unwrap();
+
+    new_2::native(process, registered_name(), options).unwrap()
+}
@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod test;
+
+use std::convert::Infallible;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+use native_implemented_function::native_implemented_function;
+
+use super::readable_table;
+
+#[native_implemented_function(lookup/2)]
+pub fn native(process: &Process, tab: Term, key: Term) -> exception::Result<Term> {
+    let table = readable_table(process, tab)?;
+    let object_vec = table
+        .try_fold_key(key, Vec::new(), |mut acc, object| {
+            acc.push(object.clone_to_process(process));
+
+            Ok::<_, Infallible>(acc)
+        })
+        .unwrap();
+
+    process.list_from_slice(&object_vec).map_err(From::from)
+}
@@ -0,0 +1,62 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::lookup_2::native;
+use crate::ets::{insert_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn without_table_errors_badarg() {
+    with_process(|process| {
+        let tab = registered_name();
+
+        assert_badarg!(
+            native(process, tab, Atom::str_to_term("key")),
+            format!("table ({}) does not exist", tab)
+        );
+    });
+}
+
+#[test]
+fn without_key_returns_empty_list() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+
+        assert_eq!(
+            native(process, tab, Atom::str_to_term("key")),
+            Ok(Term::NIL)
+        );
+    });
+}
+
+#[test]
+fn with_key_returns_object_copied_to_process() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+        let key = Atom::str_to_term("key");
+        let object = process
+            .tuple_from_slice(&[key, process.binary_from_str("value").unwrap()])
+            .unwrap();
+
+        assert_eq!(insert_2::native(process, tab, object), Ok(true.into()));
+        assert_eq!(
+            native(process, tab, key),
+            Ok(process.list_from_slice(&[object]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_set_compares_keys_exactly() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+        let object = process
+            .tuple_from_slice(&[process.integer(1).unwrap()])
+            .unwrap();
+
+        assert_eq!(insert_2::native(process, tab, object), Ok(true.into()));
+        assert_eq!(
+            native(process, tab, process.float(1.0).unwrap()),
+            Ok(Term::NIL)
+        );
+    });
+}
@@ -0,0 +1,27 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception::{self, Alloc};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::match_spec::{bindings_to_list, match_pattern, Bindings};
+use super::readable_table;
+
+#[native_implemented_function(match/2)]
+pub fn native(process: &Process, tab: Term, pattern: Term) -> exception::Result<Term> {
+    let table = readable_table(process, tab)?;
+    let match_vec = table.try_fold(Vec::new(), |mut acc, object| -> Result<_, Alloc> {
+        let mut bindings = Bindings::new();
+
+        if match_pattern(pattern, object, &mut bindings) {
+            acc.push(bindings_to_list(process, &bindings)?);
+        }
+
+        Ok(acc)
+    })?;
+
+    process.list_from_slice(&match_vec).map_err(From::from)
+}
@@ -0,0 +1,74 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::match_2::native;
+use crate::ets::{insert_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn with_variables_returns_bindings_in_variable_order() {
+    with_process(|process| {
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("ordered_set")])
+            .unwrap();
+        let tab = new_2::native(process, registered_name(), options).unwrap();
+        let objects = process
+            .list_from_slice(&[
+                process
+                    .tuple_from_slice(&[
+                        process.integer(1).unwrap(),
+                        Atom::str_to_term("one"),
+                        Atom::str_to_term("odd"),
+                    ])
+                    .unwrap(),
+                process
+                    .tuple_from_slice(&[
+                        process.integer(2).unwrap(),
+                        Atom::str_to_term("two"),
+                        Atom::str_to_term("even"),
+                    ])
+                    .unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(insert_2::native(process, tab, objects), Ok(true.into()));
+
+        let pattern = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("$2"),
+                Atom::str_to_term("$1"),
+                Atom::str_to_term("_"),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            native(process, tab, pattern),
+            Ok(process
+                .list_from_slice(&[
+                    process
+                        .list_from_slice(&[Atom::str_to_term("one"), process.integer(1).unwrap()])
+                        .unwrap(),
+                    process
+                        .list_from_slice(&[Atom::str_to_term("two"), process.integer(2).unwrap()])
+                        .unwrap(),
+                ])
+                .unwrap())
+        );
+
+        let even_pattern = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("_"),
+                Atom::str_to_term("$1"),
+                Atom::str_to_term("even"),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            native(process, tab, even_pattern),
+            Ok(process
+                .list_from_slice(&[process
+                    .list_from_slice(&[Atom::str_to_term("two")])
+                    .unwrap()])
+                .unwrap())
+        );
+    });
+}
@@ -0,0 +1,452 @@
+//! Match patterns, as used by `ets:match/2`, and match specifications, as used by
+//! `ets:select/2`.
+//!
+//! Guards and bodies support the type tests, boolean operators, term comparisons, integer
+//! arithmetic, and the `element/2`, `hd/1`, `tl/1`, `length/1`, `size/1`, `tuple_size/1`,
+//! `abs/1` and `self/0` functions.
+
+use std::collections::BTreeMap;
+use std::convert::{TryFrom, TryInto};
+
+use anyhow::*;
+use num_bigint::BigInt;
+use num_traits::{Signed, Zero};
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::{Alloc, AllocResult};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+/// The values bound to `'$N'` variables, ordered by `N`.
+pub type Bindings = BTreeMap<usize, Term>;
+
+/// Matches `term` against `pattern`, binding `'$N'` variables in `bindings`.
+///
+/// `'_'` matches anything and a variable that is already bound only matches a term that is
+/// exactly equal (`=:=`) to its value.
+pub fn match_pattern(pattern: Term, term: Term, bindings: &mut Bindings) -> bool {
+    match pattern.decode().unwrap() {
+        TypedTerm::Atom(atom) => {
+            if atom.name() == "_" {
+                true
+            } else if let Some(number) = variable_number(atom) {
+                match bindings.get(&number) {
+                    Some(bound) => exact_eq(*bound, term),
+                    None => {
+                        bindings.insert(number, term);
+
+                        true
+                    }
+                }
+            } else {
+                exact_eq(pattern, term)
+            }
+        }
+        TypedTerm::Tuple(pattern_tuple) => match term.decode().unwrap() {
+            TypedTerm::Tuple(tuple) => {
+                pattern_tuple.len() == tuple.len()
+                    && pattern_tuple
+                        .iter()
+                        .zip(tuple.iter())
+                        .all(|(pattern_element, element)| {
+                            match_pattern(*pattern_element, *element, bindings)
+                        })
+            }
+            _ => false,
+        },
+        TypedTerm::List(pattern_cons) => match term.decode().unwrap() {
+            TypedTerm::List(cons) => {
+                match_pattern(pattern_cons.head, cons.head, bindings)
+                    && match_pattern(pattern_cons.tail, cons.tail, bindings)
+            }
+            _ => false,
+        },
+        _ => exact_eq(pattern, term),
+    }
+}
+
+/// A match specification: a list of `{Head, Guards, Body}` clauses.
+pub struct MatchSpec {
+    clauses: Vec<Clause>,
+}
+
+impl MatchSpec {
+    /// The result of the first clause whose head matches `object` and whose guards are all
+    /// `true`, copied to `process`.
+    ///
+    /// If evaluating the body fails, the result is `'EXIT'`, like BEAM.
+    pub fn run(&self, process: &Process, object: Term) -> AllocResult<Option<Term>> {
+        for clause in &self.clauses {
+            let mut bindings = Bindings::new();
+
+            if !match_pattern(clause.head, object, &mut bindings) {
+                continue;
+            }
+
+            let evaluator = Evaluator {
+                process,
+                object,
+                bindings: &bindings,
+            };
+
+            if !evaluator.guards_pass(&clause.guards)? {
+                continue;
+            }
+
+            let mut result = Ok(Term::NIL);
+
+            for expression in &clause.body {
+                result = evaluator.evaluate(*expression);
+
+                if result.is_err() {
+                    break;
+                }
+            }
+
+            return match result {
+                Ok(term) => Ok(Some(term.clone_to_process(process))),
+                Err(EvaluationError::Alloc(alloc)) => Err(alloc),
+                Err(EvaluationError::Failed) => Ok(Some(atom!("EXIT"))),
+            };
+        }
+
+        Ok(None)
+    }
+}
+
+impl TryFrom<Term> for MatchSpec {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        let clause_terms = term_try_into_vec(term)
+            .with_context(|| format!("match specification ({}) is not a proper list", term))?;
+        let mut clause_vec = Vec::with_capacity(clause_terms.len());
+
+        for clause_term in clause_terms {
+            let clause = clause_term.try_into().with_context(|| {
+                format!(
+                    "match specification clause ({}) is not {{Head, Guards, Body}}",
+                    clause_term
+                )
+            })?;
+            clause_vec.push(clause);
+        }
+
+        Ok(Self {
+            clauses: clause_vec,
+        })
+    }
+}
+
+/// The value of each bound variable in variable order, as returned by `ets:match/2`.
+pub fn bindings_to_list(process: &Process, bindings: &Bindings) -> AllocResult<Term> {
+    let value_vec: Vec<Term> = bindings
+        .values()
+        .map(|value| value.clone_to_process(process))
+        .collect();
+
+    process.list_from_slice(&value_vec)
+}
+
+// Private
+
+struct Clause {
+    head: Term,
+    guards: Vec<Term>,
+    body: Vec<Term>,
+}
+
+impl TryFrom<Term> for Clause {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        let tuple: Boxed<Tuple> = term.try_into()?;
+
+        if tuple.len() != 3 {
+            return Err(anyhow!("clause ({}) does not have 3 elements", term));
+        }
+
+        let guards = term_try_into_vec(tuple[1])
+            .with_context(|| format!("guards ({}) is not a proper list", tuple[1]))?;
+        let body = term_try_into_vec(tuple[2])
+            .with_context(|| format!("body ({}) is not a proper list", tuple[2]))?;
+
+        if body.is_empty() {
+            return Err(anyhow!("body is empty"));
+        }
+
+        Ok(Self {
+            head: tuple[0],
+            guards,
+            body,
+        })
+    }
+}
+
+enum EvaluationError {
+    Alloc(Alloc),
+    /// The guard or body raised an exception, such as `badarg`.
+    Failed,
+}
+
+impl From<Alloc> for EvaluationError {
+    fn from(alloc: Alloc) -> Self {
+        Self::Alloc(alloc)
+    }
+}
+
+type EvaluationResult = Result<Term, EvaluationError>;
+
+struct Evaluator<'a> {
+    process: &'a Process,
+    object: Term,
+    bindings: &'a Bindings,
+}
+
+impl<'a> Evaluator<'a> {
+    fn guards_pass(&self, guards: &[Term]) -> AllocResult<bool> {
+        for guard in guards {
+            match self.evaluate(*guard) {
+                Ok(term) if term == Term::from(true) => continue,
+                Ok(_) | Err(EvaluationError::Failed) => return Ok(false),
+                Err(EvaluationError::Alloc(alloc)) => return Err(alloc),
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Evaluates `expression`.
+    ///
+    /// The result may refer to the object being matched, so it needs to be copied before the
+    /// table is unlocked.
+    fn evaluate(&self, expression: Term) -> EvaluationResult {
+        match expression.decode().unwrap() {
+            TypedTerm::Atom(atom) => match atom.name() {
+                "$_" => Ok(self.object),
+                "$$" => {
+                    let value_vec: Vec<Term> = self.bindings.values().copied().collect();
+
+                    self.process.list_from_slice(&value_vec).map_err(From::from)
+                }
+                _ => match variable_number(atom) {
+                    Some(number) => self
+                        .bindings
+                        .get(&number)
+                        .copied()
+                        .ok_or(EvaluationError::Failed),
+                    None => Ok(expression),
+                },
+            },
+            TypedTerm::Tuple(tuple) => self.evaluate_tuple(expression, &tuple),
+            TypedTerm::Nil => Ok(expression),
+            TypedTerm::List(_) => {
+                let element_vec = term_try_into_vec(expression)
+                    .map_err(|_| EvaluationError::Failed)?
+                    .into_iter()
+                    .map(|element| self.evaluate(element))
+                    .collect::<Result<Vec<Term>, _>>()?;
+
+                self.process
+                    .list_from_slice(&element_vec)
+                    .map_err(From::from)
+            }
+            _ => Ok(expression),
+        }
+    }
+
+    fn evaluate_tuple(&self, expression: Term, tuple: &Tuple) -> EvaluationResult {
+        if tuple.len() == 0 {
+            return Ok(expression);
+        }
+
+        // `{{Element, ...}}` constructs a tuple
+        if tuple.len() == 1 {
+            if let Ok(inner_tuple) = Boxed::<Tuple>::try_from(tuple[0].decode().unwrap()) {
+                let element_vec = inner_tuple
+                    .iter()
+                    .map(|element| self.evaluate(*element))
+                    .collect::<Result<Vec<Term>, _>>()?;
+
+                return self
+                    .process
+                    .tuple_from_slice(&element_vec)
+                    .map_err(From::from);
+            }
+        }
+
+        let function: Atom = tuple[0].try_into().map_err(|_| EvaluationError::Failed)?;
+        let arguments = &tuple[1..];
+
+        match (function.name(), arguments.len()) {
+            ("const", 1) => Ok(arguments[0]),
+            ("andalso", 2) => {
+                if self.evaluate_bool(arguments[0])? {
+                    self.evaluate_bool(arguments[1]).map(Term::from)
+                } else {
+                    Ok(false.into())
+                }
+            }
+            ("orelse", 2) => {
+                if self.evaluate_bool(arguments[0])? {
+                    Ok(true.into())
+                } else {
+                    self.evaluate_bool(arguments[1]).map(Term::from)
+                }
+            }
+            ("self", 0) => Ok(self.process.pid_term()),
+            (name, 1) => {
+                let argument = self.evaluate(arguments[0])?;
+
+                self.call_1(name, argument)
+            }
+            (name, 2) => {
+                let left = self.evaluate(arguments[0])?;
+                let right = self.evaluate(arguments[1])?;
+
+                self.call_2(name, left, right)
+            }
+            _ => Err(EvaluationError::Failed),
+        }
+    }
+
+    fn evaluate_bool(&self, expression: Term) -> Result<bool, EvaluationError> {
+        term_try_into_bool(self.evaluate(expression)?)
+    }
+
+    fn call_1(&self, name: &str, argument: Term) -> EvaluationResult {
+        let decoded = argument.decode().unwrap();
+
+        let term = match name {
+            "is_atom" => argument.is_atom().into(),
+            "is_binary" => argument.is_binary().into(),
+            "is_boolean" => argument.is_boolean().into(),
+            "is_float" => argument.is_float().into(),
+            "is_function" => argument.is_function().into(),
+            "is_integer" => argument.is_integer().into(),
+            "is_list" => argument.is_list().into(),
+            "is_map" => argument.is_map().into(),
+            "is_number" => argument.is_number().into(),
+            "is_pid" => argument.is_pid().into(),
+            "is_reference" => argument.is_reference().into(),
+            "is_tuple" => argument.is_tuple().into(),
+            "not" => (!term_try_into_bool(argument)?).into(),
+            "abs" => {
+                let integer = term_try_into_big_int(argument)?;
+
+                self.process.integer(integer.abs())?
+            }
+            "hd" => match decoded {
+                TypedTerm::List(cons) => cons.head,
+                _ => return Err(EvaluationError::Failed),
+            },
+            "tl" => match decoded {
+                TypedTerm::List(cons) => cons.tail,
+                _ => return Err(EvaluationError::Failed),
+            },
+            "length" => {
+                let len = term_try_into_vec(argument)
+                    .map_err(|_| EvaluationError::Failed)?
+                    .len();
+
+                self.process.integer(len)?
+            }
+            "size" | "tuple_size" => match decoded {
+                TypedTerm::Tuple(tuple) => self.process.integer(tuple.len())?,
+                _ => return Err(EvaluationError::Failed),
+            },
+            "-" => {
+                let integer = term_try_into_big_int(argument)?;
+
+                self.process.integer(-integer)?
+            }
+            _ => return Err(EvaluationError::Failed),
+        };
+
+        Ok(term)
+    }
+
+    fn call_2(&self, name: &str, left: Term, right: Term) -> EvaluationResult {
+        let term = match name {
+            "and" => (term_try_into_bool(left)? && term_try_into_bool(right)?).into(),
+            "or" => (term_try_into_bool(left)? || term_try_into_bool(right)?).into(),
+            "xor" => (term_try_into_bool(left)? ^ term_try_into_bool(right)?).into(),
+            "==" => (left == right).into(),
+            "/=" => (left != right).into(),
+            "=:=" => exact_eq(left, right).into(),
+            "=/=" => (!exact_eq(left, right)).into(),
+            "<" => (left < right).into(),
+            "=<" => (left <= right).into(),
+            ">" => (left > right).into(),
+            ">=" => (left >= right).into(),
+            "element" => {
+                let index: usize = left.try_into().map_err(|_| EvaluationError::Failed)?;
+                let tuple: Boxed<Tuple> = right.try_into().map_err(|_| EvaluationError::Failed)?;
+
+                if 1 <= index && index <= tuple.len() {
+                    tuple[index - 1]
+                } else {
+                    return Err(EvaluationError::Failed);
+                }
+            }
+            "+" | "-" | "*" | "div" | "rem" => {
+                let left_integer = term_try_into_big_int(left)?;
+                let right_integer = term_try_into_big_int(right)?;
+
+                let integer = match name {
+                    "+" => left_integer + right_integer,
+                    "-" => left_integer - right_integer,
+                    "*" => left_integer * right_integer,
+                    _ if right_integer.is_zero() => return Err(EvaluationError::Failed),
+                    "div" => left_integer / right_integer,
+                    _ => left_integer % right_integer,
+                };
+
+                self.process.integer(integer)?
+            }
+            _ => return Err(EvaluationError::Failed),
+        };
+
+        Ok(term)
+    }
+}
+
+fn exact_eq(left: Term, right: Term) -> bool {
+    left.decode().unwrap().exact_eq(&right.decode().unwrap())
+}
+
+fn term_try_into_big_int(term: Term) -> Result<BigInt, EvaluationError> {
+    term.try_into().map_err(|_| EvaluationError::Failed)
+}
+
+fn term_try_into_bool(term: Term) -> Result<bool, EvaluationError> {
+    term.try_into().map_err(|_| EvaluationError::Failed)
+}
+
+fn term_try_into_vec(term: Term) -> anyhow::Result<Vec<Term>> {
+    match term.decode().unwrap() {
+        TypedTerm::Nil => Ok(Vec::new()),
+        TypedTerm::List(cons) => {
+            let mut element_vec = Vec::new();
+
+            for result in cons.into_iter() {
+                element_vec.push(result.map_err(|_| ImproperListError)?);
+            }
+
+            Ok(element_vec)
+        }
+        _ => Err(TypeError.into()),
+    }
+}
+
+/// `N` for the `'$N'` variables, which are all `$` followed by an integer.
+fn variable_number(atom: Atom) -> Option<usize> {
+    let name = atom.name();
+
+    if name.starts_with('$') {
+        name[1..].parse().ok()
+    } else {
+        None
+    }
+}
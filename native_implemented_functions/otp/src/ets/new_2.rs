@@ -0,0 +1,37 @@
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::ets::{self, Options};
+
+use lumen_rt_full::process::SchedulerDependentAlloc;
+
+#[native_implemented_function(new/2)]
+pub fn native(process: &Process, name: Term, options: Term) -> exception::Result<Term> {
+    let name_atom = term_try_into_atom!(name)?;
+    let options_options: Options = options.try_into()?;
+    let reference = process.next_reference()?;
+    let reference_reference: Boxed<Reference> = reference.try_into().unwrap();
+
+    match ets::new(
+        process.pid(),
+        *reference_reference,
+        name_atom,
+        &options_options,
+    ) {
+        // Like BEAM, named tables are referred to by their name and unnamed tables by their
+        // reference
+        Some(_) if options_options.named => Ok(name),
+        Some(_) => Ok(reference),
+        None => Err(anyhow!("table named ({}) already exists", name).into()),
+    }
+}
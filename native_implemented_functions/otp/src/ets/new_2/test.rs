@@ -0,0 +1,74 @@
+use proptest::strategy::Just;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::new_2::native;
+use crate::test::{registered_name, strategy, with_process};
+
+#[test]
+fn without_atom_name_errors_badarg() {
+    run!(
+        |arc_process| {
+            (
+                Just(arc_process.clone()),
+                strategy::term::is_not_atom(arc_process.clone()),
+            )
+        },
+        |(arc_process, name)| {
+            prop_assert_is_not_atom!(native(&arc_process, name, Term::NIL), name);
+
+            Ok(())
+        },
+    );
+}
+
+#[test]
+fn without_named_table_returns_reference() {
+    with_process(|process| {
+        let tab = native(process, registered_name(), Term::NIL).unwrap();
+
+        assert!(tab.is_local_reference());
+    });
+}
+
+#[test]
+fn with_named_table_returns_name() {
+    with_process(|process| {
+        let name = registered_name();
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("named_table")])
+            .unwrap();
+
+        assert_eq!(native(process, name, options), Ok(name));
+    });
+}
+
+#[test]
+fn with_named_table_with_name_in_use_errors_badarg() {
+    with_process(|process| {
+        let name = registered_name();
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("named_table")])
+            .unwrap();
+
+        assert_eq!(native(process, name, options), Ok(name));
+        assert_badarg!(
+            native(process, name, options),
+            format!("table named ({}) already exists", name)
+        );
+    });
+}
+
+#[test]
+fn with_unsupported_option_errors_badarg() {
+    with_process(|process| {
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("unsupported")])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, registered_name(), options),
+            "supported options are set, ordered_set, bag, duplicate_bag"
+        );
+    });
+}
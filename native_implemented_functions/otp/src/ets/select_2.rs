@@ -0,0 +1,28 @@
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::exception::{self, Alloc};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::match_spec::MatchSpec;
+use super::readable_table;
+
+#[native_implemented_function(select/2)]
+pub fn native(process: &Process, tab: Term, match_spec: Term) -> exception::Result<Term> {
+    let table = readable_table(process, tab)?;
+    let match_spec_match_spec: MatchSpec = match_spec.try_into()?;
+    let result_vec = table.try_fold(Vec::new(), |mut acc, object| -> Result<_, Alloc> {
+        if let Some(result) = match_spec_match_spec.run(process, object)? {
+            acc.push(result);
+        }
+
+        Ok(acc)
+    })?;
+
+    process.list_from_slice(&result_vec).map_err(From::from)
+}
@@ -0,0 +1,115 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::select_2::native;
+use crate::ets::{insert_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn without_match_spec_list_errors_badarg() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+        let match_spec = Atom::str_to_term("match_spec");
+
+        assert_badarg!(
+            native(process, tab, match_spec),
+            format!("match specification ({}) is not a proper list", match_spec)
+        );
+    });
+}
+
+#[test]
+fn with_guard_returns_body_for_matching_objects() {
+    with_process(|process| {
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("ordered_set")])
+            .unwrap();
+        let tab = new_2::native(process, registered_name(), options).unwrap();
+        let objects: Vec<Term> = (1..=4)
+            .map(|i| {
+                process
+                    .tuple_from_slice(&[
+                        process.integer(i).unwrap(),
+                        process.integer(i * 10).unwrap(),
+                    ])
+                    .unwrap()
+            })
+            .collect();
+        let objects_list = process.list_from_slice(&objects).unwrap();
+
+        assert_eq!(
+            insert_2::native(process, tab, objects_list),
+            Ok(true.into())
+        );
+
+        // [{{'$1', '$2'}, [{'>', '$1', 2}], [{{'$2', '$1'}}]}]
+        let head = process
+            .tuple_from_slice(&[Atom::str_to_term("$1"), Atom::str_to_term("$2")])
+            .unwrap();
+        let guard = process
+            .tuple_from_slice(&[
+                Atom::str_to_term(">"),
+                Atom::str_to_term("$1"),
+                process.integer(2).unwrap(),
+            ])
+            .unwrap();
+        let body = process
+            .tuple_from_slice(&[process
+                .tuple_from_slice(&[Atom::str_to_term("$2"), Atom::str_to_term("$1")])
+                .unwrap()])
+            .unwrap();
+        let clause = process
+            .tuple_from_slice(&[
+                head,
+                process.list_from_slice(&[guard]).unwrap(),
+                process.list_from_slice(&[body]).unwrap(),
+            ])
+            .unwrap();
+        let match_spec = process.list_from_slice(&[clause]).unwrap();
+
+        assert_eq!(
+            native(process, tab, match_spec),
+            Ok(process
+                .list_from_slice(&[
+                    process
+                        .tuple_from_slice(&[
+                            process.integer(30).unwrap(),
+                            process.integer(3).unwrap()
+                        ])
+                        .unwrap(),
+                    process
+                        .tuple_from_slice(&[
+                            process.integer(40).unwrap(),
+                            process.integer(4).unwrap()
+                        ])
+                        .unwrap(),
+                ])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_object_body_returns_objects() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+        let object = process
+            .tuple_from_slice(&[Atom::str_to_term("key")])
+            .unwrap();
+
+        assert_eq!(insert_2::native(process, tab, object), Ok(true.into()));
+
+        let clause = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("_"),
+                Term::NIL,
+                process.list_from_slice(&[Atom::str_to_term("$_")]).unwrap(),
+            ])
+            .unwrap();
+        let match_spec = process.list_from_slice(&[clause]).unwrap();
+
+        assert_eq!(
+            native(process, tab, match_spec),
+            Ok(process.list_from_slice(&[object]).unwrap())
+        );
+    });
+}
@@ -0,0 +1,17 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{objects_to_list, readable_table};
+
+#[native_implemented_function(tab2list/1)]
+pub fn native(process: &Process, tab: Term) -> exception::Result<Term> {
+    let table = readable_table(process, tab)?;
+
+    objects_to_list(process, &table).map_err(From::from)
+}
@@ -0,0 +1,48 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::tab2list_1::native;
+use crate::ets::{insert_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn with_ordered_set_returns_objects_in_term_order() {
+    with_process(|process| {
+        let options = process
+            .list_from_slice(&[Atom::str_to_term("ordered_set")])
+            .unwrap();
+        let tab = new_2::native(process, registered_name(), options).unwrap();
+        let first = process
+            .tuple_from_slice(&[process.integer(1).unwrap()])
+            .unwrap();
+        let second = process
+            .tuple_from_slice(&[Atom::str_to_term("key")])
+            .unwrap();
+        let third = process
+            .tuple_from_slice(&[process.tuple_from_slice(&[]).unwrap()])
+            .unwrap();
+        let objects = process.list_from_slice(&[third, first, second]).unwrap();
+
+        assert_eq!(insert_2::native(process, tab, objects), Ok(true.into()));
+        assert_eq!(
+            native(process, tab),
+            Ok(process.list_from_slice(&[first, second, third]).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_private_table_from_another_process_errors_badarg() {
+    with_process(|owner_process| {
+        let options = owner_process
+            .list_from_slice(&[Atom::str_to_term("private")])
+            .unwrap();
+        let tab = new_2::native(owner_process, registered_name(), options).unwrap();
+
+        with_process(|process| {
+            assert_badarg!(
+                native(process, tab),
+                format!("table ({}) is private to another process", tab)
+            );
+        });
+    });
+}
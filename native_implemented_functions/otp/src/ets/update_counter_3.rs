@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+use num_bigint::BigInt;
+
+use liblumen_alloc::erts::exception::{self, InternalResult};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::ets::Type;
+
+use super::writable_table;
+
+#[native_implemented_function(update_counter/3)]
+pub fn native(process: &Process, tab: Term, key: Term, update_op: Term) -> exception::Result<Term> {
+    let table = writable_table(process, tab)?;
+
+    match table.r#type {
+        Type::Set | Type::OrderedSet => (),
+        Type::Bag | Type::DuplicateBag => {
+            return Err(anyhow!("table ({}) is not a set or ordered_set", tab).into())
+        }
+    }
+
+    let (update_ops, is_list) = term_try_into_update_ops(table.keypos, update_op)?;
+    let mut result_vec = Vec::with_capacity(update_ops.len());
+
+    let updated = table.update(key, |old_tuple| -> InternalResult<Boxed<Tuple>> {
+        let mut element_vec: Vec<Term> = old_tuple.iter().copied().collect();
+
+        for update_op in update_ops {
+            if update_op.position == table.keypos {
+                return Err(
+                    anyhow!("position ({}) is the key position", update_op.position).into(),
+                );
+            }
+
+            let element = element_vec
+                .get(update_op.position - 1)
+                .copied()
+                .ok_or_else(|| {
+                    anyhow!(
+                        "position ({}) is greater than the object size ({})",
+                        update_op.position,
+                        element_vec.len()
+                    )
+                })?;
+            let counter: BigInt = element
+                .try_into()
+                .with_context(|| format!("counter ({}) is not an integer", element))?;
+            let value = update_op.apply(counter);
+            let value_term = process.integer(value)?;
+
+            element_vec[update_op.position - 1] = value_term;
+            result_vec.push(value_term);
+        }
+
+        let new_tuple = process.tuple_from_slice(&element_vec)?;
+
+        Ok(new_tuple.try_into().unwrap())
+    })?;
+
+    if !updated {
+        Err(anyhow!("table ({}) does not have key ({})", tab, key).into())
+    } else if is_list {
+        process.list_from_slice(&result_vec).map_err(From::from)
+    } else {
+        Ok(result_vec[0])
+    }
+}
+
+// Private
+
+struct UpdateOp {
+    position: usize,
+    increment: BigInt,
+    threshold_set_value: Option<(BigInt, BigInt)>,
+}
+
+impl UpdateOp {
+    fn apply(&self, counter: BigInt) -> BigInt {
+        let value = counter + &self.increment;
+
+        match &self.threshold_set_value {
+            Some((threshold, set_value)) => {
+                let zero: BigInt = 0.into();
+
+                if (self.increment >= zero && &value > threshold)
+                    || (self.increment < zero && &value < threshold)
+                {
+                    set_value.clone()
+                } else {
+                    value
+                }
+            }
+            None => value,
+        }
+    }
+}
+
+const SUPPORTED_UPDATE_OPS_CONTEXT: &str = "supported update operations are Incr, {Pos, Incr}, \
+     {Pos, Incr, Threshold, SetValue}, or a list of {Pos, Incr} and \
+     {Pos, Incr, Threshold, SetValue}";
+
+fn term_try_into_integer(name: &str, term: Term) -> anyhow::Result<BigInt> {
+    term.try_into()
+        .with_context(|| format!("{} ({}) is not an integer", name, term))
+}
+
+fn term_try_into_update_op(term: Term) -> anyhow::Result<UpdateOp> {
+    let tuple: Boxed<Tuple> = term
+        .try_into()
+        .with_context(|| format!("update operation ({}) is not a tuple", term))?;
+
+    let threshold_set_value = match tuple.len() {
+        2 => None,
+        4 => Some((
+            term_try_into_integer("threshold", tuple[2])?,
+            term_try_into_integer("set value", tuple[3])?,
+        )),
+        len => {
+            return Err(anyhow!(
+                "update operation ({}) has {} elements instead of 2 or 4",
+                term,
+                len
+            ))
+        }
+    };
+    let position: usize = tuple[0]
+        .try_into()
+        .with_context(|| format!("position ({}) is not a positive integer", tuple[0]))?;
+
+    if position == 0 {
+        return Err(anyhow!("position ({}) is not a positive integer", tuple[0]));
+    }
+
+    Ok(UpdateOp {
+        position,
+        increment: term_try_into_integer("increment", tuple[1])?,
+        threshold_set_value,
+    })
+}
+
+/// The update operations and whether they were given as a list, in which case the results are
+/// also returned as a list.
+fn term_try_into_update_ops(keypos: usize, term: Term) -> anyhow::Result<(Vec<UpdateOp>, bool)> {
+    match term.decode().unwrap() {
+        TypedTerm::SmallInteger(_) | TypedTerm::BigInteger(_) => Ok((
+            vec![UpdateOp {
+                // Like BEAM, a bare increment updates the element after the key
+                position: keypos + 1,
+                increment: term.try_into().unwrap(),
+                threshold_set_value: None,
+            }],
+            false,
+        )),
+        TypedTerm::Tuple(_) => Ok((
+            vec![term_try_into_update_op(term).context(SUPPORTED_UPDATE_OPS_CONTEXT)?],
+            false,
+        )),
+        TypedTerm::Nil => Ok((Vec::new(), true)),
+        TypedTerm::List(cons) => {
+            let mut update_op_vec = Vec::new();
+
+            for result in cons.into_iter() {
+                match result {
+                    Ok(element) => update_op_vec.push(
+                        term_try_into_update_op(element).context(SUPPORTED_UPDATE_OPS_CONTEXT)?,
+                    ),
+                    Err(_) => return Err(ImproperListError).context(SUPPORTED_UPDATE_OPS_CONTEXT),
+                }
+            }
+
+            Ok((update_op_vec, true))
+        }
+        _ => Err(TypeError).context(SUPPORTED_UPDATE_OPS_CONTEXT),
+    }
+}
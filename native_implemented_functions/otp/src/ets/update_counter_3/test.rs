@@ -0,0 +1,104 @@
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::ets::update_counter_3::native;
+use crate::ets::{insert_2, lookup_2, new_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn without_key_errors_badarg() {
+    with_process(|process| {
+        let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+        let key = Atom::str_to_term("key");
+
+        assert_badarg!(
+            native(process, tab, key, process.integer(1).unwrap()),
+            format!("table ({}) does not have key ({})", tab, key)
+        );
+    });
+}
+
+#[test]
+fn with_increment_updates_element_after_key() {
+    with_process(|process| {
+        let (tab, key) = counter_table(process, 1);
+
+        assert_eq!(
+            native(process, tab, key, process.integer(2).unwrap()),
+            Ok(process.integer(3).unwrap())
+        );
+        assert_eq!(
+            lookup_2::native(process, tab, key),
+            Ok(process
+                .list_from_slice(&[process
+                    .tuple_from_slice(&[key, process.integer(3).unwrap()])
+                    .unwrap()])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_threshold_sets_value_when_crossed() {
+    with_process(|process| {
+        let (tab, key) = counter_table(process, 9);
+        let update_op = process
+            .tuple_from_slice(&[
+                process.integer(2).unwrap(),
+                process.integer(1).unwrap(),
+                process.integer(9).unwrap(),
+                process.integer(0).unwrap(),
+            ])
+            .unwrap();
+
+        assert_eq!(
+            native(process, tab, key, update_op),
+            Ok(process.integer(0).unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_list_of_update_ops_returns_list() {
+    with_process(|process| {
+        let (tab, key) = counter_table(process, 1);
+        let update_op = process
+            .tuple_from_slice(&[process.integer(2).unwrap(), process.integer(-1).unwrap()])
+            .unwrap();
+        let update_ops = process.list_from_slice(&[update_op, update_op]).unwrap();
+
+        assert_eq!(
+            native(process, tab, key, update_ops),
+            Ok(process
+                .list_from_slice(&[process.integer(0).unwrap(), process.integer(-1).unwrap()])
+                .unwrap())
+        );
+    });
+}
+
+#[test]
+fn with_key_position_errors_badarg() {
+    with_process(|process| {
+        let (tab, key) = counter_table(process, 1);
+        let update_op = process
+            .tuple_from_slice(&[process.integer(1).unwrap(), process.integer(1).unwrap()])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, tab, key, update_op),
+            "position (1) is the key position"
+        );
+    });
+}
+
+fn counter_table(process: &Process, counter: isize) -> (Term, Term) {
+    let tab = new_2::native(process, registered_name(), Term::NIL).unwrap();
+    let key = Atom::str_to_term("key");
+    let object = process
+        .tuple_from_slice(&[key, process.integer(counter).unwrap()])
+        .unwrap();
+
+    assert_eq!(insert_2::native(process, tab, object), Ok(true.into()));
+
+    (tab, key)
+}
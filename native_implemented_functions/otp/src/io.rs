@@ -0,0 +1,8 @@
+pub mod format_1;
+pub mod format_2;
+
+use liblumen_alloc::erts::term::prelude::Atom;
+
+fn module() -> Atom {
+    Atom::try_from_str("io").unwrap()
+}
@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+#[native_implemented_function(format/1)]
+pub fn native(format: Term) -> exception::Result<Term> {
+    super::format_2::native(format, Term::NIL)
+}
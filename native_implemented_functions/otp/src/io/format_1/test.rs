@@ -0,0 +1,22 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::io::format_1::native;
+use crate::test::with_process;
+
+#[test]
+fn without_arguments_returns_ok() {
+    with_process(|process| {
+        let format = process.charlist_from_str("io_format_1_test~n").unwrap();
+
+        assert_eq!(native(format), Ok(Atom::str_to_term("ok")));
+    });
+}
+
+#[test]
+fn with_control_sequence_that_needs_argument_errors_badarg() {
+    with_process(|process| {
+        let format = process.charlist_from_str("~p").unwrap();
+
+        assert_badarg!(native(format), "no argument is left for ~p");
+    });
+}
@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::io_lib;
+use lumen_rt_full::system;
+
+#[native_implemented_function(format/2)]
+pub fn native(format: Term, data: Term) -> exception::Result<Term> {
+    let string = io_lib::format(format, data)?;
+
+    // NOT A DEBUGGING LOG
+    system::io::print(&string);
+
+    Ok(Atom::str_to_term("ok"))
+}
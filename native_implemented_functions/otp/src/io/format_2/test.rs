@@ -0,0 +1,28 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::io::format_2::native;
+use crate::test::with_process;
+
+#[test]
+fn with_valid_format_and_data_returns_ok() {
+    with_process(|process| {
+        let format = process.binary_from_str("~w~n").unwrap();
+        let data = process
+            .list_from_slice(&[Atom::str_to_term("io_format_2_test")])
+            .unwrap();
+
+        assert_eq!(native(format, data), Ok(Atom::str_to_term("ok")));
+    });
+}
+
+#[test]
+fn with_invalid_format_errors_badarg() {
+    with_process(|process| {
+        let format = process.binary_from_str("~q").unwrap();
+
+        assert_badarg!(
+            native(format, Term::NIL),
+            "~q is not a supported control sequence"
+        );
+    });
+}
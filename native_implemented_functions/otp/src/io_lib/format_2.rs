@@ -0,0 +1,17 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::io_lib;
+
+#[native_implemented_function(format/2)]
+pub fn native(process: &Process, format: Term, data: Term) -> exception::Result<Term> {
+    let string = io_lib::format(format, data)?;
+
+    process.charlist_from_str(&string).map_err(From::from)
+}
@@ -0,0 +1,219 @@
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::io_lib::format_2::native;
+use crate::test::with_process;
+
+#[test]
+fn without_list_data_errors_badarg() {
+    with_process(|process| {
+        let format = process.charlist_from_str("~w").unwrap();
+        let data = Atom::str_to_term("data");
+
+        assert_badarg!(
+            native(process, format, data),
+            format!("data ({}) is not a list", data)
+        );
+    });
+}
+
+#[test]
+fn with_too_few_arguments_errors_badarg() {
+    with_process(|process| {
+        let format = process.charlist_from_str("~w ~w").unwrap();
+        let data = process
+            .list_from_slice(&[process.integer(1).unwrap()])
+            .unwrap();
+
+        assert_badarg!(native(process, format, data), "no argument is left for ~w");
+    });
+}
+
+#[test]
+fn with_too_many_arguments_errors_badarg() {
+    with_process(|process| {
+        let format = process.charlist_from_str("~w").unwrap();
+        let data = process
+            .list_from_slice(&[process.integer(1).unwrap(), process.integer(2).unwrap()])
+            .unwrap();
+
+        assert_badarg!(
+            native(process, format, data),
+            "does not use the last 1 argument(s)"
+        );
+    });
+}
+
+#[test]
+fn with_tilde_tilde_and_n_returns_tilde_and_newline() {
+    with_process(|process| {
+        assert_formats(process, "~~~n", &[], "~\n");
+    });
+}
+
+#[test]
+fn with_c_returns_repeated_character() {
+    with_process(|process| {
+        let a = process.integer('a' as usize).unwrap();
+
+        assert_formats(process, "~c|~3c|~-3.1c|", &[a, a, a], "a|aaa|a  |");
+    });
+}
+
+#[test]
+fn with_s_pads_and_truncates() {
+    with_process(|process| {
+        let string = process.charlist_from_str("abc").unwrap();
+        let atom = Atom::str_to_term("def");
+        let binary = process.binary_from_str("ghi").unwrap();
+
+        assert_formats(
+            process,
+            "~-6s|~6s|~2s|~6.2.*s|",
+            &[
+                string,
+                atom,
+                binary,
+                process.integer('.' as usize).unwrap(),
+                string,
+            ],
+            "abc   |   def|gh|....ab|",
+        );
+    });
+}
+
+#[test]
+fn with_w_writes_erlang_syntax() {
+    with_process(|process| {
+        let list = process.charlist_from_str("hi").unwrap();
+        let tuple = process
+            .tuple_from_slice(&[
+                Atom::str_to_term("ok"),
+                Atom::str_to_term("Hello"),
+                process.float(1.0).unwrap(),
+                process.float(0.1).unwrap(),
+                process.float(1.0e20).unwrap(),
+            ])
+            .unwrap();
+
+        assert_formats(
+            process,
+            "~w ~w",
+            &[list, tuple],
+            "[104,105] {ok,'Hello',1.0,0.1,1.0e20}",
+        );
+    });
+}
+
+#[test]
+fn with_w_and_too_small_field_width_fills_with_asterisks() {
+    with_process(|process| {
+        let atom = Atom::str_to_term("atom");
+
+        assert_formats(
+            process,
+            "~3w|~6w|~-6w|",
+            &[atom, atom, atom],
+            "***|  atom|atom  |",
+        );
+    });
+}
+
+#[test]
+fn with_p_writes_printable_lists_as_strings() {
+    with_process(|process| {
+        let list = process.charlist_from_str("hi\n").unwrap();
+        let binary = process.binary_from_str("hi").unwrap();
+
+        assert_formats(
+            process,
+            "~p ~lp ~p",
+            &[list, list, binary],
+            "\"hi\\n\" [104,105,10] <<\"hi\">>",
+        );
+    });
+}
+
+#[test]
+fn with_p_writes_maps_with_sorted_keys() {
+    with_process(|process| {
+        let map = process
+            .map_from_slice(&[
+                (Atom::str_to_term("b"), process.integer(1).unwrap()),
+                (Atom::str_to_term("a"), process.integer(2).unwrap()),
+            ])
+            .unwrap();
+
+        assert_formats(process, "~p", &[map], "#{a => 2,b => 1}");
+    });
+}
+
+#[test]
+fn with_p_breaks_terms_longer_than_line() {
+    with_process(|process| {
+        let element = Atom::str_to_term("element");
+        let list = process.list_from_slice(&[element; 10]).unwrap();
+        let elements = vec!["element"; 10].join(",\n ");
+
+        assert_formats(process, "~p", &[list], &format!("[{}]", elements));
+    });
+}
+
+#[test]
+fn with_b_and_capital_b_returns_integer_in_base() {
+    with_process(|process| {
+        let integer = process.integer(255).unwrap();
+
+        assert_formats(
+            process,
+            "~b ~.16b ~.16B ~.2B",
+            &[integer, integer, integer, integer],
+            "255 ff FF 11111111",
+        );
+    });
+}
+
+#[test]
+fn with_f_e_and_g_returns_float() {
+    with_process(|process| {
+        let float = process.float(1.5).unwrap();
+
+        assert_formats(
+            process,
+            "~f ~5.2f ~e ~.3e ~g ~g",
+            &[
+                float,
+                float,
+                float,
+                float,
+                process.float(0.5).unwrap(),
+                process.float(12345.0).unwrap(),
+            ],
+            "1.500000  1.50 1.50000e+0 1.50e+0 0.500000 1.23450e+4",
+        );
+    });
+}
+
+#[test]
+fn with_f_and_integer_errors_badarg() {
+    with_process(|process| {
+        let format = process.charlist_from_str("~f").unwrap();
+        let integer = process.integer(1).unwrap();
+        let data = process.list_from_slice(&[integer]).unwrap();
+
+        assert_badarg!(
+            native(process, format, data),
+            format!("~f argument ({}) is not a float", integer)
+        );
+    });
+}
+
+fn assert_formats(process: &Process, format: &str, arguments: &[Term], expected: &str) {
+    let format = process.charlist_from_str(format).unwrap();
+    let data = process.list_from_slice(arguments).unwrap();
+
+    assert_eq!(
+        native(process, format, data),
+        Ok(process.charlist_from_str(expected).unwrap())
+    );
+}
@@ -6,10 +6,18 @@
 #[macro_use]
 mod macros;
 
+pub mod application;
+pub mod atomics;
 pub mod binary;
+pub mod counters;
 pub mod erlang;
+pub mod ets;
+pub mod io;
+pub mod io_lib;
 pub mod lists;
 pub mod maps;
+pub mod persistent_term;
+pub mod rand;
 pub mod timer;
 
 #[cfg(test)]
@@ -0,0 +1,12 @@
+pub mod erase_1;
+pub mod get_0;
+pub mod get_1;
+pub mod get_2;
+pub mod info_0;
+pub mod put_2;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+fn module() -> Atom {
+    Atom::try_from_str("persistent_term").unwrap()
+}
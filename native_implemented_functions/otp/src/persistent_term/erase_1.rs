@@ -0,0 +1,14 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::persistent_term;
+
+/// Processes that already read the value keep it, as a value that was read is never freed.
+#[native_implemented_function(erase/1)]
+pub fn native(key: Term) -> Term {
+    persistent_term::erase(key).into()
+}
@@ -0,0 +1,27 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::persistent_term::erase_1::native;
+use crate::persistent_term::{get_1, get_2, put_2};
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn without_key_returns_false() {
+    assert_eq!(native(registered_name()), false.into());
+}
+
+#[test]
+fn with_key_returns_true_and_value_stays_readable_where_it_was_read() {
+    with_process(|process| {
+        let key = registered_name();
+        let value = process
+            .tuple_from_slice(&[atom!("erased"), process.integer(2).unwrap()])
+            .unwrap();
+        put_2::native(key, value).unwrap();
+        let stored = get_1::native(key).unwrap();
+
+        assert_eq!(native(key), true.into());
+        assert_eq!(get_2::native(key, atom!("default")), atom!("default"));
+        assert_eq!(stored, value);
+    });
+}
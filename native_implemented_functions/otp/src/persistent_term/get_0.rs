@@ -0,0 +1,20 @@
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::persistent_term;
+
+/// Only the `{Key, Value}` tuples and the list are built on the heap of `process`; the keys and
+/// values are not copied.
+#[native_implemented_function(get/0)]
+pub fn native(process: &Process) -> exception::Result<Term> {
+    let mut tuple_vec = Vec::new();
+
+    for (key, value) in persistent_term::get_all() {
+        tuple_vec.push(process.tuple_from_slice(&[key, value])?);
+    }
+
+    process.list_from_slice(&tuple_vec).map_err(From::from)
+}
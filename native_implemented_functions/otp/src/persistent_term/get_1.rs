@@ -0,0 +1,17 @@
+#[cfg(test)]
+mod test;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::persistent_term;
+
+#[native_implemented_function(get/1)]
+pub fn native(key: Term) -> exception::Result<Term> {
+    persistent_term::get(key)
+        .ok_or_else(|| anyhow!("key ({}) does not have a persistent term", key).into())
+}
@@ -0,0 +1,12 @@
+use crate::persistent_term::get_1::native;
+use crate::test::registered_name;
+
+#[test]
+fn without_key_errors_badarg() {
+    let key = registered_name();
+
+    assert_badarg!(
+        native(key),
+        format!("key ({}) does not have a persistent term", key)
+    );
+}
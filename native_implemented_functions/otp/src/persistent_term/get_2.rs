@@ -0,0 +1,10 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::persistent_term;
+
+#[native_implemented_function(get/2)]
+pub fn native(key: Term, default: Term) -> Term {
+    persistent_term::get(key).unwrap_or(default)
+}
@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod test;
+
+use std::mem;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::persistent_term;
+
+/// `#{count => Count, memory => Bytes}`
+#[native_implemented_function(info/0)]
+pub fn native(process: &Process) -> exception::Result<Term> {
+    let info = persistent_term::info();
+    let count = process.integer(info.count)?;
+    let memory = process.integer(info.words * mem::size_of::<Term>())?;
+
+    process
+        .map_from_slice(&[(atom!("count"), count), (atom!("memory"), memory)])
+        .map_err(From::from)
+}
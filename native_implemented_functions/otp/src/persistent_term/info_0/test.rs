@@ -0,0 +1,27 @@
+use std::convert::TryInto;
+
+use num_bigint::BigInt;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::persistent_term::info_0::native;
+use crate::persistent_term::put_2;
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn counts_stored_terms_and_their_memory() {
+    with_process(|process| {
+        let key = registered_name();
+        let value = process.list_from_slice(&[atom!("counted")]).unwrap();
+        put_2::native(key, value).unwrap();
+
+        let info = native(process).unwrap();
+        let map: Boxed<Map> = info.try_into().unwrap();
+        let count: BigInt = map.get(atom!("count")).unwrap().try_into().unwrap();
+        let memory: BigInt = map.get(atom!("memory")).unwrap().try_into().unwrap();
+
+        assert!(count >= 1.into());
+        assert!(memory > 0.into());
+    });
+}
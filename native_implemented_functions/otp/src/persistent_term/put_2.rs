@@ -0,0 +1,17 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::persistent_term;
+
+#[native_implemented_function(put/2)]
+pub fn native(key: Term, value: Term) -> exception::Result<Term> {
+    persistent_term::put(key, value)?;
+
+    Ok(atom!("ok"))
+}
@@ -0,0 +1,127 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::persistent_term::get_1;
+use crate::persistent_term::put_2::native;
+use crate::test::{registered_name, with_process};
+
+#[test]
+fn with_new_key_is_readable_by_other_processes() {
+    let key = registered_name();
+
+    with_process(|process| {
+        let value = process
+            .tuple_from_slice(&[atom!("config"), process.integer(1).unwrap()])
+            .unwrap();
+
+        assert_eq!(native(key, value), Ok(atom!("ok")));
+    });
+
+    with_process(|process| {
+        let value = process
+            .tuple_from_slice(&[atom!("config"), process.integer(1).unwrap()])
+            .unwrap();
+
+        assert_eq!(get_1::native(key), Ok(value));
+    });
+}
+
+#[test]
+fn with_boxed_value_is_read_as_literal() {
+    with_process(|process| {
+        let key = registered_name();
+        let value = process.list_from_slice(&[atom!("a")]).unwrap();
+        let tuple = process.tuple_from_slice(&[value]).unwrap();
+
+        assert_eq!(native(key, tuple), Ok(atom!("ok")));
+
+        let stored = get_1::native(key).unwrap();
+
+        assert!(stored.is_literal());
+        assert_eq!(stored, tuple);
+    });
+}
+
+#[test]
+fn with_list_value_nested_terms_survive_garbage_collection() {
+    with_process(|process| {
+        let key = registered_name();
+        let string = process.charlist_from_str("value").unwrap();
+        let element = process
+            .tuple_from_slice(&[atom!("config"), string])
+            .unwrap();
+        let value = process
+            .list_from_slice(&[element, process.binary_from_str("binary").unwrap()])
+            .unwrap();
+
+        assert_eq!(native(key, value), Ok(atom!("ok")));
+
+        let stored = get_1::native(key).unwrap();
+        let stored_cons: Boxed<Cons> = stored.try_into().unwrap();
+        let stored_element = stored_cons.head;
+        let stored_tuple: Boxed<Tuple> = stored_element.try_into().unwrap();
+        let stored_string = stored_tuple[1];
+
+        let mut roots = [value, stored, stored_element, stored_string];
+        process.garbage_collect(0, &mut roots).unwrap();
+
+        let [value, stored, stored_element, stored_string] = roots;
+
+        assert_eq!(stored, value);
+        let value_cons: Boxed<Cons> = value.try_into().unwrap();
+        assert_eq!(stored_element, value_cons.head);
+        assert_eq!(stored_string, process.charlist_from_str("value").unwrap());
+        // Collecting the reading process left the stored term as it was for every other reader
+        assert_eq!(get_1::native(key), Ok(value));
+    });
+}
+
+#[test]
+fn with_existing_key_replaces_value_that_is_not_exactly_equal() {
+    with_process(|process| {
+        let key = registered_name();
+
+        let float = process.float(1.0).unwrap();
+
+        assert_eq!(native(key, process.integer(1).unwrap()), Ok(atom!("ok")));
+        assert_eq!(native(key, float), Ok(atom!("ok")));
+
+        let stored = get_1::native(key).unwrap();
+
+        assert!(stored.decode().unwrap().exact_eq(&float.decode().unwrap()));
+    });
+}
+
+#[test]
+fn with_replaced_value_that_was_read_keeps_old_value_readable() {
+    with_process(|process| {
+        let key = registered_name();
+        let old_value = process.list_from_slice(&[atom!("old")]).unwrap();
+
+        assert_eq!(native(key, old_value), Ok(atom!("ok")));
+
+        let stored_old_value = get_1::native(key).unwrap();
+        let new_value = process.list_from_slice(&[atom!("new")]).unwrap();
+
+        assert_eq!(native(key, new_value), Ok(atom!("ok")));
+        assert_eq!(get_1::native(key), Ok(new_value));
+        assert_eq!(stored_old_value, old_value);
+    });
+}
+
+#[test]
+fn with_replaced_value_that_was_never_read_stores_new_value() {
+    with_process(|process| {
+        let key = registered_name();
+        let old_value = process.list_from_slice(&[atom!("old")]).unwrap();
+
+        assert_eq!(native(key, old_value), Ok(atom!("ok")));
+
+        let new_value = process.list_from_slice(&[atom!("new")]).unwrap();
+
+        assert_eq!(native(key, new_value), Ok(atom!("ok")));
+        assert_eq!(get_1::native(key), Ok(new_value));
+    });
+}
@@ -0,0 +1,229 @@
+pub mod export_seed_0;
+pub mod normal_0;
+pub mod normal_2;
+pub mod seed_1;
+pub mod seed_2;
+pub mod uniform_0;
+pub mod uniform_1;
+pub mod uniform_real_0;
+pub mod uniform_s_1;
+pub mod uniform_s_2;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{One, ToPrimitive};
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::{self, AllocResult, InternalResult};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use lumen_rt_core::context::term_is_not_type;
+use lumen_rt_full::system::random::{Algorithm, Seed, State};
+
+fn module() -> Atom {
+    Atom::try_from_str("rand").unwrap()
+}
+
+/// The process dictionary key of the implicit state.
+fn seed_key() -> Term {
+    atom!("rand_seed")
+}
+
+/// Draws from the implicit state in the process dictionary, seeding it with the default algorithm
+/// first if it isn't set, and puts the next state back.
+fn with_implicit_state<F>(process: &Process, draw: F) -> exception::Result<Term>
+where
+    F: FnOnce(&mut State) -> exception::Result<Term>,
+{
+    let state_term = process.get_value_from_key(seed_key());
+    let mut state = if state_term == atom!("undefined") {
+        State::seed_non_constant(Algorithm::DEFAULT)
+    } else {
+        term_try_into_state(state_term)?
+    };
+
+    let x = draw(&mut state)?;
+    put_state(process, &state)?;
+
+    Ok(x)
+}
+
+/// Draws from the explicit `state`, returning `{X, NewState}`.
+fn with_explicit_state<F>(process: &Process, state: Term, draw: F) -> exception::Result<Term>
+where
+    F: FnOnce(&mut State) -> exception::Result<Term>,
+{
+    let mut state_state = term_try_into_state(state)?;
+    let x = draw(&mut state_state)?;
+    let new_state = state_to_term(process, &state_state)?;
+
+    process
+        .tuple_from_slice(&[x, new_state])
+        .map_err(From::from)
+}
+
+/// Puts `state` in the process dictionary as the implicit state and returns it as a term.
+fn put_state(process: &Process, state: &State) -> exception::Result<Term> {
+    let state_term = state_to_term(process, state)?;
+    process.put(seed_key(), state_term)?;
+
+    Ok(state_term)
+}
+
+/// The state is kept in the same shape as `export_seed/0` returns, `{Alg, AlgState}`, so that
+/// states and exported states are interchangeable.
+fn state_to_term(process: &Process, state: &State) -> AllocResult<Term> {
+    let algorithm = Atom::str_to_term(state.algorithm().name());
+    let algorithm_state = match state {
+        State::Exrop([s0, s1]) | State::Exsss([s0, s1]) => {
+            process.cons(process.integer(*s0)?, process.integer(*s1)?)?
+        }
+        State::Exs1024s { list, reversed } => {
+            let list_term = u64_slice_to_list(process, list)?;
+            let reversed_term = u64_slice_to_list(process, reversed)?;
+
+            process.tuple_from_slice(&[list_term, reversed_term])?
+        }
+    };
+
+    process.tuple_from_slice(&[algorithm, algorithm_state])
+}
+
+fn u64_slice_to_list(process: &Process, slice: &[u64]) -> AllocResult<Term> {
+    let mut term_vec = Vec::with_capacity(slice.len());
+
+    for u in slice {
+        term_vec.push(process.integer(*u)?);
+    }
+
+    process.list_from_slice(&term_vec)
+}
+
+fn term_try_into_algorithm(term: Term) -> InternalResult<Algorithm> {
+    let atom: Atom = term
+        .try_into()
+        .with_context(|| term_is_not_type("algorithm", term, "exrop, exs1024s or exsss"))?;
+
+    Algorithm::from_name(atom.name())
+        .ok_or_else(|| anyhow!("algorithm ({}) is not exrop, exs1024s or exsss", term).into())
+}
+
+/// A positive integer range for `uniform/1` and `uniform_s/2`.
+fn term_try_into_range(term: Term) -> InternalResult<BigUint> {
+    let big_int: BigInt = term
+        .try_into()
+        .with_context(|| term_is_not_type("N", term, "a positive integer"))?;
+
+    match big_int.to_biguint() {
+        Some(range) if range >= BigUint::one() => Ok(range),
+        _ => Err(anyhow!(term_is_not_type("N", term, "a positive integer")).into()),
+    }
+}
+
+/// `Seed` for `seed/2`: an integer or a 3-tuple of integers.
+fn term_try_into_seed(term: Term) -> InternalResult<Seed> {
+    match term.decode()? {
+        TypedTerm::Tuple(tuple) if tuple.len() == 3 => Ok(Seed::Tuple([
+            integer_term_to_wrapped_u64(tuple[0])?,
+            integer_term_to_wrapped_u64(tuple[1])?,
+            integer_term_to_wrapped_u64(tuple[2])?,
+        ])),
+        _ => integer_term_to_wrapped_u64(term)
+            .map(Seed::Integer)
+            .with_context(|| {
+                term_is_not_type(
+                    "seed",
+                    term,
+                    "an integer or a tuple of 3 integers ({A1, A2, A3})",
+                )
+            })
+            .map_err(From::from),
+    }
+}
+
+/// The lowest 64 bits of `term` in two's complement, like `Integer band 16#ffffffffffffffff`.
+fn integer_term_to_wrapped_u64(term: Term) -> anyhow::Result<u64> {
+    let big_int: BigInt = term
+        .try_into()
+        .with_context(|| term_is_not_type("seed element", term, "an integer"))?;
+    let (sign, mut bytes) = big_int.to_bytes_le();
+    bytes.resize(8, 0);
+
+    let mut le_bytes = [0; 8];
+    le_bytes.copy_from_slice(&bytes[0..8]);
+    let magnitude = u64::from_le_bytes(le_bytes);
+
+    Ok(match sign {
+        Sign::Minus => magnitude.wrapping_neg(),
+        _ => magnitude,
+    })
+}
+
+/// The state from `seed/1,2`, `uniform_s/1,2` or `export_seed/0`.
+fn term_try_into_state(term: Term) -> InternalResult<State> {
+    let context = || term_is_not_type("state", term, "a state from seed/1,2 or export_seed/0");
+    let tuple: Boxed<Tuple> = term.try_into().with_context(context)?;
+
+    if tuple.len() != 2 {
+        return Err(anyhow!(context()).into());
+    }
+
+    let algorithm = term_try_into_algorithm(tuple[0])?;
+    let algorithm_state = tuple[1];
+
+    let option_state = match algorithm {
+        Algorithm::Exrop | Algorithm::Exsss => {
+            term_try_into_u58_pair(algorithm_state).map(|pair| match algorithm {
+                Algorithm::Exrop => State::Exrop(pair),
+                _ => State::Exsss(pair),
+            })
+        }
+        Algorithm::Exs1024s => {
+            let option_list_reversed: Option<Boxed<Tuple>> = algorithm_state.try_into().ok();
+
+            option_list_reversed
+                .filter(|list_reversed| list_reversed.len() == 2)
+                .and_then(|list_reversed| {
+                    let list = list_term_to_u64_vec(list_reversed[0])?;
+                    let reversed = list_term_to_u64_vec(list_reversed[1])?;
+
+                    if !list.is_empty() && list.len() + reversed.len() == 16 {
+                        Some(State::Exs1024s { list, reversed })
+                    } else {
+                        None
+                    }
+                })
+        }
+    };
+
+    option_state.ok_or_else(|| anyhow!(context()).into())
+}
+
+/// `[S0|S1]` with both non-negative integers that fit in 58 bits.
+fn term_try_into_u58_pair(term: Term) -> Option<[u64; 2]> {
+    let cons: Boxed<Cons> = term.try_into().ok()?;
+    let s0 = term_to_u64(cons.head).filter(|s0| *s0 < (1 << 58))?;
+    let s1 = term_to_u64(cons.tail).filter(|s1| *s1 < (1 << 58))?;
+
+    Some([s0, s1])
+}
+
+fn list_term_to_u64_vec(term: Term) -> Option<Vec<u64>> {
+    match term.decode().ok()? {
+        TypedTerm::Nil => Some(Vec::new()),
+        TypedTerm::List(cons) => cons
+            .into_iter()
+            .map(|result| result.ok().and_then(term_to_u64))
+            .collect(),
+        _ => None,
+    }
+}
+
+fn term_to_u64(term: Term) -> Option<u64> {
+    let big_int: BigInt = term.try_into().ok()?;
+
+    big_int.to_u64()
+}
@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{seed_key, term_try_into_state};
+
+/// The implicit state, which is already kept in the exported form, or `undefined` if the process
+/// has not seeded or drawn yet.
+#[native_implemented_function(export_seed/0)]
+pub fn native(process: &Process) -> Term {
+    let state = process.get_value_from_key(seed_key());
+
+    if term_try_into_state(state).is_ok() {
+        state
+    } else {
+        atom!("undefined")
+    }
+}
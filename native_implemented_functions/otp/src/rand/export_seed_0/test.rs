@@ -0,0 +1,27 @@
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::rand::export_seed_0::native;
+use crate::rand::seed_2;
+use crate::test::with_process;
+
+#[test]
+fn without_seed_returns_undefined() {
+    with_process(|process| {
+        assert_eq!(native(process), atom!("undefined"));
+    });
+}
+
+#[test]
+fn with_seed_returns_state() {
+    with_process(|process| {
+        let state = seed_2::native(
+            process,
+            Atom::str_to_term("exs1024s"),
+            process.integer(42).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(native(process), state);
+    });
+}
@@ -0,0 +1,17 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::with_implicit_state;
+
+#[native_implemented_function(normal/0)]
+pub fn native(process: &Process) -> exception::Result<Term> {
+    with_implicit_state(process, |state| {
+        process.float(state.normal()).map_err(From::from)
+    })
+}
@@ -0,0 +1,27 @@
+use std::convert::TryInto;
+
+use crate::rand::normal_0::native;
+use crate::test::with_process;
+
+#[test]
+fn returns_floats_with_mean_0_and_variance_1() {
+    with_process(|process| {
+        let len = 10_000;
+        let mut x_vec = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            let x: f64 = native(process).unwrap().try_into().unwrap();
+            x_vec.push(x);
+        }
+
+        let mean = x_vec.iter().sum::<f64>() / len as f64;
+        let variance = x_vec.iter().map(|x| (x - mean) * (x - mean)).sum::<f64>() / len as f64;
+
+        assert!(mean.abs() < 0.1, "mean ({}) is not close to 0", mean);
+        assert!(
+            (variance - 1.0).abs() < 0.1,
+            "variance ({}) is not close to 1",
+            variance
+        );
+    });
+}
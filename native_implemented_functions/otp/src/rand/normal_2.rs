@@ -0,0 +1,41 @@
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_core::context::term_is_not_type;
+
+use super::with_implicit_state;
+
+#[native_implemented_function(normal/2)]
+pub fn native(process: &Process, mean: Term, variance: Term) -> exception::Result<Term> {
+    let mean_f64: f64 = mean
+        .try_into()
+        .with_context(|| term_is_not_type("mean", mean, "a number"))?;
+    let variance_f64: f64 = variance
+        .try_into()
+        .with_context(|| term_is_not_type("variance", variance, "a non-negative number"))?;
+
+    if variance_f64 < 0.0 {
+        return Err(anyhow!(term_is_not_type(
+            "variance",
+            variance,
+            "a non-negative number"
+        ))
+        .into());
+    }
+
+    with_implicit_state(process, |state| {
+        process
+            .float(state.normal_with(mean_f64, variance_f64))
+            .map_err(From::from)
+    })
+}
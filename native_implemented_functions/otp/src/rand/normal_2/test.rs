@@ -0,0 +1,46 @@
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::rand::normal_2::native;
+use crate::test::with_process;
+
+#[test]
+fn without_number_mean_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            native(
+                process,
+                Atom::str_to_term("mean"),
+                process.integer(1).unwrap()
+            ),
+            "mean (mean) is not a number"
+        );
+    });
+}
+
+#[test]
+fn with_negative_variance_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            native(
+                process,
+                process.integer(0).unwrap(),
+                process.float(-1.0).unwrap()
+            ),
+            "is not a non-negative number"
+        );
+    });
+}
+
+#[test]
+fn with_zero_variance_returns_mean() {
+    with_process(|process| {
+        assert_eq!(
+            native(
+                process,
+                process.integer(3).unwrap(),
+                process.integer(0).unwrap()
+            ),
+            Ok(process.float(3.0).unwrap())
+        );
+    });
+}
@@ -0,0 +1,25 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_full::system::random::State;
+
+use super::{put_state, term_try_into_algorithm, term_try_into_state};
+
+/// Seeds the implicit state with a non-constant seed for the algorithm `alg_or_state`, or restores
+/// a state exported with `export_seed/0`.
+#[native_implemented_function(seed/1)]
+pub fn native(process: &Process, alg_or_state: Term) -> exception::Result<Term> {
+    let state = if alg_or_state.is_atom() {
+        State::seed_non_constant(term_try_into_algorithm(alg_or_state)?)
+    } else {
+        term_try_into_state(alg_or_state)?
+    };
+
+    put_state(process, &state)
+}
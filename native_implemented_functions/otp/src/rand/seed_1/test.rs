@@ -0,0 +1,47 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::rand::seed_1::native;
+use crate::rand::{export_seed_0, uniform_0};
+use crate::test::with_process;
+
+#[test]
+fn without_algorithm_or_state_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            native(process, Atom::str_to_term("exs64")),
+            "algorithm (exs64) is not exrop, exs1024s or exsss"
+        );
+        assert_badarg!(
+            native(process, process.integer(42).unwrap()),
+            "state (42) is not a state from seed/1,2 or export_seed/0"
+        );
+    });
+}
+
+#[test]
+fn with_algorithm_puts_state_for_algorithm() {
+    with_process(|process| {
+        let state = native(process, Atom::str_to_term("exs1024s")).unwrap();
+        let tuple: Boxed<Tuple> = state.try_into().unwrap();
+
+        assert_eq!(tuple[0], Atom::str_to_term("exs1024s"));
+        assert_eq!(export_seed_0::native(process), state);
+    });
+}
+
+#[test]
+fn with_exported_state_repeats_sequence() {
+    with_process(|process| {
+        native(process, Atom::str_to_term("exrop")).unwrap();
+        let exported = export_seed_0::native(process);
+        let first = uniform_0::native(process).unwrap();
+        let second = uniform_0::native(process).unwrap();
+
+        native(process, exported).unwrap();
+
+        assert_eq!(uniform_0::native(process), Ok(first));
+        assert_eq!(uniform_0::native(process), Ok(second));
+    });
+}
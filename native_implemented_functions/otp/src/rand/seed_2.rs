@@ -0,0 +1,20 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use lumen_rt_full::system::random::State;
+
+use super::{put_state, term_try_into_algorithm, term_try_into_seed};
+
+#[native_implemented_function(seed/2)]
+pub fn native(process: &Process, alg: Term, seed: Term) -> exception::Result<Term> {
+    let algorithm = term_try_into_algorithm(alg)?;
+    let seed_seed = term_try_into_seed(seed)?;
+
+    put_state(process, &State::seed(algorithm, seed_seed))
+}
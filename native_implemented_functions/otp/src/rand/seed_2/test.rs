@@ -0,0 +1,129 @@
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::rand::seed_2::native;
+use crate::rand::uniform_0;
+use crate::test::with_process;
+
+#[test]
+fn without_algorithm_errors_badarg() {
+    with_process(|process| {
+        let alg = Atom::str_to_term("exs64");
+
+        assert_badarg!(
+            native(process, alg, process.integer(42).unwrap()),
+            "algorithm (exs64) is not exrop, exs1024s or exsss"
+        );
+    });
+}
+
+#[test]
+fn without_integer_or_tuple_seed_errors_badarg() {
+    with_process(|process| {
+        let seed = Atom::str_to_term("seed");
+
+        assert_badarg!(
+            native(process, Atom::str_to_term("exsss"), seed),
+            "seed (seed) is not an integer or a tuple of 3 integers"
+        );
+    });
+}
+
+#[test]
+fn with_exsss_integer_seed_returns_otp_state() {
+    with_process(|process| {
+        let state = native(
+            process,
+            Atom::str_to_term("exsss"),
+            process.integer(42).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            state,
+            process
+                .tuple_from_slice(&[
+                    Atom::str_to_term("exsss"),
+                    process
+                        .cons(
+                            process.integer(132629853624823445_u64).unwrap(),
+                            process.integer(67522330609774851_u64).unwrap()
+                        )
+                        .unwrap()
+                ])
+                .unwrap()
+        );
+    });
+}
+
+#[test]
+fn with_negative_integer_seed_uses_lowest_64_bits() {
+    with_process(|process| {
+        let alg = Atom::str_to_term("exsss");
+        let negative = native(process, alg, process.integer(-1).unwrap()).unwrap();
+        let max_u64 = native(process, alg, process.integer(u64::max_value()).unwrap()).unwrap();
+
+        assert_eq!(negative, max_u64);
+    });
+}
+
+#[test]
+fn with_integer_seed_uniform_matches_otp_sequence() {
+    assert_uniform_sequence(
+        "exrop",
+        |process| process.integer(42).unwrap(),
+        &[0.6944173855195852, 0.09519529252073555, 0.9731243498477494],
+    );
+    assert_uniform_sequence(
+        "exsss",
+        |process| process.integer(42).unwrap(),
+        &[0.3672301478324621, 0.899364294071664, 0.008882807305278462],
+    );
+    assert_uniform_sequence(
+        "exs1024s",
+        |process| process.integer(42).unwrap(),
+        &[0.7076122897460778, 0.12712968251806833, 0.4116548957713666],
+    );
+}
+
+#[test]
+fn with_tuple_seed_uniform_matches_otp_sequence() {
+    let seed = |process: &Process| {
+        process
+            .tuple_from_slice(&[
+                process.integer(1).unwrap(),
+                process.integer(2).unwrap(),
+                process.integer(3).unwrap(),
+            ])
+            .unwrap()
+    };
+
+    assert_uniform_sequence(
+        "exrop",
+        seed,
+        &[0.7498295129076106, 0.06161655489244533, 0.7924073127680873],
+    );
+    assert_uniform_sequence(
+        "exsss",
+        seed,
+        &[0.40656640309762415, 0.5621860443693489, 0.8329002302426167],
+    );
+    assert_uniform_sequence(
+        "exs1024s",
+        seed,
+        &[0.06907625299228148, 0.9812752738326551, 0.2854748458370905],
+    );
+}
+
+fn assert_uniform_sequence<S>(alg: &str, seed: S, expected: &[f64])
+where
+    S: Fn(&Process) -> Term,
+{
+    with_process(|process| {
+        native(process, Atom::str_to_term(alg), seed(process)).unwrap();
+
+        for x in expected {
+            assert_eq!(uniform_0::native(process), Ok(process.float(*x).unwrap()));
+        }
+    });
+}
@@ -0,0 +1,23 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::rand::uniform_0::native;
+use crate::rand::{export_seed_0, seed_key};
+use crate::test::with_process;
+
+#[test]
+fn without_seed_seeds_exsss_state() {
+    with_process(|process| {
+        assert_eq!(process.get_value_from_key(seed_key()), atom!("undefined"));
+
+        let x: f64 = native(process).unwrap().try_into().unwrap();
+
+        assert!(0.0 <= x && x < 1.0);
+
+        let state: Boxed<Tuple> = export_seed_0::native(process).try_into().unwrap();
+
+        assert_eq!(state[0], Atom::str_to_term("exsss"));
+    });
+}
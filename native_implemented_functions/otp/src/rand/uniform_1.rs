@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod test;
+
+use num_bigint::BigInt;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{term_try_into_range, with_implicit_state};
+
+#[native_implemented_function(uniform/1)]
+pub fn native(process: &Process, n: Term) -> exception::Result<Term> {
+    let range = term_try_into_range(n)?;
+
+    with_implicit_state(process, |state| {
+        let x: BigInt = state.uniform_range(&range).into();
+
+        process.integer(x).map_err(From::from)
+    })
+}
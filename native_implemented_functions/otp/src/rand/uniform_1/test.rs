@@ -0,0 +1,82 @@
+use num_bigint::BigInt;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::rand::seed_2;
+use crate::rand::uniform_1::native;
+use crate::test::with_process;
+
+#[test]
+fn without_positive_integer_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            native(process, process.integer(0).unwrap()),
+            "N (0) is not a positive integer"
+        );
+        assert_badarg!(
+            native(process, process.float(1.0).unwrap()),
+            "is not a positive integer"
+        );
+    });
+}
+
+#[test]
+fn with_seed_matches_otp_sequence() {
+    with_process(|process| {
+        seed_2::native(
+            process,
+            Atom::str_to_term("exsss"),
+            process.integer(42).unwrap(),
+        )
+        .unwrap();
+
+        for x in &[94, 31, 15] {
+            assert_eq!(
+                native(process, process.integer(100).unwrap()),
+                Ok(process.integer(*x).unwrap())
+            );
+        }
+    });
+}
+
+#[test]
+fn with_range_bigger_than_one_draw_matches_otp_sequence() {
+    let power_of_2: BigInt = BigInt::from(1) << 200;
+
+    assert_big_range_sequence(
+        "exsss",
+        power_of_2.clone(),
+        &[
+            "73389628656294484055994241631345729604341739340851797747606",
+            "374908238079189950495448746875142894936551713328105768330512",
+        ],
+    );
+    // `exrop` has a weak low bit to skip and a range that is not a power of 2
+    assert_big_range_sequence(
+        "exrop",
+        power_of_2 + 1,
+        &[
+            "116591950030788684786209766594357350264035585659715013017480",
+            "1350011870111523828774300584850299886471947754454917254956680",
+        ],
+    );
+}
+
+fn assert_big_range_sequence(algorithm: &str, n: BigInt, expected: &[&str]) {
+    with_process(|process| {
+        seed_2::native(
+            process,
+            Atom::str_to_term(algorithm),
+            process.integer(42).unwrap(),
+        )
+        .unwrap();
+
+        let n_term = process.integer(n).unwrap();
+
+        for x in expected {
+            let x: BigInt = x.parse().unwrap();
+
+            assert_eq!(native(process, n_term), Ok(process.integer(x).unwrap()));
+        }
+    });
+}
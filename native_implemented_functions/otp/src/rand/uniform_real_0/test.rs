@@ -0,0 +1,15 @@
+use std::convert::TryInto;
+
+use crate::rand::uniform_real_0::native;
+use crate::test::with_process;
+
+#[test]
+fn returns_float_greater_than_zero_and_less_than_one() {
+    with_process(|process| {
+        for _ in 0..1_000 {
+            let x: f64 = native(process).unwrap().try_into().unwrap();
+
+            assert!(0.0 < x && x < 1.0);
+        }
+    });
+}
@@ -0,0 +1,17 @@
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::with_explicit_state;
+
+#[native_implemented_function(uniform_s/1)]
+pub fn native(process: &Process, state: Term) -> exception::Result<Term> {
+    with_explicit_state(process, state, |state| {
+        process.float(state.uniform()).map_err(From::from)
+    })
+}
@@ -0,0 +1,37 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::rand::uniform_s_1::native;
+use crate::rand::{seed_2, seed_key, uniform_0};
+use crate::test::with_process;
+
+#[test]
+fn without_state_errors_badarg() {
+    with_process(|process| {
+        assert_badarg!(
+            native(process, Atom::str_to_term("exsss")),
+            "state (exsss) is not a state from seed/1,2 or export_seed/0"
+        );
+    });
+}
+
+#[test]
+fn with_state_returns_same_sequence_as_implicit_state_without_changing_it() {
+    with_process(|process| {
+        let state = seed_2::native(
+            process,
+            Atom::str_to_term("exrop"),
+            process.integer(42).unwrap(),
+        )
+        .unwrap();
+
+        let result: Boxed<Tuple> = native(process, state).unwrap().try_into().unwrap();
+
+        assert_eq!(process.get_value_from_key(seed_key()), state);
+        assert_eq!(uniform_0::native(process), Ok(result[0]));
+        assert_eq!(process.get_value_from_key(seed_key()), result[1]);
+        assert_ne!(result[1], atom!("undefined"));
+    });
+}
@@ -0,0 +1,23 @@
+#[cfg(test)]
+mod test;
+
+use num_bigint::BigInt;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use native_implemented_function::native_implemented_function;
+
+use super::{term_try_into_range, with_explicit_state};
+
+#[native_implemented_function(uniform_s/2)]
+pub fn native(process: &Process, n: Term, state: Term) -> exception::Result<Term> {
+    let range = term_try_into_range(n)?;
+
+    with_explicit_state(process, state, |state| {
+        let x: BigInt = state.uniform_range(&range).into();
+
+        process.integer(x).map_err(From::from)
+    })
+}
@@ -0,0 +1,45 @@
+use std::convert::TryInto;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::rand::uniform_s_2::native;
+use crate::rand::{seed_2, uniform_1};
+use crate::test::with_process;
+
+#[test]
+fn without_positive_integer_errors_badarg() {
+    with_process(|process| {
+        let state = seed_2::native(
+            process,
+            Atom::str_to_term("exsss"),
+            process.integer(42).unwrap(),
+        )
+        .unwrap();
+
+        assert_badarg!(
+            native(process, process.integer(-1).unwrap(), state),
+            "N (-1) is not a positive integer"
+        );
+    });
+}
+
+#[test]
+fn with_state_returns_same_sequence_as_implicit_state() {
+    with_process(|process| {
+        let mut state = seed_2::native(
+            process,
+            Atom::str_to_term("exs1024s"),
+            process.integer(42).unwrap(),
+        )
+        .unwrap();
+        let n = process.integer(1_000).unwrap();
+
+        for _ in 0..20 {
+            let result: Boxed<Tuple> = native(process, n, state).unwrap().try_into().unwrap();
+
+            assert_eq!(uniform_1::native(process, n), Ok(result[0]));
+
+            state = result[1];
+        }
+    });
+}
@@ -90,14 +90,14 @@ pub fn badarity_reason(process: &Process, function: Term, args: Term) -> Term {
 }
 
 pub fn freeze_timeout() -> Milliseconds {
-    let frozen = monotonic::freeze_time_in_milliseconds();
+    let frozen = monotonic::start_virtual_clock();
     timer::timeout();
 
     frozen
 }
 
 pub fn freeze_at_timeout(frozen: Milliseconds) {
-    monotonic::freeze_at_time_in_milliseconds(frozen);
+    monotonic::set_virtual_time_in_milliseconds(frozen);
     timer::timeout();
 }
 
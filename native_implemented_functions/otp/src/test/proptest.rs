@@ -4,6 +4,8 @@ use std::convert::TryInto;
 
 use std::sync::atomic::AtomicUsize;
 use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use num_bigint::BigInt;
 
@@ -106,6 +108,21 @@ pub fn has_message(process: &Process, data: Term) -> bool {
     })
 }
 
+/// Waits for messages sent from other threads, such as port readers, to arrive.
+pub fn wait_for_message(process: &Process, data: Term) -> bool {
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    while !has_message(process, data) {
+        if deadline <= Instant::now() {
+            return false;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    true
+}
+
 pub fn has_heap_message(process: &Process, data: Term) -> bool {
     process
         .mailbox
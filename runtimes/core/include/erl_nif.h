@@ -0,0 +1,217 @@
+/*
+ * The subset of the ERTS NIF API implemented by the Lumen runtime, so that NIF
+ * libraries written against `erl_nif.h` can be linked into a compiled program.
+ *
+ * Build the library as usual, with this directory on the include path instead
+ * of the ERTS one, then link it when compiling the module it implements:
+ *
+ *     lumen compile -L native=path/to/lib -l static=my_nif my_module.erl
+ *
+ * `ERL_NIF_INIT(my_module, ...)` defines `my_module_nif_init`, which the
+ * compiled program finds at link time and loads at startup. A static library
+ * must be linked with `static=`, so that the linker keeps the object defining
+ * it.
+ *
+ * The runtime calls NIFs in place of the Erlang functions of the same name
+ * when those are called through the runtime, e.g. with `lumen_call`,
+ * `apply/3` or a fun. Static calls between compiled modules go to the Erlang
+ * function directly.
+ *
+ * Libraries are never reloaded, upgraded or unloaded, so only the `load`
+ * callback is called, with `[]` as its `load_info`. Dirty NIFs run on the
+ * scheduler like any other NIF.
+ */
+#ifndef __ERL_NIF_H__
+#define __ERL_NIF_H__
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define ERL_NIF_MAJOR_VERSION 2
+#define ERL_NIF_MINOR_VERSION 15
+#define ERL_NIF_VM_VARIANT "lumen"
+
+typedef uint64_t ERL_NIF_TERM;
+typedef int64_t ErlNifSInt64;
+typedef uint64_t ErlNifUInt64;
+
+typedef struct enif_environment_t ErlNifEnv;
+
+typedef struct enif_func_t {
+    const char *name;
+    unsigned arity;
+    ERL_NIF_TERM (*fptr)(ErlNifEnv *env, int argc, const ERL_NIF_TERM argv[]);
+    unsigned flags;
+} ErlNifFunc;
+
+#define ERL_NIF_DIRTY_JOB_CPU_BOUND 1
+#define ERL_NIF_DIRTY_JOB_IO_BOUND 2
+
+typedef struct enif_entry_t {
+    int major;
+    int minor;
+    const char *name;
+    int num_of_funcs;
+    ErlNifFunc *funcs;
+    int (*load)(ErlNifEnv *env, void **priv_data, ERL_NIF_TERM load_info);
+    int (*reload)(ErlNifEnv *env, void **priv_data, ERL_NIF_TERM load_info);
+    int (*upgrade)(ErlNifEnv *env, void **priv_data, void **old_priv_data, ERL_NIF_TERM load_info);
+    void (*unload)(ErlNifEnv *env, void *priv_data);
+    const char *vm_variant;
+    unsigned options;
+} ErlNifEntry;
+
+typedef struct {
+    size_t size;
+    unsigned char *data;
+    void *ref_bin;
+    void *__spare__[2];
+} ErlNifBinary;
+
+typedef struct {
+    ERL_NIF_TERM pid;
+} ErlNifPid;
+
+typedef enum {
+    ERL_NIF_LATIN1 = 1,
+    ERL_NIF_UTF8 = 2,
+} ErlNifCharEncoding;
+
+typedef struct enif_resource_type_t ErlNifResourceType;
+typedef void ErlNifResourceDtor(ErlNifEnv *env, void *obj);
+
+typedef enum {
+    ERL_NIF_RT_CREATE = 1,
+    ERL_NIF_RT_TAKEOVER = 2,
+} ErlNifResourceFlags;
+
+/* Environments */
+ErlNifEnv *enif_alloc_env(void);
+void enif_free_env(ErlNifEnv *env);
+void enif_clear_env(ErlNifEnv *env);
+void *enif_priv_data(ErlNifEnv *env);
+ERL_NIF_TERM enif_make_copy(ErlNifEnv *dst_env, ERL_NIF_TERM src_term);
+
+/* Memory */
+void *enif_alloc(size_t size);
+void *enif_realloc(void *ptr, size_t size);
+void enif_free(void *ptr);
+
+/* Integers */
+ERL_NIF_TERM enif_make_int(ErlNifEnv *env, int i);
+ERL_NIF_TERM enif_make_uint(ErlNifEnv *env, unsigned i);
+ERL_NIF_TERM enif_make_long(ErlNifEnv *env, long i);
+ERL_NIF_TERM enif_make_ulong(ErlNifEnv *env, unsigned long i);
+ERL_NIF_TERM enif_make_int64(ErlNifEnv *env, ErlNifSInt64 i);
+ERL_NIF_TERM enif_make_uint64(ErlNifEnv *env, ErlNifUInt64 i);
+int enif_get_int(ErlNifEnv *env, ERL_NIF_TERM term, int *ip);
+int enif_get_uint(ErlNifEnv *env, ERL_NIF_TERM term, unsigned *ip);
+int enif_get_long(ErlNifEnv *env, ERL_NIF_TERM term, long *ip);
+int enif_get_ulong(ErlNifEnv *env, ERL_NIF_TERM term, unsigned long *ip);
+int enif_get_int64(ErlNifEnv *env, ERL_NIF_TERM term, ErlNifSInt64 *ip);
+int enif_get_uint64(ErlNifEnv *env, ERL_NIF_TERM term, ErlNifUInt64 *ip);
+
+/* Atoms */
+ERL_NIF_TERM enif_make_atom(ErlNifEnv *env, const char *name);
+ERL_NIF_TERM enif_make_atom_len(ErlNifEnv *env, const char *name, size_t len);
+int enif_make_existing_atom(ErlNifEnv *env, const char *name, ERL_NIF_TERM *atom,
+                            ErlNifCharEncoding encoding);
+int enif_make_existing_atom_len(ErlNifEnv *env, const char *name, size_t len, ERL_NIF_TERM *atom,
+                                ErlNifCharEncoding encoding);
+int enif_get_atom(ErlNifEnv *env, ERL_NIF_TERM term, char *buf, unsigned len,
+                  ErlNifCharEncoding encoding);
+int enif_get_atom_length(ErlNifEnv *env, ERL_NIF_TERM term, unsigned *len,
+                         ErlNifCharEncoding encoding);
+
+/* Binaries */
+int enif_alloc_binary(size_t size, ErlNifBinary *bin);
+int enif_realloc_binary(ErlNifBinary *bin, size_t size);
+void enif_release_binary(ErlNifBinary *bin);
+int enif_inspect_binary(ErlNifEnv *env, ERL_NIF_TERM bin_term, ErlNifBinary *bin);
+ERL_NIF_TERM enif_make_binary(ErlNifEnv *env, ErlNifBinary *bin);
+unsigned char *enif_make_new_binary(ErlNifEnv *env, size_t size, ERL_NIF_TERM *termp);
+
+/* Tuples */
+ERL_NIF_TERM enif_make_tuple(ErlNifEnv *env, unsigned cnt, ...);
+ERL_NIF_TERM enif_make_tuple_from_array(ErlNifEnv *env, const ERL_NIF_TERM arr[], unsigned cnt);
+int enif_get_tuple(ErlNifEnv *env, ERL_NIF_TERM term, int *arity, const ERL_NIF_TERM **array);
+
+#define enif_make_tuple1(E, T1) enif_make_tuple(E, 1, T1)
+#define enif_make_tuple2(E, T1, T2) enif_make_tuple(E, 2, T1, T2)
+#define enif_make_tuple3(E, T1, T2, T3) enif_make_tuple(E, 3, T1, T2, T3)
+#define enif_make_tuple4(E, T1, T2, T3, T4) enif_make_tuple(E, 4, T1, T2, T3, T4)
+
+/* Lists */
+ERL_NIF_TERM enif_make_list(ErlNifEnv *env, unsigned cnt, ...);
+ERL_NIF_TERM enif_make_list_from_array(ErlNifEnv *env, const ERL_NIF_TERM arr[], unsigned cnt);
+ERL_NIF_TERM enif_make_list_cell(ErlNifEnv *env, ERL_NIF_TERM car, ERL_NIF_TERM cdr);
+int enif_get_list_cell(ErlNifEnv *env, ERL_NIF_TERM term, ERL_NIF_TERM *head, ERL_NIF_TERM *tail);
+int enif_get_list_length(ErlNifEnv *env, ERL_NIF_TERM term, unsigned *len);
+
+#define enif_make_list1(E, T1) enif_make_list(E, 1, T1)
+#define enif_make_list2(E, T1, T2) enif_make_list(E, 2, T1, T2)
+#define enif_make_list3(E, T1, T2, T3) enif_make_list(E, 3, T1, T2, T3)
+#define enif_make_list4(E, T1, T2, T3, T4) enif_make_list(E, 4, T1, T2, T3, T4)
+
+/* Type tests */
+int enif_is_atom(ErlNifEnv *env, ERL_NIF_TERM term);
+int enif_is_binary(ErlNifEnv *env, ERL_NIF_TERM term);
+int enif_is_empty_list(ErlNifEnv *env, ERL_NIF_TERM term);
+int enif_is_list(ErlNifEnv *env, ERL_NIF_TERM term);
+int enif_is_number(ErlNifEnv *env, ERL_NIF_TERM term);
+int enif_is_pid(ErlNifEnv *env, ERL_NIF_TERM term);
+int enif_is_tuple(ErlNifEnv *env, ERL_NIF_TERM term);
+
+/* Resources */
+ErlNifResourceType *enif_open_resource_type(ErlNifEnv *env, const char *module_str,
+                                            const char *name, ErlNifResourceDtor *dtor,
+                                            ErlNifResourceFlags flags,
+                                            ErlNifResourceFlags *tried);
+void *enif_alloc_resource(ErlNifResourceType *type, size_t size);
+ERL_NIF_TERM enif_make_resource(ErlNifEnv *env, void *obj);
+int enif_get_resource(ErlNifEnv *env, ERL_NIF_TERM term, ErlNifResourceType *type, void **objp);
+void enif_keep_resource(void *obj);
+void enif_release_resource(void *obj);
+
+/* Processes */
+ErlNifPid *enif_self(ErlNifEnv *caller_env, ErlNifPid *pid);
+int enif_get_local_pid(ErlNifEnv *env, ERL_NIF_TERM term, ErlNifPid *pid);
+int enif_send(ErlNifEnv *caller_env, const ErlNifPid *to_pid, ErlNifEnv *msg_env,
+              ERL_NIF_TERM msg);
+#define enif_make_pid(ENV, PID) ((void)(ENV), (const ERL_NIF_TERM)((PID)->pid))
+
+/* Exceptions */
+ERL_NIF_TERM enif_make_badarg(ErlNifEnv *env);
+ERL_NIF_TERM enif_raise_exception(ErlNifEnv *env, ERL_NIF_TERM reason);
+int enif_has_pending_exception(ErlNifEnv *env, ERL_NIF_TERM *reason);
+
+#ifdef __cplusplus
+}
+#define ERL_NIF_INIT_EXTERN extern "C"
+#else
+#define ERL_NIF_INIT_EXTERN
+#endif
+
+#define ERL_NIF_INIT(NAME, FUNCS, LOAD, RELOAD, UPGRADE, UNLOAD)                             \
+    ERL_NIF_INIT_EXTERN ErlNifEntry *NAME##_nif_init(void);                                  \
+    ERL_NIF_INIT_EXTERN ErlNifEntry *NAME##_nif_init(void)                                   \
+    {                                                                                        \
+        static ErlNifEntry entry = {ERL_NIF_MAJOR_VERSION,                                   \
+                                    ERL_NIF_MINOR_VERSION,                                   \
+                                    #NAME,                                                   \
+                                    sizeof(FUNCS) / sizeof(*FUNCS),                          \
+                                    FUNCS,                                                   \
+                                    LOAD,                                                    \
+                                    RELOAD,                                                  \
+                                    UPGRADE,                                                 \
+                                    UNLOAD,                                                  \
+                                    ERL_NIF_VM_VARIANT,                                      \
+                                    0};                                                      \
+        return &entry;                                                                       \
+    }
+
+#endif /* __ERL_NIF_H__ */
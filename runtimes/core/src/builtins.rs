@@ -159,10 +159,3 @@ integer_math_builtin!("__lumen_builtin_math.bsr", builtin_math_bsr, shr);
 integer_math_builtin!("__lumen_builtin_math.band", builtin_math_band, bitand);
 integer_math_builtin!("__lumen_builtin_math.bor", builtin_math_bor, bitor);
 integer_math_builtin!("__lumen_builtin_math.bxor", builtin_math_bxor, bitxor);
-
-/// Capture the current stack trace
-#[export_name = "__lumen_builtin_trace_capture"]
-pub extern "C" fn builtin_trace_capture() -> Term {
-    // TODO:
-    Term::NIL
-}
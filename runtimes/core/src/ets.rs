@@ -0,0 +1,98 @@
+//! Erlang Term Storage: tables of tuples that are shared between processes and owned by the
+//! process that created them.
+mod options;
+mod table;
+
+use std::sync::Arc;
+
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+pub use options::{Access, Options, Type};
+pub use table::Table;
+
+lazy_static! {
+    static ref TABLES: RwLock<Tables> = Default::default();
+}
+
+/// Deletes `table`.
+///
+/// Returns `false` if `table` was already deleted.
+pub fn delete(table: &Table) -> bool {
+    let mut writable_tables = TABLES.write();
+
+    match writable_tables.table_by_reference.remove(&table.reference) {
+        Some(_) => {
+            if table.named {
+                writable_tables.table_by_name.remove(&table.name);
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Deletes all tables owned by `owner`, as happens when `owner` exits.
+pub fn delete_owned_by(owner: Pid) {
+    let mut writable_tables = TABLES.write();
+    let owned_references: Vec<Reference> = writable_tables
+        .table_by_reference
+        .values()
+        .filter(|table| table.owner == owner)
+        .map(|table| table.reference)
+        .collect();
+
+    for reference in owned_references {
+        if let Some(table) = writable_tables.table_by_reference.remove(&reference) {
+            if table.named {
+                writable_tables.table_by_name.remove(&table.name);
+            }
+        }
+    }
+}
+
+pub fn get_by_name(name: &Atom) -> Option<Arc<Table>> {
+    TABLES.read().table_by_name.get(name).cloned()
+}
+
+pub fn get_by_reference(reference: &Reference) -> Option<Arc<Table>> {
+    TABLES.read().table_by_reference.get(reference).cloned()
+}
+
+/// Creates a table owned by `owner`.
+///
+/// Returns `None` if `options` has `named` set and another named table already uses `name`.
+pub fn new(owner: Pid, reference: Reference, name: Atom, options: &Options) -> Option<Arc<Table>> {
+    let mut writable_tables = TABLES.write();
+
+    if options.named && writable_tables.table_by_name.contains_key(&name) {
+        return None;
+    }
+
+    let arc_table = Arc::new(Table::new(reference, name, owner, options));
+
+    if options.named {
+        writable_tables
+            .table_by_name
+            .insert(name, arc_table.clone());
+    }
+
+    writable_tables
+        .table_by_reference
+        .insert(reference, arc_table.clone());
+
+    Some(arc_table)
+}
+
+// Private
+
+#[derive(Default)]
+struct Tables {
+    table_by_name: HashMap<Atom, Arc<Table>>,
+    table_by_reference: HashMap<Reference, Arc<Table>>,
+}
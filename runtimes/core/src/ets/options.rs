@@ -0,0 +1,183 @@
+use core::convert::{TryFrom, TryInto};
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::context::term_try_into_bool;
+use crate::proplist::TryPropListFromTermError;
+
+/// How objects with the same key are stored.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Type {
+    /// One object per key, where keys are compared with `=:=`.
+    Set,
+    /// One object per key, where keys are compared with `==` and objects are kept in term order.
+    OrderedSet,
+    /// Any number of objects per key, but no two objects are identical.
+    Bag,
+    /// Any number of objects per key, including identical objects.
+    DuplicateBag,
+}
+
+impl From<Type> for Atom {
+    fn from(r#type: Type) -> Self {
+        let name = match r#type {
+            Type::Set => "set",
+            Type::OrderedSet => "ordered_set",
+            Type::Bag => "bag",
+            Type::DuplicateBag => "duplicate_bag",
+        };
+
+        Atom::from_str(name)
+    }
+}
+
+/// Which processes can read and write a table.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Access {
+    /// Any process can read and write.
+    Public,
+    /// Any process can read, but only the owner can write.
+    Protected,
+    /// Only the owner can read and write.
+    Private,
+}
+
+impl From<Access> for Atom {
+    fn from(access: Access) -> Self {
+        let name = match access {
+            Access::Public => "public",
+            Access::Protected => "protected",
+            Access::Private => "private",
+        };
+
+        Atom::from_str(name)
+    }
+}
+
+/// The options passed to `ets:new/2`.
+///
+/// `read_concurrency`, `write_concurrency`, `decentralized_counters` and `compressed` are accepted,
+/// but have no effect, as they are only performance hints.
+#[derive(Clone, Copy, Debug)]
+pub struct Options {
+    pub r#type: Type,
+    pub access: Access,
+    /// Whether the table can be referred to by its name instead of its reference.
+    pub named: bool,
+    /// The one-based index of the key in each object.
+    pub keypos: usize,
+}
+
+impl Options {
+    const SUPPORTED_OPTIONS_CONTEXT: &'static str = "supported options are set, ordered_set, \
+         bag, duplicate_bag, public, protected, private, named_table, compressed, \
+         {keypos, pos_integer()}, {heir, none}, {read_concurrency, boolean()}, \
+         {write_concurrency, boolean()}, and {decentralized_counters, boolean()}";
+
+    fn put_option_atom(&mut self, atom: Atom) -> Result<&Self, anyhow::Error> {
+        match atom.name() {
+            "set" => self.r#type = Type::Set,
+            "ordered_set" => self.r#type = Type::OrderedSet,
+            "bag" => self.r#type = Type::Bag,
+            "duplicate_bag" => self.r#type = Type::DuplicateBag,
+            "public" => self.access = Access::Public,
+            "protected" => self.access = Access::Protected,
+            "private" => self.access = Access::Private,
+            "named_table" => self.named = true,
+            "compressed" => (),
+            name => return Err(TryPropListFromTermError::AtomName(name).into()),
+        }
+
+        Ok(self)
+    }
+
+    fn put_option_term(&mut self, option: Term) -> Result<&Self, anyhow::Error> {
+        match option.decode()? {
+            TypedTerm::Atom(atom) => self.put_option_atom(atom),
+            TypedTerm::Tuple(tuple) => self.put_option_tuple(&tuple),
+            _ => Err(TryPropListFromTermError::PropertyType.into()),
+        }
+    }
+
+    fn put_option_tuple(&mut self, tuple: &Tuple) -> Result<&Self, anyhow::Error> {
+        if tuple.len() == 2 {
+            let atom: Atom = tuple[0]
+                .try_into()
+                .map_err(|_| TryPropListFromTermError::KeywordKeyType)?;
+            let value = tuple[1];
+
+            match atom.name() {
+                "keypos" => {
+                    let keypos: usize = value.try_into().context("keypos value")?;
+
+                    if keypos == 0 {
+                        return Err(anyhow!(
+                            "keypos value ({}) is not a positive integer",
+                            value
+                        ));
+                    }
+
+                    self.keypos = keypos;
+                }
+                "heir" => {
+                    let heir: Atom = value.try_into().context("only {heir, none} is supported")?;
+
+                    if heir.name() != "none" {
+                        return Err(anyhow!("only {{heir, none}} is supported"));
+                    }
+                }
+                "read_concurrency" => {
+                    term_try_into_bool("read_concurrency value", value)?;
+                }
+                "write_concurrency" => {
+                    term_try_into_bool("write_concurrency value", value)?;
+                }
+                "decentralized_counters" => {
+                    term_try_into_bool("decentralized_counters value", value)?;
+                }
+                name => return Err(TryPropListFromTermError::KeywordKeyName(name).into()),
+            }
+
+            Ok(self)
+        } else {
+            Err(TryPropListFromTermError::TupleNotPair.into())
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            r#type: Type::Set,
+            access: Access::Protected,
+            named: false,
+            keypos: 1,
+        }
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> Result<Self, Self::Error> {
+        let mut options = Self::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.decode()? {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options
+                        .put_option_term(cons.head)
+                        .context(Self::SUPPORTED_OPTIONS_CONTEXT)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(ImproperListError).context(Self::SUPPORTED_OPTIONS_CONTEXT),
+            }
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::hash::{Hash, Hasher};
+use std::ptr::{self, NonNull};
+
+use hashbrown::HashMap;
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::erts::exception::{Alloc, AllocResult};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, HeapFragment};
+
+use super::options::{Access, Options, Type};
+
+pub struct Table {
+    pub reference: Reference,
+    pub name: Atom,
+    /// Whether the table can be referred to by `name` instead of `reference`.
+    pub named: bool,
+    pub r#type: Type,
+    pub access: Access,
+    /// The one-based index of the key in each object.
+    pub keypos: usize,
+    pub owner: Pid,
+    objects: RwLock<Objects>,
+}
+
+impl Table {
+    pub fn new(reference: Reference, name: Atom, owner: Pid, options: &Options) -> Self {
+        let objects = match options.r#type {
+            Type::Set => Objects::Set(Default::default()),
+            Type::OrderedSet => Objects::OrderedSet(Default::default()),
+            Type::Bag | Type::DuplicateBag => Objects::Bag(Default::default()),
+        };
+
+        Self {
+            reference,
+            name,
+            named: options.named,
+            r#type: options.r#type,
+            access: options.access,
+            keypos: options.keypos,
+            owner,
+            objects: RwLock::new(objects),
+        }
+    }
+
+    pub fn is_readable_by(&self, pid: Pid) -> bool {
+        self.access != Access::Private || pid == self.owner
+    }
+
+    pub fn is_writable_by(&self, pid: Pid) -> bool {
+        self.access == Access::Public || pid == self.owner
+    }
+
+    /// The number of objects in the table.
+    pub fn len(&self) -> usize {
+        self.objects.read().len()
+    }
+
+    /// The number of words used by the objects in the table.
+    pub fn memory(&self) -> usize {
+        let mut words = 0;
+
+        self.objects.read().for_each(|object| {
+            words += object.size_in_words();
+        });
+
+        words
+    }
+
+    /// Copies `objects` into the table, replacing objects with the same key in `Type::Set` and
+    /// `Type::OrderedSet` tables.
+    ///
+    /// Either all `objects` are inserted or, if they can't be copied, none are.
+    pub fn insert(&self, objects: &[Boxed<Tuple>]) -> AllocResult<()> {
+        let mut object_vec = Vec::with_capacity(objects.len());
+
+        for tuple in objects {
+            assert!(self.keypos <= tuple.len());
+            object_vec.push(Object::new(*tuple)?);
+        }
+
+        let mut writable_objects = self.objects.write();
+
+        for object in object_vec {
+            writable_objects.insert(self.r#type, self.keypos, object);
+        }
+
+        Ok(())
+    }
+
+    /// Deletes all objects with `key`.
+    pub fn delete(&self, key: Term) {
+        self.objects.write().delete(self.r#type, key);
+    }
+
+    /// Folds `f` over every object in the table.
+    ///
+    /// The objects are only valid while `f` runs, so they must be copied to keep them.
+    pub fn try_fold<A, E, F>(&self, init: A, mut f: F) -> Result<A, E>
+    where
+        F: FnMut(A, Term) -> Result<A, E>,
+    {
+        let readable_objects = self.objects.read();
+        let mut acc = init;
+
+        for object in readable_objects.iter() {
+            acc = f(acc, object.term())?;
+        }
+
+        Ok(acc)
+    }
+
+    /// Folds `f` over every object with `key`.
+    ///
+    /// The objects are only valid while `f` runs, so they must be copied to keep them.
+    pub fn try_fold_key<A, E, F>(&self, key: Term, init: A, mut f: F) -> Result<A, E>
+    where
+        F: FnMut(A, Term) -> Result<A, E>,
+    {
+        let readable_objects = self.objects.read();
+        let mut acc = init;
+
+        for object in readable_objects.get(self.r#type, key) {
+            acc = f(acc, object.term())?;
+        }
+
+        Ok(acc)
+    }
+
+    /// Replaces the object with `key` in a `Type::Set` or `Type::OrderedSet` table with a copy of
+    /// the object returned by `f`, which must have the same key.
+    ///
+    /// Returns `Ok(false)` without calling `f` if there is no object with `key`.
+    pub fn update<E, F>(&self, key: Term, f: F) -> Result<bool, E>
+    where
+        E: From<Alloc>,
+        F: FnOnce(Boxed<Tuple>) -> Result<Boxed<Tuple>, E>,
+    {
+        let mut writable_objects = self.objects.write();
+
+        let old_tuple = match writable_objects.get(self.r#type, key).next() {
+            Some(object) => object.tuple,
+            None => return Ok(false),
+        };
+
+        let new_tuple = f(old_tuple)?;
+        assert!(self.keypos <= new_tuple.len());
+        let new_object = Object::new(new_tuple)?;
+        writable_objects.insert(self.r#type, self.keypos, new_object);
+
+        Ok(true)
+    }
+}
+
+// Private
+
+/// A key compared with `=:=`, as `Type::Set`, `Type::Bag` and `Type::DuplicateBag` tables do.
+#[derive(Clone, Copy)]
+struct ExactKey(Term);
+
+impl Eq for ExactKey {}
+
+impl Hash for ExactKey {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl PartialEq for ExactKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.0
+            .decode()
+            .unwrap()
+            .exact_eq(&other.0.decode().unwrap())
+    }
+}
+
+/// A tuple copied into its own heap fragment, so that it outlives the process that inserted it
+/// and is freed as soon as it is deleted.
+struct Object {
+    tuple: Boxed<Tuple>,
+    heap_fragment: NonNull<HeapFragment>,
+}
+
+impl Object {
+    fn new(tuple: Boxed<Tuple>) -> AllocResult<Self> {
+        let tuple_term: Term = tuple.encode().unwrap();
+        let (term, heap_fragment) = tuple_term.clone_to_fragment()?;
+
+        Ok(Self {
+            tuple: term.try_into().unwrap(),
+            heap_fragment,
+        })
+    }
+
+    fn key(&self, keypos: usize) -> Term {
+        self.tuple[keypos - 1]
+    }
+
+    fn size_in_words(&self) -> usize {
+        self.term().size_in_words()
+    }
+
+    fn term(&self) -> Term {
+        self.tuple.encode().unwrap()
+    }
+}
+
+impl Drop for Object {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.heap_fragment.as_ptr()) };
+    }
+}
+
+enum Objects {
+    Set(HashMap<ExactKey, Object>),
+    OrderedSet(BTreeMap<Term, Object>),
+    /// Both `Type::Bag` and `Type::DuplicateBag`, which only differ when inserting.
+    Bag(HashMap<ExactKey, Vec<Object>>),
+}
+
+impl Objects {
+    fn delete(&mut self, r#type: Type, key: Term) {
+        match self {
+            Objects::Set(object_by_key) => {
+                object_by_key.remove(&ExactKey(key));
+            }
+            Objects::OrderedSet(object_by_key) => {
+                object_by_key.remove(&key);
+            }
+            Objects::Bag(objects_by_key) => {
+                debug_assert!(r#type == Type::Bag || r#type == Type::DuplicateBag);
+                objects_by_key.remove(&ExactKey(key));
+            }
+        }
+    }
+
+    fn for_each<F: FnMut(&Object)>(&self, f: F) {
+        self.iter().for_each(f)
+    }
+
+    fn get<'a>(&'a self, r#type: Type, key: Term) -> Box<dyn Iterator<Item = &'a Object> + 'a> {
+        match self {
+            Objects::Set(object_by_key) => Box::new(object_by_key.get(&ExactKey(key)).into_iter()),
+            Objects::OrderedSet(object_by_key) => Box::new(object_by_key.get(&key).into_iter()),
+            Objects::Bag(objects_by_key) => {
+                debug_assert!(r#type == Type::Bag || r#type == Type::DuplicateBag);
+
+                Box::new(
+                    objects_by_key
+                        .get(&ExactKey(key))
+                        .into_iter()
+                        .flat_map(|objects| objects.iter()),
+                )
+            }
+        }
+    }
+
+    fn insert(&mut self, r#type: Type, keypos: usize, object: Object) {
+        let key = object.key(keypos);
+
+        match self {
+            // The old key points into the old object's heap fragment, so the old entry has to be
+            // removed instead of only replacing the value.
+            Objects::Set(object_by_key) => {
+                object_by_key.remove(&ExactKey(key));
+                object_by_key.insert(ExactKey(key), object);
+            }
+            Objects::OrderedSet(object_by_key) => {
+                object_by_key.remove(&key);
+                object_by_key.insert(key, object);
+            }
+            Objects::Bag(objects_by_key) => {
+                let objects = objects_by_key.entry(ExactKey(key)).or_default();
+
+                let is_duplicate = r#type == Type::Bag && {
+                    let term = object.term().decode().unwrap();
+
+                    objects
+                        .iter()
+                        .any(|existing| existing.term().decode().unwrap().exact_eq(&term))
+                };
+
+                if !is_duplicate {
+                    objects.push(object);
+                }
+            }
+        }
+    }
+
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = &'a Object> + 'a> {
+        match self {
+            Objects::Set(object_by_key) => Box::new(object_by_key.values()),
+            Objects::OrderedSet(object_by_key) => Box::new(object_by_key.values()),
+            Objects::Bag(objects_by_key) => {
+                Box::new(objects_by_key.values().flat_map(|objects| objects.iter()))
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        match self {
+            Objects::Set(object_by_key) => object_by_key.len(),
+            Objects::OrderedSet(object_by_key) => object_by_key.len(),
+            Objects::Bag(objects_by_key) => objects_by_key.values().map(Vec::len).sum(),
+        }
+    }
+}
+
+// The `Term`s are only read or replaced while holding the `Table`'s lock and the heap fragments
+// they are stored in are never shared with a process.
+unsafe impl Send for Table {}
+unsafe impl Sync for Table {}
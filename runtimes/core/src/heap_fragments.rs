@@ -0,0 +1,84 @@
+use std::mem;
+use std::ptr::NonNull;
+
+use liblumen_alloc::erts;
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, HeapFragment};
+
+/// Terms built outside of any process, such as by a NIF, the embedding API or the shell.
+///
+/// Each term is built in a heap fragment of its own, which is freed, along with the reference
+/// counted terms in it, such as `ProcBin`s, when the `HeapFragments` are cleared or dropped.  The
+/// elements of tuples and lists are not copied, so they must be built in `HeapFragments` that
+/// live at least as long.
+#[derive(Default)]
+pub struct HeapFragments(Vec<NonNull<HeapFragment>>);
+
+impl HeapFragments {
+    pub fn clone_term(&mut self, term: Term) -> AllocResult<Term> {
+        if term.is_immediate() {
+            Ok(term)
+        } else {
+            term.clone_to_heap(self.alloc(term.size_in_words())?)
+        }
+    }
+
+    pub fn integer<I: Into<Integer>>(&mut self, value: I) -> AllocResult<Term> {
+        match value.into() {
+            Integer::Small(small) => Ok(small.into()),
+            Integer::Big(big) => big.clone_to_heap(self.alloc(big.size_in_words())?),
+        }
+    }
+
+    pub fn binary(&mut self, bytes: &[u8]) -> AllocResult<Term> {
+        // The header and flags words come before the bytes, with an extra word for the padding a
+        // `HeapFragment` may add
+        let words = 3 + erts::to_word_size(bytes.len());
+
+        self.alloc(words)?.heapbin_from_bytes(bytes).map(From::from)
+    }
+
+    pub fn tuple(&mut self, elements: &[Term]) -> AllocResult<Term> {
+        self.alloc(Tuple::need_in_words_from_elements(elements))?
+            .tuple_from_slice(elements)
+            .map(From::from)
+    }
+
+    pub fn list(&mut self, elements: &[Term], tail: Term) -> AllocResult<Term> {
+        if elements.is_empty() {
+            Ok(tail)
+        } else {
+            // Each `Cons` is given an extra word for the padding a `HeapFragment` may add
+            let words = elements.len() * (mem::size_of::<Cons>() / mem::size_of::<Term>() + 1);
+
+            self.alloc(words)?
+                .improper_list_from_slice(elements, tail)
+                .map(|option_cons| option_cons.unwrap().into())
+        }
+    }
+
+    /// A new heap fragment with room for `words`, freed with the others
+    pub fn alloc(&mut self, words: usize) -> AllocResult<&mut HeapFragment> {
+        let mut heap_fragment = HeapFragment::new_from_word_size(words)?;
+        self.0.push(heap_fragment);
+
+        Ok(unsafe { heap_fragment.as_mut() })
+    }
+
+    /// Frees every term built so far
+    pub fn clear(&mut self) {
+        for heap_fragment in self.0.drain(..) {
+            // Dropping a fragment would only release the term at the start of it, not the
+            // `ProcBin`s nested in it
+            unsafe { HeapFragment::release_and_free(heap_fragment) };
+        }
+    }
+}
+
+impl Drop for HeapFragments {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
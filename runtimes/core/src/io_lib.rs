@@ -0,0 +1,81 @@
+//! Formatting terms as text, like [io_lib](http://erlang.org/doc/man/io_lib.html).
+//!
+//! Every runtime and the interpreter format with these functions, so that `io:format/2` prints
+//! the same output no matter how the calling code was run.
+mod format;
+mod write;
+
+use std::convert::TryInto;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+pub use format::{format, format_str};
+pub use write::{print, write};
+
+/// Flattens `chardata`, an atom, binary, or possibly deep list of characters and binaries, into
+/// a `String`.
+///
+/// When `unicode` is `false`, characters must be in `0..=255` and binaries are Latin-1, as for
+/// `~s`.  When `unicode` is `true`, characters can be any code point and binaries are UTF-8, as
+/// for `~ts`.
+pub fn chardata_to_string(name: &str, chardata: Term, unicode: bool) -> anyhow::Result<String> {
+    let mut string = String::new();
+
+    match chardata.decode()? {
+        TypedTerm::Atom(atom) => string.push_str(atom.name()),
+        _ => push_chardata(&mut string, chardata, unicode)
+            .with_context(|| format!("{} ({}) is not chardata", name, chardata))?,
+    }
+
+    Ok(string)
+}
+
+// Private
+
+fn push_chardata(string: &mut String, chardata: Term, unicode: bool) -> anyhow::Result<()> {
+    match chardata.decode()? {
+        TypedTerm::Nil => Ok(()),
+        TypedTerm::List(cons) => {
+            for result in cons.into_iter() {
+                let element = result.map_err(|_| ImproperListError)?;
+
+                if element.is_integer() {
+                    string.push(term_try_into_char(element, unicode)?);
+                } else {
+                    push_chardata(string, element, unicode)?;
+                }
+            }
+
+            Ok(())
+        }
+        _ => {
+            let bytes: Vec<u8> = chardata.try_into()?;
+
+            if unicode {
+                string.push_str(std::str::from_utf8(&bytes)?);
+            } else {
+                string.extend(bytes.into_iter().map(char::from));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn term_try_into_char(term: Term, unicode: bool) -> anyhow::Result<char> {
+    let code_point: u32 = term
+        .try_into()
+        .with_context(|| format!("character ({}) is not a code point", term))?;
+
+    if !unicode && 255 < code_point {
+        return Err(anyhow!(
+            "character ({}) is not Latin-1, so it needs the `t` modifier",
+            term
+        ));
+    }
+
+    std::char::from_u32(code_point)
+        .ok_or_else(|| anyhow!("character ({}) is not a code point", term))
+}
@@ -0,0 +1,439 @@
+use std::convert::TryInto;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use anyhow::*;
+use num_bigint::BigInt;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::chardata_to_string;
+use super::write::{self, print_at, LINE_LENGTH};
+
+/// Formats `data` according to `format`, like `io_lib:format/2`.
+///
+/// `format` is an atom, binary or chardata and `data` must be a proper list with one element for
+/// each control sequence that consumes an argument.
+pub fn format(format: Term, data: Term) -> anyhow::Result<String> {
+    let format_string = chardata_to_string("format", format, true)?;
+    let arguments = term_try_into_arguments(data)?;
+
+    format_str(&format_string, &arguments)
+}
+
+/// Formats `arguments` according to `format`.
+///
+/// Control sequences have the form `~F.P.PadModC`, where every part before the control character
+/// `C` is optional and `F` and `P` can be `*` to take their value from the next argument.
+pub fn format_str(format: &str, arguments: &[Term]) -> anyhow::Result<String> {
+    let mut formatter = Formatter {
+        output: String::new(),
+        arguments: arguments.iter(),
+    };
+    let mut chars = format.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '~' {
+            let control = formatter.parse_control(&mut chars)?;
+            formatter.control(control)?;
+        } else {
+            formatter.output.push(c);
+        }
+    }
+
+    match formatter.arguments.len() {
+        0 => Ok(formatter.output),
+        remaining => Err(anyhow!(
+            "format ({:?}) does not use the last {} argument(s)",
+            format,
+            remaining
+        )),
+    }
+}
+
+// Private
+
+#[derive(Clone, Copy, PartialEq)]
+enum Adjust {
+    Left,
+    Right,
+}
+
+struct Control {
+    character: char,
+    field_width: Option<usize>,
+    adjust: Adjust,
+    precision: Option<usize>,
+    pad: char,
+    /// The `t` modifier
+    unicode: bool,
+    /// The `l` modifier
+    lists: bool,
+}
+
+struct Formatter<'a> {
+    output: String,
+    arguments: std::slice::Iter<'a, Term>,
+}
+
+impl<'a> Formatter<'a> {
+    fn argument(&mut self, control: char) -> anyhow::Result<Term> {
+        self.arguments
+            .next()
+            .copied()
+            .ok_or_else(|| anyhow!("no argument is left for ~{}", control))
+    }
+
+    fn column(&self) -> usize {
+        match self.output.rfind('\n') {
+            Some(index) => self.output[index + 1..].chars().count(),
+            None => self.output.chars().count(),
+        }
+    }
+
+    fn control(&mut self, control: Control) -> anyhow::Result<()> {
+        let Control {
+            character,
+            field_width,
+            adjust,
+            precision,
+            pad,
+            unicode,
+            lists,
+        } = control;
+
+        match character {
+            '~' => {
+                let repeated = "~".repeat(field_width.unwrap_or(1));
+                self.output.push_str(&repeated);
+            }
+            'n' => {
+                let repeated = "\n".repeat(field_width.unwrap_or(1));
+                self.output.push_str(&repeated);
+            }
+            'i' => {
+                self.argument(character)?;
+            }
+            'c' => {
+                let argument = self.argument(character)?;
+                let code_point: u32 = argument
+                    .try_into()
+                    .with_context(|| format!("~c argument ({}) is not a character", argument))?;
+                let code_point = if unicode {
+                    code_point
+                } else {
+                    code_point & 0xFF
+                };
+                let c = std::char::from_u32(code_point)
+                    .ok_or_else(|| anyhow!("~c argument ({}) is not a character", argument))?;
+                let count = precision.or(field_width).unwrap_or(1);
+                let repeated: String = std::iter::repeat(c).take(count).collect();
+
+                self.push_adjusted(&repeated, field_width, adjust, pad);
+            }
+            's' => {
+                let argument = self.argument(character)?;
+                let string = chardata_to_string("~s argument", argument, unicode)?;
+
+                self.push_string(string, field_width, adjust, precision, pad)?;
+            }
+            'w' => {
+                let argument = self.argument(character)?;
+                let written = write::write(argument);
+
+                self.push_term(&written, field_width, adjust, pad);
+            }
+            'p' => {
+                let argument = self.argument(character)?;
+                let line_length = field_width.unwrap_or(LINE_LENGTH);
+                let column = match precision {
+                    Some(precision) => precision.saturating_sub(1),
+                    None => self.column(),
+                };
+                let printed = print_at(argument, column, line_length, unicode, !lists);
+
+                self.output.push_str(&printed);
+            }
+            'b' | 'B' => {
+                let argument = self.argument(character)?;
+                let integer: BigInt = argument.try_into().with_context(|| {
+                    format!("~{} argument ({}) is not an integer", character, argument)
+                })?;
+                let base = precision.unwrap_or(10);
+
+                if !(2..=36).contains(&base) {
+                    return Err(anyhow!("~{} base ({}) is not in 2..=36", character, base));
+                }
+
+                let mut digits = integer.to_str_radix(base as u32);
+
+                if character == 'B' {
+                    digits.make_ascii_uppercase();
+                }
+
+                self.push_term(&digits, field_width, adjust, pad);
+            }
+            'e' | 'f' | 'g' => {
+                let argument = self.argument(character)?;
+                let float: f64 = match argument.decode()? {
+                    TypedTerm::Float(float) => float.into(),
+                    _ => {
+                        return Err(anyhow!(
+                            "~{} argument ({}) is not a float",
+                            character,
+                            argument
+                        ))
+                    }
+                };
+                let formatted = match character {
+                    'e' => float_e(float, precision.unwrap_or(6))?,
+                    'f' => float_f(float, precision.unwrap_or(6))?,
+                    _ => float_g(float, precision.unwrap_or(6))?,
+                };
+
+                self.push_term(&formatted, field_width, adjust, pad);
+            }
+            _ => {
+                return Err(anyhow!(
+                    "~{} is not a supported control sequence",
+                    character
+                ))
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_control(&mut self, chars: &mut Peekable<Chars>) -> anyhow::Result<Control> {
+        let adjust = if chars.peek() == Some(&'-') {
+            chars.next();
+
+            Adjust::Left
+        } else {
+            Adjust::Right
+        };
+
+        let mut field_width = self.parse_number(chars)?;
+        let mut adjust = adjust;
+
+        // A negative field width from `*` left adjusts like `-`
+        if let Some(width) = field_width {
+            if width < 0 {
+                adjust = Adjust::Left;
+                field_width = Some(-width);
+            }
+        }
+
+        let mut precision = None;
+        let mut pad = ' ';
+
+        if chars.peek() == Some(&'.') {
+            chars.next();
+            precision = self.parse_number(chars)?;
+
+            if chars.peek() == Some(&'.') {
+                chars.next();
+
+                pad = match chars.next() {
+                    Some('*') => {
+                        let argument = self.argument('*')?;
+                        let code_point: u32 = argument.try_into().with_context(|| {
+                            format!("pad argument ({}) is not a character", argument)
+                        })?;
+
+                        std::char::from_u32(code_point).ok_or_else(|| {
+                            anyhow!("pad argument ({}) is not a character", argument)
+                        })?
+                    }
+                    Some(c) => c,
+                    None => return Err(anyhow!("format ends in the middle of a control sequence")),
+                }
+            }
+        }
+
+        let mut unicode = false;
+        let mut lists = false;
+
+        loop {
+            match chars.peek() {
+                Some('t') => unicode = true,
+                Some('l') => lists = true,
+                _ => break,
+            }
+
+            chars.next();
+        }
+
+        let character = chars
+            .next()
+            .ok_or_else(|| anyhow!("format ends in the middle of a control sequence"))?;
+
+        Ok(Control {
+            character,
+            field_width: field_width.map(|width| width as usize),
+            adjust,
+            precision: match precision {
+                Some(precision) if precision < 0 => {
+                    return Err(anyhow!("precision ({}) is negative", precision))
+                }
+                Some(precision) => Some(precision as usize),
+                None => None,
+            },
+            pad,
+            unicode,
+            lists,
+        })
+    }
+
+    fn parse_number(&mut self, chars: &mut Peekable<Chars>) -> anyhow::Result<Option<isize>> {
+        if chars.peek() == Some(&'*') {
+            chars.next();
+
+            let argument = self.argument('*')?;
+            let number: isize = argument
+                .try_into()
+                .with_context(|| format!("* argument ({}) is not an integer", argument))?;
+
+            return Ok(Some(number));
+        }
+
+        let mut option_number: Option<isize> = None;
+
+        while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+            chars.next();
+            option_number = Some(option_number.unwrap_or(0) * 10 + digit as isize);
+        }
+
+        Ok(option_number)
+    }
+
+    fn push_adjusted(&mut self, s: &str, field_width: Option<usize>, adjust: Adjust, pad: char) {
+        let len = s.chars().count();
+        let padding: String = match field_width {
+            Some(field_width) if len < field_width => {
+                std::iter::repeat(pad).take(field_width - len).collect()
+            }
+            _ => String::new(),
+        };
+
+        match adjust {
+            Adjust::Left => {
+                self.output.push_str(s);
+                self.output.push_str(&padding);
+            }
+            Adjust::Right => {
+                self.output.push_str(&padding);
+                self.output.push_str(s);
+            }
+        }
+    }
+
+    /// Strings are truncated to the precision, then padded to the field width.
+    fn push_string(
+        &mut self,
+        string: String,
+        field_width: Option<usize>,
+        adjust: Adjust,
+        precision: Option<usize>,
+        pad: char,
+    ) -> anyhow::Result<()> {
+        let precision = match (field_width, precision) {
+            (Some(field_width), Some(precision)) if field_width < precision => {
+                return Err(anyhow!(
+                    "field width ({}) is less than precision ({})",
+                    field_width,
+                    precision
+                ))
+            }
+            (_, Some(precision)) => Some(precision),
+            (field_width, None) => field_width,
+        };
+        let truncated: String = match precision {
+            Some(precision) => string.chars().take(precision).collect(),
+            None => string,
+        };
+
+        self.push_adjusted(&truncated, field_width, adjust, pad);
+
+        Ok(())
+    }
+
+    /// Terms that don't fit in the field width are replaced by `*`s.
+    fn push_term(&mut self, s: &str, field_width: Option<usize>, adjust: Adjust, pad: char) {
+        match field_width {
+            Some(field_width) if field_width < s.chars().count() => {
+                let stars = "*".repeat(field_width);
+                self.output.push_str(&stars);
+            }
+            _ => self.push_adjusted(s, field_width, adjust, pad),
+        }
+    }
+}
+
+/// `precision` significant digits in scientific notation, like `1.23000e+2`.
+fn float_e(float: f64, precision: usize) -> anyhow::Result<String> {
+    if precision < 2 {
+        return Err(anyhow!("~e precision ({}) is less than 2", precision));
+    }
+
+    let formatted = format!("{:.*e}", precision - 1, float);
+    let (mantissa, exponent) = formatted.split_at(formatted.find('e').unwrap());
+    let exponent = &exponent[1..];
+
+    Ok(if exponent.starts_with('-') {
+        format!("{}e{}", mantissa, exponent)
+    } else {
+        format!("{}e+{}", mantissa, exponent)
+    })
+}
+
+/// `precision` digits after the decimal point.
+fn float_f(float: f64, precision: usize) -> anyhow::Result<String> {
+    if precision < 1 {
+        return Err(anyhow!("~f precision ({}) is less than 1", precision));
+    }
+
+    Ok(format!("{:.*}", precision, float))
+}
+
+/// `~f` for floats in `0.1 <= |float| < 10000.0`, otherwise `~e`, always with `precision`
+/// significant digits.
+fn float_g(float: f64, precision: usize) -> anyhow::Result<String> {
+    let absolute = float.abs();
+    let option_exponent: Option<isize> = if absolute < 1.0e-1 {
+        Some(-2)
+    } else if absolute < 1.0e0 {
+        Some(-1)
+    } else if absolute < 1.0e1 {
+        Some(0)
+    } else if absolute < 1.0e2 {
+        Some(1)
+    } else if absolute < 1.0e3 {
+        Some(2)
+    } else if absolute < 1.0e4 {
+        Some(3)
+    } else {
+        None
+    };
+
+    match option_exponent {
+        Some(exponent)
+            if (precision <= 1 && exponent == -1)
+                || ((precision as isize) - 1 > exponent && -1 <= exponent) =>
+        {
+            float_f(float, ((precision as isize) - 1 - exponent) as usize)
+        }
+        _ => float_e(float, precision),
+    }
+}
+
+fn term_try_into_arguments(data: Term) -> anyhow::Result<Vec<Term>> {
+    match data.decode()? {
+        TypedTerm::Nil => Ok(Vec::new()),
+        TypedTerm::List(cons) => cons
+            .into_iter()
+            .map(|result| result.map_err(|_| anyhow!("data ({}) is not a proper list", data)))
+            .collect(),
+        _ => Err(anyhow!("data ({}) is not a list", data)),
+    }
+}
@@ -0,0 +1,564 @@
+use std::convert::TryInto;
+use std::fmt::Write;
+
+use liblumen_alloc::erts::term::closure::Definition;
+use liblumen_alloc::erts::term::prelude::*;
+
+/// The default line length of `~p`.
+pub const LINE_LENGTH: usize = 80;
+
+/// Formats `term` in standard Erlang syntax on a single line, like `io_lib:write/1` and `~w`.
+pub fn write(term: Term) -> String {
+    let mut string = String::new();
+    Writer::WRITE.write(&mut string, term);
+
+    string
+}
+
+/// Formats `term` like `io_lib:print/1` and `~p`, where lists of printable characters are
+/// written as strings and terms that don't fit on a line are broken over several lines.
+pub fn print(term: Term) -> String {
+    print_at(term, 0, LINE_LENGTH, false, true)
+}
+
+/// `print` for a term starting at `column`.
+///
+/// `unicode` allows printable lists and binaries to contain any printable code point, instead
+/// of only Latin-1 ones.  `strings` is `false` for the `l` modifier, which always writes lists
+/// as lists.
+pub(super) fn print_at(
+    term: Term,
+    column: usize,
+    line_length: usize,
+    unicode: bool,
+    strings: bool,
+) -> String {
+    let writer = Writer {
+        strings,
+        unicode,
+        line_length: Some(line_length),
+    };
+    let mut string = String::new();
+    writer.print(&mut string, term, column);
+
+    string
+}
+
+/// The shortest representation of `float` that reads back as the same value, like
+/// `io_lib_format:fwrite_g/1`.
+fn float_to_shortest(float: f64) -> String {
+    if float == 0.0 {
+        return if float.is_sign_negative() {
+            "-0.0".to_string()
+        } else {
+            "0.0".to_string()
+        };
+    }
+
+    // Rust's `{:e}` is also the shortest round-tripping representation, so only the placement of
+    // the decimal point and exponent differ.
+    let exponential = format!("{:e}", float.abs());
+    let (mantissa, exponent) = exponential.split_at(exponential.find('e').unwrap());
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: isize = exponent[1..].parse().unwrap();
+    let sign = if float < 0.0 { "-" } else { "" };
+
+    format!("{}{}", sign, insert_decimal(exponent + 1, &digits))
+}
+
+// Private
+
+struct Writer {
+    /// Write lists of printable characters as strings and binaries as `<<"...">>`.
+    strings: bool,
+    unicode: bool,
+    /// `None` writes everything on one line.
+    line_length: Option<usize>,
+}
+
+impl Writer {
+    const WRITE: Self = Self {
+        strings: false,
+        unicode: false,
+        line_length: None,
+    };
+
+    fn flat(&self) -> Self {
+        Self {
+            strings: self.strings,
+            unicode: self.unicode,
+            line_length: None,
+        }
+    }
+
+    /// Writes `term` starting at `column`, breaking compound terms that don't fit before
+    /// `line_length` with one element per line.
+    fn print(&self, string: &mut String, term: Term, column: usize) {
+        let mut flat_string = String::new();
+        self.flat().write(&mut flat_string, term);
+
+        let fits = match self.line_length {
+            Some(line_length) => column + flat_string.chars().count() <= line_length,
+            None => true,
+        };
+
+        if fits {
+            string.push_str(&flat_string);
+
+            return;
+        }
+
+        match term.decode().unwrap() {
+            TypedTerm::Tuple(tuple) if 0 < tuple.len() => {
+                string.push('{');
+                self.print_elements(string, tuple.iter().copied(), column + 1);
+                string.push('}');
+            }
+            TypedTerm::List(cons) if !(self.strings && self.is_printable_list(term)) => {
+                let mut element_vec = Vec::new();
+                let mut tail = Term::NIL;
+
+                for result in cons.into_iter() {
+                    match result {
+                        Ok(element) => element_vec.push(element),
+                        Err(ImproperList {
+                            tail: improper_tail,
+                        }) => tail = improper_tail,
+                    }
+                }
+
+                string.push('[');
+                self.print_elements(string, element_vec.into_iter(), column + 1);
+
+                if !tail.is_nil() {
+                    string.push('|');
+                    let tail_column = last_line_len(string, column);
+                    self.print(string, tail, tail_column);
+                }
+
+                string.push(']');
+            }
+            TypedTerm::Map(map) if 0 < map.len() => {
+                string.push_str("#{");
+
+                let element_column = column + 2;
+                let mut first = true;
+
+                for (key, value) in sorted_map_entries(&map) {
+                    if !first {
+                        push_newline(string, element_column);
+                    }
+                    first = false;
+
+                    let key_column = last_line_len(string, element_column);
+                    self.print(string, key, key_column);
+                    string.push_str(" => ");
+                    let value_column = last_line_len(string, element_column);
+                    self.print(string, value, value_column);
+                    string.push(',');
+                }
+
+                string.pop();
+                string.push('}');
+            }
+            _ => string.push_str(&flat_string),
+        }
+    }
+
+    fn print_elements<I: Iterator<Item = Term>>(
+        &self,
+        string: &mut String,
+        elements: I,
+        element_column: usize,
+    ) {
+        let mut first = true;
+
+        for element in elements {
+            if !first {
+                string.push(',');
+                push_newline(string, element_column);
+            }
+            first = false;
+
+            self.print(string, element, element_column);
+        }
+    }
+
+    fn write(&self, string: &mut String, term: Term) {
+        match term.decode().unwrap() {
+            TypedTerm::Atom(atom) => write_atom(string, atom),
+            TypedTerm::SmallInteger(small_integer) => write!(string, "{}", small_integer).unwrap(),
+            TypedTerm::BigInteger(big_integer) => write!(string, "{}", big_integer).unwrap(),
+            TypedTerm::Float(float) => string.push_str(&float_to_shortest(float.into())),
+            TypedTerm::Nil => string.push_str("[]"),
+            TypedTerm::List(cons) => {
+                if self.strings && self.is_printable_list(term) {
+                    string.push('"');
+
+                    for result in cons.into_iter() {
+                        let c = std::char::from_u32(result.unwrap().try_into().unwrap()).unwrap();
+                        push_escaped_char(string, c, '"');
+                    }
+
+                    string.push('"');
+                } else {
+                    string.push('[');
+
+                    let mut first = true;
+
+                    for result in cons.into_iter() {
+                        match result {
+                            Ok(element) => {
+                                if !first {
+                                    string.push(',');
+                                }
+                                first = false;
+
+                                self.write(string, element);
+                            }
+                            Err(ImproperList { tail }) => {
+                                string.push('|');
+                                self.write(string, tail);
+                            }
+                        }
+                    }
+
+                    string.push(']');
+                }
+            }
+            TypedTerm::Tuple(tuple) => {
+                string.push('{');
+
+                for (index, element) in tuple.iter().enumerate() {
+                    if 0 < index {
+                        string.push(',');
+                    }
+
+                    self.write(string, *element);
+                }
+
+                string.push('}');
+            }
+            TypedTerm::Map(map) => {
+                string.push_str("#{");
+
+                for (index, (key, value)) in sorted_map_entries(&map).into_iter().enumerate() {
+                    if 0 < index {
+                        string.push(',');
+                    }
+
+                    self.write(string, key);
+                    string.push_str(" => ");
+                    self.write(string, value);
+                }
+
+                string.push('}');
+            }
+            TypedTerm::HeapBinary(heap_binary) => {
+                self.write_bitstring(string, heap_binary.full_byte_iter().collect(), &[])
+            }
+            TypedTerm::ProcBin(process_binary) => {
+                self.write_bitstring(string, process_binary.full_byte_iter().collect(), &[])
+            }
+            TypedTerm::BinaryLiteral(binary_literal) => {
+                self.write_bitstring(string, binary_literal.full_byte_iter().collect(), &[])
+            }
+            TypedTerm::SubBinary(subbinary) => {
+                let partial_byte_bits: Vec<u8> = subbinary.partial_byte_bit_iter().collect();
+                self.write_bitstring(
+                    string,
+                    subbinary.full_byte_iter().collect(),
+                    &partial_byte_bits,
+                )
+            }
+            TypedTerm::MatchContext(match_context) => {
+                let partial_byte_bits: Vec<u8> = match_context.partial_byte_bit_iter().collect();
+                self.write_bitstring(
+                    string,
+                    match_context.full_byte_iter().collect(),
+                    &partial_byte_bits,
+                )
+            }
+            TypedTerm::Pid(pid) => write!(string, "<0.{}.{}>", pid.number(), pid.serial()).unwrap(),
+            TypedTerm::ExternalPid(external_pid) => write!(
+                string,
+                "<{}.{}.{}>",
+                external_pid.arc_node().id(),
+                external_pid.number(),
+                external_pid.serial()
+            )
+            .unwrap(),
+            TypedTerm::Port(port) => write!(string, "#Port<0.{}>", port.as_usize()).unwrap(),
+            TypedTerm::ExternalPort(external_port) => write!(
+                string,
+                "#Port<{}.{}>",
+                external_port.arc_node().id(),
+                external_port.port().as_usize()
+            )
+            .unwrap(),
+            TypedTerm::Reference(reference) => write!(
+                string,
+                "#Ref<0.{}.{}>",
+                reference.scheduler_id(),
+                reference.number()
+            )
+            .unwrap(),
+            TypedTerm::ExternalReference(external_reference) => {
+                let reference = external_reference.reference();
+
+                write!(
+                    string,
+                    "#Ref<{}.{}.{}>",
+                    external_reference.arc_node().id(),
+                    reference.scheduler_id(),
+                    reference.number()
+                )
+                .unwrap()
+            }
+            TypedTerm::ResourceReference(resource_reference) => {
+                let address = resource_reference.as_ref() as *const Resource as usize;
+
+                write!(string, "#Ref<0.0.0.{}>", address).unwrap()
+            }
+            TypedTerm::Closure(closure) => {
+                let module = closure.module();
+
+                match closure.definition() {
+                    Definition::Export { function } => {
+                        string.push_str("fun ");
+                        write_atom(string, module);
+                        string.push(':');
+                        write_atom(string, *function);
+                        write!(string, "/{}", closure.arity()).unwrap();
+                    }
+                    Definition::Anonymous {
+                        index, old_unique, ..
+                    } => {
+                        string.push_str("#Fun<");
+                        string.push_str(module.name());
+                        write!(string, ".{}.{}>", index, old_unique).unwrap();
+                    }
+                }
+            }
+        }
+    }
+
+    /// `<<1,2,3>>`, or `<<"abc">>` for printable binaries when printing strings.  Trailing bits
+    /// are written as `Value:Size`.
+    fn write_bitstring(&self, string: &mut String, bytes: Vec<u8>, partial_byte_bits: &[u8]) {
+        string.push_str("<<");
+
+        let printable_string = if self.strings && !bytes.is_empty() {
+            if self.unicode {
+                std::str::from_utf8(&bytes)
+                    .ok()
+                    .filter(|s| s.chars().all(is_printable_unicode))
+                    .map(|s| (s.to_string(), "/utf8"))
+            } else if bytes
+                .iter()
+                .all(|byte| is_printable_latin1(char::from(*byte)))
+            {
+                Some((bytes.iter().map(|byte| char::from(*byte)).collect(), ""))
+            } else {
+                None
+            }
+        } else {
+            None
+        };
+
+        let mut first = true;
+
+        match printable_string {
+            Some((printable, suffix)) => {
+                string.push('"');
+
+                for c in printable.chars() {
+                    push_escaped_char(string, c, '"');
+                }
+
+                string.push('"');
+                string.push_str(suffix);
+                first = false;
+            }
+            None => {
+                for byte in bytes {
+                    if !first {
+                        string.push(',');
+                    }
+                    first = false;
+
+                    write!(string, "{}", byte).unwrap();
+                }
+            }
+        }
+
+        if !partial_byte_bits.is_empty() {
+            if !first {
+                string.push(',');
+            }
+
+            let value = partial_byte_bits
+                .iter()
+                .fold(0_u8, |acc, bit| (acc << 1) | bit);
+
+            write!(string, "{}:{}", value, partial_byte_bits.len()).unwrap();
+        }
+
+        string.push_str(">>");
+    }
+
+    fn is_printable_list(&self, term: Term) -> bool {
+        match term.decode().unwrap() {
+            TypedTerm::List(cons) => cons.into_iter().all(|result| match result {
+                Ok(element) => match element.try_into() {
+                    Ok(code_point) => match std::char::from_u32(code_point) {
+                        Some(c) if self.unicode => is_printable_unicode(c),
+                        Some(c) => is_printable_latin1(c),
+                        None => false,
+                    },
+                    Err(_) => false,
+                },
+                Err(_) => false,
+            }),
+            _ => false,
+        }
+    }
+}
+
+/// Atoms that are reserved words, so they must be quoted.
+const RESERVED_WORDS: &[&str] = &[
+    "after", "and", "andalso", "band", "begin", "bnot", "bor", "bsl", "bsr", "bxor", "case",
+    "catch", "cond", "div", "end", "fun", "if", "let", "not", "of", "or", "orelse", "receive",
+    "rem", "try", "when", "xor",
+];
+
+fn atom_needs_quotes(name: &str) -> bool {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        Some(first) if is_lowercase_latin1(first) => {
+            !chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '@' || is_letter_latin1(c))
+                || RESERVED_WORDS.contains(&name)
+        }
+        _ => true,
+    }
+}
+
+fn insert_decimal(place: isize, digits: &str) -> String {
+    let len = digits.len() as isize;
+
+    if place == 0 {
+        format!("0.{}", digits)
+    } else if place < 0 || len <= place {
+        let exponent = (place - 1).to_string();
+        let exponent_dot = if len == 1 { 2 } else { 1 };
+        let exponent_cost = exponent.len() as isize + 1 + exponent_dot;
+
+        if place < 0 {
+            if 2 - place <= exponent_cost {
+                format!("0.{}{}", "0".repeat((-place) as usize), digits)
+            } else {
+                insert_exponent(&exponent, digits)
+            }
+        } else if place - len + 2 <= exponent_cost {
+            format!("{}{}.0", digits, "0".repeat((place - len) as usize))
+        } else {
+            insert_exponent(&exponent, digits)
+        }
+    } else {
+        let (integral, fractional) = digits.split_at(place as usize);
+
+        format!("{}.{}", integral, fractional)
+    }
+}
+
+fn insert_exponent(exponent: &str, digits: &str) -> String {
+    let (first, rest) = digits.split_at(1);
+
+    if rest.is_empty() {
+        format!("{}.0e{}", first, exponent)
+    } else {
+        format!("{}.{}e{}", first, rest, exponent)
+    }
+}
+
+fn is_letter_latin1(c: char) -> bool {
+    ('\u{C0}'..='\u{FF}').contains(&c) && c != '\u{D7}' && c != '\u{F7}'
+}
+
+fn is_lowercase_latin1(c: char) -> bool {
+    c.is_ascii_lowercase() || (('\u{DF}'..='\u{FF}').contains(&c) && c != '\u{F7}')
+}
+
+/// The characters that `io_lib:printable_latin1_list/1` accepts.
+fn is_printable_latin1(c: char) -> bool {
+    match c {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => true,
+        '\n' | '\r' | '\t' | '\u{B}' | '\u{8}' | '\u{C}' | '\u{1B}' => true,
+        _ => false,
+    }
+}
+
+/// The characters that `io_lib:printable_unicode_list/1` accepts.
+fn is_printable_unicode(c: char) -> bool {
+    match c {
+        '\u{A0}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..='\u{10FFFF}' => true,
+        _ => is_printable_latin1(c),
+    }
+}
+
+fn last_line_len(string: &str, column: usize) -> usize {
+    match string.rfind('\n') {
+        Some(index) => string[index + 1..].chars().count(),
+        None => column + string.chars().count(),
+    }
+}
+
+fn push_escaped_char(string: &mut String, c: char, quote: char) {
+    match c {
+        '\n' => string.push_str("\\n"),
+        '\r' => string.push_str("\\r"),
+        '\t' => string.push_str("\\t"),
+        '\u{B}' => string.push_str("\\v"),
+        '\u{8}' => string.push_str("\\b"),
+        '\u{C}' => string.push_str("\\f"),
+        '\u{1B}' => string.push_str("\\e"),
+        '\u{7F}' => string.push_str("\\d"),
+        '\\' => string.push_str("\\\\"),
+        _ if c == quote => {
+            string.push('\\');
+            string.push(c);
+        }
+        _ if c < ' ' => write!(string, "\\^{}", char::from(c as u8 + 64)).unwrap(),
+        _ => string.push(c),
+    }
+}
+
+fn push_newline(string: &mut String, column: usize) {
+    string.push('\n');
+    string.extend(std::iter::repeat(' ').take(column));
+}
+
+/// Map entries in term order, which is how BEAM writes small maps.
+fn sorted_map_entries(map: &Map) -> Vec<(Term, Term)> {
+    let mut entry_vec: Vec<(Term, Term)> = map.iter().map(|(key, value)| (*key, *value)).collect();
+    entry_vec.sort_by(|(left, _), (right, _)| left.cmp(right));
+
+    entry_vec
+}
+
+fn write_atom(string: &mut String, atom: Atom) {
+    let name = atom.name();
+
+    if atom_needs_quotes(name) {
+        string.push('\'');
+
+        for c in name.chars() {
+            push_escaped_char(string, c, '\'');
+        }
+
+        string.push('\'');
+    } else {
+        string.push_str(name);
+    }
+}
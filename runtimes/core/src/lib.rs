@@ -1,9 +1,16 @@
 // Layout helpers
 #![feature(alloc_layout_extra)]
+// `enif_make_tuple` and `enif_make_list`
+#![feature(c_variadic)]
 
 pub mod builtins;
 pub mod context;
 pub mod distribution;
+pub mod ets;
+pub mod heap_fragments;
+pub mod io_lib;
+pub mod nif;
+pub mod persistent_term;
 pub mod process;
 pub mod proplist;
 pub mod registry;
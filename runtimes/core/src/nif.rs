@@ -0,0 +1,236 @@
+//! An `erl_nif.h`-compatible C API, so that NIF libraries written against ERTS can be linked into
+//! a compiled program.
+//!
+//! A NIF library is compiled against `include/erl_nif.h` and linked with `-l static=NAME` or
+//! `-l NAME`. Its `ERL_NIF_INIT(module, ...)` defines `module_nif_init`, which the compiler
+//! references from the NIF table it generates for each compiled module, and which is loaded by
+//! `InitializeLumenNifTable` when the program starts.
+//!
+//! NIFs replace the Erlang functions of the same name. Calls through the runtime, e.g. with
+//! `lumen_call`, `apply/3` or a fun, check for a NIF first, and the compiler starts each function
+//! of a module that calls `erlang:load_nif/2` with the same check, so static calls reach the NIF
+//! too. As the libraries are already loaded, `erlang:load_nif/2` only checks that one was loaded
+//! for the calling module.
+pub mod binary;
+pub mod env;
+pub mod resource;
+pub mod term;
+
+use std::convert::TryInto;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int, c_uint, c_void};
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use anyhow::*;
+use hashbrown::{HashMap, HashSet};
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::erts::exception::{self, RuntimeException};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, ModuleFunctionArity};
+
+pub use self::env::ErlNifEnv;
+
+/// The signature of a NIF, `ErlNifFunc.fptr` in C
+pub type NifFunction =
+    unsafe extern "C" fn(env: *mut ErlNifEnv, argc: c_int, argv: *const Term) -> Term;
+
+/// The function defined by `ERL_NIF_INIT`, which is referenced from the generated NIF table
+pub type NifInit = unsafe extern "C" fn() -> *const ErlNifEntry;
+
+/// `ErlNifFunc` in C
+#[repr(C)]
+pub struct ErlNifFunc {
+    pub name: *const c_char,
+    pub arity: c_uint,
+    pub fptr: NifFunction,
+    /// Dirty NIFs run on the scheduler like any other NIF
+    pub flags: c_uint,
+}
+
+/// `ErlNifEntry` in C
+#[repr(C)]
+pub struct ErlNifEntry {
+    pub major: c_int,
+    pub minor: c_int,
+    pub name: *const c_char,
+    pub num_of_funcs: c_int,
+    pub funcs: *const ErlNifFunc,
+    pub load: Option<
+        unsafe extern "C" fn(
+            env: *mut ErlNifEnv,
+            priv_data: *mut *mut c_void,
+            load_info: Term,
+        ) -> c_int,
+    >,
+    /// Never called, as a statically linked library cannot be reloaded
+    pub reload: *const c_void,
+    /// Never called, as a statically linked library cannot be upgraded
+    pub upgrade: *const c_void,
+    /// Never called, as a statically linked library is never unloaded
+    pub unload: *const c_void,
+    pub vm_variant: *const c_char,
+    pub options: c_uint,
+}
+
+/// Calls the NIF defined for `module_function_arity`, if a loaded NIF library defines one, from
+/// `process`.
+///
+/// The returned term, or the reason of the exception the NIF raised, is copied to the heap of
+/// `process`.
+pub fn call(
+    process: &Arc<Process>,
+    module_function_arity: &ModuleFunctionArity,
+    arguments: &[Term],
+) -> Option<Result<Term, RuntimeException>> {
+    let nif = *NIF_BY_MODULE_FUNCTION_ARITY
+        .read()
+        .get(module_function_arity)?;
+    let mut env = ErlNifEnv::for_process(process.clone(), nif.priv_data);
+    let returned =
+        unsafe { (nif.function)(&mut env, arguments.len() as c_int, arguments.as_ptr()) };
+
+    let result = match env.take_exception() {
+        Some(reason) => Err(exception::error(
+            clone_to_process(reason, process),
+            None,
+            None,
+            anyhow!("NIF {} raised an exception", module_function_arity).into(),
+        )),
+        None if returned.is_none() => Err(exception::badarg(
+            None,
+            anyhow!("NIF {} did not return a term", module_function_arity).into(),
+        )),
+        None => Ok(clone_to_process(returned, process)),
+    };
+
+    Some(result)
+}
+
+/// Whether a loaded NIF library defines `module_function_arity`
+pub fn is_nif(module_function_arity: &ModuleFunctionArity) -> bool {
+    NIF_BY_MODULE_FUNCTION_ARITY
+        .read()
+        .contains_key(module_function_arity)
+}
+
+/// Whether a NIF library for `module` was loaded
+pub fn is_loaded(module: Atom) -> bool {
+    LOADED_MODULES.read().contains(&module)
+}
+
+/// Loads the NIF libraries in the table generated by the compiler, which has an entry for every
+/// compiled module, and a null entry for each module without a NIF library.
+///
+/// It is expected that this will be called during startup, after the atom table is initialized,
+/// so that the `load` callbacks of the libraries can make atoms.
+#[no_mangle]
+pub unsafe extern "C" fn InitializeLumenNifTable(
+    table: *const Option<NifInit>,
+    len: usize,
+) -> bool {
+    if len == 0 {
+        return true;
+    }
+    if table.is_null() {
+        return false;
+    }
+
+    for init in slice::from_raw_parts(table, len)
+        .iter()
+        .filter_map(|init| *init)
+    {
+        if load(&*init()).is_err() {
+            return false;
+        }
+    }
+
+    true
+}
+
+// Private
+
+lazy_static! {
+    static ref NIF_BY_MODULE_FUNCTION_ARITY: RwLock<HashMap<ModuleFunctionArity, Nif>> =
+        Default::default();
+    static ref LOADED_MODULES: RwLock<HashSet<Atom>> = Default::default();
+}
+
+#[derive(Clone, Copy)]
+struct Nif {
+    function: NifFunction,
+    /// Set by the `load` callback of the library
+    priv_data: *mut c_void,
+}
+
+// `priv_data` is owned by the library, which must make it safe to use from any scheduler
+unsafe impl Send for Nif {}
+unsafe impl Sync for Nif {}
+
+unsafe fn load(entry: &ErlNifEntry) -> anyhow::Result<()> {
+    let module_name = CStr::from_ptr(entry.name)
+        .to_str()
+        .context("NIF library module name is not UTF-8")?;
+    let module = Atom::try_from_str(module_name)?;
+
+    let mut priv_data = ptr::null_mut();
+
+    if let Some(load) = entry.load {
+        let mut env = ErlNifEnv::default();
+        let status = load(&mut env, &mut priv_data, Term::NIL);
+
+        if status != 0 {
+            return Err(anyhow!(
+                "NIF library for {} failed to load ({})",
+                module_name,
+                status
+            ));
+        }
+    }
+
+    let funcs = if entry.num_of_funcs > 0 {
+        slice::from_raw_parts(entry.funcs, entry.num_of_funcs as usize)
+    } else {
+        &[]
+    };
+    let mut nif_by_module_function_arity = NIF_BY_MODULE_FUNCTION_ARITY.write();
+
+    for func in funcs {
+        let function_name = CStr::from_ptr(func.name)
+            .to_str()
+            .with_context(|| format!("NIF name in {} is not UTF-8", module_name))?;
+        let module_function_arity = ModuleFunctionArity {
+            module,
+            function: Atom::try_from_str(function_name)?,
+            arity: func
+                .arity
+                .try_into()
+                .with_context(|| format!("NIF {}:{}/{}", module_name, function_name, func.arity))?,
+        };
+
+        nif_by_module_function_arity.insert(
+            module_function_arity,
+            Nif {
+                function: func.fptr,
+                priv_data,
+            },
+        );
+    }
+
+    LOADED_MODULES.write().insert(module);
+
+    Ok(())
+}
+
+fn clone_to_process(term: Term, process: &Process) -> Term {
+    if term.is_immediate() {
+        term
+    } else {
+        term.clone_to_process(process)
+    }
+}
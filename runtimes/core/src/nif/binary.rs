@@ -0,0 +1,159 @@
+use std::os::raw::{c_int, c_uchar, c_void};
+use std::ptr;
+use std::slice;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::env::ErlNifEnv;
+
+/// `ErlNifBinary` in C
+///
+/// A binary from `enif_alloc_binary` owns its `data`, which is freed by `enif_release_binary`
+/// or `enif_make_binary`, while one from `enif_inspect_binary` borrows the bytes of a term.
+#[repr(C)]
+pub struct ErlNifBinary {
+    pub size: usize,
+    pub data: *mut c_uchar,
+    /// Non-null if the binary owns `data`
+    pub ref_bin: *mut c_void,
+    pub spare: [*mut c_void; 2],
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_alloc_binary(size: usize, bin: *mut ErlNifBinary) -> c_int {
+    // Always allocate, so that `data` and `ref_bin` are non-null for an empty binary
+    let data = libc::malloc(size.max(1));
+
+    if data.is_null() {
+        0
+    } else {
+        ptr::write(
+            bin,
+            ErlNifBinary {
+                size,
+                data: data as *mut c_uchar,
+                ref_bin: data,
+                spare: [ptr::null_mut(); 2],
+            },
+        );
+
+        1
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_realloc_binary(bin: *mut ErlNifBinary, size: usize) -> c_int {
+    let bin = &mut *bin;
+
+    if bin.ref_bin.is_null() {
+        // Copy a borrowed binary to one it owns
+        let data = bin.data;
+        let old_size = bin.size;
+
+        if enif_alloc_binary(size, bin) == 0 {
+            return 0;
+        }
+        ptr::copy_nonoverlapping(data, bin.data, old_size.min(size));
+
+        1
+    } else {
+        let data = libc::realloc(bin.ref_bin, size.max(1));
+
+        if data.is_null() {
+            0
+        } else {
+            bin.size = size;
+            bin.data = data as *mut c_uchar;
+            bin.ref_bin = data;
+
+            1
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_release_binary(bin: *mut ErlNifBinary) {
+    let bin = &mut *bin;
+
+    if !bin.ref_bin.is_null() {
+        libc::free(bin.ref_bin);
+        bin.ref_bin = ptr::null_mut();
+    }
+}
+
+/// Bitstrings which aren't a whole number of bytes are not binaries, so return 0
+#[no_mangle]
+pub unsafe extern "C" fn enif_inspect_binary(
+    _env: *mut ErlNifEnv,
+    bin_term: Term,
+    bin: *mut ErlNifBinary,
+) -> c_int {
+    match bytes(bin_term) {
+        Some(bytes) => {
+            ptr::write(
+                bin,
+                ErlNifBinary {
+                    size: bytes.len(),
+                    data: bytes.as_ptr() as *mut c_uchar,
+                    ref_bin: ptr::null_mut(),
+                    spare: [ptr::null_mut(); 2],
+                },
+            );
+
+            1
+        }
+        None => 0,
+    }
+}
+
+/// Copies the bytes of `bin` into a binary term, releasing `bin` if it owns them
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_binary(env: *mut ErlNifEnv, bin: *mut ErlNifBinary) -> Term {
+    let term = {
+        let bin = &*bin;
+        let bytes: &[u8] = if bin.size == 0 {
+            &[]
+        } else {
+            slice::from_raw_parts(bin.data, bin.size)
+        };
+
+        (*env).heap_fragments.binary(bytes).unwrap_or(Term::NONE)
+    };
+    enif_release_binary(bin);
+
+    term
+}
+
+/// Returns the bytes of a new binary term, which the NIF must fill in before the term is used
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_new_binary(
+    env: *mut ErlNifEnv,
+    size: usize,
+    termp: *mut Term,
+) -> *mut c_uchar {
+    match (*env).heap_fragments.binary(&vec![0; size]) {
+        Ok(term) => {
+            *termp = term;
+
+            bytes(term).unwrap().as_ptr() as *mut c_uchar
+        }
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+// Private
+
+unsafe fn bytes<'a>(term: Term) -> Option<&'a [u8]> {
+    // The bytes are not owned by the boxed pointers, so they outlive them
+    match term.decode() {
+        Ok(TypedTerm::HeapBinary(heap_bin)) => Some(&*(heap_bin.as_bytes() as *const [u8])),
+        Ok(TypedTerm::ProcBin(proc_bin)) => Some(&*(proc_bin.as_bytes() as *const [u8])),
+        Ok(TypedTerm::BinaryLiteral(binary_literal)) => {
+            Some(&*(binary_literal.as_bytes() as *const [u8]))
+        }
+        Ok(TypedTerm::SubBinary(subbinary)) if subbinary.is_binary() && subbinary.is_aligned() => {
+            Some(&*(subbinary.as_bytes_unchecked() as *const [u8]))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,197 @@
+use std::os::raw::{c_int, c_void};
+use std::ptr;
+use std::sync::Arc;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::heap_fragments::HeapFragments;
+use crate::registry;
+use crate::scheduler;
+
+/// The terms built by a NIF, `ErlNifEnv` in C
+///
+/// Terms are built in heap fragments owned by the environment. The environment passed to a NIF
+/// belongs to the calling process, and the term the NIF returns is copied to that process's heap
+/// when it returns, so the terms built during the call are freed with the environment.
+pub struct ErlNifEnv {
+    /// The calling process, or `None` for an environment from `enif_alloc_env`, or one passed to
+    /// a `load` callback or resource destructor
+    process: Option<Arc<Process>>,
+    pub(super) heap_fragments: HeapFragments,
+    priv_data: *mut c_void,
+    /// The reason given to `enif_raise_exception`, or `badarg` for `enif_make_badarg`
+    exception: Option<Term>,
+}
+
+impl ErlNifEnv {
+    pub(super) fn for_process(process: Arc<Process>, priv_data: *mut c_void) -> Self {
+        Self {
+            process: Some(process),
+            priv_data,
+            ..Default::default()
+        }
+    }
+
+    pub(super) fn take_exception(&mut self) -> Option<Term> {
+        self.exception.take()
+    }
+}
+
+impl Default for ErlNifEnv {
+    fn default() -> Self {
+        Self {
+            process: None,
+            heap_fragments: Default::default(),
+            priv_data: ptr::null_mut(),
+            exception: None,
+        }
+    }
+}
+
+/// `ErlNifPid` in C
+#[repr(C)]
+pub struct ErlNifPid {
+    pub pid: Term,
+}
+
+#[no_mangle]
+pub extern "C" fn enif_alloc_env() -> *mut ErlNifEnv {
+    Box::into_raw(Box::new(ErlNifEnv::default()))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_free_env(env: *mut ErlNifEnv) {
+    if !env.is_null() {
+        drop(Box::from_raw(env));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_clear_env(env: *mut ErlNifEnv) {
+    (*env).heap_fragments.clear();
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_priv_data(env: *mut ErlNifEnv) -> *mut c_void {
+    (*env).priv_data
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_alloc(size: usize) -> *mut c_void {
+    libc::malloc(size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    libc::realloc(ptr, size)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_free(ptr: *mut c_void) {
+    libc::free(ptr)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_copy(dst_env: *mut ErlNifEnv, src_term: Term) -> Term {
+    (*dst_env)
+        .heap_fragments
+        .clone_term(src_term)
+        .unwrap_or(Term::NONE)
+}
+
+/// Returns null if `caller_env` does not belong to a process
+#[no_mangle]
+pub unsafe extern "C" fn enif_self(
+    caller_env: *mut ErlNifEnv,
+    pid: *mut ErlNifPid,
+) -> *mut ErlNifPid {
+    match (*caller_env).process {
+        Some(ref process) => {
+            (*pid).pid = process.pid_term();
+
+            pid
+        }
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_local_pid(
+    _env: *mut ErlNifEnv,
+    term: Term,
+    pid: *mut ErlNifPid,
+) -> c_int {
+    if term.is_local_pid() {
+        (*pid).pid = term;
+
+        1
+    } else {
+        0
+    }
+}
+
+/// Copies `msg` to the process `to_pid`, then clears `msg_env`, if it isn't null
+///
+/// Returns 0 if the process is not alive.
+#[no_mangle]
+pub unsafe extern "C" fn enif_send(
+    _caller_env: *mut ErlNifEnv,
+    to_pid: *const ErlNifPid,
+    msg_env: *mut ErlNifEnv,
+    msg: Term,
+) -> c_int {
+    let sent = match (*to_pid).pid.decode() {
+        Ok(TypedTerm::Pid(pid)) => match registry::pid_to_process(&pid) {
+            Some(ref to_process) => match to_process.send_from_other(msg) {
+                Ok(resume) => {
+                    if resume {
+                        scheduler::stop_waiting(to_process);
+                    }
+
+                    1
+                }
+                Err(_) => 0,
+            },
+            None => 0,
+        },
+        _ => 0,
+    };
+
+    if !msg_env.is_null() {
+        (*msg_env).heap_fragments.clear();
+    }
+
+    sent
+}
+
+/// Raises `reason` as an `error` when the NIF returns, regardless of the term it returns
+#[no_mangle]
+pub unsafe extern "C" fn enif_raise_exception(env: *mut ErlNifEnv, reason: Term) -> Term {
+    (*env).exception = Some(reason);
+
+    Term::NONE
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_badarg(env: *mut ErlNifEnv) -> Term {
+    enif_raise_exception(env, atom!("badarg"))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_has_pending_exception(
+    env: *mut ErlNifEnv,
+    reason: *mut Term,
+) -> c_int {
+    match (*env).exception {
+        Some(exception) => {
+            if !reason.is_null() {
+                *reason = exception;
+            }
+
+            1
+        }
+        None => 0,
+    }
+}
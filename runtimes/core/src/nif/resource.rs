@@ -0,0 +1,186 @@
+use std::alloc::{self, Layout};
+use std::ffi::{CStr, CString};
+use std::mem;
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr;
+
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::CloneToProcess;
+
+use super::env::ErlNifEnv;
+
+/// `ErlNifResourceDtor` in C
+pub type ErlNifResourceDtor = unsafe extern "C" fn(env: *mut ErlNifEnv, obj: *mut c_void);
+
+/// `ErlNifResourceFlags` in C
+pub const ERL_NIF_RT_CREATE: c_int = 1;
+pub const ERL_NIF_RT_TAKEOVER: c_int = 2;
+
+/// `ErlNifResourceType` in C, which lives as long as the program
+pub struct ErlNifResourceType {
+    name: CString,
+    dtor: Mutex<Option<ErlNifResourceDtor>>,
+}
+
+/// Opens the resource type `name`, creating it with `ERL_NIF_RT_CREATE`, or replacing the
+/// destructor of an existing one with `ERL_NIF_RT_TAKEOVER`. As every NIF library is loaded once,
+/// resource types are not owned by a module, so `module_str` is ignored, as it is by ERTS.
+#[no_mangle]
+pub unsafe extern "C" fn enif_open_resource_type(
+    _env: *mut ErlNifEnv,
+    _module_str: *const c_char,
+    name: *const c_char,
+    dtor: Option<ErlNifResourceDtor>,
+    flags: c_int,
+    tried: *mut c_int,
+) -> *const ErlNifResourceType {
+    let name = CStr::from_ptr(name);
+    let mut resource_type_by_name = RESOURCE_TYPE_BY_NAME.lock();
+
+    let (resource_type, opened): (*const ErlNifResourceType, c_int) =
+        match resource_type_by_name.get(name) {
+            Some(resource_type) if flags & ERL_NIF_RT_TAKEOVER != 0 => {
+                *resource_type.dtor.lock() = dtor;
+
+                (*resource_type, ERL_NIF_RT_TAKEOVER)
+            }
+            None if flags & ERL_NIF_RT_CREATE != 0 => {
+                let resource_type: &'static ErlNifResourceType =
+                    Box::leak(Box::new(ErlNifResourceType {
+                        name: name.to_owned(),
+                        dtor: Mutex::new(dtor),
+                    }));
+                resource_type_by_name.insert(resource_type.name.as_c_str(), resource_type);
+
+                (resource_type, ERL_NIF_RT_CREATE)
+            }
+            _ => (ptr::null(), 0),
+        };
+
+    if !tried.is_null() {
+        *tried = opened;
+    }
+
+    resource_type
+}
+
+/// Allocates an object of `size` bytes, owned by the caller until `enif_release_resource`
+#[no_mangle]
+pub unsafe extern "C" fn enif_alloc_resource(
+    resource_type: *const ErlNifResourceType,
+    size: usize,
+) -> *mut c_void {
+    let layout = match Layout::from_size_align(OBJECT_OFFSET + size, OBJECT_ALIGN) {
+        Ok(layout) => layout,
+        Err(_) => return ptr::null_mut(),
+    };
+    let allocation = alloc::alloc(layout);
+    if allocation.is_null() {
+        return ptr::null_mut();
+    }
+
+    let object = NifObject {
+        resource_type: &*resource_type,
+        allocation,
+        layout,
+    };
+
+    match Resource::new(Box::new(object)) {
+        Ok(resource) => {
+            // The reference owned by the caller, which is never dropped in place, but read out by
+            // `enif_release_resource`
+            ptr::write(allocation as *mut Resource, resource);
+
+            allocation.add(OBJECT_OFFSET) as *mut c_void
+        }
+        Err(_) => {
+            alloc::dealloc(allocation, layout);
+
+            ptr::null_mut()
+        }
+    }
+}
+
+/// The term keeps the object alive, whether or not the caller releases it
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_resource(env: *mut ErlNifEnv, obj: *mut c_void) -> Term {
+    let resource = &*resource(obj);
+
+    match (*env).heap_fragments.alloc(resource.size_in_words()) {
+        Ok(heap_fragment) => resource.clone_to_heap(heap_fragment).unwrap_or(Term::NONE),
+        Err(_) => Term::NONE,
+    }
+}
+
+/// Returns 0 if `term` is not a resource of `resource_type`
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_resource(
+    _env: *mut ErlNifEnv,
+    term: Term,
+    resource_type: *const ErlNifResourceType,
+    objp: *mut *mut c_void,
+) -> c_int {
+    match term.decode() {
+        Ok(TypedTerm::ResourceReference(resource)) => match resource.downcast_ref::<NifObject>() {
+            Some(object) if ptr::eq(object.resource_type, resource_type) => {
+                *objp = object.allocation.add(OBJECT_OFFSET) as *mut c_void;
+
+                1
+            }
+            _ => 0,
+        },
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_keep_resource(obj: *mut c_void) {
+    mem::forget((*resource(obj)).clone());
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_release_resource(obj: *mut c_void) {
+    drop(ptr::read(resource(obj)));
+}
+
+// Private
+
+/// The object of a resource is preceded by an uncounted copy of its `Resource`, so that the
+/// reference count can be reached from the pointer to the object
+const OBJECT_OFFSET: usize =
+    (mem::size_of::<Resource>() + OBJECT_ALIGN - 1) / OBJECT_ALIGN * OBJECT_ALIGN;
+/// `malloc` alignment, as NIFs may store any type in an object
+const OBJECT_ALIGN: usize = 16;
+
+lazy_static! {
+    static ref RESOURCE_TYPE_BY_NAME: Mutex<HashMap<&'static CStr, &'static ErlNifResourceType>> =
+        Default::default();
+}
+
+/// The value of a `Resource` allocated by a NIF
+struct NifObject {
+    resource_type: &'static ErlNifResourceType,
+    allocation: *mut u8,
+    layout: Layout,
+}
+
+impl Drop for NifObject {
+    fn drop(&mut self) {
+        if let Some(dtor) = *self.resource_type.dtor.lock() {
+            let mut env = ErlNifEnv::default();
+
+            unsafe { dtor(&mut env, self.allocation.add(OBJECT_OFFSET) as *mut c_void) };
+        }
+
+        unsafe { alloc::dealloc(self.allocation, self.layout) };
+    }
+}
+
+unsafe fn resource(obj: *mut c_void) -> *mut Resource {
+    (obj as *mut u8).sub(OBJECT_OFFSET) as *mut Resource
+}
@@ -0,0 +1,401 @@
+use std::convert::TryFrom;
+use std::ffi::VaListImpl;
+use std::mem;
+use std::os::raw::{c_char, c_int, c_long, c_uint, c_ulong};
+use std::slice;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use super::env::ErlNifEnv;
+
+/// `ErlNifCharEncoding` in C
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErlNifCharEncoding {
+    Latin1 = 1,
+    Utf8 = 2,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_int(env: *mut ErlNifEnv, i: c_int) -> Term {
+    (*env).heap_fragments.integer(i).unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_uint(env: *mut ErlNifEnv, i: c_uint) -> Term {
+    (*env)
+        .heap_fragments
+        .integer(i as u64)
+        .unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_long(env: *mut ErlNifEnv, i: c_long) -> Term {
+    (*env)
+        .heap_fragments
+        .integer(i as i64)
+        .unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_ulong(env: *mut ErlNifEnv, i: c_ulong) -> Term {
+    (*env)
+        .heap_fragments
+        .integer(i as u64)
+        .unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_int64(env: *mut ErlNifEnv, i: i64) -> Term {
+    (*env).heap_fragments.integer(i).unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_uint64(env: *mut ErlNifEnv, i: u64) -> Term {
+    (*env).heap_fragments.integer(i).unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_int(_env: *mut ErlNifEnv, term: Term, ip: *mut c_int) -> c_int {
+    get_integer(term, ip)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_uint(_env: *mut ErlNifEnv, term: Term, ip: *mut c_uint) -> c_int {
+    get_integer(term, ip)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_long(_env: *mut ErlNifEnv, term: Term, ip: *mut c_long) -> c_int {
+    get_integer(term, ip)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_ulong(
+    _env: *mut ErlNifEnv,
+    term: Term,
+    ip: *mut c_ulong,
+) -> c_int {
+    get_integer(term, ip)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_int64(_env: *mut ErlNifEnv, term: Term, ip: *mut i64) -> c_int {
+    get_integer(term, ip)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_uint64(_env: *mut ErlNifEnv, term: Term, ip: *mut u64) -> c_int {
+    get_integer(term, ip)
+}
+
+/// `name` is null-terminated Latin-1
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_atom(env: *mut ErlNifEnv, name: *const c_char) -> Term {
+    enif_make_atom_len(env, name, libc::strlen(name))
+}
+
+/// `name` is Latin-1
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_atom_len(
+    _env: *mut ErlNifEnv,
+    name: *const c_char,
+    len: usize,
+) -> Term {
+    match Atom::try_from_str(decode_latin1(name, len)) {
+        Ok(atom) => atom.encode().unwrap_or(Term::NONE),
+        Err(_) => Term::NONE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_existing_atom(
+    env: *mut ErlNifEnv,
+    name: *const c_char,
+    atom: *mut Term,
+    encoding: ErlNifCharEncoding,
+) -> c_int {
+    enif_make_existing_atom_len(env, name, libc::strlen(name), atom, encoding)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_existing_atom_len(
+    _env: *mut ErlNifEnv,
+    name: *const c_char,
+    len: usize,
+    atom: *mut Term,
+    encoding: ErlNifCharEncoding,
+) -> c_int {
+    let name = match encoding {
+        ErlNifCharEncoding::Latin1 => decode_latin1(name, len),
+        ErlNifCharEncoding::Utf8 => {
+            match std::str::from_utf8(slice::from_raw_parts(name as *const u8, len)) {
+                Ok(name) => name.to_string(),
+                Err(_) => return 0,
+            }
+        }
+    };
+
+    match Atom::try_from_str_existing(name).map(|existing| existing.encode()) {
+        Ok(Ok(term)) => {
+            *atom = term;
+
+            1
+        }
+        _ => 0,
+    }
+}
+
+/// Writes the null-terminated name of the atom to `buf`, returning the number of bytes written,
+/// including the null, or 0 if `term` is not an atom, or its name does not fit in `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_atom(
+    _env: *mut ErlNifEnv,
+    term: Term,
+    buf: *mut c_char,
+    len: c_uint,
+    encoding: ErlNifCharEncoding,
+) -> c_int {
+    match atom_bytes(term, encoding) {
+        Some(bytes) if bytes.len() < len as usize => {
+            let buf = slice::from_raw_parts_mut(buf as *mut u8, bytes.len() + 1);
+            buf[..bytes.len()].copy_from_slice(&bytes);
+            buf[bytes.len()] = 0;
+
+            buf.len() as c_int
+        }
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_atom_length(
+    _env: *mut ErlNifEnv,
+    term: Term,
+    len: *mut c_uint,
+    encoding: ErlNifCharEncoding,
+) -> c_int {
+    match atom_bytes(term, encoding) {
+        Some(bytes) => {
+            *len = bytes.len() as c_uint;
+
+            1
+        }
+        None => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_tuple(env: *mut ErlNifEnv, cnt: c_uint, mut args: ...) -> Term {
+    let elements = va_terms(&mut args, cnt);
+
+    (*env).heap_fragments.tuple(&elements).unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_tuple_from_array(
+    env: *mut ErlNifEnv,
+    arr: *const Term,
+    cnt: c_uint,
+) -> Term {
+    (*env)
+        .heap_fragments
+        .tuple(terms(arr, cnt))
+        .unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_tuple(
+    _env: *mut ErlNifEnv,
+    term: Term,
+    arity: *mut c_int,
+    array: *mut *const Term,
+) -> c_int {
+    match term.decode() {
+        Ok(TypedTerm::Tuple(tuple)) => {
+            *arity = tuple.len() as c_int;
+            *array = tuple.elements().as_ptr();
+
+            1
+        }
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_list(env: *mut ErlNifEnv, cnt: c_uint, mut args: ...) -> Term {
+    let elements = va_terms(&mut args, cnt);
+
+    (*env)
+        .heap_fragments
+        .list(&elements, Term::NIL)
+        .unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_list_from_array(
+    env: *mut ErlNifEnv,
+    arr: *const Term,
+    cnt: c_uint,
+) -> Term {
+    (*env)
+        .heap_fragments
+        .list(terms(arr, cnt), Term::NIL)
+        .unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_make_list_cell(env: *mut ErlNifEnv, car: Term, cdr: Term) -> Term {
+    (*env)
+        .heap_fragments
+        .list(&[car], cdr)
+        .unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_list_cell(
+    _env: *mut ErlNifEnv,
+    term: Term,
+    head: *mut Term,
+    tail: *mut Term,
+) -> c_int {
+    match term.decode() {
+        Ok(TypedTerm::List(cons)) => {
+            *head = cons.head;
+            *tail = cons.tail;
+
+            1
+        }
+        _ => 0,
+    }
+}
+
+/// Returns 0 if `term` is not a proper list
+#[no_mangle]
+pub unsafe extern "C" fn enif_get_list_length(
+    _env: *mut ErlNifEnv,
+    term: Term,
+    len: *mut c_uint,
+) -> c_int {
+    let mut length = 0;
+    let mut tail = term;
+
+    loop {
+        match tail.decode() {
+            Ok(TypedTerm::Nil) => break,
+            Ok(TypedTerm::List(cons)) => {
+                length += 1;
+                tail = cons.tail;
+            }
+            _ => return 0,
+        }
+    }
+
+    *len = length;
+
+    1
+}
+
+#[no_mangle]
+pub extern "C" fn enif_is_atom(_env: *mut ErlNifEnv, term: Term) -> c_int {
+    term.is_atom() as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn enif_is_binary(_env: *mut ErlNifEnv, term: Term) -> c_int {
+    term.is_binary() as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn enif_is_empty_list(_env: *mut ErlNifEnv, term: Term) -> c_int {
+    term.is_nil() as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn enif_is_list(_env: *mut ErlNifEnv, term: Term) -> c_int {
+    term.is_list() as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn enif_is_number(_env: *mut ErlNifEnv, term: Term) -> c_int {
+    term.is_number() as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn enif_is_pid(_env: *mut ErlNifEnv, term: Term) -> c_int {
+    term.is_pid() as c_int
+}
+
+#[no_mangle]
+pub extern "C" fn enif_is_tuple(_env: *mut ErlNifEnv, term: Term) -> c_int {
+    term.is_tuple() as c_int
+}
+
+// Private
+
+/// Returns 0 if `term` is not an integer, or does not fit in `T`
+unsafe fn get_integer<T: TryFrom<i128>>(term: Term, ip: *mut T) -> c_int {
+    let int: i128 = match term.decode() {
+        Ok(TypedTerm::SmallInteger(small)) => i64::from(small).into(),
+        Ok(TypedTerm::BigInteger(big)) => {
+            let bytes = big.to_signed_bytes_le();
+            if bytes.len() > 16 {
+                return 0;
+            }
+            // Sign-extend the compacted representation
+            let fill = if bytes[bytes.len() - 1] & 0x80 == 0 {
+                0
+            } else {
+                0xff
+            };
+            let mut buf = [fill; 16];
+            buf[..bytes.len()].copy_from_slice(&bytes);
+            i128::from_le_bytes(buf)
+        }
+        _ => return 0,
+    };
+
+    match T::try_from(int) {
+        Ok(value) => {
+            *ip = value;
+
+            1
+        }
+        Err(_) => 0,
+    }
+}
+
+fn atom_bytes(term: Term, encoding: ErlNifCharEncoding) -> Option<Vec<u8>> {
+    match term.decode() {
+        Ok(TypedTerm::Atom(atom)) => match encoding {
+            ErlNifCharEncoding::Latin1 => atom
+                .name()
+                .chars()
+                .map(|c| u8::try_from(c as u32).ok())
+                .collect(),
+            ErlNifCharEncoding::Utf8 => Some(atom.name().as_bytes().to_vec()),
+        },
+        _ => None,
+    }
+}
+
+unsafe fn decode_latin1(name: *const c_char, len: usize) -> String {
+    slice::from_raw_parts(name as *const u8, len)
+        .iter()
+        .map(|&byte| byte as char)
+        .collect()
+}
+
+unsafe fn terms<'a>(arr: *const Term, cnt: c_uint) -> &'a [Term] {
+    if cnt == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(arr, cnt as usize)
+    }
+}
+
+unsafe fn va_terms(args: &mut VaListImpl, cnt: c_uint) -> Vec<Term> {
+    (0..cnt)
+        .map(|_| mem::transmute::<u64, Term>(args.arg::<u64>()))
+        .collect()
+}
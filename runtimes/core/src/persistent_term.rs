@@ -0,0 +1,197 @@
+//! Terms that are stored once and then read by any process without copying.
+//!
+//! Each key and value is copied into its own heap fragment, the same way ETS objects are, but the
+//! fragment is then made a literal area, like the literals compiled into the executable, so
+//! processes can keep the value, or any term nested in it, on their heaps, send it and store it
+//! in ETS without ever copying it, and garbage collection never moves it.
+//! Because of that, once a stored term has been read, it is never freed, even after it is erased
+//! or replaced, as there is no telling which processes still refer to it: updating a persistent
+//! term that has been read leaks the old one, so they are meant for values that rarely change.
+//! A term that was never read is freed as soon as it is erased or replaced.
+use std::hash::{Hash, Hasher};
+use std::ptr::NonNull;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::literal;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, HeapFragment};
+
+lazy_static! {
+    static ref TERMS: RwLock<Terms> = Default::default();
+}
+
+/// Erases the term stored under `key`.
+///
+/// Returns `false` if there was no term stored under `key`.
+pub fn erase(key: Term) -> bool {
+    TERMS.write().entry_by_key.remove(&Key(key)).is_some()
+}
+
+/// The value stored under `key` as a literal.
+pub fn get(key: Term) -> Option<Term> {
+    TERMS
+        .read()
+        .entry_by_key
+        .get(&Key(key))
+        .map(|entry| entry.read_value())
+}
+
+/// All keys and values, as literals.
+pub fn get_all() -> Vec<(Term, Term)> {
+    TERMS
+        .read()
+        .entry_by_key
+        .values()
+        .map(|entry| (entry.key(), entry.read_value()))
+        .collect()
+}
+
+/// The number of stored terms and the number of words used by them.
+///
+/// Only terms that can still be read are counted, not those kept by `erase` or `put`.
+pub fn info() -> Info {
+    let readable_terms = TERMS.read();
+
+    Info {
+        count: readable_terms.entry_by_key.len(),
+        words: readable_terms
+            .entry_by_key
+            .values()
+            .map(|entry| entry.words)
+            .sum(),
+    }
+}
+
+/// Copies `key` and `value` out of the calling process and stores them.
+///
+/// Putting a `value` that is exactly equal to the one already stored under `key` does nothing,
+/// so that it doesn't replace a term that may have been read.
+pub fn put(key: Term, value: Term) -> AllocResult<()> {
+    // Not `get`, as comparing doesn't hand the value out
+    let unchanged = TERMS
+        .read()
+        .entry_by_key
+        .get(&Key(key))
+        .map_or(false, |entry| {
+            entry
+                .value()
+                .decode()
+                .unwrap()
+                .exact_eq(&value.decode().unwrap())
+        });
+
+    if unchanged {
+        return Ok(());
+    }
+
+    let entry = Entry::new(key, value)?;
+    let mut writable_terms = TERMS.write();
+    // Removed first, as `insert` would keep the replaced entry's key, which is freed with it
+    writable_terms.entry_by_key.remove(&Key(key));
+    writable_terms.entry_by_key.insert(Key(entry.key()), entry);
+
+    Ok(())
+}
+
+pub struct Info {
+    pub count: usize,
+    pub words: usize,
+}
+
+// Private
+
+/// A `{Key, Value}` tuple copied into its own heap fragment.
+///
+/// Once the entry has been read, the heap fragment is deliberately never freed, as processes may
+/// still refer to the key or value after it is erased or replaced.
+struct Entry {
+    heap_fragment: NonNull<HeapFragment>,
+    tuple: Boxed<Tuple>,
+    words: usize,
+    read: AtomicBool,
+}
+
+impl Entry {
+    fn new(key: Term, value: Term) -> AllocResult<Self> {
+        let words = Tuple::need_in_words_from_elements(&[key, value]);
+        let mut heap_fragment = HeapFragment::new_from_word_size(words)?;
+        let heap = unsafe { heap_fragment.as_mut() };
+
+        let heap_key = key.clone_to_heap(heap)?;
+        let heap_value = value.clone_to_heap(heap)?;
+        let tuple = heap.tuple_from_slice(&[heap_key, heap_value])?;
+        unsafe { literal::register(heap) };
+
+        Ok(Self {
+            heap_fragment,
+            tuple,
+            words,
+            read: AtomicBool::new(false),
+        })
+    }
+
+    fn key(&self) -> Term {
+        self.tuple[0]
+    }
+
+    fn value(&self) -> Term {
+        self.tuple[1]
+    }
+
+    /// The value, handed out to a process, so the entry can no longer be freed
+    fn read_value(&self) -> Term {
+        self.read.store(true, Ordering::SeqCst);
+
+        self.value()
+    }
+}
+
+impl Drop for Entry {
+    fn drop(&mut self) {
+        // Entries are only dropped once removed from `TERMS`, so no `get` can read them after this
+        if !self.read.load(Ordering::SeqCst) {
+            unsafe {
+                literal::unregister(self.heap_fragment.as_ref());
+                HeapFragment::release_and_free(self.heap_fragment);
+            }
+        }
+    }
+}
+
+/// A key compared with `=:=`, as `persistent_term` does.
+#[derive(Clone, Copy)]
+struct Key(Term);
+
+impl Eq for Key {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.0
+            .decode()
+            .unwrap()
+            .exact_eq(&other.0.decode().unwrap())
+    }
+}
+
+#[derive(Default)]
+struct Terms {
+    entry_by_key: HashMap<Key, Entry>,
+}
+
+// The entries are only inserted or removed while holding the lock and the heap fragments they are
+// stored in are never written to after they are created.
+unsafe impl Send for Terms {}
+unsafe impl Sync for Terms {}
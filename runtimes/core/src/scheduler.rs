@@ -1,3 +1,5 @@
+pub mod idle;
+pub mod park;
 pub mod run_queue;
 
 use std::sync::Arc;
@@ -32,12 +34,28 @@ pub trait Scheduler {
     fn next_reference_number(&self) -> ReferenceNumber;
 }
 
+/// Reschedules `process`, which has stopped waiting, on the scheduler it belongs to.
+///
+/// This is how code in this crate, such as `enif_send`, wakes a process it sent a message to.
+/// The scheduler is implemented by the runtime, which exports `__scheduler_stop_waiting`, so
+/// callers share this one declaration of it, rather than each declaring the extern itself.
+pub fn stop_waiting(process: &Process) {
+    unsafe { scheduler_stop_waiting(process) }
+}
+
 pub trait Scheduled {
     type Scheduler;
 
     fn scheduler(&self) -> Option<Arc<Self::Scheduler>>;
 }
 
+// Private
+
+extern "Rust" {
+    #[link_name = "__scheduler_stop_waiting"]
+    fn scheduler_stop_waiting(process: &Process);
+}
+
 /*
 impl Scheduled for Process {
     fn scheduler(&self) -> Option<Arc<Scheduler>> {
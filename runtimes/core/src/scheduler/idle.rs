@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Whether a scheduler has run out of processes to run or steal.
+///
+/// Every `Idle` is counted for as long as it lives, so that `all` can tell when every scheduler
+/// is idle, which is the only time the shared virtual clock may be moved to the next timer.
+#[derive(Debug)]
+pub struct Idle {
+    idle: AtomicBool,
+}
+
+impl Idle {
+    /// Marks the scheduler idle, once it has found nothing to run or steal
+    pub fn enter(&self) {
+        if !self.idle.swap(true, Ordering::SeqCst) {
+            IDLE_SCHEDULER_COUNT.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    /// Marks the scheduler busy, before it is given a process to run
+    pub fn leave(&self) {
+        if self.idle.swap(false, Ordering::SeqCst) {
+            IDLE_SCHEDULER_COUNT.fetch_sub(1, Ordering::SeqCst);
+        }
+    }
+}
+
+impl Default for Idle {
+    fn default() -> Self {
+        SCHEDULER_COUNT.fetch_add(1, Ordering::SeqCst);
+
+        Self {
+            idle: AtomicBool::new(false),
+        }
+    }
+}
+
+impl Drop for Idle {
+    fn drop(&mut self) {
+        self.leave();
+        SCHEDULER_COUNT.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Whether every scheduler is idle
+pub fn all() -> bool {
+    IDLE_SCHEDULER_COUNT.load(Ordering::SeqCst) == SCHEDULER_COUNT.load(Ordering::SeqCst)
+}
+
+// Private
+
+static SCHEDULER_COUNT: AtomicUsize = AtomicUsize::new(0);
+static IDLE_SCHEDULER_COUNT: AtomicUsize = AtomicUsize::new(0);
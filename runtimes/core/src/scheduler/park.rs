@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use liblumen_core::locks::{Condvar, Mutex};
+
+/// Lets an idle scheduler thread sleep until another thread gives it work, instead of spinning
+/// on empty run queues.
+#[derive(Debug, Default)]
+pub struct Parker {
+    state: Mutex<State>,
+    condvar: Condvar,
+}
+
+impl Parker {
+    pub fn is_parked(&self) -> bool {
+        self.state.lock().parked
+    }
+
+    /// Blocks the current thread until `unpark` is called or `timeout` elapses.  If `unpark` was
+    /// called since the last park, returns immediately, so that a wake up sent while the scheduler
+    /// was still looking for work is not lost.
+    ///
+    /// Returns `true` if woken by `unpark`; otherwise, `false` when the `timeout` elapsed.
+    pub fn park_timeout(&self, timeout: Duration) -> bool {
+        let mut state = self.state.lock();
+
+        if !state.notified {
+            state.parked = true;
+            self.condvar.wait_for(&mut state, timeout);
+            state.parked = false;
+        }
+
+        let notified = state.notified;
+        state.notified = false;
+
+        notified
+    }
+
+    /// Wakes the parked thread or, if the thread is not parked, makes its next `park_timeout`
+    /// return immediately.
+    ///
+    /// Returns `true` if the thread was parked.
+    pub fn unpark(&self) -> bool {
+        let mut state = self.state.lock();
+        state.notified = true;
+        let parked = state.parked;
+        drop(state);
+
+        if parked {
+            self.condvar.notify_one();
+        }
+
+        parked
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    parked: bool,
+    notified: bool,
+}
@@ -45,7 +45,7 @@ impl Queues {
     }
 
     pub fn enqueue(&mut self, arc_process: Arc<Process>) {
-        match arc_process.priority {
+        match arc_process.priority() {
             Priority::Low | Priority::Normal => self.normal_low.enqueue(arc_process),
             Priority::High => self.high.enqueue(arc_process),
             Priority::Max => self.max.enqueue(arc_process),
@@ -75,6 +75,30 @@ impl Queues {
         }
     }
 
+    /// The number of processes in the run queues that another scheduler is allowed to steal.
+    pub fn stealable_len(&self) -> usize {
+        self.normal_low.len() + self.high.len()
+    }
+
+    /// Removes half of the processes from each run queue whose priority is allowed to be stolen,
+    /// so that an idle scheduler can run them instead.
+    ///
+    /// `Priority::Max` processes are never stolen: they stay on the scheduler that spawned them so
+    /// that they do not wait behind a migration.  Waiting processes are not runnable, so they are
+    /// left in place for `stop_waiting` to find on this scheduler.
+    ///
+    /// The stolen processes still have this scheduler's ID, so the thief must call
+    /// `Process::schedule_with` before enqueuing them.
+    #[must_use]
+    pub fn steal(&mut self) -> Vec<Arc<Process>> {
+        let mut stolen = Vec::with_capacity(self.stealable_len() / 2);
+
+        self.high.steal_into(&mut stolen);
+        self.normal_low.steal_into(&mut stolen);
+
+        stolen
+    }
+
     pub fn stop_waiting(&mut self, process: &Process) {
         match self.waiting.get(process) {
             Some(arc_process) => {
@@ -174,6 +198,14 @@ impl Immediate {
     pub fn enqueue(&mut self, process: Arc<Process>) {
         self.0.push_back(process);
     }
+
+    /// Moves the back half of the run queue to `stolen`.  The back is stolen, so that the
+    /// processes that have waited the longest still run next on this scheduler.
+    fn steal_into(&mut self, stolen: &mut Vec<Arc<Process>>) {
+        let len = self.0.len();
+
+        stolen.extend(self.0.drain((len - len / 2)..));
+    }
 }
 
 /// A run queue where the `Arc<Process` is run only when its delay is `0`.  This allows
@@ -213,6 +245,18 @@ impl Delayed {
         let delayed_process = DelayedProcess::new(arc_process);
         self.0.push_back(delayed_process);
     }
+
+    /// Moves the back half of the run queue to `stolen`.  The remaining delay is dropped, as
+    /// `enqueue` on the stealing scheduler's `Delayed` will start the delay over.
+    fn steal_into(&mut self, stolen: &mut Vec<Arc<Process>>) {
+        let len = self.0.len();
+
+        stolen.extend(
+            self.0
+                .drain((len - len / 2)..)
+                .map(|delayed_process| delayed_process.arc_process),
+        );
+    }
 }
 
 type Delay = u8;
@@ -226,7 +270,7 @@ struct DelayedProcess {
 impl DelayedProcess {
     fn new(arc_process: Arc<Process>) -> DelayedProcess {
         DelayedProcess {
-            delay: Self::priority_to_delay(arc_process.priority),
+            delay: Self::priority_to_delay(arc_process.priority()),
             arc_process,
         }
     }
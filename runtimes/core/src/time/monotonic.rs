@@ -1,3 +1,6 @@
+use core::cell::Cell;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
 use num_bigint::BigInt;
 
 use crate::time::{convert_milliseconds, Milliseconds, Unit};
@@ -5,14 +8,117 @@ use crate::time::{convert_milliseconds, Milliseconds, Unit};
 cfg_if::cfg_if! {
   if #[cfg(all(target_arch = "wasm32", feature = "time_web_sys"))] {
      mod web_sys;
-     pub use self::web_sys::*;
+     use self::web_sys::real_time_in_milliseconds;
   } else {
      mod std;
-     pub use self::std::*;
+     use self::std::real_time_in_milliseconds;
   }
 }
 
+/// The clock monotonic time, and so the timeouts of timers, are read from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Clock {
+    /// The monotonic clock of the system
+    Real,
+    /// A clock which only moves when it is set or advanced, so that timers time out at the same
+    /// point of a program every time it is run, however long it takes to run
+    ///
+    /// Set with `set_clock`, the virtual clock is shared by every thread and only moves forward,
+    /// so a process sees the same time whichever scheduler it runs on, as do the threads that
+    /// help the schedulers.  A thread that calls `start_virtual_clock` first, such as a test, has
+    /// a virtual clock of its own instead.
+    Virtual,
+}
+
+/// Sets the clock of every thread that has not started its own virtual clock
+///
+/// The shared virtual clock starts at the time the clock is first set to `Clock::Virtual`.
+pub fn set_clock(clock: Clock) {
+    match clock {
+        Clock::Real => VIRTUAL.store(false, Ordering::SeqCst),
+        Clock::Virtual => {
+            VIRTUAL_TIME.fetch_max(real_time_in_milliseconds(), Ordering::SeqCst);
+            VIRTUAL.store(true, Ordering::SeqCst);
+        }
+    }
+}
+
+/// The clock of the current thread
+pub fn clock() -> Clock {
+    match virtual_time_in_milliseconds() {
+        Some(_) => Clock::Virtual,
+        None => Clock::Real,
+    }
+}
+
+/// Whether the current thread has a virtual clock of its own from `start_virtual_clock`, instead
+/// of the shared or real clock
+pub fn has_thread_virtual_clock() -> bool {
+    THREAD_VIRTUAL_TIME.with(|virtual_time| virtual_time.get().is_some())
+}
+
 pub fn time(unit: Unit) -> BigInt {
     let milliseconds = time_in_milliseconds();
     convert_milliseconds(milliseconds, unit)
 }
+
+pub fn time_in_milliseconds() -> Milliseconds {
+    virtual_time_in_milliseconds().unwrap_or_else(real_time_in_milliseconds)
+}
+
+/// Switches the current thread to a virtual clock of its own, which starts at the current time,
+/// and returns that time
+///
+/// If the thread already uses a virtual clock, including the shared one, it is left where it is.
+pub fn start_virtual_clock() -> Milliseconds {
+    match virtual_time_in_milliseconds() {
+        Some(milliseconds) => milliseconds,
+        None => {
+            let milliseconds = real_time_in_milliseconds();
+            THREAD_VIRTUAL_TIME.with(|virtual_time| virtual_time.set(Some(milliseconds)));
+
+            milliseconds
+        }
+    }
+}
+
+/// Moves the virtual clock of the current thread to `milliseconds`, starting it if needed
+///
+/// A virtual clock never moves backwards, so it is left where it is if it is already past
+/// `milliseconds`.  Timers are not timed out until their scheduler next checks them, e.g. in
+/// `Hierarchy::timeout`.
+pub fn set_virtual_time_in_milliseconds(milliseconds: Milliseconds) {
+    THREAD_VIRTUAL_TIME.with(|virtual_time| match virtual_time.get() {
+        Some(thread_milliseconds) => virtual_time.set(Some(thread_milliseconds.max(milliseconds))),
+        None if VIRTUAL.load(Ordering::SeqCst) => {
+            VIRTUAL_TIME.fetch_max(milliseconds, Ordering::SeqCst);
+        }
+        None => virtual_time.set(Some(milliseconds)),
+    })
+}
+
+/// Moves the virtual clock of the current thread forward by `milliseconds`, starting it if
+/// needed, and returns the new time
+pub fn advance_virtual_time_in_milliseconds(milliseconds: Milliseconds) -> Milliseconds {
+    let advanced = start_virtual_clock() + milliseconds;
+    set_virtual_time_in_milliseconds(advanced);
+
+    advanced
+}
+
+// Private
+
+static VIRTUAL: AtomicBool = AtomicBool::new(false);
+/// The virtual clock shared by the threads that have not started their own
+static VIRTUAL_TIME: AtomicU64 = AtomicU64::new(0);
+
+thread_local! {
+    static THREAD_VIRTUAL_TIME: Cell<Option<Milliseconds>> = Cell::new(None);
+}
+
+fn virtual_time_in_milliseconds() -> Option<Milliseconds> {
+    THREAD_VIRTUAL_TIME.with(|virtual_time| match virtual_time.get() {
+        None if VIRTUAL.load(Ordering::SeqCst) => Some(VIRTUAL_TIME.load(Ordering::SeqCst)),
+        option_milliseconds => option_milliseconds,
+    })
+}
@@ -1,6 +1,6 @@
 use super::Milliseconds;
 
-pub fn time_in_milliseconds() -> Milliseconds {
+pub fn real_time_in_milliseconds() -> Milliseconds {
     let window = web_sys::window().expect("should have a window in this context");
     let performance = window
         .performance()
@@ -12,7 +12,9 @@ use std::vec::Drain;
 
 use hashbrown::HashMap;
 
-use liblumen_core::locks::Mutex;
+use lazy_static::lazy_static;
+
+use liblumen_core::locks::{Mutex, RwLock};
 
 use liblumen_alloc::borrow::CloneToProcess;
 use liblumen_alloc::erts::exception::AllocResult;
@@ -21,7 +23,12 @@ use liblumen_alloc::erts::Process;
 
 use crate::registry;
 use crate::scheduler::{self, Scheduled, Scheduler};
-use crate::time::{monotonic, Milliseconds};
+use crate::time::monotonic::{self, Clock};
+use crate::time::Milliseconds;
+
+lazy_static! {
+    static ref SKIPPING: Mutex<()> = Mutex::new(());
+}
 
 #[derive(Debug)]
 pub struct Message {
@@ -42,6 +49,56 @@ pub enum Destination {
     Process(Weak<Process>),
 }
 
+/// When the virtual clock is used, moves it to when the next timer of any scheduler is due and
+/// times out every timer due by then, returning whether any timers were timed out.
+///
+/// Schedulers call this when they have no processes to run or steal, as waiting on a virtual
+/// clock would never end.  `current` is the hierarchy of the calling scheduler and `others` those
+/// of every other scheduler.  The shared virtual clock is only moved once
+/// `scheduler::idle::all()`, so that no process still running sees later timers time out first,
+/// and then only to the earliest timer of any scheduler.  A thread with a virtual clock of its
+/// own, such as a test, only moves it to the next timer of `current`.  With the real clock,
+/// nothing is done and `false` is returned.
+pub fn skip_to_next_timeout(current: &RwLock<Hierarchy>, others: &[&RwLock<Hierarchy>]) -> bool {
+    if monotonic::clock() != Clock::Virtual {
+        return false;
+    }
+
+    if monotonic::has_thread_virtual_clock() {
+        return current.write().skip_to_next_timeout();
+    }
+
+    // Only one idle scheduler moves the clock at a time, so that another can't move it past the
+    // timers this one times out before the processes they wake have run.
+    let _skipping = SKIPPING.lock();
+
+    if !scheduler::idle::all() {
+        return false;
+    }
+
+    let hierarchies = || core::iter::once(current).chain(others.iter().copied());
+    let next_timeout_monotonic_time_milliseconds = hierarchies()
+        .filter_map(|hierarchy| hierarchy.read().next_timeout_monotonic_time_milliseconds())
+        .min();
+
+    match next_timeout_monotonic_time_milliseconds {
+        Some(next_timeout_monotonic_time_milliseconds) => {
+            // The slot a timer is in is only timed out after the wheel has moved past it
+            monotonic::set_virtual_time_in_milliseconds(
+                next_timeout_monotonic_time_milliseconds + 1,
+            );
+
+            // Timing out wakes the waiting processes, which marks their schedulers busy
+            for hierarchy in hierarchies() {
+                hierarchy.write().timeout();
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
 pub struct Hierarchy {
     at_once: Slot,
     soon: Wheel,
@@ -76,6 +133,20 @@ impl Hierarchy {
             })
     }
 
+    /// Whether there are no timers left to time out
+    pub fn is_empty(&self) -> bool {
+        self.timer_by_reference_number.is_empty()
+    }
+
+    /// The monotonic time the next timer to time out is due at, if there are any timers
+    pub fn next_timeout_monotonic_time_milliseconds(&self) -> Option<Milliseconds> {
+        self.timer_by_reference_number
+            .values()
+            .filter_map(|weak_timer| weak_timer.upgrade())
+            .map(|arc_timer| arc_timer.monotonic_time_milliseconds)
+            .min()
+    }
+
     fn position(&self, monotonic_time_milliseconds: Milliseconds) -> Position {
         if monotonic_time_milliseconds < self.soon.slot_monotonic_time_milliseconds {
             Position::AtOnce
@@ -152,6 +223,29 @@ impl Hierarchy {
         Ok(process_reference)
     }
 
+    /// Moves the virtual clock of the current thread to when the next timer of this hierarchy is
+    /// due and times out every timer due by then, returning whether any timers were timed out.
+    ///
+    /// Only the free `skip_to_next_timeout` calls this, for a thread with a virtual clock of its
+    /// own, as the shared one must wait for the timers of every scheduler.
+    fn skip_to_next_timeout(&mut self) -> bool {
+        match self.next_timeout_monotonic_time_milliseconds() {
+            Some(next_timeout_monotonic_time_milliseconds) => {
+                // The slot a timer is in is only timed out after the wheel has moved past it
+                let due_monotonic_time_milliseconds = next_timeout_monotonic_time_milliseconds + 1;
+
+                if monotonic::time_in_milliseconds() < due_monotonic_time_milliseconds {
+                    monotonic::set_virtual_time_in_milliseconds(due_monotonic_time_milliseconds);
+                }
+
+                self.timeout();
+
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn timeout(&mut self) {
         self.timeout_at_once();
 
@@ -368,6 +462,10 @@ impl Timer {
             } = self.message_heap.into_inner();
 
             destination_arc_process.send_heap_message(heap_fragment, term);
+
+            if destination_arc_process.stop_waiting() {
+                scheduler::stop_waiting(&destination_arc_process);
+            }
         }
     }
 }
@@ -1,7 +1,10 @@
 #![feature(main)]
+#![feature(linkage)]
 #![feature(termination_trait_lib)]
 
 mod atoms;
+mod locations;
+mod nifs;
 mod symbols;
 
 extern "C" {
@@ -19,7 +22,10 @@ extern "C" {
     fn lang_start(main: &dyn Fn() -> i32, argc: isize, argv: *const *const i8) -> isize;
 }
 
+/// This is weak, so that a C program which links a Lumen static library can provide its own
+/// `main`, and start the runtime with `lumen_start` instead
 #[no_mangle]
+#[linkage = "weak"]
 pub extern "C" fn main(argc: i32, argv: *const *const std::os::raw::c_char) -> i32 {
     unsafe { lang_start(&move || main_internal(), argc as isize, argv) as i32 }
 }
@@ -32,7 +38,24 @@ pub extern "C" fn main(argc: i32, argv: *const *const std::os::raw::c_char) -> i
 /// up the schedulers and other high-level runtime functionality.
 #[main]
 pub fn main_internal() -> i32 {
+    let status = init();
+    if status != 0 {
+        return status;
+    }
+
+    // Invoke platform-specific entry point
+    unsafe { lumen_entry() }
+}
+
+/// Initializes the atom, dispatch and location tables from the data generated by the compiler,
+/// then loads the linked NIF libraries
+///
+/// Returns zero on success, otherwise the status code the program should exit with. This is
+/// also used to start the runtime when it is embedded in a library, where there is no `main`.
+pub fn init() -> i32 {
     use crate::atoms::*;
+    use crate::locations::*;
+    use crate::nifs::*;
     use crate::symbols::*;
 
     // Initialize atom table
@@ -45,6 +68,15 @@ pub fn main_internal() -> i32 {
         return 103;
     }
 
-    // Invoke platform-specific entry point
-    unsafe { lumen_entry() }
+    // Initialize the location table, used to give the source location of stacktrace frames
+    if unsafe { InitializeLumenLocationTable(LOCATION_TABLE, NUM_LOCATIONS) } == false {
+        return 105;
+    }
+
+    // Load the NIF libraries, which may make atoms in their `load` callbacks
+    if unsafe { InitializeLumenNifTable(NIF_TABLE, NUM_NIFS) } == false {
+        return 104;
+    }
+
+    0
 }
@@ -0,0 +1,20 @@
+use liblumen_core::symbols::FunctionLocation;
+
+extern "C" {
+    /// This symbol is defined in the compiled executable,
+    /// and specifies the number of entries in the location table.
+    #[link_name = "__LUMEN_LOCATION_TABLE_SIZE"]
+    pub static NUM_LOCATIONS: usize;
+
+    /// This symbol is defined in the compiled executable,
+    /// and provides a pointer to the location table, which has an
+    /// entry with the source file and line of each compiled function.
+    #[link_name = "__LUMEN_LOCATION_TABLE"]
+    pub static LOCATION_TABLE: *const FunctionLocation;
+}
+
+#[link(name = "liblumen_alloc")]
+extern "C" {
+    /// This function is defined in `liblumen_alloc::erts::apply`
+    pub fn InitializeLumenLocationTable(table: *const FunctionLocation, len: usize) -> bool;
+}
@@ -0,0 +1,25 @@
+use std::ffi::c_void;
+
+/// The `module_nif_init` function defined by `ERL_NIF_INIT` in a linked NIF library
+pub type NifInit = extern "C" fn() -> *const c_void;
+
+extern "C" {
+    /// This symbol is defined in the compiled executable,
+    /// and specifies the number of entries in the NIF table.
+    #[link_name = "__LUMEN_NIF_TABLE_SIZE"]
+    pub static NUM_NIFS: usize;
+
+    /// This symbol is defined in the compiled executable,
+    /// and provides a pointer to the NIF table, which has an
+    /// entry for each compiled module. The entry is the init
+    /// function of the NIF library for that module, or null
+    /// if no linked library defines one.
+    #[link_name = "__LUMEN_NIF_TABLE"]
+    pub static NIF_TABLE: *const Option<NifInit>;
+}
+
+#[link(name = "lumen_rt_core")]
+extern "C" {
+    /// This function is defined in `lumen_rt_core::nif`
+    pub fn InitializeLumenNifTable(table: *const Option<NifInit>, len: usize) -> bool;
+}
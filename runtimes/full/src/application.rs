@@ -0,0 +1,140 @@
+//! The application environment, as set by `sys.config` and read by `application:get_env/2,3`.
+use std::ptr::{self, NonNull};
+
+use hashbrown::HashMap;
+use thiserror::Error;
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, HeapFragment};
+
+use crate::term::consult::Value;
+
+/// Returns a copy of the `key` value of `application` on the heap of `process`.
+pub fn get_env(process: &Process, application: Atom, key: Atom) -> Option<Term> {
+    ENV.read()
+        .get(application, key)
+        .map(|value| value.clone_to_process(process))
+}
+
+/// Sets the `key` value of `application` to a copy of `value`, like `application:set_env/3`.
+pub fn set_env(application: Atom, key: Atom, value: Term) -> AllocResult<()> {
+    ENV.write().set(application, key, value)
+}
+
+/// Adds the values from `env` to the application environment, replacing any values already set
+/// for the same application and key.
+pub fn merge_env(env: Env) {
+    ENV.write().merge(env)
+}
+
+#[derive(Default)]
+pub struct Env {
+    value_by_key_by_application: HashMap<Atom, HashMap<Atom, Stored>>,
+}
+
+impl Env {
+    pub fn get(&self, application: Atom, key: Atom) -> Option<Term> {
+        self.value_by_key_by_application
+            .get(&application)
+            .and_then(|value_by_key| value_by_key.get(&key))
+            .map(|stored| stored.term)
+    }
+
+    pub fn set(&mut self, application: Atom, key: Atom, value: Term) -> AllocResult<()> {
+        let stored = Stored::new(&value)?;
+        self.insert(application, key, stored);
+
+        Ok(())
+    }
+
+    /// Sets the keys from a `{Application, [{Key, Value}]}` entry of a `sys.config`.
+    pub fn set_from_value(&mut self, entry: &Value) -> Result<(), EnvError> {
+        let (application, pairs) = match entry.as_tuple() {
+            Some([application, pairs]) => match (application.as_atom(), pairs.as_proper_list()) {
+                (Some(application), Some(pairs)) => (application, pairs),
+                _ => return Err(EnvError::Application(entry.clone())),
+            },
+            _ => return Err(EnvError::Application(entry.clone())),
+        };
+
+        for pair in pairs {
+            match pair.as_tuple() {
+                Some([key, value]) => match key.as_atom() {
+                    Some(key) => {
+                        let stored = Stored::new(value).map_err(|_| EnvError::Alloc)?;
+                        self.insert(application, key, stored);
+                    }
+                    None => return Err(EnvError::Pair(pair.clone())),
+                },
+                _ => return Err(EnvError::Pair(pair.clone())),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Moves the values from `other` into `self`, replacing any values already set for the same
+    /// application and key.
+    pub fn merge(&mut self, mut other: Env) {
+        for (application, value_by_key) in other.value_by_key_by_application.drain() {
+            self.value_by_key_by_application
+                .entry(application)
+                .or_default()
+                .extend(value_by_key);
+        }
+    }
+
+    /// Any value replaced is dropped, which frees its heap fragment.
+    fn insert(&mut self, application: Atom, key: Atom, stored: Stored) {
+        self.value_by_key_by_application
+            .entry(application)
+            .or_default()
+            .insert(key, stored);
+    }
+}
+
+/// A value of the environment in the heap fragment that owns its memory
+struct Stored {
+    term: Term,
+    heap_fragment: NonNull<HeapFragment>,
+}
+
+impl Stored {
+    fn new<T: CloneToProcess>(value: &T) -> AllocResult<Self> {
+        let (term, heap_fragment) = value.clone_to_fragment()?;
+
+        Ok(Self {
+            term,
+            heap_fragment,
+        })
+    }
+}
+
+impl Drop for Stored {
+    fn drop(&mut self) {
+        unsafe { ptr::drop_in_place(self.heap_fragment.as_ptr()) };
+    }
+}
+
+// The `Term`s are only read or replaced while holding `ENV`'s lock and the heap fragments they are
+// stored in are never shared with a process.
+unsafe impl Send for Stored {}
+unsafe impl Sync for Stored {}
+
+#[derive(Debug, Error)]
+pub enum EnvError {
+    #[error("application entry ({0:?}) is not {{Application, [{{Key, Value}}]}}")]
+    Application(Value),
+    #[error("application environment entry ({0:?}) is not {{Key, Value}}")]
+    Pair(Value),
+    #[error("not enough memory for the application environment")]
+    Alloc,
+}
+
+lazy_static! {
+    static ref ENV: RwLock<Env> = Default::default();
+}
@@ -2,18 +2,51 @@ use std::convert::{TryFrom, TryInto};
 
 use anyhow::*;
 
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::Process;
 use liblumen_alloc::erts::term::prelude::*;
 
 use lumen_rt_core::proplist::TryPropListFromTermError;
 
+use crate::distribution::external_term_format::{term, version};
+
+/// Decodes `bytes`, which start with the External Term Format version number, returning the term
+/// and how many bytes of `bytes` it used.
+///
+/// Bytes from untrusted sources should be decoded with `Options::safe()`, so that they can't
+/// exhaust the atom table.
+pub fn bytes_to_term(
+    process: &Process,
+    options: &Options,
+    bytes: &[u8],
+) -> InternalResult<(Term, usize)> {
+    let after_version_bytes = version::check(bytes)?;
+    let (term, after_term_bytes) =
+        term::decode_top_level(process, options.existing, after_version_bytes)?;
+    let used_byte_len = bytes.len() - after_term_bytes.len();
+
+    Ok((term, used_byte_len))
+}
+
 pub struct Options {
+    /// `safe`: only decode atoms and exports that already exist, as neither are garbage
+    /// collected.
     pub existing: bool,
+    /// `used`: return `{Term, BytesUsed}` instead of `Term`.
     pub used: bool,
 }
 
 const SUPPORTED_OPTIONS_CONTEXT: &str = "supported optons are safe and used";
 
 impl Options {
+    /// The options for `binary_to_term(Binary, [safe])`.
+    pub fn safe() -> Self {
+        Self {
+            existing: true,
+            used: false,
+        }
+    }
+
     fn put_option_term(&mut self, option: Term) -> anyhow::Result<&Options> {
         let atom: Atom = option.try_into().context(SUPPORTED_OPTIONS_CONTEXT)?;
 
@@ -0,0 +1,85 @@
+//! Runs the instructions of a `.script` boot file, the way `init` does for a release.
+use std::sync::Arc;
+
+use anyhow::*;
+use log::info;
+
+use liblumen_alloc::erts::process::{Process, Status};
+use liblumen_alloc::{atom, CloneToProcess};
+
+use crate::config::{BootInstruction, BootScript};
+use crate::scheduler::{Scheduler, Spawned};
+use crate::term::consult::Value;
+use crate::timer;
+
+/// Runs each `apply` of `boot_script` from the init process, in order.
+///
+/// Like `init:eval_script`, each apply runs to completion before the next one starts, so that,
+/// for example, an application is only started once the applications it depends on are.  Each is
+/// run in its own process, while the scheduler keeps running the processes started by earlier
+/// applies.  An apply that exits abnormally stops the boot.
+pub fn run(scheduler: Arc<Scheduler>, boot_script: BootScript) -> Result<()> {
+    let init_arc_process = scheduler
+        .spawn_init(0)
+        .map_err(|err| anyhow!("could not spawn init: {}", err))?;
+
+    for instruction in boot_script {
+        match instruction {
+            BootInstruction::Progress(name) => info!("boot progress: {}", name),
+            BootInstruction::Apply {
+                module,
+                function,
+                arguments,
+            } => {
+                info!("boot apply: {}:{}/{}", module, function, arity(&arguments));
+
+                let arguments_term = arguments.clone_to_process(&init_arc_process);
+
+                let Spawned { arc_process, .. } = Scheduler::spawn_apply_3(
+                    &init_arc_process,
+                    Default::default(),
+                    module,
+                    function,
+                    arguments_term,
+                )
+                .map_err(|err| anyhow!("could not spawn apply: {}", err))?;
+
+                run_to_exit(&scheduler, &arc_process);
+
+                if let Status::Exiting(ref exception) = *arc_process.status.read() {
+                    match exception.reason() {
+                        Some(reason) if reason == atom!("normal") => (),
+                        // The reason is formatted while `arc_process` still owns it
+                        reason => bail!(
+                            "{}:{}/{} exited with {}",
+                            module,
+                            function,
+                            arity(&arguments),
+                            reason.map_or_else(
+                                || "system_error".to_string(),
+                                |reason| reason.to_string()
+                            )
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the scheduler until `arc_process` exits, which also runs any other processes.
+fn run_to_exit(scheduler: &Scheduler, arc_process: &Process) {
+    while !arc_process.is_exiting() {
+        if !scheduler.run_once() && !scheduler.steal() && !timer::skip_to_next_timeout() {
+            scheduler.park();
+        }
+    }
+}
+
+fn arity(arguments: &Value) -> usize {
+    arguments
+        .as_proper_list()
+        .map_or(0, |arguments| arguments.len())
+}
@@ -7,6 +7,30 @@ use liblumen_alloc::erts::term::closure::{Index, OldUnique, Unique};
 use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::erts::Arity;
 
+/// Finds the code for an anonymous function when only the `old_unique` is known, such as for a
+/// `FUN_EXT` external term, which has neither `unique` nor `arity`.
+pub fn find(module: &Atom, index: &Index, old_unique: &OldUnique) -> Option<(Unique, Arity, Code)> {
+    RW_LOCK_CODE_BY_ARITY_BY_UNIQUE_BY_OLD_UNIQUE_BY_INDEX_BY_MODULE
+        .read()
+        .get(module)
+        .and_then(|code_by_arity_by_unique_by_old_unique_by_index| {
+            code_by_arity_by_unique_by_old_unique_by_index.get(index)
+        })
+        .and_then(|code_by_arity_by_unique_by_old_unique| {
+            code_by_arity_by_unique_by_old_unique.get(old_unique)
+        })
+        .and_then(|code_by_arity_by_unique| {
+            code_by_arity_by_unique
+                .iter()
+                .flat_map(|(unique, code_by_arity)| {
+                    code_by_arity
+                        .iter()
+                        .map(move |(arity, code)| (*unique, *arity, *code))
+                })
+                .next()
+        })
+}
+
 pub fn get(
     module: &Atom,
     index: &Index,
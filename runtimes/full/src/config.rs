@@ -1,17 +1,35 @@
-use std::collections::HashMap;
 use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::io;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use clap::{App, AppSettings, Arg, SubCommand};
+use rand::Rng;
+
+use liblumen_alloc::erts::term::prelude::Atom;
+
+use lumen_rt_core::time::monotonic::Clock;
+
+use crate::application::{Env, EnvError};
+use crate::term::consult::{self, ConsultError, Value};
 
 pub type ConfigResult<T> = std::result::Result<T, ConfigError>;
-//TODO: Needs to be HashMap<Atom, HashMap<Atom, Term>>
-pub type AppConfig = HashMap<String, HashMap<String, String>>;
+pub type AppConfig = Env;
 pub type BootScript = Vec<BootInstruction>;
-//TODO: Needs to be Term
-pub type BootInstruction = String;
+
+/// The instructions of a `.script` boot file that still apply when all code is compiled into the
+/// executable.  Code loading instructions, such as `path` and `primLoad`, are skipped.
+#[derive(Debug)]
+pub enum BootInstruction {
+    /// `{progress, Name}` reports how far booting has come
+    Progress(Atom),
+    /// `{apply, {Module, Function, Arguments}}` calls `apply(Module, Function, Arguments)`
+    Apply {
+        module: Atom,
+        function: Atom,
+        arguments: Value,
+    },
+}
 
 pub enum Command {
     Run,
@@ -22,6 +40,10 @@ pub enum Command {
 #[derive(Debug)]
 pub enum ConfigError {
     FileError(OsString, io::Error),
+    ParseError(OsString, ConsultError),
+    EnvError(OsString, EnvError),
+    /// The file was parsed, but its terms are not the expected shape
+    FormatError(OsString, String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -33,6 +55,15 @@ impl std::fmt::Display for ConfigError {
                 path.to_string_lossy(),
                 err.to_string()
             ),
+            ConfigError::ParseError(ref path, ref err) => {
+                write!(f, "Failed to parse {}: {}", path.to_string_lossy(), err)
+            }
+            ConfigError::EnvError(ref path, ref err) => {
+                write!(f, "Invalid config in {}: {}", path.to_string_lossy(), err)
+            }
+            ConfigError::FormatError(ref path, ref message) => {
+                write!(f, "Invalid {}: {}", path.to_string_lossy(), message)
+            }
         }
     }
 }
@@ -41,6 +72,9 @@ impl std::error::Error for ConfigError {
     fn cause(&self) -> Option<&dyn std::error::Error> {
         match *self {
             ConfigError::FileError(ref _path, ref err) => Some(err),
+            ConfigError::ParseError(ref _path, ref err) => Some(err),
+            ConfigError::EnvError(ref _path, ref err) => Some(err),
+            ConfigError::FormatError(..) => None,
         }
     }
 }
@@ -51,6 +85,9 @@ pub struct Config {
     pub debug: bool,
     pub name: Option<String>,
     pub cookie: Option<String>,
+    pub clock: Clock,
+    /// The number of scheduler threads, including the main thread
+    pub schedulers: usize,
     pub command: Command,
     pub extra: Vec<String>,
 }
@@ -82,23 +119,40 @@ impl Config {
             .arg(Arg::with_name("name")
                      .long("name")
                      .global(true)
-                     .help("The secret cookie to use in distributed mode\n\
-                            If one is not provided, one will be generated for you in ~/.erlang.cookie")
+                     .help("The name of this node in distributed mode, as name@host\n\
+                            If no host is given, localhost is used")
                      .takes_value(true)
                      .validator(is_valid_node_name))
             .arg(Arg::with_name("cookie")
                      .long("cookie")
                      .global(true)
-                     .help("The secret cookie to use in distributed mode")
+                     .help("The secret cookie to use in distributed mode\n\
+                            If one is not provided, one will be generated for you in ~/.erlang.cookie")
                      .takes_value(true)
                      .env("COOKIE"))
+            .arg(Arg::with_name("time")
+                     .long("time")
+                     .help("The clock timers are run by\n\
+                            With virtual, time only moves forward when every process is waiting, \
+                            straight to when the next timer is due")
+                     .takes_value(true)
+                     .possible_values(&["real", "virtual"])
+                     .default_value("real")
+                     .env("LUMEN_TIME"))
+            .arg(Arg::with_name("schedulers")
+                     .long("schedulers")
+                     .help("The number of scheduler threads that run processes\n\
+                            Defaults to the number of logical CPUs")
+                     .takes_value(true)
+                     .validator(is_valid_scheduler_count)
+                     .env("LUMEN_SCHEDULERS"))
             .arg(Arg::with_name("extra")
                      .last(true)
                      .multiple(true)
                      .hidden(true))
             .subcommand(
                 SubCommand::with_name("shell")
-                    .about("Starts a new interactive shell, but does not start the system")
+                    .about("Starts a new interactive shell for evaluating expressions")
                     .arg(Arg::with_name("remote")
                             .long("remote")
                             .help("Connects a remote shell to the specified host")
@@ -122,20 +176,75 @@ impl Config {
             };
             command = Command::Run;
         }
+        let mut config = AppConfig::default();
+        if let Some(paths) = matches.values_of_os("config") {
+            for path in paths {
+                config.merge(load_app_config(&config_path(path))?);
+            }
+        }
+
         Ok(Config {
-            config: with_file(
-                matches.value_of_os("config"),
-                AppConfig::new(),
-                load_app_config,
-            )?,
+            config,
             boot: with_file(matches.value_of_os("boot"), None, load_boot_script)?,
             debug: matches.is_present("debug"),
             name: matches.value_of("name").map(|v| v.to_string()),
             cookie: matches.value_of("cookie").map(|v| v.to_string()),
+            clock: match matches.value_of("time") {
+                Some("virtual") => Clock::Virtual,
+                _ => Clock::Real,
+            },
+            schedulers: match matches.value_of("schedulers") {
+                // Already checked by `is_valid_scheduler_count`
+                Some(schedulers) => schedulers.parse().unwrap(),
+                None => num_cpus::get(),
+            },
             command,
             extra: extra.iter().map(|v| v.to_string()).collect(),
         })
     }
+
+    /// The full `name@host` of the node, if it was given a name.
+    pub fn node_name(&self) -> Option<String> {
+        self.name.as_ref().map(|name| {
+            if name.contains('@') {
+                name.clone()
+            } else {
+                format!("{}@localhost", name)
+            }
+        })
+    }
+
+    /// The cookie given with `--cookie`, or else the one in `~/.erlang.cookie`, which is generated
+    /// if it doesn't exist yet.
+    pub fn cookie(&self) -> ConfigResult<String> {
+        match &self.cookie {
+            Some(cookie) => Ok(cookie.clone()),
+            None => {
+                let home = std::env::var_os("HOME").unwrap_or_else(|| ".".into());
+                let path = Path::new(&home).join(".erlang.cookie");
+
+                if path.exists() {
+                    read_to_string(&path).map(|contents| contents.trim().to_string())
+                } else {
+                    let cookie = generate_cookie();
+
+                    fs::write(&path, &cookie)
+                        .map_err(|err| ConfigError::FileError(path.as_os_str().to_owned(), err))?;
+
+                    Ok(cookie)
+                }
+            }
+        }
+    }
+}
+
+/// A cookie of 20 uppercase letters, like the ones `erl` generates.
+fn generate_cookie() -> String {
+    let mut rng = rand::thread_rng();
+
+    (0..20)
+        .map(|_| rng.gen_range(b'A', b'Z' + 1) as char)
+        .collect()
 }
 
 fn is_valid_node_name(_f: String) -> Result<(), String> {
@@ -143,23 +252,146 @@ fn is_valid_node_name(_f: String) -> Result<(), String> {
     Ok(())
 }
 
-fn with_file<T>(v: Option<&OsStr>, default: T, fun: fn(String) -> T) -> ConfigResult<T> {
+fn is_valid_scheduler_count(count: String) -> Result<(), String> {
+    match count.parse::<usize>() {
+        Ok(count) if 0 < count => Ok(()),
+        _ => Err(format!("{} is not a positive number of schedulers", count)),
+    }
+}
+
+fn with_file<T>(
+    v: Option<&OsStr>,
+    default: T,
+    fun: fn(&Path, String) -> ConfigResult<T>,
+) -> ConfigResult<T> {
     match v {
         None => Ok(default),
         Some(p) => {
             let path = Path::new(p);
-            match fs::read_to_string(path) {
-                Err(err) => Err(ConfigError::FileError(p.to_os_string(), err)),
-                Ok(contents) => Ok(fun(contents)),
+            let contents = read_to_string(path)?;
+
+            fun(path, contents)
+        }
+    }
+}
+
+fn read_to_string(path: &Path) -> ConfigResult<String> {
+    fs::read_to_string(path).map_err(|err| ConfigError::FileError(path.as_os_str().to_owned(), err))
+}
+
+fn consult_file(path: &Path, contents: &str) -> ConfigResult<Vec<Value>> {
+    consult::consult(contents)
+        .map_err(|err| ConfigError::ParseError(path.as_os_str().to_owned(), err))
+}
+
+fn format_error(path: &Path, message: &str) -> ConfigError {
+    ConfigError::FormatError(path.as_os_str().to_owned(), message.to_string())
+}
+
+/// Like `erl -config`, the `.config` extension can be left off.
+fn config_path(p: &OsStr) -> PathBuf {
+    let path = PathBuf::from(p);
+
+    if path.extension().is_none() {
+        path.with_extension("config")
+    } else {
+        path
+    }
+}
+
+/// A `sys.config` is a single list, whose elements are either `{Application, [{Key, Value}]}` or
+/// the path of another config file to include.  Later entries replace the values of earlier
+/// entries, so the entries in the file itself override anything from the files it includes.
+fn load_app_config(path: &Path) -> ConfigResult<AppConfig> {
+    let contents = read_to_string(path)?;
+    let values = consult_file(path, &contents)?;
+
+    let entries = match values.as_slice() {
+        [value] => value.as_proper_list(),
+        _ => None,
+    }
+    .ok_or_else(|| format_error(path, "expected a single list of application configs"))?;
+
+    let mut included = AppConfig::default();
+    let mut own = AppConfig::default();
+
+    for entry in entries {
+        match entry.as_string() {
+            Some(include_path) if !include_path.is_empty() => {
+                included.merge(load_app_config(&config_path(include_path.as_ref()))?);
             }
+            _ => own
+                .set_from_value(entry)
+                .map_err(|err| ConfigError::EnvError(path.as_os_str().to_owned(), err))?,
         }
     }
+
+    included.merge(own);
+
+    Ok(included)
 }
 
-fn load_app_config(_contents: String) -> AppConfig {
-    AppConfig::new()
+/// A `.script` is `{script, {Name, Vsn}, [Instruction]}`.
+fn load_boot_script(path: &Path, contents: String) -> ConfigResult<Option<BootScript>> {
+    if path.extension() == Some(OsStr::new("boot")) {
+        return Err(format_error(
+            path,
+            "binary .boot files are not supported, use the .script it was generated from",
+        ));
+    }
+
+    let values = consult_file(path, &contents)?;
+
+    let instructions = match values.as_slice() {
+        [value] => match value.as_tuple() {
+            Some([tag, _name_vsn, instructions]) if tag.as_atom() == Some(atom("script")) => {
+                instructions.as_proper_list()
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+    .ok_or_else(|| format_error(path, "expected {script, {Name, Vsn}, [Instruction]}"))?;
+
+    let mut boot_script = BootScript::new();
+
+    for instruction in instructions {
+        let elements = instruction
+            .as_tuple()
+            .ok_or_else(|| format_error(path, "boot instruction is not a tuple"))?;
+
+        match elements {
+            [tag, name] if tag.as_atom() == Some(atom("progress")) => {
+                let name = name
+                    .as_atom()
+                    .ok_or_else(|| format_error(path, "progress name is not an atom"))?;
+
+                boot_script.push(BootInstruction::Progress(name));
+            }
+            [tag, mfa] if tag.as_atom() == Some(atom("apply")) => {
+                let (module, function, arguments) = match mfa.as_tuple() {
+                    Some([module, function, arguments]) if arguments.as_proper_list().is_some() => {
+                        match (module.as_atom(), function.as_atom()) {
+                            (Some(module), Some(function)) => (module, function, arguments.clone()),
+                            _ => return Err(format_error(path, "apply is not {apply, {M, F, A}}")),
+                        }
+                    }
+                    _ => return Err(format_error(path, "apply is not {apply, {M, F, A}}")),
+                };
+
+                boot_script.push(BootInstruction::Apply {
+                    module,
+                    function,
+                    arguments,
+                });
+            }
+            _ => log::debug!("skipping boot instruction {:?}", instruction),
+        }
+    }
+
+    Ok(Some(boot_script))
 }
 
-fn load_boot_script(_contents: String) -> Option<BootScript> {
-    None
+fn atom(name: &str) -> Atom {
+    Atom::try_from_str(name).unwrap()
 }
@@ -1,2 +1,890 @@
+//! [Distribution](http://erlang.org/doc/apps/erts/erl_dist_protocol.html): connecting to other
+//! nodes to send messages and propagate links and monitors to their processes.
+//!
+//! The local node becomes alive with `start`.  Connections are made on demand, the first time a
+//! process on another node is sent to, linked or monitored, by a thread of their own, so that a
+//! node that can't be reached never blocks the scheduler, and each connection reads control
+//! messages on its own thread.
+pub mod connection;
+pub mod control;
+pub mod epmd;
 pub mod external_term_format;
+pub mod handshake;
+mod link;
+mod monitor;
 pub mod nodes;
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::convert::TryInto;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::*;
+use hashbrown::HashMap;
+
+use liblumen_core::locks::RwLock;
+
+use liblumen_alloc::erts::exception::{AllocResult, RuntimeException};
+use liblumen_alloc::erts::process::alloc::Heap;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Node;
+use liblumen_alloc::{atom, CloneToProcess};
+
+use lumen_rt_core::distribution::nodes::node;
+use lumen_rt_core::registry::{self, pid_to_process};
+
+use crate::process::{self, exit_in_heap_fragment, SchedulerDependentAlloc};
+use crate::send::{self, send_to_process, Sent};
+
+use connection::Connection;
+use control::{Control, Element};
+use epmd::Epmd;
+use handshake::Status;
+use monitor::{Monitor, Monitored, RemoteMonitor};
+
+/// Makes the local node alive as `name`, which must be `alive_name@host`, accepting connections
+/// from nodes with the same `cookie`.
+pub fn start(name: &str, cookie: &str, epmd: Arc<dyn Epmd>) -> Result<()> {
+    let mut option_arc_distribution = RW_LOCK_OPTION_ARC_DISTRIBUTION.write();
+
+    if option_arc_distribution.is_some() {
+        return Err(anyhow!("node is already alive as {}", node::atom()));
+    }
+
+    let (alive_name, _host) = split_name(name)?;
+    let name_atom = Atom::try_from_str(name)?;
+    let listener = TcpListener::bind(("0.0.0.0", 0))?;
+    let port = listener.local_addr()?.port();
+    epmd.register(alive_name, port)?;
+
+    nodes::alive(name_atom);
+
+    *option_arc_distribution = Some(Arc::new(Distribution {
+        cookie: cookie.to_string(),
+        epmd,
+        connection_by_name: Default::default(),
+    }));
+
+    thread::Builder::new()
+        .name("distribution acceptor".to_string())
+        .spawn(move || accept(listener))?;
+
+    Ok(())
+}
+
+/// Whether `start` made the local node alive.
+pub fn is_alive() -> bool {
+    RW_LOCK_OPTION_ARC_DISTRIBUTION.read().is_some()
+}
+
+/// The connection to `node`, connecting to it if there is no connection yet.
+///
+/// A new connection is returned before the handshake with `node` finishes.  If it can't be
+/// finished, the connection is closed and its links and monitors are broken with `noconnection`.
+pub fn connect(node: Atom) -> Result<Arc<Connection>> {
+    let arc_distribution = distribution()?;
+    let (alive_name, host) = split_name(node.name())?;
+    let (alive_name, host) = (alive_name.to_string(), host.to_string());
+
+    let arc_connection = {
+        let mut connection_by_name = arc_distribution.connection_by_name.write();
+
+        if let Some(arc_connection) = connection_by_name.get(&node) {
+            return Ok(arc_connection.clone());
+        }
+
+        let arc_connection = Connection::pending(arc_node(node), 1);
+        connection_by_name.insert(node, arc_connection.clone());
+
+        arc_connection
+    };
+
+    let connector_arc_connection = arc_connection.clone();
+    let spawned = thread::Builder::new()
+        .name(format!("{} connector", node))
+        .spawn(
+            move || match initiate(&arc_distribution, node, &alive_name, &host) {
+                Ok(stream) => {
+                    let _ = connector_arc_connection.established(stream, Scratch::default());
+                }
+                Err(_) => connector_arc_connection.failed(Scratch::default()),
+            },
+        );
+
+    if let Err(error) = spawned {
+        arc_connection.failed(Scratch::default());
+
+        return Err(error.into());
+    }
+
+    Ok(arc_connection)
+}
+
+/// Sends `message` to `destination` on another node.
+///
+/// Like sending to a local process that doesn't exist, messages to nodes that can't be reached
+/// are silently dropped.
+pub fn send(destination: &ExternalPid, message: Term, options: send::Options) -> Sent {
+    send_control(
+        destination.arc_node().name(),
+        options,
+        control::SEND,
+        &[
+            Atom::str_to_term("").into(),
+            Element::ExternalPid(destination),
+        ],
+        Some(message),
+    )
+}
+
+/// Sends `message` to the process registered as `name` on `node`.
+pub fn reg_send(
+    process: &Process,
+    name: Atom,
+    node: Atom,
+    message: Term,
+    options: send::Options,
+) -> Sent {
+    send_control(
+        node,
+        options,
+        control::REG_SEND,
+        &[
+            process.pid_term().into(),
+            Atom::str_to_term("").into(),
+            name.encode().unwrap().into(),
+        ],
+        Some(message),
+    )
+}
+
+/// Links `process` to `external_pid`.  If `external_pid`'s node can't be reached, `process` gets
+/// a `noconnection` exit signal, as if the link was broken.
+pub fn link(process: &Process, external_pid: &ExternalPid) {
+    link::insert(process.pid(), external_pid.clone());
+
+    let result = connect(external_pid.arc_node().name()).and_then(|arc_connection| {
+        arc_connection.send(
+            control::LINK,
+            &[
+                process.pid_term().into(),
+                Element::ExternalPid(external_pid),
+            ],
+            None,
+        )
+    });
+
+    if result.is_err() && link::remove(process.pid(), external_pid) {
+        let from = external_pid.clone_to_process(process);
+
+        let _ = exit_signal(process, process, from, atom!("noconnection"), true);
+    }
+}
+
+pub fn unlink(process: &Process, external_pid: &ExternalPid) {
+    if link::remove(process.pid(), external_pid) {
+        if let Some(arc_connection) = connection(external_pid.arc_node().name()) {
+            let _ = arc_connection.send(
+                control::UNLINK,
+                &[
+                    process.pid_term().into(),
+                    Element::ExternalPid(external_pid),
+                ],
+                None,
+            );
+        }
+    }
+}
+
+/// Monitors `external_pid` from `process`, returning the monitor reference.
+pub fn monitor_pid(process: &Process, external_pid: &ExternalPid) -> AllocResult<Term> {
+    monitor(process, Monitored::Pid(external_pid.clone()))
+}
+
+/// Monitors the process registered as `name` on `node` from `process`, returning the monitor
+/// reference.
+pub fn monitor_name(process: &Process, name: Atom, node: Atom) -> AllocResult<Term> {
+    monitor(
+        process,
+        Monitored::Name {
+            name,
+            arc_node: arc_node(node),
+        },
+    )
+}
+
+/// Removes the monitor `reference` of `process` on a process on another node, returning whether
+/// it was such a monitor.
+pub fn demonitor(process: &Process, reference: &Reference) -> bool {
+    match monitor::remove(reference) {
+        Some(Monitor { monitored, .. }) => {
+            if let Some(arc_connection) = connection(monitored.arc_node().name()) {
+                let _ = arc_connection.send(
+                    control::DEMONITOR_P,
+                    &[
+                        process.pid_term().into(),
+                        monitored.element(),
+                        Element::Reference(node::arc_node(), reference),
+                    ],
+                    None,
+                );
+            }
+
+            true
+        }
+        None => false,
+    }
+}
+
+/// Tells the linked and monitoring processes on other nodes that `process` exited, and removes
+/// the monitors `process` had on processes on other nodes.
+pub fn propagate_exit(process: &Process, exception: &RuntimeException) {
+    let pid = process.pid();
+    let from = process.pid_term();
+    let reason = exception.reason().unwrap_or_else(|| atom!("system_error"));
+
+    for external_pid in link::take(pid) {
+        if let Some(arc_connection) = connection(external_pid.arc_node().name()) {
+            let _ = arc_connection.send(
+                control::EXIT,
+                &[
+                    from.into(),
+                    Element::ExternalPid(&external_pid),
+                    reason.into(),
+                ],
+                None,
+            );
+        }
+    }
+
+    for remote_monitor in monitor::take_remote(pid) {
+        if let Some(arc_connection) = connection(remote_monitor.monitoring.arc_node().name()) {
+            let from_proc = match remote_monitor.monitored_name {
+                Some(name) => name.encode().unwrap(),
+                None => from,
+            };
+
+            let _ = arc_connection.send(
+                control::MONITOR_P_EXIT,
+                &[
+                    from_proc.into(),
+                    Element::ExternalPid(&remote_monitor.monitoring),
+                    Element::Reference(
+                        remote_monitor.reference.arc_node(),
+                        remote_monitor.reference.reference(),
+                    ),
+                    reason.into(),
+                ],
+                None,
+            );
+        }
+    }
+
+    for (reference, Monitor { monitored, .. }) in monitor::take_monitoring(pid) {
+        if let Some(arc_connection) = connection(monitored.arc_node().name()) {
+            let _ = arc_connection.send(
+                control::DEMONITOR_P,
+                &[
+                    from.into(),
+                    monitored.element(),
+                    Element::Reference(node::arc_node(), &reference),
+                ],
+                None,
+            );
+        }
+    }
+}
+
+// Private
+
+struct Distribution {
+    cookie: String,
+    epmd: Arc<dyn Epmd>,
+    connection_by_name: RwLock<HashMap<Atom, Arc<Connection>>>,
+}
+
+impl Distribution {
+    /// The status to reply to `peer_name` with when it connects, and the connection that its
+    /// handshake establishes if it is accepted.
+    fn accepting(&self, peer_name: Atom) -> (Status, Option<Arc<Connection>>) {
+        let mut connection_by_name = self.connection_by_name.write();
+
+        match connection_by_name.get(&peer_name) {
+            None => {
+                let arc_connection = Connection::pending(arc_node(peer_name), 1);
+                connection_by_name.insert(peer_name, arc_connection.clone());
+
+                (Status::Ok, Some(arc_connection))
+            }
+            Some(arc_connection) if arc_connection.is_pending() => {
+                match Status::simultaneous(node::atom(), peer_name) {
+                    Status::OkSimultaneous => {
+                        arc_connection.add_handshake();
+
+                        (Status::OkSimultaneous, Some(arc_connection.clone()))
+                    }
+                    status => (status, None),
+                }
+            }
+            Some(_) => (Status::Alive, None),
+        }
+    }
+
+    /// Replaces the connection to `peer_name`, if any, with a new one for the handshake that
+    /// confirmed the old connection is stale or that was accepted before `peer_name` was an atom.
+    fn replace(&self, peer_name: Atom) -> Arc<Connection> {
+        let arc_connection = Connection::pending(arc_node(peer_name), 1);
+
+        if let Some(stale) = self
+            .connection_by_name
+            .write()
+            .insert(peer_name, arc_connection.clone())
+        {
+            stale.close();
+        }
+
+        arc_connection
+    }
+}
+
+/// The process that packets from a connection are decoded into.  Messages and exit signals for
+/// local processes are built on its heap and then copied to the local process, so that the reader
+/// thread never allocates on the heap of a running process.
+#[derive(Default)]
+struct Scratch {
+    option_process: Option<Process>,
+}
+
+impl Scratch {
+    /// A process with at least `need` words free on its heap.
+    fn process(&mut self, need: usize) -> AllocResult<&Process> {
+        let enough_heap = match &self.option_process {
+            Some(process) => {
+                need <= process.acquire_heap().heap_available()
+                    || process.garbage_collect(need, &mut []).is_ok()
+            }
+            None => false,
+        };
+
+        if !enough_heap {
+            self.option_process = Some(process::scratch("distribution", "decode", need)?);
+        }
+
+        Ok(self.option_process.as_ref().unwrap())
+    }
+}
+
+impl connection::Handler for Scratch {
+    fn packet(&mut self, arc_connection: &Arc<Connection>, packet: &[u8]) {
+        match packet.split_first() {
+            Some((&control::PASS_THROUGH, control_bytes)) => {
+                let need = DECODE_HEAP_WORDS_PER_BYTE * control_bytes.len();
+
+                if let Ok(process) = self.process(need) {
+                    if let Ok(control) = control::decode(process, control_bytes) {
+                        let _ = dispatch(arc_connection, process, control);
+                    }
+                }
+            }
+            // Packets without the pass through byte are only used with atom caches, which are
+            // not negotiated in the handshake.
+            _ => (),
+        }
+    }
+
+    fn closed(&mut self, arc_connection: &Arc<Connection>) {
+        let arc_node = arc_connection.arc_node();
+
+        if let Ok(arc_distribution) = distribution() {
+            let mut connection_by_name = arc_distribution.connection_by_name.write();
+
+            if let Some(registered) = connection_by_name.get(&arc_node.name()) {
+                if Arc::ptr_eq(registered, arc_connection) {
+                    connection_by_name.remove(&arc_node.name());
+                }
+            }
+        }
+
+        monitor::remove_remote_node(&arc_node);
+
+        let links = link::take_node(&arc_node);
+        let monitors = monitor::take_node(&arc_node);
+
+        if let Ok(process) = self.process(DISCONNECTED_WORDS * (links.len() + monitors.len())) {
+            disconnected(process, links, monitors);
+        }
+    }
+}
+
+/// The heap of the process that packets are decoded into needs to hold about 2 words per byte
+/// for lists, which need a cons cell for each 1 byte `NIL_EXT` element.
+const DECODE_HEAP_WORDS_PER_BYTE: usize = 2;
+
+/// Enough words for each `{'EXIT', Pid, noconnection}` or
+/// `{'DOWN', Reference, process, {Name, Node}, noconnection}` message.
+const DISCONNECTED_WORDS: usize = 32;
+
+lazy_static! {
+    static ref RW_LOCK_OPTION_ARC_DISTRIBUTION: RwLock<Option<Arc<Distribution>>> =
+        RwLock::new(None);
+}
+
+static NEXT_NODE_ID: AtomicUsize = AtomicUsize::new(1);
+
+fn accept(listener: TcpListener) {
+    for result in listener.incoming() {
+        if let Ok(mut stream) = result {
+            thread::spawn(move || {
+                if let Ok(arc_distribution) = distribution() {
+                    if set_setup_timeouts(&stream).is_err() {
+                        return;
+                    }
+
+                    let mut accepted = None;
+                    let result = handshake::accept(
+                        &mut stream,
+                        node::atom(),
+                        &arc_distribution.cookie,
+                        |peer_name| {
+                            // A name that is not an atom yet can't have a connection, and only
+                            // becomes one if the peer has the cookie
+                            match Atom::try_from_str_existing(peer_name) {
+                                Ok(peer_name) => {
+                                    let (status, option_arc_connection) =
+                                        arc_distribution.accepting(peer_name);
+                                    accepted = option_arc_connection;
+
+                                    status
+                                }
+                                Err(_) => Status::Ok,
+                            }
+                        },
+                    );
+
+                    let arc_connection = match (result, accepted) {
+                        (Ok(_), Some(arc_connection)) => arc_connection,
+                        // The peer confirmed that the existing connection is stale, or its name
+                        // was not known before it was accepted
+                        (Ok(peer), None) => arc_distribution.replace(peer.name),
+                        (Err(_), Some(arc_connection)) => {
+                            arc_connection.failed(Scratch::default());
+
+                            return;
+                        }
+                        (Err(_), None) => return,
+                    };
+
+                    let _ = arc_connection.established(stream, Scratch::default());
+                }
+            });
+        }
+    }
+}
+
+fn arc_node(name: Atom) -> Arc<Node> {
+    nodes::atom_to_arc_node(&name).unwrap_or_else(|| new_arc_node(name))
+}
+
+fn connection(node: Atom) -> Option<Arc<Connection>> {
+    RW_LOCK_OPTION_ARC_DISTRIBUTION
+        .read()
+        .as_ref()
+        .and_then(|arc_distribution| {
+            arc_distribution
+                .connection_by_name
+                .read()
+                .get(&node)
+                .cloned()
+        })
+}
+
+/// Breaks the `links` and `monitors` with processes on a node that was disconnected, as
+/// `noconnection`.
+fn disconnected(
+    process: &Process,
+    links: Vec<(Pid, ExternalPid)>,
+    monitors: Vec<(Reference, Monitor)>,
+) {
+    let noconnection = atom!("noconnection");
+
+    for (pid, external_pid) in links {
+        if let Some(arc_process) = pid_to_process(&pid) {
+            let from = external_pid.clone_to_process(process);
+
+            let _ = exit_signal(process, &arc_process, from, noconnection, true);
+        }
+    }
+
+    for (reference, monitor) in monitors {
+        if let Some(arc_process) = pid_to_process(&monitor.monitoring_pid) {
+            let _ = send_down(
+                process,
+                &arc_process,
+                &reference,
+                &monitor.monitored,
+                noconnection,
+            );
+        }
+    }
+}
+
+fn dispatch(arc_connection: &Arc<Connection>, process: &Process, control: Control) -> Result<()> {
+    let Control {
+        operation,
+        tuple,
+        message,
+    } = control;
+
+    let element = |index: usize| -> Result<Term> {
+        if index < tuple.len() {
+            Ok(tuple[index])
+        } else {
+            Err(anyhow!(
+                "control message ({}) is missing element {}",
+                tuple,
+                index
+            ))
+        }
+    };
+
+    match operation {
+        control::SEND => {
+            let message = message.ok_or_else(|| anyhow!("SEND ({}) has no message", tuple))?;
+
+            if let Some(arc_process) = term_to_process(element(2)?) {
+                send_to_process(&arc_process, message)?;
+            }
+        }
+        control::REG_SEND => {
+            let message = message.ok_or_else(|| anyhow!("REG_SEND ({}) has no message", tuple))?;
+
+            if let Some(arc_process) = term_to_process(element(3)?) {
+                send_to_process(&arc_process, message)?;
+            }
+        }
+        control::LINK => {
+            let from = element(1)?;
+            let external_pid = term_try_into_external_pid(from)?;
+            let to = element(2)?;
+
+            match term_to_process(to) {
+                Some(arc_process) => link::insert(arc_process.pid(), external_pid),
+                None => arc_connection.send(
+                    control::EXIT,
+                    &[to.into(), from.into(), atom!("noproc").into()],
+                    None,
+                )?,
+            }
+        }
+        control::UNLINK => {
+            let external_pid = term_try_into_external_pid(element(1)?)?;
+            let pid: Pid = element(2)?.try_into()?;
+
+            link::remove(pid, &external_pid);
+        }
+        control::EXIT => {
+            let from = element(1)?;
+            let external_pid = term_try_into_external_pid(from)?;
+            let pid: Pid = element(2)?.try_into()?;
+
+            if link::remove(pid, &external_pid) {
+                if let Some(arc_process) = pid_to_process(&pid) {
+                    exit_signal(process, &arc_process, from, element(3)?, true)?;
+                }
+            }
+        }
+        control::EXIT2 => {
+            if let Some(arc_process) = term_to_process(element(2)?) {
+                exit_signal(process, &arc_process, element(1)?, element(3)?, false)?;
+            }
+        }
+        control::MONITOR_P => {
+            let from = element(1)?;
+            let to = element(2)?;
+            let reference = element(3)?;
+
+            match term_to_process(to) {
+                Some(arc_process) => {
+                    let monitoring = term_try_into_external_pid(from)?;
+                    let reference = match reference.decode()? {
+                        TypedTerm::ExternalReference(external_reference) => {
+                            external_reference.as_ref().clone()
+                        }
+                        _ => {
+                            return Err(anyhow!(
+                                "reference ({}) is not from another node",
+                                reference
+                            ))
+                        }
+                    };
+
+                    monitor::insert_remote(
+                        arc_process.pid(),
+                        RemoteMonitor {
+                            monitoring,
+                            reference,
+                            monitored_name: to.try_into().ok(),
+                        },
+                    );
+                }
+                None => arc_connection.send(
+                    control::MONITOR_P_EXIT,
+                    &[
+                        to.into(),
+                        from.into(),
+                        reference.into(),
+                        atom!("noproc").into(),
+                    ],
+                    None,
+                )?,
+            }
+        }
+        control::DEMONITOR_P => {
+            let reference = element(3)?;
+
+            if let TypedTerm::ExternalReference(external_reference) = reference.decode()? {
+                monitor::remove_remote(external_reference.as_ref());
+            }
+        }
+        control::MONITOR_P_EXIT => {
+            let reference = element(3)?;
+            let reason = element(4)?;
+
+            if let TypedTerm::Reference(reference) = reference.decode()? {
+                let reference = reference.as_ref();
+
+                if let Some(Monitor {
+                    monitoring_pid,
+                    monitored,
+                }) = monitor::remove(reference)
+                {
+                    if let Some(arc_process) = pid_to_process(&monitoring_pid) {
+                        send_down(process, &arc_process, reference, &monitored, reason)?;
+                    }
+                }
+            }
+        }
+        _ => {
+            return Err(anyhow!(
+                "control message ({}) operation is not supported",
+                tuple
+            ))
+        }
+    }
+
+    Ok(())
+}
+
+fn distribution() -> Result<Arc<Distribution>> {
+    RW_LOCK_OPTION_ARC_DISTRIBUTION
+        .read()
+        .clone()
+        .ok_or_else(|| anyhow!("local node ({}) is not alive", node::atom()))
+}
+
+/// Delivers an exit signal from `from` to `destination`, either from a broken `link` or from
+/// `exit/2`.  `from` and `reason` are on the heap of `process`, which is where any `'EXIT'`
+/// message is built before it is sent to `destination`.
+fn exit_signal(
+    process: &Process,
+    destination: &Process,
+    from: Term,
+    reason: Term,
+    link: bool,
+) -> AllocResult<()> {
+    let source = anyhow!("exit signal from {} on another node", from).into();
+
+    if !link && reason == atom!("kill") {
+        exit_in_heap_fragment(destination, atom!("killed"), source);
+    } else if destination.traps_exit() {
+        let message = process.tuple_from_slice(&[atom!("EXIT"), from, reason])?;
+
+        send_from(process, destination, message)?;
+    } else if reason != atom!("normal") {
+        exit_in_heap_fragment(destination, reason, source);
+    }
+
+    Ok(())
+}
+
+/// Connects to the node `name` at `alive_name@host` and performs the handshake with it, within
+/// `handshake::SETUP_TIME` for each step.
+fn initiate(
+    arc_distribution: &Distribution,
+    name: Atom,
+    alive_name: &str,
+    host: &str,
+) -> Result<TcpStream> {
+    let port = arc_distribution
+        .epmd
+        .port_please(alive_name, host)?
+        .ok_or_else(|| anyhow!("node ({}) is not registered with epmd", name))?;
+    let mut last_error = anyhow!("host ({}) has no addresses", host);
+
+    for socket_addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&socket_addr, handshake::SETUP_TIME) {
+            Ok(mut stream) => {
+                set_setup_timeouts(&stream)?;
+                let peer =
+                    handshake::initiate(&mut stream, node::atom(), &arc_distribution.cookie)?;
+
+                if peer.name != name {
+                    return Err(anyhow!(
+                        "connected to node ({}) instead of node ({})",
+                        peer.name,
+                        name
+                    ));
+                }
+
+                return Ok(stream);
+            }
+            Err(error) => last_error = error.into(),
+        }
+    }
+
+    Err(last_error)
+}
+
+fn monitor(process: &Process, monitored: Monitored) -> AllocResult<Term> {
+    let reference_term = process.next_reference()?;
+    let reference: Boxed<Reference> = reference_term.try_into().unwrap();
+
+    let monitored_arc_node = monitored.arc_node();
+
+    // Inserted before sending, so that the monitor is broken if the connection fails afterwards
+    monitor::insert(
+        *reference.as_ref(),
+        Monitor {
+            monitoring_pid: process.pid(),
+            monitored: monitored.clone(),
+        },
+    );
+
+    let result = connect(monitored_arc_node.name()).and_then(|arc_connection| {
+        arc_connection.send(
+            control::MONITOR_P,
+            &[
+                process.pid_term().into(),
+                monitored.element(),
+                reference_term.into(),
+            ],
+            None,
+        )
+    });
+
+    if result.is_err() {
+        if let Some(Monitor { monitored, .. }) = monitor::remove(&reference) {
+            send_down(
+                process,
+                process,
+                &reference,
+                &monitored,
+                atom!("noconnection"),
+            )?;
+        }
+    }
+
+    Ok(reference_term)
+}
+
+fn new_arc_node(name: Atom) -> Arc<Node> {
+    let id = NEXT_NODE_ID.fetch_add(1, Ordering::SeqCst);
+    let arc_node = Arc::new(Node::new(id, name, 0));
+    nodes::insert(arc_node.clone());
+
+    arc_node
+}
+
+/// Sends `message` on the heap of `process` to `destination`.
+fn send_from(process: &Process, destination: &Process, message: Term) -> AllocResult<()> {
+    if process.pid() == destination.pid() {
+        process.send_from_self(message);
+
+        Ok(())
+    } else {
+        send_to_process(destination, message)
+    }
+}
+
+/// Sends `{'DOWN', reference, process, object, info}` to the monitoring `destination`, building
+/// it on the heap of `process`, where `info` is too.
+fn send_down(
+    process: &Process,
+    destination: &Process,
+    reference: &Reference,
+    monitored: &Monitored,
+    info: Term,
+) -> AllocResult<()> {
+    let reference_term = reference.clone_to_process(process);
+    let object = monitored.object(process)?;
+    let down = process.tuple_from_slice(&[
+        atom!("DOWN"),
+        reference_term,
+        atom!("process"),
+        object,
+        info,
+    ])?;
+
+    send_from(process, destination, down)
+}
+
+fn send_control(
+    node: Atom,
+    options: send::Options,
+    operation: u8,
+    elements: &[Element],
+    message: Option<Term>,
+) -> Sent {
+    let result = match connection(node) {
+        Some(arc_connection) => Ok(arc_connection),
+        None if !options.connect => return Sent::ConnectRequired,
+        None if !options.suspend => return Sent::SuspendRequired,
+        None => connect(node),
+    };
+
+    if let Ok(arc_connection) = result {
+        let _ = arc_connection.send(operation, elements, message);
+    }
+
+    Sent::Sent
+}
+
+/// Bounds every read and write of the handshake on `stream` by `handshake::SETUP_TIME`, so that a
+/// peer that stalls is given up on.
+fn set_setup_timeouts(stream: &TcpStream) -> Result<()> {
+    stream.set_read_timeout(Some(handshake::SETUP_TIME))?;
+    stream.set_write_timeout(Some(handshake::SETUP_TIME))?;
+
+    Ok(())
+}
+
+/// Splits `alive_name@host`.
+fn split_name(name: &str) -> Result<(&str, &str)> {
+    let mut parts = name.splitn(2, '@');
+
+    match (parts.next(), parts.next()) {
+        (Some(alive_name), Some(host)) if !alive_name.is_empty() && !host.is_empty() => {
+            Ok((alive_name, host))
+        }
+        _ => Err(anyhow!("node name ({}) is not alive_name@host", name)),
+    }
+}
+
+fn term_to_process(term: Term) -> Option<Arc<Process>> {
+    match term.decode().ok()? {
+        TypedTerm::Pid(pid) => pid_to_process(&pid),
+        TypedTerm::Atom(name) => registry::atom_to_process(&name),
+        _ => None,
+    }
+}
+
+fn term_try_into_external_pid(term: Term) -> Result<ExternalPid> {
+    match term.decode()? {
+        TypedTerm::ExternalPid(external_pid) => Ok(external_pid.as_ref().clone()),
+        _ => Err(anyhow!("pid ({}) is not from another node", term)),
+    }
+}
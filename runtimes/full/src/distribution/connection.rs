@@ -0,0 +1,243 @@
+//! A connection to another node after the handshake, when every message is framed by a 32-bit big
+//! endian length and a length of 0 is a tick that keeps the connection alive.
+//!
+//! A connection exists as soon as it is needed, before the handshake finishes, so that sending to
+//! another node never waits for the network; packets sent before then are written once it does.
+use std::io::{Read, Write};
+use std::mem;
+use std::net::{Shutdown, TcpStream};
+use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
+
+use anyhow::*;
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Node;
+
+use super::control::{self, Element};
+
+pub struct Connection {
+    arc_node: Arc<Node>,
+    state: Mutex<State>,
+}
+
+impl Connection {
+    /// A connection to `arc_node` that is waiting for `handshakes` handshakes, of which the first
+    /// to finish is `established`.
+    pub fn pending(arc_node: Arc<Node>, handshakes: usize) -> Arc<Self> {
+        Arc::new(Self {
+            arc_node,
+            state: Mutex::new(State::Pending {
+                buffer: Vec::new(),
+                handshakes,
+            }),
+        })
+    }
+
+    pub fn arc_node(&self) -> Arc<Node> {
+        self.arc_node.clone()
+    }
+
+    pub fn is_pending(&self) -> bool {
+        match *self.state.lock() {
+            State::Pending { .. } => true,
+            _ => false,
+        }
+    }
+
+    /// Waits for one more handshake, such as one accepted during a simultaneous connect.
+    pub fn add_handshake(&self) {
+        if let State::Pending { handshakes, .. } = &mut *self.state.lock() {
+            *handshakes += 1;
+        }
+    }
+
+    /// Sends the control message `{operation, elements...}` followed by `message`, if any.
+    pub fn send(&self, operation: u8, elements: &[Element], message: Option<Term>) -> Result<()> {
+        let packet = control::encode(operation, elements, message);
+
+        self.write_packet(&packet)
+    }
+
+    /// Writes the packets sent so far to `stream` after its handshake finished and starts the
+    /// threads that read packets from and tick it.  Fails if another handshake already finished or
+    /// the connection was closed.
+    pub fn established<H: Handler>(
+        self: &Arc<Self>,
+        stream: TcpStream,
+        mut handler: H,
+    ) -> Result<()> {
+        // A peer that sends nothing, not even ticks, for `TICK_TIMEOUT` is down
+        stream.set_read_timeout(Some(TICK_TIMEOUT))?;
+        stream.set_write_timeout(None)?;
+        let mut reader = stream.try_clone()?;
+
+        {
+            let mut state = self.state.lock();
+            let mut writer = stream;
+
+            match &*state {
+                State::Pending { buffer, .. } => {
+                    writer.write_all(buffer)?;
+                    writer.flush()?;
+                }
+                _ => {
+                    return Err(anyhow!(
+                        "connection to {} is not pending",
+                        self.arc_node.name()
+                    ))
+                }
+            }
+
+            *state = State::Connected(writer);
+        }
+
+        let reader_arc_connection = self.clone();
+        thread::Builder::new()
+            .name(format!("{} reader", self.arc_node.name()))
+            .spawn(move || {
+                while let Ok(packet) = read_packet(&mut reader) {
+                    if !packet.is_empty() {
+                        handler.packet(&reader_arc_connection, &packet);
+                    }
+                }
+
+                reader_arc_connection.close();
+                handler.closed(&reader_arc_connection);
+            })?;
+
+        let weak_connection = Arc::downgrade(self);
+        thread::Builder::new()
+            .name(format!("{} ticker", self.arc_node.name()))
+            .spawn(move || tick_until_dropped(weak_connection))?;
+
+        Ok(())
+    }
+
+    /// Gives up on one of the handshakes of a pending connection.  When it was the last, the
+    /// connection is closed and `handler` is told.
+    pub fn failed<H: Handler>(self: &Arc<Self>, mut handler: H) {
+        let closed = {
+            let mut state = self.state.lock();
+
+            match &mut *state {
+                State::Pending { handshakes, .. } if 1 < *handshakes => {
+                    *handshakes -= 1;
+
+                    false
+                }
+                State::Pending { .. } => {
+                    *state = State::Closed;
+
+                    true
+                }
+                _ => false,
+            }
+        };
+
+        if closed {
+            handler.closed(self);
+        }
+    }
+
+    /// Closes the connection, so that its reader stops and its handler is told, if it was
+    /// established.
+    pub fn close(&self) {
+        if let State::Connected(stream) = mem::replace(&mut *self.state.lock(), State::Closed) {
+            let _ = stream.shutdown(Shutdown::Both);
+        }
+    }
+
+    fn tick(&self) -> Result<()> {
+        self.write_packet(&[])
+    }
+
+    fn write_packet(&self, packet: &[u8]) -> Result<()> {
+        let len_bytes = (packet.len() as u32).to_be_bytes();
+
+        match &mut *self.state.lock() {
+            State::Pending { buffer, .. } => {
+                buffer.extend_from_slice(&len_bytes);
+                buffer.extend_from_slice(packet);
+            }
+            State::Connected(writer) => {
+                writer.write_all(&len_bytes)?;
+                writer.write_all(packet)?;
+                writer.flush()?;
+            }
+            State::Closed => {
+                return Err(anyhow!("connection to {} is closed", self.arc_node.name()))
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// How often a tick is sent when there is nothing else to send, like the default `net_ticktime`
+/// of 60 seconds divided into 4 ticks.
+pub const TICK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long the peer can send nothing before the connection is closed, which is 4 missed ticks.
+pub const TICK_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Handles what is read from a `Connection` on its reader thread.
+pub trait Handler: Send + 'static {
+    /// Called with each packet that isn't a tick.
+    fn packet(&mut self, arc_connection: &Arc<Connection>, packet: &[u8]);
+
+    /// Called once when the connection is closed.
+    fn closed(&mut self, arc_connection: &Arc<Connection>);
+}
+
+/// Starts a connection to the node at `arc_node` on `stream` after the handshake with it finished.
+pub fn start<H: Handler>(
+    arc_node: Arc<Node>,
+    stream: TcpStream,
+    handler: H,
+) -> Result<Arc<Connection>> {
+    let arc_connection = Connection::pending(arc_node, 1);
+    arc_connection.established(stream, handler)?;
+
+    Ok(arc_connection)
+}
+
+// Private
+
+enum State {
+    /// Waiting for a handshake, with the packets sent so far already framed in `buffer`.
+    Pending {
+        buffer: Vec<u8>,
+        handshakes: usize,
+    },
+    Connected(TcpStream),
+    Closed,
+}
+
+fn read_packet(reader: &mut TcpStream) -> Result<Vec<u8>> {
+    let mut len_bytes = [0; 4];
+    reader.read_exact(&mut len_bytes)?;
+
+    let mut packet = vec![0; u32::from_be_bytes(len_bytes) as usize];
+    reader.read_exact(&mut packet)?;
+
+    Ok(packet)
+}
+
+fn tick_until_dropped(weak_connection: Weak<Connection>) {
+    loop {
+        thread::sleep(TICK_INTERVAL);
+
+        match weak_connection.upgrade() {
+            Some(arc_connection) => {
+                if arc_connection.tick().is_err() {
+                    break;
+                }
+            }
+            None => break,
+        }
+    }
+}
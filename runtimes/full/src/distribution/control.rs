@@ -0,0 +1,107 @@
+//! [Control messages](http://erlang.org/doc/apps/erts/erl_dist_protocol.html#control-messages)
+//! sent between connected nodes.
+//!
+//! Every message is the `PASS_THROUGH` byte followed by the control tuple and, for the
+//! operations that carry one, the message, each encoded in the External Term Format.
+use std::convert::TryInto;
+use std::sync::Arc;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Node;
+use liblumen_alloc::fixnum;
+
+use crate::binary::to_term::{bytes_to_term, Options};
+use crate::distribution::external_term_format::{encode, version};
+
+pub const PASS_THROUGH: u8 = 112;
+
+pub const LINK: u8 = 1;
+pub const SEND: u8 = 2;
+pub const EXIT: u8 = 3;
+pub const UNLINK: u8 = 4;
+pub const REG_SEND: u8 = 6;
+pub const EXIT2: u8 = 8;
+pub const MONITOR_P: u8 = 19;
+pub const DEMONITOR_P: u8 = 20;
+pub const MONITOR_P_EXIT: u8 = 21;
+
+/// An element of a control tuple.  Pids and references of processes on other nodes are kept in
+/// the link and monitor tables outside of any heap, so they can't always be passed as a `Term`.
+pub enum Element<'a> {
+    Term(Term),
+    ExternalPid(&'a ExternalPid),
+    Reference(Arc<Node>, &'a Reference),
+}
+
+impl<'a> From<Term> for Element<'a> {
+    fn from(term: Term) -> Self {
+        Element::Term(term)
+    }
+}
+
+/// Encodes the control tuple `{operation, elements...}` and the optional `message`.
+pub fn encode(operation: u8, elements: &[Element], message: Option<Term>) -> Vec<u8> {
+    let mut byte_vec = vec![PASS_THROUGH, version::NUMBER];
+
+    encode::append_tuple_header(&mut byte_vec, 1 + elements.len());
+    encode::append_tagged(&mut byte_vec, fixnum!(operation));
+
+    for element in elements {
+        match element {
+            Element::Term(term) => encode::append_tagged(&mut byte_vec, *term),
+            Element::ExternalPid(external_pid) => {
+                encode::append_external_pid(&mut byte_vec, external_pid)
+            }
+            Element::Reference(arc_node, reference) => {
+                encode::append_reference(&mut byte_vec, arc_node.clone(), reference)
+            }
+        }
+    }
+
+    if let Some(message) = message {
+        byte_vec.extend_from_slice(&encode::term_to_byte_vec(message));
+    }
+
+    byte_vec
+}
+
+/// A decoded control message.  The terms are on the heap of the process passed to `decode`.
+pub struct Control {
+    pub operation: u8,
+    pub tuple: Boxed<Tuple>,
+    pub message: Option<Term>,
+}
+
+/// Decodes a packet without the `PASS_THROUGH` byte into `process`.
+pub fn decode(process: &Process, bytes: &[u8]) -> InternalResult<Control> {
+    let options: Options = Default::default();
+    let (control, used) = bytes_to_term(process, &options, bytes)?;
+    let tuple: Boxed<Tuple> = control
+        .try_into()
+        .with_context(|| format!("control message ({}) is not a tuple", control))?;
+    let operation: u8 = if 0 < tuple.len() {
+        tuple[0].try_into().ok()
+    } else {
+        None
+    }
+    .ok_or_else(|| anyhow!("control message ({}) has no operation", control))?;
+
+    let message_bytes = &bytes[used..];
+    let message = if message_bytes.is_empty() {
+        None
+    } else {
+        let (message, _) = bytes_to_term(process, &options, message_bytes)?;
+
+        Some(message)
+    };
+
+    Ok(Control {
+        operation,
+        tuple,
+        message,
+    })
+}
@@ -0,0 +1,157 @@
+//! Finding the port other nodes listen on, which is normally the job of the
+//! [Erlang Port Mapper Daemon](http://erlang.org/doc/apps/erts/erl_dist_protocol.html#epmd-protocol).
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use anyhow::*;
+use hashbrown::HashMap;
+
+use liblumen_core::locks::Mutex;
+
+/// Maps node names, without the `@host` part, to the port the node accepts connections on.
+pub trait Epmd: Send + Sync {
+    /// Registers this node's `name` as listening on `port`, returning the node's creation.
+    fn register(&self, name: &str, port: u16) -> Result<u32>;
+
+    /// The port node `name` on `host` listens on, or `None` if no such node is registered.
+    fn port_please(&self, name: &str, host: &str) -> Result<Option<u16>>;
+}
+
+/// Talks to the `epmd` daemon on each host.
+pub struct TcpEpmd {
+    port: u16,
+    /// EPMD keeps the registration only while the connection that registered it stays open.
+    registration: Mutex<Option<TcpStream>>,
+}
+
+impl TcpEpmd {
+    pub const DEFAULT_PORT: u16 = 4369;
+
+    pub fn new(port: u16) -> Self {
+        Self {
+            port,
+            registration: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for TcpEpmd {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_PORT)
+    }
+}
+
+impl Epmd for TcpEpmd {
+    fn register(&self, name: &str, port: u16) -> Result<u32> {
+        let mut stream = TcpStream::connect(("localhost", self.port))
+            .with_context(|| format!("could not connect to epmd on port {}", self.port))?;
+
+        let mut request = vec![ALIVE2_REQ];
+        request.extend_from_slice(&port.to_be_bytes());
+        request.push(NORMAL_NODE);
+        request.push(TCP_IPV4);
+        request.extend_from_slice(&VERSION.to_be_bytes());
+        request.extend_from_slice(&VERSION.to_be_bytes());
+        request.extend_from_slice(&(name.len() as u16).to_be_bytes());
+        request.extend_from_slice(name.as_bytes());
+        // No extra
+        request.extend_from_slice(&0_u16.to_be_bytes());
+        write_request(&mut stream, &request)?;
+
+        let mut tag_result = [0; 2];
+        stream.read_exact(&mut tag_result)?;
+
+        let creation = match tag_result {
+            [ALIVE2_RESP, 0] => {
+                let mut creation = [0; 2];
+                stream.read_exact(&mut creation)?;
+
+                u16::from_be_bytes(creation) as u32
+            }
+            [ALIVE2_X_RESP, 0] => {
+                let mut creation = [0; 4];
+                stream.read_exact(&mut creation)?;
+
+                u32::from_be_bytes(creation)
+            }
+            [_, result] => {
+                return Err(anyhow!(
+                    "epmd refused to register name ({}) with result ({})",
+                    name,
+                    result
+                ))
+            }
+        };
+
+        *self.registration.lock() = Some(stream);
+
+        Ok(creation)
+    }
+
+    fn port_please(&self, name: &str, host: &str) -> Result<Option<u16>> {
+        let mut stream = TcpStream::connect((host, self.port))
+            .with_context(|| format!("could not connect to epmd on {}:{}", host, self.port))?;
+
+        let mut request = vec![PORT_PLEASE2_REQ];
+        request.extend_from_slice(name.as_bytes());
+        write_request(&mut stream, &request)?;
+
+        let mut tag_result = [0; 2];
+        stream.read_exact(&mut tag_result)?;
+
+        match tag_result {
+            [PORT2_RESP, 0] => {
+                let mut port = [0; 2];
+                stream.read_exact(&mut port)?;
+
+                Ok(Some(u16::from_be_bytes(port)))
+            }
+            [PORT2_RESP, _] => Ok(None),
+            [tag, _] => Err(anyhow!("epmd responded with unexpected tag ({})", tag)),
+        }
+    }
+}
+
+/// Keeps the registrations in memory, so that nodes in the same OS process can find each other
+/// without a running `epmd`.
+#[derive(Default)]
+pub struct LocalEpmd {
+    port_by_name: Mutex<HashMap<String, u16>>,
+}
+
+impl Epmd for LocalEpmd {
+    fn register(&self, name: &str, port: u16) -> Result<u32> {
+        let mut port_by_name = self.port_by_name.lock();
+
+        if port_by_name.contains_key(name) {
+            Err(anyhow!("name ({}) is already registered", name))
+        } else {
+            port_by_name.insert(name.to_string(), port);
+
+            Ok(0)
+        }
+    }
+
+    fn port_please(&self, name: &str, _host: &str) -> Result<Option<u16>> {
+        Ok(self.port_by_name.lock().get(name).copied())
+    }
+}
+
+// Private
+
+const ALIVE2_X_RESP: u8 = 118;
+const ALIVE2_REQ: u8 = 120;
+const ALIVE2_RESP: u8 = 121;
+const PORT_PLEASE2_REQ: u8 = 122;
+const PORT2_RESP: u8 = 119;
+
+const NORMAL_NODE: u8 = 77;
+const TCP_IPV4: u8 = 0;
+const VERSION: u16 = 5;
+
+fn write_request(stream: &mut TcpStream, request: &[u8]) -> Result<()> {
+    stream.write_all(&(request.len() as u16).to_be_bytes())?;
+    stream.write_all(request)?;
+
+    Ok(())
+}
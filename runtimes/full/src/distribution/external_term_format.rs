@@ -4,8 +4,12 @@ mod atom_utf8;
 mod big;
 mod binary;
 mod bit_binary;
+pub mod compressed;
+pub mod encode;
 mod export;
 mod f64;
+mod float;
+mod function;
 mod i32;
 mod integer;
 mod isize;
@@ -14,8 +18,12 @@ mod map;
 mod new_float;
 mod new_function;
 mod new_pid;
+mod new_port;
+mod new_reference;
 mod newer_reference;
 mod pid;
+mod port;
+mod reference;
 mod sign;
 mod small_atom;
 mod small_atom_utf8;
@@ -75,6 +83,14 @@ pub enum DecodeError {
     UnexpectedVersion { version: u8, backtrace: Backtrace },
     #[error("unexpected tag ({tag})")]
     UnexpectedTag { tag: Tag, backtrace: Backtrace },
+    #[error("atom cache reference ({index}) is only valid in a distribution message")]
+    AtomCacheReference { index: u8, backtrace: Backtrace },
+    #[error("uncompressed size ({actual}) does not match declared size ({declared})")]
+    UncompressedSize {
+        declared: usize,
+        actual: usize,
+        backtrace: Backtrace,
+    },
 }
 
 impl From<DecodeError> for InternalException {
@@ -87,6 +103,7 @@ impl From<DecodeError> for InternalException {
 #[repr(u8)]
 pub enum Tag {
     NewFloat = 70,
+    Compressed = 80,
     BitBinary = 77,
     AtomCacheReference = 82,
     NewPID = 88,
@@ -188,6 +205,47 @@ impl Into<Creator> for Pid {
 
 // Private
 
+/// Local ports are immediates, while ports from other nodes are boxed.
+fn port_to_term(process: &Process, arc_node: Arc<Node>, id: u32) -> InternalResult<Term> {
+    if arc_node == node::arc_node() {
+        let port = unsafe { Port::from_raw(id as usize) };
+
+        port.encode()
+    } else {
+        let external_port = ExternalPort::new(arc_node, id as usize);
+
+        Ok(external_port.clone_to_process(process))
+    }
+}
+
+/// `REFERENCE_EXT`, `NEW_REFERENCE_EXT` and `NEWER_REFERENCE_EXT` all have up to 3 ID words.
+/// When all 3 are present, the first is the scheduler ID and the other 2 are the number, as
+/// encoded by `term_to_binary`.
+fn reference_id_to_term(
+    process: &Process,
+    arc_node: Arc<Node>,
+    id_words: &[u32],
+) -> InternalResult<Term> {
+    let (scheduler_id_u32, number_words) = if id_words.len() == 3 {
+        (id_words[0], &id_words[1..])
+    } else {
+        (0, id_words)
+    };
+    let number = number_words
+        .iter()
+        .fold(0_u64, |acc, word| (acc << 32) | (*word as u64));
+
+    if arc_node == node::arc_node() {
+        process
+            .reference_from_scheduler(scheduler_id_u32.into(), number)
+            .map_err(From::from)
+    } else {
+        let external_reference = ExternalReference::new(arc_node, scheduler_id_u32.into(), number);
+
+        Ok(external_reference.clone_to_process(process))
+    }
+}
+
 fn decode_vec_term<'a>(
     process: &Process,
     safe: bool,
@@ -0,0 +1,79 @@
+use std::backtrace::Backtrace;
+use std::io::{Read, Write};
+
+use anyhow::*;
+use libflate::zlib;
+
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Process;
+
+use super::{term, u32, DecodeError, Tag};
+
+/// Decodes a term compressed by `term_to_binary(Term, [compressed])`.
+///
+/// The zlib stream runs to the end of `bytes`, so no bytes are left after the term.  The declared
+/// uncompressed size is not trusted: at most one byte more than it is inflated, so that a stream
+/// that is longer or shorter than declared is rejected without inflating all of it.
+pub fn decode<'a>(
+    process: &Process,
+    safe: bool,
+    bytes: &'a [u8],
+) -> InternalResult<(Term, &'a [u8])> {
+    let (uncompressed_size, after_uncompressed_size_bytes) = u32::decode(bytes)?;
+    let uncompressed_size = uncompressed_size as usize;
+
+    let mut uncompressed_byte_vec = Vec::new();
+    zlib::Decoder::new(after_uncompressed_size_bytes)
+        .and_then(|decoder| {
+            decoder
+                .take(uncompressed_size as u64 + 1)
+                .read_to_end(&mut uncompressed_byte_vec)
+        })
+        .context("compressed bytes are not zlib")?;
+
+    if uncompressed_byte_vec.len() != uncompressed_size {
+        return Err(DecodeError::UncompressedSize {
+            declared: uncompressed_size,
+            actual: uncompressed_byte_vec.len(),
+            backtrace: Backtrace::capture(),
+        }
+        .into());
+    }
+
+    let (term, after_term_bytes) = term::decode_tagged(process, safe, &uncompressed_byte_vec)?;
+
+    if !after_term_bytes.is_empty() {
+        return Err(anyhow!(
+            "{} byte(s) are left after the compressed term",
+            after_term_bytes.len()
+        )
+        .into());
+    }
+
+    Ok((term, &bytes[bytes.len()..]))
+}
+
+/// Compresses `tagged_bytes`, the encoded term after the version number.
+///
+/// Returns `None` when compressing does not make the encoding smaller, in which case, like
+/// BEAM, the uncompressed encoding should be used.
+pub fn encode(tagged_bytes: &[u8]) -> Option<Vec<u8>> {
+    let mut encoder = zlib::Encoder::new(Vec::new()).unwrap();
+    encoder.write_all(tagged_bytes).unwrap();
+    let mut zlib_byte_vec = encoder.finish().into_result().unwrap();
+
+    // tag + uncompressed size
+    let header_len = 1 + std::mem::size_of::<u32>();
+
+    if header_len + zlib_byte_vec.len() < tagged_bytes.len() {
+        let mut byte_vec = Vec::with_capacity(header_len + zlib_byte_vec.len());
+        byte_vec.push(Tag::Compressed.into());
+        byte_vec.extend_from_slice(&(tagged_bytes.len() as u32).to_be_bytes());
+        byte_vec.append(&mut zlib_byte_vec);
+
+        Some(byte_vec)
+    } else {
+        None
+    }
+}
@@ -1,3 +1,5 @@
+use anyhow::*;
+
 use liblumen_alloc::erts::exception::InternalResult;
 use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::erts::Process;
@@ -17,6 +19,17 @@ pub fn decode<'a>(
 
     let option_code = code::export::get(&module, &function, arity);
 
+    // Like atoms, exports are never garbage collected, so `safe` only allows existing ones.
+    if safe && option_code.is_none() {
+        return Err(anyhow!(
+            "tried to convert to an export ({}:{}/{}) that doesn't exist",
+            module.name(),
+            function.name(),
+            arity
+        )
+        .into());
+    }
+
     let closure = process.export_closure(module, function, arity, option_code)?;
 
     Ok((closure, after_arity_bytes))
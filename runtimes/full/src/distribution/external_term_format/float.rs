@@ -0,0 +1,30 @@
+use std::str;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Process;
+
+use super::try_split_at;
+
+// > A finite float (i.e. not inf, -inf or NaN) is stored in string format. The format used in
+// > sprintf to format the float is "%.20e" (there are more bytes allocated than necessary). To
+// > unpack the float, use sscanf with format "%lf".
+// -- http://erlang.org/doc/apps/erts/erl_ext_dist.html#float_ext
+const FLOAT_STRING_LEN: usize = 31;
+
+pub fn decode<'a>(process: &Process, bytes: &'a [u8]) -> InternalResult<(Term, &'a [u8])> {
+    let (float_string_bytes, after_float_string_bytes) = try_split_at(bytes, FLOAT_STRING_LEN)?;
+
+    let float_str = str::from_utf8(float_string_bytes)
+        .context("float string bytes are not UTF-8")?
+        .trim_end_matches('\0');
+    let f: f64 = float_str
+        .trim()
+        .parse()
+        .with_context(|| format!("float string ({:?}) is not a float", float_str))?;
+    let float = process.float(f)?;
+
+    Ok((float, after_float_string_bytes))
+}
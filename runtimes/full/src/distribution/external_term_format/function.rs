@@ -0,0 +1,52 @@
+use anyhow::*;
+
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::term::closure::{Index, OldUnique};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Process;
+
+use crate::code;
+
+use super::{atom, decode_vec_term, isize, u32, Pid};
+
+/// `FUN_EXT` is the encoding used before `NEW_FUN_EXT`.  It lacks the `unique` MD5 and the
+/// `arity`, so they have to come from already loaded code.
+pub fn decode<'a>(
+    process: &Process,
+    safe: bool,
+    bytes: &'a [u8],
+) -> InternalResult<(Term, &'a [u8])> {
+    let (num_free, after_num_free_bytes) = u32::decode(bytes)?;
+    let (creator, after_creator_bytes) = Pid::decode(safe, after_num_free_bytes)?;
+    let (module, after_module_bytes) = atom::decode_tagged(safe, after_creator_bytes)?;
+    let (index, after_index_bytes) = isize::decode(after_module_bytes)?;
+    let (old_uniq, after_old_uniq_bytes) = isize::decode(after_index_bytes)?;
+
+    let index = index as Index;
+    let old_unique = old_uniq as OldUnique;
+
+    let (unique, arity, code) =
+        code::anonymous::find(&module, &index, &old_unique).with_context(|| {
+            format!(
+                "fun ({}, index {}, old unique {}) is not loaded, so its arity is unknown",
+                module, index, old_unique
+            )
+        })?;
+
+    let env_len: usize = num_free as usize;
+    let (env_vec, after_vec_term_bytes) =
+        decode_vec_term(process, safe, after_old_uniq_bytes, env_len)?;
+
+    let closure = process.anonymous_closure_with_env_from_slice(
+        module,
+        index,
+        old_unique,
+        unique,
+        arity,
+        Some(code),
+        creator.into(),
+        &env_vec,
+    )?;
+
+    Ok((closure, after_vec_term_bytes))
+}
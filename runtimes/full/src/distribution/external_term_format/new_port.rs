@@ -0,0 +1,20 @@
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Process;
+
+use super::{arc_node, u32};
+
+pub fn decode<'a>(
+    process: &Process,
+    safe: bool,
+    bytes: &'a [u8],
+) -> InternalResult<(Term, &'a [u8])> {
+    let (arc_node, after_node_bytes) = arc_node::decode(safe, bytes)?;
+    let (id, after_id_bytes) = u32::decode(after_node_bytes)?;
+    // TODO use creation to differentiate respawned nodes
+    let (_creation, after_creation_bytes) = u32::decode(after_id_bytes)?;
+
+    let port = super::port_to_term(process, arc_node, id)?;
+
+    Ok((port, after_creation_bytes))
+}
@@ -0,0 +1,29 @@
+use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Process;
+
+use super::{arc_node, u16, u32, u8};
+
+pub fn decode<'a>(
+    process: &Process,
+    safe: bool,
+    bytes: &'a [u8],
+) -> InternalResult<(Term, &'a [u8])> {
+    let (len_u16, after_len_bytes) = u16::decode(bytes)?;
+    let (arc_node, after_node_bytes) = arc_node::decode(safe, after_len_bytes)?;
+    // TODO use creation to differentiate respawned nodes
+    let (_creation, after_creation_bytes) = u8::decode(after_node_bytes)?;
+
+    let mut id_vec = Vec::with_capacity(len_u16 as usize);
+    let mut remaining_bytes = after_creation_bytes;
+
+    for _ in 0..len_u16 {
+        let (id, after_id_bytes) = u32::decode(remaining_bytes)?;
+        id_vec.push(id);
+        remaining_bytes = after_id_bytes;
+    }
+
+    let reference = super::reference_id_to_term(process, arc_node, &id_vec)?;
+
+    Ok((reference, remaining_bytes))
+}
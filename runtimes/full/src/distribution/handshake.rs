@@ -0,0 +1,290 @@
+//! The version 5 [distribution handshake](http://erlang.org/doc/apps/erts/erl_dist_protocol.html#distribution-handshake)
+//! that connects two nodes that share the same cookie.
+//!
+//! Every handshake message is framed by a 16-bit big endian length.
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+/// The peer node, as it introduced itself during the handshake, once it proved it has the cookie.
+pub struct Peer {
+    pub name: Atom,
+    pub flags: u32,
+}
+
+/// The status the accepting node replies with after the peer introduced itself.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    Ok,
+    /// The accepting node was also connecting to the peer, and gives up on that connection.
+    OkSimultaneous,
+    /// The accepting node was also connecting to the peer, and keeps that connection instead.
+    Nok,
+    /// The accepting node is already connected to the peer, which has to confirm that it wants a
+    /// new connection.
+    Alive,
+}
+
+impl Status {
+    /// The status of a simultaneous connect between the accepting node `name` and `peer_name`:
+    /// the connection initiated by the node with the greater name is kept.
+    pub fn simultaneous(name: Atom, peer_name: Atom) -> Self {
+        if name.name() < peer_name.name() {
+            Status::OkSimultaneous
+        } else {
+            Status::Nok
+        }
+    }
+
+    fn message(&self) -> &'static [u8] {
+        match self {
+            Status::Ok => b"sok",
+            Status::OkSimultaneous => b"sok_simultaneous",
+            Status::Nok => b"snok",
+            Status::Alive => b"salive",
+        }
+    }
+}
+
+/// How long a connection can take to set up, like the default `net_setuptime`.
+pub const SETUP_TIME: Duration = Duration::from_secs(7);
+
+/// The distribution flags this node supports.
+pub const FLAGS: u32 = PUBLISHED
+    | EXTENDED_REFERENCES
+    | DIST_MONITOR
+    | FUN_TAGS
+    | NEW_FUN_TAGS
+    | EXTENDED_PIDS_PORTS
+    | EXPORT_PTR_TAG
+    | BIT_BINARIES
+    | NEW_FLOATS
+    | UTF8_ATOMS
+    | MAP_TAG;
+
+/// The challenge digest: the MD5 of the cookie followed by the challenge as decimal.
+pub fn digest(cookie: &str, challenge: u32) -> [u8; 16] {
+    let mut input = cookie.as_bytes().to_vec();
+    input.extend_from_slice(challenge.to_string().as_bytes());
+
+    md5::compute(input).0
+}
+
+/// Performs the handshake for the node that opened the connection to `stream`.
+pub fn initiate<S: Read + Write>(stream: &mut S, name: Atom, cookie: &str) -> Result<Peer> {
+    // send_name
+    let mut send_name = vec![b'n'];
+    send_name.extend_from_slice(&VERSION.to_be_bytes());
+    send_name.extend_from_slice(&FLAGS.to_be_bytes());
+    send_name.extend_from_slice(name.name().as_bytes());
+    write_message(stream, &send_name)?;
+
+    // recv_status
+    let status = read_message(stream)?;
+
+    match status.split_first() {
+        Some((b's', b"ok")) | Some((b's', b"ok_simultaneous")) => (),
+        // This node only connects when it has no connection, so the accepting node's connection
+        // is stale
+        Some((b's', b"alive")) => write_message(stream, b"strue")?,
+        Some((b's', status)) => {
+            return Err(anyhow!(
+                "peer refused connection with status ({})",
+                String::from_utf8_lossy(status)
+            ))
+        }
+        _ => return Err(anyhow!("expected status message ({:?})", status)),
+    }
+
+    // recv_challenge
+    let (peer_name, flags, peer_challenge) = challenge_from_message(&read_message(stream)?)?;
+
+    // send_challenge_reply
+    let challenge: u32 = rand::random();
+    let mut challenge_reply = vec![b'r'];
+    challenge_reply.extend_from_slice(&challenge.to_be_bytes());
+    challenge_reply.extend_from_slice(&digest(cookie, peer_challenge));
+    write_message(stream, &challenge_reply)?;
+
+    // recv_challenge_ack
+    let challenge_ack = read_message(stream)?;
+
+    match challenge_ack.split_first() {
+        Some((b'a', peer_digest)) if peer_digest == digest(cookie, challenge) => {
+            authenticated(peer_name, flags)
+        }
+        Some((b'a', _)) => Err(anyhow!(
+            "peer ({}) does not have the same cookie",
+            peer_name
+        )),
+        _ => Err(anyhow!(
+            "expected challenge acknowledgement message ({:?})",
+            challenge_ack
+        )),
+    }
+}
+
+/// Performs the handshake for the node that accepted the connection on `stream`, replying with the
+/// `status` for the peer's name once it introduced itself.
+///
+/// The peer's name is only made an atom once the peer proved it has the cookie, so that a peer
+/// without it can't fill the atom table.
+pub fn accept<S, F>(stream: &mut S, name: Atom, cookie: &str, status: F) -> Result<Peer>
+where
+    S: Read + Write,
+    F: FnOnce(&str) -> Status,
+{
+    // recv_name
+    let send_name = read_message(stream)?;
+
+    let (peer_name, flags) = match send_name.split_first() {
+        Some((b'n', rest)) if 6 <= rest.len() => {
+            let (version_flags, name_bytes) = rest.split_at(6);
+            let flags = u32_from_bytes(&version_flags[2..]);
+            let name = name_from_bytes(name_bytes)?;
+
+            (name, flags)
+        }
+        _ => return Err(anyhow!("expected name message ({:?})", send_name)),
+    };
+
+    // send_status
+    let status = status(&peer_name);
+    write_message(stream, status.message())?;
+
+    match status {
+        Status::Ok | Status::OkSimultaneous => (),
+        Status::Nok => {
+            return Err(anyhow!(
+                "simultaneous connection from peer ({}) refused",
+                peer_name
+            ))
+        }
+        Status::Alive => {
+            // recv_status
+            let alive = read_message(stream)?;
+
+            if alive != b"strue" {
+                return Err(anyhow!(
+                    "peer ({}) kept its existing connection ({:?})",
+                    peer_name,
+                    alive
+                ));
+            }
+        }
+    }
+
+    // send_challenge
+    let challenge: u32 = rand::random();
+    let mut send_challenge = vec![b'n'];
+    send_challenge.extend_from_slice(&VERSION.to_be_bytes());
+    send_challenge.extend_from_slice(&FLAGS.to_be_bytes());
+    send_challenge.extend_from_slice(&challenge.to_be_bytes());
+    send_challenge.extend_from_slice(name.name().as_bytes());
+    write_message(stream, &send_challenge)?;
+
+    // recv_challenge_reply
+    let challenge_reply = read_message(stream)?;
+
+    let peer_challenge = match challenge_reply.split_first() {
+        Some((b'r', rest)) if rest.len() == 20 => {
+            let (peer_challenge_bytes, peer_digest) = rest.split_at(4);
+
+            if peer_digest != digest(cookie, challenge) {
+                return Err(anyhow!(
+                    "peer ({}) does not have the same cookie",
+                    peer_name
+                ));
+            }
+
+            u32_from_bytes(peer_challenge_bytes)
+        }
+        _ => {
+            return Err(anyhow!(
+                "expected challenge reply message ({:?})",
+                challenge_reply
+            ))
+        }
+    };
+
+    // send_challenge_ack
+    let mut challenge_ack = vec![b'a'];
+    challenge_ack.extend_from_slice(&digest(cookie, peer_challenge));
+    write_message(stream, &challenge_ack)?;
+
+    authenticated(peer_name, flags)
+}
+
+// Private
+
+const VERSION: u16 = 5;
+
+const PUBLISHED: u32 = 0x1;
+const EXTENDED_REFERENCES: u32 = 0x4;
+const DIST_MONITOR: u32 = 0x8;
+const FUN_TAGS: u32 = 0x10;
+const NEW_FUN_TAGS: u32 = 0x80;
+const EXTENDED_PIDS_PORTS: u32 = 0x100;
+const EXPORT_PTR_TAG: u32 = 0x200;
+const BIT_BINARIES: u32 = 0x400;
+const NEW_FLOATS: u32 = 0x800;
+const UTF8_ATOMS: u32 = 0x10000;
+const MAP_TAG: u32 = 0x20000;
+
+/// The `Peer` named `name`, which is only made an atom now that the peer has the cookie
+fn authenticated(name: String, flags: u32) -> Result<Peer> {
+    let name =
+        Atom::try_from_str(&name).map_err(|error| anyhow!("node name ({}) {}", name, error))?;
+
+    Ok(Peer { name, flags })
+}
+
+fn name_from_bytes(bytes: &[u8]) -> Result<String> {
+    let name = std::str::from_utf8(bytes).context("node name is not UTF-8")?;
+
+    Ok(name.to_string())
+}
+
+/// The peer's name, flags and challenge
+fn challenge_from_message(message: &[u8]) -> Result<(String, u32, u32)> {
+    match message.split_first() {
+        Some((b'n', rest)) if 10 <= rest.len() => {
+            let (version_flags_challenge, name_bytes) = rest.split_at(10);
+            let flags = u32_from_bytes(&version_flags_challenge[2..6]);
+            let challenge = u32_from_bytes(&version_flags_challenge[6..10]);
+            let name = name_from_bytes(name_bytes)?;
+
+            Ok((name, flags, challenge))
+        }
+        _ => Err(anyhow!("expected challenge message ({:?})", message)),
+    }
+}
+
+fn read_message<S: Read>(stream: &mut S) -> Result<Vec<u8>> {
+    let mut len_bytes = [0; 2];
+    stream.read_exact(&mut len_bytes)?;
+
+    let mut message = vec![0; u16::from_be_bytes(len_bytes) as usize];
+    stream.read_exact(&mut message)?;
+
+    Ok(message)
+}
+
+fn u32_from_bytes(bytes: &[u8]) -> u32 {
+    let mut array = [0; 4];
+    array.copy_from_slice(bytes);
+
+    u32::from_be_bytes(array)
+}
+
+fn write_message<S: Write>(stream: &mut S, message: &[u8]) -> Result<()> {
+    let len = message.len() as u16;
+    stream.write_all(&len.to_be_bytes())?;
+    stream.write_all(message)?;
+    stream.flush()?;
+
+    Ok(())
+}
@@ -0,0 +1,72 @@
+//! Links between local processes and processes on other nodes.  Links between two local processes
+//! are kept in `Process::linked_pid_set` instead.
+use std::sync::Arc;
+
+use hashbrown::{HashMap, HashSet};
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Node;
+
+pub fn insert(pid: Pid, external_pid: ExternalPid) {
+    EXTERNAL_PID_SET_BY_PID
+        .lock()
+        .entry(pid)
+        .or_default()
+        .insert(external_pid);
+}
+
+/// Returns whether `pid` was linked to `external_pid`.
+pub fn remove(pid: Pid, external_pid: &ExternalPid) -> bool {
+    let mut external_pid_set_by_pid = EXTERNAL_PID_SET_BY_PID.lock();
+
+    match external_pid_set_by_pid.get_mut(&pid) {
+        Some(external_pid_set) => {
+            let removed = external_pid_set.remove(external_pid);
+
+            if external_pid_set.is_empty() {
+                external_pid_set_by_pid.remove(&pid);
+            }
+
+            removed
+        }
+        None => false,
+    }
+}
+
+/// Removes all the links of `pid`, such as when it exits.
+pub fn take(pid: Pid) -> HashSet<ExternalPid> {
+    EXTERNAL_PID_SET_BY_PID
+        .lock()
+        .remove(&pid)
+        .unwrap_or_default()
+}
+
+/// Removes all the links to processes on `arc_node`, such as when the connection to it is lost.
+pub fn take_node(arc_node: &Arc<Node>) -> Vec<(Pid, ExternalPid)> {
+    let mut taken = Vec::new();
+
+    EXTERNAL_PID_SET_BY_PID
+        .lock()
+        .retain(|pid, external_pid_set| {
+            external_pid_set.retain(|external_pid| {
+                if &external_pid.arc_node() == arc_node {
+                    taken.push((*pid, external_pid.clone()));
+
+                    false
+                } else {
+                    true
+                }
+            });
+
+            !external_pid_set.is_empty()
+        });
+
+    taken
+}
+
+lazy_static! {
+    static ref EXTERNAL_PID_SET_BY_PID: Mutex<HashMap<Pid, HashSet<ExternalPid>>> =
+        Default::default();
+}
@@ -0,0 +1,148 @@
+//! Monitors between local processes and processes on other nodes.  Monitors between two local
+//! processes are kept in `Process::monitor_by_reference` and
+//! `Process::monitored_pid_by_reference` instead.
+use std::sync::Arc;
+
+use hashbrown::HashMap;
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Node;
+use liblumen_alloc::CloneToProcess;
+
+use super::control::Element;
+
+/// A local process monitoring a process on another node.
+pub struct Monitor {
+    pub monitoring_pid: Pid,
+    pub monitored: Monitored,
+}
+
+#[derive(Clone)]
+pub enum Monitored {
+    Pid(ExternalPid),
+    Name { name: Atom, arc_node: Arc<Node> },
+}
+
+impl Monitored {
+    pub fn arc_node(&self) -> Arc<Node> {
+        match self {
+            Monitored::Pid(external_pid) => external_pid.arc_node(),
+            Monitored::Name { arc_node, .. } => arc_node.clone(),
+        }
+    }
+
+    /// The `ToProc` of `MONITOR_P` and `DEMONITOR_P`.
+    pub fn element(&self) -> Element {
+        match self {
+            Monitored::Pid(external_pid) => Element::ExternalPid(external_pid),
+            Monitored::Name { name, .. } => Element::Term(name.encode().unwrap()),
+        }
+    }
+
+    /// The object in the `{'DOWN', Reference, process, Object, Info}` message: the pid or
+    /// `{name, node}`.
+    pub fn object(&self, process: &Process) -> AllocResult<Term> {
+        match self {
+            Monitored::Pid(external_pid) => Ok(external_pid.clone_to_process(process)),
+            Monitored::Name { name, arc_node } => process
+                .tuple_from_slice(&[name.encode().unwrap(), arc_node.name().encode().unwrap()]),
+        }
+    }
+}
+
+/// A process on another node monitoring a local process.
+pub struct RemoteMonitor {
+    pub monitoring: ExternalPid,
+    pub reference: ExternalReference,
+    /// The monitored process was monitored by this registered name instead of its pid.
+    pub monitored_name: Option<Atom>,
+}
+
+pub fn insert(reference: Reference, monitor: Monitor) {
+    MONITOR_BY_REFERENCE.lock().insert(reference, monitor);
+}
+
+pub fn remove(reference: &Reference) -> Option<Monitor> {
+    MONITOR_BY_REFERENCE.lock().remove(reference)
+}
+
+/// Removes all monitors `monitoring_pid` has on other nodes, such as when it exits.
+pub fn take_monitoring(monitoring_pid: Pid) -> Vec<(Reference, Monitor)> {
+    take_monitors(|monitor| monitor.monitoring_pid == monitoring_pid)
+}
+
+/// Removes all monitors of processes on `arc_node`, such as when the connection to it is lost.
+pub fn take_node(arc_node: &Arc<Node>) -> Vec<(Reference, Monitor)> {
+    take_monitors(|monitor| &monitor.monitored.arc_node() == arc_node)
+}
+
+pub fn insert_remote(monitored_pid: Pid, remote_monitor: RemoteMonitor) {
+    REMOTE_MONITOR_VEC_BY_PID
+        .lock()
+        .entry(monitored_pid)
+        .or_default()
+        .push(remote_monitor);
+}
+
+pub fn remove_remote(reference: &ExternalReference) {
+    REMOTE_MONITOR_VEC_BY_PID
+        .lock()
+        .retain(|_, remote_monitor_vec| {
+            remote_monitor_vec.retain(|remote_monitor| &remote_monitor.reference != reference);
+
+            !remote_monitor_vec.is_empty()
+        });
+}
+
+/// Removes all monitors of `monitored_pid` from other nodes, such as when it exits.
+pub fn take_remote(monitored_pid: Pid) -> Vec<RemoteMonitor> {
+    REMOTE_MONITOR_VEC_BY_PID
+        .lock()
+        .remove(&monitored_pid)
+        .unwrap_or_default()
+}
+
+/// Removes all monitors from processes on `arc_node`, such as when the connection to it is lost.
+pub fn remove_remote_node(arc_node: &Arc<Node>) {
+    REMOTE_MONITOR_VEC_BY_PID
+        .lock()
+        .retain(|_, remote_monitor_vec| {
+            remote_monitor_vec
+                .retain(|remote_monitor| &remote_monitor.monitoring.arc_node() != arc_node);
+
+            !remote_monitor_vec.is_empty()
+        });
+}
+
+// Private
+
+fn take_monitors<F>(predicate: F) -> Vec<(Reference, Monitor)>
+where
+    F: Fn(&Monitor) -> bool,
+{
+    let mut monitor_by_reference = MONITOR_BY_REFERENCE.lock();
+    let reference_vec: Vec<Reference> = monitor_by_reference
+        .iter()
+        .filter(|(_, monitor)| predicate(monitor))
+        .map(|(reference, _)| *reference)
+        .collect();
+
+    reference_vec
+        .into_iter()
+        .map(|reference| {
+            let monitor = monitor_by_reference.remove(&reference).unwrap();
+
+            (reference, monitor)
+        })
+        .collect()
+}
+
+lazy_static! {
+    static ref MONITOR_BY_REFERENCE: Mutex<HashMap<Reference, Monitor>> = Default::default();
+    static ref REMOTE_MONITOR_VEC_BY_PID: Mutex<HashMap<Pid, Vec<RemoteMonitor>>> =
+        Default::default();
+}
@@ -74,6 +74,16 @@ pub fn insert(arc_node: Arc<Node>) {
         .unwrap_none();
 }
 
+/// Renames the local node from `nonode@nohost` to `name` when it becomes alive.
+pub fn alive(name: Atom) {
+    let arc_node = node::arc_node();
+
+    let mut arc_node_by_name = RW_LOCK_ARC_NODE_BY_NAME.write();
+    arc_node_by_name.remove(&arc_node.name());
+    arc_node.set_name(name);
+    arc_node_by_name.insert(name, arc_node);
+}
+
 #[derive(Debug, Error)]
 pub enum NodeNotFound {
     #[error("No node with name ({name})")]
@@ -0,0 +1,543 @@
+use std::io::{Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Once};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::*;
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::erts::message::{self, Message};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Node;
+use liblumen_alloc::{atom, exit, CloneToProcess};
+
+use lumen_rt_core::distribution::nodes::node;
+
+use crate::distribution::connection::{self, Connection};
+use crate::distribution::control::{self, Control, Element};
+use crate::distribution::epmd::{Epmd, LocalEpmd};
+use crate::distribution::{self, handshake, nodes};
+use crate::send;
+use crate::test;
+
+#[test]
+fn send_to_external_pid_is_received_by_other_node() {
+    let peer = Peer::start("send_to_external_pid", COOKIE);
+    let external_pid = peer.external_pid(1);
+    let process = test::process::default();
+    let destination = process.external_pid(peer.arc_node.clone(), 1, 0).unwrap();
+
+    assert!(matches!(
+        send::send(destination, atom!("hello"), Default::default(), &process),
+        Ok(send::Sent::Sent)
+    ));
+
+    let decode_process = test::process::default();
+    let Control {
+        operation,
+        tuple,
+        message,
+    } = peer.receive(&decode_process);
+
+    assert_eq!(operation, control::SEND);
+    assert_eq!(tuple[2], external_pid.clone_to_process(&decode_process));
+    assert_eq!(message, Some(atom!("hello")));
+}
+
+#[test]
+fn send_from_other_node_is_received_by_local_process() {
+    let peer = Peer::start("send_from_other_node", COOKIE);
+    peer.connect().unwrap();
+    let process = test::process::default();
+
+    peer.send(
+        control::SEND,
+        &[Atom::str_to_term("").into(), process.pid_term().into()],
+        Some(atom!("hello")),
+    );
+
+    assert!(wait_for_message(&process, atom!("hello")));
+}
+
+#[test]
+fn reg_send_from_other_node_is_received_by_registered_process() {
+    let peer = Peer::start("reg_send_from_other_node", COOKIE);
+    peer.connect().unwrap();
+    let process = test::process::default();
+    let name = Atom::try_from_str("distribution_reg_send_registered").unwrap();
+    assert!(lumen_rt_core::registry::put_atom_to_process(
+        name,
+        process.clone()
+    ));
+    let from = peer.external_pid(1);
+
+    peer.send(
+        control::REG_SEND,
+        &[
+            Element::ExternalPid(&from),
+            Atom::str_to_term("").into(),
+            name.encode().unwrap().into(),
+        ],
+        Some(atom!("hello")),
+    );
+
+    assert!(wait_for_message(&process, atom!("hello")));
+}
+
+#[test]
+fn link_to_external_pid_sends_exit_when_process_exits() {
+    let peer = Peer::start("link_exit", COOKIE);
+    let external_pid = peer.external_pid(1);
+    let process = test::process::default();
+
+    distribution::link(&process, &external_pid);
+
+    let decode_process = test::process::default();
+    let link = peer.receive(&decode_process);
+
+    assert_eq!(link.operation, control::LINK);
+    assert_eq!(link.tuple[1], process.pid_term());
+
+    let reason = atom!("shutdown");
+    distribution::propagate_exit(&process, &exit!(reason, anyhow!("test").into()));
+
+    let exit = peer.receive(&decode_process);
+
+    assert_eq!(exit.operation, control::EXIT);
+    assert_eq!(exit.tuple[1], process.pid_term());
+    assert_eq!(exit.tuple[3], reason);
+}
+
+#[test]
+fn exit_from_linked_external_pid_is_received_by_process_trapping_exits() {
+    let peer = Peer::start("exit_trapped", COOKIE);
+    let external_pid = peer.external_pid(1);
+    let process = test::process::default();
+    process.trap_exit(true);
+
+    distribution::link(&process, &external_pid);
+
+    let decode_process = test::process::default();
+    assert_eq!(peer.receive(&decode_process).operation, control::LINK);
+
+    let reason = atom!("shutdown");
+    peer.send(
+        control::EXIT,
+        &[
+            Element::ExternalPid(&external_pid),
+            process.pid_term().into(),
+            reason.into(),
+        ],
+        None,
+    );
+
+    let from = external_pid.clone_to_process(&process);
+    let message = process
+        .tuple_from_slice(&[atom!("EXIT"), from, reason])
+        .unwrap();
+
+    assert!(wait_for_message(&process, message));
+}
+
+#[test]
+fn monitor_of_external_pid_is_down_when_other_node_sends_monitor_exit() {
+    let peer = Peer::start("monitor_exit", COOKIE);
+    let external_pid = peer.external_pid(1);
+    let process = test::process::default();
+
+    let reference = distribution::monitor_pid(&process, &external_pid).unwrap();
+
+    let decode_process = test::process::default();
+    let monitor = peer.receive(&decode_process);
+
+    assert_eq!(monitor.operation, control::MONITOR_P);
+    assert_eq!(monitor.tuple[1], process.pid_term());
+
+    let reason = atom!("shutdown");
+    peer.send(
+        control::MONITOR_P_EXIT,
+        &[
+            Element::ExternalPid(&external_pid),
+            process.pid_term().into(),
+            monitor.tuple[3].into(),
+            reason.into(),
+        ],
+        None,
+    );
+
+    let object = external_pid.clone_to_process(&process);
+    let message = process
+        .tuple_from_slice(&[atom!("DOWN"), reference, atom!("process"), object, reason])
+        .unwrap();
+
+    assert!(wait_for_message(&process, message));
+}
+
+#[test]
+fn monitor_of_name_on_unreachable_node_is_down_with_noconnection() {
+    start();
+    let process = test::process::default();
+    let name = atom_from_str("name");
+    let node = atom_from_str("unreachable@localhost");
+
+    let reference = distribution::monitor_name(&process, name, node).unwrap();
+
+    let object = process
+        .tuple_from_slice(&[name.encode().unwrap(), node.encode().unwrap()])
+        .unwrap();
+    let message = process
+        .tuple_from_slice(&[
+            atom!("DOWN"),
+            reference,
+            atom!("process"),
+            object,
+            atom!("noconnection"),
+        ])
+        .unwrap();
+
+    assert!(wait_for_message(&process, message));
+}
+
+#[test]
+fn connecting_with_different_cookie_is_refused() {
+    let peer = Peer::start("different_cookie", "different");
+    let external_pid = peer.external_pid(1);
+    let process = test::process::default();
+
+    let reference = distribution::monitor_pid(&process, &external_pid).unwrap();
+
+    let object = external_pid.clone_to_process(&process);
+    let message = process
+        .tuple_from_slice(&[
+            atom!("DOWN"),
+            reference,
+            atom!("process"),
+            object,
+            atom!("noconnection"),
+        ])
+        .unwrap();
+
+    assert!(wait_for_message(&process, message));
+    assert!(peer.connect().is_err());
+}
+
+#[test]
+fn monitor_of_stalled_node_returns_before_handshake_and_is_down_with_noconnection() {
+    start();
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    EPMD.register("stalled", listener.local_addr().unwrap().port())
+        .unwrap();
+    // Accepts, but never replies to the handshake
+    thread::spawn(move || {
+        let _streams: Vec<TcpStream> = listener.incoming().filter_map(Result::ok).collect();
+    });
+    let process = test::process::default();
+    let name = atom_from_str("name");
+    let node = atom_from_str("stalled@localhost");
+
+    let start = Instant::now();
+    let reference = distribution::monitor_name(&process, name, node).unwrap();
+
+    assert!(start.elapsed() < handshake::SETUP_TIME);
+
+    let object = process
+        .tuple_from_slice(&[name.encode().unwrap(), node.encode().unwrap()])
+        .unwrap();
+    let message = process
+        .tuple_from_slice(&[
+            atom!("DOWN"),
+            reference,
+            atom!("process"),
+            object,
+            atom!("noconnection"),
+        ])
+        .unwrap();
+
+    assert!(wait_for_message_within(
+        &process,
+        message,
+        handshake::SETUP_TIME + TIMEOUT
+    ));
+}
+
+#[test]
+fn accepted_connection_without_handshake_is_closed_after_setup_time() {
+    start();
+    let name = node::atom().name();
+    let alive_name = &name[..name.find('@').unwrap()];
+    let port = EPMD.port_please(alive_name, "localhost").unwrap().unwrap();
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream
+        .set_read_timeout(Some(handshake::SETUP_TIME + TIMEOUT))
+        .unwrap();
+
+    let mut buffer = [0; 1];
+
+    assert_eq!(stream.read(&mut buffer).unwrap(), 0);
+}
+
+#[test]
+fn simultaneous_connect_keeps_connection_from_greater_name() {
+    let lesser = atom_from_str("a@localhost");
+    let greater = atom_from_str("b@localhost");
+
+    assert_eq!(
+        handshake::Status::simultaneous(lesser, greater),
+        handshake::Status::OkSimultaneous
+    );
+    assert_eq!(
+        handshake::Status::simultaneous(greater, lesser),
+        handshake::Status::Nok
+    );
+
+    assert!(handshake_with_status(handshake::Status::OkSimultaneous).is_ok());
+    assert!(handshake_with_status(handshake::Status::Nok).is_err());
+}
+
+// Private
+
+#[test]
+fn peer_without_cookie_does_not_make_its_name_an_atom() {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let acceptor = atom_from_str("acceptor@localhost");
+    let peer_name = "peer_without_cookie@localhost";
+    let (sender, receiver) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut status_peer_name = None;
+        let result = handshake::accept(&mut stream, acceptor, COOKIE, |peer_name| {
+            status_peer_name = Some(peer_name.to_string());
+
+            handshake::Status::Ok
+        });
+
+        sender.send((status_peer_name, result.is_ok())).unwrap();
+    });
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+
+    // send_name, with version 5 and no flags
+    let mut send_name = vec![b'n', 0, 5, 0, 0, 0, 0];
+    send_name.extend_from_slice(peer_name.as_bytes());
+    write_handshake_message(&mut stream, &send_name);
+
+    assert_eq!(read_handshake_message(&mut stream), b"sok");
+    // recv_challenge
+    read_handshake_message(&mut stream);
+
+    // send_challenge_reply, with a challenge and a digest of a different cookie
+    write_handshake_message(&mut stream, &[b'r'; 21]);
+
+    let (status_peer_name, accepted) = receiver.recv_timeout(TIMEOUT).unwrap();
+
+    assert_eq!(status_peer_name.as_deref(), Some(peer_name));
+    assert!(!accepted);
+    assert!(Atom::try_from_str_existing(peer_name).is_err());
+}
+
+const COOKIE: &str = "distribution_test_cookie";
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A second node in the same OS process as the local node, speaking the distribution protocol
+/// with the same modules, but keeping what it receives for the test to check.
+struct Peer {
+    arc_node: Arc<Node>,
+    cookie: &'static str,
+    connection: Arc<Mutex<Option<Arc<Connection>>>>,
+    sender: Sender<Vec<u8>>,
+    receiver: Receiver<Vec<u8>>,
+}
+
+impl Peer {
+    fn start(alive_name: &str, cookie: &'static str) -> Self {
+        start();
+
+        let id = NEXT_PEER_NODE_ID.fetch_add(1, Ordering::SeqCst);
+        let name = format!("{}@localhost", alive_name);
+        let arc_node = Arc::new(Node::new(id, atom_from_str(&name), 0));
+        nodes::insert(arc_node.clone());
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        EPMD.register(alive_name, listener.local_addr().unwrap().port())
+            .unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let peer = Peer {
+            arc_node,
+            cookie,
+            connection: Default::default(),
+            sender,
+            receiver,
+        };
+
+        let accept_name = peer.arc_node.name();
+        let accept_connection = peer.connection.clone();
+        let accept_sender = peer.sender.clone();
+        thread::spawn(move || {
+            for result in listener.incoming() {
+                if let Ok(mut stream) = result {
+                    if handshake::accept(&mut stream, accept_name, cookie, |_| {
+                        handshake::Status::Ok
+                    })
+                    .is_ok()
+                    {
+                        let arc_connection = connection::start(
+                            node::arc_node(),
+                            stream,
+                            Forward(accept_sender.clone()),
+                        )
+                        .unwrap();
+                        *accept_connection.lock() = Some(arc_connection);
+                    }
+                }
+            }
+        });
+
+        peer
+    }
+
+    /// Connects from this node to the local node, instead of waiting for the local node to
+    /// connect.
+    fn connect(&self) -> Result<()> {
+        let name = node::atom().name();
+        let alive_name = &name[..name.find('@').unwrap()];
+        let port = EPMD.port_please(alive_name, "localhost")?.unwrap();
+        let mut stream = TcpStream::connect(("127.0.0.1", port))?;
+        handshake::initiate(&mut stream, self.arc_node.name(), self.cookie)?;
+        let arc_connection =
+            connection::start(node::arc_node(), stream, Forward(self.sender.clone()))?;
+        *self.connection.lock() = Some(arc_connection);
+
+        Ok(())
+    }
+
+    fn external_pid(&self, number: usize) -> ExternalPid {
+        ExternalPid::new(self.arc_node.clone(), number, 0).unwrap()
+    }
+
+    /// Decodes the next control message the local node sent to this node into `process`.
+    fn receive(&self, process: &Process) -> Control {
+        let packet = self.receiver.recv_timeout(TIMEOUT).unwrap();
+        let (&pass_through, control_bytes) = packet.split_first().unwrap();
+        assert_eq!(pass_through, control::PASS_THROUGH);
+
+        control::decode(process, control_bytes).unwrap()
+    }
+
+    fn send(&self, operation: u8, elements: &[Element], message: Option<Term>) {
+        let deadline = Instant::now() + TIMEOUT;
+
+        loop {
+            if let Some(arc_connection) = self.connection.lock().as_ref() {
+                arc_connection.send(operation, elements, message).unwrap();
+
+                break;
+            }
+
+            assert!(
+                Instant::now() < deadline,
+                "{} is not connected",
+                self.arc_node.name()
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+struct Forward(Sender<Vec<u8>>);
+
+impl connection::Handler for Forward {
+    fn packet(&mut self, _arc_connection: &Arc<Connection>, packet: &[u8]) {
+        let _ = self.0.send(packet.to_vec());
+    }
+
+    fn closed(&mut self, _arc_connection: &Arc<Connection>) {}
+}
+
+fn write_handshake_message(stream: &mut TcpStream, message: &[u8]) {
+    stream
+        .write_all(&(message.len() as u16).to_be_bytes())
+        .unwrap();
+    stream.write_all(message).unwrap();
+}
+
+fn atom_from_str(name: &str) -> Atom {
+    Atom::try_from_str(name).unwrap()
+}
+
+fn has_message(process: &Process, data: Term) -> bool {
+    process.mailbox.lock().borrow().iter().any(|message| {
+        &data
+            == match message {
+                Message::Process(message::Process { data }) => data,
+                Message::HeapFragment(message::HeapFragment { data, .. }) => data,
+            }
+    })
+}
+
+/// Makes the local node alive once for all tests, as the distribution state is global.
+fn start() {
+    START.call_once(|| {
+        distribution::start("distribution_test@localhost", COOKIE, EPMD.clone()).unwrap()
+    });
+}
+
+/// Initiates a handshake with a node that accepts it with `status`.
+fn handshake_with_status(status: handshake::Status) -> Result<handshake::Peer> {
+    let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let acceptor = atom_from_str("acceptor@localhost");
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let _ = handshake::accept(&mut stream, acceptor, COOKIE, |_| status);
+    });
+
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.set_read_timeout(Some(TIMEOUT)).unwrap();
+
+    handshake::initiate(&mut stream, atom_from_str("initiator@localhost"), COOKIE)
+}
+
+fn read_handshake_message(stream: &mut TcpStream) -> Vec<u8> {
+    let mut len_bytes = [0; 2];
+    stream.read_exact(&mut len_bytes).unwrap();
+
+    let mut message = vec![0; u16::from_be_bytes(len_bytes) as usize];
+    stream.read_exact(&mut message).unwrap();
+
+    message
+}
+
+fn wait_for_message(process: &Process, data: Term) -> bool {
+    wait_for_message_within(process, data, TIMEOUT)
+}
+
+fn wait_for_message_within(process: &Process, data: Term, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+
+    while !has_message(process, data) {
+        if deadline <= Instant::now() {
+            return false;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    true
+}
+
+lazy_static! {
+    static ref EPMD: Arc<LocalEpmd> = Default::default();
+}
+
+static NEXT_PEER_NODE_ID: AtomicUsize = AtomicUsize::new(1_000);
+static START: Once = Once::new();
@@ -36,8 +36,11 @@ extern crate lazy_static;
 
 extern crate chrono;
 
+pub mod application;
 pub mod binary;
 pub mod binary_to_string;
+#[cfg(not(any(test, target_arch = "wasm32")))]
+mod boot;
 // `pub` or `examples/spawn-chain`
 pub mod code;
 #[cfg(not(any(test, target_arch = "wasm32")))]
@@ -46,10 +49,13 @@ pub mod distribution;
 pub mod future;
 mod logging;
 pub mod number;
+pub mod port;
 pub mod process;
 // `pub` for `examples/spawn-chain`
 pub mod scheduler;
 pub mod send;
+#[cfg(not(any(test, target_arch = "wasm32")))]
+mod shell;
 pub mod stacktrace;
 // `pub` for `examples/spawn-chain`
 pub mod system;
@@ -76,16 +82,17 @@ fn main() -> impl ::std::process::Termination + 'static {
 
 #[cfg(not(any(test, target_arch = "wasm32")))]
 fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()> {
-    use self::config::Config;
+    use self::config::{Command, Config};
     use self::logging::Logger;
     use self::scheduler::Scheduler;
+    use self::shell::Shell;
     use self::system::break_handler::{self, Signal};
     use bus::Bus;
     use log::Level;
-    use std::thread;
+    use lumen_rt_core::time::monotonic;
 
     // Load system configuration
-    let _config = match Config::from_argv(name.to_string(), version.to_string(), argv) {
+    let mut config = match Config::from_argv(name.to_string(), version.to_string(), argv) {
         Ok(config) => config,
         Err(err) => {
             eprintln!("Config error: {}", err);
@@ -93,6 +100,8 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
         }
     };
 
+    monotonic::set_clock(config.clock);
+
     // This bus is used to receive signals across threads in the system
     let mut bus: Bus<break_handler::Signal> = Bus::new(1);
     // Each thread needs a reader
@@ -103,10 +112,66 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
     // Start logger
     Logger::init(Level::Info).expect("Unexpected failure initializing logger");
 
+    application::merge_env(std::mem::take(&mut config.config));
+
+    if let Some(node_name) = config.node_name() {
+        let cookie = match config.cookie() {
+            Ok(cookie) => cookie,
+            Err(err) => {
+                eprintln!("Config error: {}", err);
+                return Err(());
+            }
+        };
+        let epmd = std::sync::Arc::new(distribution::epmd::TcpEpmd::default());
+
+        if let Err(err) = distribution::start(&node_name, &cookie, epmd) {
+            eprintln!("Distribution error: {:?}", err);
+            return Err(());
+        }
+    }
+
     let scheduler = Scheduler::current();
+
+    // The main thread is the first scheduler, and the others only run processes they steal
+    if let Err(err) = Scheduler::spawn_threads(config.schedulers - 1) {
+        eprintln!("Scheduler error: {}", err);
+        return Err(());
+    }
+
+    if let Some(boot_script) = config.boot.take() {
+        if let Err(err) = boot::run(scheduler.clone(), boot_script) {
+            eprintln!("Boot error: {:?}", err);
+            return Err(());
+        }
+    }
+
+    let mut shell = match config.command {
+        Command::Run => None,
+        Command::Shell => match Shell::start(scheduler.clone()) {
+            Ok(shell) => Some(shell),
+            Err(err) => {
+                eprintln!("Shell error: {:?}", err);
+                return Err(());
+            }
+        },
+        Command::RemoteShell(ref node) => {
+            eprintln!(
+                "Remote shells are not supported yet, could not connect to {}",
+                node
+            );
+            return Err(());
+        }
+    };
+
     loop {
         // Run the scheduler for a cycle
         let scheduled = scheduler.run_once();
+        // Evaluate any input to the shell, and stop when the shell is quit
+        if let Some(ref mut shell) = shell {
+            if !shell.poll(&scheduler, &mut rx1) {
+                break;
+            }
+        }
         // Check for system signals, and terminate if needed
         if let Ok(sig) = rx1.try_recv() {
             match sig {
@@ -137,18 +202,18 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
         if scheduled {
             continue;
         }
-        // Otherwise,
-        // In some configurations, it makes more sense for us to spin and use
-        // spin_loop_hint here instead; namely when we're supposed to be the primary
-        // software on a system, and threads are pinned to cores, it makes no sense
-        // to yield to the system scheduler. However on a system with contention for
-        // system resources, or where threads aren't pinned to cores, we're better off
-        // explicitly yielding to the scheduler, rather than waiting to be preempted at
-        // a potentially inopportune time.
-        //
-        // In any case, for now, we always explicitly yield until we've got proper support
-        // for configuring the system
-        thread::yield_now()
+        // Otherwise, try to take work from a busier scheduler before going idle
+        if scheduler.steal() {
+            continue;
+        }
+        // With the virtual clock, waiting on the next timer would never end, so once every
+        // scheduler is idle, the clock is moved to it instead
+        if timer::skip_to_next_timeout() {
+            continue;
+        }
+        // There is nothing to steal either, so park until another scheduler gives us work or
+        // the next timer slot is due, instead of spinning on empty run queues.
+        scheduler.park();
     }
 
     Ok(())
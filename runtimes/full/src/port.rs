@@ -0,0 +1,516 @@
+//! [Ports](http://erlang.org/doc/reference_manual/ports.html): external OS programs spawned by
+//! `open_port/2` that talk to the process that opened them over their standard input and output.
+//!
+//! The process that opens a port is its owner (the connected process) and is linked to it.  Each
+//! open port has a thread reading the program's standard output, which sends what it reads to the
+//! owner as `{Port, {data, Data}}`, and a thread that owns the program, writing commands to its
+//! standard input, so that a program that is slow to read never blocks the scheduler.  When the
+//! program exits the port closes, and when the owner exits the port is closed for it.  Closing a
+//! port kills its program.
+mod name;
+mod options;
+#[cfg(all(not(target_arch = "wasm32"), test))]
+mod test;
+
+use std::io::{self, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::*;
+use hashbrown::{HashMap, HashSet};
+
+use liblumen_core::locks::{Mutex, RwLock};
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::{AllocResult, RuntimeException};
+use liblumen_alloc::erts::process::alloc::Heap;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+
+use lumen_rt_core::registry::pid_to_process;
+
+use crate::process::{self, exit_in_heap_fragment};
+use crate::send::send_to_process;
+
+pub use name::Name;
+pub use options::{Options, Packet};
+
+/// Spawns the program named by `name` and opens a port to it owned by `process`.
+pub fn open(process: &Process, name: Name, options: Options) -> io::Result<Port> {
+    let mut command = name.command(&options);
+    command.stdin(Stdio::piped()).stdout(Stdio::piped());
+
+    let mut child = command.spawn()?;
+    let stdin = child.stdin.take().unwrap();
+    let stdout = child.stdout.take().unwrap();
+
+    let port = Port::next();
+    let owner = process.pid();
+    let (sender, receiver) = mpsc::channel();
+    let reader_sender = sender.clone();
+    let mut linked_pid_set = HashSet::new();
+    linked_pid_set.insert(owner);
+
+    let arc_control_block = Arc::new(ControlBlock {
+        port,
+        name,
+        options,
+        owner,
+        os_pid: child.id(),
+        linked_pid_set: Mutex::new(linked_pid_set),
+        sender: Mutex::new(sender),
+        input: Default::default(),
+        output: Default::default(),
+    });
+
+    ARC_CONTROL_BLOCK_BY_PORT
+        .write()
+        .insert(port, arc_control_block.clone());
+
+    let writer_arc_control_block = arc_control_block.clone();
+    let result = thread::Builder::new()
+        .name(format!("{} writer", port))
+        .spawn(move || write(writer_arc_control_block, child, stdin, receiver))
+        .and_then(|_| {
+            thread::Builder::new()
+                .name(format!("{} reader", port))
+                .spawn(move || read(arc_control_block, stdout, reader_sender))
+        });
+
+    match result {
+        Ok(_) => Ok(port),
+        Err(error) => {
+            if let Some(arc_control_block) = ARC_CONTROL_BLOCK_BY_PORT.write().remove(&port) {
+                let _ = arc_control_block.sender.lock().send(Request::Close);
+            }
+
+            Err(error)
+        }
+    }
+}
+
+/// Queues `bytes` to be written to the standard input of the program by the port's writer thread,
+/// framed as the port's `packet` option says.
+pub fn command(port: Port, bytes: &[u8]) -> Result<()> {
+    let arc_control_block = control_block(port)?;
+    let mut framed = arc_control_block.options.packet.header(bytes.len())?;
+    framed.extend_from_slice(bytes);
+    let len = framed.len();
+
+    arc_control_block
+        .sender
+        .lock()
+        .send(Request::Command(framed))
+        .map_err(|_| not_open(port))?;
+    arc_control_block
+        .output
+        .fetch_add(len as u64, Ordering::SeqCst);
+
+    Ok(())
+}
+
+/// Closes `port`, returning whether it was open.  The program is killed and the processes linked
+/// to the port get a `normal` exit signal.
+pub fn close(port: Port) -> bool {
+    close_with_reason(port, atom!("normal"))
+}
+
+/// What `port_info/1` reports about `port`, if it is open.
+pub fn info(port: Port) -> Option<Info> {
+    control_block(port).ok().map(|arc_control_block| Info {
+        name: arc_control_block.name.to_string(),
+        links: arc_control_block
+            .linked_pid_set
+            .lock()
+            .iter()
+            .copied()
+            .collect(),
+        id: port.as_usize(),
+        connected: arc_control_block.owner,
+        input: arc_control_block.input.load(Ordering::SeqCst),
+        output: arc_control_block.output.load(Ordering::SeqCst),
+        os_pid: arc_control_block.os_pid,
+    })
+}
+
+pub struct Info {
+    pub name: String,
+    pub links: Vec<Pid>,
+    pub id: usize,
+    pub connected: Pid,
+    pub input: u64,
+    pub output: u64,
+    pub os_pid: u32,
+}
+
+/// Links `process` to `port`, returning whether `port` is open.
+pub fn link(process: &Process, port: Port) -> bool {
+    match control_block(port) {
+        Ok(arc_control_block) => {
+            arc_control_block
+                .linked_pid_set
+                .lock()
+                .insert(process.pid());
+
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+pub fn unlink(process: &Process, port: Port) {
+    if let Ok(arc_control_block) = control_block(port) {
+        arc_control_block
+            .linked_pid_set
+            .lock()
+            .remove(&process.pid());
+    }
+}
+
+/// Breaks the links of the exiting `process` to ports.  The ports it owns are closed, as are the
+/// ports it is linked to when it exits abnormally.
+pub fn propagate_exit(process: &Process, exception: &RuntimeException) {
+    let pid = process.pid();
+    let reason = exception.reason().unwrap_or_else(|| atom!("system_error"));
+
+    let linked_port_vec: Vec<(Port, Pid)> = ARC_CONTROL_BLOCK_BY_PORT
+        .read()
+        .values()
+        .filter(|arc_control_block| arc_control_block.linked_pid_set.lock().remove(&pid))
+        .map(|arc_control_block| (arc_control_block.port, arc_control_block.owner))
+        .collect();
+
+    for (port, owner) in linked_port_vec {
+        if owner == pid || reason != atom!("normal") {
+            close_with_reason(port, reason);
+        }
+    }
+}
+
+// Private
+
+struct ControlBlock {
+    port: Port,
+    name: Name,
+    options: Options,
+    owner: Pid,
+    os_pid: u32,
+    linked_pid_set: Mutex<HashSet<Pid>>,
+    /// Requests for the thread that owns the program.
+    sender: Mutex<Sender<Request>>,
+    /// Bytes read from the program.
+    input: AtomicU64,
+    /// Bytes sent to the program.
+    output: AtomicU64,
+}
+
+enum Request {
+    /// Framed bytes to write to the standard input of the program.
+    Command(Vec<u8>),
+    /// The program closed its standard output, so it has exited or is about to.
+    Exited,
+    /// The port was closed, so the program is killed.
+    Close,
+}
+
+impl ControlBlock {
+    /// `{Port, {data, Data}}`, where `Data` is a binary with the `binary` option, otherwise a list
+    /// of bytes.
+    fn data_message(&self, process: &Process, bytes: &[u8]) -> AllocResult<Term> {
+        let data = if self.options.binary {
+            process.binary_from_bytes(bytes)?
+        } else {
+            let mut byte_term_vec = Vec::with_capacity(bytes.len());
+
+            for byte in bytes {
+                byte_term_vec.push(process.integer(*byte)?);
+            }
+
+            process.list_from_slice(&byte_term_vec)?
+        };
+        let tagged_data = process.tuple_from_slice(&[atom!("data"), data])?;
+
+        process.tuple_from_slice(&[self.port.encode().unwrap(), tagged_data])
+    }
+
+    /// `{Port, {exit_status, Status}}`
+    fn exit_status_message(&self, process: &Process, status: ExitStatus) -> AllocResult<Term> {
+        let status_term = process.integer(exit_status_code(status))?;
+        let tagged_status = process.tuple_from_slice(&[atom!("exit_status"), status_term])?;
+
+        process.tuple_from_slice(&[self.port.encode().unwrap(), tagged_status])
+    }
+}
+
+/// A process to build messages on outside of any running process, which is reused while it has
+/// enough free heap.
+#[derive(Default)]
+struct Scratch {
+    option_process: Option<Process>,
+}
+
+impl Scratch {
+    /// A process with at least `need` words free on its heap.
+    fn process(&mut self, need: usize) -> AllocResult<&Process> {
+        let enough_heap = match &self.option_process {
+            Some(process) => {
+                need <= process.acquire_heap().heap_available()
+                    || process.garbage_collect(need, &mut []).is_ok()
+            }
+            None => false,
+        };
+
+        if !enough_heap {
+            self.option_process = Some(process::scratch("port", "read", need)?);
+        }
+
+        Ok(self.option_process.as_ref().unwrap())
+    }
+}
+
+/// Enough words for each `{'EXIT', Port, Reason}` tuple.  `Reason` itself stays on the heap of
+/// the process it came from and is copied when the message is sent.
+const EXIT_WORDS: usize = 4;
+
+/// Enough words for the tuples around the data in a `{Port, {data, Data}}` or
+/// `{Port, {exit_status, Status}}` message.
+const MESSAGE_WORDS: usize = 8;
+
+/// A list of bytes needs a cons cell of 2 words per byte, which is more than a binary of the same
+/// bytes.
+const DATA_WORDS_PER_BYTE: usize = 2;
+
+const READ_BUFFER_SIZE: usize = 4096;
+
+/// How often a program that closed its standard output is checked for having exited, while
+/// waiting for the port to be closed instead.
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+lazy_static! {
+    static ref ARC_CONTROL_BLOCK_BY_PORT: RwLock<HashMap<Port, Arc<ControlBlock>>> =
+        Default::default();
+}
+
+fn close_with_reason(port: Port, reason: Term) -> bool {
+    let option_arc_control_block = ARC_CONTROL_BLOCK_BY_PORT.write().remove(&port);
+
+    match option_arc_control_block {
+        Some(arc_control_block) => {
+            let _ = arc_control_block.sender.lock().send(Request::Close);
+
+            let linked_pid_vec: Vec<Pid> =
+                arc_control_block.linked_pid_set.lock().drain().collect();
+            exit_linked(port, linked_pid_vec, reason);
+
+            true
+        }
+        None => false,
+    }
+}
+
+fn control_block(port: Port) -> Result<Arc<ControlBlock>> {
+    ARC_CONTROL_BLOCK_BY_PORT
+        .read()
+        .get(&port)
+        .cloned()
+        .ok_or_else(|| not_open(port))
+}
+
+#[cfg(unix)]
+fn exit_status_code(status: ExitStatus) -> i32 {
+    use std::os::unix::process::ExitStatusExt;
+
+    // Like a shell, a program killed by a signal exits with 128 plus the signal number.
+    status
+        .code()
+        .or_else(|| status.signal().map(|signal| 128 + signal))
+        .unwrap_or(0)
+}
+
+#[cfg(not(unix))]
+fn exit_status_code(status: ExitStatus) -> i32 {
+    status.code().unwrap_or(0)
+}
+
+/// Sends the exit signal from `port` exiting with `reason` to the processes that were linked to
+/// it.
+fn exit_linked(port: Port, linked_pid_vec: Vec<Pid>, reason: Term) {
+    if linked_pid_vec.is_empty() {
+        return;
+    }
+
+    if let Ok(process) = process::scratch("port", "exit", EXIT_WORDS * linked_pid_vec.len()) {
+        for pid in linked_pid_vec {
+            if let Some(arc_process) = pid_to_process(&pid) {
+                let _ = exit_signal(&process, &arc_process, port, reason);
+            }
+        }
+    }
+}
+
+/// Delivers the exit signal from a linked `port` to `destination`, building any `'EXIT'` message
+/// on the heap of `process`.
+fn exit_signal(
+    process: &Process,
+    destination: &Process,
+    port: Port,
+    reason: Term,
+) -> AllocResult<()> {
+    if destination.traps_exit() {
+        let message = process.tuple_from_slice(&[atom!("EXIT"), port.encode().unwrap(), reason])?;
+
+        send_to_process(destination, message)?;
+    } else if reason != atom!("normal") {
+        exit_in_heap_fragment(
+            destination,
+            reason,
+            anyhow!("exit signal from {}", port).into(),
+        );
+    }
+
+    Ok(())
+}
+
+fn not_open(port: Port) -> anyhow::Error {
+    anyhow!("port ({}) is not open", port)
+}
+
+/// Reads the standard output of the program until it is closed, sending each read, or each packet
+/// with the `packet` option, to the owner of the port.  Then the writer thread is told, so that it
+/// waits for the program to exit.
+fn read(arc_control_block: Arc<ControlBlock>, mut stdout: ChildStdout, sender: Sender<Request>) {
+    let mut scratch = Scratch::default();
+    let mut buffer = [0; READ_BUFFER_SIZE];
+    let mut unframed = Vec::new();
+
+    loop {
+        let len = match stdout.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(ref error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(_) => break,
+        };
+
+        arc_control_block
+            .input
+            .fetch_add(len as u64, Ordering::SeqCst);
+
+        match arc_control_block.options.packet {
+            Packet::Stream => send_data(&arc_control_block, &mut scratch, &buffer[..len]),
+            Packet::Length(header_len) => {
+                unframed.extend_from_slice(&buffer[..len]);
+
+                while let Some(packet) = options::unframe(&mut unframed, header_len) {
+                    send_data(&arc_control_block, &mut scratch, &packet);
+                }
+            }
+        }
+    }
+
+    let _ = sender.send(Request::Exited);
+}
+
+/// Owns the program, writing commands to its standard input until the port is closed, when it is
+/// killed, or until it exits, when the port closes if it is still open.
+fn write(
+    arc_control_block: Arc<ControlBlock>,
+    mut child: Child,
+    stdin: ChildStdin,
+    receiver: Receiver<Request>,
+) {
+    let port = arc_control_block.port;
+    let mut option_stdin = Some(stdin);
+    let mut exited = false;
+
+    loop {
+        let request = if exited {
+            match child.try_wait() {
+                Ok(Some(status)) => {
+                    if arc_control_block.options.exit_status {
+                        send_to_owner(
+                            &arc_control_block,
+                            &mut Scratch::default(),
+                            MESSAGE_WORDS,
+                            |process| arc_control_block.exit_status_message(process, status),
+                        );
+                    }
+
+                    break;
+                }
+                Ok(None) => (),
+                Err(_) => break,
+            }
+
+            match receiver.recv_timeout(EXIT_POLL_INTERVAL) {
+                Ok(request) => request,
+                Err(RecvTimeoutError::Timeout) => continue,
+                Err(RecvTimeoutError::Disconnected) => Request::Close,
+            }
+        } else {
+            receiver.recv().unwrap_or(Request::Close)
+        };
+
+        match request {
+            Request::Command(framed) => {
+                if let Some(stdin) = option_stdin.as_mut() {
+                    if stdin
+                        .write_all(&framed)
+                        .and_then(|_| stdin.flush())
+                        .is_err()
+                    {
+                        option_stdin = None;
+                        // Like in OTP, a port that can't be written to exits instead of failing
+                        // the command.
+                        close_with_reason(port, Atom::str_to_term("epipe"));
+                    }
+                }
+            }
+            Request::Exited => exited = true,
+            Request::Close => {
+                drop(option_stdin);
+                let _ = child.kill();
+                let _ = child.wait();
+
+                return;
+            }
+        }
+    }
+
+    close(port);
+}
+
+fn send_data(arc_control_block: &ControlBlock, scratch: &mut Scratch, bytes: &[u8]) {
+    send_to_owner(
+        arc_control_block,
+        scratch,
+        DATA_WORDS_PER_BYTE * bytes.len() + MESSAGE_WORDS,
+        |process| arc_control_block.data_message(process, bytes),
+    );
+}
+
+/// Sends the message built by `message` to the owner of the port, unless the port was closed in
+/// the meantime.
+fn send_to_owner<M>(
+    arc_control_block: &ControlBlock,
+    scratch: &mut Scratch,
+    need: usize,
+    message: M,
+) where
+    M: FnOnce(&Process) -> AllocResult<Term>,
+{
+    if control_block(arc_control_block.port).is_err() {
+        return;
+    }
+
+    if let Some(arc_owner) = pid_to_process(&arc_control_block.owner) {
+        if let Ok(process) = scratch.process(need) {
+            if let Ok(message_term) = message(process) {
+                let _ = send_to_process(&arc_owner, message_term);
+            }
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{self, Display};
+use std::process::Command;
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use lumen_rt_core::context::term_is_not_type;
+
+use crate::binary_to_string::binary_to_string;
+
+use super::Options;
+
+/// The `PortName` of `open_port/2`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Name {
+    /// `{spawn, Command}` runs `Command` with `/bin/sh`, so it can contain arguments.
+    Spawn(String),
+    /// `{spawn_executable, FileName}` runs `FileName` directly, with the arguments from the
+    /// `{args, Args}` option.
+    SpawnExecutable(String),
+}
+
+impl Name {
+    pub(super) fn command(&self, options: &Options) -> Command {
+        match self {
+            Name::Spawn(command) => {
+                let mut sh = Command::new("/bin/sh");
+                sh.arg("-c").arg(format!("exec {}", command));
+
+                sh
+            }
+            Name::SpawnExecutable(file_name) => {
+                let mut executable = Command::new(file_name);
+                executable.args(&options.args);
+
+                executable
+            }
+        }
+    }
+}
+
+impl Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Name::Spawn(command) => write!(f, "{}", command),
+            Name::SpawnExecutable(file_name) => write!(f, "{}", file_name),
+        }
+    }
+}
+
+const SUPPORTED_NAMES_CONTEXT: &str =
+    "supported port names are {spawn, Command} or {spawn_executable, FileName}";
+
+impl TryFrom<Term> for Name {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> std::result::Result<Name, Self::Error> {
+        let tuple: Boxed<Tuple> = term
+            .try_into()
+            .with_context(|| format!("port name ({}) is not a tuple", term))
+            .context(SUPPORTED_NAMES_CONTEXT)?;
+
+        if tuple.len() != 2 {
+            return Err(anyhow!("port name ({}) is not a 2-tuple", term))
+                .context(SUPPORTED_NAMES_CONTEXT);
+        }
+
+        let tag: Atom = tuple[0]
+            .try_into()
+            .with_context(|| format!("port name ({}) tag is not an atom", term))
+            .context(SUPPORTED_NAMES_CONTEXT)?;
+
+        match tag.name() {
+            "spawn" => term_try_into_string("Command", tuple[1]).map(Name::Spawn),
+            "spawn_executable" => {
+                term_try_into_string("FileName", tuple[1]).map(Name::SpawnExecutable)
+            }
+            _ => Err(anyhow!("port name ({}) tag is not supported", term))
+                .context(SUPPORTED_NAMES_CONTEXT),
+        }
+    }
+}
+
+/// Strings in ports names and options can be charlists or binaries.
+pub(super) fn term_try_into_string(name: &str, term: Term) -> Result<String> {
+    let option_string = match term.decode()? {
+        TypedTerm::Nil => Some(String::new()),
+        TypedTerm::List(cons) => cons.try_into().ok(),
+        _ => binary_to_string(term).ok(),
+    };
+
+    option_string.ok_or_else(|| anyhow!(term_is_not_type(name, term, "a string or binary")))
+}
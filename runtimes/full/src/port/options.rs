@@ -0,0 +1,188 @@
+use std::convert::{TryFrom, TryInto};
+
+use anyhow::*;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use lumen_rt_core::proplist::TryPropListFromTermError;
+
+use super::name::term_try_into_string;
+
+/// The `PortSettings` of `open_port/2`.
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// Arguments for `{spawn_executable, FileName}` from `{args, Args}`.
+    pub args: Vec<String>,
+    /// Data is sent to the owner as binaries instead of lists of bytes.
+    pub binary: bool,
+    /// The owner is sent `{Port, {exit_status, Status}}` when the program exits.
+    pub exit_status: bool,
+    pub packet: Packet,
+}
+
+impl Options {
+    fn put_option_atom(&mut self, atom: Atom) -> Result<&Self> {
+        match atom.name() {
+            "binary" => {
+                self.binary = true;
+
+                Ok(self)
+            }
+            "exit_status" => {
+                self.exit_status = true;
+
+                Ok(self)
+            }
+            "stream" => {
+                self.packet = Packet::Stream;
+
+                Ok(self)
+            }
+            // The program is always talked to over its standard input and output.
+            "use_stdio" => Ok(self),
+            name => Err(TryPropListFromTermError::AtomName(name).into()),
+        }
+    }
+
+    fn put_option_term(&mut self, term: Term) -> Result<&Self> {
+        match term.decode().unwrap() {
+            TypedTerm::Atom(atom) => self.put_option_atom(atom),
+            TypedTerm::Tuple(tuple) => self.put_option_tuple(&tuple),
+            _ => Err(TryPropListFromTermError::PropertyType.into()),
+        }
+    }
+
+    fn put_option_tuple(&mut self, tuple: &Tuple) -> Result<&Self> {
+        if tuple.len() == 2 {
+            let atom: Atom = tuple[0]
+                .try_into()
+                .map_err(|_| TryPropListFromTermError::KeywordKeyType)?;
+
+            match atom.name() {
+                "args" => {
+                    self.args = term_try_into_string_vec(tuple[1]).context("args")?;
+
+                    Ok(self)
+                }
+                "packet" => {
+                    let header_len: u8 = tuple[1].try_into().context("packet")?;
+
+                    match header_len {
+                        1 | 2 | 4 => {
+                            self.packet = Packet::Length(header_len);
+
+                            Ok(self)
+                        }
+                        _ => Err(anyhow!("packet ({}) is not 1, 2, or 4", tuple[1])),
+                    }
+                }
+                name => Err(TryPropListFromTermError::KeywordKeyName(name).into()),
+            }
+        } else {
+            Err(TryPropListFromTermError::TupleNotPair.into())
+        }
+    }
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            args: Vec::new(),
+            binary: false,
+            exit_status: false,
+            packet: Packet::Stream,
+        }
+    }
+}
+
+impl TryFrom<Term> for Options {
+    type Error = anyhow::Error;
+
+    fn try_from(term: Term) -> std::result::Result<Options, Self::Error> {
+        let mut options: Options = Default::default();
+        let mut options_term = term;
+
+        loop {
+            match options_term.decode().unwrap() {
+                TypedTerm::Nil => return Ok(options),
+                TypedTerm::List(cons) => {
+                    options.put_option_term(cons.head)?;
+                    options_term = cons.tail;
+
+                    continue;
+                }
+                _ => return Err(ImproperListError.into()),
+            }
+        }
+    }
+}
+
+/// How data to and from the program is framed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Packet {
+    /// Data is sent and received as is, with no framing.
+    Stream,
+    /// Each packet is preceded by its length as a big-endian integer of this many bytes.
+    Length(u8),
+}
+
+impl Packet {
+    /// The length header for a packet of `len` bytes.
+    pub(super) fn header(self, len: usize) -> Result<Vec<u8>> {
+        match self {
+            Packet::Stream => Ok(Vec::new()),
+            Packet::Length(header_len) => {
+                let header_len = header_len as usize;
+
+                if (1_u64 << (8 * header_len)) <= (len as u64) {
+                    Err(anyhow!(
+                        "data ({} bytes) is too long for {{packet, {}}}",
+                        len,
+                        header_len
+                    ))
+                } else {
+                    let len_bytes = (len as u64).to_be_bytes();
+
+                    Ok(len_bytes[(8 - header_len)..].to_vec())
+                }
+            }
+        }
+    }
+}
+
+/// Removes the first whole packet from `unframed`, which starts with a length header of
+/// `header_len` bytes.
+pub(super) fn unframe(unframed: &mut Vec<u8>, header_len: u8) -> Option<Vec<u8>> {
+    let header_len = header_len as usize;
+
+    if unframed.len() < header_len {
+        return None;
+    }
+
+    let len = unframed[..header_len]
+        .iter()
+        .fold(0_usize, |acc, byte| (acc << 8) | (*byte as usize));
+
+    if unframed.len() < header_len + len {
+        return None;
+    }
+
+    let packet = unframed[header_len..(header_len + len)].to_vec();
+    unframed.drain(..(header_len + len));
+
+    Some(packet)
+}
+
+fn term_try_into_string_vec(list: Term) -> Result<Vec<String>> {
+    match list.decode()? {
+        TypedTerm::Nil => Ok(Vec::new()),
+        TypedTerm::List(cons) => cons
+            .into_iter()
+            .map(|result| match result {
+                Ok(element) => term_try_into_string("arg", element),
+                Err(_) => Err(ImproperListError.into()),
+            })
+            .collect(),
+        _ => Err(anyhow!("args ({}) is not a list", list)),
+    }
+}
@@ -0,0 +1,278 @@
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use anyhow::*;
+
+use liblumen_alloc::erts::message::{self, Message};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{atom, exit};
+
+use crate::port::{self, Name, Options, Packet};
+use crate::test;
+
+#[test]
+fn command_is_echoed_back_as_list_of_bytes() {
+    let process = test::process::default();
+    let port = port::open(&process, cat(), Default::default()).unwrap();
+
+    port::command(port, b"hello").unwrap();
+
+    let message = data_message(&process, port, process.charlist_from_str("hello").unwrap());
+
+    assert!(wait_for_message(&process, message));
+
+    assert!(port::close(port));
+}
+
+#[test]
+fn with_binary_and_packet_each_command_is_echoed_back_as_its_own_binary() {
+    let process = test::process::default();
+    let options = Options {
+        binary: true,
+        packet: Packet::Length(2),
+        ..Default::default()
+    };
+    let port = port::open(&process, cat(), options).unwrap();
+
+    port::command(port, b"first").unwrap();
+    port::command(port, b"second").unwrap();
+
+    for bytes in &[&b"first"[..], &b"second"[..]] {
+        let message = data_message(&process, port, process.binary_from_bytes(bytes).unwrap());
+
+        assert!(wait_for_message(&process, message));
+    }
+
+    assert!(port::close(port));
+}
+
+#[test]
+fn with_packet_command_too_long_for_header_errors() {
+    let process = test::process::default();
+    let options = Options {
+        packet: Packet::Length(1),
+        ..Default::default()
+    };
+    let port = port::open(&process, cat(), options).unwrap();
+
+    assert!(port::command(port, &[0; 256]).is_err());
+
+    assert!(port::close(port));
+}
+
+#[test]
+fn with_exit_status_sends_exit_status_when_program_exits() {
+    let process = test::process::default();
+    let options = Options {
+        exit_status: true,
+        ..Default::default()
+    };
+    let port = port::open(&process, Name::Spawn("exit 3".to_string()), options).unwrap();
+
+    let tagged_status = process
+        .tuple_from_slice(&[atom!("exit_status"), process.integer(3).unwrap()])
+        .unwrap();
+    let message = process
+        .tuple_from_slice(&[port.encode().unwrap(), tagged_status])
+        .unwrap();
+
+    assert!(wait_for_message(&process, message));
+    assert!(wait_until_closed(port));
+}
+
+#[test]
+fn close_closes_port_once() {
+    let process = test::process::default();
+    let port = port::open(&process, cat(), Default::default()).unwrap();
+
+    assert!(port::info(port).is_some());
+    assert!(port::close(port));
+    assert!(port::info(port).is_none());
+    assert!(!port::close(port));
+    assert!(port::command(port, b"closed").is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn close_kills_program_that_ignores_end_of_file() {
+    let process = test::process::default();
+    let port = port::open(&process, sleep(), Default::default()).unwrap();
+    let proc_path = format!("/proc/{}", port::info(port).unwrap().os_pid);
+
+    assert!(Path::new(&proc_path).exists());
+    assert!(port::close(port));
+    assert!(wait_until(|| !Path::new(&proc_path).exists()));
+}
+
+#[test]
+fn command_to_program_that_does_not_read_returns_without_waiting() {
+    let process = test::process::default();
+    let port = port::open(&process, sleep(), Default::default()).unwrap();
+    // More than fits in a pipe
+    let bytes = vec![0; 1024 * 1024];
+
+    let start = Instant::now();
+
+    for _ in 0..4 {
+        port::command(port, &bytes).unwrap();
+    }
+
+    assert!(start.elapsed() < TIMEOUT);
+    assert!(port::close(port));
+}
+
+#[test]
+fn info_has_owner_as_connected_and_linked() {
+    let process = test::process::default();
+    let port = port::open(&process, cat(), Default::default()).unwrap();
+
+    let info = port::info(port).unwrap();
+
+    assert_eq!(info.name, "cat");
+    assert_eq!(info.id, port.as_usize());
+    assert_eq!(info.connected, process.pid());
+    assert_eq!(info.links, vec![process.pid()]);
+    assert!(0 < info.os_pid);
+
+    port::command(port, b"hello").unwrap();
+
+    let message = data_message(&process, port, process.charlist_from_str("hello").unwrap());
+    assert!(wait_for_message(&process, message));
+
+    let info = port::info(port).unwrap();
+
+    assert_eq!(info.input, 5);
+    assert_eq!(info.output, 5);
+
+    assert!(port::close(port));
+}
+
+#[test]
+fn owner_exiting_closes_port() {
+    let process = test::process::default();
+    let port = port::open(&process, cat(), Default::default()).unwrap();
+
+    port::propagate_exit(&process, &exit!(atom!("normal"), anyhow!("test").into()));
+
+    assert!(port::info(port).is_none());
+}
+
+#[test]
+fn linked_process_exiting_normally_does_not_close_port() {
+    let owner = test::process::default();
+    let port = port::open(&owner, cat(), Default::default()).unwrap();
+    let linked = test::process::default();
+
+    assert!(port::link(&linked, port));
+
+    port::propagate_exit(&linked, &exit!(atom!("normal"), anyhow!("test").into()));
+
+    let info = port::info(port).unwrap();
+
+    assert_eq!(info.links, vec![owner.pid()]);
+
+    assert!(port::close(port));
+}
+
+#[test]
+fn linked_process_exiting_abnormally_closes_port_and_exits_owner() {
+    let owner = test::process::default();
+    let port = port::open(&owner, cat(), Default::default()).unwrap();
+    let linked = test::process::default();
+
+    assert!(port::link(&linked, port));
+
+    port::propagate_exit(
+        &linked,
+        &exit!(Atom::str_to_term("abnormal"), anyhow!("test").into()),
+    );
+
+    assert!(port::info(port).is_none());
+    assert!(owner.is_exiting());
+}
+
+#[test]
+fn program_exiting_sends_exit_to_linked_process_trapping_exits() {
+    let process = test::process::default();
+    process.trap_exit(true);
+    let port = port::open(
+        &process,
+        Name::Spawn("true".to_string()),
+        Default::default(),
+    )
+    .unwrap();
+
+    let message = process
+        .tuple_from_slice(&[atom!("EXIT"), port.encode().unwrap(), atom!("normal")])
+        .unwrap();
+
+    assert!(wait_for_message(&process, message));
+    assert!(port::info(port).is_none());
+}
+
+#[test]
+fn link_to_closed_port_is_false() {
+    let process = test::process::default();
+    let port = port::open(&process, cat(), Default::default()).unwrap();
+
+    assert!(port::close(port));
+    assert!(!port::link(&process, port));
+}
+
+// Private
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+fn cat() -> Name {
+    Name::Spawn("cat".to_string())
+}
+
+/// A program that never reads its standard input and ignores it being closed.
+fn sleep() -> Name {
+    Name::Spawn("sleep 60".to_string())
+}
+
+fn data_message(process: &Process, port: Port, data: Term) -> Term {
+    let tagged_data = process.tuple_from_slice(&[atom!("data"), data]).unwrap();
+
+    process
+        .tuple_from_slice(&[port.encode().unwrap(), tagged_data])
+        .unwrap()
+}
+
+fn has_message(process: &Process, data: Term) -> bool {
+    process.mailbox.lock().borrow().iter().any(|message| {
+        &data
+            == match message {
+                Message::Process(message::Process { data }) => data,
+                Message::HeapFragment(message::HeapFragment { data, .. }) => data,
+            }
+    })
+}
+
+fn wait_for_message(process: &Process, data: Term) -> bool {
+    wait_until(|| has_message(process, data))
+}
+
+fn wait_until<F>(condition: F) -> bool
+where
+    F: Fn() -> bool,
+{
+    let deadline = Instant::now() + TIMEOUT;
+
+    while !condition() {
+        if deadline <= Instant::now() {
+            return false;
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    true
+}
+
+fn wait_until_closed(port: Port) -> bool {
+    wait_until(|| port::info(port).is_none())
+}
@@ -1,4 +1,6 @@
 pub mod spawn;
+#[cfg(test)]
+mod test;
 
 use alloc::sync::Arc;
 
@@ -7,7 +9,8 @@ use std::convert::TryInto;
 use liblumen_alloc::erts::exception::{self, AllocResult, ArcError, RuntimeException};
 use liblumen_alloc::erts::process::alloc::{Heap, TermAlloc};
 use liblumen_alloc::erts::process::code::stack::frame::Frame;
-use liblumen_alloc::erts::process::{self, Process, ProcessHeap};
+use liblumen_alloc::erts::process::gc::GcError;
+use liblumen_alloc::erts::process::{self, MaxHeapSize, Process, ProcessHeap};
 use liblumen_alloc::erts::term::prelude::*;
 use liblumen_alloc::erts::ModuleFunctionArity;
 use liblumen_alloc::{atom, CloneToProcess, HeapFragment, Monitor};
@@ -16,6 +19,8 @@ use lumen_rt_core::process::monitor;
 use lumen_rt_core::registry::*;
 
 use crate::code;
+use crate::distribution;
+use crate::port;
 use crate::scheduler::Scheduler;
 use crate::system;
 
@@ -45,6 +50,31 @@ fn is_expected_exit_reason(reason: Term) -> bool {
     }
 }
 
+/// Garbage collects `process` after it ran out of heap, killing it if it would grow past its
+/// `max_heap_size`.
+pub fn garbage_collect(process: &Process) {
+    let max_heap_size = process.max_heap_size();
+
+    match process.garbage_collect(0, &mut []) {
+        Ok(_freed) => {
+            // Processes that are not killed are allowed to grow past their max heap size, but the
+            // growth is still reported
+            if max_heap_size.is_enabled() && max_heap_size.size < total_heap_size(process) {
+                report_max_heap_size_exceeded(process, max_heap_size);
+            }
+        }
+        Err(GcError::MaxHeapSizeExceeded) => {
+            report_max_heap_size_exceeded(process, max_heap_size);
+
+            process.exit(
+                atom!("killed"),
+                anyhow::anyhow!("maximum heap size ({} words) exceeded", max_heap_size.size).into(),
+            );
+        }
+        Err(gc_err) => panic!("fatal garbage collection error: {:?}", gc_err),
+    }
+}
+
 pub fn log_exit(process: &Process, exception: &RuntimeException) {
     use exception::Class;
     match exception.class() {
@@ -88,6 +118,8 @@ pub fn monitor(process: &Process, monitored_process: &Process) -> AllocResult<Te
 pub fn propagate_exit(process: &Process, exception: &RuntimeException) {
     monitor::propagate_exit(process, exception);
     propagate_exit_to_links(process, exception);
+    distribution::propagate_exit(process, exception);
+    port::propagate_exit(process, exception);
 }
 
 pub fn propagate_exit_to_links(process: &Process, exception: &RuntimeException) {
@@ -154,6 +186,22 @@ pub fn propagate_exit_to_links(process: &Process, exception: &RuntimeException)
     }
 }
 
+fn report_max_heap_size_exceeded(process: &Process, max_heap_size: MaxHeapSize) {
+    if max_heap_size.error_logger {
+        system::io::puts(&format!(
+            "** Process {} exceeded its maximum heap size\n   Max Heap Size: {}\n   Total Heap Size: {}\n   Kill: {}",
+            process,
+            max_heap_size.size,
+            total_heap_size(process),
+            max_heap_size.kill
+        ));
+    }
+}
+
+fn total_heap_size(process: &Process) -> usize {
+    process.heap_size() + process.off_heap_size()
+}
+
 fn send_self_exit_message(
     process: &Process,
     heap: &mut ProcessHeap,
@@ -185,7 +233,7 @@ fn exit_in_heap(process: &Process, heap: &mut ProcessHeap, reason: Term, source:
     process.exit(data, source);
 }
 
-fn exit_in_heap_fragment(process: &Process, reason: Term, source: ArcError) {
+pub(crate) fn exit_in_heap_fragment(process: &Process, reason: Term, source: ArcError) {
     let (heap_fragment_data, mut heap_fragment) = reason.clone_to_fragment().unwrap();
 
     process.attach_fragment(unsafe { heap_fragment.as_mut() });
@@ -217,6 +265,32 @@ pub fn init(minimum_heap_size: usize) -> AllocResult<Process> {
     Ok(process)
 }
 
+/// A process that is never scheduled, so that terms can be decoded into and built on its heap
+/// outside of any running process, such as on the threads reading distribution connections and
+/// ports.
+pub(crate) fn scratch(
+    module: &str,
+    function: &str,
+    minimum_heap_size: usize,
+) -> AllocResult<Process> {
+    let module_function_arity = Arc::new(ModuleFunctionArity {
+        module: Atom::try_from_str(module).unwrap(),
+        function: Atom::try_from_str(function).unwrap(),
+        arity: 0,
+    });
+
+    let heap_size = process::alloc::next_heap_size(minimum_heap_size);
+    let heap = process::alloc::heap(heap_size)?;
+
+    Ok(Process::new(
+        Default::default(),
+        None,
+        module_function_arity,
+        heap,
+        heap_size,
+    ))
+}
+
 pub trait SchedulerDependentAlloc {
     fn next_reference(&self) -> AllocResult<Term>;
 }
@@ -0,0 +1,53 @@
+use liblumen_alloc::erts::process::{MaxHeapSize, Process, Status};
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::process;
+use crate::test;
+
+#[test]
+fn garbage_collect_kills_process_that_exceeds_max_heap_size() {
+    let arc_process = test::process::default();
+    put_live_list(&arc_process);
+
+    arc_process.set_max_heap_size(MaxHeapSize {
+        size: LIST_LEN,
+        kill: true,
+        error_logger: false,
+    });
+
+    process::garbage_collect(&arc_process);
+
+    match *arc_process.status.read() {
+        Status::Exiting(ref exception) => {
+            assert_eq!(exception.reason(), Some(Atom::str_to_term("killed")))
+        }
+        ref status => panic!("process is {:?} instead of exiting", status),
+    };
+}
+
+#[test]
+fn garbage_collect_allows_process_to_exceed_max_heap_size_without_kill() {
+    let arc_process = test::process::default();
+    put_live_list(&arc_process);
+
+    arc_process.set_max_heap_size(MaxHeapSize {
+        size: LIST_LEN,
+        kill: false,
+        error_logger: false,
+    });
+
+    process::garbage_collect(&arc_process);
+
+    assert!(!arc_process.is_exiting());
+}
+
+const LIST_LEN: usize = 1_000;
+
+/// Puts a list in the process dictionary, so that it is live during garbage collection and needs
+/// more than `LIST_LEN` words.
+fn put_live_list(process: &Process) {
+    let element_vec = vec![Atom::str_to_term("element"); LIST_LEN];
+    let list = process.list_from_slice(&element_vec).unwrap();
+
+    process.put(Atom::str_to_term("list"), list).unwrap();
+}
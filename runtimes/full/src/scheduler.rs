@@ -2,10 +2,13 @@
 pub mod test;
 
 use core::fmt::{self, Debug};
-use core::sync::atomic::{AtomicU64, Ordering};
+use core::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use core::time::Duration;
 
 use alloc::sync::{Arc, Weak};
 
+use std::thread;
+
 use hashbrown::HashMap;
 
 use liblumen_core::locks::{Mutex, RwLock};
@@ -16,9 +19,12 @@ use liblumen_alloc::erts::process::{Priority, Process, Status};
 pub use liblumen_alloc::erts::scheduler::{id, ID};
 use liblumen_alloc::erts::term::prelude::*;
 
+use lumen_rt_core::ets;
 use lumen_rt_core::registry::put_pid_to_process;
+use lumen_rt_core::scheduler::idle::Idle;
+use lumen_rt_core::scheduler::park::Parker;
 use lumen_rt_core::scheduler::{run_queue, Run};
-use lumen_rt_core::timer::Hierarchy;
+use lumen_rt_core::timer::{self, Hierarchy};
 
 use crate::process;
 use crate::process::spawn;
@@ -44,6 +50,8 @@ impl Scheduled for Reference {
 pub struct Scheduler {
     pub id: ID,
     pub hierarchy: RwLock<Hierarchy>,
+    idle: Idle,
+    parker: Parker,
     // References are always 64-bits even on 32-bit platforms
     reference_count: AtomicU64,
     run_queues: RwLock<run_queue::Queues>,
@@ -107,9 +115,22 @@ impl Scheduler {
     /// > -- [The Scheduler Loop](https://blog.stenmans.org/theBeamBook/#_the_scheduler_loop)
     pub fn run(&self) {
         loop {
-            // TODO sleep or steal if nothing run
-            let _ = self.run_once();
+            if !self.run_once() && !self.steal() && !self.skip_to_next_timeout() {
+                self.park();
+            }
+        }
+    }
+
+    /// Starts `count` more scheduler threads, which `run` and steal processes from the others
+    /// until the program exits.
+    pub fn spawn_threads(count: usize) -> std::io::Result<()> {
+        for number in 1..=count {
+            thread::Builder::new()
+                .name(format!("scheduler {}", number))
+                .spawn(|| Scheduler::current().run())?;
         }
+
+        Ok(())
     }
 
     /// > 1. Update reduction counters
@@ -143,14 +164,7 @@ impl Scheduler {
                         match Process::run(&arc_process) {
                             Ok(()) => (),
                             Err(exception) => match exception {
-                                SystemException::Alloc(_) => {
-                                    match arc_process.garbage_collect(0, &mut []) {
-                                        Ok(_freed) => (),
-                                        Err(gc_err) => {
-                                            panic!("fatal garbage collection error: {:?}", gc_err)
-                                        }
-                                    }
-                                }
+                                SystemException::Alloc(_) => process::garbage_collect(&arc_process),
                                 err => panic!("system error: {}", err),
                             },
                         }
@@ -163,6 +177,7 @@ impl Scheduler {
                             Status::Exiting(ref exception) => {
                                 process::log_exit(&exiting_arc_process, exception);
                                 process::propagate_exit(&exiting_arc_process, exception);
+                                ets::delete_owned_by(exiting_arc_process.pid());
                             }
                             _ => unreachable!(),
                         },
@@ -172,7 +187,7 @@ impl Scheduler {
                     break true;
                 }
                 Run::Delayed => continue,
-                // TODO steal processes or sleep if nothing to steal
+                // The caller decides whether to `steal` or `park`
                 Run::None => break false,
             }
         }
@@ -211,6 +226,7 @@ impl Scheduler {
     }
 
     pub fn schedule(self: Arc<Scheduler>, process: Process) -> Arc<Process> {
+        self.idle.leave();
         let mut writable_run_queues = self.run_queues.write();
 
         process.schedule_with(self.id);
@@ -218,10 +234,126 @@ impl Scheduler {
         let arc_process = Arc::new(process);
 
         writable_run_queues.enqueue(Arc::clone(&arc_process));
+        let stealable_len = writable_run_queues.stealable_len();
+        drop(writable_run_queues);
+
+        // `steal` only takes half of the stealable processes, so there is nothing to take until
+        // there are at least 2.
+        if 1 < stealable_len {
+            self.unpark_thief();
+        }
 
         arc_process
     }
 
+    /// Steals runnable processes from the other scheduler with the most stealable processes.
+    ///
+    /// Returns `true` if any processes were stolen.
+    #[must_use]
+    pub fn steal(&self) -> bool {
+        let victim = self
+            .others()
+            .into_iter()
+            .max_by_key(|other| other.run_queues.read().stealable_len());
+
+        match victim {
+            Some(victim) => 0 < self.steal_from(&victim),
+            None => false,
+        }
+    }
+
+    /// Steals half of the stealable processes from `victim` and re-homes them on this scheduler.
+    ///
+    /// Returns the number of processes stolen.
+    pub fn steal_from(&self, victim: &Scheduler) -> usize {
+        debug_assert_ne!(self.id, victim.id, "scheduler can't steal from itself");
+
+        // The victim's lock is released before this scheduler's is taken, so that two schedulers
+        // stealing from each other at the same time can't deadlock.
+        let stolen = victim.run_queues.write().steal();
+        let stolen_len = stolen.len();
+
+        if 0 < stolen_len {
+            self.idle.leave();
+            let mut writable_run_queues = self.run_queues.write();
+
+            for arc_process in stolen {
+                // Re-home before enqueuing, so that `stop_waiting` and timers find this scheduler
+                // as soon as the process can run here.
+                arc_process.schedule_with(self.id);
+                writable_run_queues.enqueue(arc_process);
+            }
+        }
+
+        stolen_len
+    }
+
+    /// Marks this scheduler idle, as it has nothing to run or steal, and, when every scheduler is
+    /// idle and the virtual clock is used, moves the clock to the next timer of any scheduler and
+    /// times out the timers due by then.
+    ///
+    /// Returns `true` if the scheduler has processes to run again, so it should not park.
+    #[must_use]
+    pub fn skip_to_next_timeout(&self) -> bool {
+        self.idle.enter();
+
+        // A process enqueued after `run_once` found nothing has to run before the clock moves
+        if 0 < self.run_queues_len() {
+            self.idle.leave();
+
+            return true;
+        }
+
+        let others = self.others();
+        let other_hierarchies: Vec<&RwLock<Hierarchy>> =
+            others.iter().map(|other| &other.hierarchy).collect();
+
+        timer::skip_to_next_timeout(&self.hierarchy, &other_hierarchies)
+    }
+
+    /// Sleeps until another scheduler gives this one work or until the next timer slot needs to
+    /// be checked.
+    pub fn park(&self) {
+        PARKED_SCHEDULER_COUNT.fetch_add(1, Ordering::SeqCst);
+        self.parker.park_timeout(PARK_TIMEOUT);
+        PARKED_SCHEDULER_COUNT.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Wakes this scheduler if it is parked.
+    pub fn unpark(&self) {
+        self.parker.unpark();
+    }
+
+    /// Wakes a parked scheduler, so that it can steal from this one.
+    fn unpark_thief(&self) {
+        if 0 < PARKED_SCHEDULER_COUNT.load(Ordering::SeqCst) {
+            if let Some(thief) = self
+                .others()
+                .into_iter()
+                .find(|other| other.parker.is_parked())
+            {
+                thief.unpark();
+            }
+        }
+    }
+
+    /// All other live schedulers.
+    ///
+    /// The `Arc`s are collected before `SCHEDULER_BY_ID` is unlocked, so that if one of them is the
+    /// last strong reference, its `Drop` doesn't deadlock trying to unregister.
+    fn others(&self) -> Vec<Arc<Scheduler>> {
+        let upgraded: Vec<Arc<Scheduler>> = SCHEDULER_BY_ID
+            .lock()
+            .values()
+            .filter_map(Weak::upgrade)
+            .collect();
+
+        upgraded
+            .into_iter()
+            .filter(|other| other.id != self.id)
+            .collect()
+    }
+
     /// Spawns a process with arguments for `apply(module, function, arguments)` on its stack.
     ///
     /// This allows the `apply/3` code to be changed with `apply_3::set_code(code)` to handle new
@@ -288,6 +420,7 @@ impl Scheduler {
         // `parent_process.scheduler.lock` has to be taken first to even get the run queue in
         // `spawn`, so copy that lock order here.
         scheduler_arc_process.schedule_with(self.id);
+        self.idle.leave();
         let mut writable_run_queues = self.run_queues.write();
 
         writable_run_queues.enqueue(Arc::clone(&arc_process));
@@ -298,7 +431,9 @@ impl Scheduler {
     }
 
     pub fn stop_waiting(&self, process: &Process) {
+        self.idle.leave();
         self.run_queues.write().stop_waiting(process);
+        self.unpark();
     }
 
     // Private
@@ -307,6 +442,8 @@ impl Scheduler {
         Scheduler {
             id: id::next(),
             hierarchy: Default::default(),
+            idle: Default::default(),
+            parker: Default::default(),
             reference_count: AtomicU64::new(0),
             run_queues: Default::default(),
             unique_integer: AtomicU64::new(0),
@@ -379,6 +516,12 @@ impl Spawned {
     }
 }
 
+/// How long an idle scheduler parks before checking its timers again, which matches the
+/// 1 millisecond resolution of the `soon` timer wheel.
+const PARK_TIMEOUT: Duration = Duration::from_millis(1);
+
+static PARKED_SCHEDULER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
 thread_local! {
   static SCHEDULER: Arc<Scheduler> = Scheduler::registered();
 }
@@ -387,3 +530,15 @@ lazy_static! {
     static ref SCHEDULER_BY_ID: Mutex<HashMap<ID, Weak<Scheduler>>> =
         Mutex::new(Default::default());
 }
+
+/// Wakes a process that was sent a message by code which only depends on `lumen_rt_core`, such
+/// as `enif_send`
+#[export_name = "__scheduler_stop_waiting"]
+pub fn scheduler_stop_waiting(process: &Process) {
+    if let Some(scheduler) = process
+        .scheduler_id()
+        .and_then(|scheduler_id| Scheduler::from_id(&scheduler_id))
+    {
+        scheduler.stop_waiting(process)
+    }
+}
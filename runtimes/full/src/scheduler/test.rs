@@ -1,4 +1,5 @@
 mod spawn_apply_3;
+mod steal;
 
 use std::sync::Arc;
 
@@ -8,7 +9,7 @@ use liblumen_alloc::erts::exception::{self, AllocResult};
 use liblumen_alloc::erts::process::code;
 use liblumen_alloc::erts::process::code::result_from_exception;
 use liblumen_alloc::erts::process::code::stack::frame::{Frame, Placement};
-use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::process::{Priority, Process};
 use liblumen_alloc::erts::term::prelude::{Atom, Term};
 use liblumen_alloc::{exit, ModuleFunctionArity};
 
@@ -51,6 +52,21 @@ fn scheduler_does_run_exiting_process() {
     assert!(!scheduler.is_run_queued(&arc_process));
 }
 
+#[test]
+fn scheduler_requeues_process_in_run_queue_for_changed_priority() {
+    let arc_process = test::process::default();
+    let scheduler = Scheduler::current();
+
+    assert_eq!(arc_process.set_priority(Priority::High), Priority::Normal);
+    // The process stays in the run queue for its old priority until it is requeued after running
+    assert_eq!(scheduler.run_queue_len(Priority::High), 0);
+
+    assert!(scheduler.run_through(&arc_process));
+
+    assert_eq!(scheduler.run_queue_len(Priority::High), 1);
+    assert!(scheduler.is_run_queued(&arc_process));
+}
+
 fn exit_1_place_frame_with_arguments(
     process: &Process,
     placement: Placement,
@@ -0,0 +1,127 @@
+use super::*;
+
+use std::thread;
+
+use crate::process::spawn::options::Options;
+use crate::scheduler::{Spawned, ID};
+use crate::test::r#loop;
+
+#[test]
+fn idle_scheduler_steals_half_of_the_stealable_processes() {
+    let victim = Scheduler::current();
+    let parent_arc_process = test::process::init();
+    let child_arc_processes = children(&parent_arc_process, Priority::Normal, 8);
+    let victim_len_before = victim.run_queue_len(Priority::Normal);
+
+    let (thief_id, stolen_len, thief_len) = steal_in_thread(&victim);
+
+    assert_eq!(stolen_len, victim_len_before / 2);
+    assert_eq!(thief_len, stolen_len);
+    assert_eq!(
+        victim.run_queue_len(Priority::Normal),
+        victim_len_before - stolen_len
+    );
+
+    let rehomed_len = child_arc_processes
+        .iter()
+        .filter(|child_arc_process| child_arc_process.scheduler_id() == Some(thief_id))
+        .count();
+
+    assert_eq!(rehomed_len, stolen_len);
+
+    for child_arc_process in &child_arc_processes {
+        let rehomed = child_arc_process.scheduler_id() == Some(thief_id);
+
+        assert_eq!(victim.is_run_queued(child_arc_process), !rehomed);
+    }
+}
+
+#[test]
+fn max_priority_processes_are_not_stolen() {
+    let victim = Scheduler::current();
+    let parent_arc_process = test::process::init();
+    let child_arc_processes = children(&parent_arc_process, Priority::Max, 4);
+
+    assert_eq!(victim.run_queue_len(Priority::Max), 4);
+
+    let (_, stolen_len, _) = steal_in_thread(&victim);
+
+    // Only `parent_arc_process` is `Priority::Normal`, and a lone process is never stolen
+    assert_eq!(stolen_len, 0);
+    assert_eq!(victim.run_queue_len(Priority::Max), 4);
+
+    for child_arc_process in &child_arc_processes {
+        assert_eq!(child_arc_process.scheduler_id(), Some(victim.id));
+    }
+}
+
+#[test]
+fn work_spreads_across_idle_schedulers() {
+    let victim = Scheduler::current();
+    let parent_arc_process = test::process::init();
+    let child_arc_processes = children(&parent_arc_process, Priority::Normal, 31);
+    let victim_len_before = victim.run_queues_len();
+
+    let mut thief_ids = Vec::new();
+    let mut total_stolen_len = 0;
+
+    for _ in 0..3 {
+        let (thief_id, stolen_len, thief_len) = steal_in_thread(&victim);
+
+        assert!(0 < stolen_len);
+        assert_eq!(thief_len, stolen_len);
+
+        thief_ids.push(thief_id);
+        total_stolen_len += stolen_len;
+    }
+
+    assert_eq!(
+        victim.run_queues_len(),
+        victim_len_before - total_stolen_len
+    );
+
+    for thief_id in thief_ids {
+        assert!(child_arc_processes
+            .iter()
+            .any(|child_arc_process| child_arc_process.scheduler_id() == Some(thief_id)));
+    }
+}
+
+fn children(parent_process: &Process, priority: Priority, len: usize) -> Vec<Arc<Process>> {
+    (0..len)
+        .map(|_| {
+            let mut options: Options = Default::default();
+            options.min_heap_size = Some(16_000);
+            options.priority = Some(priority);
+
+            let Spawned { arc_process, .. } = Scheduler::spawn_code(
+                parent_process,
+                options,
+                r#loop::module(),
+                r#loop::function(),
+                &[],
+                r#loop::code,
+            )
+            .unwrap();
+
+            arc_process
+        })
+        .collect()
+}
+
+/// Steals from `victim` on a new thread, so that the thief is a different `Scheduler`.
+///
+/// Returns the thief's ID, the number of processes stolen, and the length of the thief's run
+/// queues after stealing.
+fn steal_in_thread(victim: &Arc<Scheduler>) -> (ID, usize, usize) {
+    let victim = Arc::clone(victim);
+
+    thread::spawn(move || {
+        let thief = Scheduler::current();
+        let stolen_len = thief.steal_from(&victim);
+
+        (thief.id, stolen_len, thief.run_queues_len())
+    })
+    .join()
+    .unwrap()
+}
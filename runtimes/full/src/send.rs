@@ -4,13 +4,14 @@ use std::convert::TryInto;
 
 use anyhow::*;
 
-use liblumen_alloc::erts::exception::InternalResult;
+use liblumen_alloc::erts::exception::{AllocResult, InternalResult};
 use liblumen_alloc::term::prelude::*;
 use liblumen_alloc::Process;
 
 use lumen_rt_core::distribution::nodes::node;
 use lumen_rt_core::registry::{self, pid_to_process};
 
+use crate::distribution;
 use crate::scheduler::Scheduler;
 
 pub use options::*;
@@ -38,17 +39,12 @@ pub fn send(
                     )
                 })?;
 
-                match node_atom.name() {
-                    node::DEAD_ATOM_NAME => send_to_name(name_atom, message, options, process),
-                    _ => {
-                        if !options.connect {
-                            Ok(Sent::ConnectRequired)
-                        } else if !options.suspend {
-                            Ok(Sent::SuspendRequired)
-                        } else {
-                            unimplemented!("distribution")
-                        }
-                    }
+                if node_atom == node::atom() {
+                    send_to_name(name_atom, message, options, process)
+                } else {
+                    Ok(distribution::reg_send(
+                        process, name_atom, node_atom, message, options,
+                    ))
                 }
             } else {
                 Err(anyhow!("destination ({}) is a tuple, but not 2-arity", destination).into())
@@ -62,11 +58,7 @@ pub fn send(
             } else {
                 match pid_to_process(&destination_pid) {
                     Some(destination_arc_process) => {
-                        if destination_arc_process.send_from_other(message)? {
-                            let scheduler_id = destination_arc_process.scheduler_id().unwrap();
-                            let arc_scheduler = Scheduler::from_id(&scheduler_id).unwrap();
-                            arc_scheduler.stop_waiting(&destination_arc_process);
-                        }
+                        send_to_process(&destination_arc_process, message)?;
 
                         Ok(Sent::Sent)
                     }
@@ -74,6 +66,9 @@ pub fn send(
                 }
             }
         }
+        TypedTerm::ExternalPid(external_pid) => {
+            Ok(distribution::send(&external_pid, message, options))
+        }
         _ => Err(TypeError)
             .context(format!(
                 "destination ({}) is not registered_name (atom), {{registered_name, node}}, or pid",
@@ -83,6 +78,18 @@ pub fn send(
     }
 }
 
+/// Sends `message` to a local process other than the sender, waking it up if it is waiting for a
+/// message.
+pub fn send_to_process(destination: &Process, message: Term) -> AllocResult<()> {
+    if destination.send_from_other(message)? {
+        let scheduler_id = destination.scheduler_id().unwrap();
+        let arc_scheduler = Scheduler::from_id(&scheduler_id).unwrap();
+        arc_scheduler.stop_waiting(destination);
+    }
+
+    Ok(())
+}
+
 pub enum Sent {
     Sent,
     SuspendRequired,
@@ -105,11 +112,7 @@ fn send_to_name(
     } else {
         match registry::atom_to_process(&destination) {
             Some(destination_arc_process) => {
-                if destination_arc_process.send_from_other(message)? {
-                    let scheduler_id = destination_arc_process.scheduler_id().unwrap();
-                    let arc_scheduler = Scheduler::from_id(&scheduler_id).unwrap();
-                    arc_scheduler.stop_waiting(&destination_arc_process);
-                }
+                send_to_process(&destination_arc_process, message)?;
 
                 Ok(Sent::Sent)
             }
@@ -8,9 +8,9 @@ use lumen_rt_core::proplist::TryPropListFromTermError;
 
 pub struct Options {
     // Send only suspends for some sends to ports and for remote (`ExternalPid` or
-    // `{name, remote_node}`) sends, so it does not apply at this time.
+    // `{name, remote_node}`) sends that have to connect to the remote node first.
     pub suspend: bool,
-    // Connect only applies when there is distribution, which isn't implemented yet.
+    // Connect only applies to remote sends when there is no connection to the remote node yet.
     pub connect: bool,
 }
 
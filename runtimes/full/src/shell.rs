@@ -0,0 +1,451 @@
+//! An interactive shell for `<exe> shell`, which evaluates expressions by calling the functions
+//! compiled into the executable through `erlang:apply/3` and prints the results.
+//!
+//! Input is read on a separate thread, so that the scheduler keeps running processes while the
+//! shell waits for the next expression.
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver, Sender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+
+use anyhow::anyhow;
+use bus::BusReader;
+use hashbrown::HashMap;
+use thiserror::Error;
+
+use liblumen_alloc::erts::exception;
+use liblumen_alloc::erts::process::code::stack::frame::Frame;
+use liblumen_alloc::erts::process::code::{self, Code};
+use liblumen_alloc::erts::process::{Process, Status};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{atom, CloneToProcess, ModuleFunctionArity};
+
+use lumen_rt_core::heap_fragments::HeapFragments;
+
+use crate::scheduler::{Scheduled, Scheduler, Spawned};
+use crate::system::break_handler::Signal;
+use crate::term::consult::Value;
+use crate::term::expr::{self, Expr};
+
+pub struct Shell {
+    /// Each complete input, or `None` once stdin is closed
+    inputs: Receiver<Option<String>>,
+    /// Tells the reader thread to prompt for the next input
+    prompt: Sender<()>,
+    /// The parent of the processes that evaluate calls
+    arc_process: Arc<Process>,
+    binding_by_variable: HashMap<String, Binding>,
+    open: bool,
+}
+
+impl Shell {
+    pub fn start(scheduler: Arc<Scheduler>) -> exception::Result<Self> {
+        let arc_process = scheduler.spawn_init(0)?;
+        let (input_sender, inputs) = mpsc::channel();
+        let (prompt, prompt_receiver) = mpsc::channel();
+
+        thread::Builder::new()
+            .name("shell".to_string())
+            .spawn(move || read_inputs(input_sender, prompt_receiver))
+            .expect("could not spawn shell input thread");
+
+        Ok(Self {
+            inputs,
+            prompt,
+            arc_process,
+            binding_by_variable: Default::default(),
+            open: true,
+        })
+    }
+
+    /// Evaluates the next input if one has been read.
+    ///
+    /// Returns `false` once the shell has been quit with `q()` or stdin is closed.
+    ///
+    /// While a call is evaluated, `signals` are checked instead of by the caller, so that SIGINT
+    /// can interrupt a call that never returns.
+    pub fn poll(&mut self, scheduler: &Arc<Scheduler>, signals: &mut BusReader<Signal>) -> bool {
+        match self.inputs.try_recv() {
+            Ok(Some(input)) => {
+                self.eval_input(scheduler, signals, &input);
+
+                if self.open {
+                    // The reader thread only exits when stdin closes, which is also checked
+                    // on the next poll
+                    self.prompt.send(()).ok();
+                }
+            }
+            Ok(None) | Err(TryRecvError::Disconnected) => self.open = false,
+            Err(TryRecvError::Empty) => (),
+        }
+
+        self.open
+    }
+
+    fn eval_input(
+        &mut self,
+        scheduler: &Arc<Scheduler>,
+        signals: &mut BusReader<Signal>,
+        input: &str,
+    ) {
+        let exprs = match expr::parse(input) {
+            Ok(exprs) => exprs,
+            Err(err) => {
+                println!("* syntax error: {}", err);
+
+                return;
+            }
+        };
+
+        // Holds the terms that are only needed until the input is printed
+        let mut fragments = HeapFragments::default();
+        let mut last = None;
+
+        for expr in &exprs {
+            match self.eval(scheduler, signals, &mut fragments, expr) {
+                Ok(term) => last = Some(term),
+                Err(err) => {
+                    println!("{}", err);
+
+                    return;
+                }
+            }
+        }
+
+        if let Some(term) = last {
+            println!("{}", term);
+        }
+    }
+
+    fn eval(
+        &mut self,
+        scheduler: &Arc<Scheduler>,
+        signals: &mut BusReader<Signal>,
+        fragments: &mut HeapFragments,
+        expr: &Expr,
+    ) -> Result<Term, EvalError> {
+        match expr {
+            Expr::Value(value) => Ok(value.clone_to_heap(fragments.alloc(value.size_in_words())?)?),
+            Expr::Variable(variable) => self
+                .binding_by_variable
+                .get(variable)
+                .map(|binding| binding.term)
+                .ok_or_else(|| EvalError::Unbound(variable.clone())),
+            Expr::Match { variable, expr } => {
+                let term = self.eval(scheduler, signals, fragments, expr)?;
+
+                self.bind(variable, term)
+            }
+            Expr::Call {
+                module: None,
+                function,
+                arguments,
+            } if self.is_command(*function, arguments) => self.command(*function, arguments),
+            Expr::Call {
+                module,
+                function,
+                arguments,
+            } => {
+                let module = module.unwrap_or_else(|| Atom::try_from_str("erlang").unwrap());
+                let mut argument_vec = Vec::with_capacity(arguments.len());
+
+                for argument in arguments {
+                    argument_vec.push(self.eval(scheduler, signals, fragments, argument)?);
+                }
+
+                let argument_list = fragments.list(&argument_vec, Term::NIL)?;
+
+                self.call(
+                    scheduler,
+                    signals,
+                    fragments,
+                    module,
+                    *function,
+                    argument_list,
+                )
+            }
+            Expr::Tuple(elements) => {
+                let mut element_vec = Vec::with_capacity(elements.len());
+
+                for element in elements {
+                    element_vec.push(self.eval(scheduler, signals, fragments, element)?);
+                }
+
+                Ok(fragments.tuple(&element_vec)?)
+            }
+            Expr::List { elements, tail } => {
+                let mut element_vec = Vec::with_capacity(elements.len());
+
+                for element in elements {
+                    element_vec.push(self.eval(scheduler, signals, fragments, element)?);
+                }
+
+                let tail_term = match tail {
+                    Some(tail) => self.eval(scheduler, signals, fragments, tail)?,
+                    None => Term::NIL,
+                };
+
+                Ok(fragments.list(&element_vec, tail_term)?)
+            }
+        }
+    }
+
+    /// `_` matches anything without being bound and a bound variable only matches the same value.
+    fn bind(&mut self, variable: &str, term: Term) -> Result<Term, EvalError> {
+        if variable == "_" {
+            return Ok(term);
+        }
+
+        match self.binding_by_variable.get(variable) {
+            Some(binding)
+                if binding
+                    .term
+                    .decode()
+                    .unwrap()
+                    .exact_eq(&term.decode().unwrap()) =>
+            {
+                Ok(term)
+            }
+            Some(_) => Err(EvalError::Badmatch(term.to_string())),
+            None => {
+                let mut fragments = HeapFragments::default();
+                let bound_term = fragments.clone_term(term)?;
+
+                self.binding_by_variable.insert(
+                    variable.to_string(),
+                    Binding {
+                        term: bound_term,
+                        _fragments: fragments,
+                    },
+                );
+
+                Ok(bound_term)
+            }
+        }
+    }
+
+    /// The shell commands that are handled without calling any code: `b()`, `f()`, `f(Variable)`
+    /// and `q()`.
+    fn is_command(&self, function: Atom, arguments: &[Expr]) -> bool {
+        match (function.name(), arguments) {
+            ("b", []) | ("f", []) | ("f", [Expr::Variable(_)]) | ("q", []) => true,
+            _ => false,
+        }
+    }
+
+    fn command(&mut self, function: Atom, arguments: &[Expr]) -> Result<Term, EvalError> {
+        match (function.name(), arguments) {
+            ("b", []) => {
+                let mut variables: Vec<&String> = self.binding_by_variable.keys().collect();
+                variables.sort();
+
+                for variable in variables {
+                    println!("{} = {}", variable, self.binding_by_variable[variable].term);
+                }
+            }
+            ("f", []) => self.binding_by_variable.clear(),
+            ("f", [Expr::Variable(variable)]) => {
+                self.binding_by_variable.remove(variable);
+            }
+            ("q", []) => self.open = false,
+            _ => unreachable!(),
+        }
+
+        Ok(atom!("ok"))
+    }
+
+    /// Calls `module:function(arguments)` in a new process and runs the scheduler until that
+    /// process exits, so that other processes keep running while the call waits on them.
+    ///
+    /// SIGINT kills the process, so that a call that waits forever can be interrupted.  A signal
+    /// that should terminate the runtime also quits the shell.
+    fn call(
+        &mut self,
+        scheduler: &Arc<Scheduler>,
+        signals: &mut BusReader<Signal>,
+        fragments: &mut HeapFragments,
+        module: Atom,
+        function: Atom,
+        argument_list: Term,
+    ) -> Result<Term, EvalError> {
+        let Spawned { arc_process, .. } = Scheduler::spawn_code(
+            &self.arc_process,
+            Default::default(),
+            self::module(),
+            self::function(),
+            &[
+                module.encode().unwrap(),
+                function.encode().unwrap(),
+                argument_list,
+            ],
+            apply_code,
+        )
+        .map_err(|_| EvalError::Alloc)?;
+
+        while !arc_process.is_exiting() {
+            match signals.try_recv() {
+                Ok(Signal::INT) => interrupt(&arc_process),
+                Ok(signal) if signal.should_terminate() => {
+                    self.open = false;
+                    interrupt(&arc_process);
+                }
+                _ => (),
+            }
+
+            if !scheduler.run_once() && !scheduler.steal() {
+                scheduler.park();
+            }
+        }
+
+        let returned = arc_process.get_value_from_key(return_key());
+
+        match returned.decode().unwrap() {
+            TypedTerm::Tuple(tuple) if tuple.len() == 1 => Ok(fragments.clone_term(tuple[0])?),
+            _ => match *arc_process.status.read() {
+                Status::Exiting(ref exception) => Err(EvalError::Exception {
+                    class: exception
+                        .class()
+                        .map_or_else(|| "error".to_string(), |class| class.to_string()),
+                    reason: exception
+                        .reason()
+                        .map_or_else(|| "system_error".to_string(), |reason| reason.to_string()),
+                }),
+                _ => Err(EvalError::NoReturn),
+            },
+        }
+    }
+}
+
+/// Kills the process of an interrupted call, waking it if it is waiting, so that its exit is
+/// handled like any other.
+fn interrupt(arc_process: &Arc<Process>) {
+    arc_process.exit(atom!("killed"), anyhow!("interrupted by SIGINT").into());
+
+    if let Some(scheduler) = arc_process.scheduler() {
+        scheduler.stop_waiting(arc_process);
+    }
+}
+
+struct Binding {
+    term: Term,
+    // Owns the memory of `term`
+    _fragments: HeapFragments,
+}
+
+#[derive(Debug, Error)]
+enum EvalError {
+    #[error("* variable '{0}' is unbound")]
+    Unbound(String),
+    #[error("** exception error: no match of right hand side value {0}")]
+    Badmatch(String),
+    #[error("** exception {class}: {reason}")]
+    Exception { class: String, reason: String },
+    #[error("* not enough memory to evaluate the expression")]
+    Alloc,
+    #[error("* the call stopped without returning a value")]
+    NoReturn,
+}
+
+impl From<exception::Alloc> for EvalError {
+    fn from(_: exception::Alloc) -> Self {
+        EvalError::Alloc
+    }
+}
+
+/// Prints a numbered prompt and sends each input once it ends in `.`, then waits until the
+/// shell has printed the result before prompting again.
+fn read_inputs(inputs: Sender<Option<String>>, prompt: Receiver<()>) {
+    let stdin = io::stdin();
+    let mut number = 1;
+
+    loop {
+        let mut input = String::new();
+
+        while !input.trim_end().ends_with('.') {
+            if input.trim().is_empty() {
+                input.clear();
+                print!("{}> ", number);
+                io::stdout().flush().ok();
+            }
+
+            match stdin.lock().read_line(&mut input) {
+                Ok(0) | Err(_) => {
+                    inputs.send(None).ok();
+
+                    return;
+                }
+                Ok(_) => (),
+            }
+        }
+
+        if inputs.send(Some(input)).is_err() || prompt.recv().is_err() {
+            return;
+        }
+
+        number += 1;
+    }
+}
+
+/// Calls `apply(Module, Function, Arguments)` with `Module`, `Function` and `Arguments` on the
+/// stack, returning to `return_code`.
+fn apply_code(arc_process: &Arc<Process>) -> code::Result {
+    arc_process.replace_frame(frame(return_code));
+
+    let erlang = Atom::try_from_str("erlang").unwrap();
+    let apply = Atom::try_from_str("apply").unwrap();
+    let arity = 3;
+    let apply_code =
+        crate::code::export::get(&erlang, &apply, arity).expect("erlang:apply/3 not exported");
+    arc_process.push_frame(Frame::new(
+        Arc::new(ModuleFunctionArity {
+            module: erlang,
+            function: apply,
+            arity,
+        }),
+        apply_code,
+    ));
+
+    Process::call_code(arc_process)
+}
+
+/// Puts `{Value}` returned by `apply/3` in the process dictionary, where `Shell::call` finds it
+/// after the process exits.  The tuple distinguishes a return from an exit before any dictionary
+/// entry is put.
+fn return_code(arc_process: &Arc<Process>) -> code::Result {
+    const STACK_USED: usize = 1;
+
+    let value = arc_process.stack_peek(1).unwrap();
+    let returned = arc_process.tuple_from_slice(&[value])?;
+
+    match arc_process.put(return_key(), returned) {
+        Ok(_) => {
+            arc_process.remove_last_frame(STACK_USED);
+
+            Process::call_code(arc_process)
+        }
+        Err(exception) => code::result_from_exception(arc_process, STACK_USED, exception),
+    }
+}
+
+fn frame(code: Code) -> Frame {
+    Frame::new(
+        Arc::new(ModuleFunctionArity {
+            module: module(),
+            function: function(),
+            arity: 3,
+        }),
+        code,
+    )
+}
+
+fn function() -> Atom {
+    Atom::try_from_str("eval").unwrap()
+}
+
+fn module() -> Atom {
+    Atom::try_from_str("shell").unwrap()
+}
+
+fn return_key() -> Term {
+    Atom::str_to_term("$shell_return")
+}
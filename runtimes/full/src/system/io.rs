@@ -22,6 +22,15 @@ pub extern "C" fn lumen_system_io_puts(s: *const libc::c_char) {
     puts(&sref);
 }
 
+/// Writes `s` without a trailing newline, such as the output of `io:format/2`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn print(s: &str) {
+    use std::io::Write;
+
+    print!("{}", s);
+    std::io::stdout().flush().unwrap();
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn puts(s: &str) {
     println!("{}", s);
@@ -32,3 +41,10 @@ pub fn puts(s: &str) {
 pub fn puts(s: &str) {
     console_log(s);
 }
+
+/// `console.log` always ends the line, so on wasm32 each call is its own line.
+#[cfg(target_arch = "wasm32")]
+#[allow(dead_code)]
+pub fn print(s: &str) {
+    console_log(s);
+}
@@ -1,29 +1,595 @@
-#![allow(dead_code)]
-#![allow(unused_imports)]
-/// ## Algorithms
-///
-/// * 'exrop' - Xoroshiro116+, 58 bits precision and period of 2^116-1 (jump equivalent to 2^64
-///   calls)
-/// * 'exs1024s' - Xorshift1024*, 64 bits precision and period of 2^1024-1 (jump equivalent to
-///   2^512)
-/// * 'exsp' - Xorshift116+, 58 bits precision and period of 2^116-1 (jump equivalent to 2^64)
-///
-/// Default is 'exrop'
-///
-/// ## Implementation Overview
-///
-/// Every time a random number is requested, a state is used to calculate it and a new state is
-/// produced. The state can either be implicit or be an explicit argument and return value.
-///
-/// The functions with implicit state use the process dictionary variable rand_seed to remember
-/// the current state.
-///
-/// If a process calls uniform/0, uniform/1 or uniform_real/0 without setting a seed first,
-/// seed/1 is called automatically with the default algorithm and creates a non-constant seed.
-///
-/// The functions with explicit state never use the process dictionary.
-use xorshift::*;
-
-pub extern "C" fn uniform() {
-    unimplemented!()
+//! The pseudo random number generators of Erlang's `rand` module.
+//!
+//! ## Algorithms
+//!
+//! * 'exrop' - Xoroshiro116+, 58 bits precision and period of 2^116-1 (jump equivalent to 2^64
+//!   calls)
+//! * 'exs1024s' - Xorshift1024*, 64 bits precision and period of 2^1024-1 (jump equivalent to
+//!   2^512)
+//! * 'exsss' - Xorshift116**, 58 bits precision and period of 2^116-1 (jump equivalent to 2^64)
+//!
+//! Default is 'exsss'
+//!
+//! ## Implementation Overview
+//!
+//! Every time a random number is requested, a state is used to calculate it and a new state is
+//! produced. The state can either be implicit or be an explicit argument and return value.
+//!
+//! The functions with implicit state use the process dictionary variable rand_seed to remember
+//! the current state.
+//!
+//! If a process calls uniform/0, uniform/1 or uniform_real/0 without setting a seed first,
+//! seed/1 is called automatically with the default algorithm and creates a non-constant seed.
+//!
+//! The functions with explicit state never use the process dictionary.
+//!
+//! Seeding and every draw follow `rand.erl` step by step, so that the same seed produces the same
+//! sequence as OTP.
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Algorithm {
+    Exrop,
+    Exs1024s,
+    Exsss,
+}
+
+impl Algorithm {
+    pub const DEFAULT: Algorithm = Algorithm::Exsss;
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "exrop" => Some(Algorithm::Exrop),
+            "exs1024s" => Some(Algorithm::Exs1024s),
+            "exsss" => Some(Algorithm::Exsss),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Exrop => "exrop",
+            Algorithm::Exs1024s => "exs1024s",
+            Algorithm::Exsss => "exsss",
+        }
+    }
+
+    /// The number of bits each `State::next` produces.
+    fn bits(&self) -> u32 {
+        match self {
+            Algorithm::Exrop | Algorithm::Exsss => 58,
+            Algorithm::Exs1024s => 64,
+        }
+    }
+
+    /// The number of low bits of each `State::next` that are weaker than the rest and are skipped
+    /// when concatenating draws for big ranges.
+    fn weak_low_bits(&self) -> u32 {
+        match self {
+            Algorithm::Exrop => 1,
+            Algorithm::Exs1024s => 3,
+            Algorithm::Exsss => 0,
+        }
+    }
+}
+
+/// A seed for `State::seed`.  Integers are only used modulo 2^64, so callers reduce any integer,
+/// including negative and big integers, with two's complement.
+#[derive(Clone, Copy, Debug)]
+pub enum Seed {
+    Integer(u64),
+    Tuple([u64; 3]),
+}
+
+/// The state of an algorithm, in the same shape as `rand:export_seed/0` uses.
+#[derive(Clone, Debug, PartialEq)]
+pub enum State {
+    /// `[S0|S1]`
+    Exrop([u64; 2]),
+    /// `{L, RL}`: the 16 words are `L ++ lists:reverse(RL)`, with `hd(L)` as the next `s[p]`.
+    Exs1024s { list: Vec<u64>, reversed: Vec<u64> },
+    /// `[S0|S1]`
+    Exsss([u64; 2]),
+}
+
+impl State {
+    /// The state for a `seed` from `rand:seed/2`.
+    pub fn seed(algorithm: Algorithm, seed: Seed) -> Self {
+        match (algorithm, seed) {
+            (Algorithm::Exrop, Seed::Integer(x)) => {
+                let (s0, x) = seed58(x);
+                let (s1, _) = seed58(x);
+
+                State::Exrop([s0, s1])
+            }
+            (Algorithm::Exrop, Seed::Tuple([a1, a2, a3])) => {
+                let [_, s1] = exrop_next_state([
+                    mask(58, a1.wrapping_mul(4294967197).wrapping_add(1)),
+                    mask(58, a2.wrapping_mul(4294967231).wrapping_add(1)),
+                ]);
+
+                State::Exrop(exrop_next_state([
+                    mask(58, a3.wrapping_mul(4294967279).wrapping_add(1)),
+                    s1,
+                ]))
+            }
+            (Algorithm::Exs1024s, Seed::Integer(x)) => {
+                let mut list = Vec::with_capacity(EXS1024_LEN);
+                let mut x = x;
+
+                for _ in 0..EXS1024_LEN {
+                    let (z, next_x) = seed64(x);
+                    list.push(z);
+                    x = next_x;
+                }
+
+                State::Exs1024s {
+                    list,
+                    reversed: Vec::new(),
+                }
+            }
+            (Algorithm::Exs1024s, Seed::Tuple([a1, a2, a3])) => {
+                let b1 = mask(21, (mask(21, a1) + 1) * 2097131);
+                let b2 = mask(21, (mask(21, a2) + 1) * 2097133);
+                let b3 = mask(21, (mask(21, a3) + 1) * 2097143);
+                let mut r = (b1 << 43) | (b2 << 22) | (b3 << 1) | 1;
+                let mut list = Vec::with_capacity(EXS1024_LEN);
+
+                for _ in 0..EXS1024_LEN {
+                    let (x, next_r) = exs64_next(r);
+                    list.insert(0, x);
+                    r = next_r;
+                }
+
+                State::Exs1024s {
+                    list,
+                    reversed: Vec::new(),
+                }
+            }
+            (Algorithm::Exsss, Seed::Integer(x)) => {
+                let (s0, x) = seed58(x);
+                let (s1, _) = seed58(x);
+
+                State::Exsss([s0, s1])
+            }
+            (Algorithm::Exsss, Seed::Tuple([a1, a2, a3])) => {
+                let (_, [_, s1]) = exsp_next([
+                    mask(58, a1.wrapping_mul(4294967197).wrapping_add(1)),
+                    mask(58, a2.wrapping_mul(4294967231).wrapping_add(1)),
+                ]);
+                let (_, state) =
+                    exsp_next([mask(58, a3.wrapping_mul(4294967279).wrapping_add(1)), s1]);
+
+                State::Exsss(state)
+            }
+        }
+    }
+
+    /// A non-constant seed, like `rand:seed/1` creates from the node, pid and time.
+    pub fn seed_non_constant(algorithm: Algorithm) -> Self {
+        Self::seed(
+            algorithm,
+            Seed::Tuple([rand::random(), rand::random(), rand::random()]),
+        )
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        match self {
+            State::Exrop(_) => Algorithm::Exrop,
+            State::Exs1024s { .. } => Algorithm::Exs1024s,
+            State::Exsss(_) => Algorithm::Exsss,
+        }
+    }
+
+    /// The next `Algorithm::bits` wide integer.
+    pub fn next(&mut self) -> u64 {
+        match self {
+            State::Exrop(state) => {
+                let [s0, s1] = *state;
+                *state = exrop_next_state(*state);
+
+                mask(58, s0 + s1)
+            }
+            State::Exs1024s { list, reversed } => {
+                if list.len() == 1 {
+                    list.extend(reversed.drain(..).rev());
+                }
+
+                let s0 = list.remove(0);
+                let s1 = list[0];
+                let s11 = s1 ^ (s1 << 31);
+                let s12 = s11 ^ (s11 >> 11);
+                let s01 = s0 ^ (s0 >> 30);
+                let ns1 = s01 ^ s12;
+                list[0] = ns1;
+                reversed.insert(0, s0);
+
+                ns1.wrapping_mul(1181783497276652981)
+            }
+            State::Exsss(state) => {
+                let (_, next_state) = exsp_next(*state);
+                let s0 = state[1];
+                *state = next_state;
+
+                let v0 = mask(58, s0 + bsl(58, s0, 2));
+                let v1 = rotl(58, v0, 7);
+
+                mask(58, v1 + bsl(58, v1, 3))
+            }
+        }
+    }
+
+    /// A float uniformly distributed in `0.0 =< X < 1.0` with 53 bits of precision, like
+    /// `rand:uniform/0`.
+    pub fn uniform(&mut self) -> f64 {
+        let bits = self.algorithm().bits();
+
+        (self.next() >> (bits - 53)) as f64 * pow2(-53)
+    }
+
+    /// An integer uniformly distributed in `1 =< X =< range`, like `rand:uniform/1`.
+    pub fn uniform_range(&mut self, range: &BigUint) -> BigUint {
+        assert!(!range.is_zero());
+
+        let bits = self.algorithm().bits();
+        let max: u128 = 1 << bits;
+
+        match range.to_u128() {
+            Some(range) if range <= max => {
+                let max_minus_range = max - range;
+
+                loop {
+                    let v = self.next() as u128;
+
+                    if v < range {
+                        break BigUint::from(v + 1);
+                    }
+
+                    let i = v % range;
+
+                    if v - i <= max_minus_range {
+                        break BigUint::from(i + 1);
+                    }
+                }
+            }
+            _ => {
+                let v = self.next();
+
+                self.uniform_big_range(range, v)
+            }
+        }
+    }
+
+    /// A float uniformly distributed in `0.0 < X < 1.0`, using all the precision of small floats,
+    /// like `rand:uniform_real/0`.
+    pub fn uniform_real(&mut self) -> f64 {
+        let bits = self.algorithm().bits();
+
+        'start: loop {
+            let m1 = self.next() >> (bits - 56);
+
+            if let Some(f) = top_56_bits(m1, 0) {
+                break f;
+            }
+
+            // Need more bits
+            let mut m0 = m1;
+            let mut bit_no: i32 = -56;
+
+            loop {
+                let v1 = self.next();
+
+                if bit_no == -1064 {
+                    // This is a very theoretical bottom case, filling up to 53 bits from the at
+                    // most 52 bits of `m0`.
+                    let b0 = 53 - bit_count(m0);
+
+                    break 'start ((m0 << b0) | (v1 >> (bits - b0))) as f64
+                        * pow2(-1064 - b0 as i32);
+                }
+
+                if (1 << 51) <= m0 {
+                    break 'start ((m0 << 1) | (v1 >> (bits - 1))) as f64 * pow2(bit_no - 1);
+                } else if (1 << 50) <= m0 {
+                    break 'start ((m0 << 2) | (v1 >> (bits - 2))) as f64 * pow2(bit_no - 2);
+                } else if (1 << 49) <= m0 {
+                    break 'start ((m0 << 3) | (v1 >> (bits - 3))) as f64 * pow2(bit_no - 3);
+                } else if m0 == 0 {
+                    let m1 = v1 >> (bits - 56);
+
+                    if let Some(f) = top_56_bits(m1, bit_no) {
+                        break 'start f;
+                    } else if bit_no == -1008 && m1 < (1 << 42) {
+                        // Would underflow 2^-1022, so start all over
+                        continue 'start;
+                    } else {
+                        m0 = m1;
+                        bit_no -= 56;
+                    }
+                } else {
+                    // Fill up to 53 bits
+                    let b = 53 - bit_count(m0);
+
+                    break 'start ((m0 << b) | (v1 >> (bits - b))) as f64 * pow2(bit_no - b as i32);
+                }
+            }
+        }
+    }
+
+    /// A float from the standard normal distribution, with mean 0.0 and variance 1.0, like
+    /// `rand:normal/0`.
+    pub fn normal(&mut self) -> f64 {
+        loop {
+            let bits = self.algorithm().bits();
+            let int = self.next();
+            let sign = int & (1 << (bits - 51 - 1));
+            let r = int >> (bits - 51);
+            let index = mask(8, r) as usize;
+            let ki = NORMAL_ZIGGURAT.k[index];
+            let wi = NORMAL_ZIGGURAT.w[index];
+            let x = r as f64 * wi;
+            let signed_x = if sign == 0 { x } else { -x };
+
+            if r < ki {
+                // Fast path 95% of the time
+                break signed_x;
+            } else if index == 0 {
+                // The tail
+                loop {
+                    let x = -NORMAL_INVERSE_R * self.uniform().ln();
+                    let y = -self.uniform().ln();
+
+                    if x * x < y + y {
+                        return if sign == 0 {
+                            NORMAL_R + x
+                        } else {
+                            -NORMAL_R - x
+                        };
+                    }
+                }
+            } else {
+                let fi = NORMAL_ZIGGURAT.f[index];
+                let previous_fi = NORMAL_ZIGGURAT.f[index - 1];
+                let u0 = self.uniform();
+
+                if (previous_fi - fi) * u0 + fi < (-0.5 * x * x).exp() {
+                    break signed_x;
+                }
+            }
+        }
+    }
+
+    /// A float from the normal distribution with `mean` and `variance`, like `rand:normal/2`.
+    pub fn normal_with(&mut self, mean: f64, variance: f64) -> f64 {
+        mean + variance.sqrt() * self.normal()
+    }
+
+    /// `uniform_range/4` in `rand.erl` for ranges bigger than one draw.
+    fn uniform_big_range(&mut self, range: &BigUint, v: u64) -> BigUint {
+        let algorithm = self.algorithm();
+        let bits = algorithm.bits();
+        let weak_low_bits = algorithm.weak_low_bits();
+        // Maybe waste the lowest bit(s) when shifting in new bits
+        let shift = bits - weak_low_bits;
+        let range_minus_1 = range - 1_u32;
+
+        if (range & &range_minus_1).is_zero() {
+            // Power of 2: generate at least the number of bits for the range
+            let (v1, _) = self.concatenate(range >> bits as usize, v, weak_low_bits, shift, bits);
+
+            (v1 & range_minus_1) + 1_u32
+        } else {
+            // Generate a value with at least two bits more than the range and try that for a
+            // fit, otherwise try again
+            let mut v = v;
+
+            loop {
+                let (v1, b) =
+                    self.concatenate(range >> (bits - 2) as usize, v, weak_low_bits, shift, bits);
+                let i = &v1 % range;
+
+                if &v1 - &i <= (BigUint::one() << b as usize) - range {
+                    break i + 1_u32;
+                }
+
+                v = self.next();
+            }
+        }
+    }
+
+    /// `uniform_range/7` in `rand.erl`: shifts in draws after `v` until `range` is used up,
+    /// returning the concatenation and its number of bits.  Only the `weak_low_bits` of the
+    /// concatenation so far are cleared before each shift, like `V band bnot ?MASK(WeakLowBits)`
+    /// does on Erlang's unbounded integers.
+    fn concatenate(
+        &mut self,
+        range: BigUint,
+        v: u64,
+        weak_low_bits: u32,
+        shift: u32,
+        bits: u32,
+    ) -> (BigUint, u32) {
+        let mut range = range;
+        let mut v = BigUint::from(v);
+        let mut b = bits;
+
+        while BigUint::one() < range {
+            let v1 = self.next();
+            v = (((v >> weak_low_bits as usize) << weak_low_bits as usize) << shift as usize)
+                | BigUint::from(v1);
+            range >>= shift as usize;
+            b += shift;
+        }
+
+        (v, b)
+    }
+}
+
+// Private
+
+const EXS1024_LEN: usize = 16;
+
+/// `?BSL(Width, X, N)`: shift left within `width` bits.
+fn bsl(width: u32, x: u64, n: u32) -> u64 {
+    mask(width - n, x) << n
+}
+
+/// The number of bits needed for `x`.
+fn bit_count(x: u64) -> u32 {
+    64 - x.leading_zeros()
+}
+
+fn exrop_next_state([s0, s1]: [u64; 2]) -> [u64; 2] {
+    let s1_1 = s1 ^ s0;
+
+    [
+        rotl(58, s0, 24) ^ s1_1 ^ bsl(58, s1_1, 2),
+        rotl(58, s1_1, 35),
+    ]
+}
+
+fn exs64_next(r: u64) -> (u64, u64) {
+    let r1 = r ^ (r >> 12);
+    let r2 = r1 ^ (r1 << 25);
+    let r3 = r2 ^ (r2 >> 27);
+
+    (r3.wrapping_mul(2685821657736338717), r3)
+}
+
+/// Xorshift116+ with the members swapped like `exsp_next/1` in `rand.erl`, which `exsss` uses
+/// for its state.
+fn exsp_next([s1, s0]: [u64; 2]) -> (u64, [u64; 2]) {
+    let s1_1 = s1 ^ bsl(58, s1, 24);
+    let new_s1 = s1_1 ^ s0 ^ (s1_1 >> 11) ^ (s0 >> 41);
+
+    (mask(58, s0 + new_s1), [s0, new_s1])
+}
+
+/// `?MASK(Width, X)`
+fn mask(width: u32, x: u64) -> u64 {
+    if width >= 64 {
+        x
+    } else {
+        x & ((1 << width) - 1)
+    }
+}
+
+/// 2.0 to the `exponent`, exactly, including subnormals.
+fn pow2(exponent: i32) -> f64 {
+    if -1022 <= exponent {
+        f64::from_bits(((exponent + 1023) as u64) << 52)
+    } else {
+        f64::from_bits(1 << (exponent + 1074))
+    }
+}
+
+/// `?ROTL(Width, X, N)`
+fn rotl(width: u32, x: u64, n: u32) -> u64 {
+    bsl(width, x, n) | (x >> (width - n))
+}
+
+/// Non-zero 58 bit seed from SplitMix64, returning the seed and the next SplitMix64 state.
+fn seed58(x: u64) -> (u64, u64) {
+    let mut x = x;
+
+    loop {
+        let (z, next_x) = splitmix64_next(x);
+        x = next_x;
+
+        match mask(58, z) {
+            0 => continue,
+            z => break (z, x),
+        }
+    }
+}
+
+/// Non-zero 64 bit seed from SplitMix64, returning the seed and the next SplitMix64 state.
+fn seed64(x: u64) -> (u64, u64) {
+    let mut x = x;
+
+    loop {
+        let (z, next_x) = splitmix64_next(x);
+        x = next_x;
+
+        if z != 0 {
+            break (z, x);
+        }
+    }
+}
+
+fn splitmix64_next(x: u64) -> (u64, u64) {
+    let x = x.wrapping_add(0x9e3779b97f4a7c15);
+    let z0 = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    let z1 = (z0 ^ (z0 >> 27)).wrapping_mul(0x94d049bb133111eb);
+
+    (z1 ^ (z1 >> 31), x)
+}
+
+/// The float from the 56 bits of `m1` below 2^`bit_no`, if it has at least 53 significant bits.
+fn top_56_bits(m1: u64, bit_no: i32) -> Option<f64> {
+    if (1 << 55) <= m1 {
+        // We have 56 bits - waste 3
+        Some((m1 >> 3) as f64 * pow2(bit_no - 53))
+    } else if (1 << 54) <= m1 {
+        // We have 55 bits - waste 2
+        Some((m1 >> 2) as f64 * pow2(bit_no - 54))
+    } else if (1 << 53) <= m1 {
+        // We have 54 bits - waste 1
+        Some((m1 >> 1) as f64 * pow2(bit_no - 55))
+    } else if (1 << 52) <= m1 {
+        // We have 53 bits - use all
+        Some(m1 as f64 * pow2(bit_no - 56))
+    } else {
+        None
+    }
+}
+
+/// The start of the tail of the normal distribution ziggurat.
+const NORMAL_R: f64 = 3.6541528853610088;
+const NORMAL_INVERSE_R: f64 = 1.0 / NORMAL_R;
+/// The area of each layer of the normal distribution ziggurat.
+const NORMAL_V: f64 = 0.00492867323399;
+const NORMAL_LAYERS: usize = 256;
+
+/// The layers of the ziggurat for `State::normal`, calculated like `rand.erl`'s tables were, for
+/// 51 bit draws.
+struct NormalZiggurat {
+    k: [u64; NORMAL_LAYERS],
+    w: [f64; NORMAL_LAYERS],
+    f: [f64; NORMAL_LAYERS],
+}
+
+impl NormalZiggurat {
+    fn new() -> Self {
+        let m = (1_u64 << 51) as f64;
+        let mut k = [0; NORMAL_LAYERS];
+        let mut w = [0.0; NORMAL_LAYERS];
+        let mut f = [0.0; NORMAL_LAYERS];
+
+        let mut dn = NORMAL_R;
+        let mut tn = dn;
+        let q = NORMAL_V / (-0.5 * dn * dn).exp();
+
+        k[0] = ((dn / q) * m) as u64;
+        k[1] = 0;
+        w[0] = q / m;
+        w[NORMAL_LAYERS - 1] = dn / m;
+        f[0] = 1.0;
+        f[NORMAL_LAYERS - 1] = (-0.5 * dn * dn).exp();
+
+        for i in (1..(NORMAL_LAYERS - 1)).rev() {
+            dn = (-2.0 * (NORMAL_V / dn + (-0.5 * dn * dn).exp()).ln()).sqrt();
+            k[i + 1] = ((dn / tn) * m) as u64;
+            tn = dn;
+            f[i] = (-0.5 * dn * dn).exp();
+            w[i] = dn / m;
+        }
+
+        Self { k, w, f }
+    }
+}
+
+lazy_static! {
+    static ref NORMAL_ZIGGURAT: NormalZiggurat = NormalZiggurat::new();
 }
@@ -1 +1,3 @@
+pub mod consult;
+pub mod expr;
 pub mod external_format;
@@ -0,0 +1,774 @@
+//! Reads Erlang terms from text, each ending in `.`, the same as
+//! [`file:consult/1`](http://erlang.org/doc/man/file.html#consult-1).
+//!
+//! This is used for `sys.config` and `.script` boot files, which have to be read before any
+//! process exists, so the terms are parsed into `Value`s first, which can then be cloned to a
+//! heap or heap fragment.
+#[cfg(test)]
+mod test;
+
+use std::convert::TryInto;
+use std::mem;
+
+use num_bigint::BigInt;
+use num_traits::{Num, ToPrimitive};
+use thiserror::Error;
+
+use liblumen_alloc::borrow::CloneToProcess;
+use liblumen_alloc::erts;
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
+use liblumen_alloc::erts::term::prelude::*;
+
+/// A term read from text that is not stored on any heap yet.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Atom(Atom),
+    Integer(BigInt),
+    Float(f64),
+    Binary(Vec<u8>),
+    Tuple(Vec<Value>),
+    /// A list of `elements` ending in `tail`, which is `None` for a proper list
+    List {
+        elements: Vec<Value>,
+        tail: Option<Box<Value>>,
+    },
+    Map(Vec<(Value, Value)>),
+}
+
+impl Value {
+    pub fn nil() -> Self {
+        Value::List {
+            elements: Vec::new(),
+            tail: None,
+        }
+    }
+
+    /// An Erlang string is a proper list of the code points.
+    pub fn charlist(s: &str) -> Self {
+        Value::List {
+            elements: s
+                .chars()
+                .map(|c| Value::Integer((c as u32).into()))
+                .collect(),
+            tail: None,
+        }
+    }
+
+    pub fn as_atom(&self) -> Option<Atom> {
+        match self {
+            Value::Atom(atom) => Some(*atom),
+            _ => None,
+        }
+    }
+
+    /// The elements of a proper list
+    pub fn as_proper_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List {
+                elements,
+                tail: None,
+            } => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// The characters of a proper list of code points
+    pub fn as_string(&self) -> Option<String> {
+        self.as_proper_list()?
+            .iter()
+            .map(|element| match element {
+                Value::Integer(big_int) => big_int.to_u32().and_then(std::char::from_u32),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn as_tuple(&self) -> Option<&[Value]> {
+        match self {
+            Value::Tuple(elements) => Some(elements),
+            _ => None,
+        }
+    }
+}
+
+impl CloneToProcess for Value {
+    fn clone_to_heap<A>(&self, heap: &mut A) -> AllocResult<Term>
+    where
+        A: ?Sized + TermAlloc,
+    {
+        match self {
+            Value::Atom(atom) => Ok(atom.encode().unwrap()),
+            Value::Integer(big_int) => match big_int.clone().into() {
+                Integer::Small(small_integer) => Ok(small_integer.into()),
+                Integer::Big(big_integer) => big_integer.clone_to_heap(heap),
+            },
+            Value::Float(f) => heap.float(*f).map(|float| float.into()),
+            Value::Binary(bytes) => {
+                if bytes.len() > HeapBin::MAX_SIZE {
+                    heap.procbin_from_bytes(bytes).map(|boxed| boxed.into())
+                } else {
+                    heap.heapbin_from_bytes(bytes).map(|boxed| boxed.into())
+                }
+            }
+            Value::Tuple(elements) => {
+                let mut terms = Vec::with_capacity(elements.len());
+
+                for element in elements {
+                    terms.push(element.clone_to_heap(heap)?);
+                }
+
+                heap.tuple_from_slice(&terms).map(|boxed| boxed.into())
+            }
+            Value::List { elements, tail } => {
+                let mut acc = match tail {
+                    Some(tail) => tail.clone_to_heap(heap)?,
+                    None => Term::NIL,
+                };
+
+                for element in elements.iter().rev() {
+                    let head = element.clone_to_heap(heap)?;
+                    acc = heap.cons(head, acc)?.into();
+                }
+
+                Ok(acc)
+            }
+            Value::Map(entries) => {
+                let mut pairs = Vec::with_capacity(entries.len());
+
+                for (key, value) in entries {
+                    pairs.push((key.clone_to_heap(heap)?, value.clone_to_heap(heap)?));
+                }
+
+                heap.map_from_slice(&pairs).map(|boxed| boxed.into())
+            }
+        }
+    }
+
+    /// Each boxed value is given an extra word, as a `HeapFragment` pads each allocation to the
+    /// minimum alignment.
+    fn size_in_words(&self) -> usize {
+        match self {
+            Value::Atom(_) => 0,
+            Value::Integer(big_int) => match big_int.clone().into() {
+                Integer::Small(_) => 0,
+                Integer::Big(big_integer) => big_integer.size_in_words() + 1,
+            },
+            Value::Float(_) => erts::to_word_size(mem::size_of::<Float>()) + 1,
+            Value::Binary(bytes) => {
+                if bytes.len() > HeapBin::MAX_SIZE {
+                    erts::to_word_size(mem::size_of::<ProcBin>()) + 1
+                } else {
+                    // header and flags, then the bytes
+                    2 + erts::to_word_size(bytes.len()) + 1
+                }
+            }
+            Value::Tuple(elements) => {
+                erts::to_word_size(Tuple::layout_for_len(elements.len()).size())
+                    + 1
+                    + elements.iter().map(Value::size_in_words).sum::<usize>()
+            }
+            Value::List { elements, tail } => {
+                elements.len() * (erts::to_word_size(mem::size_of::<Cons>()) + 1)
+                    + elements.iter().map(Value::size_in_words).sum::<usize>()
+                    + tail.as_ref().map_or(0, |tail| tail.size_in_words())
+            }
+            Value::Map(entries) => {
+                erts::to_word_size(mem::size_of::<Map>())
+                    + 1
+                    + entries
+                        .iter()
+                        .map(|(key, value)| key.size_in_words() + value.size_in_words())
+                        .sum::<usize>()
+            }
+        }
+    }
+}
+
+/// Parses all the `.`-terminated terms in `text`.
+pub fn consult(text: &str) -> Result<Vec<Value>, ConsultError> {
+    let mut parser = Parser::new(text);
+    let mut values = Vec::new();
+
+    loop {
+        parser.skip_whitespace_and_comments();
+
+        if parser.peek().is_none() {
+            break Ok(values);
+        }
+
+        let value = parser.value()?;
+        parser.expect_dot()?;
+
+        values.push(value);
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum ConsultError {
+    #[error("unexpected end of input on line {line}, expected {expected}")]
+    UnexpectedEnd { expected: &'static str, line: usize },
+    #[error("unexpected {found:?} on line {line}, expected {expected}")]
+    Unexpected {
+        found: char,
+        expected: &'static str,
+        line: usize,
+    },
+    #[error("variable ({name}) on line {line} can't be used in a term")]
+    Variable { name: String, line: usize },
+    #[error("invalid number ({text}) on line {line}")]
+    Number { text: String, line: usize },
+    #[error("invalid escape sequence on line {line}")]
+    Escape { line: usize },
+    #[error("binary segment on line {line} is not a byte")]
+    Byte { line: usize },
+    #[error("atom on line {line} is too long")]
+    Atom { line: usize },
+}
+
+/// Reads terms character by character, so that `crate::shell` can build expressions on top of
+/// the same lexical rules.
+pub(crate) struct Parser {
+    chars: Vec<char>,
+    index: usize,
+    line: usize,
+}
+
+impl Parser {
+    pub(crate) fn new(text: &str) -> Self {
+        Self {
+            chars: text.chars().collect(),
+            index: 0,
+            line: 1,
+        }
+    }
+
+    pub(crate) fn line(&self) -> usize {
+        self.line
+    }
+
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.peek_nth(0)
+    }
+
+    pub(crate) fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.index + n).copied()
+    }
+
+    pub(crate) fn next(&mut self) -> Option<char> {
+        let next = self.peek();
+
+        if let Some(c) = next {
+            self.index += 1;
+
+            if c == '\n' {
+                self.line += 1;
+            }
+        }
+
+        next
+    }
+
+    pub(crate) fn next_or_end(&mut self, expected: &'static str) -> Result<char, ConsultError> {
+        self.next().ok_or(ConsultError::UnexpectedEnd {
+            expected,
+            line: self.line,
+        })
+    }
+
+    pub(crate) fn unexpected(&self, found: char, expected: &'static str) -> ConsultError {
+        ConsultError::Unexpected {
+            found,
+            expected,
+            line: self.line,
+        }
+    }
+
+    pub(crate) fn skip_whitespace_and_comments(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.next();
+            } else if c == '%' {
+                while let Some(c) = self.next() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Skips whitespace and comments and then consumes `expected` if it is next.
+    pub(crate) fn eat(&mut self, expected: char) -> bool {
+        self.skip_whitespace_and_comments();
+
+        if self.peek() == Some(expected) {
+            self.next();
+
+            true
+        } else {
+            false
+        }
+    }
+
+    pub(crate) fn expect(
+        &mut self,
+        expected: char,
+        description: &'static str,
+    ) -> Result<(), ConsultError> {
+        self.skip_whitespace_and_comments();
+
+        match self.next_or_end(description)? {
+            c if c == expected => Ok(()),
+            c => Err(self.unexpected(c, description)),
+        }
+    }
+
+    /// The `.` that ends a term must be followed by whitespace, a comment or the end of input.
+    pub(crate) fn expect_dot(&mut self) -> Result<(), ConsultError> {
+        self.expect('.', "`.`")?;
+
+        match self.peek() {
+            None | Some('%') => Ok(()),
+            Some(c) if c.is_whitespace() => Ok(()),
+            Some(c) => Err(self.unexpected(c, "whitespace after `.`")),
+        }
+    }
+
+    pub(crate) fn value(&mut self) -> Result<Value, ConsultError> {
+        const EXPECTED: &str = "a term";
+
+        self.skip_whitespace_and_comments();
+
+        match self.peek() {
+            None => Err(ConsultError::UnexpectedEnd {
+                expected: EXPECTED,
+                line: self.line,
+            }),
+            Some(c) => match c {
+                'a'..='z' => self.unquoted_atom(),
+                '\'' => self.quoted_atom(),
+                'A'..='Z' | '_' => {
+                    let line = self.line;
+                    let name = self.name();
+
+                    Err(ConsultError::Variable { name, line })
+                }
+                '0'..='9' => self.number(false),
+                '-' | '+' => {
+                    self.next();
+                    self.skip_whitespace_and_comments();
+
+                    match self.peek() {
+                        Some('0'..='9') => self.number(c == '-'),
+                        Some(found) => Err(self.unexpected(found, "a number")),
+                        None => Err(ConsultError::UnexpectedEnd {
+                            expected: "a number",
+                            line: self.line,
+                        }),
+                    }
+                }
+                '$' => {
+                    self.next();
+                    let c = self.char_literal()?;
+
+                    Ok(Value::Integer((c as u32).into()))
+                }
+                '"' => self.strings().map(|s| Value::charlist(&s)),
+                '{' => {
+                    self.next();
+
+                    self.elements('}', "`,` or `}`").map(Value::Tuple)
+                }
+                '[' => {
+                    self.next();
+
+                    self.list()
+                }
+                '<' if self.peek_nth(1) == Some('<') => {
+                    self.index += 2;
+
+                    self.binary()
+                }
+                '#' if self.peek_nth(1) == Some('{') => {
+                    self.index += 2;
+
+                    self.map()
+                }
+                _ => Err(self.unexpected(c, EXPECTED)),
+            },
+        }
+    }
+
+    pub(crate) fn name(&mut self) -> String {
+        let mut name = String::new();
+
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '@' {
+                name.push(c);
+                self.next();
+            } else {
+                break;
+            }
+        }
+
+        name
+    }
+
+    fn atom(&self, name: &str) -> Result<Value, ConsultError> {
+        Atom::try_from_str(name)
+            .map(Value::Atom)
+            .map_err(|_| ConsultError::Atom { line: self.line })
+    }
+
+    fn unquoted_atom(&mut self) -> Result<Value, ConsultError> {
+        let name = self.name();
+
+        self.atom(&name)
+    }
+
+    fn quoted_atom(&mut self) -> Result<Value, ConsultError> {
+        let name = self.quoted('\'', "`'`")?;
+
+        self.atom(&name)
+    }
+
+    /// Adjacent strings are concatenated, the same as the compiler does.
+    fn strings(&mut self) -> Result<String, ConsultError> {
+        let mut s = self.quoted('"', "`\"`")?;
+
+        loop {
+            self.skip_whitespace_and_comments();
+
+            if self.peek() == Some('"') {
+                s.push_str(&self.quoted('"', "`\"`")?);
+            } else {
+                break Ok(s);
+            }
+        }
+    }
+
+    fn quoted(&mut self, quote: char, expected: &'static str) -> Result<String, ConsultError> {
+        // opening quote
+        self.next();
+
+        let mut s = String::new();
+
+        loop {
+            match self.next_or_end(expected)? {
+                c if c == quote => break Ok(s),
+                '\\' => s.push(self.escape()?),
+                c => s.push(c),
+            }
+        }
+    }
+
+    fn char_literal(&mut self) -> Result<char, ConsultError> {
+        match self.next_or_end("a character")? {
+            '\\' => self.escape(),
+            c => Ok(c),
+        }
+    }
+
+    /// [Escape sequences](http://erlang.org/doc/reference_manual/data_types.html#escape-sequences)
+    fn escape(&mut self) -> Result<char, ConsultError> {
+        let line = self.line;
+        let c = match self.next_or_end("an escape sequence")? {
+            'b' => '\u{8}',
+            'd' => '\u{7f}',
+            'e' => '\u{1b}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            's' => ' ',
+            't' => '\t',
+            'v' => '\u{b}',
+            '^' => {
+                let c = self.next_or_end("a control character")?;
+
+                ((c as u32) % 32).try_into().unwrap()
+            }
+            'x' => {
+                let mut digits = String::new();
+
+                if self.peek() == Some('{') {
+                    self.next();
+
+                    loop {
+                        match self.next_or_end("`}`")? {
+                            '}' => break,
+                            c => digits.push(c),
+                        }
+                    }
+                } else {
+                    for _ in 0..2 {
+                        digits.push(self.next_or_end("a hexadecimal digit")?);
+                    }
+                }
+
+                u32::from_str_radix(&digits, 16)
+                    .ok()
+                    .and_then(std::char::from_u32)
+                    .ok_or(ConsultError::Escape { line })?
+            }
+            c @ '0'..='7' => {
+                let mut code = c.to_digit(8).unwrap();
+
+                for _ in 0..2 {
+                    match self.peek().and_then(|c| c.to_digit(8)) {
+                        Some(digit) => {
+                            self.next();
+                            code = code * 8 + digit;
+                        }
+                        None => break,
+                    }
+                }
+
+                std::char::from_u32(code).ok_or(ConsultError::Escape { line })?
+            }
+            c => c,
+        };
+
+        Ok(c)
+    }
+
+    fn digits(&mut self, radix: u32) -> String {
+        let mut digits = String::new();
+
+        while let Some(c) = self.peek() {
+            if c.is_digit(radix) {
+                digits.push(c);
+                self.next();
+            } else if c == '_' && self.peek_nth(1).map_or(false, |c| c.is_digit(radix)) {
+                // digit separator
+                self.next();
+            } else {
+                break;
+            }
+        }
+
+        digits
+    }
+
+    fn number(&mut self, negative: bool) -> Result<Value, ConsultError> {
+        let line = self.line;
+        let integral = self.digits(10);
+
+        let value = if self.peek() == Some('#') {
+            self.next();
+
+            let radix = integral
+                .parse::<u32>()
+                .ok()
+                .filter(|radix| (2..=36).contains(radix))
+                .ok_or_else(|| ConsultError::Number {
+                    text: format!("{}#", integral),
+                    line,
+                })?;
+            let digits = self.digits(radix);
+
+            let big_int =
+                BigInt::from_str_radix(&digits, radix).map_err(|_| ConsultError::Number {
+                    text: format!("{}#{}", integral, digits),
+                    line,
+                })?;
+
+            Value::Integer(big_int)
+        } else if self.peek() == Some('.') && self.peek_nth(1).map_or(false, |c| c.is_digit(10)) {
+            self.next();
+
+            let mut text = format!("{}.{}", integral, self.digits(10));
+
+            if let Some('e') | Some('E') = self.peek() {
+                text.push('e');
+                self.next();
+
+                if let Some(sign @ '-') | Some(sign @ '+') = self.peek() {
+                    text.push(sign);
+                    self.next();
+                }
+
+                text.push_str(&self.digits(10));
+            }
+
+            let f = text
+                .parse::<f64>()
+                .map_err(|_| ConsultError::Number { text, line })?;
+
+            Value::Float(f)
+        } else {
+            Value::Integer(integral.parse().unwrap())
+        };
+
+        Ok(if negative {
+            match value {
+                Value::Integer(big_int) => Value::Integer(-big_int),
+                Value::Float(f) => Value::Float(-f),
+                _ => unreachable!(),
+            }
+        } else {
+            value
+        })
+    }
+
+    /// Parses `,`-separated values until `close`, after the opening delimiter has been consumed.
+    fn elements(
+        &mut self,
+        close: char,
+        expected: &'static str,
+    ) -> Result<Vec<Value>, ConsultError> {
+        let mut elements = Vec::new();
+
+        if self.eat(close) {
+            return Ok(elements);
+        }
+
+        loop {
+            elements.push(self.value()?);
+
+            self.skip_whitespace_and_comments();
+
+            match self.next_or_end(expected)? {
+                ',' => continue,
+                c if c == close => break Ok(elements),
+                c => break Err(self.unexpected(c, expected)),
+            }
+        }
+    }
+
+    fn list(&mut self) -> Result<Value, ConsultError> {
+        const EXPECTED: &str = "`,`, `|` or `]`";
+
+        let mut elements = Vec::new();
+
+        if self.eat(']') {
+            return Ok(Value::nil());
+        }
+
+        loop {
+            elements.push(self.value()?);
+
+            self.skip_whitespace_and_comments();
+
+            match self.next_or_end(EXPECTED)? {
+                ',' => continue,
+                ']' => {
+                    break Ok(Value::List {
+                        elements,
+                        tail: None,
+                    })
+                }
+                '|' => {
+                    let tail = self.value()?;
+                    self.expect(']', "`]`")?;
+
+                    // `[a | [b]]` is the proper list `[a, b]`
+                    break Ok(match tail {
+                        Value::List {
+                            elements: tail_elements,
+                            tail,
+                        } => {
+                            elements.extend(tail_elements);
+
+                            Value::List { elements, tail }
+                        }
+                        tail => Value::List {
+                            elements,
+                            tail: Some(Box::new(tail)),
+                        },
+                    });
+                }
+                c => break Err(self.unexpected(c, EXPECTED)),
+            }
+        }
+    }
+
+    /// Binary segments can only be byte integers or strings, optionally with a `/utf8` type.
+    fn binary(&mut self) -> Result<Value, ConsultError> {
+        const EXPECTED: &str = "`,` or `>>`";
+
+        let mut bytes = Vec::new();
+
+        self.skip_whitespace_and_comments();
+
+        if self.peek() == Some('>') && self.peek_nth(1) == Some('>') {
+            self.index += 2;
+
+            return Ok(Value::Binary(bytes));
+        }
+
+        loop {
+            self.skip_whitespace_and_comments();
+
+            let line = self.line;
+
+            match self.peek() {
+                Some('"') => {
+                    let s = self.strings()?;
+
+                    if self.eat('/') {
+                        match self.name().as_str() {
+                            "utf8" => bytes.extend_from_slice(s.as_bytes()),
+                            _ => return Err(ConsultError::Byte { line }),
+                        }
+                    } else {
+                        for c in s.chars() {
+                            let byte: u8 = (c as u32)
+                                .try_into()
+                                .map_err(|_| ConsultError::Byte { line })?;
+                            bytes.push(byte);
+                        }
+                    }
+                }
+                _ => match self.value()? {
+                    Value::Integer(big_int) => {
+                        let byte = big_int.to_u8().ok_or(ConsultError::Byte { line })?;
+                        bytes.push(byte);
+                    }
+                    _ => return Err(ConsultError::Byte { line }),
+                },
+            }
+
+            self.skip_whitespace_and_comments();
+
+            match self.next_or_end(EXPECTED)? {
+                ',' => continue,
+                '>' if self.peek() == Some('>') => {
+                    self.next();
+
+                    break Ok(Value::Binary(bytes));
+                }
+                c => break Err(self.unexpected(c, EXPECTED)),
+            }
+        }
+    }
+
+    fn map(&mut self) -> Result<Value, ConsultError> {
+        const EXPECTED: &str = "`,` or `}`";
+
+        let mut entries = Vec::new();
+
+        if self.eat('}') {
+            return Ok(Value::Map(entries));
+        }
+
+        loop {
+            let key = self.value()?;
+            self.expect('=', "`=>`")?;
+            self.expect('>', "`=>`")?;
+            let value = self.value()?;
+
+            entries.push((key, value));
+
+            self.skip_whitespace_and_comments();
+
+            match self.next_or_end(EXPECTED)? {
+                ',' => continue,
+                '}' => break Ok(Value::Map(entries)),
+                c => break Err(self.unexpected(c, EXPECTED)),
+            }
+        }
+    }
+}
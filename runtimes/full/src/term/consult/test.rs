@@ -0,0 +1,138 @@
+use super::*;
+
+#[test]
+fn atoms() {
+    assert_eq!(
+        consult("ok. 'EXIT'. 'with\\nnewline'. node@host.").unwrap(),
+        vec![
+            atom("ok"),
+            atom("EXIT"),
+            atom("with\nnewline"),
+            atom("node@host")
+        ]
+    );
+}
+
+#[test]
+fn integers() {
+    assert_eq!(
+        consult("1. -2. 16#ff. 1_000. $a. $\\n. 123456789012345678901234567890.").unwrap(),
+        vec![
+            integer(1),
+            integer(-2),
+            integer(255),
+            integer(1_000),
+            integer('a' as i64),
+            integer('\n' as i64),
+            Value::Integer("123456789012345678901234567890".parse().unwrap())
+        ]
+    );
+}
+
+#[test]
+fn floats() {
+    assert_eq!(
+        consult("1.5. -0.25. 1.0e3. 2.5E-1.").unwrap(),
+        vec![
+            Value::Float(1.5),
+            Value::Float(-0.25),
+            Value::Float(1000.0),
+            Value::Float(0.25)
+        ]
+    );
+}
+
+#[test]
+fn adjacent_strings_are_concatenated_into_a_charlist() {
+    assert_eq!(
+        consult("\"ab\" \"c\".").unwrap(),
+        vec![Value::charlist("abc")]
+    );
+}
+
+#[test]
+fn binaries() {
+    assert_eq!(
+        consult("<<>>. <<1, 2>>. <<\"abc\">>. <<\"é\"/utf8>>.").unwrap(),
+        vec![
+            Value::Binary(vec![]),
+            Value::Binary(vec![1, 2]),
+            Value::Binary(b"abc".to_vec()),
+            Value::Binary("é".as_bytes().to_vec())
+        ]
+    );
+}
+
+#[test]
+fn containers() {
+    assert_eq!(
+        consult("{a, [b | c], [d | [e]], #{f => {}}}.").unwrap(),
+        vec![Value::Tuple(vec![
+            atom("a"),
+            Value::List {
+                elements: vec![atom("b")],
+                tail: Some(Box::new(atom("c")))
+            },
+            Value::List {
+                elements: vec![atom("d"), atom("e")],
+                tail: None
+            },
+            Value::Map(vec![(atom("f"), Value::Tuple(vec![]))])
+        ])]
+    );
+}
+
+#[test]
+fn comments_are_skipped() {
+    assert_eq!(
+        consult("% sys.config\n[{app, % inline\n []}].\n% trailing").unwrap(),
+        vec![Value::List {
+            elements: vec![Value::Tuple(vec![atom("app"), Value::nil()])],
+            tail: None
+        }]
+    );
+}
+
+#[test]
+fn without_dot_errors() {
+    assert_eq!(
+        consult("ok").unwrap_err(),
+        ConsultError::UnexpectedEnd {
+            expected: "`.`",
+            line: 1
+        }
+    );
+}
+
+#[test]
+fn with_variable_errors() {
+    assert_eq!(
+        consult("\n{ok, Value}.").unwrap_err(),
+        ConsultError::Variable {
+            name: "Value".to_string(),
+            line: 2
+        }
+    );
+}
+
+#[test]
+fn clone_to_fragment_fits_nested_value() {
+    let values = consult(
+        "[{kernel, [{logger_level, info}, {limits, #{max => 1.5, min => -99999999999999999999}}]},
+          {app, [{name, <<\"a binary that is too long to be a heap binary, so it is a procbin\">>},
+                 {path, \"/var/lib/app\"}]}].",
+    )
+    .unwrap();
+
+    let (term, _) = values[0].clone_to_fragment().unwrap();
+
+    assert!(term.is_non_empty_list());
+}
+
+fn atom(name: &str) -> Value {
+    Value::Atom(Atom::from_str(name))
+}
+
+fn integer(i: i64) -> Value {
+    Value::Integer(i.into())
+}
@@ -0,0 +1,202 @@
+//! The expressions the shell can evaluate: terms, variables, `Variable = Expression` matches and
+//! calls, separated by `,` and ending in `.`.
+#[cfg(test)]
+mod test;
+
+use liblumen_alloc::erts::term::prelude::*;
+
+use crate::term::consult::{ConsultError, Parser, Value};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    /// A term without any variables or calls in it
+    Value(Value),
+    Variable(String),
+    /// `Variable = Expression` binds `Variable` or checks that it is already bound to the same
+    /// value.
+    Match {
+        variable: String,
+        expr: Box<Expr>,
+    },
+    /// `Module:Function(Arguments)`, or `Function(Arguments)` when `module` is `None`
+    Call {
+        module: Option<Atom>,
+        function: Atom,
+        arguments: Vec<Expr>,
+    },
+    Tuple(Vec<Expr>),
+    /// A list of `elements` ending in `tail`, which is `None` for a proper list
+    List {
+        elements: Vec<Expr>,
+        tail: Option<Box<Expr>>,
+    },
+}
+
+/// Parses the `,`-separated expressions of one `.`-terminated shell input.
+pub fn parse(text: &str) -> Result<Vec<Expr>, ConsultError> {
+    let mut parser = Parser::new(text);
+    let mut exprs = Vec::new();
+
+    loop {
+        exprs.push(expr(&mut parser)?);
+
+        if !parser.eat(',') {
+            break;
+        }
+    }
+
+    parser.expect_dot()?;
+    parser.skip_whitespace_and_comments();
+
+    match parser.peek() {
+        None => Ok(exprs),
+        Some(c) => Err(parser.unexpected(c, "the end of the input after `.`")),
+    }
+}
+
+fn expr(parser: &mut Parser) -> Result<Expr, ConsultError> {
+    parser.skip_whitespace_and_comments();
+
+    match parser.peek() {
+        Some('A'..='Z') | Some('_') => {
+            let variable = parser.name();
+
+            parser.skip_whitespace_and_comments();
+
+            // `=`, but not `==`, `=:=`, `=/=`, `=<` or `=>`
+            let is_match = parser.peek() == Some('=')
+                && match parser.peek_nth(1) {
+                    Some('=') | Some(':') | Some('/') | Some('<') | Some('>') => false,
+                    _ => true,
+                };
+
+            if is_match {
+                parser.next();
+
+                Ok(Expr::Match {
+                    variable,
+                    expr: Box::new(expr(parser)?),
+                })
+            } else {
+                Ok(Expr::Variable(variable))
+            }
+        }
+        Some('a'..='z') | Some('\'') => {
+            let name = atom(parser, "an atom")?;
+
+            parser.skip_whitespace_and_comments();
+
+            match parser.peek() {
+                Some(':') => {
+                    parser.next();
+                    let function = atom(parser, "a function name")?;
+                    parser.expect('(', "`(`")?;
+
+                    Ok(Expr::Call {
+                        module: Some(name),
+                        function,
+                        arguments: exprs(parser, ')', "`,` or `)`")?,
+                    })
+                }
+                Some('(') => {
+                    parser.next();
+
+                    Ok(Expr::Call {
+                        module: None,
+                        function: name,
+                        arguments: exprs(parser, ')', "`,` or `)`")?,
+                    })
+                }
+                _ => Ok(Expr::Value(Value::Atom(name))),
+            }
+        }
+        Some('{') => {
+            parser.next();
+
+            exprs(parser, '}', "`,` or `}`").map(Expr::Tuple)
+        }
+        Some('[') => {
+            parser.next();
+
+            list(parser)
+        }
+        _ => parser.value().map(Expr::Value),
+    }
+}
+
+fn atom(parser: &mut Parser, expected: &'static str) -> Result<Atom, ConsultError> {
+    parser.skip_whitespace_and_comments();
+
+    match parser.peek() {
+        Some('a'..='z') | Some('\'') => match parser.value()? {
+            Value::Atom(atom) => Ok(atom),
+            _ => unreachable!(),
+        },
+        Some(c) => Err(parser.unexpected(c, expected)),
+        None => Err(ConsultError::UnexpectedEnd {
+            expected,
+            line: parser.line(),
+        }),
+    }
+}
+
+/// Parses `,`-separated expressions until `close`, after the opening delimiter has been consumed.
+fn exprs(
+    parser: &mut Parser,
+    close: char,
+    expected: &'static str,
+) -> Result<Vec<Expr>, ConsultError> {
+    let mut exprs = Vec::new();
+
+    if parser.eat(close) {
+        return Ok(exprs);
+    }
+
+    loop {
+        exprs.push(expr(parser)?);
+
+        parser.skip_whitespace_and_comments();
+
+        match parser.next_or_end(expected)? {
+            ',' => continue,
+            c if c == close => break Ok(exprs),
+            c => break Err(parser.unexpected(c, expected)),
+        }
+    }
+}
+
+fn list(parser: &mut Parser) -> Result<Expr, ConsultError> {
+    const EXPECTED: &str = "`,`, `|` or `]`";
+
+    let mut elements = Vec::new();
+
+    if parser.eat(']') {
+        return Ok(Expr::Value(Value::nil()));
+    }
+
+    loop {
+        elements.push(expr(parser)?);
+
+        parser.skip_whitespace_and_comments();
+
+        match parser.next_or_end(EXPECTED)? {
+            ',' => continue,
+            ']' => {
+                break Ok(Expr::List {
+                    elements,
+                    tail: None,
+                })
+            }
+            '|' => {
+                let tail = expr(parser)?;
+                parser.expect(']', "`]`")?;
+
+                break Ok(Expr::List {
+                    elements,
+                    tail: Some(Box::new(tail)),
+                });
+            }
+            c => break Err(parser.unexpected(c, EXPECTED)),
+        }
+    }
+}
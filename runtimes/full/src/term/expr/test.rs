@@ -0,0 +1,77 @@
+use super::*;
+
+#[test]
+fn value() {
+    assert_eq!(
+        parse("#{a => <<\"b\">>}.").unwrap(),
+        vec![Expr::Value(Value::Map(vec![(
+            Value::Atom(atom("a")),
+            Value::Binary(b"b".to_vec())
+        )]))]
+    );
+}
+
+#[test]
+fn remote_call() {
+    assert_eq!(
+        parse("lists:reverse([1, X]).").unwrap(),
+        vec![Expr::Call {
+            module: Some(atom("lists")),
+            function: atom("reverse"),
+            arguments: vec![Expr::List {
+                elements: vec![integer(1), Expr::Variable("X".to_string())],
+                tail: None
+            }]
+        }]
+    );
+}
+
+#[test]
+fn local_call() {
+    assert_eq!(
+        parse("self().").unwrap(),
+        vec![Expr::Call {
+            module: None,
+            function: atom("self"),
+            arguments: vec![]
+        }]
+    );
+}
+
+#[test]
+fn matches_separated_by_commas() {
+    assert_eq!(
+        parse("X = 1, Y = {X, two}.\n").unwrap(),
+        vec![
+            Expr::Match {
+                variable: "X".to_string(),
+                expr: Box::new(integer(1))
+            },
+            Expr::Match {
+                variable: "Y".to_string(),
+                expr: Box::new(Expr::Tuple(vec![
+                    Expr::Variable("X".to_string()),
+                    Expr::Value(Value::Atom(atom("two")))
+                ]))
+            }
+        ]
+    );
+}
+
+#[test]
+fn without_dot_errors() {
+    assert!(parse("erlang:self()").is_err());
+}
+
+#[test]
+fn with_input_after_dot_errors() {
+    assert!(parse("1. 2.").is_err());
+}
+
+fn atom(name: &str) -> Atom {
+    Atom::try_from_str(name).unwrap()
+}
+
+fn integer(i: i64) -> Expr {
+    Expr::Value(Value::Integer(i.into()))
+}
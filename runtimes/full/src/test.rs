@@ -1,2 +1,3 @@
 pub mod r#loop;
+mod nif;
 pub mod process;
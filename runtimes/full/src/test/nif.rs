@@ -0,0 +1,319 @@
+//! Tests of the NIF layer in `lumen_rt_core`, which needs a runtime to schedule the processes it
+//! calls NIFs from and sends messages to.
+use std::mem::{self, MaybeUninit};
+use std::os::raw::{c_char, c_int, c_uint};
+use std::ptr;
+use std::slice;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::exception::Class;
+use liblumen_alloc::erts::message::{self, Message};
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::ModuleFunctionArity;
+
+use lumen_rt_core::nif::binary::*;
+use lumen_rt_core::nif::env::*;
+use lumen_rt_core::nif::resource::*;
+use lumen_rt_core::nif::term::*;
+use lumen_rt_core::nif::{self, ErlNifEntry, ErlNifFunc, InitializeLumenNifTable, NifInit};
+
+use crate::test::process;
+
+#[test]
+fn enif_make_int64_round_trips_through_enif_get_int64() {
+    with_env(|env| unsafe {
+        for &i in &[0, -1, i64::min_value(), i64::max_value()] {
+            let term = enif_make_int64(env, i);
+            let mut got = 0;
+
+            assert_eq!(enif_get_int64(env, term, &mut got), 1);
+            assert_eq!(got, i);
+        }
+    });
+}
+
+#[test]
+fn enif_get_int_of_integer_that_does_not_fit_returns_0() {
+    with_env(|env| unsafe {
+        let term = enif_make_uint64(env, u64::max_value());
+        let mut got_u64 = 0;
+
+        assert_eq!(enif_get_uint64(env, term, &mut got_u64), 1);
+        assert_eq!(got_u64, u64::max_value());
+
+        let mut got_int = 0;
+
+        assert_eq!(enif_get_int(env, term, &mut got_int), 0);
+    });
+}
+
+#[test]
+fn enif_make_atom_round_trips_through_enif_get_atom() {
+    with_env(|env| unsafe {
+        let term = enif_make_atom(env, c_str(b"nif_test_atom\0"));
+
+        assert_eq!(term, atom!("nif_test_atom"));
+
+        let mut buf = [0 as c_char; 32];
+
+        assert_eq!(
+            enif_get_atom(
+                env,
+                term,
+                buf.as_mut_ptr(),
+                buf.len() as c_uint,
+                ErlNifCharEncoding::Latin1
+            ),
+            14
+        );
+        assert_eq!(
+            slice::from_raw_parts(buf.as_ptr() as *const u8, 14),
+            b"nif_test_atom\0"
+        );
+    });
+}
+
+#[test]
+fn enif_make_binary_round_trips_through_enif_inspect_binary() {
+    with_env(|env| unsafe {
+        let mut bin = MaybeUninit::<ErlNifBinary>::uninit();
+
+        assert_eq!(enif_alloc_binary(3, bin.as_mut_ptr()), 1);
+
+        let mut bin = bin.assume_init();
+        ptr::copy_nonoverlapping(b"nif".as_ptr(), bin.data, 3);
+        let term = enif_make_binary(env, &mut bin);
+
+        assert!(bin.ref_bin.is_null());
+
+        let mut inspected = MaybeUninit::<ErlNifBinary>::uninit();
+
+        assert_eq!(enif_inspect_binary(env, term, inspected.as_mut_ptr()), 1);
+
+        let inspected = inspected.assume_init();
+
+        assert_eq!(
+            slice::from_raw_parts(inspected.data, inspected.size),
+            b"nif"
+        );
+    });
+}
+
+#[test]
+fn enif_make_tuple_from_array_round_trips_through_enif_get_tuple() {
+    with_env(|env| unsafe {
+        let elements = [enif_make_int(env, 1), enif_make_atom(env, c_str(b"two\0"))];
+        let term = enif_make_tuple_from_array(env, elements.as_ptr(), elements.len() as c_uint);
+        let mut arity = 0;
+        let mut array = ptr::null();
+
+        assert_eq!(enif_get_tuple(env, term, &mut arity, &mut array), 1);
+        assert_eq!(slice::from_raw_parts(array, arity as usize), &elements);
+    });
+}
+
+#[test]
+fn enif_make_list_from_array_round_trips_through_enif_get_list_cell() {
+    with_env(|env| unsafe {
+        let elements = [enif_make_int(env, 1), enif_make_atom(env, c_str(b"two\0"))];
+        let term = enif_make_list_from_array(env, elements.as_ptr(), elements.len() as c_uint);
+        let mut len = 0;
+
+        assert_eq!(enif_get_list_length(env, term, &mut len), 1);
+        assert_eq!(len, 2);
+
+        let mut head = Term::NONE;
+        let mut tail = Term::NONE;
+
+        assert_eq!(enif_get_list_cell(env, term, &mut head, &mut tail), 1);
+        assert_eq!(head, elements[0]);
+        assert_eq!(enif_get_list_cell(env, tail, &mut head, &mut tail), 1);
+        assert_eq!(head, elements[1]);
+        assert_eq!(tail, Term::NIL);
+    });
+}
+
+#[test]
+fn enif_alloc_resource_object_is_got_from_its_term_after_it_is_released() {
+    with_env(|env| unsafe {
+        let flags = ERL_NIF_RT_CREATE | ERL_NIF_RT_TAKEOVER;
+        let resource_type = enif_open_resource_type(
+            env,
+            ptr::null(),
+            c_str(b"nif_test_resource\0"),
+            None,
+            flags,
+            ptr::null_mut(),
+        );
+        let other_resource_type = enif_open_resource_type(
+            env,
+            ptr::null(),
+            c_str(b"nif_test_other_resource\0"),
+            None,
+            flags,
+            ptr::null_mut(),
+        );
+
+        assert!(!resource_type.is_null());
+        assert!(!other_resource_type.is_null());
+
+        let obj = enif_alloc_resource(resource_type, mem::size_of::<u64>());
+
+        assert!(!obj.is_null());
+
+        *(obj as *mut u64) = 42;
+        let term = enif_make_resource(env, obj);
+        // The term keeps the object alive
+        enif_release_resource(obj);
+
+        let mut got = ptr::null_mut();
+
+        assert_eq!(enif_get_resource(env, term, resource_type, &mut got), 1);
+        assert_eq!(got, obj);
+        assert_eq!(*(got as *mut u64), 42);
+        assert_eq!(
+            enif_get_resource(env, term, other_resource_type, &mut got),
+            0
+        );
+    });
+}
+
+#[test]
+fn enif_raise_exception_raises_error_when_nif_returns() {
+    load_nif_test();
+
+    let arc_process = process::default();
+    let reason = arc_process
+        .tuple_from_slice(&[atom!("nif_test"), arc_process.integer(1).unwrap()])
+        .unwrap();
+
+    match nif::call(&arc_process, &nif_test("raise", 1), &[reason]) {
+        Some(Err(exception)) => {
+            assert_eq!(exception.class(), Some(Class::Error { arguments: None }));
+            assert_eq!(exception.reason(), Some(reason));
+        }
+        result => panic!("expected NIF to raise, but got {:?}", result),
+    }
+}
+
+#[test]
+fn nif_returns_term_copied_to_process() {
+    load_nif_test();
+
+    let arc_process = process::default();
+    let first = arc_process.integer(1).unwrap();
+    let second = atom!("second");
+
+    assert!(nif::is_nif(&nif_test("swap", 2)));
+    assert!(nif::is_loaded(Atom::from_str("nif_test")));
+    assert_eq!(
+        nif::call(&arc_process, &nif_test("swap", 2), &[first, second]),
+        Some(Ok(arc_process.tuple_from_slice(&[second, first]).unwrap()))
+    );
+    assert!(nif::call(&arc_process, &nif_test("swap", 3), &[first, second, first]).is_none());
+}
+
+#[test]
+fn enif_send_copies_message_to_process() {
+    let arc_process = process::default();
+
+    unsafe {
+        let msg_env = enif_alloc_env();
+        let elements = [
+            enif_make_int(msg_env, 1),
+            enif_make_atom(msg_env, c_str(b"sent\0")),
+        ];
+        let msg = enif_make_list_from_array(msg_env, elements.as_ptr(), elements.len() as c_uint);
+        let to_pid = ErlNifPid {
+            pid: arc_process.pid_term(),
+        };
+
+        assert_eq!(enif_send(ptr::null_mut(), &to_pid, msg_env, msg), 1);
+
+        // The message outlives the environment it was made in
+        enif_free_env(msg_env);
+    }
+
+    let expected = arc_process
+        .list_from_slice(&[arc_process.integer(1).unwrap(), atom!("sent")])
+        .unwrap();
+
+    assert!(has_message(&arc_process, expected));
+}
+
+fn c_str(bytes: &'static [u8]) -> *const c_char {
+    bytes.as_ptr() as *const c_char
+}
+
+fn has_message(process: &Process, data: Term) -> bool {
+    process.mailbox.lock().borrow().iter().any(|message| {
+        &data
+            == match message {
+                Message::Process(message::Process { data }) => data,
+                Message::HeapFragment(message::HeapFragment { data, .. }) => data,
+            }
+    })
+}
+
+fn with_env<F: FnOnce(*mut ErlNifEnv)>(f: F) {
+    let env = enif_alloc_env();
+    f(env);
+    unsafe { enif_free_env(env) };
+}
+
+fn load_nif_test() {
+    let table: [Option<NifInit>; 1] = [Some(nif_test_nif_init)];
+
+    assert!(unsafe { InitializeLumenNifTable(table.as_ptr(), table.len()) });
+}
+
+fn nif_test(function: &str, arity: u8) -> ModuleFunctionArity {
+    ModuleFunctionArity {
+        module: Atom::from_str("nif_test"),
+        function: Atom::from_str(function),
+        arity,
+    }
+}
+
+/// What `ERL_NIF_INIT(nif_test, ...)` defines in C
+unsafe extern "C" fn nif_test_nif_init() -> *const ErlNifEntry {
+    let funcs: &'static [ErlNifFunc] = Box::leak(Box::new([
+        ErlNifFunc {
+            name: c_str(b"raise\0"),
+            arity: 1,
+            fptr: raise,
+            flags: 0,
+        },
+        ErlNifFunc {
+            name: c_str(b"swap\0"),
+            arity: 2,
+            fptr: swap,
+            flags: 0,
+        },
+    ]));
+
+    Box::leak(Box::new(ErlNifEntry {
+        major: 2,
+        minor: 15,
+        name: c_str(b"nif_test\0"),
+        num_of_funcs: funcs.len() as c_int,
+        funcs: funcs.as_ptr(),
+        load: None,
+        reload: ptr::null(),
+        upgrade: ptr::null(),
+        unload: ptr::null(),
+        vm_variant: c_str(b"beam.vanilla\0"),
+        options: 0,
+    }))
+}
+
+unsafe extern "C" fn raise(env: *mut ErlNifEnv, _argc: c_int, argv: *const Term) -> Term {
+    enif_raise_exception(env, enif_make_copy(env, *argv))
+}
+
+unsafe extern "C" fn swap(env: *mut ErlNifEnv, _argc: c_int, argv: *const Term) -> Term {
+    let elements = [*argv.add(1), *argv];
+
+    enif_make_tuple_from_array(env, elements.as_ptr(), elements.len() as c_uint)
+}
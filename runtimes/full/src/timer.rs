@@ -50,3 +50,12 @@ pub fn start(
 pub fn timeout() {
     Scheduler::current().hierarchy.write().timeout();
 }
+
+/// Marks the thread's scheduler idle and, once every scheduler is idle, moves the virtual clock to
+/// the next timer of any scheduler, and times out the timers due by then.
+///
+/// Returns `false` if the thread uses the real clock, other schedulers are still busy or there
+/// are no timers.
+pub fn skip_to_next_timeout() -> bool {
+    Scheduler::current().skip_to_next_timeout()
+}
@@ -0,0 +1,117 @@
+/*
+ * The C API of a library built by `lumen compile --crate-type staticlib` or
+ * `--crate-type cdylib`, used to call its exported Erlang functions.
+ *
+ *     lumen_start(argc, argv);
+ *
+ *     lumen_env_t *env = lumen_env_new();
+ *     lumen_term_t args[] = {lumen_make_int(env, 1), lumen_make_atom(env, "ok")};
+ *     lumen_term_t result;
+ *     if (lumen_call(env, "my_module", "my_function", args, 2, &result) == LUMEN_OK) {
+ *         ...
+ *     }
+ *     lumen_env_free(env);
+ *
+ *     lumen_stop();
+ *
+ * The runtime runs on the thread that calls `lumen_start`, and every other
+ * function must be called from that same thread. A call runs in its own
+ * Erlang process, and any processes it spawns keep running while it waits on
+ * them, until the call returns.
+ *
+ * When linking the static library, the system libraries the runtime uses must
+ * be linked as well, e.g. `-lpthread -ldl -lm` on Linux.
+ */
+#ifndef LUMEN_H
+#define LUMEN_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An Erlang term, which is only valid as long as the environment it was built in */
+typedef uint64_t lumen_term_t;
+
+/* Returned by the `lumen_make_*` functions when the term could not be built */
+#define LUMEN_NONE ((lumen_term_t)0)
+
+/* Owns the terms built in it, and the results of calls made with it */
+typedef struct lumen_env lumen_env_t;
+
+typedef enum lumen_status {
+    LUMEN_OK = 0,
+    /* A pointer was null, a name was not valid UTF-8, or a term was LUMEN_NONE */
+    LUMEN_BADARG = 1,
+    /* The runtime has not been started, or has been stopped */
+    LUMEN_NOT_RUNNING = 2,
+    /* The runtime can only be started once per program */
+    LUMEN_ALREADY_STARTED = 3,
+    /* The runtime can only be used from the thread that started it */
+    LUMEN_WRONG_THREAD = 4,
+    /* The runtime could not be started or stopped */
+    LUMEN_SYSTEM_ERROR = 5,
+    /* There is no function with the given module, name and arity */
+    LUMEN_UNDEF = 6,
+    /* The call raised an exception, or its process exited, before returning */
+    LUMEN_EXCEPTION = 7,
+    /* The call is waiting for a message that no process is left to send */
+    LUMEN_STALLED = 8,
+    /* There is not enough memory for a term or process */
+    LUMEN_NOMEM = 9,
+} lumen_status_t;
+
+/*
+ * Starts the runtime on the calling thread. The arguments are returned by
+ * `init:get_plain_arguments/0`, and may be empty.
+ */
+lumen_status_t lumen_start(int argc, const char *const *argv);
+
+/* Stops the runtime, after which it can no longer be used */
+lumen_status_t lumen_stop(void);
+
+/*
+ * Calls `module:function(argv...)`, with `argc` as the arity.
+ *
+ * On `LUMEN_OK`, `result` is set to the returned value, and on
+ * `LUMEN_EXCEPTION` to the reason of the exception, built in `env`.
+ */
+lumen_status_t lumen_call(lumen_env_t *env,
+                          const char *module,
+                          const char *function,
+                          const lumen_term_t *argv,
+                          size_t argc,
+                          lumen_term_t *result);
+
+lumen_env_t *lumen_env_new(void);
+/* Frees the environment, along with every term built in it */
+void lumen_env_free(lumen_env_t *env);
+
+/*
+ * The elements of tuples and lists are not copied, so they must be built in
+ * an environment that lives at least as long as the one the tuple or list is
+ * built in. A tuple or list with a `LUMEN_NONE` element is not built.
+ */
+lumen_term_t lumen_make_atom(lumen_env_t *env, const char *name);
+lumen_term_t lumen_make_int(lumen_env_t *env, int64_t value);
+lumen_term_t lumen_make_binary(lumen_env_t *env, const uint8_t *data, size_t len);
+lumen_term_t lumen_make_tuple(lumen_env_t *env, const lumen_term_t *elements, size_t arity);
+lumen_term_t lumen_make_list(lumen_env_t *env, const lumen_term_t *elements, size_t len);
+
+/* Each returns false if the term is not of the expected type */
+bool lumen_get_int(lumen_term_t term, int64_t *value);
+/* The name is not null-terminated, but lives as long as the program */
+bool lumen_get_atom(lumen_term_t term, const char **name, size_t *len);
+bool lumen_get_binary(lumen_term_t term, const uint8_t **data, size_t *len);
+bool lumen_get_tuple(lumen_term_t term, const lumen_term_t **elements, size_t *arity);
+bool lumen_get_list_cell(lumen_term_t term, lumen_term_t *head, lumen_term_t *tail);
+bool lumen_is_empty_list(lumen_term_t term);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* LUMEN_H */
@@ -1,10 +1,16 @@
+pub mod apply;
+pub mod nif;
 pub mod receive;
+pub mod stacktrace;
 
+use std::alloc::Layout;
 use std::convert::TryInto;
 use std::panic;
+use std::ptr;
 
+use liblumen_alloc::erts::exception::AllocResult;
 use liblumen_alloc::erts::term::prelude::*;
-use liblumen_alloc::erts::Process;
+use liblumen_alloc::erts::{HeapFragment, Process};
 
 use lumen_rt_core::process::current_process;
 use lumen_rt_core::registry;
@@ -46,3 +52,35 @@ pub extern "C" fn builtin_send(to_term: Term, msg: Term) -> Term {
         Term::NONE
     }
 }
+
+/// Constructs a term that did not fit on the heap of `process` in a heap fragment of `layout`,
+/// which is attached to `process` if `construct` succeeds.
+///
+/// Compiled code holds terms that a collection would move, so builtins cannot collect garbage to
+/// make room on the heap.
+pub(crate) fn construct_in_heap_fragment<F>(
+    process: &Process,
+    layout: Layout,
+    construct: F,
+) -> AllocResult<Term>
+where
+    F: FnOnce(&mut HeapFragment) -> AllocResult<Term>,
+{
+    let mut heap_fragment = HeapFragment::new(layout)?;
+    let heap_fragment_ref = unsafe { heap_fragment.as_mut() };
+    // Start the fragment with `NONE`, so that dropping it never releases uninitialized memory
+    unsafe { ptr::write(heap_fragment_ref.data().as_ptr() as *mut Term, Term::NONE) };
+
+    match construct(heap_fragment_ref) {
+        Ok(term) => {
+            process.attach_fragment(heap_fragment_ref);
+
+            Ok(term)
+        }
+        Err(alloc) => {
+            unsafe { ptr::drop_in_place(heap_fragment.as_ptr()) };
+
+            Err(alloc)
+        }
+    }
+}
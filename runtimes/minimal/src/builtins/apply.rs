@@ -0,0 +1,207 @@
+//! Calls to functions which are only known at runtime, i.e. `Module:function(...)` with a
+//! variable `Module` or `function`, `apply/3`, and calls to funs captured with `fun M:F/A`.
+//!
+//! The compiler lowers dynamic calls to calls to `erlang:apply/3`, and captured functions to
+//! export closures made by `erlang:make_fun/3`, so that both look the callee up in the dispatch
+//! table.
+use std::convert::TryInto;
+use std::ffi::c_void;
+use std::mem;
+use std::sync::Arc;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::apply;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::closure::ClosureLayout;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::erts::Arity;
+use liblumen_alloc::ModuleFunctionArity;
+
+use lumen_rt_core::nif;
+use lumen_rt_core::process::current_process;
+
+use crate::builtins;
+
+use super::stacktrace;
+
+/// Calls `module:function` with the elements of the `arguments` list, raising `undef` if no
+/// compiled function or NIF of that name exists.
+#[unwind(allowed)]
+#[export_name = "erlang:apply/3"]
+pub extern "C" fn apply_3(module: Term, function: Term, arguments: Term) -> Term {
+    let arc_process = current_process();
+
+    let (module, function) = match (module.decode(), function.decode()) {
+        (Ok(TypedTerm::Atom(module)), Ok(TypedTerm::Atom(function))) => (module, function),
+        _ => raise(&arc_process, atom!("badarg")),
+    };
+    let arguments: Vec<Term> = match arguments.decode() {
+        Ok(TypedTerm::Nil) => Vec::new(),
+        Ok(TypedTerm::List(cons)) => match cons.into_iter().collect() {
+            Ok(arguments) => arguments,
+            Err(_) => raise(&arc_process, atom!("badarg")),
+        },
+        _ => raise(&arc_process, atom!("badarg")),
+    };
+
+    call(&arc_process, module, function, &arguments)
+}
+
+/// Makes the export closure of `module:function/arity`, which need not exist until it is called.
+///
+/// Export closures are only made up to arity 16, raising `system_limit` above that.
+#[unwind(allowed)]
+#[export_name = "erlang:make_fun/3"]
+pub extern "C" fn make_fun_3(module: Term, function: Term, arity: Term) -> Term {
+    let arc_process = current_process();
+
+    let (module, function, arity) = match (module.decode(), function.decode(), arity.decode()) {
+        (
+            Ok(TypedTerm::Atom(module)),
+            Ok(TypedTerm::Atom(function)),
+            Ok(TypedTerm::SmallInteger(arity)),
+        ) => match arity.try_into() {
+            Ok(arity) => (module, function, arity),
+            Err(_) => raise(&arc_process, atom!("badarg")),
+        },
+        _ => raise(&arc_process, atom!("badarg")),
+    };
+
+    // Compiled code calls the code of a closure with as many arguments as its arity
+    let code = match call_export(arity) {
+        Some(code) => code,
+        None => raise(&arc_process, atom!("system_limit")),
+    };
+    let result = Closure::new_export(
+        &mut *arc_process.acquire_heap(),
+        module,
+        function,
+        arity,
+        Some(code),
+    )
+    .map(|closure| closure.into())
+    .or_else(|_| {
+        let layout = ClosureLayout::for_env_len(0).layout().clone();
+
+        builtins::construct_in_heap_fragment(&arc_process, layout, |heap_fragment| {
+            Closure::new_export(heap_fragment, module, function, arity, Some(code))
+                .map(|closure| closure.into())
+        })
+    });
+
+    match result {
+        Ok(closure) => closure,
+        Err(_) => raise(&arc_process, atom!("system_limit")),
+    }
+}
+
+// Private
+
+/// Defines the code of export closures, one function per arity, which compiled code calls with a
+/// pointer to the closure followed by the arguments.
+macro_rules! call_exports {
+    ($($arity:literal => $name:ident($($argument:ident),*);)*) => {
+        $(
+            #[unwind(allowed)]
+            unsafe extern "C" fn $name(closure: *mut Term, $($argument: Term),*) -> Term {
+                let closure = Closure::from_raw_term(closure);
+
+                call(
+                    &current_process(),
+                    closure.module(),
+                    closure.function(),
+                    &[$($argument),*],
+                )
+            }
+        )*
+
+        /// The code of the export closures of `arity`, if there is one
+        fn call_export(arity: Arity) -> Option<*const c_void> {
+            let code = match arity {
+                $($arity => $name as *const c_void,)*
+                _ => return None,
+            };
+
+            Some(code)
+        }
+    };
+}
+
+call_exports! {
+    0 => call_export_0();
+    1 => call_export_1(a);
+    2 => call_export_2(a, b);
+    3 => call_export_3(a, b, c);
+    4 => call_export_4(a, b, c, d);
+    5 => call_export_5(a, b, c, d, e);
+    6 => call_export_6(a, b, c, d, e, f);
+    7 => call_export_7(a, b, c, d, e, f, g);
+    8 => call_export_8(a, b, c, d, e, f, g, h);
+    9 => call_export_9(a, b, c, d, e, f, g, h, i);
+    10 => call_export_10(a, b, c, d, e, f, g, h, i, j);
+    11 => call_export_11(a, b, c, d, e, f, g, h, i, j, k);
+    12 => call_export_12(a, b, c, d, e, f, g, h, i, j, k, l);
+    13 => call_export_13(a, b, c, d, e, f, g, h, i, j, k, l, m);
+    14 => call_export_14(a, b, c, d, e, f, g, h, i, j, k, l, m, n);
+    15 => call_export_15(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o);
+    16 => call_export_16(a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p);
+}
+
+fn call(arc_process: &Arc<Process>, module: Atom, function: Atom, arguments: &[Term]) -> Term {
+    // A function cannot have more arguments than fit in an arity, so cannot be defined
+    let arity = match arguments.len().try_into() {
+        Ok(arity) => arity,
+        Err(_) => raise(arc_process, atom!("undef")),
+    };
+    let module_function_arity = ModuleFunctionArity {
+        module,
+        function,
+        arity,
+    };
+
+    // A NIF replaces the Erlang function of the same name
+    match nif::call(arc_process, &module_function_arity, arguments) {
+        Some(Ok(returned)) => return returned,
+        Some(Err(exception)) => raise(
+            arc_process,
+            exception.reason().unwrap_or_else(|| atom!("badarg")),
+        ),
+        None => (),
+    }
+
+    if apply::find_symbol(&module_function_arity).is_none() {
+        raise(arc_process, atom!("undef"));
+    }
+
+    // Like a direct call, a builtin that fails returns `NONE` to the caller
+    unsafe { apply::apply(&module_function_arity, arguments) }.unwrap_or(Term::NONE)
+}
+
+/// Raises `error:reason` in the compiled code that made the call, in the same form as the
+/// exceptions raised by compiled code itself.
+pub(crate) fn raise(process: &Process, reason: Term) -> ! {
+    let trace = stacktrace::builtin_trace_capture();
+    let elements = [atom!("error"), reason, trace];
+    let result = process.tuple_from_slice(&elements).or_else(|_| {
+        let (layout, _) = Tuple::layout_for(&elements);
+
+        builtins::construct_in_heap_fragment(process, layout, |heap_fragment| {
+            heap_fragment
+                .tuple_from_slice(&elements)
+                .map(|exception| exception.into())
+        })
+    });
+    // Not even a heap fragment for the exception can be allocated, so the process cannot continue
+    let exception = match result {
+        Ok(exception) => exception,
+        Err(_) => std::process::abort(),
+    };
+
+    unsafe {
+        panic::__lumen_start_panic(mem::transmute::<Term, usize>(exception));
+    }
+
+    // Only reached if the exception could not be raised, so there is nowhere to return to
+    std::process::abort()
+}
@@ -0,0 +1,82 @@
+//! Builtins for modules that load NIF libraries.
+//!
+//! NIF libraries are linked into the executable and loaded when it starts. The compiler starts
+//! every function of a module that calls `erlang:load_nif/2` with a call to
+//! `__lumen_builtin_is_nif`, and calls the NIF through `erlang:apply/3` instead of the Erlang
+//! function if a library defines one, so that NIFs replace their stubs however they are called.
+use std::convert::TryInto;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::ProcessFlags;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::ModuleFunctionArity;
+
+use lumen_rt_core::nif;
+use lumen_rt_core::process::current_process;
+
+use super::{apply, stacktrace};
+
+/// Returns whether a loaded NIF library defines `module:function/arity`
+#[export_name = "__lumen_builtin_is_nif"]
+pub extern "C" fn builtin_is_nif(module: Term, function: Term, arity: Term) -> Term {
+    let is_nif = match (module.decode(), function.decode(), arity.decode()) {
+        (
+            Ok(TypedTerm::Atom(module)),
+            Ok(TypedTerm::Atom(function)),
+            Ok(TypedTerm::SmallInteger(arity)),
+        ) => match arity.try_into() {
+            Ok(arity) => nif::is_nif(&ModuleFunctionArity {
+                module,
+                function,
+                arity,
+            }),
+            Err(_) => false,
+        },
+        _ => false,
+    };
+
+    is_nif.into()
+}
+
+/// Returns `ok` if a NIF library for the calling module was loaded when the executable started,
+/// otherwise `{error, {load_failed, Text}}`.
+///
+/// As the library is already loaded, neither `path` nor `load_info` are used.
+#[unwind(allowed)]
+#[export_name = "erlang:load_nif/2"]
+pub extern "C" fn load_nif_2(_path: Term, _load_info: Term) -> Term {
+    let text = match stacktrace::caller() {
+        Some(caller) if nif::is_loaded(caller.module) => return atom!("ok"),
+        Some(caller) => format!("no NIF library for {} is linked", caller.module),
+        None => "load_nif/2 can only be called from a compiled module".to_string(),
+    };
+
+    let arc_process = current_process();
+    let result = arc_process
+        .charlist_from_str(&text)
+        .and_then(|text| arc_process.tuple_from_slice(&[atom!("load_failed"), text]))
+        .and_then(|reason| arc_process.tuple_from_slice(&[atom!("error"), reason]));
+
+    match result {
+        Ok(error) => error,
+        Err(_) => {
+            arc_process.set_flags(ProcessFlags::GrowHeap | ProcessFlags::ForceGC);
+            Term::NONE
+        }
+    }
+}
+
+/// Raises `error:reason` from the stub of a NIF whose library was not loaded
+#[unwind(allowed)]
+#[export_name = "erlang:nif_error/1"]
+pub extern "C" fn nif_error_1(reason: Term) -> Term {
+    apply::raise(&current_process(), reason)
+}
+
+/// Raises `error:reason` from the stub of a NIF whose library was not loaded, which, like
+/// `erlang:error/2`, is called with the arguments of the stub.
+#[unwind(allowed)]
+#[export_name = "erlang:nif_error/2"]
+pub extern "C" fn nif_error_2(reason: Term, _arguments: Term) -> Term {
+    apply::raise(&current_process(), reason)
+}
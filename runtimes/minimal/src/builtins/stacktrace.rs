@@ -0,0 +1,269 @@
+//! Stacktraces of the exceptions raised by compiled code.
+//!
+//! When an exception is raised, `trace_capture` walks the native frames of the process, keeping
+//! the return address and the address of the function of each frame in an opaque handle that is
+//! raised with the exception. A `catch` that binds the stacktrace passes the handle to
+//! `trace_construct`, which maps those functions to
+//! `{Module, Function, Arity, [{file, File}, {line, Line}]}` through the dispatch and location
+//! tables emitted by the compiler, skipping the frames of the runtime.
+//!
+//! `line` is the line of the call in the function, looked up from the return address in the
+//! DWARF line table of the program. Without debug info, such as for JIT compiled code, it is the
+//! line where the function is defined, from the location table.
+use std::alloc::Layout;
+use std::ffi::c_void;
+use std::mem;
+
+use unwind::{
+    _Unwind_Backtrace, _Unwind_Context, _Unwind_FindEnclosingFunction, _Unwind_GetIP,
+    _Unwind_Reason_Code,
+};
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::apply;
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::alloc::TermAlloc;
+use liblumen_alloc::erts::process::Process;
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::ModuleFunctionArity;
+
+use lumen_rt_core::process::current_process;
+
+use crate::builtins;
+
+/// The most native frames captured, which includes the frames of the runtime
+const MAX_NATIVE_FRAMES: usize = 64;
+/// The most frames in a stacktrace, like the default `backtrace_depth` of ERTS
+const MAX_FRAMES: usize = 8;
+
+/// Captures the stacktrace of the exception being raised, returning a handle to it
+#[export_name = "__lumen_builtin_trace_capture"]
+pub extern "C" fn builtin_trace_capture() -> Term {
+    let arc_process = current_process();
+
+    // An exception is raised whether or not there is room for its stacktrace, but then
+    // `erlang:get_stacktrace/0` must not return the one of an earlier exception
+    let handle = match arc_process.resource(Box::new(Trace::capture())) {
+        Ok(handle) => handle,
+        Err(_) => {
+            *arc_process.stacktrace.lock() = None;
+
+            return Term::NIL;
+        }
+    };
+    if let Ok(TypedTerm::ResourceReference(resource)) = handle.decode() {
+        *arc_process.stacktrace.lock() = Some(Resource::clone(&resource));
+    }
+
+    handle
+}
+
+/// Constructs the stacktrace captured as `capture` by `__lumen_builtin_trace_capture`
+#[export_name = "__lumen_builtin_trace_construct"]
+pub extern "C" fn builtin_trace_construct(capture: Term) -> Term {
+    let arc_process = current_process();
+
+    match capture.decode() {
+        Ok(TypedTerm::ResourceReference(resource)) => match resource.downcast_ref::<Trace>() {
+            Some(trace) => construct(&arc_process, trace),
+            None => Term::NIL,
+        },
+        _ => Term::NIL,
+    }
+}
+
+/// Returns the stacktrace of the last exception raised in the calling process
+#[export_name = "erlang:get_stacktrace/0"]
+pub extern "C" fn get_stacktrace_0() -> Term {
+    let arc_process = current_process();
+    let resource = arc_process.stacktrace.lock().clone();
+
+    match resource
+        .as_ref()
+        .and_then(|resource| resource.downcast_ref::<Trace>())
+    {
+        Some(trace) => construct(&arc_process, trace),
+        None => Term::NIL,
+    }
+}
+
+/// Returns the innermost compiled function on the stack, i.e. the function that called the
+/// builtin calling this
+pub(super) fn caller() -> Option<ModuleFunctionArity> {
+    Trace::capture()
+        .frames
+        .iter()
+        .find_map(|frame| apply::find_ident(frame.function).copied())
+}
+
+// Private
+
+/// Constructs the stacktrace of `trace` on the heap of `process`.
+///
+/// Compiled code holds terms that a collection would move, so a stacktrace that does not fit on
+/// the heap is constructed in a heap fragment instead of collecting garbage.  Only when even that
+/// cannot be allocated is the stacktrace empty.
+fn construct(process: &Process, trace: &Trace) -> Term {
+    let frames = trace.compiled_frames();
+    let result = Trace::construct(&mut *process.acquire_heap(), &frames);
+
+    match result {
+        Ok(stacktrace) => stacktrace,
+        Err(_) => construct_in_heap_fragment(process, &frames).unwrap_or(Term::NIL),
+    }
+}
+
+fn construct_in_heap_fragment(process: &Process, frames: &[CompiledFrame]) -> AllocResult<Term> {
+    let layout = Layout::array::<Term>(Trace::need_in_words(frames)).unwrap();
+
+    builtins::construct_in_heap_fragment(process, layout, |heap_fragment| {
+        Trace::construct(heap_fragment, frames)
+    })
+}
+
+/// A native frame, innermost first
+struct Frame {
+    /// The start of the function
+    function: *const c_void,
+    /// Where the function returns to, which is just after the call it is in
+    return_address: usize,
+}
+
+/// A frame of a compiled Erlang function with its source location
+struct CompiledFrame {
+    module_function_arity: &'static ModuleFunctionArity,
+    location: Option<(&'static str, u32)>,
+}
+
+struct Trace {
+    frames: Vec<Frame>,
+}
+
+impl Trace {
+    fn capture() -> Self {
+        extern "C" fn trace_fn(ctx: *mut _Unwind_Context, arg: *mut c_void) -> _Unwind_Reason_Code {
+            let frames = unsafe { &mut *(arg as *mut Vec<Frame>) };
+            let return_address = unsafe { _Unwind_GetIP(ctx) };
+            let function = unsafe { _Unwind_FindEnclosingFunction(return_address as *mut c_void) };
+            if !function.is_null() {
+                frames.push(Frame {
+                    function: function as *const c_void,
+                    return_address: return_address as usize,
+                });
+            }
+
+            if frames.len() < MAX_NATIVE_FRAMES {
+                _Unwind_Reason_Code::_URC_NO_REASON
+            } else {
+                _Unwind_Reason_Code::_URC_NORMAL_STOP
+            }
+        }
+
+        let mut frames = Vec::with_capacity(MAX_NATIVE_FRAMES);
+        // Whatever was walked before an error is still a valid, if short, stacktrace
+        unsafe {
+            _Unwind_Backtrace(trace_fn, &mut frames as *mut _ as *mut c_void);
+        }
+
+        Self { frames }
+    }
+
+    fn compiled_frames(&self) -> Vec<CompiledFrame> {
+        self.frames
+            .iter()
+            // Only compiled Erlang functions are in the dispatch table
+            .filter_map(|frame| {
+                apply::find_ident(frame.function).map(|module_function_arity| CompiledFrame {
+                    module_function_arity,
+                    location: apply::find_location(module_function_arity).map(|(file, line)| {
+                        (file, call_line(frame.return_address).unwrap_or(line))
+                    }),
+                })
+            })
+            .take(MAX_FRAMES)
+            .collect()
+    }
+
+    fn construct<A: TermAlloc>(heap: &mut A, frames: &[CompiledFrame]) -> AllocResult<Term> {
+        let mut frame_vec = Vec::with_capacity(frames.len());
+        for compiled_frame in frames {
+            frame_vec.push(frame(heap, compiled_frame)?);
+        }
+
+        list(heap, &frame_vec)
+    }
+
+    /// An upper bound on the words `construct` allocates for `frames`, which includes the padding
+    /// a `HeapFragment` may add to each allocation
+    fn need_in_words(frames: &[CompiledFrame]) -> usize {
+        let cons_words = mem::size_of::<Cons>() / mem::size_of::<Term>() + 1;
+        let tuple_words =
+            |len: usize| Tuple::need_in_words_from_elements(&vec![Term::NIL; len]) + 1;
+
+        frames
+            .iter()
+            .map(|compiled_frame| {
+                let location_words = match compiled_frame.location {
+                    Some((file, _)) => {
+                        file.chars().count() * cons_words + 2 * tuple_words(2) + 2 * cons_words
+                    }
+                    None => 0,
+                };
+
+                cons_words + tuple_words(4) + location_words
+            })
+            .sum::<usize>()
+            .max(1)
+    }
+}
+
+/// The line of the call that returns to `return_address`
+fn call_line(return_address: usize) -> Option<u32> {
+    let mut line = None;
+    // The return address is after the call, so it may be on the line after the call
+    backtrace::resolve((return_address - 1) as *mut c_void, |symbol| {
+        if line.is_none() {
+            line = symbol.lineno();
+        }
+    });
+
+    line
+}
+
+fn frame<A: TermAlloc>(heap: &mut A, compiled_frame: &CompiledFrame) -> AllocResult<Term> {
+    let location = match compiled_frame.location {
+        Some((file, line)) => {
+            let file_charlist = list_from_str(heap, file)?;
+            let file: Term = heap
+                .tuple_from_slice(&[atom!("file"), file_charlist])?
+                .into();
+            let line = heap.integer(line as usize)?;
+            let line: Term = heap.tuple_from_slice(&[atom!("line"), line])?.into();
+
+            list(heap, &[file, line])?
+        }
+        None => Term::NIL,
+    };
+
+    let module_function_arity = compiled_frame.module_function_arity;
+    let arity = heap.integer(module_function_arity.arity)?;
+
+    // Atoms are immediates, so encoding them cannot fail
+    heap.tuple_from_slice(&[
+        module_function_arity.module.encode().unwrap(),
+        module_function_arity.function.encode().unwrap(),
+        arity,
+        location,
+    ])
+    .map(From::from)
+}
+
+fn list<A: TermAlloc>(heap: &mut A, elements: &[Term]) -> AllocResult<Term> {
+    heap.list_from_slice(elements)
+        .map(|option_cons| option_cons.map(From::from).unwrap_or(Term::NIL))
+}
+
+fn list_from_str<A: TermAlloc>(heap: &mut A, s: &str) -> AllocResult<Term> {
+    heap.charlist_from_str(s)
+        .map(|option_cons| option_cons.map(From::from).unwrap_or(Term::NIL))
+}
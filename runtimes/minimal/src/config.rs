@@ -6,6 +6,8 @@ use std::path::Path;
 
 use clap::{App, AppSettings, Arg, SubCommand};
 
+use lumen_rt_core::time::monotonic::Clock;
+
 pub type ConfigResult<T> = std::result::Result<T, ConfigError>;
 //TODO: Needs to be HashMap<Atom, HashMap<Atom, Term>>
 pub type AppConfig = HashMap<String, HashMap<String, String>>;
@@ -51,6 +53,9 @@ pub struct Config {
     pub debug: bool,
     pub name: Option<String>,
     pub cookie: Option<String>,
+    pub clock: Clock,
+    /// The number of scheduler threads, including the main thread
+    pub schedulers: usize,
     pub command: Command,
     pub extra: Vec<String>,
 }
@@ -92,6 +97,22 @@ impl Config {
                      .help("The secret cookie to use in distributed mode")
                      .takes_value(true)
                      .env("COOKIE"))
+            .arg(Arg::with_name("time")
+                     .long("time")
+                     .help("The clock timers are run by\n\
+                            With virtual, time only moves forward when every process is waiting, \
+                            straight to when the next timer is due")
+                     .takes_value(true)
+                     .possible_values(&["real", "virtual"])
+                     .default_value("real")
+                     .env("LUMEN_TIME"))
+            .arg(Arg::with_name("schedulers")
+                     .long("schedulers")
+                     .help("The number of scheduler threads that run processes\n\
+                            Defaults to the number of logical CPUs")
+                     .takes_value(true)
+                     .validator(is_valid_scheduler_count)
+                     .env("LUMEN_SCHEDULERS"))
             .arg(Arg::with_name("extra")
                      .last(true)
                      .multiple(true)
@@ -132,6 +153,15 @@ impl Config {
             debug: matches.is_present("debug"),
             name: matches.value_of("name").map(|v| v.to_string()),
             cookie: matches.value_of("cookie").map(|v| v.to_string()),
+            clock: match matches.value_of("time") {
+                Some("virtual") => Clock::Virtual,
+                _ => Clock::Real,
+            },
+            schedulers: match matches.value_of("schedulers") {
+                // Already checked by `is_valid_scheduler_count`
+                Some(schedulers) => schedulers.parse().unwrap(),
+                None => num_cpus::get(),
+            },
             command,
             extra: extra.iter().map(|v| v.to_string()).collect(),
         })
@@ -143,6 +173,13 @@ fn is_valid_node_name(_f: String) -> Result<(), String> {
     Ok(())
 }
 
+fn is_valid_scheduler_count(count: String) -> Result<(), String> {
+    match count.parse::<usize>() {
+        Ok(count) if 0 < count => Ok(()),
+        _ => Err(format!("{} is not a positive number of schedulers", count)),
+    }
+}
+
 fn with_file<T>(v: Option<&OsStr>, default: T, fun: fn(String) -> T) -> ConfigResult<T> {
     match v {
         None => Ok(default),
@@ -0,0 +1,542 @@
+//! The C API used to call compiled Erlang from a C or C++ program, which links a library built
+//! with `--crate-type staticlib` or `--crate-type cdylib`.
+//!
+//! These functions are declared in `include/lumen.h`, which the compiler writes next to the
+//! library. The runtime is started with `lumen_start`, after which the thread that started it
+//! runs the scheduler whenever it calls into Erlang with `lumen_call`, so every other function
+//! must be called from that thread.
+//!
+//! Terms passed to and returned from Erlang are built in an environment (`lumen_env_t`), which
+//! owns the memory of its terms until it is freed.
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::slice;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, ThreadId};
+
+use anyhow::anyhow;
+use hashbrown::HashMap;
+use lazy_static::lazy_static;
+use log::Level;
+use once_cell::sync::OnceCell;
+
+use liblumen_core::locks::Mutex;
+
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::apply;
+use liblumen_alloc::erts::exception::AllocResult;
+use liblumen_alloc::erts::process::alloc;
+use liblumen_alloc::erts::process::{Priority, Process, Status};
+use liblumen_alloc::erts::term::prelude::*;
+use liblumen_alloc::{CloneToProcess, ModuleFunctionArity};
+
+use lumen_rt_core::heap_fragments::HeapFragments;
+use lumen_rt_core::nif;
+use lumen_rt_core::process::current_process;
+use lumen_rt_core::time::monotonic;
+
+use crate::config::Config;
+use crate::logging;
+use crate::scheduler::Scheduler;
+
+/// The result of the functions in the embedding API, `lumen_status_t` in C
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LumenStatus {
+    Ok = 0,
+    /// A pointer was null, a name was not valid UTF-8, or a term was `NONE`
+    Badarg = 1,
+    /// The runtime has not been started, or has been stopped
+    NotRunning = 2,
+    /// The runtime can only be started once per program
+    AlreadyStarted = 3,
+    /// The runtime can only be used from the thread that started it
+    WrongThread = 4,
+    /// The runtime could not be started or stopped
+    SystemError = 5,
+    /// There is no function with the given module, name and arity
+    Undef = 6,
+    /// The call raised an exception, or its process exited, before returning
+    Exception = 7,
+    /// The call is waiting for a message that no process is left to send
+    Stalled = 8,
+    /// There is not enough memory for a term or process
+    Nomem = 9,
+}
+
+/// The thread that started the runtime, which is the only one with a scheduler
+static THREAD: OnceCell<ThreadId> = OnceCell::new();
+static STOPPED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref CALL_BY_PID: Mutex<HashMap<Pid, Call>> = Mutex::new(Default::default());
+}
+
+/// Starts the runtime on the calling thread
+///
+/// This initializes the atom and dispatch tables of the compiled code, the same way `main` does
+/// for an executable, then sets up logging and the scheduler.
+#[no_mangle]
+pub unsafe extern "C" fn lumen_start(argc: c_int, argv: *const *const c_char) -> LumenStatus {
+    if argc < 0 || (argc > 0 && argv.is_null()) {
+        return LumenStatus::Badarg;
+    }
+    if THREAD.set(thread::current().id()).is_err() {
+        return LumenStatus::AlreadyStarted;
+    }
+
+    if liblumen_crt::init() != 0 {
+        return LumenStatus::SystemError;
+    }
+    if crate::env::init_argv(argv, argc as u32).is_err() {
+        return LumenStatus::Badarg;
+    }
+    // The arguments belong to the embedding program, so the runtime is only configured by the
+    // environment, such as `LUMEN_TIME`
+    match Config::from_argv(
+        env!("CARGO_PKG_NAME").to_string(),
+        env!("CARGO_PKG_VERSION").to_string(),
+        Vec::new(),
+    ) {
+        Ok(config) => monotonic::set_clock(config.clock),
+        Err(_) => return LumenStatus::Badarg,
+    }
+    let level_filter = Level::Info.to_level_filter();
+    if logging::init(level_filter).is_err() {
+        return LumenStatus::SystemError;
+    }
+
+    // The scheduler is created for this thread on first use, but no init process is spawned, as
+    // the program only runs the calls made through `lumen_call`
+    Scheduler::current();
+
+    LumenStatus::Ok
+}
+
+/// Stops the runtime, after which it can no longer be used
+#[no_mangle]
+pub extern "C" fn lumen_stop() -> LumenStatus {
+    if let Err(status) = check_thread() {
+        return status;
+    }
+
+    STOPPED.store(true, Ordering::SeqCst);
+
+    match Scheduler::current().shutdown() {
+        Ok(_) => LumenStatus::Ok,
+        Err(_) => LumenStatus::SystemError,
+    }
+}
+
+/// Calls `module:function(argv...)` in a new process, and runs the scheduler until it returns
+///
+/// On success, or if the call raised an exception, `result` is set to the returned value, or the
+/// reason of the exception, built in `env`.
+#[no_mangle]
+pub unsafe extern "C" fn lumen_call(
+    env: *mut Env,
+    module: *const c_char,
+    function: *const c_char,
+    argv: *const Term,
+    argc: usize,
+    result: *mut Term,
+) -> LumenStatus {
+    if let Err(status) = check_thread() {
+        return status;
+    }
+    if result.is_null() {
+        return LumenStatus::Badarg;
+    }
+    let arguments = match term_slice(env, argv, argc) {
+        Ok(arguments) => arguments,
+        Err(status) => return status,
+    };
+
+    // A function can only exist if its module and name are already atoms
+    let module = match existing_atom(module) {
+        Ok(Some(atom)) => atom,
+        Ok(None) => return LumenStatus::Undef,
+        Err(status) => return status,
+    };
+    let function = match existing_atom(function) {
+        Ok(Some(atom)) => atom,
+        Ok(None) => return LumenStatus::Undef,
+        Err(status) => return status,
+    };
+    if argc > u8::max_value() as usize {
+        return LumenStatus::Undef;
+    }
+    let module_function_arity = ModuleFunctionArity {
+        module,
+        function,
+        arity: argc as u8,
+    };
+    if !nif::is_nif(&module_function_arity) && apply::find_symbol(&module_function_arity).is_none()
+    {
+        return LumenStatus::Undef;
+    }
+
+    call(&mut *env, module_function_arity, arguments, &mut *result)
+}
+
+/// Creates an environment to build terms in
+#[no_mangle]
+pub extern "C" fn lumen_env_new() -> *mut Env {
+    Box::into_raw(Box::new(Env::default()))
+}
+
+/// Frees an environment, along with every term built in it
+#[no_mangle]
+pub unsafe extern "C" fn lumen_env_free(env: *mut Env) {
+    if !env.is_null() {
+        drop(Box::from_raw(env));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lumen_make_atom(_env: *mut Env, name: *const c_char) -> Term {
+    if name.is_null() {
+        return Term::NONE;
+    }
+
+    match CStr::from_ptr(name).to_str() {
+        Ok(name) => Atom::try_from_str(name)
+            .map(|atom| atom.encode().unwrap())
+            .unwrap_or(Term::NONE),
+        Err(_) => Term::NONE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lumen_make_int(env: *mut Env, value: i64) -> Term {
+    if env.is_null() {
+        return Term::NONE;
+    }
+
+    (*env).integer(value).unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lumen_make_binary(env: *mut Env, data: *const u8, len: usize) -> Term {
+    if env.is_null() || (len > 0 && data.is_null()) {
+        return Term::NONE;
+    }
+    let bytes = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    };
+
+    (*env).binary(bytes).unwrap_or(Term::NONE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lumen_make_tuple(
+    env: *mut Env,
+    elements: *const Term,
+    arity: usize,
+) -> Term {
+    match term_slice(env, elements, arity) {
+        Ok(elements) => (*env).tuple(elements).unwrap_or(Term::NONE),
+        Err(_) => Term::NONE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lumen_make_list(env: *mut Env, elements: *const Term, len: usize) -> Term {
+    match term_slice(env, elements, len) {
+        Ok(elements) => (*env).list(elements, Term::NIL).unwrap_or(Term::NONE),
+        Err(_) => Term::NONE,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lumen_get_int(term: Term, value: *mut i64) -> bool {
+    let int: i64 = match term.decode() {
+        Ok(TypedTerm::SmallInteger(small)) => small.into(),
+        Ok(TypedTerm::BigInteger(big)) => {
+            let bytes = big.to_signed_bytes_le();
+            if bytes.len() > 8 {
+                return false;
+            }
+            // Sign-extend the compacted representation
+            let fill = if bytes[bytes.len() - 1] & 0x80 == 0 {
+                0
+            } else {
+                0xff
+            };
+            let mut buf = [fill; 8];
+            buf[..bytes.len()].copy_from_slice(&bytes);
+            i64::from_le_bytes(buf)
+        }
+        _ => return false,
+    };
+
+    *value = int;
+    true
+}
+
+/// The name is not null-terminated, but lives as long as the program
+#[no_mangle]
+pub unsafe extern "C" fn lumen_get_atom(
+    term: Term,
+    name: *mut *const c_char,
+    len: *mut usize,
+) -> bool {
+    match term.decode() {
+        Ok(TypedTerm::Atom(atom)) => {
+            let atom_name = atom.name();
+            *name = atom_name.as_ptr() as *const c_char;
+            *len = atom_name.len();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Bitstrings which aren't a whole number of bytes are not binaries, so return `false`
+#[no_mangle]
+pub unsafe extern "C" fn lumen_get_binary(
+    term: Term,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> bool {
+    // The bytes are not owned by the boxed pointers, so they outlive them
+    let bytes: &[u8] = match term.decode() {
+        Ok(TypedTerm::HeapBinary(heap_bin)) => &*(heap_bin.as_bytes() as *const [u8]),
+        Ok(TypedTerm::ProcBin(proc_bin)) => &*(proc_bin.as_bytes() as *const [u8]),
+        Ok(TypedTerm::BinaryLiteral(binary_literal)) => {
+            &*(binary_literal.as_bytes() as *const [u8])
+        }
+        Ok(TypedTerm::SubBinary(subbinary)) if subbinary.is_binary() && subbinary.is_aligned() => {
+            &*(subbinary.as_bytes_unchecked() as *const [u8])
+        }
+        _ => return false,
+    };
+
+    *data = bytes.as_ptr();
+    *len = bytes.len();
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lumen_get_tuple(
+    term: Term,
+    elements: *mut *const Term,
+    arity: *mut usize,
+) -> bool {
+    match term.decode() {
+        Ok(TypedTerm::Tuple(tuple)) => {
+            *elements = tuple.elements().as_ptr();
+            *arity = tuple.len();
+            true
+        }
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lumen_get_list_cell(term: Term, head: *mut Term, tail: *mut Term) -> bool {
+    match term.decode() {
+        Ok(TypedTerm::List(cons)) => {
+            *head = cons.head;
+            *tail = cons.tail;
+            true
+        }
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn lumen_is_empty_list(term: Term) -> bool {
+    term.is_nil()
+}
+
+/// The terms built through the embedding API, `lumen_env_t` in C
+///
+/// The elements of tuples and lists are not copied, so they must be built in an environment that
+/// lives at least as long.
+pub type Env = HeapFragments;
+
+/// A call made with `lumen_call`, which is picked up by `call_entry` in the process spawned to
+/// make it
+struct Call {
+    module_function_arity: ModuleFunctionArity,
+    /// Copied to the heap of the process
+    arguments: Vec<Term>,
+    /// Set by `call_entry` if the called function returns
+    returned: Option<Term>,
+}
+
+// The terms are only used by the process making the call, and then by `lumen_call` once that
+// process has exited, both on the thread that runs the scheduler
+unsafe impl Send for Call {}
+
+/// Runs the call in a new process, and copies the returned value, or the exit reason, from the
+/// heap of that process into `env`
+fn call(
+    env: &mut Env,
+    module_function_arity: ModuleFunctionArity,
+    arguments: &[Term],
+    result: &mut Term,
+) -> LumenStatus {
+    let scheduler = Scheduler::current();
+    let arc_process = match spawn_call(&scheduler, module_function_arity, arguments) {
+        Ok(arc_process) => arc_process,
+        Err(_) => return LumenStatus::Nomem,
+    };
+
+    // Other processes, such as those spawned by the call, keep running while the call waits on
+    // them
+    while !arc_process.is_exiting() {
+        if !scheduler.run_once() && !scheduler.skip_to_next_timeout() {
+            // Processes on other schedulers may still wake the call
+            if !scheduler.others_are_idle() {
+                scheduler.park();
+                continue;
+            }
+
+            break;
+        }
+    }
+
+    let returned = CALL_BY_PID
+        .lock()
+        .remove(&arc_process.pid())
+        .unwrap()
+        .returned;
+    let (status, term) = match returned {
+        Some(returned) => (LumenStatus::Ok, returned),
+        None => match *arc_process.status.read() {
+            Status::Exiting(ref exception) => (
+                LumenStatus::Exception,
+                exception.reason().unwrap_or_else(|| atom!("normal")),
+            ),
+            _ => (LumenStatus::Stalled, Term::NONE),
+        },
+    };
+
+    if status == LumenStatus::Stalled {
+        // Nothing is left to run but the call is still waiting, so kill it, and let the scheduler
+        // reap it
+        arc_process.exit(atom!("kill"), anyhow!("lumen_call stalled").into());
+        scheduler.stop_waiting(&arc_process);
+        let _ = scheduler.run_once();
+
+        return status;
+    }
+
+    match env.clone_term(term) {
+        Ok(cloned) => {
+            *result = cloned;
+            status
+        }
+        Err(_) => LumenStatus::Nomem,
+    }
+}
+
+fn spawn_call(
+    scheduler: &Scheduler,
+    module_function_arity: ModuleFunctionArity,
+    arguments: &[Term],
+) -> AllocResult<Arc<Process>> {
+    let (heap, heap_size) = alloc::default_heap()?;
+    let arc_process = Arc::new(Process::new_with_stack(
+        Priority::Normal,
+        None,
+        Arc::new(module_function_arity),
+        heap,
+        heap_size,
+    )?);
+    let arguments = arguments
+        .iter()
+        .map(|argument| argument.clone_to_process(&arc_process))
+        .collect();
+
+    CALL_BY_PID.lock().insert(
+        arc_process.pid(),
+        Call {
+            module_function_arity,
+            arguments,
+            returned: None,
+        },
+    );
+    scheduler.spawn_entry(arc_process.clone(), call_entry);
+
+    Ok(arc_process)
+}
+
+/// The entry point of the processes spawned by `lumen_call`, which returns to the scheduler when
+/// the called function does
+extern "C" fn call_entry() {
+    let arc_process = current_process();
+    let pid = arc_process.pid();
+    // The lock is not held during the call, as other processes may make calls while it waits
+    let (module_function_arity, arguments) = {
+        let call_by_pid = CALL_BY_PID.lock();
+        let call = &call_by_pid[&pid];
+
+        (call.module_function_arity, call.arguments.clone())
+    };
+
+    // A NIF replaces the Erlang function of the same name
+    let result = match nif::call(&arc_process, &module_function_arity, &arguments) {
+        Some(Ok(returned)) => Ok(returned),
+        Some(Err(exception)) => {
+            arc_process.exception(exception);
+
+            Err(())
+        }
+        None => unsafe { apply::apply(&module_function_arity, &arguments) },
+    };
+
+    if let Ok(returned) = result {
+        if let Some(call) = CALL_BY_PID.lock().get_mut(&pid) {
+            call.returned = Some(returned);
+        }
+    }
+}
+
+fn check_thread() -> Result<(), LumenStatus> {
+    match THREAD.get() {
+        None => Err(LumenStatus::NotRunning),
+        Some(_) if STOPPED.load(Ordering::SeqCst) => Err(LumenStatus::NotRunning),
+        Some(id) if *id != thread::current().id() => Err(LumenStatus::WrongThread),
+        Some(_) => Ok(()),
+    }
+}
+
+unsafe fn existing_atom(name: *const c_char) -> Result<Option<Atom>, LumenStatus> {
+    if name.is_null() {
+        return Err(LumenStatus::Badarg);
+    }
+
+    match CStr::from_ptr(name).to_str() {
+        Ok(name) => Ok(Atom::try_from_str_existing(name).ok()),
+        Err(_) => Err(LumenStatus::Badarg),
+    }
+}
+
+/// The terms of `elements`, none of which may be `NONE`, as it is not a valid term
+unsafe fn term_slice<'a>(
+    env: *mut Env,
+    elements: *const Term,
+    len: usize,
+) -> Result<&'a [Term], LumenStatus> {
+    if env.is_null() || (len > 0 && elements.is_null()) {
+        return Err(LumenStatus::Badarg);
+    }
+
+    let terms = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(elements, len)
+    };
+
+    if terms.iter().any(|term| term.is_none()) {
+        Err(LumenStatus::Badarg)
+    } else {
+        Ok(terms)
+    }
+}
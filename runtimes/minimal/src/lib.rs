@@ -3,6 +3,7 @@
 #![feature(termination_trait_lib)]
 #![feature(thread_local)]
 #![feature(alloc_layout_extra)]
+#![feature(unwind_attributes)]
 
 #[cfg(not(unix))]
 compile_error!("lumen_rt_minimal is only supported on unix targets!");
@@ -14,6 +15,7 @@ extern crate panic;
 mod macros;
 mod builtins;
 mod config;
+mod embed;
 pub mod env;
 mod logging;
 mod process;
@@ -23,6 +25,8 @@ mod sys;
 use bus::Bus;
 use log::Level;
 
+use lumen_rt_core::time::monotonic;
+
 use self::config::Config;
 use self::scheduler::Scheduler;
 use self::sys::break_handler::{self, Signal};
@@ -37,12 +41,13 @@ fn main() -> impl ::std::process::Termination + 'static {
 fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()> {
     self::env::init_argv_from_slice(std::env::args_os()).unwrap();
     // Load system configuration
-    let _config = match Config::from_argv(name.to_string(), version.to_string(), argv) {
+    let config = match Config::from_argv(name.to_string(), version.to_string(), argv) {
         Ok(config) => config,
         Err(err) => {
             panic!("Config error: {}", err);
         }
     };
+    monotonic::set_clock(config.clock);
 
     // This bus is used to receive signals across threads in the system
     let mut bus: Bus<break_handler::Signal> = Bus::new(1);
@@ -56,6 +61,11 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
     logging::init(level_filter).expect("Unexpected failure initializing logger");
 
     let scheduler = Scheduler::current();
+    // The main thread is the first scheduler, and the others only run processes they steal
+    if let Err(err) = Scheduler::spawn_threads(config.schedulers - 1) {
+        eprintln!("Scheduler error: {}", err);
+        return Err(());
+    }
     scheduler.init().unwrap();
     loop {
         // Run the scheduler for a cycle
@@ -90,6 +100,17 @@ fn main_internal(name: &str, version: &str, argv: Vec<String>) -> Result<(), ()>
         if scheduled {
             continue;
         }
+        // With the virtual clock, the processes waiting on timers are woken straight away once
+        // every scheduler is idle
+        if scheduler.skip_to_next_timeout() {
+            continue;
+        }
+        // Processes still running on other schedulers may give this one work, so park until they
+        // are idle too
+        if !scheduler.others_are_idle() {
+            scheduler.park();
+            continue;
+        }
 
         break;
     }
@@ -5,8 +5,10 @@ use std::fmt::{self, Debug};
 use std::mem;
 use std::ops::Deref;
 use std::ptr;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
+use std::thread;
+use std::time::Duration;
 
 use hashbrown::HashMap;
 
@@ -28,11 +30,19 @@ use liblumen_alloc::erts::ModuleFunctionArity;
 
 use lumen_rt_core as rt_core;
 use lumen_rt_core::process::CURRENT_PROCESS;
+use lumen_rt_core::scheduler::idle::Idle;
+use lumen_rt_core::scheduler::park::Parker;
 use lumen_rt_core::scheduler::{run_queue, Run};
-use lumen_rt_core::timer::Hierarchy;
+use lumen_rt_core::timer::{self, Hierarchy};
 
 const MAX_REDUCTION_COUNT: u32 = 20;
 
+/// How long an idle scheduler parks before checking its timers again, which matches the
+/// 1 millisecond resolution of the `soon` timer wheel.
+const PARK_TIMEOUT: Duration = Duration::from_millis(1);
+
+static PARKED_SCHEDULER_COUNT: AtomicUsize = AtomicUsize::new(0);
+
 // External thread locals owned by the generated code
 extern "C" {
     #[thread_local]
@@ -159,12 +169,17 @@ fn do_process_return(scheduler: &Scheduler) -> bool {
 pub struct Scheduler {
     id: id::ID,
     hierarchy: RwLock<Hierarchy>,
+    idle: Idle,
+    parker: Parker,
     // References are always 64-bits even on 32-bit platforms
     reference_count: AtomicU64,
     run_queues: RwLock<run_queue::Queues>,
     // Non-monotonic unique integers are scoped to the scheduler ID and then use this per-scheduler
     // `u64`.
     unique_integer: AtomicU64,
+    // Set while the outgoing process has been requeued, but `swap_stack` has not saved its
+    // registers yet, so that it can't be stolen and resumed from stale registers.
+    swapping: AtomicBool,
     root: Arc<Process>,
     init: ThreadLocalCell<Arc<Process>>,
     current: ThreadLocalCell<Arc<Process>>,
@@ -236,8 +251,11 @@ impl Scheduler {
             init: ThreadLocalCell::new(init),
             current,
             hierarchy: Default::default(),
+            idle: Default::default(),
+            parker: Default::default(),
             reference_count: AtomicU64::new(0),
             unique_integer: AtomicU64::new(0),
+            swapping: AtomicBool::new(false),
         })
     }
 
@@ -265,7 +283,7 @@ impl Scheduler {
         unsafe {
             self.init.set(init);
         }
-        Scheduler::spawn_internal(clone, self.id, &self.run_queues);
+        self.spawned(Scheduler::spawn_internal(clone, self.id, &self.run_queues));
 
         Ok(())
     }
@@ -317,7 +335,145 @@ impl Scheduler {
     }
 
     pub fn stop_waiting(&self, process: &Process) {
+        self.idle.leave();
         self.run_queues.write().stop_waiting(process);
+        self.unpark();
+    }
+
+    /// Steals runnable processes from the other scheduler with the most stealable processes.
+    ///
+    /// Returns `true` if any processes were stolen.
+    #[must_use]
+    pub fn steal(&self) -> bool {
+        let victim = self
+            .others()
+            .into_iter()
+            .max_by_key(|other| other.run_queues.read().stealable_len());
+
+        match victim {
+            Some(victim) => 0 < self.steal_from(&victim),
+            None => false,
+        }
+    }
+
+    /// Steals half of the stealable processes from `victim` and re-homes them on this scheduler.
+    ///
+    /// Returns the number of processes stolen.
+    pub fn steal_from(&self, victim: &Scheduler) -> usize {
+        debug_assert_ne!(self.id, victim.id, "scheduler can't steal from itself");
+
+        let stolen = {
+            let mut victim_run_queues = victim.run_queues.write();
+
+            // `swapping` is set before the outgoing process is requeued, so while holding the
+            // victim's run queues, it being clear means every queued process has saved registers.
+            if victim.swapping.load(Ordering::SeqCst) {
+                return 0;
+            }
+
+            let (roots, stolen): (Vec<_>, Vec<_>) = victim_run_queues
+                .steal()
+                .into_iter()
+                .partition(|arc_process| Arc::ptr_eq(arc_process, &victim.root));
+
+            // The root process runs on the victim's thread stack, so it can never move.
+            for root in roots {
+                victim_run_queues.enqueue(root);
+            }
+
+            stolen
+        };
+        let stolen_len = stolen.len();
+
+        if 0 < stolen_len {
+            self.idle.leave();
+            let mut rq = self.run_queues.write();
+
+            for process in stolen {
+                process.schedule_with(self.id);
+                rq.enqueue(process);
+            }
+        }
+
+        stolen_len
+    }
+
+    /// Sleeps until another scheduler gives this one work or until the next timer slot needs to
+    /// be checked.
+    pub fn park(&self) {
+        PARKED_SCHEDULER_COUNT.fetch_add(1, Ordering::SeqCst);
+        self.parker.park_timeout(PARK_TIMEOUT);
+        PARKED_SCHEDULER_COUNT.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    /// Wakes this scheduler if it is parked.
+    pub fn unpark(&self) {
+        self.parker.unpark();
+    }
+
+    /// Wakes a parked scheduler, so that it can steal from this one.
+    fn unpark_thief(&self) {
+        if 0 < PARKED_SCHEDULER_COUNT.load(Ordering::SeqCst) {
+            if let Some(thief) = self
+                .others()
+                .into_iter()
+                .find(|other| other.parker.is_parked())
+            {
+                thief.unpark();
+            }
+        }
+    }
+
+    /// Returns `true` if every other scheduler is parked with no processes to run, so none of
+    /// them can give this one more work.
+    pub fn others_are_idle(&self) -> bool {
+        self.others().iter().all(|other| {
+            let run_queues = other.run_queues.read();
+
+            other.parker.is_parked()
+                && run_queues.stealable_len() == 0
+                && run_queues.run_queue_len(Priority::Max) == 0
+        })
+    }
+
+    /// Starts `count` more scheduler threads, which run and steal processes from the others
+    /// until the program exits.
+    pub fn spawn_threads(count: usize) -> std::io::Result<()> {
+        for number in 1..=count {
+            thread::Builder::new()
+                .name(format!("scheduler {}", number))
+                .spawn(|| Scheduler::current().run())?;
+        }
+
+        Ok(())
+    }
+
+    /// The loop of a scheduler thread other than the main thread.  Unlike the main thread, it
+    /// parks instead of stopping when it has nothing to run.
+    fn run(&self) {
+        loop {
+            // `run_once` steals when it has nothing to run
+            if !self.run_once() && !self.skip_to_next_timeout() {
+                self.park();
+            }
+        }
+    }
+
+    /// All other live schedulers.
+    ///
+    /// The `Arc`s are collected before `SCHEDULERS` is unlocked, so that if one of them is the last
+    /// strong reference, its `Drop` doesn't deadlock trying to unregister.
+    fn others(&self) -> Vec<Arc<Self>> {
+        let upgraded: Vec<Arc<Self>> = SCHEDULERS
+            .lock()
+            .values()
+            .filter_map(Weak::upgrade)
+            .collect();
+
+        upgraded
+            .into_iter()
+            .filter(|other| other.id != self.id)
+            .collect()
     }
 
     // TODO: Request application master termination for controlled shutdown
@@ -378,6 +534,30 @@ impl Scheduler {
         self.process_yield(/* root= */ true)
     }
 
+    /// Marks this scheduler idle, as it has nothing to run or steal, and, when every scheduler is
+    /// idle and the virtual clock is used, moves the clock to the next timer of any scheduler and
+    /// times it out, so that the processes waiting on it can run.
+    ///
+    /// Returns `false` if the clock is real, other schedulers are still busy or there are no
+    /// timers, in which case nothing this scheduler does will wake a waiting process.
+    #[must_use]
+    pub fn skip_to_next_timeout(&self) -> bool {
+        self.idle.enter();
+
+        // A process enqueued after `run_once` found nothing has to run before the clock moves
+        if 0 < self.run_queues_len() {
+            self.idle.leave();
+
+            return true;
+        }
+
+        let others = self.others();
+        let other_hierarchies: Vec<&RwLock<Hierarchy>> =
+            others.iter().map(|other| &other.hierarchy).collect();
+
+        timer::skip_to_next_timeout(&self.hierarchy, &other_hierarchies)
+    }
+
     /// This function performs two roles, albeit virtually identical:
     ///
     /// First, this function is called by the scheduler to resume execution
@@ -427,14 +607,17 @@ impl Scheduler {
                     continue;
                 }
                 Run::None if is_root => {
-                    info!("no processes remaining to schedule, exiting loop");
                     // If no processes are available, then the scheduler should steal,
-                    // but if it can't/doesn't, then it must terminate, as there is
-                    // nothing we can swap to. When we break here, we're returning
-                    // to the core scheduler loop, which _must_ terminate, if it does
-                    // not, we'll just end up right back here again.
-                    //
-                    // TODO: stealing
+                    // but if it can't/doesn't, then there is nothing we can swap to.
+                    // When we break here, we're returning to the core scheduler loop,
+                    // which parks or terminates the scheduler, as if it does not,
+                    // we'll just end up right back here again.
+                    if self.steal() {
+                        info!("stole processes from another scheduler");
+                        continue;
+                    }
+
+                    info!("no processes remaining to schedule, exiting loop");
                     break false;
                 }
                 Run::None => unreachable!(),
@@ -483,6 +666,10 @@ impl Scheduler {
         // Save the previous process registers for the stack swap
         let prev_ctx = &prev.registers as *const _ as *mut _;
 
+        // Keep `prev` from being stolen until `swap_stack` has saved its registers
+        self.swapping.store(true, Ordering::SeqCst);
+        let swapping = &self.swapping as *const AtomicBool as *mut bool;
+
         // Then try to schedule it for the future
         // If the process is exiting, then handle the exit, otherwise
         // proceed to the stack swap
@@ -512,7 +699,7 @@ impl Scheduler {
         // since the process called `process_yield`. From here we unwind back
         // to the call to `process_yield` and resume execution from the point
         // where it was called.
-        swap_stack(prev_ctx, new_ctx);
+        swap_stack(prev_ctx, new_ctx, swapping);
     }
 
     /// Schedules the given process for execution
@@ -524,6 +711,7 @@ impl Scheduler {
         );
 
         process.schedule_with(self.id);
+        self.idle.leave();
 
         let mut rq = self.run_queues.write();
         rq.enqueue(process);
@@ -532,7 +720,7 @@ impl Scheduler {
     /// Spawns a new process using the given init function as its entry
     #[inline]
     pub fn spawn(&mut self, process: Arc<Process>) -> anyhow::Result<()> {
-        Self::spawn_internal(process, self.id, &self.run_queues);
+        self.spawned(Self::spawn_internal(process, self.id, &self.run_queues));
         Ok(())
     }
 
@@ -551,9 +739,35 @@ impl Scheduler {
         Ok(())
     }
 
-    fn spawn_internal(process: Arc<Process>, id: id::ID, run_queues: &RwLock<run_queue::Queues>) {
-        process.schedule_with(id);
+    /// Spawns a new process which starts by calling `entry`, rather than the function named by its
+    /// initial module, function and arity, e.g. to make a call through the embedding API
+    pub fn spawn_entry(&self, process: Arc<Process>, entry: extern "C" fn()) {
+        self.spawned(Self::spawn_with_entry(
+            process,
+            entry as u64,
+            self.id,
+            &self.run_queues,
+        ));
+    }
 
+    /// Wakes a parked scheduler to steal from this one, now that a spawn has left
+    /// `stealable_len` stealable processes in its run queues.
+    fn spawned(&self, stealable_len: usize) {
+        self.idle.leave();
+
+        // `steal` only takes half of the stealable processes, so there is nothing to take until
+        // there are at least 2.
+        if 1 < stealable_len {
+            self.unpark_thief();
+        }
+    }
+
+    /// Returns the number of stealable processes in `run_queues` after the spawn
+    fn spawn_internal(
+        process: Arc<Process>,
+        id: id::ID,
+        run_queues: &RwLock<run_queue::Queues>,
+    ) -> usize {
         let mfa = &process.initial_module_function_arity;
         let init_fn_result = apply::find_symbol(&mfa);
         if init_fn_result.is_none() {
@@ -564,6 +778,18 @@ impl Scheduler {
         }
         let init_fn = init_fn_result.unwrap();
 
+        Self::spawn_with_entry(process, init_fn as u64, id, run_queues)
+    }
+
+    /// Returns the number of stealable processes in `run_queues` after the spawn
+    fn spawn_with_entry(
+        process: Arc<Process>,
+        init_fn: u64,
+        id: id::ID,
+        run_queues: &RwLock<run_queue::Queues>,
+    ) -> usize {
+        process.schedule_with(id);
+
         #[inline(always)]
         unsafe fn push(sp: &mut StackPointer, value: u64) {
             sp.0 = sp.0.offset(-1);
@@ -583,7 +809,7 @@ impl Scheduler {
             // Function that will be called when returning from init_fn
             push(&mut sp, process_return_continuation as u64);
             // Function that the newly spawned process should call first
-            push(&mut sp, init_fn as u64);
+            push(&mut sp, init_fn);
             // Update process stack pointer
             let s_top = &process.stack.top as *const _ as *mut _;
             ptr::write(s_top, sp.0 as *const u8);
@@ -598,6 +824,8 @@ impl Scheduler {
 
         let mut rq = run_queues.write();
         rq.enqueue(process);
+
+        rq.stealable_len()
     }
 }
 
@@ -613,10 +841,17 @@ fn reset_reduction_counter() -> u64 {
 /// This function uses inline assembly to save the callee-saved registers for the outgoing
 /// process, and restore them for the incoming process. When this function returns, it will
 /// resume execution where `swap_stack` was called previously.
+///
+/// `swapping` is cleared as soon as the registers of `prev` are saved, so that other schedulers
+/// know `prev` can be stolen.
 #[naked]
 #[inline(never)]
 #[cfg(all(unix, target_arch = "x86_64"))]
-unsafe fn swap_stack(prev: *mut CalleeSavedRegisters, new: *const CalleeSavedRegisters) {
+unsafe fn swap_stack(
+    prev: *mut CalleeSavedRegisters,
+    new: *const CalleeSavedRegisters,
+    swapping: *mut bool,
+) {
     asm!("
         # Save the stack pointer, and callee-saved registers of `prev`
         movq     %rsp, ($0)
@@ -627,6 +862,9 @@ unsafe fn swap_stack(prev: *mut CalleeSavedRegisters, new: *const CalleeSavedReg
         movq     %rbx, 40($0)
         movq     %rbp, 48($0)
 
+        # `prev` is safe to steal now that its registers are saved
+        movb     $$0, ($2)
+
         # Restore the stack pointer, and callee-saved registers of `new`
         movq     ($1),   %rsp
         movq     8($1),  %r15
@@ -651,7 +889,7 @@ unsafe fn swap_stack(prev: *mut CalleeSavedRegisters, new: *const CalleeSavedReg
         .cfi_restore %rbp
         "
     :
-    : "r"(prev), "r"(new)
+    : "r"(prev), "r"(new), "r"(swapping)
     :
     : "volatile", "alignstack"
     );
@@ -1,14 +1,22 @@
 use std::ffi::CStr;
+use std::io::Write;
 
 use libc;
 
+use liblumen_alloc::atom;
+use liblumen_alloc::erts::process::ProcessFlags;
 use liblumen_alloc::erts::term::prelude::*;
 
+use lumen_rt_core::io_lib;
+use lumen_rt_core::process::current_process;
+
+use crate::builtins::apply::raise;
+
 #[export_name = "__lumen_builtin_printf"]
 pub extern "C" fn printf_1(term: Term) -> Term {
     match term.decode() {
-        Ok(tt) => {
-            println!("{}", tt);
+        Ok(_) => {
+            println!("{}", io_lib::print(term));
             Atom::from_str("ok").encode().unwrap()
         }
         Err(reason) => {
@@ -25,13 +33,43 @@ pub extern "C" fn put_chars_1(s: *const libc::c_char) -> Option<Term> {
     Some(ok!())
 }
 
+#[unwind(allowed)]
+#[export_name = "io:format/1"]
+pub extern "C" fn format_1(format: Term) -> Term {
+    format_2(format, Term::NIL)
+}
+
+/// Raises `badarg` if `format` is not a valid format string or `data` does not match it
+#[unwind(allowed)]
 #[export_name = "io:format/2"]
-pub extern "C" fn format_2(
-    _s: *const libc::c_char,
-    _argv: *const Term,
-    _argc: libc::c_uint,
-) -> Option<Term> {
-    unimplemented!();
+pub extern "C" fn format_2(format: Term, data: Term) -> Term {
+    match io_lib::format(format, data) {
+        Ok(string) => {
+            print!("{}", string);
+            std::io::stdout().flush().unwrap();
+
+            ok!()
+        }
+        Err(_) => raise(&current_process(), atom!("badarg")),
+    }
+}
+
+/// Raises `badarg` if `format` is not a valid format string or `data` does not match it
+#[unwind(allowed)]
+#[export_name = "io_lib:format/2"]
+pub extern "C" fn io_lib_format_2(format: Term, data: Term) -> Term {
+    let process = current_process();
+
+    match io_lib::format(format, data) {
+        Ok(string) => match process.charlist_from_str(&string) {
+            Ok(charlist) => charlist,
+            Err(_) => {
+                process.set_flags(ProcessFlags::GrowHeap | ProcessFlags::ForceGC);
+                Term::NONE
+            }
+        },
+        Err(_) => raise(&process, atom!("badarg")),
+    }
 }
 
 #[export_name = "io:nl/0"]